@@ -117,6 +117,11 @@ contract DeployContractsL1 is Script {
             0x0000000000000000000000000000000000000000000000000000000000000000 // programVKey (empty for StateTransition)
         );
 
+        // Register a rollup for every further L2 the sandbox was scaled to
+        registerScaledRollups(
+            polygonRollupManager, polygonZkEVMGlobalExitRootV2, aggERC20, fflonkVerifier, polygonZkEVMBridgeV2
+        );
+
         // stop broadcasting so logs don't count as on-chain txs
         vm.stopBroadcast();
 
@@ -137,4 +142,43 @@ contract DeployContractsL1 is Script {
         console2.log("L2 Rollup registered with ID: 1 (Chain ID: 1101)");
         console2.log("L3 Rollup registered with ID: 2 (Chain ID: 137)");
     }
+
+    // Rollups 3..L2_COUNT back the chains added with `aggsandbox start --scale l2=N`.
+    // Their addresses are logged as "PolygonZkEVM L<rollup ID + 1>:".
+    function registerScaledRollups(
+        PolygonRollupManager polygonRollupManager,
+        PolygonZkEVMGlobalExitRootV2 polygonZkEVMGlobalExitRootV2,
+        AggERC20 aggERC20,
+        FflonkVerifier fflonkVerifier,
+        PolygonZkEVMBridgeV2 polygonZkEVMBridgeV2
+    ) internal {
+        uint256 l2Count = vm.envOr("L2_COUNT", uint256(2));
+
+        for (uint256 rollupID = 3; rollupID <= l2Count; rollupID++) {
+            uint64 chainID = uint64(
+                vm.envOr(string.concat("CHAIN_ID_AGGLAYER_", vm.toString(rollupID)), uint256(1100 + rollupID))
+            );
+
+            PolygonZkEVM rollup = new PolygonZkEVM(
+                IPolygonZkEVMGlobalExitRootV2(address(polygonZkEVMGlobalExitRootV2)),
+                IERC20(address(aggERC20)),
+                IVerifierRollup(address(fflonkVerifier)),
+                IPolygonZkEVMBridge(address(polygonZkEVMBridgeV2)),
+                uint64(rollupID),
+                chainID
+            );
+
+            polygonRollupManager.addExistingRollup(
+                IPolygonRollupBase(address(rollup)), // rollupAddress
+                address(fflonkVerifier), // verifier
+                1, // forkID
+                chainID, // chainID
+                0x0000000000000000000000000000000000000000000000000000000000000000, // initRoot (genesis state root)
+                IPolygonRollupManager.VerifierType.StateTransition, // rollupVerifierType
+                0x0000000000000000000000000000000000000000000000000000000000000000 // programVKey (empty for StateTransition)
+            );
+
+            console2.log(string.concat("PolygonZkEVM L", vm.toString(rollupID + 1), ":        "), address(rollup));
+        }
+    }
 }
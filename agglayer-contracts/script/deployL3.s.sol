@@ -16,6 +16,9 @@ contract DeployContractsL2 is Script {
         uint256 deployerKey = vm.envUint("PRIVATE_KEY_1");
         address deployer = vm.addr(deployerKey);
 
+        // network 2 by default; scaled L2s pass their own network ID
+        uint32 networkID = uint32(vm.envOr("NETWORK_ID", uint256(2)));
+
         // start broadcasting transactions
         vm.startBroadcast(deployerKey);
 
@@ -38,7 +41,7 @@ contract DeployContractsL2 is Script {
 
         // Initialize the bridge
         polygonZkEVMBridgeV2.initialize(
-            2, // _networkID - 2 for second L2
+            networkID, // _networkID - 2 for second L2, n for further scaled L2s
             address(0), // _gasTokenAddress - address(0) for ETH
             0, // _gasTokenNetwork
             IBasePolygonZkEVMGlobalExitRoot(address(globalExitRootManagerL2SovereignChain)), // _globalExitRootManager
@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.22;
+
+/// Name to address registry of the sandbox contracts
+///
+/// Deployed by `aggsandbox start` through the CREATE2 deployer with a fixed
+/// salt, so it lives at the same address on every sandbox network. Each
+/// network's registry maps names such as "bridge", "bridgeExtension" and
+/// "aggERC20" to the contracts deployed on that network.
+contract SandboxRegistry {
+    address public owner;
+
+    mapping(bytes32 => address) private _entries;
+    mapping(bytes32 => bool) private _known;
+    string[] private _names;
+
+    event EntrySet(string name, address addr);
+    event OwnershipTransferred(address indexed previousOwner, address indexed newOwner);
+
+    error NotOwner();
+    error EmptyName();
+
+    modifier onlyOwner() {
+        if (msg.sender != owner) revert NotOwner();
+        _;
+    }
+
+    constructor() {
+        // Deployed through the CREATE2 deployer, so the sender is the deployer contract
+        owner = tx.origin;
+        emit OwnershipTransferred(address(0), tx.origin);
+    }
+
+    function set(string calldata name, address addr) external onlyOwner {
+        if (bytes(name).length == 0) revert EmptyName();
+        bytes32 key = keccak256(bytes(name));
+        if (!_known[key]) {
+            _known[key] = true;
+            _names.push(name);
+        }
+        _entries[key] = addr;
+        emit EntrySet(name, addr);
+    }
+
+    function get(string calldata name) external view returns (address) {
+        return _entries[keccak256(bytes(name))];
+    }
+
+    /// Names set so far, in the order they were first set
+    function names() external view returns (string[] memory) {
+        return _names;
+    }
+
+    function transferOwnership(address newOwner) external onlyOwner {
+        emit OwnershipTransferred(owner, newOwner);
+        owner = newOwner;
+    }
+}
@@ -147,6 +147,7 @@ pub async fn get_l1_info_tree_index(
 }
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod tests {
     use super::*;
     use crate::config::{
@@ -166,6 +167,9 @@ mod tests {
                 base_url: RpcUrl::new(base_url).expect("Valid test URL"),
                 timeout: Duration::from_millis(5000),
                 retry_attempts: 3,
+                max_in_flight: 8,
+                rate_limit: 0,
+                agglayer_url: RpcUrl::new("http://localhost:4444").expect("Valid test URL"),
             },
             networks: NetworkConfig {
                 l1: ChainConfig {
@@ -173,14 +177,17 @@ mod tests {
                     chain_id: ChainId::new("1").expect("Valid test chain ID"),
                     rpc_url: RpcUrl::new("http://localhost:8545").expect("Valid test URL"),
                     fork_url: None,
+                    ws_url: None,
                 },
                 l2: ChainConfig {
                     name: "Test-L2".to_string(),
                     chain_id: ChainId::new("1101").expect("Valid test chain ID"),
                     rpc_url: RpcUrl::new("http://localhost:8546").expect("Valid test URL"),
                     fork_url: None,
+                    ws_url: None,
                 },
                 l3: None,
+                extra_l2s: Vec::new(),
             },
             accounts: AccountConfig {
                 accounts: vec![
@@ -188,11 +195,15 @@ mod tests {
                         .expect("Valid test address"),
                 ],
                 private_keys: vec!["0xkey".to_string()],
+                named: Default::default(),
+                selected_key: None,
+                hardware_signer: None,
             },
             contracts: ContractConfig {
                 l1_contracts: HashMap::new(),
                 l2_contracts: HashMap::new(),
                 l3_contracts: HashMap::new(),
+                extra_contracts: Default::default(),
             },
         }
     }
@@ -325,7 +336,8 @@ mod tests {
 
         assert!(result.is_ok());
         let response = result.unwrap();
-        assert_eq!(response.data, mock_response);
+        assert_eq!(response.data["proof"], mock_response["proof"]);
+        assert_eq!(response.data["smt_proof"], json!(["0xabc", "0xdef"]));
     }
 
     #[tokio::test]
@@ -368,4 +380,39 @@ mod tests {
         let pretty_result = serde_json::to_string_pretty(&test_data);
         assert!(pretty_result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_for_each_page_walks_every_page() {
+        let server = MockServer::start().await;
+        for (number, deposits) in [("1", vec![0, 1]), ("2", vec![2, 3]), ("3", vec![4])] {
+            let bridges: Vec<serde_json::Value> = deposits
+                .iter()
+                .map(|count| json!({ "deposit_count": count }))
+                .collect();
+            Mock::given(method("GET"))
+                .and(path("/bridge/v1/bridges"))
+                .and(query_param("page_number", number))
+                .and(query_param("page_size", "2"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({ "bridges": bridges, "count": 5 })),
+                )
+                .expect(1)
+                .mount(&server)
+                .await;
+        }
+
+        let config = create_test_config(&server.uri());
+        let client = OptimizedApiClient::new(crate::api_client::CacheConfig::default());
+        let mut seen = Vec::new();
+        let pages = client
+            .for_each_page("bridges", &config, 0, 2, |_, bridges| {
+                seen.extend(bridges.iter().map(|bridge| bridge["deposit_count"].clone()));
+                Ok(())
+            })
+            .await
+            .unwrap();
+        assert_eq!(pages, 3);
+        assert_eq!(seen, vec![0, 1, 2, 3, 4]);
+    }
 }
@@ -1,16 +1,280 @@
-use crate::config::Config;
-use crate::error::{ApiError, Result};
-use crate::types::NetworkId;
+use crate::config::{ApiConfig, Config};
+use crate::error::{AggSandboxError, ApiError, Result};
+use crate::types::{BridgeDeposit, ClaimProofResponse, L1InfoLeaf, L1InfoTreeIndex, NetworkId};
 use dashmap::DashMap;
 use lru::LruCache;
 use reqwest::{Client, ClientBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
 use std::hash::Hash;
 use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::sync::LazyLock;
-use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
-use tracing::{debug, info, instrument, warn};
+use std::sync::{LazyLock, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{OnceCell, RwLock, Semaphore, SemaphorePermit};
+use tracing::{debug, info, info_span, instrument, warn, Instrument};
+
+/// Delay before the first retry, doubled on every following one
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+/// Upper bound of the delay between two attempts
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// Exponential backoff for transient API failures
+///
+/// `API_RETRY_ATTEMPTS` is the total number of attempts, so `1` disables
+/// retries. Every attempt is bounded by `API_TIMEOUT_MS` and waits for a slot
+/// of the host's [`HostLimiter`] first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: RETRY_BASE_DELAY,
+            max_delay: RETRY_MAX_DELAY,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn from_config(api: &ApiConfig) -> Self {
+        Self {
+            max_attempts: api.retry_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Upper bound of the delay after a failed attempt (1-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Backoff with jitter: half of the delay is fixed, the other half random,
+    /// so concurrent commands hitting the same outage do not retry in lockstep
+    fn delay_with_jitter(&self, attempt: u32) -> Duration {
+        use ethers::core::rand::Rng;
+
+        let backoff = self.backoff(attempt);
+        let half = backoff / 2;
+        let jitter_ms = ethers::core::rand::thread_rng().gen_range(0..=half.as_millis() as u64);
+        half + Duration::from_millis(jitter_ms)
+    }
+}
+
+/// Throttle of the requests sent to one bridge service host
+///
+/// At most `API_MAX_IN_FLIGHT` requests to the host run at once and, with
+/// `API_RATE_LIMIT` set, their starts are spaced evenly so no more than that
+/// many start per second. The limits are taken from the configuration the
+/// host is first requested with.
+#[derive(Debug)]
+pub struct HostLimiter {
+    in_flight: Semaphore,
+    /// Minimum time between two request starts, `None` without a rate limit
+    interval: Option<Duration>,
+    next_start: tokio::sync::Mutex<tokio::time::Instant>,
+}
+
+impl HostLimiter {
+    pub fn new(api: &ApiConfig) -> Self {
+        Self {
+            in_flight: Semaphore::new(api.max_in_flight.max(1)),
+            interval: (api.rate_limit > 0).then(|| Duration::from_secs(1) / api.rate_limit),
+            next_start: tokio::sync::Mutex::new(tokio::time::Instant::now()),
+        }
+    }
+
+    /// Wait for a free slot, the request runs while the permit is held
+    pub async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        // The semaphore is never closed
+        let permit = self.in_flight.acquire().await.ok();
+        if let Some(interval) = self.interval {
+            let start = {
+                let mut next_start = self.next_start.lock().await;
+                let start = (*next_start).max(tokio::time::Instant::now());
+                *next_start = start + interval;
+                start
+            };
+            tokio::time::sleep_until(start).await;
+        }
+        permit
+    }
+}
+
+/// `host:port` of a URL, the key requests are limited by
+pub fn host_key(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            Some(format!(
+                "{}:{}",
+                url.host_str()?,
+                url.port_or_known_default()?
+            ))
+        })
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Outcome of a GET shared by every caller waiting on the same URL
+type SharedResponse = std::result::Result<serde_json::Value, ApiError>;
+
+/// Network failures, timeouts, rate limiting and 5xx responses are worth retrying
+pub fn is_transient(error: &AggSandboxError) -> bool {
+    match error {
+        AggSandboxError::Api(ApiError::NetworkError(_)) => true,
+        AggSandboxError::Api(ApiError::RequestFailed { status, .. }) => {
+            *status >= 500 || *status == 429
+        }
+        _ => false,
+    }
+}
+
+/// Field layout of a claim proof response in one family of bridge service releases
+///
+/// Each field lists JSON pointers tried in order, so small variations inside
+/// a family (such as a nested `l1_info_tree_leaf`) share one adapter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofAdapter {
+    pub name: &'static str,
+    smt_proof: &'static [&'static str],
+    smt_proof_rollup: &'static [&'static str],
+    mainnet_exit_root: &'static [&'static str],
+    rollup_exit_root: &'static [&'static str],
+}
+
+/// Supported claim proof layouts, newest first
+///
+/// Responses are rewritten to the layout claim building reads: top-level
+/// `smt_proof` and `smt_proof_rollup` arrays and the exit roots in
+/// `l1_info_tree_leaf`.
+pub const PROOF_ADAPTERS: &[ProofAdapter] = &[
+    ProofAdapter {
+        name: "aggkit",
+        smt_proof: &["/proof_local_exit_root"],
+        smt_proof_rollup: &["/proof_rollup_exit_root"],
+        mainnet_exit_root: &[
+            "/l1_info_tree_leaf/mainnet_exit_root",
+            "/l1_info_tree_leaf/inner/mainnet_exit_root",
+        ],
+        rollup_exit_root: &[
+            "/l1_info_tree_leaf/rollup_exit_root",
+            "/l1_info_tree_leaf/inner/rollup_exit_root",
+        ],
+    },
+    ProofAdapter {
+        name: "aggkit-smt",
+        smt_proof: &["/smt_proof"],
+        smt_proof_rollup: &["/smt_proof_rollup"],
+        mainnet_exit_root: &[
+            "/l1_info_tree_leaf/mainnet_exit_root",
+            "/l1_info_tree_leaf/inner/mainnet_exit_root",
+        ],
+        rollup_exit_root: &[
+            "/l1_info_tree_leaf/rollup_exit_root",
+            "/l1_info_tree_leaf/inner/rollup_exit_root",
+        ],
+    },
+    ProofAdapter {
+        name: "zkevm-bridge-service",
+        smt_proof: &["/proof/merkle_proof"],
+        smt_proof_rollup: &["/proof/rollup_merkle_proof"],
+        mainnet_exit_root: &["/proof/main_exit_root", "/proof/mainnet_exit_root"],
+        rollup_exit_root: &["/proof/rollup_exit_root"],
+    },
+];
+
+impl ProofAdapter {
+    fn lookup<'a>(
+        proof: &'a serde_json::Value,
+        pointers: &[&str],
+    ) -> Option<&'a serde_json::Value> {
+        pointers
+            .iter()
+            .find_map(|pointer| proof.pointer(pointer).filter(|value| !value.is_null()))
+    }
+
+    /// Whether a response uses this adapter's layout
+    pub fn matches(&self, proof: &serde_json::Value) -> bool {
+        Self::lookup(proof, self.smt_proof).is_some_and(serde_json::Value::is_array)
+    }
+
+    /// Rewrite a response to the canonical layout, keeping the original fields
+    pub fn normalize(&self, proof: &serde_json::Value) -> serde_json::Value {
+        let mut normalized = proof.clone();
+        let Some(object) = normalized.as_object_mut() else {
+            return normalized;
+        };
+
+        for (key, pointers) in [
+            ("smt_proof", self.smt_proof),
+            ("smt_proof_rollup", self.smt_proof_rollup),
+        ] {
+            if let Some(value) = Self::lookup(proof, pointers) {
+                object.insert(key.to_string(), value.clone());
+            }
+        }
+
+        // Fields of a nested leaf are lifted next to the exit roots
+        let mut leaf = proof
+            .get("l1_info_tree_leaf")
+            .and_then(serde_json::Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(inner) = proof
+            .pointer("/l1_info_tree_leaf/inner")
+            .and_then(serde_json::Value::as_object)
+        {
+            leaf.extend(
+                inner
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone())),
+            );
+        }
+        for (key, pointers) in [
+            ("mainnet_exit_root", self.mainnet_exit_root),
+            ("rollup_exit_root", self.rollup_exit_root),
+        ] {
+            if let Some(value) = Self::lookup(proof, pointers) {
+                leaf.insert(key.to_string(), value.clone());
+            }
+        }
+        if !leaf.is_empty() {
+            object.insert(
+                "l1_info_tree_leaf".to_string(),
+                serde_json::Value::Object(leaf),
+            );
+        }
+        normalized
+    }
+}
+
+/// Adapter for the layout of a claim proof response
+pub fn detect_proof_format(proof: &serde_json::Value) -> Option<&'static ProofAdapter> {
+    PROOF_ADAPTERS.iter().find(|adapter| adapter.matches(proof))
+}
+
+/// Bring a claim proof response from any supported bridge service release to the canonical layout
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub fn normalize_claim_proof(proof: serde_json::Value) -> Result<serde_json::Value> {
+    let adapter = detect_proof_format(&proof).ok_or_else(|| {
+        let formats: Vec<&str> = PROOF_ADAPTERS.iter().map(|adapter| adapter.name).collect();
+        ApiError::response_validation_failed(&format!(
+            "claim proof has no SMT proof in any supported format ({})",
+            formats.join(", ")
+        ))
+    })?;
+    debug!(format = adapter.name, "Normalizing claim proof");
+    Ok(adapter.normalize(&proof))
+}
 
 /// Cache entry that includes the cached data and its expiration time
 #[derive(Debug, Clone)]
@@ -19,13 +283,13 @@ struct CacheEntry<T> {
     expires_at: Instant,
 }
 
-/// Cache key for API requests
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Cache key for API requests: network, endpoint and query parameters
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CacheKey {
     pub endpoint: String,
     pub network_id: Option<u64>,
-    pub leaf_index: Option<u64>,
-    pub deposit_count: Option<u64>,
+    /// Query parameters other than the network
+    pub params: BTreeMap<String, String>,
 }
 
 impl CacheKey {
@@ -33,8 +297,7 @@ impl CacheKey {
         Self {
             endpoint,
             network_id: None,
-            leaf_index: None,
-            deposit_count: None,
+            params: BTreeMap::new(),
         }
     }
 
@@ -43,15 +306,81 @@ impl CacheKey {
         self
     }
 
-    pub fn with_leaf_index(mut self, leaf_index: u64) -> Self {
-        self.leaf_index = Some(leaf_index);
+    pub fn with_param(mut self, name: &str, value: impl ToString) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
         self
     }
 
-    pub fn with_deposit_count(mut self, deposit_count: u64) -> Self {
-        self.deposit_count = Some(deposit_count);
-        self
+    pub fn with_leaf_index(self, leaf_index: u64) -> Self {
+        self.with_param("leaf_index", leaf_index)
+    }
+
+    pub fn with_deposit_count(self, deposit_count: u64) -> Self {
+        self.with_param("deposit_count", deposit_count)
+    }
+}
+
+/// Page of a paginated list endpoint, numbered from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Page {
+    pub number: u64,
+    pub size: u64,
+}
+
+impl Page {
+    pub fn first(size: u64) -> Self {
+        Self { number: 1, size }
+    }
+
+    pub fn next(self) -> Self {
+        Self {
+            number: self.number + 1,
+            ..self
+        }
     }
+
+    /// Whether a page holding `items` entries is the last one of a list of `total`
+    ///
+    /// Without a total a page that is not full ends the list.
+    pub fn is_last(&self, items: usize, total: Option<u64>) -> bool {
+        let fetched = (self.number - 1) * self.size + items as u64;
+        items == 0 || (items as u64) < self.size || total.is_some_and(|total| fetched >= total)
+    }
+}
+
+/// File the cached claim proofs and L1 info tree indices are kept in between
+/// commands, relative to the project root
+pub const CACHE_FILE: &str = ".aggsandbox/api-cache.json";
+
+/// Whether responses of an endpoint are kept in the cache file
+///
+/// A claim proof or L1 info tree index, once returned, does not change until
+/// the sandbox is restarted, while deposit and claim lists grow with every
+/// bridge. Those are only cached for the life of the process (the shell,
+/// `serve` and `dashboard`).
+fn is_persistent(endpoint: &str) -> bool {
+    matches!(endpoint, "claim-proof" | "l1-info-tree-index")
+}
+
+/// Cached response as stored in [`CACHE_FILE`]
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredEntry {
+    key: CacheKey,
+    /// Expiry in milliseconds since the Unix epoch
+    expires_at_ms: u64,
+    data: serde_json::Value,
+}
+
+fn unix_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+fn write_entries(path: &Path, entries: &[StoredEntry]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_vec(entries)?)
 }
 
 /// Configuration for the API client cache
@@ -106,11 +435,21 @@ impl CacheStats {
 }
 
 /// High-performance API client with caching and connection pooling
+///
+/// Requests are throttled per host, and identical GETs running at the same
+/// time share a single upstream request.
 pub struct OptimizedApiClient {
     client: Client,
     cache: ResponseCache,
     cache_config: CacheConfig,
     stats: Arc<DashMap<String, CacheStats>>,
+    /// Cleared by `--no-cache`
+    cache_enabled: AtomicBool,
+    /// File persistent responses are kept in, see [`Self::persist_to`]
+    cache_file: OnceLock<PathBuf>,
+    cache_file_loaded: OnceCell<()>,
+    limiters: DashMap<String, Arc<HostLimiter>>,
+    in_flight: DashMap<String, Arc<OnceCell<SharedResponse>>>,
 }
 
 /// Global client instance for reuse across API calls
@@ -149,6 +488,11 @@ impl OptimizedApiClient {
             cache,
             cache_config,
             stats: Arc::new(DashMap::new()),
+            cache_enabled: AtomicBool::new(true),
+            cache_file: OnceLock::new(),
+            cache_file_loaded: OnceCell::new(),
+            limiters: DashMap::new(),
+            in_flight: DashMap::new(),
         })
     }
 
@@ -178,20 +522,159 @@ impl OptimizedApiClient {
             .unwrap_or_default()
     }
 
-    /// Clear the cache (useful for testing or manual cache invalidation)
-    #[allow(dead_code, clippy::disallowed_methods)] // Allow for tracing macro expansion
+    /// Send every request to the bridge service, bypassing the cache (`--no-cache`)
+    pub fn disable_cache(&self) {
+        self.cache_enabled.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_cache_enabled(&self) -> bool {
+        self.cache_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Keep claim proofs and L1 info tree indices in `path` between commands
+    pub fn persist_to(&self, path: impl Into<PathBuf>) {
+        let _ = self.cache_file.set(path.into());
+    }
+
+    /// Number of unexpired cached responses, including the ones in the cache file
+    pub async fn cached_entries(&self) -> usize {
+        self.load_cache_file().await;
+        let cache = self.cache.read().await;
+        cache
+            .iter()
+            .filter(|(_, entry)| !Self::is_expired(entry))
+            .count()
+    }
+
+    /// Drop the cached responses of an endpoint, for one network or all of them
+    ///
+    /// Pollers call this before each attempt to see the bridge service's
+    /// current state, leaving other endpoints and the cache file alone.
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
+    pub async fn invalidate(&self, endpoint: &str, network_id: Option<u64>) {
+        let mut cache = self.cache.write().await;
+        let stale: Vec<CacheKey> = cache
+            .iter()
+            .filter(|(key, _)| {
+                key.endpoint == endpoint
+                    && network_id.is_none_or(|network_id| key.network_id == Some(network_id))
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &stale {
+            cache.pop(key);
+        }
+        debug!(
+            endpoint,
+            network_id,
+            entries = stale.len(),
+            "Cache entries invalidated"
+        );
+    }
+
+    /// Clear the responses cached for the life of the process
+    ///
+    /// Persistent responses stay valid until the sandbox changes, so they are
+    /// kept, in memory and in the cache file.
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
     pub async fn clear_cache(&self) {
         let mut cache = self.cache.write().await;
-        cache.clear();
+        let transient: Vec<CacheKey> = cache
+            .iter()
+            .filter(|(key, _)| !is_persistent(&key.endpoint))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &transient {
+            cache.pop(key);
+        }
         info!("Cache cleared");
     }
 
+    /// Clear the cache and delete the cache file
+    ///
+    /// For `cache clear` and for chain state changes, such as a new sandbox or
+    /// a restored snapshot, that the persisted responses no longer describe.
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
+    pub async fn clear_cache_file(&self) {
+        let mut cache = self.cache.write().await;
+        cache.clear();
+        if let Some(path) = self.cache_file.get() {
+            // Loaded or not, nothing from the file is valid any more
+            let _ = self.cache_file_loaded.set(());
+            match fs::remove_file(path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => warn!(path = %path.display(), error = %e, "Failed to remove cache file"),
+            }
+        }
+        info!("Cache and cache file cleared");
+    }
+
+    /// Load the unexpired entries of the cache file, once
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
+    async fn load_cache_file(&self) {
+        let Some(path) = self.cache_file.get() else {
+            return;
+        };
+        self.cache_file_loaded
+            .get_or_init(|| async {
+                let entries: Vec<StoredEntry> = fs::read_to_string(path)
+                    .ok()
+                    .and_then(|content| serde_json::from_str(&content).ok())
+                    .unwrap_or_default();
+                let now_ms = unix_ms(SystemTime::now());
+                let now = Instant::now();
+                let mut cache = self.cache.write().await;
+                let mut loaded = 0;
+                for entry in entries {
+                    if entry.expires_at_ms > now_ms {
+                        let ttl = Duration::from_millis(entry.expires_at_ms - now_ms);
+                        cache.put(
+                            entry.key,
+                            CacheEntry {
+                                data: entry.data,
+                                expires_at: now + ttl,
+                            },
+                        );
+                        loaded += 1;
+                    }
+                }
+                debug!(path = %path.display(), entries = loaded, "Loaded cache file");
+            })
+            .await;
+    }
+
+    /// Write the unexpired persistent entries to the cache file
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
+    fn save_cache_file(&self, cache: &LruCache<CacheKey, CacheEntry<serde_json::Value>>) {
+        let Some(path) = self.cache_file.get() else {
+            return;
+        };
+        let now_ms = unix_ms(SystemTime::now());
+        let now = Instant::now();
+        let entries: Vec<StoredEntry> = cache
+            .iter()
+            .filter(|(key, entry)| is_persistent(&key.endpoint) && !Self::is_expired(entry))
+            .map(|(key, entry)| StoredEntry {
+                key: key.clone(),
+                expires_at_ms: now_ms
+                    + entry.expires_at.saturating_duration_since(now).as_millis() as u64,
+                data: entry.data.clone(),
+            })
+            .collect();
+        if let Err(e) = write_entries(path, &entries) {
+            warn!(path = %path.display(), error = %e, "Failed to write cache file");
+        }
+    }
+
     /// Get TTL for a specific endpoint
     fn get_ttl_for_endpoint(&self, endpoint: &str) -> Duration {
         match endpoint {
             "bridges" => self.cache_config.bridges_ttl,
             "claims" => self.cache_config.claims_ttl,
-            "claim-proof" | "l1-info-tree-index" => self.cache_config.proof_ttl,
+            "claim-proof" | "l1-info-tree-index" | "l1-info-tree-leaf" => {
+                self.cache_config.proof_ttl
+            }
             _ => self.cache_config.default_ttl,
         }
     }
@@ -228,7 +711,12 @@ impl OptimizedApiClient {
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<serde_json::Value>>,
     {
+        if !self.is_cache_enabled() {
+            debug!(cache_key = ?cache_key, "Cache disabled, fetching from API");
+            return fetch_fn().await;
+        }
         let endpoint = cache_key.endpoint.clone();
+        self.load_cache_file().await;
 
         // Try to get from cache first
         {
@@ -266,7 +754,10 @@ impl OptimizedApiClient {
                 },
             ) {
                 debug!(cache_key = ?cache_key, "Cache entry evicted due to capacity");
-                self.stats.entry(endpoint).or_default().evictions += 1;
+                self.stats.entry(endpoint.clone()).or_default().evictions += 1;
+            }
+            if is_persistent(&endpoint) {
+                self.save_cache_file(&cache);
             }
 
             debug!(
@@ -293,6 +784,7 @@ impl OptimizedApiClient {
             .client
             .get(url)
             .timeout(timeout)
+            .headers(crate::telemetry::trace_headers())
             .send()
             .await
             .map_err(|e| {
@@ -328,40 +820,172 @@ impl OptimizedApiClient {
         Ok(data)
     }
 
+    /// Limiter of the host a URL points at
+    fn limiter(&self, url: &str, api: &ApiConfig) -> Arc<HostLimiter> {
+        self.limiters
+            .entry(host_key(url))
+            .or_insert_with(|| Arc::new(HostLimiter::new(api)))
+            .clone()
+    }
+
+    /// Make an HTTP GET request, retrying transient failures with exponential backoff
+    ///
+    /// Callers requesting a URL that is already being fetched wait for that
+    /// request instead of sending their own.
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
+    pub async fn get_with_retry(&self, url: &str, api: &ApiConfig) -> Result<serde_json::Value> {
+        let shared = self.in_flight.entry(url.to_string()).or_default().clone();
+        let mut fetched = false;
+        let leader = &mut fetched;
+        let response = shared
+            .get_or_init(|| async move {
+                *leader = true;
+                self.fetch_with_retry(url, api)
+                    .await
+                    .map_err(|error| match error {
+                        AggSandboxError::Api(error) => error,
+                        error => ApiError::NetworkError(error.to_string()),
+                    })
+            })
+            .await
+            .clone();
+        if fetched {
+            self.in_flight
+                .remove_if(url, |_, pending| Arc::ptr_eq(pending, &shared));
+        } else {
+            debug!(url = %url, "Shared the response of a concurrent request");
+        }
+        Ok(response?)
+    }
+
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
+    async fn fetch_with_retry(&self, url: &str, api: &ApiConfig) -> Result<serde_json::Value> {
+        let policy = RetryPolicy::from_config(api);
+        let limiter = self.limiter(url, api);
+        let mut attempt = 1;
+        loop {
+            let span =
+                info_span!("api_attempt", url = %url, attempt, max_attempts = policy.max_attempts);
+            let result = async {
+                let _permit = limiter.acquire().await;
+                self.get_with_timeout(url, api.timeout).await
+            }
+            .instrument(span)
+            .await;
+
+            match result {
+                Err(error) if attempt < policy.max_attempts && is_transient(&error) => {
+                    let delay = policy.delay_with_jitter(attempt);
+                    warn!(
+                        url = %url,
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %error,
+                        "Transient API failure, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Get bridges with caching
     #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
     #[instrument(fields(network_id = network_id), skip(self, config))]
     pub async fn get_bridges(&self, config: &Config, network_id: u64) -> Result<serde_json::Value> {
-        let cache_key = CacheKey::new("bridges".to_string()).with_network_id(network_id);
-
-        let base_url = config.get_api_base_url(NetworkId::new(network_id)?);
-        let url = format!("{base_url}/bridge/v1/bridges?network_id={network_id}");
-
-        let timeout = config.api.timeout;
+        self.get_list("bridges", config, network_id, None).await
+    }
 
-        self.get_cached_or_fetch(cache_key, || async {
-            self.get_with_timeout(&url, timeout).await
-        })
-        .await
+    /// Get one page of bridges with caching
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
+    #[instrument(fields(network_id = network_id, page = page.number), skip(self, config))]
+    pub async fn get_bridges_page(
+        &self,
+        config: &Config,
+        network_id: u64,
+        page: Page,
+    ) -> Result<serde_json::Value> {
+        self.get_list("bridges", config, network_id, Some(page))
+            .await
     }
 
     /// Get claims with caching
     #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
     #[instrument(fields(network_id = network_id), skip(self, config))]
     pub async fn get_claims(&self, config: &Config, network_id: u64) -> Result<serde_json::Value> {
-        let cache_key = CacheKey::new("claims".to_string()).with_network_id(network_id);
+        self.get_list("claims", config, network_id, None).await
+    }
 
-        let base_url = config.get_api_base_url(NetworkId::new(network_id)?);
-        let url = format!("{base_url}/bridge/v1/claims?network_id={network_id}");
+    /// Get one page of claims with caching
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
+    #[instrument(fields(network_id = network_id, page = page.number), skip(self, config))]
+    pub async fn get_claims_page(
+        &self,
+        config: &Config,
+        network_id: u64,
+        page: Page,
+    ) -> Result<serde_json::Value> {
+        self.get_list("claims", config, network_id, Some(page))
+            .await
+    }
 
-        let timeout = config.api.timeout;
+    /// List endpoint of a network, the bridge service's default page without `page`
+    async fn get_list(
+        &self,
+        endpoint: &str,
+        config: &Config,
+        network_id: u64,
+        page: Option<Page>,
+    ) -> Result<serde_json::Value> {
+        let mut cache_key = CacheKey::new(endpoint.to_string()).with_network_id(network_id);
+
+        let base_url = config.get_api_base_url(NetworkId::new(network_id)?);
+        let mut url = format!("{base_url}/bridge/v1/{endpoint}?network_id={network_id}");
+        if let Some(page) = page {
+            cache_key = cache_key
+                .with_param("page_number", page.number)
+                .with_param("page_size", page.size);
+            url.push_str(&format!(
+                "&page_number={}&page_size={}",
+                page.number, page.size
+            ));
+        }
 
         self.get_cached_or_fetch(cache_key, || async {
-            self.get_with_timeout(&url, timeout).await
+            self.get_with_retry(&url, &config.api).await
         })
         .await
     }
 
+    /// Fetch every page of a list endpoint, handing the entries of each page
+    /// to `on_page` as it arrives
+    ///
+    /// `endpoint` is `bridges` or `claims`, also the field holding the entries.
+    /// Returns the number of pages fetched.
+    pub async fn for_each_page(
+        &self,
+        endpoint: &str,
+        config: &Config,
+        network_id: u64,
+        page_size: u64,
+        mut on_page: impl FnMut(Page, &[serde_json::Value]) -> Result<()>,
+    ) -> Result<u64> {
+        let mut page = Page::first(page_size);
+        loop {
+            let response = self
+                .get_list(endpoint, config, network_id, Some(page))
+                .await?;
+            let items = response[endpoint].as_array().map_or(&[][..], Vec::as_slice);
+            on_page(page, items)?;
+            if page.is_last(items.len(), response["count"].as_u64()) {
+                return Ok(page.number);
+            }
+            page = page.next();
+        }
+    }
+
     /// Get claim proof with caching
     #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
     #[instrument(
@@ -383,10 +1007,9 @@ impl OptimizedApiClient {
         let base_url = config.get_api_base_url(NetworkId::new(network_id)?);
         let url = format!("{base_url}/bridge/v1/claim-proof?network_id={network_id}&leaf_index={leaf_index}&deposit_count={deposit_count}");
 
-        let timeout = config.api.timeout;
-
+        // Normalized before caching so every caller sees the same layout
         self.get_cached_or_fetch(cache_key, || async {
-            self.get_with_timeout(&url, timeout).await
+            normalize_claim_proof(self.get_with_retry(&url, &config.api).await?)
         })
         .await
     }
@@ -410,16 +1033,98 @@ impl OptimizedApiClient {
         let base_url = config.get_api_base_url(NetworkId::new(network_id)?);
         let url = format!("{base_url}/bridge/v1/l1-info-tree-index?network_id={network_id}&deposit_count={deposit_count}");
 
-        let timeout = config.api.timeout;
+        self.get_cached_or_fetch(cache_key, || async {
+            self.get_with_retry(&url, &config.api).await
+        })
+        .await
+    }
+
+    /// Get an L1 info tree leaf with caching
+    ///
+    /// On L1 this is the leaf at `leaf_index`, on an L2 the first leaf
+    /// injected there at or after it.
+    #[allow(clippy::disallowed_methods)] // Allow for tracing macro expansion
+    #[instrument(
+        fields(network_id = network_id, leaf_index = leaf_index),
+        skip(self, config)
+    )]
+    pub async fn get_l1_info_tree_leaf(
+        &self,
+        config: &Config,
+        network_id: u64,
+        leaf_index: u32,
+    ) -> Result<serde_json::Value> {
+        let cache_key = CacheKey::new("l1-info-tree-leaf".to_string())
+            .with_network_id(network_id)
+            .with_param("leaf_index", leaf_index);
+
+        let base_url = config.get_api_base_url(NetworkId::new(network_id)?);
+        let url = format!("{base_url}/bridge/v1/injected-l1-info-leaf?network_id={network_id}&leaf_index={leaf_index}");
 
         self.get_cached_or_fetch(cache_key, || async {
-            self.get_with_timeout(&url, timeout).await
+            self.get_with_retry(&url, &config.api).await
         })
         .await
     }
+
+    /// Deposits made on a network, validated into [`BridgeDeposit`]s
+    pub async fn get_bridge_deposits(
+        &self,
+        config: &Config,
+        network_id: u64,
+    ) -> Result<Vec<BridgeDeposit>> {
+        BridgeDeposit::list_from(&self.get_bridges(config, network_id).await?)
+    }
+
+    /// Claim proof validated into a [`ClaimProofResponse`]
+    pub async fn get_typed_claim_proof(
+        &self,
+        config: &Config,
+        network_id: u64,
+        leaf_index: u64,
+        deposit_count: u64,
+    ) -> Result<ClaimProofResponse> {
+        ClaimProofResponse::from_value(
+            &self
+                .get_claim_proof(config, network_id, leaf_index, deposit_count)
+                .await?,
+        )
+    }
+
+    /// L1 info tree leaf validated into an [`L1InfoLeaf`], `None` if the service has no such leaf
+    pub async fn get_typed_l1_info_tree_leaf(
+        &self,
+        config: &Config,
+        network_id: u64,
+        leaf_index: u32,
+    ) -> Result<Option<L1InfoLeaf>> {
+        match self
+            .get_l1_info_tree_leaf(config, network_id, leaf_index)
+            .await
+        {
+            Ok(leaf) => L1InfoLeaf::from_value(&leaf).map(Some),
+            Err(AggSandboxError::Api(ApiError::RequestFailed { status: 404, .. })) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// L1 info tree index of a deposit, whichever shape the service returns it in
+    pub async fn get_typed_l1_info_tree_index(
+        &self,
+        config: &Config,
+        network_id: u64,
+        deposit_count: u64,
+    ) -> Result<u64> {
+        L1InfoTreeIndex::from_value(
+            &self
+                .get_l1_info_tree_index(config, network_id, deposit_count)
+                .await?,
+        )
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod tests {
     use super::*;
 
@@ -432,8 +1137,12 @@ mod tests {
 
         assert_eq!(key.endpoint, "test-endpoint");
         assert_eq!(key.network_id, Some(1));
-        assert_eq!(key.leaf_index, Some(10));
-        assert_eq!(key.deposit_count, Some(5));
+        assert_eq!(key.params["leaf_index"], "10");
+        assert_eq!(key.params["deposit_count"], "5");
+        assert_ne!(
+            key,
+            CacheKey::new("test-endpoint".to_string()).with_network_id(1)
+        );
     }
 
     #[test]
@@ -530,4 +1239,333 @@ mod tests {
             client.cache_config.default_ttl
         );
     }
+
+    #[test]
+    fn test_detect_proof_format() {
+        let aggkit = serde_json::json!({
+            "proof_local_exit_root": ["0x01"],
+            "proof_rollup_exit_root": ["0x02"],
+            "l1_info_tree_leaf": {"mainnet_exit_root": "0xaa", "rollup_exit_root": "0xbb"}
+        });
+        let smt = serde_json::json!({"smt_proof": ["0x01"], "l1_info_tree_leaf": {}});
+        let legacy = serde_json::json!({"proof": {"merkle_proof": ["0x01"]}});
+
+        assert_eq!(detect_proof_format(&aggkit).unwrap().name, "aggkit");
+        assert_eq!(detect_proof_format(&smt).unwrap().name, "aggkit-smt");
+        assert_eq!(
+            detect_proof_format(&legacy).unwrap().name,
+            "zkevm-bridge-service"
+        );
+        assert!(detect_proof_format(&serde_json::json!({"error": "not found"})).is_none());
+    }
+
+    #[test]
+    fn test_normalize_claim_proof_formats() {
+        let expected_smt = serde_json::json!(["0x01", "0x02"]);
+        let expected_rollup = serde_json::json!(["0x03"]);
+        let responses = [
+            serde_json::json!({
+                "proof_local_exit_root": ["0x01", "0x02"],
+                "proof_rollup_exit_root": ["0x03"],
+                "l1_info_tree_leaf": {"mainnet_exit_root": "0xaa", "rollup_exit_root": "0xbb", "l1_info_tree_index": 4}
+            }),
+            serde_json::json!({
+                "smt_proof": ["0x01", "0x02"],
+                "smt_proof_rollup": ["0x03"],
+                "l1_info_tree_leaf": {"block_num": 9, "inner": {"mainnet_exit_root": "0xaa", "rollup_exit_root": "0xbb", "l1_info_tree_index": 4}}
+            }),
+            serde_json::json!({
+                "proof": {
+                    "merkle_proof": ["0x01", "0x02"],
+                    "rollup_merkle_proof": ["0x03"],
+                    "main_exit_root": "0xaa",
+                    "rollup_exit_root": "0xbb"
+                }
+            }),
+        ];
+
+        for response in responses {
+            let normalized = normalize_claim_proof(response).unwrap();
+            assert_eq!(normalized["smt_proof"], expected_smt);
+            assert_eq!(normalized["smt_proof_rollup"], expected_rollup);
+            assert_eq!(normalized["l1_info_tree_leaf"]["mainnet_exit_root"], "0xaa");
+            assert_eq!(normalized["l1_info_tree_leaf"]["rollup_exit_root"], "0xbb");
+        }
+    }
+
+    #[test]
+    fn test_normalize_claim_proof_is_idempotent() {
+        let response = serde_json::json!({
+            "proof_local_exit_root": ["0x01"],
+            "l1_info_tree_leaf": {"inner": {"mainnet_exit_root": "0xaa", "rollup_exit_root": "0xbb"}}
+        });
+
+        let once = normalize_claim_proof(response).unwrap();
+        let twice = normalize_claim_proof(once.clone()).unwrap();
+
+        assert_eq!(once, twice);
+        assert_eq!(once["proof_local_exit_root"], serde_json::json!(["0x01"]));
+        assert_eq!(once["l1_info_tree_leaf"]["mainnet_exit_root"], "0xaa");
+        assert!(once["l1_info_tree_leaf"]["inner"].is_object());
+    }
+
+    #[test]
+    fn test_normalize_claim_proof_rejects_unknown_format() {
+        let error = normalize_claim_proof(serde_json::json!({"error": "proof not ready"}))
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("aggkit, aggkit-smt, zkevm-bridge-service"));
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy::default();
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(250));
+        assert_eq!(policy.backoff(2), Duration::from_millis(500));
+        assert_eq!(policy.backoff(3), Duration::from_secs(1));
+        assert_eq!(policy.backoff(10), RETRY_MAX_DELAY);
+        assert_eq!(policy.backoff(u32::MAX), RETRY_MAX_DELAY);
+
+        for attempt in 1..5 {
+            let delay = policy.delay_with_jitter(attempt);
+            assert!(delay >= policy.backoff(attempt) / 2);
+            assert!(delay <= policy.backoff(attempt));
+        }
+    }
+
+    #[test]
+    fn test_transient_errors() {
+        assert!(is_transient(
+            &ApiError::network_error("connection reset").into()
+        ));
+        assert!(is_transient(
+            &ApiError::request_failed("url", 503, "unavailable").into()
+        ));
+        assert!(is_transient(
+            &ApiError::request_failed("url", 429, "slow down").into()
+        ));
+        assert!(!is_transient(
+            &ApiError::request_failed("url", 404, "not found").into()
+        ));
+        assert!(!is_transient(&ApiError::json_parse_error("eof").into()));
+    }
+
+    #[tokio::test]
+    async fn test_get_with_retry_recovers_from_5xx() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/flaky"))
+            .respond_with(ResponseTemplate::new(502))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/flaky"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"ok": true})))
+            .mount(&server)
+            .await;
+
+        let client = OptimizedApiClient::new(CacheConfig::default());
+        let api = ApiConfig {
+            base_url: crate::types::RpcUrl::new(server.uri()).unwrap(),
+            timeout: Duration::from_secs(5),
+            retry_attempts: 2,
+            max_in_flight: 8,
+            rate_limit: 0,
+            ..ApiConfig::default()
+        };
+
+        let data = client
+            .get_with_retry(&format!("{}/flaky", server.uri()), &api)
+            .await
+            .unwrap();
+        assert_eq!(data["ok"], true);
+
+        let single_attempt = ApiConfig {
+            retry_attempts: 1,
+            ..api
+        };
+        Mock::given(method("GET"))
+            .and(path("/down"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+        assert!(client
+            .get_with_retry(&format!("{}/down", server.uri()), &single_attempt)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_gets_share_one_request() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/bridges"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"deposits": []}))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = OptimizedApiClient::new(CacheConfig::default());
+        let api = ApiConfig {
+            base_url: crate::types::RpcUrl::new(server.uri()).unwrap(),
+            timeout: Duration::from_secs(5),
+            retry_attempts: 1,
+            max_in_flight: 8,
+            rate_limit: 0,
+            ..ApiConfig::default()
+        };
+        let url = format!("{}/bridges", server.uri());
+
+        let (first, second, third) = tokio::join!(
+            client.get_with_retry(&url, &api),
+            client.get_with_retry(&url, &api),
+            client.get_with_retry(&url, &api),
+        );
+        for data in [first, second, third] {
+            assert_eq!(data.unwrap()["deposits"], serde_json::json!([]));
+        }
+        assert!(client.in_flight.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_host_limiter_spaces_requests() {
+        let api = ApiConfig {
+            max_in_flight: 2,
+            rate_limit: 10,
+            ..ApiConfig::default()
+        };
+        let limiter = HostLimiter::new(&api);
+
+        let started = tokio::time::Instant::now();
+        let first = limiter.acquire().await;
+        let second = limiter.acquire().await;
+        assert!(first.is_some() && second.is_some());
+        assert_eq!(started.elapsed(), Duration::from_millis(100));
+        assert_eq!(limiter.in_flight.available_permits(), 0);
+
+        drop(first);
+        let _third = limiter.acquire().await;
+        assert_eq!(started.elapsed(), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_host_key() {
+        assert_eq!(
+            host_key("http://localhost:5577/bridge/v1/bridges"),
+            "localhost:5577"
+        );
+        assert_eq!(
+            host_key("https://bridge.example.com/api"),
+            "bridge.example.com:443"
+        );
+        assert_eq!(host_key("not a url"), "not a url");
+    }
+
+    #[tokio::test]
+    async fn test_cache_file_outlives_client() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".aggsandbox").join("api-cache.json");
+        let proof_key = CacheKey::new("claim-proof".to_string())
+            .with_network_id(1)
+            .with_leaf_index(3)
+            .with_deposit_count(7);
+
+        let client = OptimizedApiClient::new(CacheConfig::default());
+        client.persist_to(&path);
+        client
+            .get_cached_or_fetch(proof_key.clone(), || async {
+                Ok(serde_json::json!({"proof": 1}))
+            })
+            .await
+            .unwrap();
+        client
+            .get_cached_or_fetch(
+                CacheKey::new("bridges".to_string()).with_network_id(1),
+                || async { Ok(serde_json::json!({"deposits": []})) },
+            )
+            .await
+            .unwrap();
+
+        // Only the proof is kept for the next command
+        let next = OptimizedApiClient::new(CacheConfig::default());
+        next.persist_to(&path);
+        assert_eq!(next.cached_entries().await, 1);
+        let proof = next
+            .get_cached_or_fetch(proof_key, || async {
+                Err(AggSandboxError::Other(
+                    "the proof should come from the cache file".to_string(),
+                ))
+            })
+            .await
+            .unwrap();
+        assert_eq!(proof["proof"], 1);
+
+        next.clear_cache().await;
+        assert!(path.exists());
+        assert_eq!(next.cached_entries().await, 1);
+        next.clear_cache_file().await;
+        assert!(!path.exists());
+        assert_eq!(next.cached_entries().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_endpoint() {
+        let client = OptimizedApiClient::new(CacheConfig::default());
+        for (endpoint, network_id) in [("bridges", 0), ("bridges", 1), ("claims", 0)] {
+            client
+                .get_cached_or_fetch(
+                    CacheKey::new(endpoint.to_string()).with_network_id(network_id),
+                    || async { Ok(serde_json::json!({})) },
+                )
+                .await
+                .unwrap();
+        }
+
+        client.invalidate("bridges", Some(0)).await;
+        assert_eq!(client.cached_entries().await, 2);
+        client.invalidate("bridges", None).await;
+        assert_eq!(client.cached_entries().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_disabled_cache_always_fetches() {
+        let client = OptimizedApiClient::new(CacheConfig::default());
+        client.disable_cache();
+        let key = CacheKey::new("bridges".to_string()).with_network_id(0);
+        for expected in [1, 2] {
+            let data = client
+                .get_cached_or_fetch(key.clone(), || async {
+                    Ok(serde_json::json!({"fetch": expected}))
+                })
+                .await
+                .unwrap();
+            assert_eq!(data["fetch"], expected);
+        }
+        assert_eq!(client.cached_entries().await, 0);
+    }
+
+    #[test]
+    fn test_last_page() {
+        let page = Page::first(10);
+        assert!(!page.is_last(10, None));
+        assert!(page.is_last(4, None));
+        assert!(page.is_last(10, Some(10)));
+        assert!(!page.next().is_last(10, Some(25)));
+        assert!(page.next().next().is_last(5, Some(25)));
+        assert!(page.is_last(0, Some(25)));
+    }
 }
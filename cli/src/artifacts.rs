@@ -0,0 +1,220 @@
+//! ABIs of the contracts deployed by the sandbox
+//!
+//! Contract names are taken from the Foundry broadcast files of the deployment
+//! scripts (`broadcast/<script>/<chain id>/run-latest.json`) and ABIs from the
+//! build artifacts (`out/<File>.sol/<Contract>.json`) of `agglayer-contracts`.
+//! Both only exist once the contracts were built and deployed from this
+//! checkout, so every lookup is best effort.
+
+use ethers::abi::Abi;
+use ethers::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// EIP-1967 implementation slot: `bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)`
+pub const IMPLEMENTATION_SLOT: &str =
+    "0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+
+/// Foundry project holding the sandbox contracts, relative to the working directory
+const CONTRACTS_DIR: &str = "agglayer-contracts";
+
+/// Deployed contract names and their ABIs
+#[derive(Debug, Default)]
+pub struct Artifacts {
+    root: PathBuf,
+    deployments: HashMap<Address, String>,
+    abis: HashMap<String, Option<Arc<Abi>>>,
+}
+
+impl Artifacts {
+    /// Load deployments from the `agglayer-contracts` checkout
+    pub fn load() -> Self {
+        Self::load_from(Path::new(CONTRACTS_DIR))
+    }
+
+    /// Load deployments from the broadcast files of a Foundry project
+    pub fn load_from(root: &Path) -> Self {
+        let mut deployments = HashMap::new();
+        let scripts = fs::read_dir(root.join("broadcast")).into_iter().flatten();
+        for chain_dir in scripts
+            .flatten()
+            .filter_map(|script| fs::read_dir(script.path()).ok())
+            .flatten()
+            .flatten()
+        {
+            let run = fs::read_to_string(chain_dir.path().join("run-latest.json"))
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok());
+            if let Some(run) = run {
+                deployments.extend(deployments_from_broadcast(&run));
+            }
+        }
+
+        Self {
+            root: root.to_path_buf(),
+            deployments,
+            abis: HashMap::new(),
+        }
+    }
+
+    /// Name of the contract deployed at an address
+    pub fn contract_name(&self, address: Address) -> Option<&str> {
+        self.deployments.get(&address).map(String::as_str)
+    }
+
+    /// ABI of a contract from its build artifact, cached after the first lookup
+    pub fn abi(&mut self, name: &str) -> Option<Arc<Abi>> {
+        let out = self.root.join("out");
+        self.abis
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                let artifact = find_artifact(&out, name)?;
+                let content: serde_json::Value =
+                    serde_json::from_str(&fs::read_to_string(artifact).ok()?).ok()?;
+                serde_json::from_value(content["abi"].clone())
+                    .ok()
+                    .map(Arc::new)
+            })
+            .clone()
+    }
+
+    /// Creation bytecode of a contract from its build artifact
+    pub fn bytecode(&self, name: &str) -> Option<Bytes> {
+        let artifact = find_artifact(&self.root.join("out"), name)?;
+        let content: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(artifact).ok()?).ok()?;
+        let bytecode: Bytes = content["bytecode"]["object"].as_str()?.parse().ok()?;
+        (!bytecode.is_empty()).then_some(bytecode)
+    }
+}
+
+/// Contracts created by a broadcast run, keyed by address
+fn deployments_from_broadcast(run: &serde_json::Value) -> Vec<(Address, String)> {
+    run["transactions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|tx| {
+            matches!(
+                tx["transactionType"].as_str(),
+                Some("CREATE") | Some("CREATE2")
+            )
+        })
+        .filter_map(|tx| {
+            let address = tx["contractAddress"].as_str()?.parse::<Address>().ok()?;
+            let name = tx["contractName"].as_str()?;
+            Some((address, name.to_string()))
+        })
+        .collect()
+}
+
+/// Artifact of a contract, usually `out/<Name>.sol/<Name>.json`
+fn find_artifact(out: &Path, name: &str) -> Option<PathBuf> {
+    let file = format!("{name}.json");
+    let conventional = out.join(format!("{name}.sol")).join(&file);
+    if conventional.is_file() {
+        return Some(conventional);
+    }
+    fs::read_dir(out)
+        .ok()?
+        .flatten()
+        .map(|source| source.path().join(&file))
+        .find(|path| path.is_file())
+}
+
+/// Implementation behind an EIP-1967 proxy, `None` for contracts that are not proxies
+pub async fn read_implementation(provider: &Provider<Http>, address: Address) -> Option<Address> {
+    let slot = IMPLEMENTATION_SLOT.parse::<H256>().ok()?;
+    let value = provider.get_storage_at(address, slot, None).await.ok()?;
+    let word = value.as_bytes();
+    if word[..12].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    let implementation = Address::from_slice(&word[12..]);
+    (!implementation.is_zero()).then_some(implementation)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_deployments_from_broadcast() {
+        let run = json!({
+            "transactions": [
+                {
+                    "transactionType": "CREATE",
+                    "contractName": "PolygonZkEVMBridgeV2",
+                    "contractAddress": "0x63ee8865a8b25919b5103d02586aaaf078ee9102"
+                },
+                {
+                    "transactionType": "CALL",
+                    "contractName": "PolygonZkEVMBridgeV2",
+                    "contractAddress": "0x0000000000000000000000000000000000000001"
+                },
+                {"transactionType": "CREATE", "contractName": null, "contractAddress": "0x01"}
+            ]
+        });
+
+        let deployments = deployments_from_broadcast(&run);
+
+        assert_eq!(deployments.len(), 1);
+        assert_eq!(deployments[0].1, "PolygonZkEVMBridgeV2");
+    }
+
+    #[test]
+    fn test_abi_from_artifact() {
+        let root = tempfile::tempdir().unwrap();
+        let artifact_dir = root.path().join("out").join("Counter.sol");
+        fs::create_dir_all(&artifact_dir).unwrap();
+        fs::write(
+            artifact_dir.join("Counter.json"),
+            json!({
+                "abi": [{
+                    "type": "event",
+                    "name": "Incremented",
+                    "anonymous": false,
+                    "inputs": [{"name": "by", "type": "uint256", "indexed": false}]
+                }]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let mut artifacts = Artifacts::load_from(root.path());
+
+        let abi = artifacts.abi("Counter").unwrap();
+        assert!(abi.event("Incremented").is_ok());
+        assert!(artifacts.abi("Missing").is_none());
+    }
+
+    #[test]
+    fn test_bytecode_from_artifact() {
+        let root = tempfile::tempdir().unwrap();
+        let artifact_dir = root.path().join("out").join("Token.sol");
+        fs::create_dir_all(&artifact_dir).unwrap();
+        fs::write(
+            artifact_dir.join("Token.json"),
+            json!({"abi": [], "bytecode": {"object": "0x6080604052"}}).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            artifact_dir.join("IToken.json"),
+            json!({"abi": [], "bytecode": {"object": "0x"}}).to_string(),
+        )
+        .unwrap();
+
+        let artifacts = Artifacts::load_from(root.path());
+
+        assert_eq!(
+            artifacts.bytecode("Token").unwrap().to_vec(),
+            vec![0x60, 0x80, 0x60, 0x40, 0x52]
+        );
+        assert!(artifacts.bytecode("IToken").is_none());
+        assert!(artifacts.bytecode("Missing").is_none());
+    }
+}
@@ -0,0 +1,1448 @@
+//! Command-line interface: argument parsing and command dispatch
+//!
+//! The `aggsandbox` binary only parses the command line with [`Cli`], sets up
+//! logging and hands the command to [`run`]. The interactive shell and the
+//! `serve` control server dispatch through the same [`execute`].
+
+use clap::{CommandFactory, Parser, Subcommand};
+use colored::*;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "docker")]
+use crate::docker;
+use crate::{
+    api_client, commands, config, error, events, logging, project, schema, telemetry, types, ui,
+    validation,
+};
+
+use commands::bridge::signer::SignerKind;
+#[cfg(feature = "metrics")]
+use commands::MonitorCommands;
+use commands::{
+    AssertCommands, BridgeCommands, CacheCommands, ChainCommands, ConfigCommands,
+    ContractsCommands, IndexCommands, RegistryCommands, ShowCommands, SnapshotCommands,
+    StateCommands, TestCommands, TokenCommands, TxCommands,
+};
+use error::Result;
+use logging::LogConfig;
+use tracing::{error, info, warn};
+use ui::OutputFormat;
+use validation::{parse_network_id, parse_network_selection, parse_rpc_override};
+
+#[derive(Parser)]
+#[command(name = "aggsandbox")]
+#[command(about = "🚀 CLI for managing Agglayer sandbox environment")]
+#[command(
+    long_about = "AggSandbox CLI provides comprehensive tools for managing your Agglayer sandbox environment.\n\nThis tool helps you start, stop, monitor, and interact with your sandbox infrastructure\nincluding L1/L2 chains, bridge services, and blockchain events.\n\nExamples:\n  `aggsandbox start --detach`             # Start sandbox in background\n  `aggsandbox start --fork --multi-l2`    # Start with real data and multiple L2s\n  `aggsandbox logs -f aggkit`             # Follow aggkit logs\n  `aggsandbox show bridges --network-id 0` # Show L1 bridge information\n  `aggsandbox events --chain anvil-l1`    # Show recent blockchain events"
+)]
+#[command(version = "0.1.0")]
+#[command(author = "Agglayer Team")]
+#[command(after_long_help = exit_code_help())]
+#[command(
+    help_template = "{before-help}{name} {version}\n{about-with-newline}\n{usage-heading} {usage}\n\n{all-args}{after-help}"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+    /// Enable verbose output (-v for debug, -vv for trace)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count, help = "Enable verbose output (-v debug, -vv trace)")]
+    pub verbose: u8,
+    /// Enable quiet mode (only errors and warnings)
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Suppress all output except errors and warnings"
+    )]
+    pub quiet: bool,
+    /// Set log format style
+    #[arg(long, global = true, default_value = "pretty", value_parser = ["pretty", "compact", "json"], help = "Set log output format")]
+    pub log_format: String,
+    /// OTLP/HTTP collector receiving the command's spans
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        help = "Export traces to this OTLP/HTTP collector, e.g. http://localhost:4318 (default: $OTEL_EXPORTER_OTLP_ENDPOINT)"
+    )]
+    pub otlp_endpoint: Option<String>,
+    /// Output format for command results
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value = "human",
+        help = "Output format for command results (human, json)"
+    )]
+    pub output: OutputFormat,
+    /// Refuse any operation that would reach the public internet
+    #[arg(
+        long,
+        global = true,
+        help = "Fail fast if any endpoint would reach the public internet"
+    )]
+    pub offline: bool,
+    /// Simulate bridge transactions instead of broadcasting them
+    #[arg(
+        long,
+        global = true,
+        help = "Simulate bridge transactions (eth_call + eth_estimateGas) without broadcasting"
+    )]
+    pub dry_run: bool,
+    /// Project root holding the compose files, .env and config file
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIR",
+        help = "Sandbox project directory (default: $AGGSANDBOX_HOME, or found from the working directory upwards)"
+    )]
+    pub project_dir: Option<PathBuf>,
+    /// RPC endpoint overrides, e.g. for a remote sandbox
+    #[arg(
+        long,
+        global = true,
+        value_name = "NETWORK=URL",
+        value_parser = parse_rpc_override,
+        help = "Use this RPC endpoint for a network (repeatable), e.g. l1=https://sandbox.example.com:8545"
+    )]
+    pub rpc_url: Vec<(u64, types::RpcUrl)>,
+    /// Bridge service override, e.g. for a remote sandbox
+    #[arg(
+        long,
+        global = true,
+        value_name = "URL",
+        value_parser = parse_api_url,
+        help = "Use this bridge service API base URL"
+    )]
+    pub api_url: Option<types::RpcUrl>,
+    /// Bypass the bridge service response cache
+    #[arg(
+        long,
+        global = true,
+        help = "Send every bridge service request instead of using cached responses"
+    )]
+    pub no_cache: bool,
+    /// Print the JSON Schema of the command's JSON output instead of running it
+    #[arg(
+        long,
+        global = true,
+        help = "Print the JSON Schema of the command's --output json document"
+    )]
+    pub schema: bool,
+    /// Print the exit code of every error category instead of running a command
+    #[arg(
+        long,
+        global = true,
+        help = "Print the table of exit codes per error category"
+    )]
+    pub exit_code_map: bool,
+}
+
+/// Clap value parser for the bridge service URL
+fn parse_api_url(input: &str) -> std::result::Result<types::RpcUrl, String> {
+    types::RpcUrl::new(input.trim()).map_err(|e| e.to_string())
+}
+
+/// Exit code table appended to `--help`
+fn exit_code_help() -> String {
+    format!("Exit codes:\n{}", error::exit_code::render_table())
+}
+
+#[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)] // Parsed once per run
+pub enum Commands {
+    /// 🚀 Start the sandbox environment
+    #[cfg(feature = "docker")]
+    #[command(
+        long_about = "Start the Agglayer sandbox environment with Docker Compose.\n\nThis command initializes and starts all required services including:\n- L1 Ethereum node (Anvil)\n- L2 Polygon zkEVM node (Anvil)\n- Bridge service\n- Agglayer service\n\nExamples:\n  `aggsandbox start`                     # Start with default settings\n  `aggsandbox start --detach`            # Start in background\n  `aggsandbox start --build`             # Rebuild images before starting\n  `aggsandbox start --fork`              # Use real blockchain data\n  `aggsandbox start --fork --multi-l2`   # Fork mode with multiple L2 chains\n  `aggsandbox start --scale l2=4`        # Four L2 chains from a generated compose file\n  `aggsandbox start --detach --wait-timeout 300` # Allow slow machines 5 minutes to become healthy\n\nWith --detach, start waits until every RPC node and bridge service responds and\nexits non-zero if one is still unhealthy after --wait-timeout seconds (default 120)."
+    )]
+    Start {
+        /// Run services in detached mode (background)
+        #[arg(short, long, help = "Start services in background (detached mode)")]
+        detach: bool,
+        /// Build Docker images before starting services
+        #[arg(short, long, help = "Rebuild Docker images before starting")]
+        build: bool,
+        /// Enable fork mode using real blockchain data
+        #[arg(
+            short,
+            long,
+            help = "Use real blockchain data from FORK_URL environment variables"
+        )]
+        fork: bool,
+        /// Enable multi-L2 configuration with additional chains
+        #[arg(
+            short,
+            long,
+            help = "Start with a second L2 chain for multi-chain testing"
+        )]
+        multi_l2: bool,
+        /// Number of L2 chains, generating a compose file for more than two
+        #[arg(
+            long,
+            value_name = "l2=N",
+            value_parser = docker::parse_scale,
+            conflicts_with = "multi_l2",
+            help = "Start N L2 chains (l2=N), each with its own aggkit; more than two run from a generated docker-compose.generated.yml"
+        )]
+        scale: Option<u64>,
+        // Enable automatic claim sponsoring
+        #[arg(
+            short,
+            long,
+            help = "Claimsponsor will sponsor all claims automatically"
+        )]
+        claim_all: bool,
+        /// Seconds to wait for the services to become healthy in detached mode
+        #[arg(
+            long,
+            value_name = "SECS",
+            default_value_t = commands::readiness::DEFAULT_WAIT_TIMEOUT,
+            help = "Seconds to wait for the RPC nodes and bridge services to become healthy with --detach (0 to skip)"
+        )]
+        wait_timeout: u64,
+    },
+    /// 🛑 Stop the sandbox environment
+    #[cfg(feature = "docker")]
+    #[command(
+        long_about = "Stop all sandbox services using docker-compose down.\n\nThis command gracefully shuts down all running services and containers.\nOptionally, you can also remove associated Docker volumes.\n\nExamples:\n  `aggsandbox stop`          # Stop services, keep data\n  `aggsandbox stop -v`       # Stop services and remove volumes"
+    )]
+    Stop {
+        /// Remove Docker volumes when stopping (⚠️  deletes all data)
+        #[arg(
+            short,
+            long,
+            help = "Remove Docker volumes and all persistent data (⚠️  destructive)"
+        )]
+        volumes: bool,
+    },
+    /// 📊 Show status of all services
+    #[cfg(feature = "docker")]
+    #[command(
+        long_about = "Display the current status of all sandbox services.\n\nShows which containers are running, stopped, or have errors.\nIncludes health checks and port information for active services.\n\nBesides the containers' state, health check, published ports and image digest,\nthe head block of every chain and whether each network's bridge service answers\nare shown. --json (or --output json) prints the same as one document for\nmonitoring scripts.\n\nExamples:\n  `aggsandbox status`\n  `aggsandbox status --json | jq '.chains[].head_block'`"
+    )]
+    Status {
+        /// Output raw JSON without formatting (for scripting)
+        #[arg(long, help = "Output raw JSON without decorative formatting")]
+        json: bool,
+    },
+    /// 🖥️  Live dashboard of the running sandbox
+    #[cfg(all(feature = "docker", feature = "tui"))]
+    #[command(
+        long_about = "Show a live terminal dashboard of the running sandbox.\n\nOne screen shows the health of the services, the head block and bridge\nservice indexing of every chain, pending, claimable and claimed deposits\nbetween the networks, the latest events and whether the L1 global exit root\nhas been injected on the L2s. The screen is refreshed every --interval seconds.\n\nKeys: q quit, tab switch between bridges and services, ↑/↓ select,\nenter show the selected bridge, r restart the selected service, f refresh now\n\nExamples:\n  `aggsandbox dashboard`\n  `aggsandbox dashboard --interval 2 --blocks 50`"
+    )]
+    Dashboard {
+        /// Seconds between refreshes
+        #[arg(short, long, default_value = "5", help = "Seconds between refreshes")]
+        interval: u64,
+        /// Blocks scanned for recent events
+        #[arg(
+            short,
+            long,
+            default_value = "20",
+            help = "Blocks of each chain scanned for recent events"
+        )]
+        blocks: u64,
+    },
+    /// 🩺 Diagnose the sandbox environment
+    #[command(
+        long_about = "Check that the environment can run the sandbox and print a pass/fail checklist.\n\nChecks the Docker daemon, Docker Compose, ports 8545-8547/5577/5578, the .env file,\nthe contract address variables, RPC connectivity and the bridge service API.\nEvery failed check comes with the command or edit that fixes it, and the\ncommand exits non-zero when a check fails.\n\nExamples:\n  `aggsandbox doctor`\n  `aggsandbox doctor --output json`"
+    )]
+    Doctor,
+    /// 📋 Show logs from services
+    #[cfg(feature = "docker")]
+    #[command(
+        long_about = "Display logs from sandbox services.\n\nView logs from all services or filter by specific service name.\nUse --follow to stream logs in real-time.\n\nExamples:\n  `aggsandbox logs`                    # Show all logs\n  `aggsandbox logs aggkit`             # Show aggkit logs (bridge, oracle, etc.)\n  `aggsandbox logs -f`                 # Follow all logs\n  `aggsandbox logs -f anvil-l1`        # Follow L1 node logs\n  `aggsandbox logs -f aggkit`          # Follow aggkit logs in real-time\n  `aggsandbox logs aggkit anvil-l1`    # Show logs of several services\n  `aggsandbox logs aggkit --since 10m --level warn`  # Recent aggkit warnings and errors\n  `aggsandbox logs -f anvil-l1 --grep 'eth_(call|sendRawTransaction)'`\n\n--level reads the level of JSON lines and of text lines with an upper-case level\nword such as aggkit's; lines without a level are hidden when it is set."
+    )]
+    Logs {
+        /// Follow log output in real-time
+        #[arg(short, long, help = "Stream logs continuously (like 'tail -f')")]
+        follow: bool,
+        /// Service names to show logs for
+        #[arg(help = "Service names (e.g., aggkit, anvil-l1, anvil-l2, contract-deployer)")]
+        services: Vec<String>,
+        /// Only show lines matching a regular expression
+        #[arg(
+            long,
+            value_name = "REGEX",
+            help = "Only show lines matching a regular expression"
+        )]
+        grep: Option<String>,
+        /// Only show logs newer than a duration or timestamp
+        #[arg(
+            long,
+            value_name = "DURATION",
+            help = "Only show logs newer than a duration (10m, 1h30m) or an RFC 3339 timestamp"
+        )]
+        since: Option<String>,
+        /// Minimum level of structured log lines
+        #[arg(
+            long,
+            value_name = "LEVEL",
+            help = "Only show structured log lines at or above a level (trace, debug, info, warn, error, fatal)"
+        )]
+        level: Option<String>,
+    },
+    /// 📦 Write a bug report of the sandbox state
+    #[command(
+        long_about = "Bundle the sandbox state into one markdown file to attach to bug reports
+against aggkit or the bridge contracts.
+
+The report contains the services with their image digests, the chain heads, the
+endpoint matrix, the contract addresses, per network the latest bridges and
+claims and the contract events of the last --blocks blocks, and the last
+--log-lines lines of every service's log. Sections that cannot be collected,
+e.g. because a service is down, record their error instead of failing the report.
+
+Review the report before sharing it: logs may contain addresses and keys.
+
+Examples:
+  `aggsandbox report`                          # aggsandbox-report-<timestamp>.md
+  `aggsandbox report --output-file bug.md`
+  `aggsandbox report --log-lines 1000 --blocks 500`"
+    )]
+    Report {
+        /// File to write the report to
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "File to write the report to (default: aggsandbox-report-<timestamp>.md)"
+        )]
+        output_file: Option<std::path::PathBuf>,
+        /// Log lines per service
+        #[arg(long, default_value_t = commands::report::DEFAULT_LOG_LINES, help = "Number of log lines to include per service")]
+        log_lines: usize,
+        /// Blocks of contract events per network
+        #[arg(long, default_value_t = commands::report::DEFAULT_EVENT_BLOCKS, help = "Number of recent blocks to include events from per network")]
+        blocks: u64,
+    },
+    /// 🔄 Restart the sandbox environment
+    #[cfg(feature = "docker")]
+    #[command(
+        long_about = "Restart all sandbox services.\n\nThis performs a stop followed by start operation,\npreserving volumes and configuration.\n\nExample:\n  `aggsandbox restart`"
+    )]
+    Restart,
+    /// ⬆️  Update the sandbox images and the CLI
+    #[cfg(feature = "docker")]
+    #[command(
+        long_about = "Pull newer sandbox images and check for a newer CLI release.\n\nPulls the images referenced by the compose files (aggkit, the contract\ndeployer, anvil) and prints their digests before and after. With --check\nnothing is pulled: local digests are compared with Docker Hub instead.\nWith --cli the running binary is replaced by the latest GitHub release.\n\nRestart the sandbox afterwards to run the updated images.\n\nExamples:\n  `aggsandbox upgrade`                # Pull newer images\n  `aggsandbox upgrade --check`        # Only report available updates\n  `aggsandbox upgrade --cli`          # Also install the latest CLI release"
+    )]
+    Upgrade {
+        /// Only report updates without pulling or installing
+        #[arg(long, conflicts_with = "cli", help = "Only report available updates")]
+        check: bool,
+        /// Replace the CLI binary with the latest release
+        #[arg(long, help = "Also install the latest CLI release")]
+        cli: bool,
+    },
+    /// ℹ️  Show sandbox configuration and accounts
+    #[cfg(feature = "docker")]
+    #[command(
+        long_about = "Display comprehensive sandbox configuration information.\n\nShows:\n- Network configuration (L1/L2 RPC URLs, Chain IDs)\n- Account addresses and balances\n- Contract deployment addresses\n- Bridge service endpoints\n\nExample:\n  `aggsandbox info`"
+    )]
+    Info,
+    /// 🌉 Show bridge and blockchain information
+    #[command(
+        long_about = "Access bridge data and blockchain information.\n\nQuery bridges, claims, proofs, and other bridge-related data\nfrom the Agglayer bridge service API.\n\nExamples:\n  `aggsandbox show bridges --network-id 0`     # List bridges for L1\n  `aggsandbox show claims --network-id 1`      # Show claims for first L2\n  `aggsandbox show proof --network-id 0 --leaf-index 0 --deposit-count 1`"
+    )]
+    Show {
+        #[command(subcommand)]
+        subcommand: ShowCommands,
+    },
+    /// 🧪 Assert on-chain state in test scripts
+    #[command(
+        long_about = "Check balances and claim status from shell-based test scripts.\n\nEach assertion exits with a non-zero status and prints the expected and actual\nvalues when it does not hold.\n\nExamples:\n  `aggsandbox assert balance --network-id 1 --address 0x123... --gte 100`\n  `aggsandbox assert claimed --network-id 1 --global-index 18446744073709551616`"
+    )]
+    Assert {
+        #[command(subcommand)]
+        subcommand: AssertCommands,
+    },
+    /// ⏱️ Monitor a long-lived sandbox
+    #[cfg(feature = "metrics")]
+    #[command(
+        long_about = "Verify the health of a long-lived or shared sandbox automatically.\n\nA canary bridge and claim round trip runs at a fixed interval, its latency is\nrecorded in .aggsandbox/slo.jsonl and rounds over the threshold raise alerts.\n\nExamples:\n  `aggsandbox monitor slo --threshold 90`\n  `aggsandbox monitor slo --interval 60 --alert webhook --webhook-url https://hooks.example.com/sandbox`"
+    )]
+    Monitor {
+        #[command(subcommand)]
+        subcommand: MonitorCommands,
+    },
+    /// 🔁 Run built-in end-to-end tests against the sandbox
+    #[command(
+        long_about = "Run canned bridge flows against the running sandbox and check the results.\n\nEach suite exits with a non-zero status when a step fails, so it can gate CI\nfor new sandbox images.\n\nExamples:\n  `aggsandbox test e2e`\n  `aggsandbox test e2e --network-id 2 --output json`"
+    )]
+    Test {
+        #[command(subcommand)]
+        subcommand: TestCommands,
+    },
+    /// 🌉 Execute bridge operations (asset transfers, claims, messages)
+    #[command(
+        long_about = "Execute bridge operations using direct smart contract interactions.\n\nPerform cross-chain asset transfers, claim bridged assets, and send messages\nbetween L1 and L2 networks with user-friendly commands.\n\nExamples:\n  `aggsandbox bridge asset --network-id 0 --destination-network-id 1 --amount 100000000000000000 --token-address 0x0000...`\n  `aggsandbox bridge claim --network-id 1 --tx-hash 0xabc... --source-network-id 0`\n  `aggsandbox bridge message --network-id 0 --destination-network-id 1 --target 0x123... --data 0xabc...`\n  `aggsandbox bridge --account deployer claim-all --network-id 1`  # Sign with a named account\n  `aggsandbox bridge --signer ledger asset --network-id 0 ...`     # Sign on a Ledger (fork mode)\n  `aggsandbox bridge --no-wait asset --network-id 0 ...`           # Do not wait for the receipt\n  `aggsandbox bridge --idempotency-key ci-deposit-1 asset ...`     # Bridge only once across retries"
+    )]
+    Bridge {
+        #[command(subcommand)]
+        subcommand: BridgeCommands,
+        /// Named account used to sign transactions instead of the first Anvil key
+        #[arg(
+            long,
+            global = true,
+            help = "Sign with a named account (aggsandbox.toml, AGGSANDBOX_ACCOUNT_<NAME> or keystore)"
+        )]
+        account: Option<String>,
+        /// Signer for transactions: local key or a hardware wallet
+        #[arg(
+            long,
+            global = true,
+            value_enum,
+            default_value = "local",
+            help = "Sign with a local key, a Ledger or a Trezor"
+        )]
+        signer: SignerKind,
+        /// Derivation path of the hardware wallet account
+        #[arg(
+            long,
+            global = true,
+            help = "HD path for --signer ledger/trezor (default: first Ledger Live/Trezor account)"
+        )]
+        hd_path: Option<String>,
+        /// Wait for the receipt of sent transactions (the default)
+        #[arg(
+            long,
+            global = true,
+            overrides_with = "no_wait",
+            help = "Wait for transactions to be mined and decode revert reasons (default)"
+        )]
+        wait: bool,
+        /// Return as soon as transactions are sent
+        #[arg(
+            long,
+            global = true,
+            overrides_with = "wait",
+            help = "Return as soon as transactions are sent, without waiting for the receipt"
+        )]
+        no_wait: bool,
+        /// Record the operation under a key and skip it when the key was already completed
+        #[arg(
+            long,
+            global = true,
+            value_name = "KEY",
+            help = "Skip the operation and print its recorded result if KEY already completed"
+        )]
+        idempotency_key: Option<String>,
+    },
+    /// 💸 Fund a test account with ETH and sandbox tokens
+    #[command(
+        long_about = "Give an address ETH and/or sandbox test tokens on any network.\n\nETH is added to the current balance with anvil_setBalance. Tokens are minted by\nthe deployer account and can be given as AGG (the AggERC20 test token) or by\naddress. Token amounts are raw units and accept scientific notation.\n\nExamples:\n  `aggsandbox fund --network 1 --address 0xabc... --eth 10`\n  `aggsandbox fund --network 1 --address 0xabc... --eth 10 --token AGG --amount 1e18`\n  `aggsandbox fund -n 0 --address 0xabc... --token AGG --amount 5e20`",
+        group(clap::ArgGroup::new("funds").required(true).multiple(true).args(["eth", "token"]))
+    )]
+    Fund {
+        /// Network to fund the account on
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID or alias (0/l1, 1/l2, 2/l3)")]
+        network_id: u64,
+        /// Account to fund
+        #[arg(long, help = "Address to fund")]
+        address: String,
+        /// ETH to add to the balance
+        #[arg(long, help = "ETH to add, in ether (e.g. 10 or 0.5)")]
+        eth: Option<String>,
+        /// Sandbox token to mint
+        #[arg(
+            long,
+            requires = "amount",
+            help = "Token to mint: AGG or a sandbox token address"
+        )]
+        token: Option<String>,
+        /// Token amount to mint
+        #[arg(
+            long,
+            requires = "token",
+            help = "Raw token amount to mint (e.g. 1000000 or 1e18)"
+        )]
+        amount: Option<String>,
+    },
+    /// 🪙 Deploy, list and inspect ERC20 tokens
+    #[command(
+        long_about = "Deploy mintable ERC20 test tokens on the sandbox networks.\n\nDeployed tokens are recorded in .aggsandbox/tokens.json and can be funded with\n`aggsandbox fund` like the tokens deployed at startup. `token info` shows the\ncached metadata of any token and its wrapped counterparts.\n\nExamples:\n  `aggsandbox token deploy -n 0 --name \"Test USD\" --symbol TUSD --decimals 6`\n  `aggsandbox token list`\n  `aggsandbox token info 0x5FbDB2315678afecb367f032d93F642f64180aa3`"
+    )]
+    Token {
+        #[command(subcommand)]
+        subcommand: TokenCommands,
+    },
+    /// ⏱️ Control block production and time on the sandbox chains
+    #[command(
+        long_about = "Drive the Anvil nodes with cheat codes to test timeouts and exit-root propagation.\n\nMine blocks, advance the chain time, enable interval mining or turn automine\noff on one or more networks at a time.\n\nExamples:\n  `aggsandbox chain mine -n 1 --blocks 10`\n  `aggsandbox chain increase-time -n 0 -n 1 1h`\n  `aggsandbox chain set-interval -n 0 12`\n  `aggsandbox chain automine -n 1 off`"
+    )]
+    Chain {
+        #[command(subcommand)]
+        subcommand: ChainCommands,
+    },
+    /// ⛽ Replace or cancel stuck transactions
+    #[command(
+        long_about = "Recover from transactions stuck in the mempool.\n\nA transaction sent with too low a gas price stays pending and holds up every\nlater transaction of its account. Bump it with a higher gas price, or cancel it\nby spending its nonce on a 0 ETH transfer to yourself.\n\nExamples:\n  `aggsandbox tx bump --tx-hash 0xabc... -n 1`\n  `aggsandbox tx cancel --nonce 12 -n 0`\n  `aggsandbox tx --account alice cancel --nonce 3 -n 1`"
+    )]
+    Tx {
+        #[command(subcommand)]
+        subcommand: TxCommands,
+        /// Named account that sent the transaction
+        #[arg(
+            long,
+            global = true,
+            help = "Sign with a named account (aggsandbox.toml, AGGSANDBOX_ACCOUNT_<NAME> or keystore)"
+        )]
+        account: Option<String>,
+    },
+    /// ⚙️ Manage the CLI configuration
+    #[command(
+        long_about = "Manage the aggsandbox configuration file.\n\nShow the effective configuration and where each value comes from, validate,\nscaffold and edit config files, and import RPC URLs, chain IDs and contract\naddresses from other Agglayer dev environments, such as a Kurtosis enclave, so\nthe bridge, claim and show commands can be used against stacks not started\nwith `aggsandbox start`.\n\nExamples:\n  `aggsandbox config show`\n  `aggsandbox config validate`\n  `aggsandbox config set networks.l2.rpc_url http://localhost:9545`\n  `aggsandbox config import --from kurtosis cdk`"
+    )]
+    Config {
+        #[command(subcommand)]
+        subcommand: ConfigCommands,
+    },
+    /// 📐 Inspect deployed sandbox contracts
+    #[command(
+        long_about = "Inspect the contracts deployed by the sandbox.\n\nReports bytecode sizes against the EIP-170 limit, EIP-1967 proxy\nimplementations and admins, and contract owners across networks.\n\nExamples:\n  `aggsandbox contracts report`\n  `aggsandbox contracts report --network-id 1 --output json`"
+    )]
+    Contracts {
+        #[command(subcommand)]
+        subcommand: ContractsCommands,
+    },
+    /// 💾 Save and restore sandbox chain state
+    #[command(
+        long_about = "Save the state of every sandbox network and restore it later.\n\nSnapshots combine anvil_dumpState and evm_snapshot with metadata about deposit\ncounts and contract addresses, and are stored in .aggsandbox/snapshots/.\n\nExamples:\n  `aggsandbox snapshot save bridged-usdc`\n  `aggsandbox snapshot restore bridged-usdc`\n  `aggsandbox snapshot list`"
+    )]
+    Snapshot {
+        #[command(subcommand)]
+        subcommand: SnapshotCommands,
+    },
+    /// 🗂️ Maintain the local bridge index
+    #[command(
+        long_about = "Index bridge, claim and exit-root events of the sandbox networks locally.\n\nThe index is stored in .aggsandbox/index/ and read by `show bridges`,\n`show claims` and `bridge claim` with --source local, which keeps them working\nwhen the bridge service is slow or not running.\n\nExamples:\n  `aggsandbox index sync`\n  `aggsandbox index sync --follow`\n  `aggsandbox index status`\n  `aggsandbox index reset -n 1`"
+    )]
+    Index {
+        #[command(subcommand)]
+        subcommand: IndexCommands,
+    },
+    /// 🗃️  Maintain the local state under .aggsandbox/
+    #[command(
+        long_about = "Maintain the local state the CLI keeps under .aggsandbox/.\n\nOld records of the local bridge index can be pruned into an archive to keep it\nbounded during long-running benchmarks and soak tests.\n\nExamples:\n  `aggsandbox state prune --older-than 7d`\n  `aggsandbox state prune --older-than 12h --discard`"
+    )]
+    State {
+        #[command(subcommand)]
+        subcommand: StateCommands,
+    },
+    /// 💾 Manage the cache of bridge service responses
+    #[command(
+        long_about = "Manage the cache of bridge service responses.\n\nClaim proofs and L1 info tree indices are kept in .aggsandbox/api-cache.json\nbetween commands; deposit and claim lists are only cached within one process\nsuch as the shell. Pass --no-cache to any command to bypass the cache.\n\nExamples:\n  `aggsandbox cache clear`"
+    )]
+    Cache {
+        #[command(subcommand)]
+        subcommand: CacheCommands,
+    },
+    /// 📍 Discover sandbox contracts through the on-chain registry
+    #[command(
+        long_about = "Read and write the on-chain registry of sandbox contract addresses.\n\nThe registry is deployed at the same CREATE2 address on every network during\n`aggsandbox start` and maps names such as bridge, bridgeExtension and aggERC20 to\nthe contracts of that network, so external scripts and contracts can find them\nwithout the .env file.\n\nExamples:\n  `aggsandbox registry address`\n  `aggsandbox registry list -n 1`\n  `aggsandbox registry get bridge -n 0`\n  `aggsandbox registry set myReceiver 0x1234... -n 1`"
+    )]
+    Registry {
+        #[command(subcommand)]
+        subcommand: RegistryCommands,
+    },
+    /// 🔌 Send a raw JSON-RPC request to a sandbox network
+    #[command(
+        long_about = "Send an arbitrary JSON-RPC request to a network using the configured RPC endpoint.\n\nParameters are parsed as JSON when possible (false, 12, {\"to\":\"0x...\"}) and passed\nas strings otherwise, so block tags and hex quantities need no quoting. The result\nis pretty-printed.\n\nExamples:\n  `aggsandbox rpc --network-id 1 eth_getBlockByNumber latest false`\n  `aggsandbox rpc -n 0 eth_getBalance 0x123... latest`\n  `aggsandbox rpc -n 1 anvil_mine 0x10`"
+    )]
+    Rpc {
+        /// Network to send the request to
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID or alias (0/l1, 1/l2, 2/l3)")]
+        network_id: u64,
+        /// JSON-RPC method name
+        #[arg(help = "JSON-RPC method (e.g. eth_blockNumber)")]
+        method: String,
+        /// Method parameters
+        #[arg(
+            allow_hyphen_values = true,
+            help = "Method parameters (JSON values or plain strings)"
+        )]
+        params: Vec<String>,
+    },
+    /// 🐚 Interactive shell with history, context and completion
+    #[cfg(feature = "tui")]
+    #[command(
+        long_about = "Start an interactive shell for exploratory debugging sessions.\n\nCommands are typed without the `aggsandbox` prefix. The shell keeps a current\nnetwork and account, adds them as --network-id and --account to commands that\naccept them, and remembers them and the command history between sessions\n(in ~/.aggsandbox). Tab completes subcommands, flags and addresses of accounts\nand deployed contracts.\n\nBuilt-ins: network <id|alias|none>, account <name|none>, context, addresses, help, exit\n\nExample:\n  `aggsandbox shell`\n  aggsandbox> network l2\n  aggsandbox[n1]> show claims"
+    )]
+    Shell,
+    /// 🌐 Serve sandbox operations over a REST API
+    #[cfg(feature = "daemon")]
+    #[command(
+        long_about = "Run an HTTP server that exposes sandbox operations as REST endpoints.\n\nWeb UIs and CI pipelines written in other languages can start and stop the\nsandbox, bridge and claim, and query bridges, claims and events without\nwrapping the binary. Request bodies use the command's long flags in snake case,\ne.g. POST /bridge/claim with {\"network_id\": 1, \"tx_hash\": \"0x...\", \"source_network_id\": 0}.\n\nEndpoints: GET /health, GET /status, POST /start, POST /stop,\nPOST /bridge/<asset|claim|claim-all|message|bridge-message|bridge-and-call>,\nGET /bridges, GET /claims, GET /events\n\nExamples:\n  `aggsandbox serve`\n  `aggsandbox serve --host 0.0.0.0 --port 8090`\n  `curl -X POST localhost:5590/bridge/asset -d '{\"network_id\":0,\"destination_network_id\":1,\"amount\":\"1000\",\"token_address\":\"0x0000000000000000000000000000000000000000\"}' -H 'content-type: application/json'`"
+    )]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1", help = "Address to listen on")]
+        host: String,
+        /// Port to listen on
+        #[arg(short, long, default_value = "5590", help = "Port to listen on")]
+        port: u16,
+    },
+    /// 📡 Fetch and display blockchain events
+    #[command(
+        long_about = "Monitor blockchain events from L1 and L2 chains.\n\nFetch and display recent events from specified blockchain,\nwith options to filter by contract address and block range.\n\nExamples:\n  `aggsandbox events --network-id 0`                # Recent L1 events\n  `aggsandbox events --network-id 1 --blocks 20`    # Last 20 blocks from first L2\n  `aggsandbox events --network-id 0 --address 0x123` # Events from specific contract\n  `aggsandbox events --network-id 1 --follow`       # Stream new events until Ctrl-C\n  `aggsandbox events --network-id 0 --format json | jq .decoded` # NDJSON output\n  `aggsandbox events --network-id 0 --no-proxy-resolve` # Skip proxy implementation lookups\n  `aggsandbox events --network-id all --interleave` # Every network on one timeline\n  `aggsandbox events --network-id 0 --from-block 100 --to-block 250` # Explicit block range\n\nLegacy (deprecated) examples:\n  `aggsandbox events --chain anvil-l1`              # Use --network-id 0 instead"
+    )]
+    Events {
+        /// Network IDs to fetch events from (preferred over --chain)
+        #[arg(short = 'n', long = "network-id", alias = "network", value_parser = parse_network_selection, value_delimiter = ',', help = "Network ID or alias to query (0/l1, 1/l2, 2/l3), several separated by commas, or all")]
+        network_ids: Vec<u64>,
+        /// Blockchain to fetch events from (deprecated, use --network-id instead)
+        #[arg(short, long, value_parser = ["anvil-l1", "anvil-l2", "anvil-l3"], help = "Chain to query (anvil-l1, anvil-l2, or anvil-l3) - DEPRECATED: use --network-id")]
+        chain: Option<String>,
+        /// Number of recent blocks to scan for events
+        #[arg(
+            short,
+            long,
+            default_value = "10",
+            help = "Number of recent blocks to scan (default: 10)"
+        )]
+        blocks: u64,
+        /// First block to scan, replacing the --blocks window
+        #[arg(
+            long,
+            help = "First block to scan: a block number, earliest or latest (replaces --blocks)"
+        )]
+        from_block: Option<events::BlockBound>,
+        /// Last block to scan
+        #[arg(
+            long,
+            conflicts_with = "follow",
+            help = "Last block to scan: a block number, earliest or latest (default: latest)"
+        )]
+        to_block: Option<events::BlockBound>,
+        /// Filter events by contract address
+        #[arg(short = 'a', long, help = "Contract address to filter events (0x...)")]
+        address: Option<String>,
+        /// Event output format
+        #[arg(
+            long,
+            value_enum,
+            default_value = "text",
+            help = "Event output format: text, or json for one JSON object per event (NDJSON)"
+        )]
+        format: events::EventFormat,
+        /// Keep streaming new events until interrupted with Ctrl-C
+        #[arg(
+            short,
+            long,
+            alias = "watch",
+            help = "Stream new events as they are emitted (Ctrl-C to stop)"
+        )]
+        follow: bool,
+        /// Polling interval in seconds when following
+        #[arg(
+            long,
+            default_value = "2",
+            requires = "follow",
+            help = "Seconds between polls for new blocks with --follow"
+        )]
+        interval: u64,
+        /// Merge the events of several networks into one timeline
+        #[arg(
+            long,
+            conflicts_with = "follow",
+            help = "With several networks, order all events by block timestamp instead of grouping them by network"
+        )]
+        interleave: bool,
+        /// Skip resolving EIP-1967 proxies to their implementation ABI
+        #[arg(
+            long,
+            help = "Decode with built-in event definitions only, without reading proxy implementations"
+        )]
+        no_proxy_resolve: bool,
+    },
+}
+
+/// Subcommands of the command line, separated by spaces
+pub fn command_path(matches: &clap::ArgMatches) -> String {
+    let mut path = Vec::new();
+    let mut current = matches;
+    while let Some((name, subcommand)) = current.subcommand() {
+        path.push(name);
+        current = subcommand;
+    }
+    path.join(" ")
+}
+
+/// Working directory the CLI was invoked from, before `run` enters the project root
+static INVOCATION_DIR: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+
+/// Resolve the file arguments of a command against the invocation directory
+///
+/// Commands run in the project root, so a relative path given on the command
+/// line would otherwise be read or written there instead of where the user
+/// typed it.
+fn resolve_user_paths(command: &mut Commands, base: &Path) {
+    let resolve = |path: &mut PathBuf| {
+        if path.is_relative() {
+            *path = base.join(&*path);
+        }
+    };
+    match command {
+        Commands::Report {
+            output_file: Some(path),
+            ..
+        } => resolve(path),
+        Commands::Bridge { subcommand, .. } => match subcommand {
+            commands::BridgeCommands::Claim {
+                from_payload: Some(path),
+                ..
+            } => resolve(path),
+            commands::BridgeCommands::ClaimAll {
+                checkpoint, resume, ..
+            } => checkpoint
+                .iter_mut()
+                .chain(resume.iter_mut())
+                .for_each(resolve),
+            commands::BridgeCommands::Utils(
+                commands::bridge::utilities::UtilityCommands::BuildPayload {
+                    export: Some(path),
+                    ..
+                },
+            ) => resolve(path),
+            _ => {}
+        },
+        Commands::Config { subcommand } => match subcommand {
+            commands::ConfigCommands::Validate { path: Some(path) }
+            | commands::ConfigCommands::Init { to: Some(path), .. }
+            | commands::ConfigCommands::Set {
+                file: Some(path), ..
+            } => resolve(path),
+            commands::ConfigCommands::Import {
+                from, source, to, ..
+            } => {
+                to.iter_mut().for_each(resolve);
+                if matches!(from, commands::config::ImportSource::Json) {
+                    let mut path = PathBuf::from(&*source);
+                    resolve(&mut path);
+                    *source = path.display().to_string();
+                }
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn run(cli: Cli) -> Result<()> {
+    info!("Starting AggSandbox CLI v0.1.0");
+
+    if let Ok(dir) = std::env::current_dir() {
+        let _ = INVOCATION_DIR.set(dir);
+    }
+    // Relative paths (compose files, .env, config and state files) resolve
+    // against the project root, wherever the CLI is invoked from
+    if let Some(root) = project::locate(cli.project_dir.as_deref())? {
+        std::env::set_current_dir(&root).map_err(|e| {
+            error::AggSandboxError::Config(error::ConfigError::validation_failed(&format!(
+                "Failed to enter project directory {}: {e}",
+                root.display()
+            )))
+        })?;
+        info!(project_dir = %root.display(), "Using project directory");
+    }
+
+    // Ensure we're in the right directory (check for appropriate compose file based on command)
+    let needs_multi_l2 = match &cli.command {
+        #[cfg(feature = "docker")]
+        Commands::Start {
+            multi_l2, scale, ..
+        } => *multi_l2 || *scale == Some(2),
+        _ => false,
+    };
+
+    let compose_file = if needs_multi_l2 {
+        "docker-compose.multi-l2.yml"
+    } else {
+        "docker-compose.yml"
+    };
+
+    // Only the lifecycle commands drive Docker Compose; the others talk to the
+    // RPC endpoints and bridge service, which may belong to a remote sandbox.
+    // An imported config file points the CLI at a non-compose stack.
+    #[cfg(feature = "docker")]
+    let lifecycle_command = matches!(
+        cli.command,
+        Commands::Start { .. }
+            | Commands::Stop { .. }
+            | Commands::Status { .. }
+            | Commands::Logs { .. }
+            | Commands::Restart
+    );
+    #[cfg(not(feature = "docker"))]
+    let lifecycle_command = false;
+    #[cfg(all(feature = "docker", feature = "tui"))]
+    let lifecycle_command = lifecycle_command || matches!(cli.command, Commands::Dashboard { .. });
+    let needs_compose = lifecycle_command && config::Config::config_file().is_none();
+    if needs_compose && !Path::new(compose_file).exists() {
+        error!(
+            "{} not found in the working directory or its parents",
+            compose_file
+        );
+        warn!(
+            "Run this command inside the sandbox project, pass --project-dir or set {}",
+            project::HOME_ENV
+        );
+        return Err(error::AggSandboxError::Config(
+            error::ConfigError::missing_required(&format!(
+                "{compose_file} file in the project directory"
+            )),
+        ));
+    }
+
+    if needs_compose {
+        info!("Found {} in project directory", compose_file);
+    }
+
+    // Load environment variables from .env file if it exists
+    if Path::new(".env").exists() {
+        info!("Loading environment variables from .env file");
+        dotenv::dotenv().ok();
+    } else {
+        info!("No .env file found, using system environment variables");
+    }
+
+    // Endpoint flags are applied as the environment variables they stand for,
+    // which take precedence over .env and the config file
+    for (network_id, url) in &cli.rpc_url {
+        let var = format!("RPC_{}", network_id + 1);
+        info!(network = network_id, url = %url, "Overriding {var}");
+        std::env::set_var(var, url.as_str());
+    }
+    if let Some(url) = &cli.api_url {
+        info!(url = %url, "Overriding API_BASE_URL");
+        std::env::set_var("API_BASE_URL", url.as_str());
+    }
+
+    if cli.offline {
+        info!("Offline mode enabled, verifying all endpoints are local");
+        #[cfg(feature = "docker")]
+        if matches!(cli.command, Commands::Start { fork: true, .. }) {
+            return Err(error::AggSandboxError::Config(
+                error::ConfigError::validation_failed(
+                    "Fork mode requires public RPC access and cannot be used with --offline",
+                ),
+            ));
+        }
+        config::Config::load()?.validate_offline()?;
+        if let Commands::Monitor {
+            subcommand:
+                MonitorCommands::Slo {
+                    webhook_url: Some(url),
+                    ..
+                },
+        } = &cli.command
+        {
+            validation::Validator::validate_local_url(url, "--webhook-url")?;
+        }
+    }
+
+    if cli.dry_run {
+        info!("Dry-run mode enabled, bridge transactions are only simulated");
+    }
+    commands::bridge::common::set_dry_run(cli.dry_run);
+
+    let api_client = api_client::OptimizedApiClient::global();
+    if cli.no_cache {
+        info!("Response cache disabled, every bridge service request is sent");
+        api_client.disable_cache();
+    } else {
+        api_client.persist_to(api_client::CACHE_FILE);
+    }
+
+    let result = match cli.command {
+        #[cfg(feature = "tui")]
+        Commands::Shell => commands::run_shell(cli.output, cli.offline).await,
+        #[cfg(feature = "daemon")]
+        Commands::Serve { host, port } => {
+            info!(host = %host, port = port, "Executing serve command");
+            commands::handle_serve(&host, port, cli.output, cli.offline).await
+        }
+        #[cfg(all(feature = "docker", feature = "tui"))]
+        Commands::Dashboard { interval, blocks } => {
+            info!(
+                interval = interval,
+                blocks = blocks,
+                "Executing dashboard command"
+            );
+            commands::handle_dashboard(interval, blocks).await
+        }
+        command => execute(command, cli.output, cli.offline).await,
+    };
+
+    match &result {
+        Ok(_) => info!("Command completed successfully"),
+        Err(e) => error!(error = %e, "Command failed"),
+    }
+
+    result
+}
+
+/// Execute a parsed command, shared by the command line and `aggsandbox shell`
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+#[cfg_attr(not(feature = "docker"), allow(unused_variables))] // `offline` only guards docker commands
+pub async fn execute(mut command: Commands, output: OutputFormat, offline: bool) -> Result<()> {
+    if let Some(dir) = INVOCATION_DIR.get() {
+        resolve_user_paths(&mut command, dir);
+    }
+    match command {
+        #[cfg(feature = "docker")]
+        Commands::Start {
+            detach,
+            build,
+            fork,
+            multi_l2,
+            scale,
+            claim_all,
+            wait_timeout,
+        } => {
+            let l2_count = scale.unwrap_or(if multi_l2 { 2 } else { 1 });
+            info!(
+                detach = detach,
+                build = build,
+                fork = fork,
+                l2_count = l2_count,
+                claim_all = claim_all,
+                "Executing start command"
+            );
+            commands::handle_start(
+                detach,
+                build,
+                fork,
+                l2_count,
+                claim_all,
+                offline,
+                wait_timeout,
+            )
+            .await;
+            Ok(())
+        }
+        #[cfg(feature = "docker")]
+        Commands::Stop { volumes } => {
+            info!(remove_volumes = volumes, "Executing stop command");
+            commands::handle_stop(volumes);
+            Ok(())
+        }
+        #[cfg(feature = "docker")]
+        Commands::Status { json } => {
+            info!("Executing status command");
+            let output = if json { OutputFormat::Json } else { output };
+            commands::handle_status(output).await;
+            Ok(())
+        }
+        Commands::Doctor => {
+            info!("Executing doctor command");
+            commands::handle_doctor(output).await
+        }
+        #[cfg(feature = "docker")]
+        Commands::Logs {
+            follow,
+            services,
+            grep,
+            since,
+            level,
+        } => {
+            info!(follow = follow, services = ?services, "Executing logs command");
+            commands::handle_logs(commands::LogOptions {
+                follow,
+                services,
+                grep,
+                since,
+                level,
+            })
+        }
+        Commands::Report {
+            output_file,
+            log_lines,
+            blocks,
+        } => {
+            info!(
+                log_lines = log_lines,
+                blocks = blocks,
+                "Executing report command"
+            );
+            commands::handle_report(output_file, log_lines, blocks, output).await
+        }
+        #[cfg(feature = "docker")]
+        Commands::Restart => {
+            info!("Executing restart command");
+            commands::handle_restart(offline).await;
+            Ok(())
+        }
+        #[cfg(feature = "docker")]
+        Commands::Upgrade { check, cli } => {
+            info!(check = check, cli = cli, "Executing upgrade command");
+            commands::handle_upgrade(check, cli, offline, output).await
+        }
+        #[cfg(feature = "docker")]
+        Commands::Info => {
+            info!("Executing info command");
+            commands::handle_info(output).await
+        }
+        Commands::Show { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing show command");
+            commands::handle_show(subcommand, output).await
+        }
+        Commands::Assert { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing assert command");
+            commands::handle_assert(subcommand, output).await
+        }
+        #[cfg(feature = "metrics")]
+        Commands::Monitor { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing monitor command");
+            commands::handle_monitor(subcommand, output).await
+        }
+        Commands::Test { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing test command");
+            commands::handle_test(subcommand, output).await
+        }
+        Commands::Bridge {
+            subcommand,
+            account,
+            signer,
+            hd_path,
+            wait,
+            no_wait,
+            idempotency_key,
+        } => {
+            info!(subcommand = ?subcommand, account = ?account, signer = ?signer, "Executing bridge command");
+            commands::bridge::receipt::set_wait(wait || !no_wait);
+            commands::handle_bridge(
+                subcommand,
+                account.as_deref(),
+                signer,
+                hd_path,
+                idempotency_key.as_deref(),
+                output,
+            )
+            .await
+        }
+        Commands::Fund {
+            network_id,
+            address,
+            eth,
+            token,
+            amount,
+        } => {
+            info!(network_id = network_id, address = %address, eth = ?eth, token = ?token, "Executing fund command");
+            let options = commands::FundOptions {
+                network_id,
+                address,
+                eth,
+                token,
+                amount,
+            };
+            commands::handle_fund(options, output).await
+        }
+        Commands::Token { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing token command");
+            commands::handle_token(subcommand, output).await
+        }
+        Commands::Chain { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing chain command");
+            commands::handle_chain(subcommand, output).await
+        }
+        Commands::Tx {
+            subcommand,
+            account,
+        } => {
+            info!(subcommand = ?subcommand, account = ?account, "Executing tx command");
+            commands::handle_tx(subcommand, account.as_deref(), output).await
+        }
+        Commands::Config { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing config command");
+            commands::handle_config(subcommand, output).await
+        }
+        Commands::Contracts { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing contracts command");
+            commands::handle_contracts(subcommand, output).await
+        }
+        Commands::Snapshot { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing snapshot command");
+            commands::handle_snapshot(subcommand, output).await
+        }
+        Commands::Index { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing index command");
+            commands::handle_index(subcommand, output).await
+        }
+        Commands::State { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing state command");
+            commands::handle_state(subcommand, output).await
+        }
+        Commands::Cache { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing cache command");
+            commands::handle_cache(subcommand, output).await
+        }
+        Commands::Registry { subcommand } => {
+            info!(subcommand = ?subcommand, "Executing registry command");
+            commands::handle_registry(subcommand, output).await
+        }
+        Commands::Rpc {
+            network_id,
+            method,
+            params,
+        } => {
+            info!(network_id = network_id, method = %method, "Executing rpc command");
+            commands::handle_rpc(network_id, method, params, output).await
+        }
+        Commands::Events {
+            network_ids,
+            chain,
+            blocks,
+            from_block,
+            to_block,
+            address,
+            format,
+            follow,
+            interval,
+            interleave,
+            no_proxy_resolve,
+        } => {
+            info!(network_ids = ?network_ids, chain = ?chain, blocks = blocks, from_block = ?from_block, to_block = ?to_block, address = ?address, follow = follow, interleave = interleave, "Executing events command");
+            commands::handle_events(
+                network_ids,
+                chain,
+                blocks,
+                from_block,
+                to_block,
+                address,
+                format,
+                follow,
+                interval,
+                interleave,
+                !no_proxy_resolve,
+                output,
+            )
+            .await
+        }
+        #[cfg(feature = "tui")]
+        Commands::Shell => Err(error::AggSandboxError::Config(
+            error::ConfigError::validation_failed("Already running inside `aggsandbox shell`"),
+        )),
+        #[cfg(feature = "daemon")]
+        Commands::Serve { .. } => Err(error::AggSandboxError::Config(
+            error::ConfigError::validation_failed(
+                "Run `aggsandbox serve` directly, not from the shell or the server",
+            ),
+        )),
+        #[cfg(all(feature = "docker", feature = "tui"))]
+        Commands::Dashboard { .. } => Err(error::AggSandboxError::Config(
+            error::ConfigError::validation_failed(
+                "Run `aggsandbox dashboard` directly, not from the shell or the server",
+            ),
+        )),
+    }
+}
+
+/// Initialize logging based on CLI configuration
+#[allow(clippy::disallowed_methods)] // Allow for error propagation and print functions
+pub fn initialize_logging(cli: &Cli) -> Result<()> {
+    let level = logging::level_from_verbosity(cli.verbose, cli.quiet);
+    let format = logging::format_from_str(&cli.log_format).map_err(|e| {
+        error::AggSandboxError::Config(error::ConfigError::invalid_value(
+            "log_format",
+            &cli.log_format,
+            &e,
+        ))
+    })?;
+
+    let config = LogConfig {
+        level,
+        format,
+        include_location: cli.verbose > 0,
+        include_target: cli.verbose > 1,
+        include_spans: cli.verbose > 1,
+        otlp_endpoint: cli
+            .otlp_endpoint
+            .clone()
+            .or_else(|| std::env::var(telemetry::OTLP_ENDPOINT_ENV).ok())
+            .filter(|endpoint| !endpoint.is_empty()),
+    };
+    // Traces would leave the machine as surely as any other request
+    if let (true, Some(endpoint)) = (cli.offline, &config.otlp_endpoint) {
+        validation::Validator::validate_local_url(endpoint, "--otlp-endpoint")?;
+    }
+
+    logging::init_logging(&config).map_err(|e| {
+        error::AggSandboxError::Config(error::ConfigError::validation_failed(&format!(
+            "logging initialization: {e}"
+        )))
+    })?;
+
+    Ok(())
+}
+
+/// Handle `--schema` and `--exit-code-map`, returning `None` when neither is set
+///
+/// The arguments are parsed leniently, so both flags work without the
+/// command's required arguments.
+pub fn handle_report_flags<I, T>(args: I) -> Option<error::Result<()>>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<std::ffi::OsString> + Clone,
+{
+    let matches = Cli::command()
+        .ignore_errors(true)
+        .try_get_matches_from(args)
+        .ok()?;
+    // Global flags are propagated down to the innermost subcommand
+    let mut innermost = &matches;
+    while let Some((_, subcommand)) = innermost.subcommand() {
+        innermost = subcommand;
+    }
+    if innermost.get_flag("exit_code_map") {
+        println!("{}", error::exit_code::render_table());
+        return Some(Ok(()));
+    }
+    if innermost.get_flag("schema") {
+        return Some(print_schema(&command_path(&matches)));
+    }
+    None
+}
+
+/// Print the JSON Schema of a command, named like `bridge claim`
+fn print_schema(name: &str) -> error::Result<()> {
+    match schema::schema_for(name) {
+        Some(schema) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&schema).unwrap_or_default()
+            );
+            Ok(())
+        }
+        None => Err(error::AggSandboxError::Other(format!(
+            "No JSON output schema for '{}'. Commands with a schema:\n  {}",
+            if name.is_empty() { "aggsandbox" } else { name },
+            schema::commands().collect::<Vec<_>>().join("\n  ")
+        ))),
+    }
+}
+
+/// Print user-friendly error messages with enhanced suggestions
+pub fn print_error(error: &error::AggSandboxError) {
+    eprintln!("\n{} {error}", "❌ Error:".red().bold());
+
+    // Provide additional context and suggestions based on error type
+    match error {
+        error::AggSandboxError::Config(config_err) => {
+            eprintln!("\n{}", "🔧 Configuration Issue".yellow().bold());
+            match config_err {
+                error::ConfigError::EnvVarNotFound(var) => {
+                    eprintln!("{}", "💡 Quick Fix:".blue().bold());
+                    eprintln!("   1. Create or edit your .env file:");
+                    eprintln!(
+                        "      {}",
+                        format!("echo '{var}=your_value' >> .env").cyan()
+                    );
+                    eprintln!("   2. Or set it temporarily:");
+                    eprintln!("      {}", format!("export {var}=your_value").cyan());
+                    eprintln!("\n{}", "📚 Learn more:".dimmed());
+                    eprintln!("   Check the README for required environment variables");
+                }
+                error::ConfigError::InvalidValue { key, .. } => {
+                    eprintln!("{}", "💡 Quick Fix:".blue().bold());
+                    eprintln!("   1. Check the value for '{}'", key.cyan());
+                    eprintln!("   2. Refer to configuration documentation");
+                    eprintln!("   3. Use `aggsandbox info` to see current config");
+                }
+                error::ConfigError::MissingRequired(item) => {
+                    eprintln!("{}", "💡 Quick Fix:".blue().bold());
+                    eprintln!("   The following is required: {}", item.cyan());
+                    eprintln!("   Make sure you're in the correct directory and all files exist");
+                }
+                _ => {
+                    eprintln!("{}", "💡 Suggestion:".blue().bold());
+                    eprintln!("   Check your configuration files and environment variables");
+                }
+            }
+        }
+        error::AggSandboxError::Docker(docker_err) => {
+            eprintln!("\n{}", "🐳 Docker Issue".yellow().bold());
+            match docker_err {
+                error::DockerError::ComposeFileNotFound(_) => {
+                    eprintln!("{}", "💡 Quick Fix:".blue().bold());
+                    eprintln!("   1. Navigate to the project root directory:");
+                    eprintln!("      {}", "cd /path/to/aggsandbox".cyan());
+                    eprintln!("   2. Verify docker-compose.yml exists:");
+                    eprintln!("      {}", "ls docker-compose.yml".cyan());
+                    eprintln!("\n{}", "🎯 Current directory should contain:".dimmed());
+                    eprintln!("   • docker-compose.yml");
+                    eprintln!("   • .env (optional)");
+                    eprintln!("   • contracts/ directory");
+                }
+                error::DockerError::CommandFailed { .. } => {
+                    eprintln!("{}", "💡 Troubleshooting Steps:".blue().bold());
+                    eprintln!("   1. Check Docker, Compose and port conflicts:");
+                    eprintln!("      {}", "aggsandbox doctor".cyan());
+                    eprintln!("   2. Stop any existing containers:");
+                    eprintln!("      {}", "aggsandbox stop".cyan());
+                }
+                _ => {
+                    eprintln!("{}", "💡 General Docker Help:".blue().bold());
+                    eprintln!("   • Ensure Docker Desktop is running");
+                    eprintln!("   • Check Docker has sufficient resources");
+                    eprintln!("   • Try: docker system prune (removes unused data)");
+                }
+            }
+        }
+        error::AggSandboxError::Api(api_err) => {
+            eprintln!("\n{}", "🌐 API Connection Issue".yellow().bold());
+            match api_err {
+                error::ApiError::NetworkError(_) => {
+                    eprintln!("{}", "💡 Troubleshooting Steps:".blue().bold());
+                    eprintln!("   1. Check RPC and bridge service connectivity:");
+                    eprintln!("      {}", "aggsandbox doctor".cyan());
+                    eprintln!("   2. Start if not running:");
+                    eprintln!("      {}", "aggsandbox start --detach".cyan());
+                    eprintln!("\n{}", "⏱️  Services need time to start up".dimmed());
+                }
+                error::ApiError::EndpointUnavailable(_) => {
+                    eprintln!("{}", "💡 Wait and Retry:".blue().bold());
+                    eprintln!("   • API service is starting up");
+                    eprintln!("   • Wait 30-60 seconds and try again");
+                    eprintln!("   • Check service health:");
+                    eprintln!("     {}", "aggsandbox logs aggkit".cyan());
+                }
+                error::ApiError::RequestFailed { status, .. } => {
+                    eprintln!("{}", "💡 HTTP Error Help:".blue().bold());
+                    match *status {
+                        404 => eprintln!("   • Endpoint not found - check API version"),
+                        500 => eprintln!("   • Server error - check service logs"),
+                        503 => eprintln!("   • Service unavailable - wait and retry"),
+                        _ => eprintln!("   • Check service logs for details"),
+                    }
+                    eprintln!("     {}", "aggsandbox logs".cyan());
+                }
+                _ => {
+                    eprintln!("{}", "💡 General API Help:".blue().bold());
+                    eprintln!("   • Verify all services are running");
+                    eprintln!("   • Check network connectivity");
+                    eprintln!("   • Review service logs for errors");
+                }
+            }
+        }
+        error::AggSandboxError::Events(event_err) => {
+            eprintln!("\n{}", "📡 Blockchain Events Issue".yellow().bold());
+            match event_err {
+                error::EventError::InvalidChain(_chain) => {
+                    eprintln!("{}", "💡 Valid Chains:".blue().bold());
+                    eprintln!("   • {} - Ethereum L1 chain", "anvil-l1".green());
+                    eprintln!("   • {} - Polygon zkEVM L2 chain", "anvil-l2".green());
+                    eprintln!(
+                        "   • {} - Additional L2 chain (if enabled)",
+                        "anvil-l3".green()
+                    );
+                    eprintln!("\n{}", "📝 Example usage:".dimmed());
+                    eprintln!(
+                        "   {}",
+                        "aggsandbox events --chain anvil-l1 --blocks 5".cyan()
+                    );
+                    eprintln!(
+                        "   {}",
+                        "aggsandbox events --chain anvil-l2 --blocks 10".cyan()
+                    );
+                }
+                error::EventError::RpcConnectionFailed(_) => {
+                    eprintln!("{}", "💡 RPC Connection Fix:".blue().bold());
+                    eprintln!("   1. Ensure sandbox is running:");
+                    eprintln!("      {}", "aggsandbox status".cyan());
+                    eprintln!("   2. Check if chain is available:");
+                    eprintln!("      {}", "aggsandbox info".cyan());
+                    eprintln!("   3. Verify RPC endpoints are responding");
+                    eprintln!("\n{}", "🔍 Multi-L2 mode:".dimmed());
+                    eprintln!("   anvil-l3 is only available with --multi-l2 flag");
+                }
+                error::EventError::ChainHalted(_) => {
+                    eprintln!("{}", "💡 Chain Halt Fix:".blue().bold());
+                    eprintln!("   1. Check whether the node container crashed:");
+                    eprintln!("      {}", "aggsandbox status".cyan());
+                    eprintln!("   2. Read the node logs named in the error, e.g.:");
+                    eprintln!("      {}", "aggsandbox logs anvil-l2".cyan());
+                    eprintln!("   3. With automine off, mine the pending transactions:");
+                    eprintln!("      {}", "aggsandbox chain mine -n <ID>".cyan());
+                }
+                _ => {
+                    eprintln!("{}", "💡 Events Troubleshooting:".blue().bold());
+                    eprintln!("   • Check if the specified chain is running");
+                    eprintln!("   • Verify block range is valid");
+                    eprintln!("   • Ensure contract address format is correct");
+                }
+            }
+        }
+        _ => {
+            eprintln!("\n{}", "🆘 General Help".yellow().bold());
+            eprintln!("{}", "💡 Common Solutions:".blue().bold());
+            eprintln!("   • Check if you're in the project root directory");
+            eprintln!("   • Ensure Docker Desktop is running");
+            eprintln!("   • Try restarting the sandbox:");
+            eprintln!("     {}", "aggsandbox restart".cyan());
+            eprintln!("   • Check the documentation or README");
+        }
+    }
+
+    eprintln!("\n{}", "🔗 Need more help?".bright_blue().bold());
+    eprintln!(
+        "   • Run {} to check the environment",
+        "aggsandbox doctor".cyan()
+    );
+    eprintln!(
+        "   • Run {} for detailed information",
+        "aggsandbox --help".cyan()
+    );
+    eprintln!(
+        "   • Use {} for command-specific help",
+        "aggsandbox <command> --help".cyan()
+    );
+    eprintln!("   • Check logs with {}", "aggsandbox logs".cyan());
+    eprintln!();
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_user_paths() {
+        let base = Path::new("/work");
+        let parse = |args: &[&str]| {
+            let mut cli =
+                Cli::try_parse_from(std::iter::once("aggsandbox").chain(args.iter().copied()))
+                    .unwrap();
+            resolve_user_paths(&mut cli.command, base);
+            cli.command
+        };
+
+        let Commands::Bridge {
+            subcommand: commands::BridgeCommands::Claim { from_payload, .. },
+            ..
+        } = parse(&["bridge", "claim", "--from-payload", "claim.json"])
+        else {
+            panic!("expected bridge claim");
+        };
+        assert_eq!(from_payload, Some(PathBuf::from("/work/claim.json")));
+
+        let Commands::Config {
+            subcommand: commands::ConfigCommands::Init { to, .. },
+        } = parse(&["config", "init", "--to", "/etc/aggsandbox.toml"])
+        else {
+            panic!("expected config init");
+        };
+        assert_eq!(to, Some(PathBuf::from("/etc/aggsandbox.toml")));
+
+        // Without a path the default stays relative to the project directory
+        let Commands::Config {
+            subcommand: commands::ConfigCommands::Init { to, .. },
+        } = parse(&["config", "init"])
+        else {
+            panic!("expected config init");
+        };
+        assert_eq!(to, None);
+    }
+}
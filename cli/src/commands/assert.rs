@@ -0,0 +1,301 @@
+use crate::commands::bridge::common::{get_network_name, validate_address, validate_network_id};
+use crate::commands::bridge::utilities::decode_global_index;
+use crate::commands::bridge::{
+    get_bridge_contract_address, get_provider, BridgeContract, ERC20Contract,
+};
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui::{OutputFormat, UI};
+use crate::validation::{parse_network_id, Validator};
+use ethers::prelude::*;
+use serde::Serialize;
+
+/// Assertion subcommands for shell-based test scripts
+#[derive(Debug, clap::Subcommand)]
+pub enum AssertCommands {
+    /// 💰 Assert the ETH or ERC20 balance of an address
+    #[command(
+        long_about = "Compare the balance of an address against an expected value.
+
+Exactly one comparison must be given. Values are raw amounts (wei or the token's
+smallest unit) in decimal or 0x-prefixed hex. Without --token the native ETH
+balance is checked.
+
+Exits with a non-zero status and prints the expected and actual values when the
+assertion does not hold.
+
+Examples:
+  aggsandbox assert balance --network-id 1 --address 0x123... --gte 1000000000000000000
+  aggsandbox assert balance -n 1 --address 0x123... --token 0x456... --eq 100",
+        group(clap::ArgGroup::new("comparison").required(true).args(["eq", "gte", "gt", "lte", "lt"]))
+    )]
+    Balance {
+        /// Network to query
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID")]
+        network_id: u64,
+        /// Account whose balance is checked
+        #[arg(long, help = "Address whose balance is checked")]
+        address: String,
+        /// ERC20 token contract (omit for native ETH)
+        #[arg(long, help = "ERC20 token address (default: native ETH)")]
+        token: Option<String>,
+        #[arg(long, help = "Balance must equal this value")]
+        eq: Option<String>,
+        #[arg(long, help = "Balance must be greater than or equal to this value")]
+        gte: Option<String>,
+        #[arg(long, help = "Balance must be greater than this value")]
+        gt: Option<String>,
+        #[arg(long, help = "Balance must be less than or equal to this value")]
+        lte: Option<String>,
+        #[arg(long, help = "Balance must be less than this value")]
+        lt: Option<String>,
+    },
+    /// ✅ Assert that a deposit has been claimed
+    #[command(
+        long_about = "Check the bridge contract on the destination network for a claimed deposit.
+
+The global index identifies the deposit (see `aggsandbox bridge utils compute-index`).
+Use --unclaimed to assert the opposite.
+
+Examples:
+  aggsandbox assert claimed --network-id 1 --global-index 18446744073709551616
+  aggsandbox assert claimed -n 0 --global-index 4294967301 --unclaimed"
+    )]
+    Claimed {
+        /// Network the deposit is claimed on
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Destination network ID")]
+        network_id: u64,
+        /// Global index of the deposit
+        #[arg(long, help = "Global index of the deposit (decimal or 0x hex)")]
+        global_index: String,
+        /// Assert the deposit has NOT been claimed
+        #[arg(long, help = "Assert that the deposit is still unclaimed")]
+        unclaimed: bool,
+    },
+}
+
+/// Comparison operator used by balance assertions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Gte,
+    Gt,
+    Lte,
+    Lt,
+}
+
+impl Comparison {
+    /// Whether `actual <op> expected` holds
+    pub fn holds(self, actual: U256, expected: U256) -> bool {
+        match self {
+            Comparison::Eq => actual == expected,
+            Comparison::Gte => actual >= expected,
+            Comparison::Gt => actual > expected,
+            Comparison::Lte => actual <= expected,
+            Comparison::Lt => actual < expected,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Eq => "==",
+            Comparison::Gte => ">=",
+            Comparison::Gt => ">",
+            Comparison::Lte => "<=",
+            Comparison::Lt => "<",
+        }
+    }
+}
+
+/// Outcome of an assertion, printed in JSON output mode
+#[derive(Debug, Serialize)]
+struct AssertionResult {
+    assertion: &'static str,
+    passed: bool,
+    description: String,
+    expected: String,
+    actual: String,
+}
+
+/// Handle assert commands
+pub async fn handle_assert(subcommand: AssertCommands, output: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+
+    let result = match subcommand {
+        AssertCommands::Balance {
+            network_id,
+            address,
+            token,
+            eq,
+            gte,
+            gt,
+            lte,
+            lt,
+        } => {
+            let (comparison, expected) = [
+                (Comparison::Eq, eq),
+                (Comparison::Gte, gte),
+                (Comparison::Gt, gt),
+                (Comparison::Lte, lte),
+                (Comparison::Lt, lt),
+            ]
+            .into_iter()
+            .find_map(|(comparison, value)| value.map(|value| (comparison, value)))
+            .ok_or_else(|| {
+                assertion_error("A comparison (--eq, --gte, --gt, --lte, --lt) is required")
+            })?;
+            let expected = parse_amount(&expected)?;
+
+            let actual = get_balance(&config, network_id, &address, token.as_deref()).await?;
+            let asset = token.as_deref().unwrap_or("ETH");
+            AssertionResult {
+                assertion: "balance",
+                passed: comparison.holds(actual, expected),
+                description: format!(
+                    "{asset} balance of {address} on network {network_id} ({})",
+                    get_network_name(network_id)
+                ),
+                expected: format!("{} {expected}", comparison.symbol()),
+                actual: actual.to_string(),
+            }
+        }
+        AssertCommands::Claimed {
+            network_id,
+            global_index,
+            unclaimed,
+        } => {
+            validate_network_id(network_id, "Network")?;
+            let global_index = parse_amount(&global_index)?;
+            let (source_network, deposit_count) = decode_global_index(global_index);
+
+            let provider = get_provider(&config, network_id).await?;
+            let bridge =
+                BridgeContract::new(get_bridge_contract_address(&config, network_id)?, provider);
+            let claimed = bridge
+                .is_claimed(deposit_count, source_network as u32)
+                .call()
+                .await
+                .map_err(|e| assertion_error(&format!("Failed to check claim status: {e}")))?;
+
+            let expected_claimed = !unclaimed;
+            AssertionResult {
+                assertion: "claimed",
+                passed: claimed == expected_claimed,
+                description: format!(
+                    "claim status of deposit {deposit_count} from network {source_network} on network {network_id} ({})",
+                    get_network_name(network_id)
+                ),
+                expected: claim_status(expected_claimed).to_string(),
+                actual: claim_status(claimed).to_string(),
+            }
+        }
+    };
+
+    report(&result, output)
+}
+
+/// Print the assertion outcome and fail when it does not hold
+fn report(result: &AssertionResult, output: OutputFormat) -> Result<()> {
+    let ui = UI::new(output);
+    if output.is_json() {
+        ui.json(&serde_json::to_value(result).unwrap_or_default());
+    } else if result.passed {
+        ui.success(&format!(
+            "Assertion passed: {} is {}",
+            result.description, result.actual
+        ));
+    }
+
+    if result.passed {
+        Ok(())
+    } else {
+        Err(assertion_error(&format!(
+            "Assertion failed: {}\n  expected: {}\n  actual:   {}",
+            result.description, result.expected, result.actual
+        )))
+    }
+}
+
+/// Read the ETH or ERC20 balance of an address
+pub async fn get_balance(
+    config: &Config,
+    network_id: u64,
+    address: &str,
+    token: Option<&str>,
+) -> Result<U256> {
+    validate_network_id(network_id, "Network")?;
+    let account = validate_address(address, "Address")?;
+    let provider = get_provider(config, network_id).await?;
+
+    match token {
+        Some(token) => {
+            let token = validate_address(token, "Token address")?;
+            ERC20Contract::new(token, provider)
+                .balance_of(account)
+                .call()
+                .await
+                .map_err(|e| assertion_error(&format!("Failed to get token balance: {e}")))
+        }
+        None => provider
+            .get_balance(account, None)
+            .await
+            .map_err(|e| assertion_error(&format!("Failed to get balance: {e}"))),
+    }
+}
+
+/// Parse a raw amount given in decimal or 0x-prefixed hex
+fn parse_amount(value: &str) -> Result<U256> {
+    Validator::parse_uint(value).map_err(|_| assertion_error(&format!("Invalid amount '{value}'")))
+}
+
+fn claim_status(claimed: bool) -> &'static str {
+    if claimed {
+        "claimed"
+    } else {
+        "unclaimed"
+    }
+}
+
+fn assertion_error(message: &str) -> AggSandboxError {
+    AggSandboxError::Other(message.to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparisons() {
+        let hundred = U256::from(100);
+        assert!(Comparison::Eq.holds(hundred, hundred));
+        assert!(Comparison::Gte.holds(hundred, hundred));
+        assert!(!Comparison::Gt.holds(hundred, hundred));
+        assert!(Comparison::Lte.holds(U256::from(99), hundred));
+        assert!(!Comparison::Lt.holds(U256::from(101), hundred));
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("100").unwrap(), U256::from(100));
+        assert_eq!(parse_amount("0x64").unwrap(), U256::from(100));
+        assert!(parse_amount("1.5").is_err());
+    }
+
+    #[test]
+    fn test_failed_assertion_reports_diff() {
+        let result = AssertionResult {
+            assertion: "balance",
+            passed: false,
+            description: "ETH balance".to_string(),
+            expected: ">= 100".to_string(),
+            actual: "42".to_string(),
+        };
+
+        let message = report(&result, OutputFormat::Human)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("expected: >= 100"));
+        assert!(message.contains("actual:   42"));
+    }
+}
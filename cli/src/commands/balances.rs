@@ -0,0 +1,409 @@
+//! Balances of the test accounts across networks (`aggsandbox show balances`)
+//!
+//! Every sandbox token (AggERC20 and the tokens of `token deploy`) is shown as
+//! one column named after its symbol and origin network, e.g. `AGG@0`. On the
+//! other networks the column holds the balance of the wrapped token, resolved
+//! with `getTokenWrappedAddress`, so bridged tokens appear without configuring
+//! their L2 addresses.
+
+use ethers::prelude::*;
+use serde::Serialize;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::commands::bridge::common::{format_amount, get_network_name, validate_address};
+use crate::commands::bridge::faucet::{layer_for_network, resolve_sandbox_token};
+use crate::commands::bridge::token_deploy::TokenRegistry;
+use crate::commands::bridge::token_metadata::cached_token_metadata;
+use crate::commands::bridge::{
+    get_bridge_contract_address, get_provider, BridgeContract, ERC20Contract,
+};
+use crate::concurrency::join_all;
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+
+/// Sandbox token on the network it was deployed on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OriginToken {
+    pub symbol: String,
+    pub network_id: u64,
+    pub address: Address,
+    pub decimals: u8,
+}
+
+impl OriginToken {
+    /// Column name, e.g. `AGG@0`
+    pub fn label(&self) -> String {
+        format!("{}@{}", self.symbol, self.network_id)
+    }
+
+    /// Whether a `--token` filter selects this token
+    pub fn matches(&self, filter: &str) -> bool {
+        filter.eq_ignore_ascii_case(&self.symbol)
+            || filter.eq_ignore_ascii_case(&self.label())
+            || Address::from_str(filter).is_ok_and(|address| address == self.address)
+    }
+}
+
+/// Balance of one token of an account on one network
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenBalance {
+    pub token: String,
+    /// Token contract on this network, `None` when it was never bridged here
+    pub address: Option<Address>,
+    #[serde(serialize_with = "decimal")]
+    pub balance: Option<U256>,
+}
+
+/// Balances of one account on one network
+#[derive(Debug, Clone, Serialize)]
+pub struct BalanceRow {
+    pub account: Address,
+    pub network_id: u64,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "decimal")]
+    pub eth: Option<U256>,
+    pub tokens: Vec<TokenBalance>,
+}
+
+/// Which balances `show balances` reads
+#[derive(Debug, Clone)]
+pub struct BalanceQuery {
+    pub networks: Vec<u64>,
+    pub accounts: Vec<Address>,
+    pub include_eth: bool,
+    pub tokens: Vec<OriginToken>,
+}
+
+impl BalanceQuery {
+    /// Query for the given filters, defaulting to every test account and token
+    pub async fn new(
+        config: &Config,
+        networks: Vec<u64>,
+        addresses: &[String],
+        token_filters: &[String],
+    ) -> Result<Self> {
+        let accounts = if addresses.is_empty() {
+            config
+                .accounts
+                .accounts
+                .iter()
+                .map(|account| validate_address(account.as_str(), "Account"))
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            addresses
+                .iter()
+                .map(|address| validate_address(address, "Address"))
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let include_eth = token_filters.is_empty()
+            || token_filters
+                .iter()
+                .any(|filter| filter.eq_ignore_ascii_case("ETH"));
+        let mut tokens = sandbox_tokens(config).await;
+        if !token_filters.is_empty() {
+            tokens.retain(|token| token_filters.iter().any(|filter| token.matches(filter)));
+            let unknown: Vec<&String> = token_filters
+                .iter()
+                .filter(|filter| !filter.eq_ignore_ascii_case("ETH"))
+                .filter(|filter| !tokens.iter().any(|token| token.matches(filter)))
+                .collect();
+            if let Some(filter) = unknown.first() {
+                return Err(AggSandboxError::Other(format!(
+                    "Unknown token '{filter}', use ETH, a symbol such as AGG, a column such as AGG@0 or an origin token address"
+                )));
+            }
+        }
+
+        Ok(Self {
+            networks,
+            accounts,
+            include_eth,
+            tokens,
+        })
+    }
+
+    /// Read every balance, one row per account and network
+    pub async fn fetch(&self, config: &Config) -> Result<Vec<BalanceRow>> {
+        let mut rows = Vec::new();
+        for &network_id in &self.networks {
+            let addresses = self.token_addresses(config, network_id).await?;
+            let provider = get_provider(config, network_id).await?;
+            let network_rows = join_all(
+                self.accounts
+                    .iter()
+                    .map(|&account| {
+                        balance_row(
+                            &provider,
+                            network_id,
+                            account,
+                            self.include_eth,
+                            &self.tokens,
+                            &addresses,
+                        )
+                    })
+                    .collect(),
+            )
+            .await;
+            for row in network_rows {
+                rows.push(row?);
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Address of every token on a network, the wrapped token off its origin
+    async fn token_addresses(
+        &self,
+        config: &Config,
+        network_id: u64,
+    ) -> Result<Vec<Option<Address>>> {
+        let bridge = BridgeContract::new(
+            get_bridge_contract_address(config, network_id)?,
+            get_provider(config, network_id).await?,
+        );
+        let mut addresses = Vec::new();
+        for token in &self.tokens {
+            if token.network_id == network_id {
+                addresses.push(Some(token.address));
+                continue;
+            }
+            let wrapped = bridge
+                .get_token_wrapped_address(token.network_id as u32, token.address)
+                .call()
+                .await
+                .map_err(|e| {
+                    AggSandboxError::Other(format!(
+                        "Failed to resolve the wrapped {} on network {network_id}: {e}",
+                        token.label()
+                    ))
+                })?;
+            addresses.push((!wrapped.is_zero()).then_some(wrapped));
+        }
+        Ok(addresses)
+    }
+}
+
+async fn balance_row(
+    provider: &Arc<Provider<Http>>,
+    network_id: u64,
+    account: Address,
+    include_eth: bool,
+    tokens: &[OriginToken],
+    addresses: &[Option<Address>],
+) -> Result<BalanceRow> {
+    let eth = if include_eth {
+        Some(provider.get_balance(account, None).await.map_err(|e| {
+            AggSandboxError::Other(format!(
+                "Failed to get the ETH balance of {account:#x} on network {network_id}: {e}"
+            ))
+        })?)
+    } else {
+        None
+    };
+
+    let mut balances = Vec::new();
+    for (token, address) in tokens.iter().zip(addresses) {
+        let balance = match address {
+            Some(address) => Some(
+                ERC20Contract::new(*address, provider.clone())
+                    .balance_of(account)
+                    .call()
+                    .await
+                    .map_err(|e| {
+                        AggSandboxError::Other(format!(
+                            "Failed to get the {} balance of {account:#x} on network {network_id}: {e}",
+                            token.label()
+                        ))
+                    })?,
+            ),
+            None => None,
+        };
+        balances.push(TokenBalance {
+            token: token.label(),
+            address: *address,
+            balance,
+        });
+    }
+
+    Ok(BalanceRow {
+        account,
+        network_id,
+        eth,
+        tokens: balances,
+    })
+}
+
+/// AggERC20 of every network and the tokens recorded by `token deploy`
+async fn sandbox_tokens(config: &Config) -> Vec<OriginToken> {
+    let mut tokens = Vec::new();
+    for network_id in config.networks.network_ids() {
+        let Ok(layer) = layer_for_network(network_id) else {
+            continue;
+        };
+        if let Some(address) = resolve_sandbox_token(&config.contracts, &layer, "AGG") {
+            let decimals = cached_token_metadata(config, network_id, address)
+                .await
+                .ok()
+                .and_then(|metadata| metadata.decimals)
+                .unwrap_or(18);
+            tokens.push(OriginToken {
+                symbol: "AGG".to_string(),
+                network_id,
+                address,
+                decimals,
+            });
+        }
+    }
+    for deployed in TokenRegistry::default_location().list() {
+        tokens.push(OriginToken {
+            symbol: deployed.symbol,
+            network_id: deployed.network_id,
+            address: deployed.address,
+            decimals: deployed.decimals,
+        });
+    }
+    tokens
+}
+
+/// Raw amounts as decimal strings in JSON output, like the bridge service does
+fn decimal<S: serde::Serializer>(
+    value: &Option<U256>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serializer.serialize_str(&value.to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Table of the rows, one column per asset
+pub fn render_table(query: &BalanceQuery, rows: &[BalanceRow]) -> String {
+    let mut header = vec!["Account".to_string(), "Network".to_string()];
+    if query.include_eth {
+        header.push("ETH".to_string());
+    }
+    header.extend(query.tokens.iter().map(OriginToken::label));
+
+    let mut lines = vec![header];
+    for row in rows {
+        let mut line = vec![
+            format!("{:#x}", row.account),
+            format!("{} ({})", row.network_id, get_network_name(row.network_id)),
+        ];
+        if let Some(eth) = row.eth {
+            line.push(format_amount(eth, 18));
+        }
+        for (token, balance) in query.tokens.iter().zip(&row.tokens) {
+            line.push(balance.balance.map_or_else(
+                || "-".to_string(),
+                |balance| format_amount(balance, token.decimals),
+            ));
+        }
+        lines.push(line);
+    }
+
+    let widths: Vec<usize> = (0..lines[0].len())
+        .map(|column| {
+            lines
+                .iter()
+                .map(|line| line[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let separator = widths
+        .iter()
+        .map(|width| "─".repeat(width + 2))
+        .collect::<Vec<_>>();
+    let mut output = format!("┌{}┐\n", separator.join("┬"));
+    for (index, line) in lines.iter().enumerate() {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(column, (cell, width))| {
+                // Amounts are right-aligned so their digits line up
+                if column < 2 {
+                    format!(" {cell:<width$} ")
+                } else {
+                    format!(" {cell:>width$} ")
+                }
+            })
+            .collect();
+        output.push_str(&format!("│{}│\n", cells.join("│")));
+        if index == 0 {
+            output.push_str(&format!("├{}┤\n", separator.join("┼")));
+        }
+    }
+    output.push_str(&format!("└{}┘", separator.join("┴")));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agg() -> OriginToken {
+        OriginToken {
+            symbol: "AGG".to_string(),
+            network_id: 0,
+            address: Address::from_low_u64_be(0xa6),
+            decimals: 18,
+        }
+    }
+
+    #[test]
+    fn test_token_filter() {
+        let token = agg();
+        assert_eq!(token.label(), "AGG@0");
+        assert!(token.matches("agg"));
+        assert!(token.matches("AGG@0"));
+        assert!(token.matches(&format!("{:#x}", token.address)));
+        assert!(!token.matches("AGG@1"));
+        assert!(!token.matches("ETH"));
+    }
+
+    #[test]
+    fn test_render_table() {
+        let query = BalanceQuery {
+            networks: vec![0, 1],
+            accounts: vec![Address::zero()],
+            include_eth: true,
+            tokens: vec![agg()],
+        };
+        let rows = vec![
+            BalanceRow {
+                account: Address::zero(),
+                network_id: 0,
+                eth: Some(U256::exp10(18)),
+                tokens: vec![TokenBalance {
+                    token: "AGG@0".to_string(),
+                    address: Some(agg().address),
+                    balance: Some(U256::exp10(18) * 5),
+                }],
+            },
+            BalanceRow {
+                account: Address::zero(),
+                network_id: 1,
+                eth: Some(U256::zero()),
+                tokens: vec![TokenBalance {
+                    token: "AGG@0".to_string(),
+                    address: None,
+                    balance: None,
+                }],
+            },
+        ];
+
+        let table = render_table(&query, &rows);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[1].contains("ETH") && lines[1].contains("AGG@0"));
+        assert!(lines[3].contains("0 (Mainnet)") && lines[3].ends_with(" 5 │"));
+        assert!(lines[4].contains("1 (AggLayer-1)") && lines[4].ends_with(" - │"));
+        // Every line has the same width
+        assert!(lines
+            .iter()
+            .all(|line| line.chars().count() == lines[0].chars().count()));
+    }
+}
@@ -2,13 +2,19 @@ use crate::config::Config;
 use crate::error::Result;
 use crate::ui;
 use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::{debug, info};
 
+use super::fees;
+use super::permit;
+use super::receipt;
+use super::timing::{Stage, StageTimer};
 use super::{
-    common::validation_error, get_bridge_contract_address, get_wallet_with_provider,
-    is_eth_address, BridgeContract, ERC20Contract,
+    common::{is_dry_run, rpc_error, send_or_simulate, validation_error},
+    faucet, get_bridge_contract_address, get_wallet_with_provider, is_eth_address, BridgeContract,
+    ERC20Contract,
 };
 
 /// Gas options for transactions
@@ -16,6 +22,10 @@ use super::{
 pub struct GasOptions {
     pub gas_limit: Option<u64>,
     pub gas_price: Option<String>,
+    /// EIP-1559 max fee per gas in wei
+    pub max_fee: Option<String>,
+    /// EIP-1559 max priority fee per gas in wei
+    pub priority_fee: Option<String>,
 }
 
 impl GasOptions {
@@ -23,29 +33,97 @@ impl GasOptions {
         Self {
             gas_limit,
             gas_price: gas_price.map(|s| s.to_string()),
+            max_fee: None,
+            priority_fee: None,
         }
     }
 
-    pub fn apply_to_call_with_return<M: Middleware + 'static, D: ethers::core::abi::Detokenize>(
+    /// Set the EIP-1559 fees, estimated from the fee history when omitted
+    pub fn with_fees(mut self, max_fee: Option<&str>, priority_fee: Option<&str>) -> Self {
+        self.max_fee = max_fee.map(|s| s.to_string());
+        self.priority_fee = priority_fee.map(|s| s.to_string());
+        self
+    }
+
+    /// Apply the gas limit and fees to a contract call sent through `client`
+    ///
+    /// Chains without a base fee get a legacy transaction. On EIP-1559 chains
+    /// `--gas-price` alone sets both fees, as before, and otherwise the fees
+    /// not given are estimated from `eth_feeHistory`.
+    pub async fn apply_to_call_with_return<
+        M: Middleware + 'static,
+        D: ethers::core::abi::Detokenize,
+    >(
         &self,
         mut call: ContractCall<M, D>,
-    ) -> ContractCall<M, D> {
+        client: &M,
+    ) -> Result<ContractCall<M, D>> {
         if let Some(gas) = self.gas_limit {
             call = call.gas(gas);
         }
-        if let Some(price) = &self.gas_price {
-            if let Ok(price_wei) = U256::from_dec_str(price) {
-                call = call.gas_price(price_wei);
+        let wei = |value: &Option<String>, flag: &str| {
+            value
+                .as_deref()
+                .map(|value| {
+                    U256::from_dec_str(value)
+                        .map_err(|e| validation_error(&format!("Invalid {flag} '{value}': {e}")))
+                })
+                .transpose()
+        };
+        let gas_price = wei(&self.gas_price, "--gas-price")?;
+        let max_fee = wei(&self.max_fee, "--max-fee")?;
+        let priority_fee = wei(&self.priority_fee, "--priority-fee")?;
+
+        if !fees::supports_eip1559(client).await? {
+            if max_fee.is_some() || priority_fee.is_some() {
+                return Err(validation_error(
+                    "The chain has no base fee and does not accept EIP-1559 transactions; use --gas-price instead of --max-fee/--priority-fee",
+                ));
+            }
+            call = call.legacy();
+            if let Some(price) = gas_price {
+                call = call.gas_price(price);
+            }
+            return Ok(call);
+        }
+
+        if let (Some(price), None, None) = (gas_price, max_fee, priority_fee) {
+            return Ok(call.gas_price(price));
+        }
+        let (max_fee, priority_fee) = match (max_fee, priority_fee) {
+            (Some(max_fee), Some(priority_fee)) => (max_fee, priority_fee),
+            (max_fee, priority_fee) => {
+                let (estimated_max_fee, estimated_priority_fee) =
+                    fees::estimate_fees(client).await?;
+                let priority_fee = priority_fee.unwrap_or(estimated_priority_fee);
+                let max_fee = max_fee.unwrap_or(
+                    estimated_max_fee.saturating_sub(estimated_priority_fee) + priority_fee,
+                );
+                (max_fee, priority_fee)
             }
+        };
+        if priority_fee > max_fee {
+            return Err(validation_error(&format!(
+                "Priority fee {priority_fee} exceeds max fee {max_fee}"
+            )));
+        }
+        debug!(%max_fee, %priority_fee, "Using EIP-1559 fees");
+        if let TypedTransaction::Eip1559(tx) = &mut call.tx {
+            tx.max_fee_per_gas = Some(max_fee);
+            tx.max_priority_fee_per_gas = Some(priority_fee);
         }
-        call
+        Ok(call)
     }
 }
 
 /// Arguments for bridging assets between networks
 ///
 /// Use the builder pattern to construct this struct:
-/// ```rust
+/// ```no_run
+/// # use aggsandbox::commands::bridge::BridgeAssetArgs;
+/// # use aggsandbox::config::Config;
+/// # fn main() -> aggsandbox::error::Result<()> {
+/// # let config = Config::load()?;
 /// // Basic usage with required fields
 /// let args = BridgeAssetArgs::builder()
 ///     .config(&config)
@@ -62,11 +140,14 @@ impl GasOptions {
 ///     .destination_network(1)
 ///     .amount("1000000000000000000")
 ///     .token_address("0xA0b86a33E6776e39e6b37ddEC4F25B04Dd9Fc4DC")
-///     .to_address("0x1234567890123456789012345678901234567890")
+///     .recipient_address("0x1234567890123456789012345678901234567890")
 ///     .gas_limit(300000)
 ///     .gas_price("20000000000")
 ///     .private_key("0x1234567890123456789012345678901234567890123456789012345678901234")
+///     .faucet_if_needed(true)
 ///     .build_with_crate_error()?;
+/// # Ok(())
+/// # }
 /// ```
 pub struct BridgeAssetArgs<'a> {
     pub config: &'a Config,
@@ -77,6 +158,8 @@ pub struct BridgeAssetArgs<'a> {
     pub to_address: Option<&'a str>,
     pub gas_options: GasOptions,
     pub private_key: Option<&'a str>,
+    pub faucet_if_needed: bool,
+    pub use_permit: bool,
 }
 
 impl<'a> BridgeAssetArgs<'a> {
@@ -119,6 +202,8 @@ pub struct BridgeAssetArgsBuilder<'a> {
     to_address: Option<&'a str>,
     gas_options: Option<GasOptions>,
     private_key: Option<&'a str>,
+    faucet_if_needed: bool,
+    use_permit: bool,
 }
 
 impl<'a> Default for BridgeAssetArgsBuilder<'a> {
@@ -132,6 +217,8 @@ impl<'a> Default for BridgeAssetArgsBuilder<'a> {
             to_address: None,
             gas_options: Some(GasOptions::new(None, None)),
             private_key: None,
+            faucet_if_needed: false,
+            use_permit: false,
         }
     }
 }
@@ -205,6 +292,18 @@ impl<'a> BridgeAssetArgsBuilder<'a> {
         self
     }
 
+    /// Fund a missing balance from the deployer account before bridging
+    pub fn faucet_if_needed(mut self, faucet_if_needed: bool) -> Self {
+        self.faucet_if_needed = faucet_if_needed;
+        self
+    }
+
+    /// Sign an EIP-2612 permit instead of sending an approve transaction
+    pub fn use_permit(mut self, use_permit: bool) -> Self {
+        self.use_permit = use_permit;
+        self
+    }
+
     pub fn build(self) -> std::result::Result<BridgeAssetArgs<'a>, &'static str> {
         let config = self.config.ok_or("Config is required")?;
         let source_network = self.source_network.ok_or("Source network is required")?;
@@ -241,6 +340,8 @@ impl<'a> BridgeAssetArgsBuilder<'a> {
             to_address: self.to_address,
             gas_options,
             private_key: self.private_key,
+            faucet_if_needed: self.faucet_if_needed,
+            use_permit: self.use_permit,
         })
     }
 
@@ -253,10 +354,12 @@ impl<'a> BridgeAssetArgsBuilder<'a> {
 /// Bridge assets between networks
 #[allow(clippy::disallowed_methods)] // Allow tracing macros
 pub async fn bridge_asset(args: BridgeAssetArgs<'_>) -> Result<()> {
-    let client =
-        get_wallet_with_provider(args.config, args.source_network, args.private_key).await?;
+    let mut timer = StageTimer::start();
+    let client = Arc::new(
+        get_wallet_with_provider(args.config, args.source_network, args.private_key).await?,
+    );
     let bridge_address = get_bridge_contract_address(args.config, args.source_network)?;
-    let bridge = BridgeContract::new(bridge_address, Arc::new(client.clone()));
+    let bridge = BridgeContract::new(bridge_address, Arc::clone(&client));
 
     let destination_network_id = args.destination_network as u32;
 
@@ -267,7 +370,7 @@ pub async fn bridge_asset(args: BridgeAssetArgs<'_>) -> Result<()> {
             ))
         })?
     } else {
-        client.address()
+        client.inner().address()
     };
 
     let amount_wei = U256::from_dec_str(args.amount).map_err(|e| {
@@ -282,8 +385,25 @@ pub async fn bridge_asset(args: BridgeAssetArgs<'_>) -> Result<()> {
         ))
     })?;
 
+    if args.faucet_if_needed && is_dry_run() {
+        ui::ui().warning("--faucet-if-needed is skipped in a dry run");
+    } else if args.faucet_if_needed {
+        faucet::top_up(
+            args.config,
+            args.source_network,
+            client.inner().address(),
+            token_addr,
+            amount_wei,
+        )
+        .await?;
+    }
+
     // Handle ETH vs ERC20 token bridging
     let tx_hash_for_claim = if is_eth_address(args.token_address) {
+        if args.use_permit {
+            ui::ui().warning("--use-permit is ignored when bridging ETH");
+        }
+
         info!(
             "Bridging ETH from network {} to network {}",
             args.source_network, args.destination_network
@@ -301,13 +421,18 @@ pub async fn bridge_asset(args: BridgeAssetArgs<'_>) -> Result<()> {
             )
             .value(amount_wei);
 
-        let call = args.gas_options.apply_to_call_with_return(call);
+        let call = args
+            .gas_options
+            .apply_to_call_with_return(call, client.as_ref())
+            .await?;
 
-        let tx = call.send().await.map_err(|e| {
-            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                &format!("Failed to send bridge transaction: {e}"),
-            ))
-        })?;
+        let Some(tx) = timer
+            .time(Stage::TxSubmission, send_or_simulate(&call))
+            .await
+            .map_err(|e| rpc_error("send bridge transaction", receipt::describe_error(&e)))?
+        else {
+            return Ok(());
+        };
 
         let tx_hash = tx.tx_hash();
         ui::ui().success(&format!("Bridge transaction submitted: {tx_hash:#x}"));
@@ -320,52 +445,69 @@ pub async fn bridge_asset(args: BridgeAssetArgs<'_>) -> Result<()> {
         debug!("ERC20 Bridge Debug:");
         debug!("  - Token address: {}", args.token_address);
         debug!("  - Token address (parsed): {token_addr:?}");
-        debug!("  - From address: {:?}", client.address());
+        debug!("  - From address: {:?}", client.inner().address());
         debug!("  - Bridge address: {bridge_address:?}");
         debug!("  - Amount: {} (Wei: {amount_wei})", args.amount);
         debug!("  - Destination network ID: {destination_network_id}");
         debug!("  - Recipient: {recipient:?}");
 
-        // First check and approve if needed
-        let token = ERC20Contract::new(token_addr, Arc::new(client.clone()));
-
-        debug!(
-            "Checking allowance: token.allowance({:?}, {bridge_address:?})",
-            client.address()
-        );
-        let allowance = token
-            .allowance(client.address(), bridge_address)
-            .call()
-            .await
-            .map_err(|e| {
-                crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                    &format!("Failed to check allowance: {e}"),
-                ))
-            })?;
-
-        debug!("Current allowance: {allowance}, Required: {amount_wei}");
-
-        if allowance < amount_wei {
-            info!("Approving bridge contract to spend {} tokens", args.amount);
-            debug!("Calling approve: token.approve({bridge_address:?}, {amount_wei})");
-            let approve_call = token.approve(bridge_address, amount_wei);
-            let approve_tx = approve_call.send().await.map_err(|e| {
-                crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                    &format!("Failed to approve tokens: {e}"),
-                ))
-            })?;
-            ui::ui().success(&format!(
-                "Token approval transaction: {:#x}",
-                approve_tx.tx_hash()
-            ));
-
-            // Wait for approval to be mined
-            approve_tx.await.map_err(|e| {
-                crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                    &format!("Approval transaction failed: {e}"),
-                ))
-            })?;
-        }
+        // Either sign a permit the bridge redeems, or check and approve if needed
+        let permit_data = if args.use_permit {
+            info!("Signing an EIP-2612 permit for {} tokens", args.amount);
+            let permit_data =
+                permit::sign_permit(&client, token_addr, bridge_address, amount_wei).await?;
+            ui::ui().success("Signed permit, skipping the approval transaction");
+            permit_data
+        } else {
+            let token = ERC20Contract::new(token_addr, Arc::clone(&client));
+
+            debug!(
+                "Checking allowance: token.allowance({:?}, {bridge_address:?})",
+                client.inner().address()
+            );
+            let allowance = token
+                .allowance(client.inner().address(), bridge_address)
+                .call()
+                .await
+                .map_err(|e| rpc_error("check allowance", e))?;
+
+            debug!("Current allowance: {allowance}, Required: {amount_wei}");
+
+            if allowance < amount_wei {
+                info!("Approving bridge contract to spend {} tokens", args.amount);
+                debug!("Calling approve: token.approve({bridge_address:?}, {amount_wei})");
+                let approve_call = token.approve(bridge_address, amount_wei);
+                let approve_tx = timer
+                    .time(Stage::TxSubmission, send_or_simulate(&approve_call))
+                    .await
+                    .map_err(|e| rpc_error("approve tokens", receipt::describe_error(&e)))?;
+                if let Some(approve_tx) = approve_tx {
+                    ui::ui().success(&format!(
+                        "Token approval transaction: {:#x}",
+                        approve_tx.tx_hash()
+                    ));
+
+                    // Wait for approval to be mined
+                    let approve_receipt = timer
+                        .time(Stage::MiningWait, approve_tx)
+                        .await
+                        .map_err(|e| rpc_error("wait for the approval transaction", e))?;
+                    if let Some(approve_receipt) = approve_receipt {
+                        receipt::ensure_success(
+                            client.as_ref(),
+                            &approve_receipt,
+                            "Approval transaction",
+                        )
+                        .await?;
+                    }
+                } else {
+                    ui::ui().warning(
+                        "The approval was only simulated, so the simulated bridge call may revert for missing allowance",
+                    );
+                }
+            }
+            Bytes::new()
+        };
 
         // Now bridge the tokens
         debug!("Calling bridgeAsset:");
@@ -380,23 +522,35 @@ pub async fn bridge_asset(args: BridgeAssetArgs<'_>) -> Result<()> {
             recipient,
             amount_wei,
             token_addr,
-            true,         // forceUpdateGlobalExitRoot
-            Bytes::new(), // empty permit data
+            true, // forceUpdateGlobalExitRoot
+            permit_data,
         );
 
-        let call = args.gas_options.apply_to_call_with_return(call);
+        let call = args
+            .gas_options
+            .apply_to_call_with_return(call, client.as_ref())
+            .await?;
 
-        let tx = call.send().await.map_err(|e| {
-            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                &format!("Failed to send bridge transaction: {e}"),
-            ))
-        })?;
+        let Some(tx) = timer
+            .time(Stage::TxSubmission, send_or_simulate(&call))
+            .await
+            .map_err(|e| rpc_error("send bridge transaction", receipt::describe_error(&e)))?
+        else {
+            return Ok(());
+        };
 
         let tx_hash = tx.tx_hash();
         ui::ui().success(&format!("Bridge transaction submitted: {tx_hash:#x}"));
         tx_hash
     };
 
+    timer
+        .time(
+            Stage::MiningWait,
+            receipt::wait_for_receipt(client.as_ref(), tx_hash_for_claim, "Bridge transaction"),
+        )
+        .await?;
+
     // Determine the correct source network for claiming
     // For bridge-back scenarios (wrapped tokens), we need to use the original token's network
     let claim_source_network = if !is_eth_address(args.token_address) {
@@ -414,6 +568,78 @@ pub async fn bridge_asset(args: BridgeAssetArgs<'_>) -> Result<()> {
 
     ui::ui().tip(&format!("Use `aggsandbox bridge claim --network-id {} --tx-hash {tx_hash_for_claim:#x} --source-network-id {claim_source_network}` to claim assets", args.destination_network));
     ui::ui().warning("Wait at least 5 seconds after bridging before claiming to allow AggKit to update the Global Exit Root (GER)");
+    timer.report(
+        "bridge asset",
+        serde_json::json!({
+            "tx_hash": format!("{tx_hash_for_claim:#x}"),
+            "source_network": args.source_network,
+            "destination_network": args.destination_network,
+        }),
+    );
 
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    /// JSON-RPC node that accepts everything but the raw transaction
+    struct RejectingNode;
+
+    impl wiremock::Respond for RejectingNode {
+        fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            let request: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let result = match request["method"].as_str().unwrap() {
+                "eth_chainId" => serde_json::json!("0x1"),
+                "eth_getTransactionCount" => serde_json::json!("0x0"),
+                "eth_gasPrice" | "eth_estimateGas" => serde_json::json!("0x5208"),
+                "eth_getBlockByNumber" => serde_json::json!({ "number": "0x1" }),
+                _ => {
+                    return wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "error": { "code": -32000, "message": "insufficient funds" },
+                    }))
+                }
+            };
+            wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": result,
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejected_bridge_reports_rpc_exit_code() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(RejectingNode)
+            .mount(&server)
+            .await;
+        let mut config = Config::default();
+        config.networks.l1.rpc_url = crate::types::RpcUrl::new(server.uri()).unwrap();
+        config.contracts.l1_contracts.insert(
+            "PolygonZkEVMBridge".to_string(),
+            crate::types::EthereumAddress::new(format!("{:#x}", Address::repeat_byte(0xb0)))
+                .unwrap(),
+        );
+
+        let args = BridgeAssetArgs::builder()
+            .config(&config)
+            .source_network(0)
+            .destination_network(1)
+            .amount("1")
+            .token_address("0x0000000000000000000000000000000000000000")
+            .build_with_crate_error()
+            .unwrap();
+        let error = bridge_asset(args).await.unwrap_err();
+        assert!(
+            error.to_string().contains("send bridge transaction"),
+            "{error}"
+        );
+        assert_eq!(error.exit_code(), crate::error::exit_code::RPC);
+    }
+}
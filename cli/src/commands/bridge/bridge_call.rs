@@ -6,6 +6,9 @@ use std::str::FromStr;
 use std::sync::Arc;
 use tracing::{debug, info};
 
+use super::common::{rpc_error, send_or_simulate};
+use super::receipt;
+use super::timing::{Stage, StageTimer};
 use super::{
     get_bridge_extension_address, get_wallet_with_provider, BridgeExtensionContract, ERC20Contract,
     GasOptions,
@@ -14,7 +17,9 @@ use super::{
 /// Parameters for bridge message operations
 ///
 /// Use the builder pattern to construct this struct:
-/// ```rust
+/// ```no_run
+/// # use aggsandbox::commands::bridge::BridgeMessageParams;
+/// # fn main() -> aggsandbox::error::Result<()> {
 /// // Basic usage with required fields
 /// let params = BridgeMessageParams::builder()
 ///     .target("0x1234567890123456789012345678901234567890")
@@ -28,6 +33,8 @@ use super::{
 ///     .amount("1000000000000000000")
 ///     .fallback_address("0x0987654321098765432109876543210987654321")
 ///     .build_with_crate_error()?;
+/// # Ok(())
+/// # }
 /// ```
 #[derive(Debug, Clone)]
 pub struct BridgeMessageParams {
@@ -157,7 +164,11 @@ impl BridgeMessageParamsBuilder {
 /// Arguments for bridge and call operations with approval
 ///
 /// Use the builder pattern to construct this struct:
-/// ```rust
+/// ```no_run
+/// # use aggsandbox::commands::bridge::BridgeAndCallArgs;
+/// # use aggsandbox::config::Config;
+/// # fn main() -> aggsandbox::error::Result<()> {
+/// # let config = Config::load()?;
 /// let args = BridgeAndCallArgs::builder()
 ///     .config(&config)
 ///     .source_network(0)
@@ -168,6 +179,8 @@ impl BridgeMessageParamsBuilder {
 ///     .data("0x12345678")
 ///     .fallback("0x1111111111111111111111111111111111111111")
 ///     .build_with_crate_error()?;
+/// # Ok(())
+/// # }
 /// ```
 pub struct BridgeAndCallArgs<'a> {
     pub config: &'a Config,
@@ -376,6 +389,7 @@ impl<'a> BridgeAndCallArgsBuilder<'a> {
 }
 
 /// Bridge message using direct bridgeMessage call
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
 pub async fn bridge_message(
     config: &Config,
     source_network: u64,
@@ -384,9 +398,10 @@ pub async fn bridge_message(
     gas_options: GasOptions,
     private_key: Option<&str>,
 ) -> Result<()> {
-    let client = get_wallet_with_provider(config, source_network, private_key).await?;
+    let mut timer = StageTimer::start();
+    let client = Arc::new(get_wallet_with_provider(config, source_network, private_key).await?);
     let bridge_address = super::get_bridge_contract_address(config, source_network)?;
-    let bridge = super::BridgeContract::new(bridge_address, Arc::new(client.clone()));
+    let bridge = super::BridgeContract::new(bridge_address, Arc::clone(&client));
 
     let destination_network_id = destination_network as u32;
 
@@ -419,6 +434,7 @@ pub async fn bridge_message(
 
     // Create metadata with the call data
     let metadata = call_data;
+    super::common::warn_if_large_metadata(&metadata);
 
     let mut call = bridge.bridge_message(
         destination_network_id,
@@ -431,20 +447,43 @@ pub async fn bridge_message(
         call = call.value(eth_amount);
     }
 
-    let call = gas_options.apply_to_call_with_return(call);
+    let call = gas_options
+        .apply_to_call_with_return(call, bridge.client_ref())
+        .await?;
 
-    let tx = call.send().await.map_err(|e| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            &format!("Failed to send bridge message transaction: {e}"),
-        ))
-    })?;
+    let Some(tx) = timer
+        .time(Stage::TxSubmission, send_or_simulate(&call))
+        .await
+        .map_err(|e| {
+            rpc_error(
+                "send bridge message transaction",
+                receipt::describe_error(&e),
+            )
+        })?
+    else {
+        return Ok(());
+    };
 
     ui::ui().success(&format!(
         "Bridge message transaction submitted: {:#x}",
         tx.tx_hash()
     ));
+    timer
+        .time(
+            Stage::MiningWait,
+            receipt::wait_for_receipt(client.as_ref(), tx.tx_hash(), "Bridge message transaction"),
+        )
+        .await?;
     ui::ui().tip(&format!("Use `aggsandbox bridge claim --network-id {} --tx-hash {:#x} --source-network-id {}` to claim message", destination_network, tx.tx_hash(), source_network));
     ui::ui().warning("Wait at least 5 seconds after bridging before claiming to allow AggKit to update the Global Exit Root (GER)");
+    timer.report(
+        "bridge message",
+        serde_json::json!({
+            "tx_hash": format!("{:#x}", tx.tx_hash()),
+            "source_network": source_network,
+            "destination_network": destination_network,
+        }),
+    );
 
     Ok(())
 }
@@ -457,9 +496,10 @@ pub async fn get_precalculated_l2_token_address(
     token_address: &str,
     private_key: Option<&str>,
 ) -> Result<Address> {
-    let client = get_wallet_with_provider(config, destination_network, private_key).await?;
+    let client =
+        Arc::new(get_wallet_with_provider(config, destination_network, private_key).await?);
     let bridge_address = super::get_bridge_contract_address(config, destination_network)?;
-    let bridge = super::BridgeContract::new(bridge_address, Arc::new(client.clone()));
+    let bridge = super::BridgeContract::new(bridge_address, Arc::clone(&client));
 
     let token_addr = Address::from_str(token_address).map_err(|e| {
         crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
@@ -487,11 +527,7 @@ pub async fn get_precalculated_l2_token_address(
         .precalculated_wrapper_address(1, token_addr, token_name, token_symbol, token_decimals)
         .call()
         .await
-        .map_err(|e| {
-            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                &format!("Failed to get precalculated address: {e}"),
-            ))
-        })?;
+        .map_err(|e| rpc_error("get precalculated address", e))?;
 
     Ok(l2_token_address)
 }
@@ -499,10 +535,12 @@ pub async fn get_precalculated_l2_token_address(
 /// Bridge tokens and execute contract call with automatic approval
 #[allow(clippy::disallowed_methods)]
 pub async fn bridge_and_call_with_approval(args: BridgeAndCallArgs<'_>) -> Result<()> {
-    let client =
-        get_wallet_with_provider(args.config, args.source_network, args.private_key).await?;
+    let mut timer = StageTimer::start();
+    let client = Arc::new(
+        get_wallet_with_provider(args.config, args.source_network, args.private_key).await?,
+    );
     let bridge_ext_address = get_bridge_extension_address(args.config, args.source_network)?;
-    let bridge_ext = BridgeExtensionContract::new(bridge_ext_address, Arc::new(client.clone()));
+    let bridge_ext = BridgeExtensionContract::new(bridge_ext_address, Arc::clone(&client));
 
     let destination_network_id = args.destination_network as u32;
 
@@ -537,6 +575,8 @@ pub async fn bridge_and_call_with_approval(args: BridgeAndCallArgs<'_>) -> Resul
         ))
     })?;
 
+    super::common::warn_if_large_metadata(&call_data_bytes);
+
     debug!("Bridge and Call Debug:");
     debug!("  - Token address: {}", args.token_address);
     debug!("  - Amount: {} (Wei: {amount_wei})", args.amount);
@@ -566,18 +606,14 @@ pub async fn bridge_and_call_with_approval(args: BridgeAndCallArgs<'_>) -> Resul
 
     // Step 1: Check and approve bridge extension to spend tokens (skip for ETH)
     if !super::is_eth_address(args.token_address) {
-        let token = ERC20Contract::new(token_addr, Arc::new(client.clone()));
+        let token = ERC20Contract::new(token_addr, Arc::clone(&client));
 
         debug!("Checking allowance for bridge extension...");
         let allowance = token
-            .allowance(client.address(), bridge_ext_address)
+            .allowance(client.inner().address(), bridge_ext_address)
             .call()
             .await
-            .map_err(|e| {
-                crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                    &format!("Failed to check allowance: {e}"),
-                ))
-            })?;
+            .map_err(|e| rpc_error("check allowance", e))?;
 
         debug!("Current allowance: {allowance}, Required: {amount_wei}");
 
@@ -588,22 +624,34 @@ pub async fn bridge_and_call_with_approval(args: BridgeAndCallArgs<'_>) -> Resul
             );
             debug!("Calling approve: token.approve({bridge_ext_address:?}, {amount_wei})");
             let approve_call = token.approve(bridge_ext_address, amount_wei);
-            let approve_tx = approve_call.send().await.map_err(|e| {
-                crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                    &format!("Failed to approve tokens: {e}"),
-                ))
-            })?;
-            ui::ui().success(&format!(
-                "Token approval transaction: {:#x}",
-                approve_tx.tx_hash()
-            ));
-
-            // Wait for approval to be mined
-            approve_tx.await.map_err(|e| {
-                crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                    &format!("Approval transaction failed: {e}"),
-                ))
-            })?;
+            let approve_tx = timer
+                .time(Stage::TxSubmission, send_or_simulate(&approve_call))
+                .await
+                .map_err(|e| rpc_error("approve tokens", receipt::describe_error(&e)))?;
+            if let Some(approve_tx) = approve_tx {
+                ui::ui().success(&format!(
+                    "Token approval transaction: {:#x}",
+                    approve_tx.tx_hash()
+                ));
+
+                // Wait for approval to be mined
+                let approve_receipt = timer
+                    .time(Stage::MiningWait, approve_tx)
+                    .await
+                    .map_err(|e| rpc_error("wait for the approval transaction", e))?;
+                if let Some(approve_receipt) = approve_receipt {
+                    receipt::ensure_success(
+                        client.as_ref(),
+                        &approve_receipt,
+                        "Approval transaction",
+                    )
+                    .await?;
+                }
+            } else {
+                ui::ui().warning(
+                    "The approval was only simulated, so the simulated bridge call may revert for missing allowance",
+                );
+            }
         }
     } else {
         debug!("Skipping allowance check for ETH (native token)");
@@ -631,39 +679,56 @@ pub async fn bridge_and_call_with_approval(args: BridgeAndCallArgs<'_>) -> Resul
         call = call.gas(3_000_000u64); // Default high gas limit
     }
 
-    let call = args.gas_options.apply_to_call_with_return(call);
+    let call = args
+        .gas_options
+        .apply_to_call_with_return(call, client.as_ref())
+        .await?;
 
-    let tx = call.send().await.map_err(|e| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            &format!("Failed to send bridge and call transaction: {e}"),
-        ))
-    })?;
+    let Some(tx) = timer
+        .time(Stage::TxSubmission, send_or_simulate(&call))
+        .await
+        .map_err(|e| {
+            rpc_error(
+                "send bridge and call transaction",
+                receipt::describe_error(&e),
+            )
+        })?
+    else {
+        return Ok(());
+    };
 
     ui::ui().success(&format!(
         "Bridge and call transaction submitted: {:#x}",
         tx.tx_hash()
     ));
+    timer
+        .time(
+            Stage::MiningWait,
+            receipt::wait_for_receipt(client.as_ref(), tx.tx_hash(), "Bridge and call transaction"),
+        )
+        .await?;
 
     ui::ui().info("🔧 This creates TWO bridge transactions:");
     ui::ui().info("   1. Asset bridge (leaf_type: 0) - bridges tokens to PolygonBridge");
     ui::ui().info("   2. Message bridge (leaf_type: 1) - execute calldata from JumpPoint");
 
     ui::ui().blank_line();
-    ui::ui().tip("To complete the process, you need to claim both bridges:");
-    ui::ui().info(&format!(
-        "   1. First check bridges: `aggsandbox show bridges --network-id {}`",
+    ui::ui().tip(&format!(
+        "Claim both in order with `aggsandbox bridge claim-bundle --network-id {} --tx-hash {:#x} --source-network-id {}`",
+        args.destination_network,
+        tx.tx_hash(),
         args.source_network
     ));
-    ui::ui().info(&format!(
-        "   2. Find entries with tx_hash: {:#x}",
-        tx.tx_hash()
-    ));
-    ui::ui().info("   3. Note the deposit_count for asset bridge (leaf_type: 0)");
-    ui::ui().info("   4. Note the deposit_count for message bridge (leaf_type: 1, has calldata)");
-    ui::ui().info(&format!("   5. Claim asset: `aggsandbox bridge claim --network-id {} --tx-hash {:#x} --source-network-id {} --deposit-count <asset_deposit_count>`", args.destination_network, tx.tx_hash(), args.source_network));
-    ui::ui().info(&format!("   6. Claim message: `aggsandbox bridge claim --network-id {} --tx-hash {:#x} --source-network-id {} --deposit-count <message_deposit_count>`", args.destination_network, tx.tx_hash(), args.source_network));
 
     ui::ui().warning("Wait at least 5 seconds after bridging before claiming to allow AggKit to update the Global Exit Root (GER)");
+    timer.report(
+        "bridge and call",
+        serde_json::json!({
+            "tx_hash": format!("{:#x}", tx.tx_hash()),
+            "source_network": args.source_network,
+            "destination_network": args.destination_network,
+        }),
+    );
 
     Ok(())
 }
@@ -0,0 +1,449 @@
+//! Decoding of raw calldata sent to the sandbox contracts
+//!
+//! `bridge utils decode-calldata` matches the function selector against the
+//! bridge, bridge extension and ERC20 functions below and decodes the
+//! arguments. `bytes` arguments that are themselves calls to one of these
+//! functions (the `callData` of `bridgeAndCall`, the `permitData` of
+//! `bridgeAsset`) are decoded recursively.
+//!
+//! `bridge utils encode-call` goes the other way and ABI-encodes a call from a
+//! function signature and its arguments, e.g. the `--data` of `bridge-and-call`.
+
+use super::common::validation_error;
+use crate::error::Result;
+use crate::events::{DecodedEvent, DecodedField};
+use crate::validation::Validator;
+use ethers::abi::token::{LenientTokenizer, Tokenizer};
+use ethers::abi::{Function, ParamType, Token};
+use ethers::types::U256;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Functions of the sandbox contracts in human readable ABI form, by contract
+const CALL_ABI: &[(&str, &[&str])] = &[
+    (
+        "PolygonZkEVMBridge",
+        &[
+            "function bridgeAsset(uint32 destinationNetwork, address destinationAddress, uint256 amount, address token, bool forceUpdateGlobalExitRoot, bytes permitData)",
+            "function bridgeMessage(uint32 destinationNetwork, address destinationAddress, bool forceUpdateGlobalExitRoot, bytes metadata)",
+            "function bridgeMessageWETH(uint32 destinationNetwork, address destinationAddress, uint256 amountWETH, bool forceUpdateGlobalExitRoot, bytes metadata)",
+            "function claimAsset(uint256 globalIndex, bytes32 mainnetExitRoot, bytes32 rollupExitRoot, uint32 originNetwork, address originTokenAddress, uint32 destinationNetwork, address destinationAddress, uint256 amount, bytes metadata)",
+            "function claimMessage(uint256 globalIndex, bytes32 mainnetExitRoot, bytes32 rollupExitRoot, uint32 originNetwork, address originAddress, uint32 destinationNetwork, address destinationAddress, uint256 amount, bytes metadata)",
+            "function updateGlobalExitRoot()",
+        ],
+    ),
+    (
+        "BridgeExtension",
+        &[
+            "function bridgeAndCall(address token, uint256 amount, uint32 destinationNetwork, address callAddress, address fallbackAddress, bytes callData, bool forceUpdateGlobalExitRoot)",
+            "function onMessageReceived(address originAddress, uint32 originNetwork, bytes data)",
+        ],
+    ),
+    (
+        "ERC20",
+        &[
+            "function transfer(address to, uint256 amount)",
+            "function transferFrom(address from, address to, uint256 amount)",
+            "function approve(address spender, uint256 amount)",
+            "function permit(address owner, address spender, uint256 value, uint256 deadline, uint8 v, bytes32 r, bytes32 s)",
+            "function mint(address to, uint256 amount)",
+        ],
+    ),
+];
+
+/// How deep `bytes` arguments are decoded as nested calls
+const MAX_NESTING: usize = 3;
+
+/// Known functions keyed by their selector, with the contract defining them
+type KnownFunctions = HashMap<[u8; 4], (&'static str, Function)>;
+
+/// [`KnownFunctions`] parsed from [`CALL_ABI`] on first use
+fn known_functions() -> &'static KnownFunctions {
+    static FUNCTIONS: OnceLock<KnownFunctions> = OnceLock::new();
+    FUNCTIONS.get_or_init(|| {
+        CALL_ABI
+            .iter()
+            .flat_map(|(contract, signatures)| {
+                ethers::abi::parse_abi(signatures)
+                    .map(|abi| abi.functions().cloned().collect::<Vec<_>>())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(move |function| (function.short_signature(), (*contract, function)))
+            })
+            .collect()
+    })
+}
+
+/// A function call decoded from its calldata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedCall {
+    /// Contract defining the function
+    pub contract: &'static str,
+    /// Canonical signature such as `transfer(address,uint256)`
+    pub signature: String,
+    pub selector: [u8; 4],
+    /// Decoded arguments, or a note when they do not match the ABI
+    pub arguments: DecodedEvent,
+    /// `bytes` arguments that are calls to known functions, keyed by argument
+    pub nested: Vec<(String, DecodedCall)>,
+}
+
+impl DecodedCall {
+    /// JSON representation used by `--json` output
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+    pub fn to_json(&self) -> serde_json::Value {
+        let nested: serde_json::Map<_, _> = self
+            .nested
+            .iter()
+            .map(|(key, call)| (key.clone(), call.to_json()))
+            .collect();
+        serde_json::json!({
+            "contract": self.contract,
+            "function": self.signature,
+            "selector": format!("0x{}", hex::encode(self.selector)),
+            "arguments": self.arguments.to_json(),
+            "nested": nested,
+            "notes": self.arguments.notes,
+        })
+    }
+}
+
+/// Parse `0x` prefixed (or bare) hex calldata
+pub fn parse_calldata(input: &str) -> Result<Vec<u8>> {
+    let input = input.trim();
+    let hex_data = input
+        .strip_prefix("0x")
+        .or_else(|| input.strip_prefix("0X"))
+        .unwrap_or(input);
+    hex::decode(hex_data).map_err(|e| validation_error(&format!("Calldata is not valid hex: {e}")))
+}
+
+/// Decode calldata against the bridge, bridge extension and ERC20 functions
+pub fn decode_calldata(data: &[u8]) -> Result<DecodedCall> {
+    let selector: [u8; 4] = data
+        .get(..4)
+        .and_then(|selector| selector.try_into().ok())
+        .ok_or_else(|| {
+            validation_error(&format!(
+                "Calldata must hold at least a 4-byte function selector, got {} bytes",
+                data.len()
+            ))
+        })?;
+    decode_with_depth(selector, &data[4..], 0).ok_or_else(|| {
+        validation_error(&format!(
+            "Unknown function selector 0x{}: not a bridge, bridge extension or ERC20 function",
+            hex::encode(selector)
+        ))
+    })
+}
+
+/// Canonical signature without the output types, e.g. `transfer(address,uint256)`
+fn canonical_signature(function: &Function) -> String {
+    let signature = function.signature();
+    signature
+        .split_once(':')
+        .map_or(signature.as_str(), |(signature, _)| signature)
+        .to_string()
+}
+
+fn decode_with_depth(selector: [u8; 4], arguments: &[u8], depth: usize) -> Option<DecodedCall> {
+    let (contract, function) = known_functions().get(&selector)?;
+    let signature = canonical_signature(function);
+
+    let (decoded, nested) = match function.decode_input(arguments) {
+        Ok(tokens) => {
+            let mut nested = Vec::new();
+            let fields = function
+                .inputs
+                .iter()
+                .zip(&tokens)
+                .map(|(input, token)| {
+                    let field = DecodedField::from_param(&input.name, &input.kind, token);
+                    if let Some(call) = nested_call(token, depth) {
+                        nested.push((field.key.clone(), call));
+                    }
+                    field
+                })
+                .collect();
+            (
+                DecodedEvent {
+                    fields,
+                    notes: Vec::new(),
+                },
+                nested,
+            )
+        }
+        Err(e) => (
+            DecodedEvent {
+                fields: Vec::new(),
+                notes: vec![format!(
+                    "⚠️  Arguments do not match the {} ABI ({e})",
+                    function.name
+                )],
+            },
+            Vec::new(),
+        ),
+    };
+
+    Some(DecodedCall {
+        contract,
+        signature,
+        selector,
+        arguments: decoded,
+        nested,
+    })
+}
+
+/// Decode a `bytes` argument that holds a call to a known function
+fn nested_call(token: &Token, depth: usize) -> Option<DecodedCall> {
+    let Token::Bytes(bytes) = token else {
+        return None;
+    };
+    if depth + 1 >= MAX_NESTING || bytes.len() < 4 {
+        return None;
+    }
+    let selector: [u8; 4] = bytes[..4].try_into().ok()?;
+    decode_with_depth(selector, &bytes[4..], depth + 1)
+}
+
+/// A function call encoded from its signature and arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodedCall {
+    /// Canonical signature such as `transfer(address,uint256)`
+    pub signature: String,
+    /// Selector followed by the ABI-encoded arguments
+    pub calldata: Vec<u8>,
+}
+
+impl EncodedCall {
+    pub fn selector(&self) -> &[u8] {
+        &self.calldata[..4]
+    }
+
+    /// Calldata as `0x` hex
+    pub fn hex(&self) -> String {
+        format!("0x{}", hex::encode(&self.calldata))
+    }
+
+    /// JSON representation used by `--json` output
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "function": self.signature,
+            "selector": format!("0x{}", hex::encode(self.selector())),
+            "calldata": self.hex(),
+        })
+    }
+}
+
+/// Parse a function signature such as `transfer(address,uint256)` or
+/// `function transfer(address to, uint256 amount)`
+fn parse_function(signature: &str) -> Result<Function> {
+    let signature = signature.trim();
+    let declaration = signature
+        .strip_prefix("function ")
+        .unwrap_or(signature)
+        .trim();
+    ethers::abi::parse_abi(&[format!("function {declaration}").as_str()])
+        .ok()
+        .and_then(|abi| abi.functions().next().cloned())
+        .ok_or_else(|| {
+            validation_error(&format!(
+                "Invalid function signature '{signature}', expected e.g. transfer(address,uint256)"
+            ))
+        })
+}
+
+/// Tokenize a command line argument for a parameter of the given type
+///
+/// Top-level integers also accept `0x` hex, like the amounts of other commands.
+fn tokenize(kind: &ParamType, value: &str) -> std::result::Result<Token, String> {
+    match kind {
+        ParamType::Uint(bits) => {
+            let number = Validator::parse_uint(value).map_err(|e| e.to_string())?;
+            if *bits < 256 && number >= U256::one() << *bits {
+                return Err(format!("{number} does not fit in uint{bits}"));
+            }
+            Ok(Token::Uint(number))
+        }
+        _ => LenientTokenizer::tokenize(kind, value.trim()).map_err(|e| e.to_string()),
+    }
+}
+
+/// ABI-encode a call to `signature` with command line arguments
+pub fn encode_call(signature: &str, arguments: &[String]) -> Result<EncodedCall> {
+    let function = parse_function(signature)?;
+    if function.inputs.len() != arguments.len() {
+        return Err(validation_error(&format!(
+            "{} expects {} argument(s), got {}",
+            canonical_signature(&function),
+            function.inputs.len(),
+            arguments.len()
+        )));
+    }
+
+    let tokens = function
+        .inputs
+        .iter()
+        .zip(arguments)
+        .enumerate()
+        .map(|(position, (input, value))| {
+            tokenize(&input.kind, value).map_err(|e| {
+                let name = if input.name.is_empty() {
+                    format!("#{}", position + 1)
+                } else {
+                    input.name.clone()
+                };
+                validation_error(&format!(
+                    "Invalid {} argument {name} '{value}': {e}",
+                    input.kind
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let calldata = function
+        .encode_input(&tokens)
+        .map_err(|e| validation_error(&format!("Failed to encode arguments: {e}")))?;
+
+    Ok(EncodedCall {
+        signature: canonical_signature(&function),
+        calldata,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use ethers::types::{Address, U256};
+
+    fn encode(signature: &str, tokens: &[Token]) -> Vec<u8> {
+        let (_, function) = known_functions()
+            .values()
+            .find(|(_, function)| function.signature().starts_with(signature))
+            .expect("known function");
+        function.encode_input(tokens).expect("valid arguments")
+    }
+
+    #[test]
+    fn test_all_call_definitions_parse() {
+        let count: usize = CALL_ABI.iter().map(|(_, functions)| functions.len()).sum();
+        assert_eq!(known_functions().len(), count);
+    }
+
+    #[test]
+    fn test_decode_bridge_and_call_with_nested_transfer() {
+        let receiver = Address::repeat_byte(0x22);
+        let transfer = encode(
+            "transfer(",
+            &[Token::Address(receiver), Token::Uint(U256::from(5))],
+        );
+        let data = encode(
+            "bridgeAndCall(",
+            &[
+                Token::Address(Address::repeat_byte(0x11)),
+                Token::Uint(U256::from(1_000)),
+                Token::Uint(U256::from(1)),
+                Token::Address(Address::repeat_byte(0x33)),
+                Token::Address(Address::repeat_byte(0x44)),
+                Token::Bytes(transfer),
+                Token::Bool(true),
+            ],
+        );
+
+        let call = decode_calldata(&data).unwrap();
+        assert_eq!(call.contract, "BridgeExtension");
+        assert_eq!(
+            call.signature,
+            "bridgeAndCall(address,uint256,uint32,address,address,bytes,bool)"
+        );
+        let arguments = call.arguments.to_json();
+        assert_eq!(arguments["amount"], "1000");
+        assert_eq!(arguments["destination_network"], "1");
+        assert_eq!(arguments["force_update_global_exit_root"], "true");
+
+        assert_eq!(call.nested.len(), 1);
+        let (key, nested) = &call.nested[0];
+        assert_eq!(key, "call_data");
+        assert_eq!(nested.signature, "transfer(address,uint256)");
+        assert_eq!(
+            nested.arguments.to_json()["to"],
+            format!("0x{receiver:x}").as_str()
+        );
+    }
+
+    #[test]
+    fn test_truncated_arguments_are_reported() {
+        let data = encode(
+            "approve(",
+            &[
+                Token::Address(Address::repeat_byte(0x11)),
+                Token::Uint(U256::one()),
+            ],
+        );
+        let call = decode_calldata(&data[..20]).unwrap();
+        assert_eq!(call.signature, "approve(address,uint256)");
+        assert!(call.arguments.fields.is_empty());
+        assert_eq!(call.arguments.notes.len(), 1);
+    }
+
+    #[test]
+    fn test_unknown_or_short_calldata_is_rejected() {
+        assert!(decode_calldata(&[0xde, 0xad]).is_err());
+        assert!(decode_calldata(&[0xde, 0xad, 0xbe, 0xef]).is_err());
+        assert_eq!(
+            parse_calldata("0xa9059cbb").unwrap(),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+        assert!(parse_calldata("0xzz").is_err());
+    }
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn test_encode_call_round_trips_through_decode() {
+        let receiver = Address::repeat_byte(0x22);
+        let encoded = encode_call(
+            "transfer(address,uint256)",
+            &args(&[&format!("{receiver:?}"), "0x64"]),
+        )
+        .unwrap();
+        assert_eq!(encoded.signature, "transfer(address,uint256)");
+        assert_eq!(encoded.selector(), [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(
+            encoded.calldata,
+            encode(
+                "transfer(",
+                &[Token::Address(receiver), Token::Uint(U256::from(100))]
+            )
+        );
+
+        let decoded = decode_calldata(&encoded.calldata).unwrap();
+        assert_eq!(decoded.arguments.to_json()["amount"], "100");
+    }
+
+    #[test]
+    fn test_encode_call_accepts_named_declarations() {
+        let encoded = encode_call(
+            "function setValue(uint256 value, bool flag, string note, bytes data, uint8[] list)",
+            &args(&["7", "true", "hello", "0xdead", "[1,2]"]),
+        )
+        .unwrap();
+        assert_eq!(
+            encoded.signature,
+            "setValue(uint256,bool,string,bytes,uint8[])"
+        );
+        assert!(encoded.hex().starts_with("0x"));
+    }
+
+    #[test]
+    fn test_encode_call_rejects_bad_input() {
+        assert!(encode_call("not a signature", &[]).is_err());
+        assert!(encode_call("transfer(address,uint256)", &args(&["0x01"])).is_err());
+        assert!(encode_call("transfer(address,uint256)", &args(&["0xzz", "1"])).is_err());
+        assert!(encode_call("setValue(uint8)", &args(&["256"])).is_err());
+        assert!(encode_call("setValue(uint8)", &args(&["255"])).is_ok());
+    }
+}
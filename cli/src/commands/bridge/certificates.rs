@@ -0,0 +1,334 @@
+//! Certificates submitted to the agglayer
+//!
+//! An L2 settles its state on L1 by submitting certificates to the agglayer,
+//! each carrying the new local exit root of its bridge. A certificate moves
+//! from pending through proven and candidate to settled, when the agglayer
+//! has pushed its local exit root to the L1 rollup manager; until then the
+//! deposits it covers cannot be claimed on other networks. The agglayer only
+//! exposes the latest known, pending and settled certificate of a network, so
+//! these are what is shown.
+
+use crate::config::Config;
+use crate::error::{ApiError, EventError, Result};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use super::rollups::{bridge_local_exit_root, Contribution};
+
+/// Stage of a certificate in the agglayer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateStatus {
+    Pending,
+    Proven,
+    Candidate,
+    InError,
+    Settled,
+    /// A status this version does not know
+    Unknown,
+}
+
+impl CertificateStatus {
+    /// Status of an agglayer certificate header, with the error of one in error
+    ///
+    /// The agglayer encodes the status as its variant name, or as an object
+    /// keyed by `InError` holding the error.
+    pub fn parse(status: &Value) -> (Self, Option<String>) {
+        if let Some(error) = status.get("InError") {
+            let error = error.get("error").unwrap_or(error);
+            let error = error
+                .as_str()
+                .map_or_else(|| error.to_string(), str::to_string);
+            return (Self::InError, Some(error));
+        }
+        let status = match status.as_str() {
+            Some("Pending") => Self::Pending,
+            Some("Proven") => Self::Proven,
+            Some("Candidate") => Self::Candidate,
+            Some("InError") => Self::InError,
+            Some("Settled") => Self::Settled,
+            _ => Self::Unknown,
+        };
+        (status, None)
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Proven => "proven",
+            Self::Candidate => "candidate, settlement submitted to L1",
+            Self::InError => "in error",
+            Self::Settled => "settled",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// Certificate header as returned by the agglayer
+#[derive(Debug, Deserialize)]
+struct CertificateHeader {
+    certificate_id: H256,
+    height: u64,
+    #[serde(default)]
+    epoch_number: Option<u64>,
+    #[serde(default)]
+    certificate_index: Option<u64>,
+    #[serde(default)]
+    prev_local_exit_root: Option<H256>,
+    new_local_exit_root: H256,
+    status: Value,
+    #[serde(default)]
+    settlement_tx_hash: Option<H256>,
+}
+
+/// A certificate submitted by a network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Certificate {
+    pub certificate_id: H256,
+    pub height: u64,
+    pub epoch_number: Option<u64>,
+    pub certificate_index: Option<u64>,
+    pub prev_local_exit_root: Option<H256>,
+    pub new_local_exit_root: H256,
+    pub status: CertificateStatus,
+    /// Why the agglayer rejected the certificate, when in error
+    pub error: Option<String>,
+    pub settlement_tx_hash: Option<H256>,
+}
+
+impl Certificate {
+    /// Certificate of an agglayer response, `None` when the network has none
+    pub fn from_value(value: &Value) -> Result<Option<Self>> {
+        if value.is_null() {
+            return Ok(None);
+        }
+        let header = CertificateHeader::deserialize(value).map_err(|e| {
+            ApiError::response_validation_failed(&format!("invalid certificate header: {e}"))
+        })?;
+        let (status, error) = CertificateStatus::parse(&header.status);
+        Ok(Some(Self {
+            certificate_id: header.certificate_id,
+            height: header.height,
+            epoch_number: header.epoch_number,
+            certificate_index: header.certificate_index,
+            prev_local_exit_root: header.prev_local_exit_root,
+            new_local_exit_root: header.new_local_exit_root,
+            status,
+            error,
+            settlement_tx_hash: header.settlement_tx_hash,
+        }))
+    }
+
+    /// One line summary: height, status and ID
+    pub fn summary(&self) -> String {
+        format!(
+            "height {}, {} ({:#x})",
+            self.height,
+            self.status.describe(),
+            self.certificate_id
+        )
+    }
+}
+
+/// Latest certificates of one network and whether its bridge state settled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NetworkCertificates {
+    pub network_id: u64,
+    pub latest_known: Option<Certificate>,
+    pub latest_pending: Option<Certificate>,
+    pub latest_settled: Option<Certificate>,
+    /// Current local exit root of the network's bridge, for configured networks
+    pub bridge_local_exit_root: Option<H256>,
+    /// Whether the last settled local exit root matches the bridge
+    pub settlement: Contribution,
+}
+
+/// Read the latest certificates of a network from the agglayer
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn fetch_certificates(config: &Config, network_id: u64) -> Result<NetworkCertificates> {
+    let url = config.api.agglayer_url.as_str();
+    let provider = Provider::<Http>::try_from(url).map_err(|e| {
+        EventError::rpc_connection_failed(&format!("Invalid agglayer URL {url}: {e}"))
+    })?;
+    debug!(network_id, url, "Reading agglayer certificates");
+
+    let header = |method: &'static str| {
+        let provider = &provider;
+        async move {
+            let value: Value = provider
+                .request(method, [network_id])
+                .await
+                .map_err(|e| {
+                    EventError::rpc_connection_failed(&format!(
+                        "Agglayer query {method} at {url} failed: {e}; set AGGLAYER_URL to the JSON-RPC endpoint of the agglayer"
+                    ))
+                })?;
+            Certificate::from_value(&value)
+        }
+    };
+    let latest_known = header("interop_getLatestKnownCertificateHeader").await?;
+    let latest_pending = header("interop_getLatestPendingCertificateHeader").await?;
+    let latest_settled = header("interop_getLatestSettledCertificateHeader").await?;
+
+    let bridge_local_exit_root = bridge_local_exit_root(config, network_id).await;
+    let settled_root = latest_settled
+        .as_ref()
+        .map_or_else(H256::zero, |certificate| certificate.new_local_exit_root);
+    Ok(NetworkCertificates {
+        network_id,
+        latest_known,
+        latest_pending,
+        latest_settled,
+        bridge_local_exit_root,
+        settlement: Contribution::new(settled_root, bridge_local_exit_root),
+    })
+}
+
+/// Summary rows of a network's certificates
+pub fn summary_rows(certificates: &NetworkCertificates) -> Vec<(String, String)> {
+    let summary = |certificate: &Option<Certificate>| {
+        certificate
+            .as_ref()
+            .map_or_else(|| "none".to_string(), Certificate::summary)
+    };
+    let settlement = match certificates.settlement {
+        Contribution::None => "nothing settled yet",
+        Contribution::Current => "up to date, the bridge state is settled on L1",
+        Contribution::Behind => "behind, newer deposits are not settled yet",
+        Contribution::Unknown => "unknown (not a configured network)",
+    };
+    let mut rows = vec![
+        (
+            "Latest known".to_string(),
+            summary(&certificates.latest_known),
+        ),
+        (
+            "Latest pending".to_string(),
+            summary(&certificates.latest_pending),
+        ),
+        (
+            "Latest settled".to_string(),
+            summary(&certificates.latest_settled),
+        ),
+    ];
+    if let Some(settled) = &certificates.latest_settled {
+        rows.push((
+            "Settled local exit root".to_string(),
+            format!("{:#x}", settled.new_local_exit_root),
+        ));
+        if let Some(tx_hash) = settled.settlement_tx_hash {
+            rows.push(("Settlement tx".to_string(), format!("{tx_hash:#x}")));
+        }
+    }
+    rows.push((
+        "Bridge local exit root".to_string(),
+        certificates
+            .bridge_local_exit_root
+            .map_or_else(|| "n/a".to_string(), |root| format!("{root:#x}")),
+    ));
+    rows.push(("Settlement".to_string(), settlement.to_string()));
+    rows
+}
+
+/// Detail rows of a certificate
+pub fn certificate_rows(certificate: &Certificate) -> Vec<(String, String)> {
+    let optional = |value: Option<u64>| value.map_or_else(|| "n/a".to_string(), |v| v.to_string());
+    let mut rows = vec![
+        (
+            "Certificate ID".to_string(),
+            format!("{:#x}", certificate.certificate_id),
+        ),
+        ("Height".to_string(), certificate.height.to_string()),
+        (
+            "Status".to_string(),
+            certificate.status.describe().to_string(),
+        ),
+        ("Epoch".to_string(), optional(certificate.epoch_number)),
+        (
+            "Index in epoch".to_string(),
+            optional(certificate.certificate_index),
+        ),
+        (
+            "Previous local exit root".to_string(),
+            certificate
+                .prev_local_exit_root
+                .map_or_else(|| "n/a".to_string(), |root| format!("{root:#x}")),
+        ),
+        (
+            "New local exit root".to_string(),
+            format!("{:#x}", certificate.new_local_exit_root),
+        ),
+    ];
+    if let Some(error) = &certificate.error {
+        rows.push(("Error".to_string(), error.clone()));
+    }
+    rows
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_certificate_status() {
+        assert_eq!(
+            CertificateStatus::parse(&json!("Pending")),
+            (CertificateStatus::Pending, None)
+        );
+        assert_eq!(
+            CertificateStatus::parse(&json!("Settled")),
+            (CertificateStatus::Settled, None)
+        );
+        assert_eq!(
+            CertificateStatus::parse(&json!({ "InError": { "error": "InvalidSignature" } })),
+            (
+                CertificateStatus::InError,
+                Some("InvalidSignature".to_string())
+            )
+        );
+        let (status, error) = CertificateStatus::parse(
+            &json!({ "InError": { "error": { "TypeConversionError": "overflow" } } }),
+        );
+        assert_eq!(status, CertificateStatus::InError);
+        assert_eq!(
+            error.as_deref(),
+            Some(r#"{"TypeConversionError":"overflow"}"#)
+        );
+        assert_eq!(
+            CertificateStatus::parse(&json!("Unheard")).0,
+            CertificateStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn test_certificate_from_header() {
+        let root = |byte: &str| format!("0x{}", byte.repeat(32));
+        let header = json!({
+            "network_id": 1,
+            "height": 4,
+            "epoch_number": 12,
+            "certificate_index": 0,
+            "certificate_id": root("0c"),
+            "prev_local_exit_root": root("aa"),
+            "new_local_exit_root": root("bb"),
+            "metadata": root("00"),
+            "status": "Settled",
+            "settlement_tx_hash": root("5e"),
+        });
+
+        let certificate = Certificate::from_value(&header).unwrap().unwrap();
+        assert_eq!(certificate.height, 4);
+        assert_eq!(certificate.status, CertificateStatus::Settled);
+        assert_eq!(certificate.new_local_exit_root, H256::repeat_byte(0xbb));
+        assert_eq!(
+            certificate.settlement_tx_hash,
+            Some(H256::repeat_byte(0x5e))
+        );
+        assert_eq!(Certificate::from_value(&Value::Null).unwrap(), None);
+        assert!(Certificate::from_value(&json!({ "height": 4 })).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Checkpoints of batch claims
+//!
+//! `bridge claim-all` writes the outcome of every claim to a checkpoint file
+//! as soon as it is known, by default `.aggsandbox/checkpoints/claim-all-<id>.json`.
+//! Ctrl-C stops a run after the claim in flight; `--resume <FILE>` continues
+//! it, skipping the deposits the checkpoint records as claimed and retrying
+//! the failed ones, and the summary reports the totals across all runs.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+
+use super::claim_all::{ClaimOutcome, PendingDeposit};
+use super::common::validation_error;
+use crate::error::Result;
+use crate::ui;
+
+const CHECKPOINT_DIR: &str = ".aggsandbox/checkpoints";
+
+/// Progress of a `claim-all` run, kept across resumed runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchCheckpoint {
+    pub network: u64,
+    pub source_network: Option<u64>,
+    /// Runs that worked on this checkpoint, including the current one
+    pub runs: u32,
+    /// Whether the last run was stopped by Ctrl-C
+    pub interrupted: bool,
+    pub updated_at: String,
+    /// Latest outcome of every deposit a run attempted
+    pub outcomes: Vec<ClaimOutcome>,
+}
+
+impl BatchCheckpoint {
+    pub fn new(network: u64, source_network: Option<u64>) -> Self {
+        Self {
+            network,
+            source_network,
+            runs: 0,
+            interrupted: false,
+            updated_at: chrono::Utc::now().to_rfc3339(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    /// Checkpoint a run on `network` writes when no path is given
+    pub fn default_path(network: u64) -> PathBuf {
+        Path::new(CHECKPOINT_DIR).join(format!("claim-all-{network}.json"))
+    }
+
+    /// Checkpoint of an earlier run, to be resumed
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            validation_error(&format!(
+                "Failed to read checkpoint {}: {e}",
+                path.display()
+            ))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            validation_error(&format!(
+                "Checkpoint {} is not a claim-all checkpoint: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Write the checkpoint, replacing the file only once it is complete
+    pub fn save(&mut self, path: &Path) -> Result<()> {
+        self.updated_at = chrono::Utc::now().to_rfc3339();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let partial = path.with_extension("json.partial");
+        fs::write(&partial, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&partial, path)?;
+        Ok(())
+    }
+
+    /// Fail when resuming a checkpoint of a different claim-all invocation
+    pub fn ensure_matches(&self, network: u64, source_network: Option<u64>) -> Result<()> {
+        if self.network != network || self.source_network != source_network {
+            let sources = |source: Option<u64>| {
+                source.map_or_else(|| "all networks".to_string(), |id| format!("network {id}"))
+            };
+            return Err(validation_error(&format!(
+                "Checkpoint is for claims on network {} from {}, not on network {network} from {}",
+                self.network,
+                sources(self.source_network),
+                sources(source_network)
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether an earlier run already claimed the deposit
+    pub fn is_completed(&self, deposit: &PendingDeposit) -> bool {
+        self.outcome(deposit).is_some_and(|outcome| outcome.success)
+    }
+
+    fn outcome(&self, deposit: &PendingDeposit) -> Option<&ClaimOutcome> {
+        self.outcomes.iter().find(|outcome| {
+            outcome.deposit.source_network == deposit.source_network
+                && outcome.deposit.deposit_count == deposit.deposit_count
+        })
+    }
+
+    /// Record the outcome of a claim, replacing the one of an earlier attempt
+    pub fn record(&mut self, outcome: ClaimOutcome) {
+        match self.outcomes.iter_mut().find(|recorded| {
+            recorded.deposit.source_network == outcome.deposit.source_network
+                && recorded.deposit.deposit_count == outcome.deposit.deposit_count
+        }) {
+            Some(recorded) => *recorded = outcome,
+            None => self.outcomes.push(outcome),
+        }
+    }
+
+    /// Deposits claimed and still failing across all runs
+    pub fn totals(&self) -> (usize, usize) {
+        let succeeded = self.outcomes.iter().filter(|o| o.success).count();
+        (succeeded, self.outcomes.len() - succeeded)
+    }
+}
+
+/// Ctrl-C handling of a batch run
+///
+/// The first Ctrl-C asks the run to stop after the item in flight, so its
+/// outcome still reaches the checkpoint; a second one exits immediately.
+pub struct Interrupt {
+    requested: Arc<AtomicBool>,
+    handler: JoinHandle<()>,
+}
+
+impl Interrupt {
+    #[allow(clippy::disallowed_methods)] // Allow std::process::exit on a second Ctrl-C
+    pub fn spawn() -> Self {
+        let requested = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&requested);
+        let handler = tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            flag.store(true, Ordering::SeqCst);
+            ui::ui().warning(
+                "Interrupted; stopping after the claim in flight (press Ctrl-C again to exit now)",
+            );
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(130);
+            }
+        });
+        Self { requested, handler }
+    }
+
+    pub fn requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for Interrupt {
+    fn drop(&mut self) {
+        self.handler.abort();
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn outcome(deposit_count: u64, success: bool) -> ClaimOutcome {
+        ClaimOutcome {
+            deposit: PendingDeposit {
+                source_network: 0,
+                deposit_count,
+                leaf_type: 0,
+                tx_hash: "0xaa".to_string(),
+                amount: "1".to_string(),
+            },
+            success,
+            error: (!success).then(|| "reverted".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_record_replaces_earlier_attempt() {
+        let mut checkpoint = BatchCheckpoint::new(1, Some(0));
+        checkpoint.record(outcome(0, true));
+        checkpoint.record(outcome(1, false));
+        assert_eq!(checkpoint.totals(), (1, 1));
+        assert!(checkpoint.is_completed(&outcome(0, true).deposit));
+        assert!(!checkpoint.is_completed(&outcome(1, true).deposit));
+
+        checkpoint.record(outcome(1, true));
+        assert_eq!(checkpoint.outcomes.len(), 2);
+        assert_eq!(checkpoint.totals(), (2, 0));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoints").join("claim-all-1.json");
+        assert!(BatchCheckpoint::load(&path).is_err());
+
+        let mut checkpoint = BatchCheckpoint::new(1, None);
+        checkpoint.runs = 2;
+        checkpoint.interrupted = true;
+        checkpoint.record(outcome(3, true));
+        checkpoint.save(&path).unwrap();
+
+        let loaded = BatchCheckpoint::load(&path).unwrap();
+        assert_eq!(loaded.runs, 2);
+        assert!(loaded.interrupted);
+        assert!(loaded.is_completed(&outcome(3, false).deposit));
+        assert!(!path.with_extension("json.partial").exists());
+    }
+
+    #[test]
+    fn test_ensure_matches() {
+        let checkpoint = BatchCheckpoint::new(1, Some(0));
+        assert!(checkpoint.ensure_matches(1, Some(0)).is_ok());
+        assert!(checkpoint.ensure_matches(2, Some(0)).is_err());
+        assert!(checkpoint.ensure_matches(1, None).is_err());
+    }
+
+    #[test]
+    fn test_default_path() {
+        assert_eq!(
+            BatchCheckpoint::default_path(1),
+            Path::new(".aggsandbox/checkpoints/claim-all-1.json")
+        );
+    }
+}
@@ -0,0 +1,666 @@
+//! Batch claiming of every unclaimed deposit destined for a network
+//!
+//! Deposits are discovered through the bridge service, filtered with the
+//! on-chain `isClaimed` check on the destination bridge contract and then
+//! claimed one by one (or concurrently with `--parallel`) through the regular
+//! `claim_asset` flow.
+//!
+//! When a claim gas budget is configured (see [`super::claim_budget`]) the
+//! signer's spending is checked before claiming, and before every claim in
+//! sequential mode, so an unattended run stops once the budget is exhausted.
+//!
+//! Every outcome is written to a checkpoint (see [`super::checkpoint`]) so an
+//! interrupted run can be resumed.
+
+use crate::api_client::OptimizedApiClient;
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui;
+use ethers::providers::Middleware;
+use ethers::types::Address;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+use super::checkpoint::{BatchCheckpoint, Interrupt};
+
+use super::claim_asset::{claim_asset, ClaimAssetArgs};
+use super::claim_budget::{enforce_claim_budget, ClaimBudget};
+use super::common::{
+    contract::get_bridge_contract, get_network_name, rpc_error, serialize_json, validate_network_id,
+};
+use super::indexing::warn_if_indexing_lag;
+use super::{get_wallet_with_provider, GasOptions};
+
+/// Arguments for claiming all pending deposits on a network
+///
+/// Use the builder pattern to construct this struct:
+/// ```no_run
+/// # use std::path::PathBuf;
+/// # use aggsandbox::commands::bridge::claim_budget::ClaimBudget;
+/// # use aggsandbox::commands::bridge::ClaimAllArgs;
+/// # use aggsandbox::config::Config;
+/// # fn main() -> aggsandbox::error::Result<()> {
+/// # let config = Config::load()?;
+/// let args = ClaimAllArgs::builder()
+///     .config(&config)
+///     .network(1)
+///     .source_network(Some(0))
+///     .parallel(true)
+///     .budget(ClaimBudget::from_env()?)
+///     .resume(Some(PathBuf::from("claim-all-1.json")))
+///     .build_with_crate_error()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct ClaimAllArgs<'a> {
+    pub config: &'a Config,
+    pub network: u64,
+    pub source_network: Option<u64>,
+    pub parallel: bool,
+    pub gas_options: GasOptions,
+    pub private_key: Option<&'a str>,
+    pub budget: Option<ClaimBudget>,
+    /// File the outcome of every claim is written to
+    pub checkpoint: PathBuf,
+    /// Whether `checkpoint` holds an earlier run to continue
+    pub resume: bool,
+}
+
+impl<'a> ClaimAllArgs<'a> {
+    /// Create a new builder instance
+    pub fn builder() -> ClaimAllArgsBuilder<'a> {
+        ClaimAllArgsBuilder::default()
+    }
+}
+
+/// Builder for constructing ClaimAllArgs with validation
+pub struct ClaimAllArgsBuilder<'a> {
+    config: Option<&'a Config>,
+    network: Option<u64>,
+    source_network: Option<u64>,
+    parallel: bool,
+    gas_options: GasOptions,
+    private_key: Option<&'a str>,
+    budget: Option<ClaimBudget>,
+    checkpoint: Option<PathBuf>,
+    resume: Option<PathBuf>,
+}
+
+impl<'a> Default for ClaimAllArgsBuilder<'a> {
+    fn default() -> Self {
+        Self {
+            config: None,
+            network: None,
+            source_network: None,
+            parallel: false,
+            gas_options: GasOptions::new(None, None),
+            private_key: None,
+            budget: None,
+            checkpoint: None,
+            resume: None,
+        }
+    }
+}
+
+impl<'a> ClaimAllArgsBuilder<'a> {
+    /// Set the configuration
+    pub fn config(mut self, config: &'a Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Set the network to claim on
+    pub fn network(mut self, network: u64) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Only claim deposits made on this source network (default: all other networks)
+    pub fn source_network(mut self, source_network: Option<u64>) -> Self {
+        self.source_network = source_network;
+        self
+    }
+
+    /// Submit claims concurrently instead of one after another
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Set gas options
+    pub fn gas_options(mut self, gas_options: GasOptions) -> Self {
+        self.gas_options = gas_options;
+        self
+    }
+
+    /// Set private key for signing transactions
+    pub fn private_key(mut self, private_key: &'a str) -> Self {
+        self.private_key = Some(private_key);
+        self
+    }
+
+    /// Stop claiming once the signer has spent this much on claim gas
+    pub fn budget(mut self, budget: Option<ClaimBudget>) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Write the checkpoint here instead of `.aggsandbox/checkpoints/claim-all-<id>.json`
+    pub fn checkpoint(mut self, checkpoint: Option<PathBuf>) -> Self {
+        self.checkpoint = checkpoint;
+        self
+    }
+
+    /// Continue the run recorded in this checkpoint, updating it as claims complete
+    pub fn resume(mut self, resume: Option<PathBuf>) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Build the ClaimAllArgs with validation
+    pub fn build(self) -> std::result::Result<ClaimAllArgs<'a>, &'static str> {
+        let config = self.config.ok_or("Config is required")?;
+        let network = self.network.ok_or("Network is required")?;
+
+        if self.source_network == Some(network) {
+            return Err("Source and destination networks must be different");
+        }
+        if self.checkpoint.is_some() && self.resume.is_some() {
+            return Err("A resumed run updates the checkpoint it resumes; drop --checkpoint");
+        }
+        let resume = self.resume.is_some();
+        let checkpoint = self
+            .resume
+            .or(self.checkpoint)
+            .unwrap_or_else(|| BatchCheckpoint::default_path(network));
+
+        Ok(ClaimAllArgs {
+            config,
+            network,
+            source_network: self.source_network,
+            parallel: self.parallel,
+            gas_options: self.gas_options,
+            private_key: self.private_key,
+            budget: self.budget,
+            checkpoint,
+            resume,
+        })
+    }
+
+    /// Build and convert to crate's Result type
+    pub fn build_with_crate_error(self) -> Result<ClaimAllArgs<'a>> {
+        self.build().map_err(|e| {
+            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(e))
+        })
+    }
+}
+
+/// A deposit destined for the claim network, as reported by the bridge service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingDeposit {
+    pub source_network: u64,
+    pub deposit_count: u64,
+    pub leaf_type: u8,
+    pub tx_hash: String,
+    pub amount: String,
+}
+
+/// Outcome of a single claim attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimOutcome {
+    #[serde(flatten)]
+    pub deposit: PendingDeposit,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Summary of a claim-all run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClaimAllSummary {
+    pub network: u64,
+    pub already_claimed: usize,
+    pub attempted: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<ClaimOutcome>,
+    /// Whether Ctrl-C stopped the run before every deposit was attempted
+    pub interrupted: bool,
+    pub checkpoint: String,
+    /// Runs recorded in the checkpoint, including this one
+    pub runs: u32,
+    /// Deposits claimed across all runs
+    pub total_succeeded: usize,
+    /// Deposits whose latest claim attempt failed, across all runs
+    pub total_failed: usize,
+}
+
+/// Extract deposits destined for `destination_network` from a bridges API response
+///
+/// Deposits are returned in deposit_count order so that asset leaves created by
+/// bridgeAndCall are claimed before their companion message leaves.
+pub fn pending_deposits_from_response(
+    response: &serde_json::Value,
+    source_network: u64,
+    destination_network: u64,
+) -> Vec<PendingDeposit> {
+    let mut deposits: Vec<PendingDeposit> = response["bridges"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|bridge| bridge["destination_network"].as_u64() == Some(destination_network))
+        .filter_map(|bridge| {
+            Some(PendingDeposit {
+                source_network,
+                deposit_count: bridge["deposit_count"].as_u64()?,
+                leaf_type: bridge["leaf_type"].as_u64().unwrap_or(0) as u8,
+                tx_hash: bridge["bridge_tx_hash"].as_str()?.to_string(),
+                amount: bridge["amount"].as_str().unwrap_or("0").to_string(),
+            })
+        })
+        .collect();
+
+    deposits.sort_by_key(|deposit| deposit.deposit_count);
+    deposits.dedup_by_key(|deposit| deposit.deposit_count);
+    deposits
+}
+
+/// Claim every unclaimed deposit destined for a network and print a summary
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn claim_all(args: ClaimAllArgs<'_>) -> Result<ClaimAllSummary> {
+    validate_network_id(args.network, "Network")?;
+    if let Some(source) = args.source_network {
+        validate_network_id(source, "Source network")?;
+    }
+
+    let sponsor = match args.budget {
+        Some(_) => Some(
+            get_wallet_with_provider(args.config, args.network, args.private_key)
+                .await?
+                .inner()
+                .address(),
+        ),
+        None => None,
+    };
+    enforce_claim_budget(args.config, args.network, sponsor, args.budget.as_ref()).await?;
+
+    let mut checkpoint = if args.resume {
+        let checkpoint = BatchCheckpoint::load(&args.checkpoint)?;
+        checkpoint.ensure_matches(args.network, args.source_network)?;
+        let (succeeded, failed) = checkpoint.totals();
+        ui::ui().info(&format!(
+            "Resuming {} ({} run(s): {succeeded} claimed, {failed} failed)",
+            args.checkpoint.display(),
+            checkpoint.runs
+        ));
+        checkpoint
+    } else {
+        BatchCheckpoint::new(args.network, args.source_network)
+    };
+    checkpoint.runs += 1;
+    checkpoint.interrupted = false;
+
+    let (mut pending, mut already_claimed) = find_unclaimed_deposits(&args).await?;
+    let before = pending.len();
+    pending.retain(|deposit| !checkpoint.is_completed(deposit));
+    already_claimed += before - pending.len();
+    info!(
+        network = args.network,
+        pending = pending.len(),
+        already_claimed = already_claimed,
+        "Found unclaimed deposits"
+    );
+
+    if pending.is_empty() {
+        ui::ui().info(&format!(
+            "No unclaimed deposits destined for network {} ({})",
+            args.network,
+            get_network_name(args.network)
+        ));
+    } else {
+        ui::ui().info(&format!(
+            "📦 Claiming {} deposit(s) on network {} ({}){}",
+            pending.len(),
+            args.network,
+            get_network_name(args.network),
+            if args.parallel { " in parallel" } else { "" }
+        ));
+    }
+
+    save_checkpoint(&mut checkpoint, &args.checkpoint);
+    let interrupt = Interrupt::spawn();
+    let to_claim = pending.len();
+    let results = if args.parallel {
+        claim_parallel(&args, pending, &mut checkpoint).await
+    } else {
+        claim_sequential(&args, pending, sponsor, &mut checkpoint, &interrupt).await
+    };
+    checkpoint.interrupted = interrupt.requested() && results.len() < to_claim;
+    save_checkpoint(&mut checkpoint, &args.checkpoint);
+
+    let succeeded = results.iter().filter(|result| result.success).count();
+    let (total_succeeded, total_failed) = checkpoint.totals();
+    let summary = ClaimAllSummary {
+        network: args.network,
+        already_claimed,
+        attempted: results.len(),
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+        interrupted: checkpoint.interrupted,
+        checkpoint: args.checkpoint.display().to_string(),
+        runs: checkpoint.runs,
+        total_succeeded,
+        total_failed,
+    };
+
+    print_summary(&summary)?;
+    Ok(summary)
+}
+
+/// Write the checkpoint; a failure is reported but does not stop the claims
+fn save_checkpoint(checkpoint: &mut BatchCheckpoint, path: &std::path::Path) {
+    if let Err(e) = checkpoint.save(path) {
+        ui::ui().warning(&format!(
+            "Failed to write checkpoint {}: {e}",
+            path.display()
+        ));
+    }
+}
+
+/// Query the bridge service for deposits to the claim network and drop those already claimed on-chain
+async fn find_unclaimed_deposits(args: &ClaimAllArgs<'_>) -> Result<(Vec<PendingDeposit>, usize)> {
+    let api_client = OptimizedApiClient::global();
+    api_client.invalidate("bridges", None).await;
+
+    let source_networks: Vec<u64> = match args.source_network {
+        Some(source) => vec![source],
+        None => {
+            let mut networks = args.config.networks.network_ids();
+            networks.retain(|network| *network != args.network);
+            networks
+        }
+    };
+
+    let bridge = get_bridge_contract(args.config, args.network, args.private_key).await?;
+    let mut pending = Vec::new();
+    let mut already_claimed = 0;
+
+    for source_network in source_networks {
+        warn_if_indexing_lag(args.config, source_network).await;
+        let response = api_client.get_bridges(args.config, source_network).await?;
+
+        for deposit in pending_deposits_from_response(&response, source_network, args.network) {
+            let claimed = bridge
+                .is_claimed(deposit.deposit_count as u32, source_network as u32)
+                .call()
+                .await
+                .map_err(|e| rpc_error("check claim status", e))?;
+
+            if claimed {
+                debug!(
+                    source_network,
+                    deposit_count = deposit.deposit_count,
+                    "Deposit already claimed, skipping"
+                );
+                already_claimed += 1;
+            } else {
+                pending.push(deposit);
+            }
+        }
+    }
+
+    Ok((pending, already_claimed))
+}
+
+/// Claim a single deposit through the regular claim flow
+async fn claim_deposit(
+    config: &Config,
+    network: u64,
+    deposit: &PendingDeposit,
+    gas_options: GasOptions,
+    private_key: Option<&str>,
+) -> Result<()> {
+    let mut builder = ClaimAssetArgs::builder()
+        .config(config)
+        .network(network)
+        .tx_hash(&deposit.tx_hash)
+        .source_network(deposit.source_network)
+        .deposit_count(Some(deposit.deposit_count))
+        .gas_options(gas_options);
+    if let Some(key) = private_key {
+        builder = builder.private_key(key);
+    }
+
+    claim_asset(builder.build_with_crate_error()?).await
+}
+
+/// Claim deposits one after another in deposit_count order
+async fn claim_sequential(
+    args: &ClaimAllArgs<'_>,
+    pending: Vec<PendingDeposit>,
+    sponsor: Option<Address>,
+    checkpoint: &mut BatchCheckpoint,
+    interrupt: &Interrupt,
+) -> Vec<ClaimOutcome> {
+    let mut results = Vec::with_capacity(pending.len());
+    for deposit in pending {
+        if interrupt.requested() {
+            break;
+        }
+        if !results.is_empty() {
+            if let Err(e) =
+                enforce_claim_budget(args.config, args.network, sponsor, args.budget.as_ref()).await
+            {
+                ui::ui().warning(&format!("Stopping claim-all: {e}"));
+                break;
+            }
+        }
+
+        let result = claim_deposit(
+            args.config,
+            args.network,
+            &deposit,
+            args.gas_options.clone(),
+            args.private_key,
+        )
+        .await;
+        let outcome = ClaimOutcome {
+            success: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+            deposit,
+        };
+        checkpoint.record(outcome.clone());
+        save_checkpoint(checkpoint, &args.checkpoint);
+        results.push(outcome);
+    }
+    results
+}
+
+/// Claim deposits concurrently, keeping results in deposit_count order
+///
+/// Claims already submitted cannot be called back, so an interrupt only
+/// takes effect once they all completed.
+async fn claim_parallel(
+    args: &ClaimAllArgs<'_>,
+    pending: Vec<PendingDeposit>,
+    checkpoint: &mut BatchCheckpoint,
+) -> Vec<ClaimOutcome> {
+    let config = Arc::new(args.config.clone());
+    let private_key = args.private_key.map(str::to_string);
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for (position, deposit) in pending.into_iter().enumerate() {
+        let config = Arc::clone(&config);
+        let gas_options = args.gas_options.clone();
+        let private_key = private_key.clone();
+        let network = args.network;
+        tasks.spawn(async move {
+            let result = claim_deposit(
+                &config,
+                network,
+                &deposit,
+                gas_options,
+                private_key.as_deref(),
+            )
+            .await;
+            let outcome = ClaimOutcome {
+                success: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+                deposit,
+            };
+            (position, outcome)
+        });
+    }
+
+    let mut results = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((position, outcome)) => {
+                checkpoint.record(outcome.clone());
+                save_checkpoint(checkpoint, &args.checkpoint);
+                results.push((position, outcome));
+            }
+            Err(e) => debug!(error = %e, "Claim task panicked"),
+        }
+    }
+    results.sort_by_key(|(position, _)| *position);
+    results.into_iter().map(|(_, outcome)| outcome).collect()
+}
+
+/// Print the claim summary as a table, or as JSON in JSON output mode
+fn print_summary(summary: &ClaimAllSummary) -> Result<()> {
+    if ui::ui().is_json() {
+        let json_str = serialize_json(summary)?;
+        ui::ui().json(&serde_json::from_str::<serde_json::Value>(&json_str).unwrap_or_default());
+        return Ok(());
+    }
+
+    let labels: Vec<String> = summary
+        .results
+        .iter()
+        .map(|result| {
+            format!(
+                "#{} from {} ({})",
+                result.deposit.deposit_count,
+                result.deposit.source_network,
+                get_network_name(result.deposit.source_network)
+            )
+        })
+        .collect();
+    let statuses: Vec<String> = summary
+        .results
+        .iter()
+        .map(|result| match &result.error {
+            None => format!("✅ claimed ({})", leaf_type_name(result.deposit.leaf_type)),
+            Some(error) => format!("❌ {error}"),
+        })
+        .collect();
+    let totals = format!(
+        "{} claimed, {} failed, {} already claimed",
+        summary.succeeded, summary.failed, summary.already_claimed
+    );
+
+    let mut rows: Vec<(&str, &str)> = labels
+        .iter()
+        .zip(&statuses)
+        .map(|(label, status)| (label.as_str(), status.as_str()))
+        .collect();
+    rows.push(("Total", totals.as_str()));
+    let across_runs = format!(
+        "{} claimed, {} failed across {} runs",
+        summary.total_succeeded, summary.total_failed, summary.runs
+    );
+    if summary.runs > 1 {
+        rows.push(("All runs", across_runs.as_str()));
+    }
+    rows.push(("Checkpoint", summary.checkpoint.as_str()));
+    ui::ui().table("📋 Claim Summary", &rows);
+
+    if summary.interrupted {
+        ui::ui().tip(&format!(
+            "Continue with 'aggsandbox bridge claim-all --network-id {} --resume {}'",
+            summary.network, summary.checkpoint
+        ));
+    }
+
+    Ok(())
+}
+
+fn leaf_type_name(leaf_type: u8) -> &'static str {
+    if leaf_type == 1 {
+        "message"
+    } else {
+        "asset"
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_pending_deposits_filtered_and_ordered() {
+        let response = json!({
+            "bridges": [
+                {"deposit_count": 3, "leaf_type": 1, "destination_network": 1, "bridge_tx_hash": "0xbb", "amount": "0"},
+                {"deposit_count": 2, "leaf_type": 0, "destination_network": 1, "bridge_tx_hash": "0xbb", "amount": "10"},
+                {"deposit_count": 1, "leaf_type": 0, "destination_network": 2, "bridge_tx_hash": "0xaa", "amount": "5"},
+                {"deposit_count": 0, "leaf_type": 0, "destination_network": 1, "amount": "5"}
+            ]
+        });
+
+        let deposits = pending_deposits_from_response(&response, 0, 1);
+
+        assert_eq!(deposits.len(), 2);
+        assert_eq!(deposits[0].deposit_count, 2);
+        assert_eq!(deposits[0].leaf_type, 0);
+        assert_eq!(deposits[1].deposit_count, 3);
+        assert_eq!(deposits[1].leaf_type, 1);
+        assert!(deposits.iter().all(|deposit| deposit.source_network == 0));
+    }
+
+    #[test]
+    fn test_builder_rejects_same_source_and_destination() {
+        let config = Config::default();
+        let result = ClaimAllArgs::builder()
+            .config(&config)
+            .network(1)
+            .source_network(Some(1))
+            .build();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_builder_checkpoint_paths() {
+        let config = Config::default();
+        let args = ClaimAllArgs::builder()
+            .config(&config)
+            .network(1)
+            .build()
+            .unwrap();
+        assert_eq!(args.checkpoint, BatchCheckpoint::default_path(1));
+        assert!(!args.resume);
+
+        let args = ClaimAllArgs::builder()
+            .config(&config)
+            .network(1)
+            .resume(Some(PathBuf::from("run.json")))
+            .build()
+            .unwrap();
+        assert_eq!(args.checkpoint, PathBuf::from("run.json"));
+        assert!(args.resume);
+
+        assert!(ClaimAllArgs::builder()
+            .config(&config)
+            .network(1)
+            .checkpoint(Some(PathBuf::from("a.json")))
+            .resume(Some(PathBuf::from("b.json")))
+            .build()
+            .is_err());
+    }
+}
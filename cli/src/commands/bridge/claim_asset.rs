@@ -1,22 +1,38 @@
 use crate::api_client::OptimizedApiClient;
 use crate::config::Config;
 use crate::error::Result;
+use crate::types::BridgeDeposit;
 use crate::ui;
 use ethers::prelude::*;
-use ethers::signers::LocalWallet;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::debug;
 
+use super::known_issues;
+use super::local_index::{self, DataSource};
+use super::proof::{print_claim_path, verify_claim_proof};
+use super::proof_wait::{self, AutoWait};
+use super::receipt;
+use super::timing::{Stage, StageTimer};
+use super::token_metadata::cached_token_metadata;
+use super::utilities::{compute_global_index, ClaimPayload, ComputeGlobalIndexArgs};
 use super::{
-    common::validation_error, get_bridge_contract_address, get_wallet_with_provider,
-    BridgeContract, ERC20Contract, GasOptions,
+    common::{get_network_name, is_dry_run, rpc_error, send_or_simulate, validation_error},
+    frontrun, get_bridge_contract_address, get_bridge_extension_address, get_wallet_with_provider,
+    message_preflight, BridgeContract, GasOptions, SandboxClient,
 };
 
 /// Arguments for claiming bridged assets
 ///
 /// Use the builder pattern to construct this struct:
-/// ```rust
+/// ```no_run
+/// # use aggsandbox::commands::bridge::local_index::DataSource;
+/// # use aggsandbox::commands::bridge::proof_wait::AutoWait;
+/// # use aggsandbox::commands::bridge::{ClaimAssetArgs, GasOptions};
+/// # use aggsandbox::config::Config;
+/// # fn main() -> aggsandbox::error::Result<()> {
+/// # let config = Config::load()?;
+/// # let gas_options = GasOptions::new(None, None);
 /// let args = ClaimAssetArgs::builder()
 ///     .config(&config)
 ///     .network(1)
@@ -35,7 +51,12 @@ use super::{
 ///     .gas_options(gas_options)
 ///     .private_key("0x1234567890123456789012345678901234567890123456789012345678901234")
 ///     .custom_data(Some("0x12345678"))
+///     .source(DataSource::Local)
+///     .verify_proof(true)
+///     .auto_wait(Some(AutoWait::new(300, 3)))
 ///     .build_with_crate_error()?;
+/// # Ok(())
+/// # }
 /// ```
 pub struct ClaimAssetArgs<'a> {
     pub config: &'a Config,
@@ -49,6 +70,14 @@ pub struct ClaimAssetArgs<'a> {
     pub private_key: Option<&'a str>,
     pub custom_data: Option<&'a str>,
     pub msg_value: Option<&'a str>,
+    /// Where the deposit is looked up
+    pub source: DataSource,
+    /// Verify the proof against the on-chain exit roots before claiming
+    pub verify_proof: bool,
+    /// Poll until the claim proof is available before claiming
+    pub auto_wait: Option<AutoWait>,
+    /// Claim BridgeExtension messages whose call would revert and pay the fallback address
+    pub allow_fallback: bool,
 }
 
 impl<'a> ClaimAssetArgs<'a> {
@@ -109,6 +138,10 @@ pub struct ClaimAssetArgsBuilder<'a> {
     private_key: Option<&'a str>,
     custom_data: Option<&'a str>,
     msg_value: Option<&'a str>,
+    source: DataSource,
+    verify_proof: bool,
+    auto_wait: Option<AutoWait>,
+    allow_fallback: bool,
 }
 
 impl<'a> Default for ClaimAssetArgsBuilder<'a> {
@@ -124,6 +157,10 @@ impl<'a> Default for ClaimAssetArgsBuilder<'a> {
             private_key: None,
             custom_data: None,
             msg_value: None,
+            source: DataSource::Api,
+            verify_proof: false,
+            auto_wait: None,
+            allow_fallback: false,
         }
     }
 }
@@ -209,6 +246,30 @@ impl<'a> ClaimAssetArgsBuilder<'a> {
         self
     }
 
+    /// Look the deposit up in the bridge service or the local index
+    pub fn source(mut self, source: DataSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Verify the claim proof against the on-chain exit roots before claiming
+    pub fn verify_proof(mut self, verify_proof: bool) -> Self {
+        self.verify_proof = verify_proof;
+        self
+    }
+
+    /// Wait for the deposit to become claimable instead of failing right away
+    pub fn auto_wait(mut self, auto_wait: Option<AutoWait>) -> Self {
+        self.auto_wait = auto_wait;
+        self
+    }
+
+    /// Claim messages even if the pre-flight check finds their call would revert
+    pub fn allow_fallback(mut self, allow_fallback: bool) -> Self {
+        self.allow_fallback = allow_fallback;
+        self
+    }
+
     /// Build the ClaimAssetArgs with validation
     pub fn build(self) -> std::result::Result<ClaimAssetArgs<'a>, &'static str> {
         let config = self.config.ok_or("Config is required")?;
@@ -254,6 +315,10 @@ impl<'a> ClaimAssetArgsBuilder<'a> {
             private_key: self.private_key,
             custom_data: self.custom_data,
             msg_value: self.msg_value,
+            source: self.source,
+            verify_proof: self.verify_proof,
+            auto_wait: self.auto_wait,
+            allow_fallback: self.allow_fallback,
         })
     }
 
@@ -264,13 +329,17 @@ impl<'a> ClaimAssetArgsBuilder<'a> {
 }
 
 /// Claim bridged assets on destination network
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
 pub async fn claim_asset(args: ClaimAssetArgs<'_>) -> Result<()> {
+    let mut timer = StageTimer::start();
     let client = get_wallet_with_provider(args.config, args.network, args.private_key).await?;
     let bridge_address = get_bridge_contract_address(args.config, args.network)?;
-    let bridge = BridgeContract::new(bridge_address, Arc::new(client.clone()));
+    let bridge = BridgeContract::new(bridge_address, Arc::new(client));
     let api_client = OptimizedApiClient::global();
 
     tracing::debug!("Looking for bridge transaction with hash: {}", args.tx_hash);
+    let tx_hash = H256::from_str(args.tx_hash)
+        .map_err(|e| validation_error(&format!("Invalid transaction hash: {e}")))?;
 
     // For bridge-back scenarios (L2→L1), we need special logic:
     // - The bridge transaction is on the intermediate network (L2)
@@ -280,21 +349,21 @@ pub async fn claim_asset(args: ClaimAssetArgs<'_>) -> Result<()> {
     {
         // Potential bridge-back scenario: L2→L1 claim
         // First try to find the transaction on L2 (network 1)
-        let l2_bridges = api_client.get_bridges(args.config, 1).await.ok();
-        if let Some(l2_response) = l2_bridges {
-            if let Some(bridges) = l2_response["bridges"].as_array() {
-                if bridges
-                    .iter()
-                    .any(|bridge| bridge["bridge_tx_hash"].as_str() == Some(args.tx_hash))
-                {
-                    tracing::debug!("Detected bridge-back scenario: transaction found on L2, using L2 for proof data");
-                    (1u64, 1u64) // Bridge tx is on L2, proof data from L2
-                } else {
-                    (args.source_network, args.source_network) // Normal scenario
-                }
-            } else {
-                (args.source_network, args.source_network) // Normal scenario
-            }
+        let l2_bridges = timer
+            .time(
+                Stage::ApiLookup,
+                local_index::get_bridges(args.config, 1, args.source),
+            )
+            .await
+            .ok();
+        let found_on_l2 = l2_bridges
+            .and_then(|response| BridgeDeposit::list_from(&response).ok())
+            .is_some_and(|deposits| !deposits_of_tx(&deposits, tx_hash).is_empty());
+        if found_on_l2 {
+            tracing::debug!(
+                "Detected bridge-back scenario: transaction found on L2, using L2 for proof data"
+            );
+            (1u64, 1u64) // Bridge tx is on L2, proof data from L2
         } else {
             (args.source_network, args.source_network) // Normal scenario
         }
@@ -302,283 +371,235 @@ pub async fn claim_asset(args: ClaimAssetArgs<'_>) -> Result<()> {
         (args.source_network, args.source_network) // Normal scenario
     };
 
+    if args.source == DataSource::Api {
+        timer
+            .time(
+                Stage::ApiLookup,
+                super::indexing::warn_if_indexing_lag(args.config, bridge_tx_network),
+            )
+            .await;
+    }
+
+    if let Some(auto_wait) = args.auto_wait {
+        timer
+            .time(
+                Stage::ApiLookup,
+                proof_wait::wait_until_claimable(
+                    args.config,
+                    bridge_tx_network,
+                    proof_source_network,
+                    args.tx_hash,
+                    args.deposit_count,
+                    args.source,
+                    auto_wait,
+                ),
+            )
+            .await?;
+    }
+
     // Get bridges from the network where the transaction actually occurred
-    let bridges_response = api_client
-        .get_bridges(args.config, bridge_tx_network)
-        .await
-        .map_err(|e| validation_error(&format!("Failed to get bridges: {e}")))?;
+    let bridges_response = timer
+        .time(
+            Stage::ApiLookup,
+            local_index::get_bridges(args.config, bridge_tx_network, args.source),
+        )
+        .await?;
 
-    let bridges = bridges_response["bridges"]
-        .as_array()
-        .ok_or_else(|| validation_error("Invalid bridges response"))?;
+    let deposits = BridgeDeposit::list_from(&bridges_response)?;
 
     // Find our bridge transaction
     // For bridge-and-call operations, we need to handle multiple bridges in the same transaction
-    let bridge_info = if let Some(specific_deposit_count) = args.deposit_count {
+    let matching_deposits = deposits_of_tx(&deposits, tx_hash);
+    tracing::debug!(
+        "Found {} bridges with tx_hash {}",
+        matching_deposits.len(),
+        args.tx_hash
+    );
+    let deposit = if let Some(specific_deposit_count) = args.deposit_count {
         tracing::debug!(
             "Looking for bridge with tx_hash: {} and deposit_count: {specific_deposit_count}",
             args.tx_hash
         );
-        bridges
-            .iter()
-            .find(|bridge| {
-                bridge["bridge_tx_hash"].as_str() == Some(args.tx_hash)
-                    && bridge["deposit_count"].as_u64() == Some(specific_deposit_count)
-            })
+        matching_deposits
+            .into_iter()
+            .find(|deposit| deposit.deposit_count == specific_deposit_count)
             .ok_or_else(|| {
-                crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                    &format!(
-                        "Bridge transaction {} with deposit_count {specific_deposit_count} not found",
-                        args.tx_hash
-                    ),
+                validation_error(&format!(
+                    "Bridge transaction {} with deposit_count {specific_deposit_count} not found",
+                    args.tx_hash
                 ))
             })?
     } else {
-        tracing::debug!(
-            "Looking for bridge with tx_hash: {} (deposit_count auto-detected)",
-            args.tx_hash
-        );
-
-        // Get all bridges with this transaction hash
-        let matching_bridges: Vec<_> = bridges
-            .iter()
-            .filter(|bridge| bridge["bridge_tx_hash"].as_str() == Some(args.tx_hash))
-            .collect();
-
-        tracing::debug!(
-            "Found {} bridges with tx_hash {}",
-            matching_bridges.len(),
-            args.tx_hash
-        );
-        for (i, bridge) in matching_bridges.iter().enumerate() {
-            tracing::debug!(
-                "Bridge {i}: deposit_count={}, leaf_type={}",
-                bridge["deposit_count"].as_u64().unwrap_or(999),
-                bridge["leaf_type"].as_u64().unwrap_or(999)
-            );
-        }
-
-        if matching_bridges.is_empty() {
-            return Err(crate::error::AggSandboxError::Config(
-                crate::error::ConfigError::validation_failed(&format!(
+        match matching_deposits.as_slice() {
+            [] => {
+                return Err(validation_error(&format!(
                     "Bridge transaction {} not found",
                     args.tx_hash
-                )),
-            ));
-        }
-
-        // If multiple bridges found (bridge-and-call), default to the asset bridge
-        if matching_bridges.len() > 1 {
-            tracing::debug!(
-                "Multiple bridges found for tx_hash {} (bridge-and-call operation)",
-                args.tx_hash
-            );
-            for bridge in &matching_bridges {
+                )))
+            }
+            [deposit] => *deposit,
+            // Bridge-and-call transactions make an asset and a message deposit
+            several => {
+                for deposit in several {
+                    tracing::debug!(
+                        "Bridge: deposit_count={}, leaf_type={}",
+                        deposit.deposit_count,
+                        deposit.leaf_type
+                    );
+                }
                 tracing::debug!(
-                    "Bridge: deposit_count={}, leaf_type={} ({})",
-                    bridge["deposit_count"].as_u64().unwrap_or(999),
-                    bridge["leaf_type"].as_u64().unwrap_or(999),
-                    if bridge["leaf_type"].as_u64() == Some(0) {
-                        "Asset"
-                    } else {
-                        "Message"
-                    }
+                    "Defaulting to asset bridge (leaf_type=0). Use --deposit-count to specify."
                 );
-            }
-            tracing::debug!(
-                "Defaulting to asset bridge (leaf_type=0). Use --deposit-count to specify."
-            );
-
-            // Default to asset bridge (leaf_type = 0)
-            matching_bridges
-                .iter()
-                .find(|bridge| bridge["leaf_type"].as_u64() == Some(0))
-                .ok_or_else(|| {
-                    crate::error::AggSandboxError::Config(
-                        crate::error::ConfigError::validation_failed(&format!(
+                several
+                    .iter()
+                    .copied()
+                    .find(|deposit| deposit.is_asset())
+                    .ok_or_else(|| {
+                        validation_error(&format!(
                             "No asset bridge found in bridge-and-call transaction {}",
                             args.tx_hash
-                        )),
-                    )
-                })?
-        } else {
-            matching_bridges[0]
+                        ))
+                    })?
+            }
         }
     };
 
-    let deposit_count = bridge_info["deposit_count"].as_u64().ok_or_else(|| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            "Missing deposit_count in bridge info",
-        ))
-    })?;
-
+    let deposit_count = deposit.deposit_count;
     tracing::debug!("Found bridge with deposit count: {deposit_count}");
 
-    // Determine bridge type from bridge info
-    let leaf_type = bridge_info["leaf_type"].as_u64().unwrap_or(0) as u8;
+    let leaf_type = deposit.leaf_type;
     tracing::debug!("Bridge leaf type: {leaf_type} (0=Asset, 1=Message)");
 
-    // Get L1 info tree index from the proof source network
-    // For bridge-back scenarios, this uses L2 (where the bridge tx occurred)
-    let tree_index_response = api_client
-        .get_l1_info_tree_index(args.config, proof_source_network, deposit_count)
+    // Refuse to claim on a bridge that is not the deposit's destination
+    let contract_network_id = bridge
+        .network_id()
+        .call()
         .await
-        .map_err(|e| {
-            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                &format!("Failed to get L1 info tree index: {e}"),
-            ))
-        })?;
+        .map_err(|e| rpc_error("get bridge network ID", e))?;
+    let reported_destination = known_issues::trusted_destination(deposit, bridge_tx_network);
+    check_claim_network(
+        contract_network_id,
+        args.network,
+        reported_destination,
+        bridge_tx_network,
+    )?;
 
-    let leaf_index = tree_index_response["l1_info_tree_index"]
-        .as_u64()
-        .unwrap_or(tree_index_response.as_u64().unwrap_or(0));
+    // Get L1 info tree index from the proof source network
+    // For bridge-back scenarios, this uses L2 (where the bridge tx occurred)
+    let leaf_index = timer
+        .time(
+            Stage::ApiLookup,
+            api_client.get_typed_l1_info_tree_index(
+                args.config,
+                proof_source_network,
+                deposit_count,
+            ),
+        )
+        .await?;
 
     tracing::debug!("L1 info tree index: {leaf_index}");
 
     // Get claim proof from the proof source network
     // For bridge-back scenarios, this uses L2 (where the bridge tx occurred)
-    let proof_response = api_client
-        .get_claim_proof(args.config, proof_source_network, leaf_index, deposit_count)
-        .await
-        .map_err(|e| {
-            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                &format!("Failed to get claim proof: {e}"),
-            ))
-        })?;
-
-    let l1_info_tree_leaf = &proof_response["l1_info_tree_leaf"];
-    let mainnet_exit_root = l1_info_tree_leaf["mainnet_exit_root"]
-        .as_str()
-        .ok_or_else(|| {
-            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                "Missing mainnet_exit_root in proof",
-            ))
-        })?;
-
-    let rollup_exit_root = l1_info_tree_leaf["rollup_exit_root"]
-        .as_str()
-        .ok_or_else(|| {
-            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                "Missing rollup_exit_root in proof",
-            ))
-        })?;
+    let proof = timer
+        .time(
+            Stage::ProofFetch,
+            api_client.get_typed_claim_proof(
+                args.config,
+                proof_source_network,
+                leaf_index,
+                deposit_count,
+            ),
+        )
+        .await?;
+    let mainnet_root = proof.l1_info_tree_leaf.mainnet_exit_root;
+    let rollup_root = proof.l1_info_tree_leaf.rollup_exit_root;
 
     tracing::debug!("Got claim proof data");
 
     // Extract bridge parameters
-    let origin_network = bridge_info["origin_network"]
-        .as_u64()
-        .map(|n| n as u32)
-        .unwrap_or_else(|| args.source_network as u32);
-    let destination_network_id = bridge_info["destination_network"]
-        .as_u64()
+    let origin_network = deposit.origin_network.unwrap_or(args.source_network as u32);
+    let destination_network_id = reported_destination
         .map(|n| n as u32)
         .unwrap_or_else(|| args.network as u32);
+    let origin_addr = deposit.origin_address;
+    let dest_addr = deposit.destination_address;
+    let amount_wei = deposit.amount;
 
-    // For both message and asset bridges, use the addresses from bridge data
-    let origin_addr = bridge_info["origin_address"].as_str().ok_or_else(|| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            "Missing origin_address in bridge info",
-        ))
-    })?;
-    let dest_addr = bridge_info["destination_address"].as_str().ok_or_else(|| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            "Missing destination_address in bridge info",
-        ))
-    })?;
-
-    let bridge_type = if leaf_type == 1 { "message" } else { "asset" };
-    tracing::debug!("Using bridge addresses for {bridge_type} bridge:");
-    tracing::debug!("Origin: {origin_addr} (network {})", args.source_network);
-    tracing::debug!("Destination: {dest_addr} (network {})", args.network);
-
-    let (origin_address, destination_address) = (origin_addr.to_string(), dest_addr.to_string());
-    let amount = bridge_info["amount"].as_str().ok_or_else(|| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            "Missing amount in bridge info",
-        ))
-    })?;
-    let metadata = if let Some(custom) = args.custom_data {
-        tracing::debug!("Using custom metadata: {custom}");
-        custom
+    let bridge_type = if deposit.is_asset() {
+        "asset"
     } else {
-        bridge_info["metadata"].as_str().unwrap_or("0x")
+        "message"
+    };
+    tracing::debug!("Using bridge addresses for {bridge_type} bridge:");
+    tracing::debug!("Origin: {origin_addr:#x} (network {})", args.source_network);
+    tracing::debug!("Destination: {dest_addr:#x} (network {})", args.network);
+
+    let metadata = match args.custom_data {
+        Some(custom) => {
+            tracing::debug!("Using custom metadata: {custom}");
+            hex::decode(custom.trim_start_matches("0x"))
+                .map_err(|e| validation_error(&format!("Invalid metadata hex: {e}")))?
+        }
+        None => deposit.metadata.clone(),
     };
-
-    // Convert addresses and amount
-    let origin_addr = Address::from_str(&origin_address).map_err(|e| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            &format!("Invalid origin address: {e}"),
-        ))
-    })?;
-    let dest_addr = Address::from_str(&destination_address).map_err(|e| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            &format!("Invalid destination address: {e}"),
-        ))
-    })?;
-    let amount_wei = U256::from_dec_str(amount).map_err(|e| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            &format!("Invalid amount: {e}"),
-        ))
-    })?;
-    let mainnet_root = H256::from_str(mainnet_exit_root).map_err(|e| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            &format!("Invalid mainnet exit root: {e}"),
-        ))
-    })?;
-    let rollup_root = H256::from_str(rollup_exit_root).map_err(|e| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            &format!("Invalid rollup exit root: {e}"),
-        ))
-    })?;
 
     // Encode ERC20 token metadata properly for claimAsset
-    let metadata_bytes = if !metadata.is_empty() && metadata != "0x" {
+    let metadata_bytes = if !metadata.is_empty() {
         // Use metadata from API if available
-        hex::decode(metadata.trim_start_matches("0x")).map_err(|e| {
-            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-                &format!("Invalid metadata hex: {e}"),
-            ))
-        })?
+        metadata
+    } else if !deposit.is_asset() {
+        // Message leaves are hashed with their metadata exactly as bridged
+        Vec::new()
     } else {
         // For first-time bridges, fetch and encode ERC20 token details
-        let source_client =
-            get_wallet_with_provider(args.config, proof_source_network, None).await?;
-        let token_contract = ERC20Contract::new(origin_addr, Arc::new(source_client));
-
-        // Fetch token details
-        let token_name = token_contract
-            .name()
-            .call()
-            .await
-            .unwrap_or_else(|_| "AggERC20".to_string());
-        let token_symbol = token_contract
-            .symbol()
-            .call()
-            .await
-            .unwrap_or_else(|_| "AGGERC20".to_string());
-        let token_decimals = token_contract.decimals().call().await.unwrap_or(18u8);
-
-        tracing::info!(
-            "Encoding ERC20 metadata: name={token_name}, symbol={token_symbol}, decimals={token_decimals}"
-        );
-
-        // ABI encode as (string,string,uint8)
-        use ethers::abi::{encode, Token};
-        let tokens = vec![
-            Token::String(token_name),
-            Token::String(token_symbol),
-            Token::Uint(U256::from(token_decimals)),
-        ];
-        encode(&tokens)
+        let token_metadata =
+            cached_token_metadata(args.config, proof_source_network, origin_addr).await?;
+        tracing::info!(?token_metadata, "Encoding ERC20 metadata");
+        token_metadata.encode()
     };
 
+    if args.verify_proof {
+        let hex = |roots: &[H256]| -> Vec<String> {
+            roots.iter().map(|root| format!("{root:#x}")).collect()
+        };
+        let payload = ClaimPayload {
+            smt_proof: hex(&proof.smt_proof),
+            smt_proof_rollup: proof.smt_proof_rollup.as_deref().map(hex),
+            global_index: compute_global_index(ComputeGlobalIndexArgs {
+                index_local: deposit_count,
+                source_network_id: bridge_tx_network,
+            })
+            .to_string(),
+            mainnet_exit_root: format!("{mainnet_root:#x}"),
+            rollup_exit_root: format!("{rollup_root:#x}"),
+            origin_network,
+            origin_token_address: format!("{origin_addr:#x}"),
+            destination_network: destination_network_id,
+            destination_address: format!("{dest_addr:#x}"),
+            amount: amount_wei.to_string(),
+            metadata: format!("0x{}", hex::encode(&metadata_bytes)),
+            leaf_type,
+            global_exit_root: proof
+                .l1_info_tree_leaf
+                .global_exit_root()
+                .map(|root| format!("{root:#x}")),
+        };
+        let verification = verify_claim_proof(args.config, &payload).await?;
+        if let Some(failure) = verification.failure() {
+            print_claim_path(ui::ui(), &verification.proof_path);
+            return Err(validation_error(&format!(
+                "Claim proof verification failed, claim not sent: {failure}"
+            )));
+        }
+        ui::ui().success("Claim proof verified against the on-chain exit roots");
+    }
+
     // Call the appropriate claim function based on leaf type
-    let tx_hash = if leaf_type == 0 {
+    let claim_result = if leaf_type == 0 {
         // Asset bridge - call claimAsset
         ui::ui().info(&format!(
-            "💰 Claiming asset: {} tokens to {}",
-            amount, destination_address
+            "💰 Claiming asset: {amount_wei} tokens to {dest_addr:#x}"
         ));
 
         let asset_params = AssetClaimParams {
@@ -593,7 +614,12 @@ pub async fn claim_asset(args: ClaimAssetArgs<'_>) -> Result<()> {
             metadata_bytes: metadata_bytes.clone(),
         };
 
-        execute_claim_asset(&bridge, asset_params, &args.gas_options).await?
+        timer
+            .time(
+                Stage::TxSubmission,
+                execute_claim_asset(&bridge, asset_params, &args.gas_options),
+            )
+            .await
     } else {
         // Message bridge - call claimMessage
         debug!("Claiming message bridge to trigger contract execution");
@@ -624,20 +650,114 @@ pub async fn claim_asset(args: ClaimAssetArgs<'_>) -> Result<()> {
             .msg_value(msg_value_wei)
             .build_with_crate_error()?;
 
-        super::claim_message::execute_claim_message(claim_message_args).await?
+        // Calls of bridge-and-call messages fail silently into the fallback address
+        let is_extension_message = get_bridge_extension_address(args.config, args.network)
+            .is_ok_and(|extension| extension == dest_addr);
+        if is_extension_message {
+            if let Some(preflight) = message_preflight::simulate(&claim_message_args).await {
+                preflight.print();
+                if preflight.routes_to_fallback() && !args.allow_fallback && !is_dry_run() {
+                    return Err(validation_error(
+                        "Claim not sent: the message call would revert and the tokens would go to the fallback address; pass --allow-fallback to claim anyway",
+                    ));
+                }
+            }
+        }
+
+        timer
+            .time(
+                Stage::TxSubmission,
+                super::claim_message::execute_claim_message(claim_message_args),
+            )
+            .await
+    };
+
+    // Explain claims that lost a race against another claimer
+    let tx_hash = match claim_result {
+        Err(e) if frontrun::is_already_claimed_error(&e.to_string()) => {
+            return Err(
+                frontrun::already_claimed_error(&bridge, bridge_tx_network, deposit_count).await,
+            );
+        }
+        result => result?,
+    };
+    let Some(tx_hash) = tx_hash else {
+        return Ok(());
     };
 
     ui::ui().success(&format!("Claim transaction submitted: {tx_hash:#x}"));
+    timer
+        .time(
+            Stage::MiningWait,
+            frontrun::check_claim_receipt(&bridge, tx_hash, bridge_tx_network, deposit_count),
+        )
+        .await?;
     if leaf_type == 0 {
         ui::ui().success("🎉 Assets should be available once the transaction is mined!");
     } else {
         ui::ui().success("🎉 Message bridge claimed! Contract call should execute automatically.");
     }
     ui::ui().tip("For future claims, ensure you wait at least 5 seconds after bridging to allow AggKit to update the Global Exit Root (GER)");
+    timer.report(
+        "claim",
+        serde_json::json!({
+            "tx_hash": format!("{tx_hash:#x}"),
+            "network": args.network,
+            "source_network": args.source_network,
+            "deposit_count": deposit_count,
+        }),
+    );
 
     Ok(())
 }
 
+/// Deposits made by a transaction, several for bridge-and-call transactions
+fn deposits_of_tx(deposits: &[BridgeDeposit], tx_hash: H256) -> Vec<&BridgeDeposit> {
+    deposits
+        .iter()
+        .filter(|deposit| deposit.bridge_tx_hash == tx_hash)
+        .collect()
+}
+
+/// Check that a claim is sent to the bridge of the deposit's destination network
+///
+/// `contract_network_id` is the `networkID()` of the bridge on `network`, the
+/// network the claim is sent to. It has to agree with `network`, or the
+/// configured bridge address or RPC URL points at another chain, and with the
+/// destination of the deposit, or the claim would revert on-chain.
+pub fn check_claim_network(
+    contract_network_id: u32,
+    network: u64,
+    deposit_destination: Option<u64>,
+    deposit_source: u64,
+) -> Result<()> {
+    if u64::from(contract_network_id) != network {
+        return Err(validation_error(&format!(
+            "The bridge contract configured for network {network} ({}) reports networkID {contract_network_id} ({}); check the bridge address and RPC URL configured for network {network}",
+            get_network_name(network),
+            get_network_name(contract_network_id.into())
+        )));
+    }
+    let Some(destination) = deposit_destination else {
+        return Ok(());
+    };
+    if destination != network {
+        let hint = if network == deposit_source {
+            format!(
+                ": network {network} is where the deposit was made, claims are sent on the destination network"
+            )
+        } else {
+            String::new()
+        };
+        return Err(validation_error(&format!(
+            "This deposit is destined for network {destination} ({}), not network {network} ({}){hint}. Claim it with --network-id {destination}",
+            get_network_name(destination),
+            get_network_name(network)
+        )));
+    }
+    Ok(())
+}
+
 /// Parameters for asset claiming operations
 #[derive(Debug, Clone)]
 pub struct AssetClaimParams {
@@ -652,12 +772,12 @@ pub struct AssetClaimParams {
     pub metadata_bytes: Vec<u8>,
 }
 
-/// Execute claimAsset contract call
+/// Execute claimAsset contract call, `None` in a dry run
 pub async fn execute_claim_asset(
-    bridge: &BridgeContract<SignerMiddleware<Arc<Provider<Http>>, LocalWallet>>,
+    bridge: &BridgeContract<SandboxClient>,
     params: AssetClaimParams,
     gas_options: &GasOptions,
-) -> Result<H256> {
+) -> Result<Option<H256>> {
     let mut call = bridge.claim_asset(
         params.deposit_count.into(), // globalIndex
         params.mainnet_root.into(),  // mainnetExitRoot
@@ -674,11 +794,41 @@ pub async fn execute_claim_asset(
         call = call.gas(3_000_000u64); // Default high gas limit for claims
     }
 
-    let call = gas_options.apply_to_call_with_return(call);
-    let tx = call.send().await.map_err(|e| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            &format!("Failed to send claim asset transaction: {e}"),
-        ))
-    })?;
-    Ok(tx.tx_hash())
+    let call = gas_options
+        .apply_to_call_with_return(call, bridge.client_ref())
+        .await?;
+    let tx = send_or_simulate(&call)
+        .await
+        .map_err(|e| rpc_error("send claim asset transaction", receipt::describe_error(&e)))?;
+    Ok(tx.map(|tx| tx.tx_hash()))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_claim_network_accepts_destination() {
+        assert!(check_claim_network(1, 1, Some(1), 0).is_ok());
+        assert!(check_claim_network(0, 0, None, 1).is_ok());
+    }
+
+    #[test]
+    fn test_check_claim_network_rejects_source_chain() {
+        let message = check_claim_network(0, 0, Some(1), 0)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("destined for network 1"));
+        assert!(message.contains("where the deposit was made"));
+        assert!(message.contains("--network-id 1"));
+    }
+
+    #[test]
+    fn test_check_claim_network_rejects_misconfigured_bridge() {
+        let message = check_claim_network(1, 2, Some(2), 0)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("reports networkID 1"));
+    }
 }
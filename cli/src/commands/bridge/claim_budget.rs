@@ -0,0 +1,342 @@
+//! Claim gas spending and budget enforcement
+//!
+//! Spending is derived from the bridge service's claim list: the receipt of
+//! every claim transaction gives `gas_used * effective_gas_price`. When a
+//! sponsor address is known only claims it submitted are counted, so the total
+//! reflects what the sponsoring account has paid. The budget applies per
+//! network and is read from `CLAIM_GAS_BUDGET_ETH`, with warnings at the
+//! percentages listed in `CLAIM_GAS_BUDGET_WARN_AT` (default `50,80`).
+
+use crate::api_client::OptimizedApiClient;
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui;
+use ethers::prelude::*;
+use ethers::utils::{format_ether, parse_ether};
+use serde::Serialize;
+use tracing::debug;
+
+use super::common::{get_network_name, serialize_json, validate_address, validation_error};
+use super::get_provider;
+
+/// Environment variable holding the per-network budget in ETH
+pub const BUDGET_ENV: &str = "CLAIM_GAS_BUDGET_ETH";
+/// Environment variable holding the warning thresholds in percent
+pub const WARN_AT_ENV: &str = "CLAIM_GAS_BUDGET_WARN_AT";
+/// Environment variable holding the address whose claims are counted
+pub const SPONSOR_ENV: &str = "CLAIM_SPONSOR_ADDRESS";
+
+const DEFAULT_WARN_AT: [u8; 2] = [50, 80];
+
+/// Per-network claim gas budget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimBudget {
+    pub limit_wei: U256,
+    /// Warning thresholds in percent of the limit, ascending
+    pub warn_at: Vec<u8>,
+}
+
+impl ClaimBudget {
+    /// Parse a budget given in ETH with warning thresholds such as "50,80"
+    pub fn parse(limit_eth: &str, warn_at: Option<&str>) -> Result<Self> {
+        let limit_wei = parse_ether(limit_eth.trim()).map_err(|_| {
+            validation_error(&format!(
+                "Invalid claim gas budget '{limit_eth}': expected an amount in ETH"
+            ))
+        })?;
+
+        let mut thresholds = match warn_at {
+            Some(list) => list
+                .split(',')
+                .map(|value| {
+                    value
+                        .trim()
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|percent| (1..=100).contains(percent))
+                        .ok_or_else(|| {
+                            validation_error(&format!(
+                                "Invalid budget warning threshold '{value}': expected a percentage between 1 and 100"
+                            ))
+                        })
+                })
+                .collect::<Result<Vec<u8>>>()?,
+            None => DEFAULT_WARN_AT.to_vec(),
+        };
+        thresholds.sort_unstable();
+        thresholds.dedup();
+
+        Ok(Self {
+            limit_wei,
+            warn_at: thresholds,
+        })
+    }
+
+    /// Load the budget from the environment, `None` when no budget is configured
+    pub fn from_env() -> Result<Option<Self>> {
+        Self::resolve(None, None)
+    }
+
+    /// Resolve the budget from command-line overrides, falling back to the environment
+    pub fn resolve(limit_eth: Option<&str>, warn_at: Option<&str>) -> Result<Option<Self>> {
+        let env_limit = std::env::var(BUDGET_ENV).ok();
+        let env_warn_at = std::env::var(WARN_AT_ENV).ok();
+        match limit_eth.or(env_limit.as_deref()) {
+            Some(limit) if !limit.trim().is_empty() => {
+                Self::parse(limit, warn_at.or(env_warn_at.as_deref())).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Classify the amount spent against this budget
+    pub fn evaluate(&self, spent_wei: U256) -> BudgetStatus {
+        if spent_wei >= self.limit_wei {
+            return BudgetStatus::Exceeded;
+        }
+        if self.limit_wei.is_zero() {
+            return BudgetStatus::Ok;
+        }
+
+        let used_percent = (spent_wei * U256::from(100) / self.limit_wei).as_u64();
+        self.warn_at
+            .iter()
+            .rev()
+            .find(|threshold| used_percent >= u64::from(**threshold))
+            .map_or(BudgetStatus::Ok, |threshold| {
+                BudgetStatus::Warning(*threshold)
+            })
+    }
+}
+
+/// Position of the spending relative to the budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", content = "threshold", rename_all = "snake_case")]
+pub enum BudgetStatus {
+    Ok,
+    /// Spending passed the given warning threshold (percent)
+    Warning(u8),
+    Exceeded,
+}
+
+/// Claim gas spent on one network
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaimGasSpend {
+    pub network_id: u64,
+    pub sponsor: Option<Address>,
+    pub claims_counted: usize,
+    pub spent_wei: U256,
+    pub budget_wei: Option<U256>,
+    pub status: BudgetStatus,
+}
+
+impl ClaimGasSpend {
+    /// Human readable one-line summary
+    pub fn describe(&self) -> String {
+        let spent = format_ether(self.spent_wei);
+        let network = format!(
+            "network {} ({})",
+            self.network_id,
+            get_network_name(self.network_id)
+        );
+        match self.budget_wei {
+            Some(budget) => format!(
+                "{network}: {spent} ETH of {} ETH claim gas budget spent over {} claim(s)",
+                format_ether(budget),
+                self.claims_counted
+            ),
+            None => format!(
+                "{network}: {spent} ETH spent on claim gas over {} claim(s)",
+                self.claims_counted
+            ),
+        }
+    }
+}
+
+/// Resolve the sponsor address from an explicit value or `CLAIM_SPONSOR_ADDRESS`
+pub fn resolve_sponsor(sponsor: Option<&str>) -> Result<Option<Address>> {
+    let from_env = std::env::var(SPONSOR_ENV).ok();
+    match sponsor.or(from_env.as_deref()) {
+        Some(address) if !address.trim().is_empty() => {
+            validate_address(address.trim(), "Sponsor address").map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Sum the gas paid for claim transactions on a network
+///
+/// Claims whose receipt is not available yet are skipped.
+pub async fn get_claim_gas_spend(
+    config: &Config,
+    network_id: u64,
+    sponsor: Option<Address>,
+    budget: Option<&ClaimBudget>,
+) -> Result<ClaimGasSpend> {
+    let api_client = OptimizedApiClient::global();
+    api_client.invalidate("claims", Some(network_id)).await;
+    let claims_response = api_client.get_claims(config, network_id).await?;
+
+    let provider = get_provider(config, network_id).await?;
+    let mut claims_counted = 0;
+    let mut spent_wei = U256::zero();
+
+    for tx_hash in claim_tx_hashes(&claims_response) {
+        let receipt = match provider.get_transaction_receipt(tx_hash).await {
+            Ok(Some(receipt)) => receipt,
+            Ok(None) => continue,
+            Err(e) => {
+                debug!(network_id, tx_hash = ?tx_hash, error = %e, "Could not fetch claim receipt");
+                continue;
+            }
+        };
+        if sponsor.is_some_and(|sponsor| receipt.from != sponsor) {
+            continue;
+        }
+        if let (Some(gas_used), Some(gas_price)) = (receipt.gas_used, receipt.effective_gas_price) {
+            spent_wei += gas_used * gas_price;
+            claims_counted += 1;
+        }
+    }
+
+    Ok(ClaimGasSpend {
+        network_id,
+        sponsor,
+        claims_counted,
+        spent_wei,
+        budget_wei: budget.map(|budget| budget.limit_wei),
+        status: budget.map_or(BudgetStatus::Ok, |budget| budget.evaluate(spent_wei)),
+    })
+}
+
+/// Unique claim transaction hashes from a claims API response
+fn claim_tx_hashes(response: &serde_json::Value) -> Vec<H256> {
+    let mut hashes: Vec<H256> = response["claims"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|claim| claim["claim_tx_hash"].as_str()?.parse().ok())
+        .collect();
+    hashes.sort_unstable();
+    hashes.dedup();
+    hashes
+}
+
+/// Check the budget before claiming on a network
+///
+/// Prints a warning once a threshold is passed and fails when the budget is
+/// exhausted. Does nothing when no budget is configured.
+pub async fn enforce_claim_budget(
+    config: &Config,
+    network_id: u64,
+    sponsor: Option<Address>,
+    budget: Option<&ClaimBudget>,
+) -> Result<()> {
+    let Some(budget) = budget else {
+        return Ok(());
+    };
+
+    let spend = get_claim_gas_spend(config, network_id, sponsor, Some(budget)).await?;
+    match spend.status {
+        BudgetStatus::Ok => Ok(()),
+        BudgetStatus::Warning(threshold) => {
+            ui::ui().warning(&format!(
+                "Claim gas budget above {threshold}%: {}",
+                spend.describe()
+            ));
+            Ok(())
+        }
+        BudgetStatus::Exceeded => Err(validation_error(&format!(
+            "Claim gas budget exhausted: {}. Raise {BUDGET_ENV} or pass --budget to continue",
+            spend.describe()
+        ))),
+    }
+}
+
+/// Print the claim gas spending of each network as a table, or as JSON in JSON output mode
+pub fn print_claim_gas_spend(spends: &[ClaimGasSpend]) -> Result<()> {
+    if ui::ui().is_json() {
+        let json_str = serialize_json(&spends)?;
+        ui::ui().json(&serde_json::from_str::<serde_json::Value>(&json_str).unwrap_or_default());
+        return Ok(());
+    }
+
+    for spend in spends {
+        match spend.status {
+            BudgetStatus::Ok => ui::ui().info(&spend.describe()),
+            BudgetStatus::Warning(threshold) => {
+                ui::ui().warning(&format!("{} (above {threshold}%)", spend.describe()))
+            }
+            BudgetStatus::Exceeded => {
+                ui::ui().warning(&format!("{} (budget exhausted)", spend.describe()))
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_budget() {
+        let budget = ClaimBudget::parse("0.5", Some("90, 25,90")).unwrap();
+        assert_eq!(budget.limit_wei, parse_ether("0.5").unwrap());
+        assert_eq!(budget.warn_at, vec![25, 90]);
+
+        let default = ClaimBudget::parse("1", None).unwrap();
+        assert_eq!(default.warn_at, vec![50, 80]);
+
+        assert!(ClaimBudget::parse("lots", None).is_err());
+        assert!(ClaimBudget::parse("1", Some("150")).is_err());
+    }
+
+    #[test]
+    fn test_budget_thresholds() {
+        let budget = ClaimBudget::parse("1", None).unwrap();
+        let eth = |value: &str| parse_ether(value).unwrap();
+
+        assert_eq!(budget.evaluate(eth("0.1")), BudgetStatus::Ok);
+        assert_eq!(budget.evaluate(eth("0.5")), BudgetStatus::Warning(50));
+        assert_eq!(budget.evaluate(eth("0.85")), BudgetStatus::Warning(80));
+        assert_eq!(budget.evaluate(eth("1")), BudgetStatus::Exceeded);
+    }
+
+    #[test]
+    fn test_claim_tx_hashes_deduplicated() {
+        let hash = format!("0x{}", "ab".repeat(32));
+        let response = json!({
+            "claims": [
+                {"claim_tx_hash": hash},
+                {"claim_tx_hash": hash},
+                {"claim_tx_hash": "pending"},
+                {"global_index": "1"}
+            ]
+        });
+
+        assert_eq!(claim_tx_hashes(&response).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_api_failure_reports_api_exit_code() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        let mut config = Config::default();
+        config.api.base_url = crate::types::RpcUrl::new(server.uri()).unwrap();
+        config.api.retry_attempts = 1;
+
+        let error = get_claim_gas_spend(&config, 0, None, None)
+            .await
+            .unwrap_err();
+        assert_eq!(error.exit_code(), crate::error::exit_code::API);
+    }
+}
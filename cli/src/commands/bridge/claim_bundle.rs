@@ -0,0 +1,250 @@
+//! Claims of both deposits of a bridge-and-call transaction
+//!
+//! `bridgeAndCall` makes two deposits in one transaction: an asset deposit
+//! paying the tokens to the JumpPoint address and a message deposit whose
+//! claim deploys the JumpPoint, which forwards the tokens to the call target.
+//! The message claim only finds the tokens once the asset claim is mined, so
+//! `bridge claim-bundle` claims the asset deposit, waits until the destination
+//! bridge reports it claimed and then claims the message deposit. Deposits
+//! already claimed are skipped, so an interrupted bundle can be re-run.
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::types::BridgeDeposit;
+use crate::ui;
+use ethers::prelude::*;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use super::claim_asset::{claim_asset, ClaimAssetArgs};
+use super::common::{get_network_name, is_dry_run, rpc_error, validation_error};
+use super::local_index::{self, DataSource};
+use super::proof_wait::{self, AutoWait};
+use super::{get_bridge_contract_address, get_provider, BridgeContract, GasOptions};
+
+/// How long the asset claim may take to be mined before the message is claimed
+const ASSET_CLAIM_TIMEOUT: Duration = Duration::from_secs(120);
+const ASSET_CLAIM_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Arguments for claiming a bridge-and-call transaction
+pub struct ClaimBundleArgs<'a> {
+    pub config: &'a Config,
+    /// Network to claim on, the destination of the bridge-and-call
+    pub network: u64,
+    pub tx_hash: &'a str,
+    pub source_network: u64,
+    pub gas_options: GasOptions,
+    pub private_key: Option<&'a str>,
+    /// ETH sent with the message claim (in wei)
+    pub msg_value: Option<&'a str>,
+    /// Where the deposits are looked up
+    pub source: DataSource,
+    /// Poll until the deposits are claimable before claiming
+    pub auto_wait: Option<AutoWait>,
+    /// Claim the message even if the pre-flight check finds its call would revert
+    pub allow_fallback: bool,
+}
+
+/// Asset and message deposit made by one bridge-and-call transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositBundle {
+    pub asset: BridgeDeposit,
+    pub message: BridgeDeposit,
+}
+
+impl DepositBundle {
+    /// Split the deposits of `tx_hash` into its asset and message deposit
+    pub fn from_deposits(deposits: &[BridgeDeposit], tx_hash: H256) -> Result<Self> {
+        let of_tx: Vec<&BridgeDeposit> = deposits
+            .iter()
+            .filter(|deposit| deposit.bridge_tx_hash == tx_hash)
+            .collect();
+        if of_tx.is_empty() {
+            return Err(validation_error(&format!(
+                "Bridge transaction {tx_hash:#x} not found"
+            )));
+        }
+        let asset = of_tx.iter().find(|deposit| deposit.is_asset());
+        let message = of_tx.iter().find(|deposit| !deposit.is_asset());
+        match (asset, message) {
+            (Some(asset), Some(message)) => Ok(Self {
+                asset: (*asset).clone(),
+                message: (*message).clone(),
+            }),
+            _ => Err(validation_error(&format!(
+                "Transaction {tx_hash:#x} made {} deposit(s) but no asset and message pair; it is not a bridge-and-call transaction, claim it with `aggsandbox bridge claim`",
+                of_tx.len()
+            ))),
+        }
+    }
+}
+
+/// Claim the asset deposit of a bridge-and-call transaction, then its message deposit
+pub async fn claim_bundle(args: ClaimBundleArgs<'_>) -> Result<()> {
+    let tx_hash = H256::from_str(args.tx_hash)
+        .map_err(|e| validation_error(&format!("Invalid transaction hash: {e}")))?;
+
+    if let Some(auto_wait) = args.auto_wait {
+        proof_wait::wait_until_claimable(
+            args.config,
+            args.source_network,
+            args.source_network,
+            args.tx_hash,
+            None,
+            args.source,
+            auto_wait,
+        )
+        .await?;
+    }
+
+    let response = local_index::get_bridges(args.config, args.source_network, args.source).await?;
+    let bundle = DepositBundle::from_deposits(&BridgeDeposit::list_from(&response)?, tx_hash)?;
+    ui::ui().info(&format!(
+        "🔗 Bridge-and-call {tx_hash:#x}: asset deposit {}, message deposit {}, claiming on network {} ({})",
+        bundle.asset.deposit_count,
+        bundle.message.deposit_count,
+        args.network,
+        get_network_name(args.network)
+    ));
+
+    let bridge = BridgeContract::new(
+        get_bridge_contract_address(args.config, args.network)?,
+        get_provider(args.config, args.network).await?,
+    );
+
+    if is_claimed(&bridge, &bundle.asset, args.source_network).await? {
+        ui::ui().info(&format!(
+            "Asset deposit {} already claimed, skipping",
+            bundle.asset.deposit_count
+        ));
+    } else {
+        ui::ui().info(&format!(
+            "1️⃣  Claiming asset deposit {}",
+            bundle.asset.deposit_count
+        ));
+        claim_leg(&args, &bundle.asset, None).await?;
+        if is_dry_run() {
+            ui::ui().warning(
+                "Dry run: the asset claim was not sent, so the simulated message claim runs without its tokens",
+            );
+        } else {
+            wait_until_claimed(&bridge, &bundle.asset, args.source_network).await?;
+        }
+    }
+
+    if is_claimed(&bridge, &bundle.message, args.source_network).await? {
+        ui::ui().info(&format!(
+            "Message deposit {} already claimed, skipping",
+            bundle.message.deposit_count
+        ));
+    } else {
+        ui::ui().info(&format!(
+            "2️⃣  Claiming message deposit {}",
+            bundle.message.deposit_count
+        ));
+        claim_leg(&args, &bundle.message, args.msg_value).await?;
+    }
+
+    ui::ui().success(&format!(
+        "🎉 Bridge-and-call {tx_hash:#x} claimed: asset deposit {} and message deposit {}",
+        bundle.asset.deposit_count, bundle.message.deposit_count
+    ));
+    Ok(())
+}
+
+/// Claim one deposit of the bundle through the regular claim flow
+async fn claim_leg(
+    args: &ClaimBundleArgs<'_>,
+    deposit: &BridgeDeposit,
+    msg_value: Option<&str>,
+) -> Result<()> {
+    let mut builder = ClaimAssetArgs::builder()
+        .config(args.config)
+        .network(args.network)
+        .tx_hash(args.tx_hash)
+        .source_network(args.source_network)
+        .deposit_count(Some(deposit.deposit_count))
+        .gas_options(args.gas_options.clone())
+        .source(args.source)
+        .auto_wait(args.auto_wait)
+        .allow_fallback(args.allow_fallback)
+        .msg_value(msg_value);
+    if let Some(key) = args.private_key {
+        builder = builder.private_key(key);
+    }
+    claim_asset(builder.build_with_crate_error()?).await
+}
+
+async fn is_claimed(
+    bridge: &BridgeContract<Provider<Http>>,
+    deposit: &BridgeDeposit,
+    source_network: u64,
+) -> Result<bool> {
+    bridge
+        .is_claimed(deposit.deposit_count as u32, source_network as u32)
+        .call()
+        .await
+        .map_err(|e| rpc_error("check claim status", e))
+}
+
+/// Poll the destination bridge until the deposit is claimed
+async fn wait_until_claimed(
+    bridge: &BridgeContract<Provider<Http>>,
+    deposit: &BridgeDeposit,
+    source_network: u64,
+) -> Result<()> {
+    let deadline = Instant::now() + ASSET_CLAIM_TIMEOUT;
+    while !is_claimed(bridge, deposit, source_network).await? {
+        if Instant::now() >= deadline {
+            return Err(validation_error(&format!(
+                "Asset deposit {} is still unclaimed after {}s, message not claimed; re-run claim-bundle once the asset claim is mined",
+                deposit.deposit_count,
+                ASSET_CLAIM_TIMEOUT.as_secs()
+            )));
+        }
+        tokio::time::sleep(ASSET_CLAIM_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn deposit(tx_hash: H256, deposit_count: u64, leaf_type: u8) -> BridgeDeposit {
+        BridgeDeposit::from_value(&serde_json::json!({
+            "bridge_tx_hash": format!("{tx_hash:#x}"),
+            "deposit_count": deposit_count,
+            "leaf_type": leaf_type,
+            "origin_address": "0x0000000000000000000000000000000000000000",
+            "destination_address": "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+            "amount": "0",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_bundle_from_deposits() {
+        let tx = H256::repeat_byte(0xaa);
+        let other = H256::repeat_byte(0xbb);
+        let deposits = [deposit(other, 3, 0), deposit(tx, 4, 0), deposit(tx, 5, 1)];
+
+        let bundle = DepositBundle::from_deposits(&deposits, tx).unwrap();
+        assert_eq!(bundle.asset.deposit_count, 4);
+        assert_eq!(bundle.message.deposit_count, 5);
+    }
+
+    #[test]
+    fn test_bundle_needs_both_deposits() {
+        let tx = H256::repeat_byte(0xaa);
+        assert!(DepositBundle::from_deposits(&[deposit(tx, 4, 0)], tx)
+            .unwrap_err()
+            .to_string()
+            .contains("not a bridge-and-call transaction"));
+        assert!(DepositBundle::from_deposits(&[], tx)
+            .unwrap_err()
+            .to_string()
+            .contains("not found"));
+    }
+}
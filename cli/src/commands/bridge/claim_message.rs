@@ -1,19 +1,31 @@
 use crate::error::Result;
 use ethers::prelude::*;
-use ethers::providers::Http;
-use ethers::signers::LocalWallet;
-use std::sync::Arc;
 
-use super::{BridgeContract, GasOptions};
+use super::common::{rpc_error, send_or_simulate};
+use super::receipt;
+use super::{BridgeContract, GasOptions, SandboxClient};
 
 /// Type alias for the bridge contract with middleware
-pub type BridgeContractWithMiddleware<'a> =
-    &'a BridgeContract<SignerMiddleware<Arc<Provider<Http>>, LocalWallet>>;
+pub type BridgeContractWithMiddleware<'a> = &'a BridgeContract<SandboxClient>;
 
 /// Arguments for executing claim message operations
 ///
 /// Use the builder pattern to construct this struct:
-/// ```rust
+/// ```no_run
+/// # use aggsandbox::commands::bridge::claim_message::{BridgeContractWithMiddleware, ClaimMessageArgs};
+/// # use aggsandbox::commands::bridge::GasOptions;
+/// # use ethers::types::{Address, H256, U256};
+/// # #[allow(clippy::too_many_arguments)]
+/// # fn example(
+/// #     bridge: BridgeContractWithMiddleware<'_>,
+/// #     mainnet_root: H256,
+/// #     rollup_root: H256,
+/// #     origin_addr: Address,
+/// #     dest_addr: Address,
+/// #     amount_wei: U256,
+/// #     metadata_bytes: Vec<u8>,
+/// #     gas_options: &GasOptions,
+/// # ) -> aggsandbox::error::Result<()> {
 /// let args = ClaimMessageArgs::builder()
 ///     .bridge(&bridge)
 ///     .deposit_count(1)
@@ -27,6 +39,8 @@ pub type BridgeContractWithMiddleware<'a> =
 ///     .metadata_bytes(metadata_bytes)
 ///     .gas_options(gas_options)
 ///     .build_with_crate_error()?;
+/// # Ok(())
+/// # }
 /// ```
 pub struct ClaimMessageArgs<'a> {
     pub bridge: BridgeContractWithMiddleware<'a>,
@@ -237,8 +251,8 @@ impl<'a> ClaimMessageArgsBuilder<'a> {
     }
 }
 
-/// Execute claimMessage contract call
-pub async fn execute_claim_message(args: ClaimMessageArgs<'_>) -> Result<H256> {
+/// claimMessage call of the arguments, without gas options applied
+pub fn claim_message_call(args: &ClaimMessageArgs<'_>) -> ContractCall<SandboxClient, ()> {
     let mut call = args.bridge.claim_message(
         args.deposit_count.into(), // globalIndex
         args.mainnet_root.into(),  // mainnetExitRoot
@@ -248,7 +262,7 @@ pub async fn execute_claim_message(args: ClaimMessageArgs<'_>) -> Result<H256> {
         args.destination_network_id,
         args.dest_addr,
         args.amount_wei,
-        ethers::types::Bytes::from(args.metadata_bytes), // message data
+        ethers::types::Bytes::from(args.metadata_bytes.clone()), // message data
     );
 
     // Add ETH value if specified for message bridge claims
@@ -259,13 +273,21 @@ pub async fn execute_claim_message(args: ClaimMessageArgs<'_>) -> Result<H256> {
     if args.gas_options.gas_limit.is_none() {
         call = call.gas(3_000_000u64); // Default high gas limit for claims
     }
+    call
+}
 
-    let call = args.gas_options.apply_to_call_with_return(call);
-    let tx = call.send().await.map_err(|e| {
-        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
-            &format!("Failed to send claim message transaction: {e}"),
-        ))
+/// Execute claimMessage contract call, `None` in a dry run
+pub async fn execute_claim_message(args: ClaimMessageArgs<'_>) -> Result<Option<H256>> {
+    let call = args
+        .gas_options
+        .apply_to_call_with_return(claim_message_call(&args), args.bridge.client_ref())
+        .await?;
+    let tx = send_or_simulate(&call).await.map_err(|e| {
+        rpc_error(
+            "send claim message transaction",
+            receipt::describe_error(&e),
+        )
     })?;
 
-    Ok(tx.tx_hash())
+    Ok(tx.map(|tx| tx.tx_hash()))
 }
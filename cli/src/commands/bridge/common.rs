@@ -5,9 +5,11 @@
 
 use crate::config::Config;
 use crate::error::Result;
+use ethers::abi::{Detokenize, Function, Token};
 use ethers::prelude::*;
 use serde::Serialize;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Validate Ethereum address format
 pub fn validate_address(address: &str, field_name: &str) -> Result<Address> {
@@ -42,10 +44,10 @@ pub fn validate_address(address: &str, field_name: &str) -> Result<Address> {
 
 /// Validate network ID
 pub fn validate_network_id(network_id: u64, field_name: &str) -> Result<()> {
-    if network_id > 2 {
+    if network_id > crate::types::MAX_NETWORK_ID {
         return Err(crate::error::AggSandboxError::Config(
             crate::error::ConfigError::validation_failed(
-                &format!("{field_name} must be 0 (Mainnet), 1 (AggLayer-1), or 2 (AggLayer-2), got: {network_id}"),
+                &format!("{field_name} must be 0 (Mainnet) or an L2 network ID from 1 to {}, got: {network_id}", crate::types::MAX_NETWORK_ID),
             ),
         ));
     }
@@ -53,12 +55,30 @@ pub fn validate_network_id(network_id: u64, field_name: &str) -> Result<()> {
 }
 
 /// Get network display name
-pub fn get_network_name(network_id: u64) -> &'static str {
+pub fn get_network_name(network_id: u64) -> String {
     match network_id {
-        0 => "Mainnet",
-        1 => "AggLayer-1",
-        2 => "AggLayer-2",
-        _ => "Unknown",
+        0 => "Mainnet".to_string(),
+        id if id <= crate::types::MAX_NETWORK_ID => format!("AggLayer-{id}"),
+        _ => "Unknown".to_string(),
+    }
+}
+
+/// Raw amount in whole units with at most four decimals, e.g. `1.5`
+pub fn format_amount(amount: U256, decimals: u8) -> String {
+    let Ok(formatted) = ethers::utils::format_units(amount, u32::from(decimals)) else {
+        return amount.to_string();
+    };
+    let Some((whole, fraction)) = formatted.split_once('.') else {
+        return formatted;
+    };
+    let fraction = fraction.get(..4).unwrap_or(fraction).trim_end_matches('0');
+    if fraction.is_empty() {
+        if whole == "0" && !amount.is_zero() {
+            return "<0.0001".to_string();
+        }
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
     }
 }
 
@@ -67,29 +87,279 @@ pub fn validation_error(message: &str) -> crate::error::AggSandboxError {
     crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(message))
 }
 
+/// Create RPC error for a failed node request or transaction
+pub fn rpc_error(action: &str, error: impl std::fmt::Display) -> crate::error::AggSandboxError {
+    crate::error::EventError::rpc_connection_failed(&format!("Failed to {action}: {error}")).into()
+}
+
+/// Action of a JSON-RPC call, as reported by `rpc_error`
+pub fn rpc_action(network_id: u64, method: &str) -> String {
+    format!(
+        "call {method} on network {network_id} ({})",
+        get_network_name(network_id)
+    )
+}
+
+/// Send a raw JSON-RPC request, such as an Anvil cheat code, to a network's node
+pub async fn node_request<P, R>(
+    provider: &Provider<Http>,
+    network_id: u64,
+    method: &str,
+    params: P,
+) -> Result<R>
+where
+    P: Serialize + Send + Sync + std::fmt::Debug,
+    R: Serialize + serde::de::DeserializeOwned + Send + std::fmt::Debug,
+{
+    provider
+        .request(method, params)
+        .await
+        .map_err(|e| rpc_error(&rpc_action(network_id, method), e))
+}
+
 /// Serialize JSON output with error handling
 pub fn serialize_json<T: Serialize>(data: &T) -> Result<String> {
     serde_json::to_string_pretty(data)
         .map_err(|e| validation_error(&format!("Failed to serialize output to JSON: {e}")))
 }
 
+/// Metadata size above which bridge commands warn about destination claim costs
+pub const LARGE_METADATA_WARN_BYTES: usize = 8 * 1024;
+
+/// Size and estimated claim cost of a deposit's metadata
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MetadataDiagnostics {
+    /// Metadata length in bytes
+    pub byte_len: usize,
+    /// Estimated extra gas the metadata adds to the claim transaction
+    pub estimated_claim_gas: u64,
+}
+
+impl MetadataDiagnostics {
+    /// Estimate the cost of carrying `metadata` through a claim
+    ///
+    /// Counts calldata bytes (16 gas non-zero, 4 gas zero, including ABI
+    /// padding) plus hashing, copying and memory expansion for the leaf hash.
+    pub fn from_bytes(metadata: &[u8]) -> Self {
+        let byte_len = metadata.len();
+        let words = byte_len.div_ceil(32) as u64;
+        let padding = words * 32 - byte_len as u64;
+
+        let calldata_gas: u64 = metadata
+            .iter()
+            .map(|byte| if *byte == 0 { 4 } else { 16 })
+            .sum::<u64>()
+            + padding * 4;
+        let keccak_gas = 6 * words;
+        let copy_gas = 3 * words;
+        let memory_gas = 3 * words + words * words / 512;
+
+        Self {
+            byte_len,
+            estimated_claim_gas: calldata_gas + keccak_gas + copy_gas + memory_gas,
+        }
+    }
+
+    /// Parse hex metadata as returned by the bridge service
+    pub fn from_hex(metadata: &str) -> Option<Self> {
+        hex::decode(metadata.trim_start_matches("0x"))
+            .ok()
+            .map(|bytes| Self::from_bytes(&bytes))
+    }
+
+    /// Whether the metadata is large enough to warrant a warning
+    pub fn is_large(&self) -> bool {
+        self.byte_len > LARGE_METADATA_WARN_BYTES
+    }
+}
+
+/// Warn when bridged metadata may exceed destination gas expectations
+pub fn warn_if_large_metadata(metadata: &[u8]) {
+    let diagnostics = MetadataDiagnostics::from_bytes(metadata);
+    if diagnostics.is_large() {
+        crate::ui::ui().warning(&format!(
+            "Large metadata: {} bytes adds ~{} gas to the claim on the destination network",
+            diagnostics.byte_len, diagnostics.estimated_claim_gas
+        ));
+        crate::ui::ui()
+            .tip("Set --gas-limit on the claim if the default claim gas limit is exceeded");
+    }
+}
+
+/// Whether transactions are only simulated (`--dry-run`)
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Simulate bridge transactions instead of broadcasting them
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether `--dry-run` is in effect
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Decoded argument of a simulated call
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CallArgument {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub value: String,
+}
+
+/// What a transaction would have done, printed by `--dry-run`
+#[derive(Debug, Clone, Serialize)]
+pub struct DryRun {
+    pub function: String,
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub calldata: Bytes,
+    pub arguments: Vec<CallArgument>,
+    pub estimated_gas: Option<U256>,
+    /// Why `eth_call` failed, if the transaction would revert
+    pub revert: Option<String>,
+}
+
+impl DryRun {
+    /// Table rows of the simulated transaction
+    pub fn rows(&self) -> Vec<(String, String)> {
+        let address = |address: Option<Address>| {
+            address.map_or_else(|| "-".to_string(), |a| format!("{a:#x}"))
+        };
+        let mut rows = vec![
+            ("Function".to_string(), self.function.clone()),
+            ("From".to_string(), address(self.from)),
+            ("To".to_string(), address(self.to)),
+            ("Value (wei)".to_string(), self.value.to_string()),
+        ];
+        rows.extend(self.arguments.iter().map(|argument| {
+            (
+                format!("  {} ({})", argument.name, argument.kind),
+                argument.value.clone(),
+            )
+        }));
+        rows.push((
+            "Estimated gas".to_string(),
+            self.estimated_gas
+                .map_or_else(|| "-".to_string(), |gas| gas.to_string()),
+        ));
+        rows.push((
+            "eth_call".to_string(),
+            match &self.revert {
+                Some(reason) => format!("❌ reverts: {reason}"),
+                None => "✅ succeeds".to_string(),
+            },
+        ));
+        rows
+    }
+}
+
+/// Human readable value of a decoded argument
+fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(address) => format!("{address:#x}"),
+        Token::Bytes(bytes) | Token::FixedBytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Uint(value) | Token::Int(value) => value.to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => value.clone(),
+        Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => format!(
+            "[{}]",
+            tokens
+                .iter()
+                .map(format_token)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Arguments of `function` decoded from its calldata
+pub fn decode_arguments(function: &Function, calldata: &[u8]) -> Vec<CallArgument> {
+    let tokens = calldata
+        .get(4..)
+        .and_then(|input| function.decode_input(input).ok())
+        .unwrap_or_default();
+    function
+        .inputs
+        .iter()
+        .zip(tokens.iter())
+        .map(|(param, token)| CallArgument {
+            name: param.name.clone(),
+            kind: param.kind.to_string(),
+            value: format_token(token),
+        })
+        .collect()
+}
+
+/// Print a simulated transaction
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub fn print_dry_run(dry_run: &DryRun) {
+    let ui = crate::ui::ui();
+    if ui.is_json() {
+        ui.json(&serde_json::json!({ "dry_run": dry_run }));
+        return;
+    }
+    let rows = dry_run.rows();
+    let rows: Vec<(&str, &str)> = rows
+        .iter()
+        .map(|(label, value)| (label.as_str(), value.as_str()))
+        .collect();
+    ui.table("🧪 Dry Run", &rows);
+    ui.info("Dry run: the transaction was not broadcast");
+}
+
+/// Broadcast a contract call, or only simulate it with `--dry-run`
+///
+/// In dry-run mode the transaction is run through `eth_call` and
+/// `eth_estimateGas`, its decoded calldata, sender, value and gas estimate are
+/// printed, and `None` is returned instead of a pending transaction. A
+/// simulated revert is reported rather than returned as an error.
+pub async fn send_or_simulate<'a, M, D>(
+    call: &'a ContractCall<M, D>,
+) -> std::result::Result<Option<PendingTransaction<'a, M::Provider>>, ContractError<M>>
+where
+    M: Middleware + 'static,
+    D: Detokenize,
+{
+    if !is_dry_run() {
+        return call.send().await.map(Some);
+    }
+
+    let calldata = call.calldata().unwrap_or_default();
+    let revert = call
+        .call()
+        .await
+        .err()
+        .map(|e| super::receipt::describe_error(&e));
+    let estimated_gas = call.estimate_gas().await.ok();
+    print_dry_run(&DryRun {
+        function: call.function.signature(),
+        from: call.tx.from().copied(),
+        to: call.tx.to().and_then(|to| to.as_address().copied()),
+        value: call.tx.value().copied().unwrap_or_default(),
+        arguments: decode_arguments(&call.function, &calldata),
+        calldata,
+        estimated_gas,
+        revert,
+    });
+    Ok(None)
+}
+
 /// Contract interaction utilities
 pub mod contract {
     use super::*;
     use crate::commands::bridge::{
-        get_bridge_contract_address, get_wallet_with_provider, BridgeContract,
+        get_bridge_contract_address, get_wallet_with_provider, BridgeContract, SandboxClient,
     };
-    use ethers::middleware::SignerMiddleware;
-    use ethers::providers::{Http, Provider};
-    use ethers::signers::LocalWallet;
-    use std::sync::Arc;
 
     /// Get bridge contract instance with validation
     pub async fn get_bridge_contract(
         config: &Config,
         network: u64,
         private_key: Option<&str>,
-    ) -> Result<BridgeContract<SignerMiddleware<Arc<Provider<Http>>, LocalWallet>>> {
+    ) -> Result<BridgeContract<SandboxClient>> {
         validate_network_id(network, "Network")?;
         let client = get_wallet_with_provider(config, network, private_key).await?;
         let bridge_address = get_bridge_contract_address(config, network)?;
@@ -98,9 +368,74 @@ pub mod contract {
 }
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_amount() {
+        let ether = U256::exp10(18);
+        assert_eq!(format_amount(ether * 3 / 2, 18), "1.5");
+        assert_eq!(format_amount(ether * 10_000, 18), "10000");
+        assert_eq!(format_amount(U256::zero(), 18), "0");
+        assert_eq!(format_amount(U256::one(), 18), "<0.0001");
+        assert_eq!(format_amount(U256::from(1_234_567), 6), "1.2345");
+    }
+
+    #[test]
+    fn test_decode_arguments() {
+        #[allow(deprecated)]
+        let function = Function {
+            name: "bridgeAsset".to_string(),
+            inputs: vec![
+                ethers::abi::Param {
+                    name: "destinationNetwork".to_string(),
+                    kind: ethers::abi::ParamType::Uint(32),
+                    internal_type: None,
+                },
+                ethers::abi::Param {
+                    name: "destinationAddress".to_string(),
+                    kind: ethers::abi::ParamType::Address,
+                    internal_type: None,
+                },
+                ethers::abi::Param {
+                    name: "permitData".to_string(),
+                    kind: ethers::abi::ParamType::Bytes,
+                    internal_type: None,
+                },
+            ],
+            outputs: vec![],
+            constant: None,
+            state_mutability: ethers::abi::StateMutability::Payable,
+        };
+        let calldata = function
+            .encode_input(&[
+                Token::Uint(1.into()),
+                Token::Address(Address::repeat_byte(0x11)),
+                Token::Bytes(vec![0xab, 0xcd]),
+            ])
+            .unwrap();
+
+        let arguments = decode_arguments(&function, &calldata);
+        let values: Vec<(&str, &str, &str)> = arguments
+            .iter()
+            .map(|a| (a.name.as_str(), a.kind.as_str(), a.value.as_str()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("destinationNetwork", "uint32", "1"),
+                (
+                    "destinationAddress",
+                    "address",
+                    "0x1111111111111111111111111111111111111111"
+                ),
+                ("permitData", "bytes", "0xabcd"),
+            ]
+        );
+        assert!(decode_arguments(&function, &calldata[..3]).is_empty());
+    }
+
     #[test]
     fn test_validate_address_valid() {
         let result = validate_address("0xA0b86a33E6776e39e6b37ddEC4F25B04Dd9Fc4DC", "Test address");
@@ -122,7 +457,30 @@ mod tests {
 
     #[test]
     fn test_validate_network_id_invalid() {
-        assert!(validate_network_id(3, "Network").is_err());
+        assert!(validate_network_id(3, "Network").is_ok());
+        assert!(validate_network_id(crate::types::MAX_NETWORK_ID + 1, "Network").is_err());
+    }
+
+    #[test]
+    fn test_metadata_diagnostics() {
+        let empty = MetadataDiagnostics::from_hex("0x").unwrap();
+        assert_eq!(empty.byte_len, 0);
+        assert_eq!(empty.estimated_claim_gas, 0);
+
+        // One word of non-zero bytes: 32 * 16 calldata + 6 keccak + 3 copy + 3 memory
+        let word = MetadataDiagnostics::from_bytes(&[0xff; 32]);
+        assert_eq!(word.byte_len, 32);
+        assert_eq!(word.estimated_claim_gas, 32 * 16 + 12);
+
+        // Zero bytes and ABI padding are cheaper than non-zero bytes
+        let short = MetadataDiagnostics::from_bytes(&[0x00]);
+        assert_eq!(short.estimated_claim_gas, 32 * 4 + 12);
+
+        assert!(!word.is_large());
+        assert!(
+            MetadataDiagnostics::from_bytes(&vec![1u8; LARGE_METADATA_WARN_BYTES + 1]).is_large()
+        );
+        assert!(MetadataDiagnostics::from_hex("0xzz").is_none());
     }
 
     #[test]
@@ -130,6 +488,7 @@ mod tests {
         assert_eq!(get_network_name(0), "Mainnet");
         assert_eq!(get_network_name(1), "AggLayer-1");
         assert_eq!(get_network_name(2), "AggLayer-2");
+        assert_eq!(get_network_name(5), "AggLayer-5");
         assert_eq!(get_network_name(99), "Unknown");
     }
 }
@@ -0,0 +1,378 @@
+//! Exit root state of the global exit root managers
+//!
+//! On L1, `PolygonZkEVMGlobalExitRootV2` keeps the last mainnet and rollup exit
+//! roots and derives the global exit root from them. The sovereign L2 managers
+//! only keep their own rollup exit root and the global exit roots injected from
+//! L1. A claim on an L2 needs the L1 global exit root covering its deposit to
+//! have been injected there, so comparing both sides shows why a proof is not
+//! usable yet.
+
+use crate::config::Config;
+use crate::error::Result;
+use ethers::prelude::*;
+use serde::Serialize;
+use std::str::FromStr;
+use tracing::debug;
+
+use super::common::{get_network_name, rpc_error, validation_error};
+use super::faucet::layer_for_network;
+use super::get_provider;
+
+/// Blocks searched backwards for the last exit root update
+const UPDATE_SCAN_BLOCKS: u64 = 10_000;
+
+abigen!(
+    ExitRootManager,
+    r#"[
+        function lastMainnetExitRoot() external view returns (bytes32)
+        function lastRollupExitRoot() external view returns (bytes32)
+        function getLastGlobalExitRoot() external view returns (bytes32)
+        function globalExitRootMap(bytes32) external view returns (uint256)
+        function insertedGERCount() external view returns (uint256)
+        function depositCount() external view returns (uint256)
+        event UpdateL1InfoTree(bytes32 indexed mainnetExitRoot, bytes32 indexed rollupExitRoot)
+        event InsertGlobalExitRoot(bytes32 indexed newGlobalExitRoot)
+    ]"#,
+);
+
+/// Block of the last exit root update
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RootUpdate {
+    pub block_number: u64,
+    pub timestamp: Option<u64>,
+    pub tx_hash: Option<H256>,
+}
+
+/// Exit roots of one network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExitRoots {
+    pub network_id: u64,
+    pub manager: Address,
+    /// Only known on L1
+    pub mainnet_exit_root: Option<H256>,
+    pub rollup_exit_root: H256,
+    /// On L1 the current one, on L2 the last one injected
+    pub global_exit_root: Option<H256>,
+    /// Global exit roots injected so far (L2 only)
+    pub injected_count: Option<u64>,
+    /// Last update found within the last `UPDATE_SCAN_BLOCKS` blocks
+    pub last_update: Option<RootUpdate>,
+    /// Whether the current L1 global exit root has been injected (L2 only)
+    pub l1_root_injected: Option<bool>,
+}
+
+fn manager_address(config: &Config, network_id: u64) -> Result<Address> {
+    let layer = layer_for_network(network_id)?;
+    let address = config
+        .contracts
+        .get_contract(&layer, "GlobalExitRootManager");
+    Address::from_str(&address).map_err(|_| {
+        validation_error(&format!(
+            "No global exit root manager configured for network {network_id} ({})",
+            get_network_name(network_id)
+        ))
+    })
+}
+
+/// Last log of `event` emitted by `manager` in the scanned block range
+async fn last_update(
+    provider: &Provider<Http>,
+    manager: Address,
+    event: H256,
+    network_id: u64,
+) -> Result<Option<(Log, RootUpdate)>> {
+    let action = format!(
+        "scan exit root updates on network {network_id} ({})",
+        get_network_name(network_id)
+    );
+    let latest = provider
+        .get_block_number()
+        .await
+        .map_err(|e| rpc_error(&action, e))?
+        .as_u64();
+    let filter = Filter::new()
+        .address(manager)
+        .topic0(event)
+        .from_block(latest.saturating_sub(UPDATE_SCAN_BLOCKS))
+        .to_block(latest);
+    let logs = provider
+        .get_logs(&filter)
+        .await
+        .map_err(|e| rpc_error(&action, e))?;
+    let Some(log) = logs.into_iter().last() else {
+        return Ok(None);
+    };
+    let block_number = log.block_number.map_or(0, |number| number.as_u64());
+    let timestamp = provider
+        .get_block(block_number)
+        .await
+        .ok()
+        .flatten()
+        .map(|block| block.timestamp.as_u64());
+    let update = RootUpdate {
+        block_number,
+        timestamp,
+        tx_hash: log.transaction_hash,
+    };
+    Ok(Some((log, update)))
+}
+
+/// Number of leaves in the L1 info tree, kept by the L1 manager
+pub async fn l1_info_tree_leaf_count(config: &Config) -> Result<u64> {
+    let provider = get_provider(config, 0).await?;
+    let contract = ExitRootManager::new(manager_address(config, 0)?, provider);
+    let count = contract
+        .deposit_count()
+        .call()
+        .await
+        .map_err(|e| rpc_error("read the L1 info tree leaf count", e))?;
+    Ok(count.as_u64())
+}
+
+/// Exit roots of a network
+///
+/// For an L2, `l1_global_exit_root` is checked against the injected roots.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn fetch_exit_roots(
+    config: &Config,
+    network_id: u64,
+    l1_global_exit_root: Option<H256>,
+) -> Result<ExitRoots> {
+    let provider = get_provider(config, network_id).await?;
+    let manager = manager_address(config, network_id)?;
+    let contract = ExitRootManager::new(manager, provider.clone());
+    debug!(network_id, manager = ?manager, "Reading exit roots");
+    let action = format!(
+        "read exit roots on network {network_id} ({})",
+        get_network_name(network_id)
+    );
+
+    let rollup_exit_root = H256::from(
+        contract
+            .last_rollup_exit_root()
+            .call()
+            .await
+            .map_err(|e| rpc_error(&action, e))?,
+    );
+
+    if network_id == 0 {
+        let mainnet_exit_root = H256::from(
+            contract
+                .last_mainnet_exit_root()
+                .call()
+                .await
+                .map_err(|e| rpc_error(&action, e))?,
+        );
+        let global_exit_root = H256::from(
+            contract
+                .get_last_global_exit_root()
+                .call()
+                .await
+                .map_err(|e| rpc_error(&action, e))?,
+        );
+        let update = last_update(
+            &provider,
+            manager,
+            UpdateL1InfoTreeFilter::signature(),
+            network_id,
+        )
+        .await?;
+        return Ok(ExitRoots {
+            network_id,
+            manager,
+            mainnet_exit_root: Some(mainnet_exit_root),
+            rollup_exit_root,
+            global_exit_root: Some(global_exit_root),
+            injected_count: None,
+            last_update: update.map(|(_, update)| update),
+            l1_root_injected: None,
+        });
+    }
+
+    let injected_count = contract
+        .inserted_ger_count()
+        .call()
+        .await
+        .map_err(|e| rpc_error(&action, e))?
+        .as_u64();
+    let update = last_update(
+        &provider,
+        manager,
+        InsertGlobalExitRootFilter::signature(),
+        network_id,
+    )
+    .await?;
+    let global_exit_root = update
+        .as_ref()
+        .and_then(|(log, _)| log.topics.get(1).copied());
+    let l1_root_injected = match l1_global_exit_root {
+        Some(root) if root.is_zero() => Some(true),
+        Some(root) => Some(root_known(&contract, root, network_id).await?),
+        None => None,
+    };
+
+    Ok(ExitRoots {
+        network_id,
+        manager,
+        mainnet_exit_root: None,
+        rollup_exit_root,
+        global_exit_root,
+        injected_count: Some(injected_count),
+        last_update: update.map(|(_, update)| update),
+        l1_root_injected,
+    })
+}
+
+async fn root_known(
+    contract: &ExitRootManager<Provider<Http>>,
+    root: H256,
+    network_id: u64,
+) -> Result<bool> {
+    Ok(!contract
+        .global_exit_root_map(root.0)
+        .call()
+        .await
+        .map_err(|e| {
+            rpc_error(
+                &format!("look up global exit root {root:#x} on network {network_id}"),
+                e,
+            )
+        })?
+        .is_zero())
+}
+
+/// Whether the exit root manager of a network knows a global exit root
+///
+/// On L1 the manager records every global exit root it computes, on an L2 the
+/// ones injected from L1. A claim is only accepted against a known root.
+pub async fn global_exit_root_known(config: &Config, network_id: u64, root: H256) -> Result<bool> {
+    let provider = get_provider(config, network_id).await?;
+    let manager = manager_address(config, network_id)?;
+    root_known(&ExitRootManager::new(manager, provider), root, network_id).await
+}
+
+/// Exit roots of L1 followed by the requested L2s, checked against the L1 global exit root
+pub async fn fetch_all_exit_roots(config: &Config, networks: &[u64]) -> Result<Vec<ExitRoots>> {
+    let l1 = fetch_exit_roots(config, 0, None).await?;
+    let l1_root = l1.global_exit_root;
+    let mut roots = Vec::with_capacity(networks.len());
+    for &network_id in networks {
+        if network_id == 0 {
+            roots.push(l1.clone());
+        } else {
+            roots.push(fetch_exit_roots(config, network_id, l1_root).await?);
+        }
+    }
+    Ok(roots)
+}
+
+/// Display rows of the exit roots of a network
+pub fn exit_root_rows(roots: &ExitRoots) -> Vec<(String, String)> {
+    let hash = |root: Option<H256>| root.map_or_else(|| "n/a".to_string(), |r| format!("{r:#x}"));
+    let mut rows = vec![("Manager".to_string(), format!("{:#x}", roots.manager))];
+    if roots.network_id == 0 {
+        rows.push((
+            "Mainnet exit root".to_string(),
+            hash(roots.mainnet_exit_root),
+        ));
+    }
+    rows.push((
+        "Rollup exit root".to_string(),
+        format!("{:#x}", roots.rollup_exit_root),
+    ));
+    let global_label = if roots.network_id == 0 {
+        "Global exit root"
+    } else {
+        "Last injected GER"
+    };
+    rows.push((global_label.to_string(), hash(roots.global_exit_root)));
+    if let Some(count) = roots.injected_count {
+        rows.push(("Injected GERs".to_string(), count.to_string()));
+    }
+    let last_update = match &roots.last_update {
+        Some(update) => match update
+            .timestamp
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0))
+        {
+            Some(time) => format!(
+                "block {} ({})",
+                update.block_number,
+                time.format("%Y-%m-%d %H:%M:%S UTC")
+            ),
+            None => format!("block {}", update.block_number),
+        },
+        None => format!("none in the last {UPDATE_SCAN_BLOCKS} blocks"),
+    };
+    rows.push(("Last update".to_string(), last_update));
+    if let Some(injected) = roots.l1_root_injected {
+        let state = if injected {
+            "yes"
+        } else {
+            "no, not propagated yet"
+        };
+        rows.push(("L1 GER injected".to_string(), state.to_string()));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn l2_roots(injected: Option<bool>) -> ExitRoots {
+        ExitRoots {
+            network_id: 1,
+            manager: Address::repeat_byte(0xaa),
+            mainnet_exit_root: None,
+            rollup_exit_root: H256::repeat_byte(0x01),
+            global_exit_root: None,
+            injected_count: Some(3),
+            last_update: Some(RootUpdate {
+                block_number: 42,
+                timestamp: Some(1_700_000_000),
+                tx_hash: None,
+            }),
+            l1_root_injected: injected,
+        }
+    }
+
+    #[test]
+    fn test_l2_rows() {
+        let rows = exit_root_rows(&l2_roots(Some(false)));
+        let labels: Vec<&str> = rows.iter().map(|(label, _)| label.as_str()).collect();
+
+        assert_eq!(
+            labels,
+            vec![
+                "Manager",
+                "Rollup exit root",
+                "Last injected GER",
+                "Injected GERs",
+                "Last update",
+                "L1 GER injected"
+            ]
+        );
+        assert_eq!(rows[2].1, "n/a");
+        assert_eq!(rows[4].1, "block 42 (2023-11-14 22:13:20 UTC)");
+        assert_eq!(rows[5].1, "no, not propagated yet");
+    }
+
+    #[test]
+    fn test_l1_rows() {
+        let roots = ExitRoots {
+            network_id: 0,
+            mainnet_exit_root: Some(H256::repeat_byte(0x02)),
+            global_exit_root: Some(H256::repeat_byte(0x03)),
+            injected_count: None,
+            last_update: None,
+            l1_root_injected: None,
+            ..l2_roots(None)
+        };
+        let rows = exit_root_rows(&roots);
+
+        assert_eq!(rows[1].0, "Mainnet exit root");
+        assert_eq!(rows[3].0, "Global exit root");
+        assert_eq!(rows[4].1, "none in the last 10000 blocks");
+        assert_eq!(rows.len(), 5);
+    }
+}
@@ -0,0 +1,265 @@
+//! Balance top-ups for `bridge asset --faucet-if-needed`
+//!
+//! Scripted scenarios often bridge from freshly selected accounts that hold
+//! too little of the token being bridged. Before bridging, the missing balance
+//! is funded from the deployer account (the first configured key): ETH is
+//! transferred, and tokens deployed by the sandbox are minted, falling back to
+//! a transfer when the deployer is not the owner. Tokens deployed with
+//! `token deploy` count as sandbox tokens; any other token is never funded.
+
+use crate::config::{Config, ContractConfig};
+use crate::error::Result;
+use crate::ui;
+use ethers::prelude::*;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+use super::common::{rpc_error, validation_error};
+use super::token_deploy::TokenRegistry;
+use super::{get_provider, get_wallet_with_provider, ERC20Contract};
+
+/// Sandbox-deployed token contracts that the deployer can fund
+const SANDBOX_TOKENS: &[&str] = &["AggERC20"];
+/// ETH kept on top of the bridged amount to pay for approval and bridge gas
+const GAS_HEADROOM_WEI: u64 = 10_000_000_000_000_000; // 0.01 ETH
+
+/// Amount missing for `balance` to cover `required`, `None` when it already does
+pub fn shortfall(balance: U256, required: U256) -> Option<U256> {
+    (balance < required).then(|| required - balance)
+}
+
+/// Address of a sandbox token given by contract name or symbol, e.g. `AggERC20` or `AGG`
+pub fn resolve_sandbox_token(
+    contracts: &ContractConfig,
+    layer: &str,
+    name: &str,
+) -> Option<Address> {
+    let name = match name.to_ascii_lowercase().as_str() {
+        "agg" | "aggerc20" => "AggERC20",
+        _ => return None,
+    };
+    Address::from_str(&contracts.get_contract(layer, name)).ok()
+}
+
+/// Whether `token` is a sandbox-deployed token on the given layer
+pub fn is_sandbox_token(contracts: &ContractConfig, layer: &str, token: Address) -> bool {
+    SANDBOX_TOKENS.iter().any(|name| {
+        Address::from_str(&contracts.get_contract(layer, name)).is_ok_and(|addr| addr == token)
+    })
+}
+
+/// Config layer (`l1`, `l2`, `l3`, ...) of a network ID
+pub fn layer_for_network(network_id: u64) -> Result<String> {
+    if network_id > crate::types::MAX_NETWORK_ID {
+        return Err(validation_error(&format!(
+            "Unsupported network ID: {network_id}"
+        )));
+    }
+    Ok(crate::config::layer_name(network_id))
+}
+
+fn deployer_key(config: &Config) -> Result<&str> {
+    config
+        .accounts
+        .private_keys
+        .first()
+        .map(String::as_str)
+        .ok_or_else(|| validation_error("No deployer key configured to fund accounts from"))
+}
+
+/// Make sure `holder` can bridge `amount` of `token` on `network_id`
+///
+/// ETH is checked against the amount plus gas headroom; for tokens the holder
+/// also gets enough ETH to pay for the approval and bridge transactions.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn top_up(
+    config: &Config,
+    network_id: u64,
+    holder: Address,
+    token: Address,
+    amount: U256,
+) -> Result<()> {
+    let layer = layer_for_network(network_id)?;
+    let provider = get_provider(config, network_id).await?;
+    let deployer =
+        Arc::new(get_wallet_with_provider(config, network_id, Some(deployer_key(config)?)).await?);
+
+    let is_eth = token == Address::zero();
+    if !is_eth
+        && !is_sandbox_token(&config.contracts, &layer, token)
+        && !TokenRegistry::default_location().contains(network_id, token)
+    {
+        return Err(validation_error(&format!(
+            "--faucet-if-needed only works for ETH and sandbox-deployed tokens, {token:#x} is not one on network {network_id}"
+        )));
+    }
+
+    let eth_required = if is_eth {
+        amount.saturating_add(U256::from(GAS_HEADROOM_WEI))
+    } else {
+        U256::from(GAS_HEADROOM_WEI)
+    };
+    let eth_balance = provider
+        .get_balance(holder, None)
+        .await
+        .map_err(|e| rpc_error("fetch ETH balance", e))?;
+    if let Some(missing) = shortfall(eth_balance, eth_required) {
+        if holder == deployer.inner().address() {
+            return Err(validation_error(&format!(
+                "Deployer {holder:#x} is short of {missing} wei ETH and cannot fund itself"
+            )));
+        }
+        info!(holder = ?holder, missing = %missing, "Funding ETH shortfall from deployer");
+        let tx = TransactionRequest::new().to(holder).value(missing);
+        let pending = deployer
+            .send_transaction(tx, None)
+            .await
+            .map_err(|e| rpc_error("send faucet ETH transfer", e))?;
+        let tx_hash = pending.tx_hash();
+        pending
+            .await
+            .map_err(|e| rpc_error("confirm faucet ETH transfer", e))?;
+        ui::ui().success(&format!(
+            "Faucet sent {missing} wei ETH to {holder:#x}: {tx_hash:#x}"
+        ));
+    }
+
+    if is_eth {
+        return Ok(());
+    }
+
+    let token_balance = ERC20Contract::new(token, provider)
+        .balance_of(holder)
+        .call()
+        .await
+        .map_err(|e| rpc_error("fetch token balance", e))?;
+    let Some(missing) = shortfall(token_balance, amount) else {
+        debug!(holder = ?holder, balance = %token_balance, "Token balance already sufficient");
+        return Ok(());
+    };
+
+    info!(holder = ?holder, token = ?token, missing = %missing, "Funding token shortfall from deployer");
+    let tx_hash = mint_from_deployer(config, network_id, holder, token, missing).await?;
+    ui::ui().success(&format!(
+        "Faucet funded {missing} of token {token:#x} to {holder:#x}: {tx_hash:#x}"
+    ));
+
+    Ok(())
+}
+
+/// Mint `amount` of a sandbox token to `recipient` as the deployer
+///
+/// Falls back to a transfer from the deployer's own balance when the token
+/// refuses the mint, and waits for the transaction to be mined.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn mint_from_deployer(
+    config: &Config,
+    network_id: u64,
+    recipient: Address,
+    token: Address,
+    amount: U256,
+) -> Result<H256> {
+    let deployer =
+        Arc::new(get_wallet_with_provider(config, network_id, Some(deployer_key(config)?)).await?);
+    let erc20 = ERC20Contract::new(token, deployer.clone());
+
+    let mint = erc20.mint(recipient, amount);
+    // Built up front: the pending transaction borrows the call it was sent from
+    let transfer = erc20.transfer(recipient, amount);
+    let pending = match mint.send().await {
+        Ok(pending) => pending,
+        Err(e) => {
+            debug!(error = %e, "Mint rejected, transferring from deployer instead");
+            if recipient == deployer.inner().address() {
+                return Err(validation_error(&format!(
+                    "Deployer {recipient:#x} cannot mint {token:#x}: {e}"
+                )));
+            }
+            transfer
+                .send()
+                .await
+                .map_err(|e| rpc_error("send faucet token transfer", e))?
+        }
+    };
+    let tx_hash = pending.tx_hash();
+    let receipt = pending
+        .await
+        .map_err(|e| rpc_error("confirm faucet token transaction", e))?;
+    if receipt.is_some_and(|r| r.status == Some(U64::zero())) {
+        return Err(validation_error(&format!(
+            "Faucet token transaction {tx_hash:#x} reverted"
+        )));
+    }
+    Ok(tx_hash)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use crate::types::EthereumAddress;
+    use std::collections::HashMap;
+
+    const AGG_ERC20: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+
+    fn contracts() -> ContractConfig {
+        let mut l1_contracts = HashMap::new();
+        l1_contracts.insert(
+            "AggERC20".to_string(),
+            EthereumAddress::new(AGG_ERC20).unwrap(),
+        );
+        ContractConfig {
+            l1_contracts,
+            l2_contracts: HashMap::new(),
+            l3_contracts: HashMap::new(),
+            extra_contracts: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_shortfall() {
+        assert_eq!(
+            shortfall(U256::from(3), U256::from(10)),
+            Some(U256::from(7))
+        );
+        assert_eq!(shortfall(U256::from(10), U256::from(10)), None);
+        assert_eq!(shortfall(U256::from(12), U256::from(10)), None);
+    }
+
+    #[test]
+    fn test_is_sandbox_token() {
+        let contracts = contracts();
+        let token = Address::from_str(AGG_ERC20).unwrap();
+
+        assert!(is_sandbox_token(&contracts, "l1", token));
+        assert!(!is_sandbox_token(&contracts, "l2", token));
+        assert!(!is_sandbox_token(
+            &contracts,
+            "l1",
+            Address::repeat_byte(0x11)
+        ));
+    }
+
+    #[test]
+    fn test_resolve_sandbox_token() {
+        let contracts = contracts();
+        let token = Address::from_str(AGG_ERC20).unwrap();
+
+        assert_eq!(resolve_sandbox_token(&contracts, "l1", "AGG"), Some(token));
+        assert_eq!(
+            resolve_sandbox_token(&contracts, "l1", "aggerc20"),
+            Some(token)
+        );
+        assert_eq!(resolve_sandbox_token(&contracts, "l2", "AGG"), None);
+        assert_eq!(resolve_sandbox_token(&contracts, "l1", "USDC"), None);
+    }
+
+    #[test]
+    fn test_layer_for_network() {
+        assert_eq!(layer_for_network(0).unwrap(), "l1");
+        assert_eq!(layer_for_network(2).unwrap(), "l3");
+        assert_eq!(layer_for_network(7).unwrap(), "l8");
+        assert!(layer_for_network(crate::types::MAX_NETWORK_ID + 1).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! EIP-1559 fee detection and estimation
+//!
+//! Transactions are sent as type-2 transactions when the latest block of the
+//! target chain has a base fee, and as legacy transactions otherwise. Without
+//! `--max-fee` / `--priority-fee`, type-2 fees are estimated from
+//! `eth_feeHistory`, so fork mode against real networks pays what recent
+//! blocks paid instead of the sandbox defaults.
+
+use super::common::rpc_error;
+use crate::error::Result;
+use ethers::prelude::*;
+
+/// Blocks of fee history the estimate is based on
+pub const FEE_HISTORY_BLOCKS: u64 = 10;
+/// Reward percentile of each block taken as its priority fee
+const REWARD_PERCENTILE: f64 = 50.0;
+/// Priority fee used when recent blocks paid none (1 gwei)
+pub const DEFAULT_PRIORITY_FEE: u64 = 1_000_000_000;
+
+/// Whether the chain prices gas with a base fee (London and later)
+pub async fn supports_eip1559<M: Middleware>(client: &M) -> Result<bool> {
+    let block = client
+        .get_block(BlockNumber::Latest)
+        .await
+        .map_err(|e| rpc_error("read the latest block", e))?;
+    Ok(block.is_some_and(|block| block.base_fee_per_gas.is_some()))
+}
+
+/// Max fee and priority fee per gas estimated from recent blocks
+pub async fn estimate_fees<M: Middleware>(client: &M) -> Result<(U256, U256)> {
+    let history = client
+        .fee_history(
+            FEE_HISTORY_BLOCKS,
+            BlockNumber::Latest,
+            &[REWARD_PERCENTILE],
+        )
+        .await
+        .map_err(|e| rpc_error("read the fee history", e))?;
+    Ok(fees_from_history(&history))
+}
+
+/// Fees for the next block of a fee history
+///
+/// The priority fee is the median of the blocks' median rewards. The max fee
+/// leaves room for the base fee to double, the most it can rise over six full
+/// blocks, before the transaction stops being includable.
+pub fn fees_from_history(history: &FeeHistory) -> (U256, U256) {
+    // The last entry is the base fee of the block after the newest one
+    let next_base_fee = history.base_fee_per_gas.last().copied().unwrap_or_default();
+
+    let mut rewards: Vec<U256> = history
+        .reward
+        .iter()
+        .filter_map(|rewards| rewards.first().copied())
+        .filter(|reward| !reward.is_zero())
+        .collect();
+    rewards.sort();
+    let priority_fee = rewards
+        .get(rewards.len() / 2)
+        .copied()
+        .unwrap_or_else(|| U256::from(DEFAULT_PRIORITY_FEE));
+
+    (next_base_fee * 2 + priority_fee, priority_fee)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(base_fees: &[u64], rewards: &[u64]) -> FeeHistory {
+        FeeHistory {
+            base_fee_per_gas: base_fees.iter().map(|fee| U256::from(*fee)).collect(),
+            gas_used_ratio: vec![0.5; rewards.len()],
+            oldest_block: U256::from(100),
+            reward: rewards
+                .iter()
+                .map(|reward| vec![U256::from(*reward)])
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_fees_use_next_base_fee_and_median_reward() {
+        let (max_fee, priority_fee) = fees_from_history(&history(&[10, 20, 30, 40], &[3, 1, 2]));
+        assert_eq!(priority_fee, U256::from(2));
+        assert_eq!(max_fee, U256::from(82));
+    }
+
+    #[test]
+    fn test_empty_blocks_use_default_priority_fee() {
+        let (max_fee, priority_fee) = fees_from_history(&history(&[7, 7], &[0]));
+        assert_eq!(priority_fee, U256::from(DEFAULT_PRIORITY_FEE));
+        assert_eq!(max_fee, U256::from(14 + DEFAULT_PRIORITY_FEE));
+
+        let (_, priority_fee) = fees_from_history(&history(&[], &[]));
+        assert_eq!(priority_fee, U256::from(DEFAULT_PRIORITY_FEE));
+    }
+}
@@ -0,0 +1,248 @@
+//! Diagnosis of claims that lost a race against another claimer
+//!
+//! A claim reverts with `AlreadyClaimed()` when someone else, typically a
+//! sponsoring claimer such as the AggKit auto-claimer, claimed the deposit
+//! between the proof lookup and the transaction being mined. Instead of
+//! reporting a bare revert, the recent `ClaimEvent`s of the destination bridge
+//! are scanned for the deposit's global index to name the competing
+//! transaction, its sender and when it landed.
+
+use crate::error::{AggSandboxError, Result};
+use crate::ui;
+use ethers::prelude::*;
+use std::time::Duration;
+use tracing::debug;
+
+use super::common::validation_error;
+use super::gas_report;
+use super::receipt::{is_waiting, revert_reason};
+use super::utilities::decode_global_index;
+use super::BridgeContract;
+
+/// Selector of the bridge's `AlreadyClaimed()` custom error
+pub const ALREADY_CLAIMED_SELECTOR: &str = "0x646cf558";
+/// How far back ClaimEvents are scanned for the competing claim
+const CLAIM_SCAN_BLOCKS: u64 = 10_000;
+/// Receipt polls before a submitted claim is assumed to still be pending
+const RECEIPT_POLLS: u32 = 20;
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Claim transaction that claimed a deposit before ours
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompetingClaim {
+    pub tx_hash: H256,
+    pub block_number: u64,
+    pub claimer: Option<Address>,
+    pub destination_address: Address,
+    pub timestamp: Option<u64>,
+}
+
+impl CompetingClaim {
+    pub fn describe(&self) -> String {
+        let claimer = self
+            .claimer
+            .map_or_else(|| "an unknown sender".to_string(), |a| format!("{a:#x}"));
+        let when = self
+            .timestamp
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0))
+            .map(|time| format!(" at {}", time.format("%Y-%m-%d %H:%M:%S UTC")))
+            .unwrap_or_default();
+        format!(
+            "claimed by {claimer} in transaction {:#x} (block {}{when}), funds sent to {:#x}",
+            self.tx_hash, self.block_number, self.destination_address
+        )
+    }
+}
+
+/// Whether an error message carries the `AlreadyClaimed()` revert
+pub fn is_already_claimed_error(message: &str) -> bool {
+    message.contains("AlreadyClaimed") || message.contains(&ALREADY_CLAIMED_SELECTOR[2..])
+}
+
+/// Whether a ClaimEvent's global index refers to the given deposit
+///
+/// Besides the encoded global index, the raw deposit count is accepted as the
+/// sandbox claims pass it unencoded.
+pub fn claim_matches(global_index: U256, source_network: u64, deposit_count: u64) -> bool {
+    if global_index == U256::from(deposit_count) {
+        return true;
+    }
+    let (network, local_index) = decode_global_index(global_index);
+    network == source_network && u64::from(local_index) == deposit_count
+}
+
+/// Wait for a submitted claim to be mined and explain an `AlreadyClaimed` revert
+///
+/// Claims still pending after the polling window, or any claim under
+/// `--no-wait`, are left alone; the deposit is checked with `isClaimed` only
+/// when the transaction reverted. Other reverts report the decoded reason.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn check_claim_receipt<M: Middleware + 'static>(
+    bridge: &BridgeContract<M>,
+    tx_hash: H256,
+    source_network: u64,
+    deposit_count: u64,
+) -> Result<()> {
+    if !is_waiting() {
+        return Ok(());
+    }
+
+    let client = bridge.client();
+    let mut receipt = None;
+    for _ in 0..RECEIPT_POLLS {
+        receipt = client
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(|e| AggSandboxError::Other(format!("Failed to fetch claim receipt: {e}")))?;
+        if receipt.is_some() {
+            break;
+        }
+        tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+    }
+
+    let Some(receipt) = receipt else {
+        debug!(tx_hash = ?tx_hash, "Claim not mined yet, skipping revert check");
+        return Ok(());
+    };
+    if receipt.status != Some(U64::zero()) {
+        gas_report::record_receipt(&receipt);
+        return Ok(());
+    }
+
+    let claimed = bridge
+        .is_claimed(deposit_count as u32, source_network as u32)
+        .call()
+        .await
+        .unwrap_or(false);
+    if claimed {
+        Err(already_claimed_error(bridge, source_network, deposit_count).await)
+    } else {
+        let reason = revert_reason(client.as_ref(), &receipt).await;
+        Err(validation_error(&format!(
+            "Claim transaction {tx_hash:#x} reverted: {reason}"
+        )))
+    }
+}
+
+/// Build the error for a deposit that was claimed by someone else
+pub async fn already_claimed_error<M: Middleware + 'static>(
+    bridge: &BridgeContract<M>,
+    source_network: u64,
+    deposit_count: u64,
+) -> AggSandboxError {
+    let deposit = format!("Deposit {deposit_count} from network {source_network}");
+    match find_competing_claim(bridge, source_network, deposit_count).await {
+        Some(competing) => {
+            ui::ui().warning(&format!(
+                "Claim front-run: {deposit} was {}",
+                competing.describe()
+            ));
+            validation_error(&format!(
+                "{deposit} was already {}",
+                competing.describe()
+            ))
+        }
+        None => validation_error(&format!(
+            "{deposit} was already claimed; the competing claim is older than the last {CLAIM_SCAN_BLOCKS} blocks"
+        )),
+    }
+}
+
+/// Most recent ClaimEvent of the destination bridge for a deposit
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn find_competing_claim<M: Middleware + 'static>(
+    bridge: &BridgeContract<M>,
+    source_network: u64,
+    deposit_count: u64,
+) -> Option<CompetingClaim> {
+    let client = bridge.client();
+    let latest = client.get_block_number().await.ok()?.as_u64();
+    let events = bridge
+        .claim_event_filter()
+        .from_block(latest.saturating_sub(CLAIM_SCAN_BLOCKS))
+        .query_with_meta()
+        .await
+        .map_err(|e| debug!(error = %e, "Failed to scan ClaimEvents"))
+        .ok()?;
+
+    let (event, meta) = events
+        .into_iter()
+        .rev()
+        .find(|(event, _)| claim_matches(event.global_index, source_network, deposit_count))?;
+
+    let claimer = client
+        .get_transaction(meta.transaction_hash)
+        .await
+        .ok()
+        .flatten()
+        .map(|tx| tx.from);
+    let timestamp = client
+        .get_block(meta.block_number)
+        .await
+        .ok()
+        .flatten()
+        .map(|block| block.timestamp.as_u64());
+
+    Some(CompetingClaim {
+        tx_hash: meta.transaction_hash,
+        block_number: meta.block_number.as_u64(),
+        claimer,
+        destination_address: event.destination_address,
+        timestamp,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::bridge::utilities::{compute_global_index, ComputeGlobalIndexArgs};
+
+    #[test]
+    fn test_already_claimed_selector() {
+        let selector = &ethers::utils::id("AlreadyClaimed()")[..4];
+        assert_eq!(
+            format!("0x{}", hex::encode(selector)),
+            ALREADY_CLAIMED_SELECTOR
+        );
+    }
+
+    #[test]
+    fn test_is_already_claimed_error() {
+        assert!(is_already_claimed_error(
+            "Failed to send claim asset transaction: execution reverted: custom error 646cf558"
+        ));
+        assert!(is_already_claimed_error("reverted with AlreadyClaimed()"));
+        assert!(!is_already_claimed_error(
+            "execution reverted: InvalidSmtProof"
+        ));
+    }
+
+    #[test]
+    fn test_claim_matches() {
+        let mainnet = compute_global_index(ComputeGlobalIndexArgs {
+            index_local: 7,
+            source_network_id: 0,
+        });
+        assert!(claim_matches(mainnet, 0, 7));
+        assert!(!claim_matches(mainnet, 1, 7));
+        assert!(!claim_matches(mainnet, 0, 8));
+
+        assert!(claim_matches(U256::from(7), 0, 7));
+    }
+
+    #[test]
+    fn test_describe_competing_claim() {
+        let competing = CompetingClaim {
+            tx_hash: H256::repeat_byte(0xab),
+            block_number: 42,
+            claimer: Some(Address::repeat_byte(0x11)),
+            destination_address: Address::repeat_byte(0x22),
+            timestamp: Some(0),
+        };
+
+        let description = competing.describe();
+
+        assert!(description.contains("0x1111111111111111111111111111111111111111"));
+        assert!(description.contains("block 42 at 1970-01-01 00:00:00 UTC"));
+    }
+}
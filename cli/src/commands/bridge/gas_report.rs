@@ -0,0 +1,350 @@
+//! Gas used by bridge and claim operations
+//!
+//! Every transaction an operation waits for (approvals, bridges, claims) adds
+//! its `gas_used` and `effective_gas_price` to the operation. When the
+//! operation reports its timings the total is printed and appended to
+//! `.aggsandbox/gas_report.json`, which `aggsandbox show gas-report`
+//! aggregates per operation type. The report covers the current sandbox
+//! session: `aggsandbox stop` clears it. Nothing is recorded under `--no-wait`
+//! or `--dry-run`, where no receipt is read.
+
+use crate::commands::bridge::common::format_amount;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tracing::debug;
+
+const GAS_REPORT_FILE: &str = ".aggsandbox/gas_report.json";
+
+/// Receipts of the operation in progress
+#[allow(clippy::disallowed_types)] // Only locked briefly, never across an await
+static RECEIPTS: std::sync::Mutex<Vec<TransactionGas>> = std::sync::Mutex::new(Vec::new());
+
+/// Gas of one mined transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionGas {
+    pub tx_hash: H256,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+}
+
+impl TransactionGas {
+    pub fn cost(&self) -> U256 {
+        self.gas_used.saturating_mul(self.effective_gas_price)
+    }
+}
+
+/// Gas of one finished operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperationGas {
+    /// Operation as reported with its timings, e.g. `bridge asset` or `claim`
+    pub operation: String,
+    pub network: Option<u64>,
+    pub transactions: Vec<TransactionGas>,
+    pub recorded_at: String,
+}
+
+impl OperationGas {
+    pub fn gas_used(&self) -> U256 {
+        self.transactions
+            .iter()
+            .fold(U256::zero(), |total, tx| total.saturating_add(tx.gas_used))
+    }
+
+    pub fn cost(&self) -> U256 {
+        self.transactions
+            .iter()
+            .fold(U256::zero(), |total, tx| total.saturating_add(tx.cost()))
+    }
+
+    /// Gas price paid on average, weighted by gas
+    pub fn effective_gas_price(&self) -> U256 {
+        self.cost().checked_div(self.gas_used()).unwrap_or_default()
+    }
+
+    /// Gas fields added to the operation's JSON report
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "transactions": self.transactions.len(),
+            "gas_used": self.gas_used().to_string(),
+            "effective_gas_price": self.effective_gas_price().to_string(),
+            "cost_wei": self.cost().to_string(),
+        })
+    }
+}
+
+/// Gas of every operation of one type
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OperationSummary {
+    pub operation: String,
+    pub runs: usize,
+    pub transactions: usize,
+    #[serde(serialize_with = "decimal")]
+    pub total_gas_used: U256,
+    #[serde(serialize_with = "decimal")]
+    pub average_gas_used: U256,
+    #[serde(serialize_with = "decimal")]
+    pub min_gas_used: U256,
+    #[serde(serialize_with = "decimal")]
+    pub max_gas_used: U256,
+    /// Gas price paid on average, weighted by gas
+    #[serde(serialize_with = "decimal")]
+    pub average_gas_price: U256,
+    #[serde(serialize_with = "decimal")]
+    pub total_cost_wei: U256,
+}
+
+fn decimal<S: serde::Serializer>(value: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+/// Add a mined transaction to the operation in progress
+pub fn record_receipt(receipt: &TransactionReceipt) {
+    let gas = TransactionGas {
+        tx_hash: receipt.transaction_hash,
+        gas_used: receipt.gas_used.unwrap_or_default(),
+        effective_gas_price: receipt.effective_gas_price.unwrap_or_default(),
+    };
+    if let Ok(mut receipts) = RECEIPTS.lock() {
+        receipts.push(gas);
+    }
+}
+
+/// Take the transactions of the finished operation, `None` when none was mined
+pub fn finish_operation(operation: &str, network: Option<u64>) -> Option<OperationGas> {
+    let transactions = RECEIPTS
+        .lock()
+        .map(|mut receipts| std::mem::take(&mut *receipts))
+        .unwrap_or_default();
+    if transactions.is_empty() {
+        return None;
+    }
+    let gas = OperationGas {
+        operation: operation.to_string(),
+        network,
+        transactions,
+        recorded_at: chrono::Utc::now().to_rfc3339(),
+    };
+    GasReportStore::default_location().append(&gas);
+    Some(gas)
+}
+
+/// Operations of the session stored in the working directory
+#[derive(Debug, Clone)]
+pub struct GasReportStore {
+    path: PathBuf,
+}
+
+impl GasReportStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Report at `.aggsandbox/gas_report.json` of the working directory
+    pub fn default_location() -> Self {
+        Self::new(GAS_REPORT_FILE)
+    }
+
+    /// Recorded operations, oldest first
+    pub fn load(&self) -> Vec<OperationGas> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Append an operation
+    ///
+    /// The operation itself succeeded, so a failed write is only logged.
+    #[allow(clippy::disallowed_methods)] // Allow tracing macros
+    pub fn append(&self, gas: &OperationGas) {
+        let mut operations = self.load();
+        operations.push(gas.clone());
+
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(&operations).map_err(|e| e.to_string()))
+            .and_then(|json| fs::write(&self.path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            debug!(path = %self.path.display(), error = %e, "Failed to record gas usage");
+        }
+    }
+
+    pub fn clear(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Aggregate operations per type, in order of their first run
+pub fn summarize(operations: &[OperationGas]) -> Vec<OperationSummary> {
+    let mut order: Vec<&str> = Vec::new();
+    let mut by_operation: BTreeMap<&str, Vec<&OperationGas>> = BTreeMap::new();
+    for gas in operations {
+        if !by_operation.contains_key(gas.operation.as_str()) {
+            order.push(&gas.operation);
+        }
+        by_operation.entry(&gas.operation).or_default().push(gas);
+    }
+
+    order
+        .into_iter()
+        .map(|operation| {
+            let runs = &by_operation[operation];
+            let gas_used: Vec<U256> = runs.iter().map(|gas| gas.gas_used()).collect();
+            let total_gas_used = gas_used
+                .iter()
+                .fold(U256::zero(), |total, gas| total.saturating_add(*gas));
+            let total_cost_wei = runs
+                .iter()
+                .fold(U256::zero(), |total, gas| total.saturating_add(gas.cost()));
+            OperationSummary {
+                operation: operation.to_string(),
+                runs: runs.len(),
+                transactions: runs.iter().map(|gas| gas.transactions.len()).sum(),
+                total_gas_used,
+                average_gas_used: total_gas_used / U256::from(runs.len()),
+                min_gas_used: gas_used.iter().copied().min().unwrap_or_default(),
+                max_gas_used: gas_used.iter().copied().max().unwrap_or_default(),
+                average_gas_price: total_cost_wei
+                    .checked_div(total_gas_used)
+                    .unwrap_or_default(),
+                total_cost_wei,
+            }
+        })
+        .collect()
+}
+
+/// Table of the summaries, one row per operation type
+pub fn render_table(summaries: &[OperationSummary]) -> String {
+    let header = [
+        "Operation",
+        "Runs",
+        "Txs",
+        "Avg gas",
+        "Min gas",
+        "Max gas",
+        "Avg price (gwei)",
+        "Total cost (ETH)",
+    ];
+    let mut lines = vec![header
+        .iter()
+        .map(|cell| cell.to_string())
+        .collect::<Vec<_>>()];
+    for summary in summaries {
+        lines.push(vec![
+            summary.operation.clone(),
+            summary.runs.to_string(),
+            summary.transactions.to_string(),
+            summary.average_gas_used.to_string(),
+            summary.min_gas_used.to_string(),
+            summary.max_gas_used.to_string(),
+            format_amount(summary.average_gas_price, 9),
+            format_amount(summary.total_cost_wei, 18),
+        ]);
+    }
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            lines
+                .iter()
+                .map(|line| line[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let separator = widths
+        .iter()
+        .map(|width| "─".repeat(width + 2))
+        .collect::<Vec<_>>();
+    let mut output = format!("┌{}┐\n", separator.join("┬"));
+    for (index, line) in lines.iter().enumerate() {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!(" {cell:<width$} "))
+            .collect();
+        output.push_str(&format!("│{}│\n", cells.join("│")));
+        if index == 0 {
+            output.push_str(&format!("├{}┤\n", separator.join("┼")));
+        }
+    }
+    output.push_str(&format!("└{}┘", separator.join("┴")));
+    output
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn operation(name: &str, transactions: &[(u64, u64)]) -> OperationGas {
+        OperationGas {
+            operation: name.to_string(),
+            network: Some(0),
+            transactions: transactions
+                .iter()
+                .map(|&(gas_used, price)| TransactionGas {
+                    tx_hash: H256::zero(),
+                    gas_used: gas_used.into(),
+                    effective_gas_price: price.into(),
+                })
+                .collect(),
+            recorded_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_operation_totals() {
+        let gas = operation("bridge asset", &[(50_000, 10), (150_000, 2)]);
+        assert_eq!(gas.gas_used(), U256::from(200_000));
+        assert_eq!(gas.cost(), U256::from(800_000));
+        assert_eq!(gas.effective_gas_price(), U256::from(4));
+    }
+
+    #[test]
+    fn test_summarize_per_operation() {
+        let summaries = summarize(&[
+            operation("claim", &[(100_000, 1)]),
+            operation("bridge asset", &[(50_000, 1), (150_000, 1)]),
+            operation("claim", &[(120_000, 3)]),
+        ]);
+        assert_eq!(summaries.len(), 2);
+
+        let claim = &summaries[0];
+        assert_eq!(claim.operation, "claim");
+        assert_eq!((claim.runs, claim.transactions), (2, 2));
+        assert_eq!(claim.total_gas_used, U256::from(220_000));
+        assert_eq!(claim.average_gas_used, U256::from(110_000));
+        assert_eq!(claim.min_gas_used, U256::from(100_000));
+        assert_eq!(claim.max_gas_used, U256::from(120_000));
+        assert_eq!(claim.total_cost_wei, U256::from(460_000));
+        assert_eq!(claim.average_gas_price, U256::from(2));
+
+        assert_eq!(summaries[1].operation, "bridge asset");
+        assert_eq!(summaries[1].transactions, 2);
+
+        let table = render_table(&summaries);
+        assert!(table.contains("claim"));
+        assert!(table.contains("110000"));
+    }
+
+    #[test]
+    fn test_store_appends_and_clears() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = GasReportStore::new(dir.path().join(".aggsandbox/gas_report.json"));
+        assert!(store.load().is_empty());
+
+        store.append(&operation("claim", &[(100_000, 1)]));
+        store.append(&operation("claim", &[(90_000, 1)]));
+        assert_eq!(store.load().len(), 2);
+
+        store.clear();
+        assert!(store.load().is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+//! Chain halt detection during waits
+//!
+//! `bridge claim --auto-wait` and `bridge watch` poll the bridge service until
+//! something happens on chain, and would keep polling forever if an Anvil node
+//! stopped, e.g. because its container crashed. [`ChainWatch`] probes the
+//! nodes between polls and fails with "stuck at block N since T" once a chain
+//! has not advanced for [`halt_timeout`] although it should have: its RPC
+//! stopped answering, or transactions are pending that it does not mine. An
+//! idle automining chain produces no blocks and is not treated as halted.
+
+use crate::config::Config;
+use crate::error::{AggSandboxError, EventError, Result};
+use ethers::prelude::*;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::common::get_network_name;
+use super::get_provider;
+
+/// Environment variable overriding how long a chain may stall, in seconds
+pub const HALT_TIMEOUT_ENV: &str = "CHAIN_HALT_TIMEOUT";
+const DEFAULT_HALT_TIMEOUT: Duration = Duration::from_secs(60);
+/// Minimum time between two probes of the same chain
+const PROBE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long a chain may stall before it counts as halted
+pub fn halt_timeout() -> Duration {
+    std::env::var(HALT_TIMEOUT_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map_or(DEFAULT_HALT_TIMEOUT, Duration::from_secs)
+}
+
+/// Chain head as seen by one probe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Observation {
+    Head {
+        number: u64,
+        timestamp: u64,
+        /// Transactions waiting in the node's mempool
+        pending: u64,
+    },
+    Unreachable(String),
+}
+
+/// Why a chain counts as halted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HaltReason {
+    Unreachable(String),
+    NotMining { pending: u64 },
+}
+
+/// Chain that stopped producing blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainHalt {
+    pub network_id: u64,
+    /// Last block seen, `None` when the node never answered
+    pub block: Option<u64>,
+    /// Timestamp of `block`
+    pub since: Option<u64>,
+    pub stalled_for: Duration,
+    pub reason: HaltReason,
+}
+
+impl ChainHalt {
+    /// Docker service running the network's node
+    pub fn service(&self) -> String {
+        format!("anvil-l{}", self.network_id + 1)
+    }
+
+    pub fn message(&self) -> String {
+        let since = self
+            .since
+            .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp as i64, 0));
+        let head = match (self.block, since) {
+            (Some(block), Some(since)) => {
+                format!("stuck at block {block} since {}", since.to_rfc3339())
+            }
+            (Some(block), None) => format!("stuck at block {block}"),
+            (None, _) => "not reachable".to_string(),
+        };
+        let stalled = self.stalled_for.as_secs();
+        let reason = match &self.reason {
+            HaltReason::Unreachable(error) => {
+                format!("its RPC has not answered for {stalled}s ({error})")
+            }
+            HaltReason::NotMining { pending } => {
+                format!("{pending} pending transaction(s) have not been mined for {stalled}s")
+            }
+        };
+        format!(
+            "network {} ({}) is {head}: {reason}. Check the node with 'aggsandbox logs {}'",
+            self.network_id,
+            get_network_name(self.network_id),
+            self.service()
+        )
+    }
+}
+
+impl From<ChainHalt> for AggSandboxError {
+    fn from(halt: ChainHalt) -> Self {
+        EventError::chain_halted(&halt.message()).into()
+    }
+}
+
+/// Progress of one chain across probes
+#[derive(Debug, Clone)]
+pub struct HaltDetector {
+    network_id: u64,
+    timeout: Duration,
+    block: Option<u64>,
+    timestamp: Option<u64>,
+    progressed_at: Instant,
+}
+
+impl HaltDetector {
+    pub fn new(network_id: u64, timeout: Duration, now: Instant) -> Self {
+        Self {
+            network_id,
+            timeout,
+            block: None,
+            timestamp: None,
+            progressed_at: now,
+        }
+    }
+
+    /// Record a probe, returning the halt once the chain stalled too long
+    pub fn observe(&mut self, observation: Observation, now: Instant) -> Option<ChainHalt> {
+        let reason = match observation {
+            Observation::Head {
+                number,
+                timestamp,
+                pending,
+            } => {
+                // A new block is progress, and without pending transactions
+                // there is nothing to mine
+                if self.block != Some(number) || pending == 0 {
+                    self.block = Some(number);
+                    self.timestamp = Some(timestamp);
+                    self.progressed_at = now;
+                    return None;
+                }
+                HaltReason::NotMining { pending }
+            }
+            Observation::Unreachable(error) => HaltReason::Unreachable(error),
+        };
+
+        let stalled_for = now.duration_since(self.progressed_at);
+        (stalled_for >= self.timeout).then_some(ChainHalt {
+            network_id: self.network_id,
+            block: self.block,
+            since: self.timestamp,
+            stalled_for,
+            reason,
+        })
+    }
+}
+
+/// Probe the head and mempool of a chain
+pub async fn probe<M: Middleware>(client: &M) -> Observation {
+    let block = match client.get_block(BlockNumber::Latest).await {
+        Ok(Some(block)) => block,
+        Ok(None) => return Observation::Unreachable("no latest block".to_string()),
+        Err(e) => return Observation::Unreachable(e.to_string()),
+    };
+    // Nodes without the txpool namespace are only checked for reachability
+    let pending = client
+        .txpool_status()
+        .await
+        .map_or(0, |status| status.pending.as_u64());
+    Observation::Head {
+        number: block.number.unwrap_or_default().as_u64(),
+        timestamp: block.timestamp.as_u64(),
+        pending,
+    }
+}
+
+/// Halt detection for the chains a wait depends on
+pub struct ChainWatch {
+    chains: Vec<(Arc<Provider<Http>>, HaltDetector)>,
+    last_probe: Option<Instant>,
+}
+
+impl ChainWatch {
+    pub async fn new(config: &Config, network_ids: &[u64]) -> Result<Self> {
+        let timeout = halt_timeout();
+        let now = Instant::now();
+        let mut chains = Vec::with_capacity(network_ids.len());
+        for &network_id in network_ids {
+            chains.push((
+                get_provider(config, network_id).await?,
+                HaltDetector::new(network_id, timeout, now),
+            ));
+        }
+        Ok(Self {
+            chains,
+            last_probe: None,
+        })
+    }
+
+    /// Probe the chains unless they were probed recently, failing on a halt
+    pub async fn check(&mut self) -> Result<()> {
+        let now = Instant::now();
+        if self
+            .last_probe
+            .is_some_and(|last| now.duration_since(last) < PROBE_INTERVAL)
+        {
+            return Ok(());
+        }
+        self.last_probe = Some(now);
+
+        for (provider, detector) in &mut self.chains {
+            let observation = probe(provider.as_ref()).await;
+            if let Some(halt) = detector.observe(observation, Instant::now()) {
+                return Err(halt.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn head(number: u64, pending: u64) -> Observation {
+        Observation::Head {
+            number,
+            timestamp: 1_700_000_000 + number,
+            pending,
+        }
+    }
+
+    #[test]
+    fn test_idle_chain_is_not_halted() {
+        let start = Instant::now();
+        let mut detector = HaltDetector::new(1, Duration::from_secs(60), start);
+        assert!(detector.observe(head(10, 0), start).is_none());
+        assert!(detector
+            .observe(head(10, 0), start + Duration::from_secs(600))
+            .is_none());
+    }
+
+    #[test]
+    fn test_pending_transactions_not_mined() {
+        let start = Instant::now();
+        let mut detector = HaltDetector::new(1, Duration::from_secs(60), start);
+        assert!(detector.observe(head(10, 2), start).is_none());
+        assert!(detector
+            .observe(head(10, 2), start + Duration::from_secs(30))
+            .is_none());
+
+        let halt = detector
+            .observe(head(10, 2), start + Duration::from_secs(61))
+            .unwrap();
+        assert_eq!(halt.block, Some(10));
+        assert_eq!(halt.reason, HaltReason::NotMining { pending: 2 });
+        assert!(halt
+            .message()
+            .contains("stuck at block 10 since 2023-11-14T22:13:30+00:00"));
+        assert!(halt.message().contains("aggsandbox logs anvil-l2"));
+    }
+
+    #[test]
+    fn test_new_block_resets_the_timer() {
+        let start = Instant::now();
+        let mut detector = HaltDetector::new(0, Duration::from_secs(60), start);
+        detector.observe(head(10, 1), start);
+        detector.observe(head(11, 1), start + Duration::from_secs(50));
+        assert!(detector
+            .observe(head(11, 1), start + Duration::from_secs(100))
+            .is_none());
+    }
+
+    #[test]
+    fn test_unreachable_node() {
+        let start = Instant::now();
+        let mut detector = HaltDetector::new(2, Duration::from_secs(60), start);
+        detector.observe(head(7, 0), start);
+        let unreachable = Observation::Unreachable("connection refused".to_string());
+        assert!(detector
+            .observe(unreachable.clone(), start + Duration::from_secs(10))
+            .is_none());
+
+        let halt = detector
+            .observe(unreachable, start + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(halt.service(), "anvil-l3");
+        assert!(halt.message().contains("stuck at block 7"));
+        assert!(halt.message().contains("connection refused"));
+    }
+}
@@ -0,0 +1,177 @@
+//! Idempotency keys for bridge and claim operations
+//!
+//! CI wrappers that retry flaky steps would otherwise bridge funds or send
+//! claims twice. With `--idempotency-key` an operation that completes is
+//! recorded under the key in `.aggsandbox/idempotency.json`, and running the
+//! command again with the same key prints the recorded result instead of
+//! sending anything.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::common::validation_error;
+use crate::error::{AggSandboxError, Result};
+use crate::ui;
+
+const IDEMPOTENCY_FILE: &str = ".aggsandbox/idempotency.json";
+
+/// Result of the last operation that finished, as reported with its timings
+#[allow(clippy::disallowed_types)] // Only locked briefly, never across an await
+static LAST_RESULT: std::sync::Mutex<Option<serde_json::Value>> = std::sync::Mutex::new(None);
+
+/// Operation completed under an idempotency key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletedOperation {
+    /// Bridge subcommand that ran, e.g. `asset` or `claim`
+    pub command: String,
+    /// Result the operation reported (transaction hash, networks, ...)
+    pub result: serde_json::Value,
+    pub completed_at: String,
+}
+
+impl CompletedOperation {
+    pub fn new(command: &str, result: serde_json::Value) -> Self {
+        Self {
+            command: command.to_string(),
+            result,
+            completed_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Completed operations stored in the working directory
+#[derive(Debug, Clone)]
+pub struct IdempotencyStore {
+    path: PathBuf,
+}
+
+impl IdempotencyStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Store at `.aggsandbox/idempotency.json` of the working directory
+    pub fn default_location() -> Self {
+        Self::new(IDEMPOTENCY_FILE)
+    }
+
+    /// Completed operations by key, empty when nothing was recorded yet
+    pub fn load(&self) -> BTreeMap<String, CompletedOperation> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<CompletedOperation> {
+        self.load().remove(key)
+    }
+
+    /// Record an operation that completed under `key`
+    ///
+    /// Unlike the last-used values a failed write is an error: a retry would
+    /// send the operation again.
+    pub fn record(&self, key: &str, operation: CompletedOperation) -> Result<()> {
+        let mut operations = self.load();
+        operations.insert(key.to_string(), operation);
+
+        self.path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(&operations).map_err(|e| e.to_string()))
+            .and_then(|json| fs::write(&self.path, json).map_err(|e| e.to_string()))
+            .map_err(|e| {
+                AggSandboxError::Other(format!(
+                    "Failed to record idempotency key '{key}' in {}: {e}",
+                    self.path.display()
+                ))
+            })
+    }
+}
+
+/// Remember the result of a finished operation for [`take_result`]
+pub fn capture(result: &serde_json::Value) {
+    if let Ok(mut last) = LAST_RESULT.lock() {
+        *last = Some(result.clone());
+    }
+}
+
+/// Result of the operation that finished last, if any
+pub fn take_result() -> Option<serde_json::Value> {
+    LAST_RESULT.lock().ok().and_then(|mut last| last.take())
+}
+
+/// Print the recorded result of an operation instead of running it again
+///
+/// Fails when the key was used for another subcommand, which is almost
+/// certainly a key reused by mistake.
+pub fn replay(key: &str, command: &str, completed: &CompletedOperation) -> Result<()> {
+    if completed.command != command {
+        return Err(validation_error(&format!(
+            "Idempotency key '{key}' was already used for 'bridge {}' at {}",
+            completed.command, completed.completed_at
+        )));
+    }
+
+    let ui = ui::ui();
+    if ui.is_json() {
+        let mut result = completed.result.clone();
+        if let Some(object) = result.as_object_mut() {
+            object.insert("idempotency_key".to_string(), key.into());
+            object.insert("replayed".to_string(), true.into());
+            object.insert(
+                "completed_at".to_string(),
+                completed.completed_at.clone().into(),
+            );
+        }
+        ui.json(&result);
+        return Ok(());
+    }
+
+    ui.success(&format!(
+        "'bridge {command}' already completed under idempotency key '{key}' at {}; nothing sent",
+        completed.completed_at
+    ));
+    if let Some(tx_hash) = completed.result["tx_hash"].as_str() {
+        ui.info(&format!("Transaction: {tx_hash}"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_record_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IdempotencyStore::new(dir.path().join(".aggsandbox").join("idempotency.json"));
+        assert!(store.get("deposit-1").is_none());
+
+        let operation = CompletedOperation::new("asset", json!({ "tx_hash": "0xabc" }));
+        store.record("deposit-1", operation.clone()).unwrap();
+        store
+            .record(
+                "claim-1",
+                CompletedOperation::new("claim", json!({ "tx_hash": "0xdef" })),
+            )
+            .unwrap();
+
+        assert_eq!(store.get("deposit-1"), Some(operation));
+        assert_eq!(store.get("claim-1").unwrap().command, "claim");
+    }
+
+    #[test]
+    fn test_replay_rejects_other_command() {
+        let completed = CompletedOperation::new("asset", json!({ "tx_hash": "0xabc" }));
+        let error = replay("deposit-1", "claim", &completed)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("already used for 'bridge asset'"));
+    }
+}
@@ -0,0 +1,217 @@
+//! Bridge service (aggkit) indexing lag detection
+//!
+//! Each aggkit instance indexes deposits from its network's bridge contract.
+//! Comparing the contract's `depositCount()` with the deposits the service has
+//! indexed tells whether it is behind, and the block of the last indexed deposit
+//! bounds how far behind the chain head it can be.
+
+use crate::api_client::OptimizedApiClient;
+use crate::config::Config;
+use crate::error::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use super::common::{get_network_name, rpc_error};
+use super::{get_bridge_contract_address, get_provider, BridgeContract};
+
+/// Indexing state of the bridge service for one network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexingStatus {
+    pub network_id: u64,
+    pub chain_head: u64,
+    pub contract_deposit_count: u64,
+    pub indexed_deposit_count: u64,
+    pub last_indexed_block: Option<u64>,
+    /// Upper bound on how many blocks the service is behind (0 when in sync)
+    pub blocks_behind: u64,
+}
+
+impl IndexingStatus {
+    /// Build the status from the chain head, the contract deposit count and a bridges API response
+    pub fn from_parts(
+        network_id: u64,
+        chain_head: u64,
+        contract_deposit_count: u64,
+        bridges_response: &serde_json::Value,
+    ) -> Self {
+        let bridges = bridges_response["bridges"].as_array();
+        let indexed_deposit_count = bridges
+            .into_iter()
+            .flatten()
+            .filter_map(|bridge| bridge["deposit_count"].as_u64())
+            .max()
+            .map_or(0, |count| count + 1);
+        let last_indexed_block = bridges
+            .into_iter()
+            .flatten()
+            .filter_map(|bridge| bridge["block_num"].as_u64())
+            .max();
+
+        let blocks_behind = if contract_deposit_count > indexed_deposit_count {
+            chain_head.saturating_sub(last_indexed_block.unwrap_or(0))
+        } else {
+            0
+        };
+
+        Self {
+            network_id,
+            chain_head,
+            contract_deposit_count,
+            indexed_deposit_count,
+            last_indexed_block,
+            blocks_behind,
+        }
+    }
+
+    /// Number of deposits made on-chain that the service has not indexed yet
+    pub fn unindexed_deposits(&self) -> u64 {
+        self.contract_deposit_count
+            .saturating_sub(self.indexed_deposit_count)
+    }
+
+    /// Whether the service is missing deposits that exist on-chain
+    pub fn is_lagging(&self) -> bool {
+        self.unindexed_deposits() > 0
+    }
+
+    /// Human readable one-line summary
+    pub fn describe(&self) -> String {
+        if self.is_lagging() {
+            format!(
+                "aggkit is {} blocks behind on network {} ({}): {} deposit(s) not indexed yet",
+                self.blocks_behind,
+                self.network_id,
+                get_network_name(self.network_id),
+                self.unindexed_deposits()
+            )
+        } else {
+            format!(
+                "network {} ({}) in sync at block {} ({} deposit(s) indexed)",
+                self.network_id,
+                get_network_name(self.network_id),
+                self.chain_head,
+                self.indexed_deposit_count
+            )
+        }
+    }
+}
+
+/// Query the chain and the bridge service for the indexing state of a network
+pub async fn get_indexing_status(config: &Config, network_id: u64) -> Result<IndexingStatus> {
+    let provider = get_provider(config, network_id).await?;
+    let bridge = BridgeContract::new(
+        get_bridge_contract_address(config, network_id)?,
+        provider.clone(),
+    );
+
+    let chain_head = provider
+        .get_block_number()
+        .await
+        .map_err(|e| rpc_error("get chain head", e))?
+        .as_u64();
+    let contract_deposit_count = bridge
+        .deposit_count()
+        .call()
+        .await
+        .map_err(|e| rpc_error("get bridge deposit count", e))?
+        .as_u64();
+
+    let api_client = OptimizedApiClient::global();
+    api_client.invalidate("bridges", Some(network_id)).await;
+    let bridges_response = api_client.get_bridges(config, network_id).await?;
+
+    Ok(IndexingStatus::from_parts(
+        network_id,
+        chain_head,
+        contract_deposit_count,
+        &bridges_response,
+    ))
+}
+
+/// Get the indexing state of every configured network, skipping networks that cannot be queried
+pub async fn get_all_indexing_status(config: &Config) -> Vec<IndexingStatus> {
+    let mut statuses = Vec::new();
+    for network_id in config.networks.network_ids() {
+        match get_indexing_status(config, network_id).await {
+            Ok(status) => statuses.push(status),
+            Err(e) => debug!(network_id, error = %e, "Could not determine indexing status"),
+        }
+    }
+    statuses
+}
+
+/// Warn when the bridge service has not yet indexed all deposits of a network
+///
+/// Best effort: failures to query the chain or the service are only logged.
+pub async fn warn_if_indexing_lag(config: &Config, network_id: u64) {
+    match get_indexing_status(config, network_id).await {
+        Ok(status) if status.is_lagging() => {
+            crate::ui::ui().warning(&format!(
+                "{}; proof may not be available yet",
+                status.describe()
+            ));
+        }
+        Ok(_) => {}
+        Err(e) => debug!(network_id, error = %e, "Could not determine indexing status"),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_in_sync_when_all_deposits_indexed() {
+        let response = json!({
+            "bridges": [
+                {"deposit_count": 0, "block_num": 10},
+                {"deposit_count": 1, "block_num": 15}
+            ]
+        });
+        let status = IndexingStatus::from_parts(0, 40, 2, &response);
+
+        assert_eq!(status.indexed_deposit_count, 2);
+        assert_eq!(status.last_indexed_block, Some(15));
+        assert_eq!(status.blocks_behind, 0);
+        assert!(!status.is_lagging());
+    }
+
+    #[test]
+    fn test_lagging_when_deposits_missing() {
+        let response = json!({
+            "bridges": [{"deposit_count": 0, "block_num": 10}]
+        });
+        let status = IndexingStatus::from_parts(1, 22, 3, &response);
+
+        assert!(status.is_lagging());
+        assert_eq!(status.unindexed_deposits(), 2);
+        assert_eq!(status.blocks_behind, 12);
+        assert!(status.describe().starts_with("aggkit is 12 blocks behind"));
+    }
+
+    #[test]
+    fn test_nothing_indexed_yet() {
+        let status = IndexingStatus::from_parts(0, 5, 1, &json!({"bridges": []}));
+
+        assert_eq!(status.indexed_deposit_count, 0);
+        assert_eq!(status.last_indexed_block, None);
+        assert_eq!(status.blocks_behind, 5);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_failure_reports_rpc_exit_code() {
+        let mut config = Config::default();
+        config.networks.l1.rpc_url = crate::types::RpcUrl::new("http://127.0.0.1:1").unwrap();
+        config.contracts.l1_contracts.insert(
+            "PolygonZkEVMBridge".to_string(),
+            crate::types::EthereumAddress::new("0x5FbDB2315678afecb367f032d93F642f64180aa3")
+                .unwrap(),
+        );
+
+        let error = get_indexing_status(&config, 0).await.unwrap_err();
+        assert_eq!(error.exit_code(), crate::error::exit_code::RPC);
+    }
+}
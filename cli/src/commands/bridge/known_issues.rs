@@ -0,0 +1,281 @@
+//! Known aggkit bridge service bugs and their workarounds
+//!
+//! Some aggkit releases return data that contradicts the chain, which has
+//! confused users again and again. Responses of the affected endpoints are
+//! checked against the symptoms below; when one shows up a warning names the
+//! issue, the detected aggkit version and the workaround the CLI applies or
+//! suggests. Each issue is reported once per run.
+
+use std::collections::HashSet;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use serde_json::Value;
+#[cfg(feature = "docker")]
+use tracing::debug;
+
+use crate::types::BridgeDeposit;
+use crate::ui;
+
+/// Bridge service endpoint a known issue affects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint {
+    /// `/bridge/v1/bridges`
+    Bridges,
+    /// `/bridge/v1/claims` when pending claims are asked for
+    PendingClaims,
+}
+
+/// Entry of the built-in knowledge base
+#[derive(Debug)]
+pub struct KnownIssue {
+    pub id: &'static str,
+    pub endpoint: Endpoint,
+    pub symptom: &'static str,
+    pub workaround: &'static str,
+    /// First aggkit version without the bug, `None` while unfixed
+    pub fixed_in: Option<&'static str>,
+    /// Whether a response of `network_id` shows the symptom
+    detect: fn(network_id: u64, response: &Value) -> bool,
+}
+
+pub const KNOWN_ISSUES: &[KnownIssue] = &[
+    KnownIssue {
+        id: "destination-network-zero",
+        endpoint: Endpoint::Bridges,
+        symptom: "bridges report their own network as destination_network (always 0 on L1)",
+        workaround: "the destination network is taken from the claim's --network-id instead",
+        fixed_in: None,
+        detect: reports_own_network_as_destination,
+    },
+    KnownIssue {
+        id: "pending-claims-missing",
+        endpoint: Endpoint::PendingClaims,
+        symptom: "the claims endpoint only lists claims that completed, so pending claims are missing",
+        workaround: "use `aggsandbox show claims --pending`, which derives pending claims from unclaimed deposits",
+        fixed_in: None,
+        detect: lists_no_pending_claims,
+    },
+];
+
+/// Issues already reported in this run
+#[allow(clippy::disallowed_types)] // Only locked briefly, never across an await
+static REPORTED: std::sync::Mutex<Option<HashSet<&'static str>>> = std::sync::Mutex::new(None);
+
+static AGGKIT_VERSION: OnceLock<Option<String>> = OnceLock::new();
+
+fn reports_own_network_as_destination(network_id: u64, response: &Value) -> bool {
+    response["bridges"].as_array().is_some_and(|bridges| {
+        bridges
+            .iter()
+            .any(|bridge| bridge["destination_network"].as_u64() == Some(network_id))
+    })
+}
+
+fn lists_no_pending_claims(_network_id: u64, response: &Value) -> bool {
+    response["claims"].as_array().is_some_and(|claims| {
+        !claims.iter().any(|claim| {
+            claim["status"]
+                .as_str()
+                .is_some_and(|status| status.eq_ignore_ascii_case("pending"))
+                || claim["claim_tx_hash"].as_str() == Some("")
+        })
+    })
+}
+
+/// Issues of `endpoint` that a response of `network_id` shows
+pub fn detect(endpoint: Endpoint, network_id: u64, response: &Value) -> Vec<&'static KnownIssue> {
+    KNOWN_ISSUES
+        .iter()
+        .filter(|issue| issue.endpoint == endpoint && (issue.detect)(network_id, response))
+        .collect()
+}
+
+/// Warn about the known issues a response shows, once per issue
+pub fn check(endpoint: Endpoint, network_id: u64, response: &Value) {
+    let issues = detect(endpoint, network_id, response);
+    if issues.is_empty() {
+        return;
+    }
+
+    let version = aggkit_version();
+    for issue in issues {
+        let first_report = REPORTED
+            .lock()
+            .map(|mut reported| reported.get_or_insert_with(HashSet::new).insert(issue.id))
+            .unwrap_or(false);
+        if first_report {
+            ui::ui().warning(&warning_message(issue, version.as_deref()));
+        }
+    }
+}
+
+fn warning_message(issue: &KnownIssue, version: Option<&str>) -> String {
+    let version = version.map_or_else(|| "aggkit".to_string(), |v| format!("aggkit {v}"));
+    let fixed = issue
+        .fixed_in
+        .map_or_else(String::new, |fixed_in| format!(", fixed in {fixed_in}"));
+    format!(
+        "Known bridge service issue '{}' ({version}{fixed}): {}; workaround: {}",
+        issue.id, issue.symptom, issue.workaround
+    )
+}
+
+/// Destination network of a bridge, unless the bridge service reported it wrongly
+///
+/// A bridge never targets the network it was sent on, so a bridge of
+/// `bridge_tx_network` reporting that network as destination shows the
+/// `destination-network-zero` issue, and the caller's own value should be
+/// used instead.
+pub fn trusted_destination(deposit: &BridgeDeposit, bridge_tx_network: u64) -> Option<u64> {
+    deposit
+        .destination_network
+        .map(u64::from)
+        .filter(|destination| *destination != bridge_tx_network)
+}
+
+/// Version of the running aggkit, detected once per run
+///
+/// `AGGKIT_VERSION` overrides the detection, which reads the image tag of the
+/// `aggkit` service and falls back to the image's version label.
+pub fn aggkit_version() -> Option<String> {
+    AGGKIT_VERSION
+        .get_or_init(|| {
+            std::env::var("AGGKIT_VERSION")
+                .ok()
+                .filter(|version| !version.is_empty())
+                .or_else(detect_aggkit_version)
+        })
+        .clone()
+}
+
+fn detect_aggkit_version() -> Option<String> {
+    let image = aggkit_image()?;
+    if let Some(tag) = image_tag(&image) {
+        return Some(tag.to_string());
+    }
+
+    let output = Command::new("docker")
+        .args([
+            "image",
+            "inspect",
+            "--format",
+            "{{ index .Config.Labels \"org.opencontainers.image.version\" }}",
+            &image,
+        ])
+        .output()
+        .ok()?;
+    let label = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !label.is_empty() && label != "<no value>").then_some(label)
+}
+
+/// Image of the running `aggkit` service
+#[cfg(feature = "docker")]
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+fn aggkit_image() -> Option<String> {
+    let cmd = crate::docker::create_auto_docker_builder().build_ps_json_command();
+    let services = crate::docker::execute_docker_command_with_output(cmd)
+        .map(|output| crate::docker::parse_ps_json(&output))
+        .map_err(|e| debug!(error = %e, "Could not list the sandbox services"))
+        .ok()?;
+    services
+        .iter()
+        .find(|service| service["Service"].as_str() == Some("aggkit"))
+        .and_then(|service| service["Image"].as_str())
+        .map(str::to_string)
+}
+
+/// Services are not inspected without the `docker` feature
+#[cfg(not(feature = "docker"))]
+fn aggkit_image() -> Option<String> {
+    None
+}
+
+/// Version tag of an image reference, `None` for `latest` or untagged images
+fn image_tag(image: &str) -> Option<&str> {
+    let name = image.split('@').next()?;
+    let (repository, tag) = name.rsplit_once(':')?;
+    // A colon before the last slash belongs to a registry port
+    if tag.contains('/') || repository.is_empty() || tag == "latest" {
+        return None;
+    }
+    Some(tag)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect_destination_network_zero() {
+        let affected = json!({
+            "bridges": [
+                { "deposit_count": 0, "destination_network": 1 },
+                { "deposit_count": 1, "destination_network": 0 },
+            ]
+        });
+        let ids: Vec<_> = detect(Endpoint::Bridges, 0, &affected)
+            .iter()
+            .map(|issue| issue.id)
+            .collect();
+        assert_eq!(ids, ["destination-network-zero"]);
+
+        // Bridging back from L2 to L1 legitimately targets network 0
+        let back_to_l1 = json!({ "bridges": [{ "deposit_count": 0, "destination_network": 0 }] });
+        assert!(detect(Endpoint::Bridges, 1, &back_to_l1).is_empty());
+        assert!(detect(Endpoint::Bridges, 0, &json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_detect_pending_claims_missing() {
+        assert_eq!(
+            detect(Endpoint::PendingClaims, 1, &json!({ "claims": [] }))[0].id,
+            "pending-claims-missing"
+        );
+        let completed = json!({ "claims": [{ "status": "completed", "claim_tx_hash": "0xab" }] });
+        assert_eq!(detect(Endpoint::PendingClaims, 1, &completed).len(), 1);
+        assert!(detect(
+            Endpoint::PendingClaims,
+            1,
+            &json!({ "claims": [{ "status": "pending", "claim_tx_hash": "" }] })
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_trusted_destination() {
+        let mut deposit = BridgeDeposit::from_value(&json!({
+            "bridge_tx_hash": format!("0x{}", "ab".repeat(32)),
+            "deposit_count": 0,
+            "origin_address": "0x0000000000000000000000000000000000000000",
+            "destination_network": 0,
+            "destination_address": "0x0000000000000000000000000000000000000000",
+            "amount": "1",
+        }))
+        .unwrap();
+        assert_eq!(trusted_destination(&deposit, 0), None);
+        assert_eq!(trusted_destination(&deposit, 1), Some(0));
+        deposit.destination_network = None;
+        assert_eq!(trusted_destination(&deposit, 1), None);
+    }
+
+    #[test]
+    fn test_image_tag() {
+        assert_eq!(image_tag("ametelnethermind/aggkit:0.5.2"), Some("0.5.2"));
+        assert_eq!(image_tag("ametelnethermind/aggkit:latest"), None);
+        assert_eq!(image_tag("ametelnethermind/aggkit"), None);
+        assert_eq!(image_tag("localhost:5000/aggkit"), None);
+        assert_eq!(image_tag("localhost:5000/aggkit:v1@sha256:ab"), Some("v1"));
+    }
+
+    #[test]
+    fn test_warning_message() {
+        let message = warning_message(&KNOWN_ISSUES[0], Some("0.5.2"));
+        assert!(message
+            .starts_with("Known bridge service issue 'destination-network-zero' (aggkit 0.5.2)"));
+        assert!(message.contains("--network-id"));
+        assert!(warning_message(&KNOWN_ISSUES[1], None).contains("(aggkit)"));
+    }
+}
@@ -0,0 +1,147 @@
+//! Leaves of the L1 info tree as indexed by aggkit
+//!
+//! Every `UpdateL1InfoTree` on L1 appends a leaf holding the new global exit
+//! root, the hash of the previous block and the block timestamp. Claim proofs
+//! are rooted in one of these leaves, and an L2 can only verify them once the
+//! leaf's global exit root has been injected there. The bridge service of L1
+//! returns the leaves themselves, the one of an L2 the leaves injected on it.
+
+use crate::api_client::OptimizedApiClient;
+use crate::concurrency::join_all;
+use crate::config::Config;
+use crate::error::Result;
+use crate::types::L1InfoLeaf;
+use serde::Serialize;
+use std::ops::Range;
+use tracing::debug;
+
+use super::exit_roots::l1_info_tree_leaf_count;
+
+/// Leaves of the L1 info tree seen from one network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct L1InfoTreeLeaves {
+    pub network_id: u64,
+    /// Leaves in the tree according to the L1 global exit root manager
+    pub leaf_count: u64,
+    pub leaves: Vec<L1InfoLeaf>,
+    /// Requested leaves the service does not know, not indexed yet on L1 or
+    /// not injected on an L2
+    pub missing: Vec<u32>,
+}
+
+/// Indices of the last `count` leaves of a tree with `leaf_count` leaves
+pub fn recent_leaves(leaf_count: u64, count: u32) -> Range<u32> {
+    let end = u32::try_from(leaf_count).unwrap_or(u32::MAX);
+    end.saturating_sub(count)..end
+}
+
+/// Leaf `leaf_index` seen from a network
+///
+/// On an L2 the service answers with the next injected leaf when this one was
+/// skipped, which is returned as is for the caller to report.
+pub async fn fetch_leaf(
+    config: &Config,
+    network_id: u64,
+    leaf_index: u32,
+) -> Result<Option<L1InfoLeaf>> {
+    OptimizedApiClient::global()
+        .get_typed_l1_info_tree_leaf(config, network_id, leaf_index)
+        .await
+}
+
+/// The last `count` leaves of the L1 info tree seen from a network
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn fetch_recent_leaves(
+    config: &Config,
+    network_id: u64,
+    count: u32,
+) -> Result<L1InfoTreeLeaves> {
+    let leaf_count = l1_info_tree_leaf_count(config).await?;
+    let indices = recent_leaves(leaf_count, count);
+    debug!(
+        network_id,
+        leaf_count,
+        ?indices,
+        "Reading L1 info tree leaves"
+    );
+
+    let results = join_all(
+        indices
+            .clone()
+            .map(|leaf_index| fetch_leaf(config, network_id, leaf_index))
+            .collect(),
+    )
+    .await;
+
+    let mut leaves = Vec::new();
+    let mut missing = Vec::new();
+    // Newest first, like the other listings
+    for (leaf_index, leaf) in indices.zip(results).rev() {
+        match leaf? {
+            Some(leaf) if leaf.l1_info_tree_index == leaf_index => leaves.push(leaf),
+            _ => missing.push(leaf_index),
+        }
+    }
+    Ok(L1InfoTreeLeaves {
+        network_id,
+        leaf_count,
+        leaves,
+        missing,
+    })
+}
+
+/// Display rows of a leaf
+pub fn leaf_rows(leaf: &L1InfoLeaf) -> Vec<(String, String)> {
+    let timestamp = chrono::DateTime::from_timestamp(leaf.timestamp as i64, 0).map_or_else(
+        || leaf.timestamp.to_string(),
+        |datetime| {
+            format!(
+                "{} ({})",
+                leaf.timestamp,
+                datetime.format("%Y-%m-%d %H:%M:%S UTC")
+            )
+        },
+    );
+    let mut rows = vec![
+        ("Index".to_string(), leaf.l1_info_tree_index.to_string()),
+        (
+            "L1 block".to_string(),
+            format!("{} (position {})", leaf.block_num, leaf.block_pos),
+        ),
+        ("Timestamp".to_string(), timestamp),
+        (
+            "Global exit root".to_string(),
+            format!("{:#x}", leaf.global_exit_root),
+        ),
+        (
+            "Mainnet exit root".to_string(),
+            format!("{:#x}", leaf.mainnet_exit_root),
+        ),
+        (
+            "Rollup exit root".to_string(),
+            format!("{:#x}", leaf.rollup_exit_root),
+        ),
+        (
+            "Previous block hash".to_string(),
+            leaf.previous_block_hash
+                .map_or_else(|| "n/a".to_string(), |hash| format!("{hash:#x}")),
+        ),
+    ];
+    if let Some(hash) = leaf.hash {
+        rows.push(("Leaf hash".to_string(), format!("{hash:#x}")));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_leaves() {
+        assert_eq!(recent_leaves(25, 10), 15..25);
+        assert_eq!(recent_leaves(4, 10), 0..4);
+        assert_eq!(recent_leaves(0, 10), 0..0);
+        assert_eq!(recent_leaves(u64::MAX, 2), u32::MAX - 2..u32::MAX);
+    }
+}
@@ -0,0 +1,150 @@
+//! Last-used bridge networks and token of the working directory
+//!
+//! `bridge asset`, `bridge message` and `bridge claim` record the networks and
+//! token they ran with in `.aggsandbox/last_used.json`, and fall back to them
+//! when the flags are omitted, so iterating on one flow does not mean retyping
+//! the same `-n 0 -d 1 -t 0x...` every time.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::fs;
+use std::path::PathBuf;
+use tracing::debug;
+
+use super::common::validation_error;
+use crate::error::Result;
+use crate::ui;
+
+const LAST_USED_FILE: &str = ".aggsandbox/last_used.json";
+
+/// Networks and token of the last bridge operation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LastUsed {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_network: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_network: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_address: Option<String>,
+}
+
+/// Last-used values stored in the working directory
+#[derive(Debug, Clone)]
+pub struct LastUsedStore {
+    path: PathBuf,
+}
+
+impl LastUsedStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Store at `.aggsandbox/last_used.json` of the working directory
+    pub fn default_location() -> Self {
+        Self::new(LAST_USED_FILE)
+    }
+
+    /// Recorded values, empty when nothing was recorded yet
+    pub fn load(&self) -> LastUsed {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Merge `update` into the recorded values
+    ///
+    /// Failing to write is not an error of the bridge operation that just
+    /// succeeded, so it is only logged.
+    #[allow(clippy::disallowed_methods)] // Allow tracing macros
+    pub fn record(&self, update: LastUsed) {
+        let mut last_used = self.load();
+        if update.source_network.is_some() {
+            last_used.source_network = update.source_network;
+        }
+        if update.destination_network.is_some() {
+            last_used.destination_network = update.destination_network;
+        }
+        if update.token_address.is_some() {
+            last_used.token_address = update.token_address;
+        }
+
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(&last_used).map_err(|e| e.to_string()))
+            .and_then(|json| fs::write(&self.path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            debug!(path = %self.path.display(), error = %e, "Failed to record last-used bridge values");
+        }
+    }
+}
+
+/// Value of a flag, or its last-used value with a note when it was omitted
+pub fn or_last_used<T: Clone + Display>(
+    value: Option<T>,
+    last: Option<&T>,
+    flag: &str,
+) -> Result<T> {
+    if let Some(value) = value {
+        return Ok(value);
+    }
+    match last {
+        Some(last) => {
+            ui::ui().info(&format!("Using last-used {flag} {last}"));
+            Ok(last.clone())
+        }
+        None => Err(validation_error(&format!(
+            "{flag} is required (no last-used value recorded in {LAST_USED_FILE} yet)"
+        ))),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_merges_values() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = LastUsedStore::new(dir.path().join(".aggsandbox").join("last_used.json"));
+        assert_eq!(store.load(), LastUsed::default());
+
+        store.record(LastUsed {
+            source_network: Some(0),
+            destination_network: Some(1),
+            token_address: Some("0x0000000000000000000000000000000000000000".to_string()),
+        });
+        store.record(LastUsed {
+            source_network: Some(1),
+            destination_network: Some(2),
+            token_address: None,
+        });
+
+        let last_used = store.load();
+        assert_eq!(last_used.source_network, Some(1));
+        assert_eq!(last_used.destination_network, Some(2));
+        assert_eq!(
+            last_used.token_address.as_deref(),
+            Some("0x0000000000000000000000000000000000000000")
+        );
+    }
+
+    #[test]
+    fn test_or_last_used() {
+        // Falling back reports the value used through the UI
+        crate::ui::init_ui(crate::ui::OutputFormat::Json, true);
+        assert_eq!(
+            or_last_used(Some(2u64), Some(&1), "--network-id").unwrap(),
+            2
+        );
+        assert_eq!(or_last_used(None, Some(&1u64), "--network-id").unwrap(), 1);
+        let error = or_last_used::<u64>(None, None, "--network-id")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("--network-id is required"));
+    }
+}
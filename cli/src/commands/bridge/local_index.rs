@@ -0,0 +1,1592 @@
+//! Local bridge index built from chain events
+//!
+//! `BridgeEvent`s and `ClaimEvent`s of the bridge contracts, and the exit-root
+//! updates of the global exit root managers, are read with `eth_getLogs` and
+//! stored in the SQLite database `.aggsandbox/index/index.sqlite`, keyed by
+//! network. A sync only fetches the blocks after the last indexed one; when
+//! that block's hash no longer matches the chain (the sandbox was restarted or
+//! reorganized) the network is rebuilt. Events are keyed by their deposit
+//! count, claim or exit root, so logs fetched twice are stored once, and
+//! concurrent syncs and readers are serialized by SQLite. The per-network
+//! `network-<id>.json` files of older versions are imported when the database
+//! is opened.
+//!
+//! Records are rendered in the shape of the bridge service responses, so
+//! `show bridges`, `show claims` and `bridge claim` read them with
+//! `--source local` when the bridge service is slow or not running. With the
+//! default `--source api` the same records are used automatically when the
+//! service cannot be reached; they are then marked `"source": "chain"` and
+//! list the fields only the service supplies in `missing_fields`.
+//!
+//! Every record remembers when it was indexed. `state prune` and the
+//! `INDEX_RETENTION` policy move records older than a given age into
+//! `.aggsandbox/index/archive/network-<id>.jsonl`, which keeps the index of a
+//! long-running sandbox bounded.
+
+use crate::config::Config;
+use crate::error::{AggSandboxError, ApiError, Result};
+use crate::ui;
+use ethers::abi::RawLog;
+use ethers::contract::EthEvent;
+use ethers::prelude::*;
+use ethers::utils::keccak256;
+use rusqlite::{params, Connection, OptionalExtension, Row, Transaction, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::common::{get_network_name, rpc_action, rpc_error};
+use super::faucet::layer_for_network;
+use super::known_issues::{self, Endpoint};
+use super::{get_bridge_contract_address, get_provider};
+
+/// Index directory, relative to the project root
+const INDEX_DIR: &str = ".aggsandbox/index";
+/// Database of the index, inside the index directory
+const DATABASE_FILE: &str = "index.sqlite";
+/// How long to wait for a concurrent sync holding the database lock
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+/// Version of `SCHEMA`, kept in the database `user_version`
+const SCHEMA_VERSION: i64 = 1;
+/// Tables of the index, one row per record and a sync cursor per network
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cursors (
+    network_id INTEGER PRIMARY KEY,
+    last_block INTEGER NOT NULL,
+    last_block_hash TEXT
+);
+CREATE TABLE IF NOT EXISTS deposits (
+    network_id INTEGER NOT NULL,
+    deposit_count INTEGER NOT NULL,
+    bridge_tx_hash TEXT NOT NULL,
+    block_num INTEGER NOT NULL,
+    leaf_type INTEGER NOT NULL,
+    origin_network INTEGER NOT NULL,
+    origin_address TEXT NOT NULL,
+    destination_network INTEGER NOT NULL,
+    destination_address TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    metadata BLOB NOT NULL,
+    indexed_at INTEGER NOT NULL,
+    PRIMARY KEY (network_id, deposit_count)
+);
+CREATE TABLE IF NOT EXISTS claims (
+    network_id INTEGER NOT NULL,
+    claim_tx_hash TEXT NOT NULL,
+    global_index TEXT NOT NULL,
+    block_num INTEGER NOT NULL,
+    origin_network INTEGER NOT NULL,
+    origin_address TEXT NOT NULL,
+    destination_address TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    indexed_at INTEGER NOT NULL,
+    PRIMARY KEY (network_id, claim_tx_hash, global_index)
+);
+CREATE TABLE IF NOT EXISTS exit_roots (
+    network_id INTEGER NOT NULL,
+    tx_hash TEXT NOT NULL,
+    global_exit_root TEXT NOT NULL,
+    block_num INTEGER NOT NULL,
+    mainnet_exit_root TEXT,
+    rollup_exit_root TEXT,
+    indexed_at INTEGER NOT NULL,
+    PRIMARY KEY (network_id, tx_hash, global_exit_root)
+);
+";
+/// Blocks per `eth_getLogs` request
+const LOG_CHUNK_BLOCKS: u64 = 2_000;
+/// Maximum age of indexed records, pruned after every sync when set (e.g. `7d`)
+pub const RETENTION_ENV: &str = "INDEX_RETENTION";
+/// Fields of bridge service `bridges` records that `BridgeEvent` logs do not carry
+pub const API_ONLY_BRIDGE_FIELDS: &[&str] = &[
+    "block_pos",
+    "block_timestamp",
+    "from_address",
+    "calldata",
+    "is_native_token",
+    "bridge_hash",
+];
+/// Fields of bridge service `claims` records that `ClaimEvent` logs do not carry
+pub const API_ONLY_CLAIM_FIELDS: &[&str] = &[
+    "block_timestamp",
+    "from_address",
+    "destination_network",
+    "global_exit_root",
+    "mainnet_exit_root",
+    "rollup_exit_root",
+    "metadata",
+];
+
+abigen!(
+    IndexedEvents,
+    r#"[
+        event BridgeEvent(uint8 leafType, uint32 originNetwork, address originAddress, uint32 destinationNetwork, address destinationAddress, uint256 amount, bytes metadata, uint32 depositCount)
+        event ClaimEvent(uint256 globalIndex, uint32 originNetwork, address originAddress, address destinationAddress, uint256 amount)
+        event UpdateL1InfoTree(bytes32 indexed mainnetExitRoot, bytes32 indexed rollupExitRoot)
+        event InsertGlobalExitRoot(bytes32 indexed newGlobalExitRoot)
+    ]"#,
+);
+
+/// Where bridge and claim data is read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum DataSource {
+    /// The bridge service API
+    #[default]
+    Api,
+    /// The local index, synced from chain events first
+    Local,
+}
+
+/// Deposit made on an indexed network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedDeposit {
+    pub bridge_tx_hash: H256,
+    pub block_num: u64,
+    pub leaf_type: u8,
+    pub origin_network: u32,
+    pub origin_address: Address,
+    pub destination_network: u32,
+    pub destination_address: Address,
+    pub amount: U256,
+    pub metadata: Bytes,
+    pub deposit_count: u32,
+    /// Unix time the record was indexed at, `now` for legacy records without one
+    #[serde(default = "unix_now", deserialize_with = "indexed_at_or_now")]
+    pub indexed_at: u64,
+}
+
+/// Claim made on an indexed network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedClaim {
+    pub claim_tx_hash: H256,
+    pub block_num: u64,
+    pub global_index: U256,
+    pub origin_network: u32,
+    pub origin_address: Address,
+    pub destination_address: Address,
+    pub amount: U256,
+    #[serde(default = "unix_now", deserialize_with = "indexed_at_or_now")]
+    pub indexed_at: u64,
+}
+
+/// Global exit root update of an indexed network
+///
+/// On L1 the mainnet and rollup exit roots are known and the global exit root
+/// is derived from them; L2 managers only emit the inserted global exit root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExitRootUpdate {
+    pub tx_hash: H256,
+    pub block_num: u64,
+    pub global_exit_root: H256,
+    pub mainnet_exit_root: Option<H256>,
+    pub rollup_exit_root: Option<H256>,
+    #[serde(default = "unix_now", deserialize_with = "indexed_at_or_now")]
+    pub indexed_at: u64,
+}
+
+/// Indexed events of one network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkIndex {
+    pub network_id: u64,
+    pub last_block: Option<u64>,
+    pub last_block_hash: Option<H256>,
+    pub deposits: Vec<IndexedDeposit>,
+    pub claims: Vec<IndexedClaim>,
+    pub exit_roots: Vec<ExitRootUpdate>,
+}
+
+impl NetworkIndex {
+    pub fn new(network_id: u64) -> Self {
+        Self {
+            network_id,
+            last_block: None,
+            last_block_hash: None,
+            deposits: Vec::new(),
+            claims: Vec::new(),
+            exit_roots: Vec::new(),
+        }
+    }
+
+    /// Add the events of the bridge and global exit root manager among `logs`
+    pub fn apply_logs(&mut self, logs: &[Log], bridge: Address, ger_manager: Option<Address>) {
+        let indexed_at = unix_now();
+        for log in logs {
+            let (Some(tx_hash), Some(block_num), Some(topic0)) =
+                (log.transaction_hash, log.block_number, log.topics.first())
+            else {
+                continue;
+            };
+            let block_num = block_num.as_u64();
+            let raw = RawLog {
+                topics: log.topics.clone(),
+                data: log.data.to_vec(),
+            };
+
+            if log.address == bridge {
+                if *topic0 == BridgeEventFilter::signature() {
+                    let Ok(event) = <BridgeEventFilter as EthEvent>::decode_log(&raw) else {
+                        debug!(tx_hash = ?tx_hash, "Skipping undecodable BridgeEvent");
+                        continue;
+                    };
+                    self.deposits
+                        .retain(|deposit| deposit.deposit_count != event.deposit_count);
+                    self.deposits.push(IndexedDeposit {
+                        bridge_tx_hash: tx_hash,
+                        block_num,
+                        leaf_type: event.leaf_type,
+                        origin_network: event.origin_network,
+                        origin_address: event.origin_address,
+                        destination_network: event.destination_network,
+                        destination_address: event.destination_address,
+                        amount: event.amount,
+                        metadata: event.metadata,
+                        deposit_count: event.deposit_count,
+                        indexed_at,
+                    });
+                } else if *topic0 == ClaimEventFilter::signature() {
+                    let Ok(event) = <ClaimEventFilter as EthEvent>::decode_log(&raw) else {
+                        debug!(tx_hash = ?tx_hash, "Skipping undecodable ClaimEvent");
+                        continue;
+                    };
+                    self.claims.push(IndexedClaim {
+                        claim_tx_hash: tx_hash,
+                        block_num,
+                        global_index: event.global_index,
+                        origin_network: event.origin_network,
+                        origin_address: event.origin_address,
+                        destination_address: event.destination_address,
+                        amount: event.amount,
+                        indexed_at,
+                    });
+                }
+            } else if Some(log.address) == ger_manager {
+                let update = if *topic0 == UpdateL1InfoTreeFilter::signature() {
+                    <UpdateL1InfoTreeFilter as EthEvent>::decode_log(&raw)
+                        .ok()
+                        .map(|event| {
+                            let mut roots = [0u8; 64];
+                            roots[..32].copy_from_slice(&event.mainnet_exit_root);
+                            roots[32..].copy_from_slice(&event.rollup_exit_root);
+                            ExitRootUpdate {
+                                tx_hash,
+                                block_num,
+                                global_exit_root: H256(keccak256(roots)),
+                                mainnet_exit_root: Some(H256(event.mainnet_exit_root)),
+                                rollup_exit_root: Some(H256(event.rollup_exit_root)),
+                                indexed_at,
+                            }
+                        })
+                } else if *topic0 == InsertGlobalExitRootFilter::signature() {
+                    <InsertGlobalExitRootFilter as EthEvent>::decode_log(&raw)
+                        .ok()
+                        .map(|event| ExitRootUpdate {
+                            tx_hash,
+                            block_num,
+                            global_exit_root: H256(event.new_global_exit_root),
+                            mainnet_exit_root: None,
+                            rollup_exit_root: None,
+                            indexed_at,
+                        })
+                } else {
+                    None
+                };
+                self.exit_roots.extend(update);
+            }
+        }
+    }
+
+    /// Deposits in the shape of the bridge service `bridges` response, newest first
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+    pub fn bridges_json(&self) -> serde_json::Value {
+        let bridges: Vec<serde_json::Value> = self
+            .deposits
+            .iter()
+            .rev()
+            .map(|deposit| {
+                serde_json::json!({
+                    "network_id": self.network_id,
+                    "block_num": deposit.block_num,
+                    "bridge_tx_hash": format!("{:#x}", deposit.bridge_tx_hash),
+                    "leaf_type": deposit.leaf_type,
+                    "origin_network": deposit.origin_network,
+                    "origin_address": format!("{:#x}", deposit.origin_address),
+                    "destination_network": deposit.destination_network,
+                    "destination_address": format!("{:#x}", deposit.destination_address),
+                    "amount": deposit.amount.to_string(),
+                    "metadata": format!("0x{}", hex::encode(&deposit.metadata)),
+                    "deposit_count": deposit.deposit_count,
+                })
+            })
+            .collect();
+        serde_json::json!({ "count": bridges.len(), "bridges": bridges, "source": "local" })
+    }
+
+    /// Claims in the shape of the bridge service `claims` response, newest first
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+    pub fn claims_json(&self) -> serde_json::Value {
+        let claims: Vec<serde_json::Value> = self
+            .claims
+            .iter()
+            .rev()
+            .map(|claim| {
+                serde_json::json!({
+                    "network_id": self.network_id,
+                    "block_num": claim.block_num,
+                    "claim_tx_hash": format!("{:#x}", claim.claim_tx_hash),
+                    "global_index": claim.global_index.to_string(),
+                    "origin_network": claim.origin_network,
+                    "origin_address": format!("{:#x}", claim.origin_address),
+                    "destination_address": format!("{:#x}", claim.destination_address),
+                    "amount": claim.amount.to_string(),
+                    "status": "completed",
+                })
+            })
+            .collect();
+        serde_json::json!({ "count": claims.len(), "claims": claims, "source": "local" })
+    }
+}
+
+/// Records removed from a network index by pruning
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PrunedRecords {
+    pub deposits: Vec<IndexedDeposit>,
+    pub claims: Vec<IndexedClaim>,
+    pub exit_roots: Vec<ExitRootUpdate>,
+}
+
+impl PrunedRecords {
+    pub fn len(&self) -> usize {
+        self.deposits.len() + self.claims.len() + self.exit_roots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// One JSON object per record, tagged with its `kind`
+    fn archive_lines(&self) -> Vec<String> {
+        fn tagged(kind: &str, record: impl Serialize) -> String {
+            let mut value = serde_json::to_value(record).unwrap_or_default();
+            value["kind"] = kind.into();
+            value.to_string()
+        }
+        self.deposits
+            .iter()
+            .map(|deposit| tagged("deposit", deposit))
+            .chain(self.claims.iter().map(|claim| tagged("claim", claim)))
+            .chain(
+                self.exit_roots
+                    .iter()
+                    .map(|update| tagged("exit_root", update)),
+            )
+            .collect()
+    }
+}
+
+/// Result of syncing one network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncSummary {
+    pub network_id: u64,
+    pub from_block: u64,
+    pub to_block: u64,
+    pub new_deposits: usize,
+    pub new_claims: usize,
+    pub new_exit_roots: usize,
+    /// The index no longer matched the chain and was rebuilt from block 0
+    pub rebuilt: bool,
+    /// Records pruned under the `INDEX_RETENTION` policy
+    pub pruned: usize,
+}
+
+/// Records newly stored by a sync, events already in the index are not counted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InsertedRecords {
+    pub deposits: usize,
+    pub claims: usize,
+    pub exit_roots: usize,
+}
+
+/// Network indexes stored in the index database
+#[derive(Debug, Clone)]
+pub struct IndexStore {
+    root: PathBuf,
+}
+
+impl IndexStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Store under `.aggsandbox/index` of the working directory
+    pub fn default_location() -> Self {
+        Self::new(INDEX_DIR)
+    }
+
+    fn path(&self) -> PathBuf {
+        self.root.join(DATABASE_FILE)
+    }
+
+    fn error(&self, action: &str, error: impl std::fmt::Display) -> AggSandboxError {
+        AggSandboxError::Other(format!(
+            "Failed to {action} bridge index {}: {error}",
+            self.path().display()
+        ))
+    }
+
+    /// Open the database, creating it and its tables when missing
+    fn open(&self) -> Result<Connection> {
+        fs::create_dir_all(&self.root).map_err(|e| self.error("create", e))?;
+        let mut connection = Connection::open(self.path()).map_err(|e| self.error("open", e))?;
+        connection
+            .busy_timeout(BUSY_TIMEOUT)
+            .and_then(|()| create_tables(&mut connection))
+            .map_err(|e| {
+                self.error(
+                    "open",
+                    format!("{e}, remove it with `aggsandbox index reset`"),
+                )
+            })?;
+        self.import_legacy(&mut connection)?;
+        Ok(connection)
+    }
+
+    /// Whether anything was indexed, in the database or in legacy JSON files
+    fn exists(&self) -> bool {
+        self.path().exists() || !self.legacy_files().is_empty()
+    }
+
+    /// Per-network JSON indexes written before the index moved to SQLite
+    fn legacy_files(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("network-") && name.ends_with(".json"))
+            })
+            .collect()
+    }
+
+    /// Import the legacy JSON indexes into the database, then remove them
+    ///
+    /// Networks already in the database keep their records. Unreadable files
+    /// are dropped: everything in the index is on chain, so the next sync
+    /// rebuilds it.
+    #[allow(clippy::disallowed_methods)] // Allow tracing macros
+    fn import_legacy(&self, connection: &mut Connection) -> Result<()> {
+        for path in self.legacy_files() {
+            let legacy = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|content| {
+                    serde_json::from_str::<NetworkIndex>(&content).map_err(|e| e.to_string())
+                });
+            match legacy {
+                Ok(index) => {
+                    let write = |e: rusqlite::Error| self.error("import", e);
+                    let transaction = connection.transaction().map_err(write)?;
+                    let synced: bool = transaction
+                        .query_row(
+                            "SELECT EXISTS (SELECT 1 FROM cursors WHERE network_id = ?1)",
+                            params![index.network_id],
+                            |row| row.get(0),
+                        )
+                        .map_err(write)?;
+                    if !synced {
+                        insert_records(&transaction, &index).map_err(write)?;
+                        if let Some(block) = index.last_block {
+                            write_cursor(
+                                &transaction,
+                                index.network_id,
+                                block,
+                                index.last_block_hash,
+                            )
+                            .map_err(write)?;
+                        }
+                    }
+                    transaction.commit().map_err(write)?;
+                    info!(network_id = index.network_id, path = %path.display(), "Imported legacy bridge index");
+                }
+                Err(e) => {
+                    warn!(path = %path.display(), error = %e, "Dropping unreadable legacy bridge index");
+                }
+            }
+            // A concurrent open may have imported and removed it already
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(self.error("import", e));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Last synced block of a network and its hash, `None` when it was never synced
+    pub fn cursor(&self, network_id: u64) -> Result<Option<(u64, Option<H256>)>> {
+        if !self.exists() {
+            return Ok(None);
+        }
+        self.open()?
+            .query_row(
+                "SELECT last_block, last_block_hash FROM cursors WHERE network_id = ?1",
+                params![network_id],
+                |row| Ok((row.get(0)?, parse_optional(row, 1)?)),
+            )
+            .optional()
+            .map_err(|e| self.error("read", e))
+    }
+
+    /// Index of a network, `None` when it was never synced
+    pub fn load(&self, network_id: u64) -> Result<Option<NetworkIndex>> {
+        let Some((last_block, last_block_hash)) = self.cursor(network_id)? else {
+            return Ok(None);
+        };
+        let connection = self.open()?;
+        let read = |e: rusqlite::Error| self.error("read", e);
+        let mut index = NetworkIndex::new(network_id);
+        index.last_block = Some(last_block);
+        index.last_block_hash = last_block_hash;
+        index.deposits = select(
+            &connection,
+            "SELECT * FROM deposits WHERE network_id = ?1 ORDER BY deposit_count",
+            network_id,
+            deposit_from_row,
+        )
+        .map_err(read)?;
+        index.claims = select(
+            &connection,
+            "SELECT * FROM claims WHERE network_id = ?1 ORDER BY block_num, rowid",
+            network_id,
+            claim_from_row,
+        )
+        .map_err(read)?;
+        index.exit_roots = select(
+            &connection,
+            "SELECT * FROM exit_roots WHERE network_id = ?1 ORDER BY block_num, rowid",
+            network_id,
+            exit_root_from_row,
+        )
+        .map_err(read)?;
+        Ok(Some(index))
+    }
+
+    /// Store the records of `index` that are not indexed yet
+    ///
+    /// Does not move the sync cursor, see [`IndexStore::set_cursor`].
+    pub fn insert(&self, index: &NetworkIndex) -> Result<InsertedRecords> {
+        let mut connection = self.open()?;
+        let write = |e: rusqlite::Error| self.error("write", e);
+        let transaction = connection.transaction().map_err(write)?;
+        let inserted = insert_records(&transaction, index).map_err(write)?;
+        transaction.commit().map_err(write)?;
+        Ok(inserted)
+    }
+
+    /// Record the last synced block of a network
+    pub fn set_cursor(&self, network_id: u64, block: u64, hash: Option<H256>) -> Result<()> {
+        write_cursor(&self.open()?, network_id, block, hash).map_err(|e| self.error("write", e))
+    }
+
+    /// Store a whole network index, replacing what was indexed before
+    pub fn save(&self, index: &NetworkIndex) -> Result<()> {
+        self.remove(index.network_id)?;
+        self.insert(index)?;
+        if let Some(block) = index.last_block {
+            self.set_cursor(index.network_id, block, index.last_block_hash)?;
+        }
+        Ok(())
+    }
+
+    /// Append pruned records to the archive of a network, returning its path
+    pub fn archive(&self, network_id: u64, pruned: &PrunedRecords) -> Result<PathBuf> {
+        let dir = self.root.join("archive");
+        let path = dir.join(format!("network-{network_id}.jsonl"));
+        let io_error = |e: std::io::Error| {
+            AggSandboxError::Other(format!(
+                "Failed to write index archive {}: {e}",
+                path.display()
+            ))
+        };
+        fs::create_dir_all(&dir).map_err(io_error)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(io_error)?;
+        for line in pruned.archive_lines() {
+            writeln!(file, "{line}").map_err(io_error)?;
+        }
+        Ok(path)
+    }
+
+    /// Prune the records of a network older than `max_age`, archiving them unless `discard`
+    pub fn prune(
+        &self,
+        network_id: u64,
+        max_age: Duration,
+        discard: bool,
+    ) -> Result<PrunedRecords> {
+        self.prune_before(
+            network_id,
+            unix_now().saturating_sub(max_age.as_secs()),
+            discard,
+        )
+    }
+
+    /// Prune the records of a network indexed before `cutoff` (Unix time)
+    fn prune_before(&self, network_id: u64, cutoff: u64, discard: bool) -> Result<PrunedRecords> {
+        if !self.exists() {
+            return Ok(PrunedRecords::default());
+        }
+        let mut connection = self.open()?;
+        let write = |e: rusqlite::Error| self.error("prune", e);
+        let transaction = connection.transaction().map_err(write)?;
+        let older = |table: &str| {
+            format!("SELECT * FROM {table} WHERE network_id = ?1 AND indexed_at < {cutoff}")
+        };
+        let pruned = PrunedRecords {
+            deposits: select(
+                &transaction,
+                &older("deposits"),
+                network_id,
+                deposit_from_row,
+            )
+            .map_err(write)?,
+            claims: select(&transaction, &older("claims"), network_id, claim_from_row)
+                .map_err(write)?,
+            exit_roots: select(
+                &transaction,
+                &older("exit_roots"),
+                network_id,
+                exit_root_from_row,
+            )
+            .map_err(write)?,
+        };
+        if pruned.is_empty() {
+            return Ok(pruned);
+        }
+        if !discard {
+            self.archive(network_id, &pruned)?;
+        }
+        for table in ["deposits", "claims", "exit_roots"] {
+            transaction
+                .execute(
+                    &format!("DELETE FROM {table} WHERE network_id = ?1 AND indexed_at < ?2"),
+                    params![network_id, cutoff],
+                )
+                .map_err(write)?;
+        }
+        transaction.commit().map_err(write)?;
+        Ok(pruned)
+    }
+
+    /// Delete the index of a network, returning whether there was one
+    pub fn remove(&self, network_id: u64) -> Result<bool> {
+        if !self.exists() {
+            return Ok(false);
+        }
+        let mut connection = self.open()?;
+        let write = |e: rusqlite::Error| self.error("remove", e);
+        let transaction = connection.transaction().map_err(write)?;
+        let mut removed = 0;
+        for table in ["cursors", "deposits", "claims", "exit_roots"] {
+            removed += transaction
+                .execute(
+                    &format!("DELETE FROM {table} WHERE network_id = ?1"),
+                    params![network_id],
+                )
+                .map_err(write)?;
+        }
+        transaction.commit().map_err(write)?;
+        Ok(removed > 0)
+    }
+}
+
+/// Create the tables of the index
+///
+/// Tables of an older schema version are dropped first: everything in the
+/// index is on chain, so the next sync rebuilds it.
+fn create_tables(connection: &mut Connection) -> rusqlite::Result<()> {
+    let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let version: i64 = transaction.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version < SCHEMA_VERSION {
+        transaction.execute_batch(
+            "DROP TABLE IF EXISTS cursors;
+             DROP TABLE IF EXISTS deposits;
+             DROP TABLE IF EXISTS claims;
+             DROP TABLE IF EXISTS exit_roots;",
+        )?;
+        transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    }
+    transaction.execute_batch(SCHEMA)?;
+    transaction.commit()
+}
+
+/// Insert the records of `index` that are not indexed yet, see [`IndexStore::insert`]
+fn insert_records(
+    transaction: &Transaction,
+    index: &NetworkIndex,
+) -> rusqlite::Result<InsertedRecords> {
+    let mut inserted = InsertedRecords::default();
+    for deposit in &index.deposits {
+        inserted.deposits += transaction.execute(
+                "INSERT OR IGNORE INTO deposits VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    index.network_id,
+                    deposit.deposit_count,
+                    format!("{:#x}", deposit.bridge_tx_hash),
+                    deposit.block_num,
+                    deposit.leaf_type,
+                    deposit.origin_network,
+                    format!("{:#x}", deposit.origin_address),
+                    deposit.destination_network,
+                    format!("{:#x}", deposit.destination_address),
+                    deposit.amount.to_string(),
+                    deposit.metadata.to_vec(),
+                    deposit.indexed_at,
+                ],
+            )?;
+    }
+    for claim in &index.claims {
+        inserted.claims += transaction.execute(
+            "INSERT OR IGNORE INTO claims VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                index.network_id,
+                format!("{:#x}", claim.claim_tx_hash),
+                claim.global_index.to_string(),
+                claim.block_num,
+                claim.origin_network,
+                format!("{:#x}", claim.origin_address),
+                format!("{:#x}", claim.destination_address),
+                claim.amount.to_string(),
+                claim.indexed_at,
+            ],
+        )?;
+    }
+    for update in &index.exit_roots {
+        inserted.exit_roots += transaction.execute(
+            "INSERT OR IGNORE INTO exit_roots VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                index.network_id,
+                format!("{:#x}", update.tx_hash),
+                format!("{:#x}", update.global_exit_root),
+                update.block_num,
+                update.mainnet_exit_root.map(|root| format!("{root:#x}")),
+                update.rollup_exit_root.map(|root| format!("{root:#x}")),
+                update.indexed_at,
+            ],
+        )?;
+    }
+    Ok(inserted)
+}
+
+fn write_cursor(
+    connection: &Connection,
+    network_id: u64,
+    block: u64,
+    hash: Option<H256>,
+) -> rusqlite::Result<()> {
+    connection
+        .execute(
+            "INSERT INTO cursors VALUES (?1, ?2, ?3)
+             ON CONFLICT (network_id) DO UPDATE
+             SET last_block = excluded.last_block, last_block_hash = excluded.last_block_hash",
+            params![network_id, block, hash.map(|hash| format!("{hash:#x}"))],
+        )
+        .map(|_| ())
+}
+
+/// Records of one network selected by `sql`, whose only parameter is the network ID
+fn select<T>(
+    connection: &Connection,
+    sql: &str,
+    network_id: u64,
+    from_row: fn(&Row) -> rusqlite::Result<T>,
+) -> rusqlite::Result<Vec<T>> {
+    connection
+        .prepare(sql)?
+        .query_map(params![network_id], from_row)?
+        .collect()
+}
+
+fn deposit_from_row(row: &Row) -> rusqlite::Result<IndexedDeposit> {
+    Ok(IndexedDeposit {
+        bridge_tx_hash: parse(row, "bridge_tx_hash")?,
+        block_num: row.get("block_num")?,
+        leaf_type: row.get("leaf_type")?,
+        origin_network: row.get("origin_network")?,
+        origin_address: parse(row, "origin_address")?,
+        destination_network: row.get("destination_network")?,
+        destination_address: parse(row, "destination_address")?,
+        amount: parse_decimal(row, "amount")?,
+        metadata: row.get::<_, Vec<u8>>("metadata")?.into(),
+        deposit_count: row.get("deposit_count")?,
+        indexed_at: row.get("indexed_at")?,
+    })
+}
+
+fn claim_from_row(row: &Row) -> rusqlite::Result<IndexedClaim> {
+    Ok(IndexedClaim {
+        claim_tx_hash: parse(row, "claim_tx_hash")?,
+        block_num: row.get("block_num")?,
+        global_index: parse_decimal(row, "global_index")?,
+        origin_network: row.get("origin_network")?,
+        origin_address: parse(row, "origin_address")?,
+        destination_address: parse(row, "destination_address")?,
+        amount: parse_decimal(row, "amount")?,
+        indexed_at: row.get("indexed_at")?,
+    })
+}
+
+fn exit_root_from_row(row: &Row) -> rusqlite::Result<ExitRootUpdate> {
+    let column = |name: &str| row.as_ref().column_index(name);
+    Ok(ExitRootUpdate {
+        tx_hash: parse(row, "tx_hash")?,
+        block_num: row.get("block_num")?,
+        global_exit_root: parse(row, "global_exit_root")?,
+        mainnet_exit_root: parse_optional(row, column("mainnet_exit_root")?)?,
+        rollup_exit_root: parse_optional(row, column("rollup_exit_root")?)?,
+        indexed_at: row.get("indexed_at")?,
+    })
+}
+
+/// Address or hash stored as hex text
+fn parse<T>(row: &Row, column: &str) -> rusqlite::Result<T>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    let index = row.as_ref().column_index(column)?;
+    parse_optional(row, index)?.ok_or(rusqlite::Error::InvalidColumnType(
+        index,
+        column.to_string(),
+        rusqlite::types::Type::Null,
+    ))
+}
+
+fn parse_optional<T>(row: &Row, index: usize) -> rusqlite::Result<Option<T>>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    row.get::<_, Option<String>>(index)?
+        .map(|text| text.parse().map_err(|e| conversion_error(index, e)))
+        .transpose()
+}
+
+/// 256-bit value stored as decimal text
+fn parse_decimal(row: &Row, column: &str) -> rusqlite::Result<U256> {
+    let index = row.as_ref().column_index(column)?;
+    U256::from_dec_str(&row.get::<_, String>(index)?).map_err(|e| conversion_error(index, e))
+}
+
+fn conversion_error(index: usize, error: impl std::fmt::Display) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        index,
+        rusqlite::types::Type::Text,
+        error.to_string().into(),
+    )
+}
+
+/// Fetch the events of `network_id` produced since the last sync
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn sync_network(
+    config: &Config,
+    store: &IndexStore,
+    network_id: u64,
+) -> Result<(NetworkIndex, SyncSummary)> {
+    let provider = get_provider(config, network_id).await?;
+    let bridge = get_bridge_contract_address(config, network_id)?;
+    let ger_manager = Address::from_str(
+        &config
+            .contracts
+            .get_contract(&layer_for_network(network_id)?, "GlobalExitRootManager"),
+    )
+    .ok();
+
+    let cursor = store.cursor(network_id)?;
+    let latest = provider
+        .get_block(BlockNumber::Latest)
+        .await
+        .map_err(|e| rpc_error(&rpc_action(network_id, "eth_getBlockByNumber"), e))?
+        .ok_or_else(|| {
+            rpc_error(
+                &rpc_action(network_id, "eth_getBlockByNumber"),
+                "no latest block",
+            )
+        })?;
+    let latest_number = latest.number.map_or(0, |number| number.as_u64());
+
+    let rebuilt = match cursor {
+        Some((block, Some(hash))) => {
+            let current = provider
+                .get_block(block)
+                .await
+                .map_err(|e| rpc_error(&rpc_action(network_id, "eth_getBlockByNumber"), e))?;
+            current.and_then(|current| current.hash) != Some(hash)
+        }
+        _ => false,
+    };
+    if rebuilt {
+        info!(
+            network_id,
+            "Indexed chain no longer matches, rebuilding the index"
+        );
+        store.remove(network_id)?;
+    }
+
+    let from_block = match cursor {
+        Some((block, _)) if !rebuilt => block + 1,
+        _ => 0,
+    };
+    let mut inserted = InsertedRecords::default();
+    let addresses: Vec<Address> = std::iter::once(bridge).chain(ger_manager).collect();
+    for (start, end) in block_ranges(from_block, latest_number, LOG_CHUNK_BLOCKS) {
+        let filter = Filter::new()
+            .address(addresses.clone())
+            .from_block(start)
+            .to_block(end);
+        let logs = provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| rpc_error(&rpc_action(network_id, "eth_getLogs"), e))?;
+        debug!(
+            network_id,
+            start,
+            end,
+            logs = logs.len(),
+            "Indexed block range"
+        );
+        let mut chunk = NetworkIndex::new(network_id);
+        chunk.apply_logs(&logs, bridge, ger_manager);
+        let chunk_inserted = store.insert(&chunk)?;
+        inserted.deposits += chunk_inserted.deposits;
+        inserted.claims += chunk_inserted.claims;
+        inserted.exit_roots += chunk_inserted.exit_roots;
+    }
+    store.set_cursor(network_id, latest_number, latest.hash)?;
+
+    let pruned = match retention()? {
+        Some(max_age) => store.prune(network_id, max_age, false)?.len(),
+        None => 0,
+    };
+    let index = store
+        .load(network_id)?
+        .unwrap_or_else(|| NetworkIndex::new(network_id));
+
+    let summary = SyncSummary {
+        network_id,
+        from_block,
+        to_block: latest_number,
+        new_deposits: inserted.deposits,
+        new_claims: inserted.claims,
+        new_exit_roots: inserted.exit_roots,
+        rebuilt,
+        pruned,
+    };
+    Ok((index, summary))
+}
+
+/// Index of a network for `--source local`, synced first when the node is reachable
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn local_index(config: &Config, network_id: u64) -> Result<NetworkIndex> {
+    let store = IndexStore::default_location();
+    match sync_network(config, &store, network_id).await {
+        Ok((index, _)) => Ok(index),
+        Err(e) => {
+            debug!(network_id, error = %e, "Bridge index sync failed");
+            let index = store.load(network_id)?.ok_or_else(|| {
+                AggSandboxError::Other(format!(
+                    "No local bridge index for network {network_id} and syncing it failed: {e}"
+                ))
+            })?;
+            ui::ui().warning(&format!(
+                "Could not sync the local index of network {network_id} ({}), showing data up to block {}",
+                get_network_name(network_id),
+                index
+                    .last_block
+                    .map_or_else(|| "n/a".to_string(), |block| block.to_string())
+            ));
+            Ok(index)
+        }
+    }
+}
+
+/// Bridges of a network from the bridge service or the local index
+pub async fn get_bridges(
+    config: &Config,
+    network_id: u64,
+    source: DataSource,
+) -> Result<serde_json::Value> {
+    match source {
+        DataSource::Api => {
+            let bridges = match crate::api_client::OptimizedApiClient::global()
+                .get_bridges(config, network_id)
+                .await
+            {
+                Ok(bridges) => bridges,
+                Err(e) if is_api_unavailable(&e) => {
+                    return bridges_from_chain(config, network_id, &e).await
+                }
+                Err(e) => return Err(e),
+            };
+            known_issues::check(Endpoint::Bridges, network_id, &bridges);
+            Ok(bridges)
+        }
+        DataSource::Local => Ok(local_index(config, network_id).await?.bridges_json()),
+    }
+}
+
+/// Claims of a network from the bridge service or the local index
+pub async fn get_claims(
+    config: &Config,
+    network_id: u64,
+    source: DataSource,
+) -> Result<serde_json::Value> {
+    match source {
+        DataSource::Api => {
+            match crate::api_client::OptimizedApiClient::global()
+                .get_claims(config, network_id)
+                .await
+            {
+                Ok(claims) => Ok(claims),
+                Err(e) if is_api_unavailable(&e) => claims_from_chain(config, network_id, &e).await,
+                Err(e) => Err(e),
+            }
+        }
+        DataSource::Local => Ok(local_index(config, network_id).await?.claims_json()),
+    }
+}
+
+/// Whether an error means the bridge service could not be reached at all
+///
+/// Error responses of a running service are not covered: its data is then
+/// wrong rather than missing, and chain events would hide the problem.
+pub fn is_api_unavailable(error: &AggSandboxError) -> bool {
+    matches!(
+        error,
+        AggSandboxError::Api(ApiError::NetworkError(_) | ApiError::EndpointUnavailable(_))
+    )
+}
+
+/// Bridges of a network rebuilt from `BridgeEvent` logs in place of the bridge service
+pub async fn bridges_from_chain(
+    config: &Config,
+    network_id: u64,
+    error: &AggSandboxError,
+) -> Result<serde_json::Value> {
+    warn_api_unavailable("bridges", network_id, error);
+    let bridges = local_index(config, network_id).await?.bridges_json();
+    Ok(mark_chain_fallback(bridges, API_ONLY_BRIDGE_FIELDS))
+}
+
+/// Claims of a network rebuilt from `ClaimEvent` logs in place of the bridge service
+pub async fn claims_from_chain(
+    config: &Config,
+    network_id: u64,
+    error: &AggSandboxError,
+) -> Result<serde_json::Value> {
+    warn_api_unavailable("claims", network_id, error);
+    let claims = local_index(config, network_id).await?.claims_json();
+    Ok(mark_chain_fallback(claims, API_ONLY_CLAIM_FIELDS))
+}
+
+fn warn_api_unavailable(records: &str, network_id: u64, error: &AggSandboxError) {
+    ui::ui().warning(&format!(
+        "Bridge service unreachable ({error}); {records} of network {network_id} were rebuilt \
+         from chain events. Fields only the service supplies are missing, and claim proofs \
+         still require it"
+    ));
+}
+
+/// Mark records read from chain because the bridge service was unreachable
+///
+/// `missing_fields` lists what the service would have added, so scripts can
+/// tell an empty value from one that was never available.
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn mark_chain_fallback(mut data: serde_json::Value, api_only_fields: &[&str]) -> serde_json::Value {
+    data["source"] = serde_json::json!("chain");
+    data["api_unavailable"] = serde_json::json!(true);
+    data["missing_fields"] = serde_json::json!(api_only_fields);
+    data
+}
+
+/// Retention policy from `INDEX_RETENTION`, `None` when records are kept forever
+pub fn retention() -> Result<Option<Duration>> {
+    match std::env::var(RETENTION_ENV) {
+        Ok(value) if !value.trim().is_empty() => parse_age(&value)
+            .map(Some)
+            .map_err(|e| AggSandboxError::Other(format!("Invalid {RETENTION_ENV}: {e}"))),
+        _ => Ok(None),
+    }
+}
+
+/// Clap value parser for ages such as `90m`, `12h`, `7d` or `2w`
+pub fn parse_age(input: &str) -> std::result::Result<Duration, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (amount, unit) = input.split_at(split);
+    let invalid = || format!("'{input}' is not an age such as 12h or 7d (units: s, m, h, d, w)");
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        "w" => 604_800,
+        _ => return Err(invalid()),
+    };
+    Ok(Duration::from_secs(amount.saturating_mul(seconds)))
+}
+
+fn unix_now() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+/// Indexing time of a record, stamped `now` when legacy JSON indexes left it `null`
+fn indexed_at_or_now<'de, D>(deserializer: D) -> std::result::Result<u64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Option::<u64>::deserialize(deserializer)?.unwrap_or_else(unix_now))
+}
+
+/// Inclusive block ranges of at most `chunk` blocks covering `from..=to`
+fn block_ranges(from: u64, to: u64, chunk: u64) -> Vec<(u64, u64)> {
+    let mut ranges = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let end = start.saturating_add(chunk - 1).min(to);
+        ranges.push((start, end));
+        start = end + 1;
+    }
+    ranges
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use ethers::abi::{encode, Token};
+
+    const BRIDGE: Address = H160([0xb0; 20]);
+    const GER: Address = H160([0x6e; 20]);
+
+    fn log(address: Address, topics: Vec<H256>, data: Vec<u8>, block: u64) -> Log {
+        Log {
+            address,
+            topics,
+            data: data.into(),
+            block_number: Some(block.into()),
+            transaction_hash: Some(H256::repeat_byte(block as u8)),
+            ..Default::default()
+        }
+    }
+
+    fn bridge_event(deposit_count: u32, block: u64) -> Log {
+        let data = encode(&[
+            Token::Uint(0.into()),
+            Token::Uint(0.into()),
+            Token::Address(Address::zero()),
+            Token::Uint(1.into()),
+            Token::Address(Address::repeat_byte(0x11)),
+            Token::Uint(U256::exp10(18)),
+            Token::Bytes(vec![0xab]),
+            Token::Uint(deposit_count.into()),
+        ]);
+        log(BRIDGE, vec![BridgeEventFilter::signature()], data, block)
+    }
+
+    #[test]
+    fn test_is_api_unavailable() {
+        assert!(is_api_unavailable(&AggSandboxError::Api(
+            ApiError::network_error("Connection failed")
+        )));
+        assert!(is_api_unavailable(&AggSandboxError::Api(
+            ApiError::endpoint_unavailable("/bridge/v1/bridges")
+        )));
+        assert!(!is_api_unavailable(&AggSandboxError::Api(
+            ApiError::request_failed("http://localhost:5577", 500, "internal error")
+        )));
+        assert!(!is_api_unavailable(&AggSandboxError::Other("boom".into())));
+    }
+
+    #[test]
+    fn test_mark_chain_fallback() {
+        let data = mark_chain_fallback(
+            serde_json::json!({ "count": 0, "bridges": [], "source": "local" }),
+            API_ONLY_BRIDGE_FIELDS,
+        );
+        assert_eq!(data["source"], "chain");
+        assert_eq!(data["api_unavailable"], true);
+        assert_eq!(data["missing_fields"][1], "block_timestamp");
+        assert_eq!(data["count"], 0);
+    }
+
+    #[test]
+    fn test_apply_logs() {
+        let claim = encode(&[
+            Token::Uint(U256::from_dec_str("18446744073709551621").unwrap()),
+            Token::Uint(0.into()),
+            Token::Address(Address::zero()),
+            Token::Address(Address::repeat_byte(0x22)),
+            Token::Uint(5.into()),
+        ]);
+        // Events of other contracts are ignored
+        let mut foreign = bridge_event(1, 6);
+        foreign.address = Address::repeat_byte(0x99);
+        let logs = vec![
+            bridge_event(0, 3),
+            log(BRIDGE, vec![ClaimEventFilter::signature()], claim, 4),
+            log(
+                GER,
+                vec![
+                    UpdateL1InfoTreeFilter::signature(),
+                    H256::repeat_byte(1),
+                    H256::repeat_byte(2),
+                ],
+                Vec::new(),
+                5,
+            ),
+            foreign,
+        ];
+
+        let mut index = NetworkIndex::new(0);
+        index.apply_logs(&logs, BRIDGE, Some(GER));
+        index.apply_logs(&[bridge_event(0, 3)], BRIDGE, Some(GER));
+
+        assert_eq!(index.deposits.len(), 1);
+        assert_eq!(index.deposits[0].destination_network, 1);
+        assert_eq!(index.deposits[0].metadata.to_vec(), vec![0xab]);
+        assert_eq!(index.claims.len(), 1);
+        assert_eq!(index.claims[0].amount, U256::from(5));
+        assert_eq!(index.exit_roots.len(), 1);
+        assert_eq!(
+            index.exit_roots[0].mainnet_exit_root,
+            Some(H256::repeat_byte(1))
+        );
+    }
+
+    #[test]
+    fn test_api_shaped_json() {
+        let mut index = NetworkIndex::new(0);
+        index.apply_logs(&[bridge_event(0, 3), bridge_event(1, 4)], BRIDGE, None);
+
+        let bridges = index.bridges_json();
+
+        assert_eq!(bridges["count"], 2);
+        assert_eq!(bridges["bridges"][0]["deposit_count"], 1);
+        assert_eq!(bridges["bridges"][0]["amount"], "1000000000000000000");
+        assert_eq!(bridges["bridges"][0]["metadata"], "0xab");
+        assert_eq!(
+            bridges["bridges"][0]["bridge_tx_hash"],
+            format!("{:#x}", H256::repeat_byte(4))
+        );
+        assert_eq!(index.claims_json()["count"], 0);
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().join("index"));
+        assert_eq!(store.load(1).unwrap(), None);
+
+        let mut index = NetworkIndex::new(1);
+        index.apply_logs(&[bridge_event(7, 9)], BRIDGE, None);
+        index.last_block = Some(9);
+        store.save(&index).unwrap();
+
+        assert_eq!(store.load(1).unwrap(), Some(index));
+        assert!(store.remove(1).unwrap());
+        assert!(!store.remove(1).unwrap());
+    }
+
+    #[test]
+    fn test_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().join("index"));
+        let mut index = NetworkIndex::new(1);
+        index.apply_logs(&[bridge_event(0, 3), bridge_event(1, 4)], BRIDGE, None);
+        index.deposits[0].indexed_at = 100;
+        index.deposits[1].indexed_at = 2_000;
+        index.last_block = Some(4);
+        store.save(&index).unwrap();
+
+        let pruned = store.prune_before(1, 1_000, false).unwrap();
+        assert_eq!(pruned.deposits.len(), 1);
+        assert_eq!(pruned.deposits[0].deposit_count, 0);
+        let remaining = store.load(1).unwrap().unwrap();
+        assert_eq!(remaining.deposits.len(), 1);
+        assert_eq!(remaining.deposits[0].indexed_at, 2_000);
+        assert!(store.prune_before(1, 1_000, false).unwrap().is_empty());
+
+        let path = store.archive(1, &pruned).unwrap();
+        let archived = fs::read_to_string(path).unwrap();
+        assert_eq!(archived.lines().count(), 2);
+        assert!(archived.contains("\"kind\":\"deposit\""));
+    }
+
+    #[test]
+    fn test_open_drops_older_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().join("index"));
+        fs::create_dir_all(dir.path().join("index")).unwrap();
+        Connection::open(store.path())
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE cursors (network_id INTEGER PRIMARY KEY, last_block INTEGER NOT NULL, last_block_hash TEXT);
+                 INSERT INTO cursors VALUES (1, 9, NULL);
+                 CREATE TABLE deposits (network_id INTEGER NOT NULL, deposit_count INTEGER NOT NULL);",
+            )
+            .unwrap();
+
+        assert_eq!(store.load(1).unwrap(), None);
+        let mut index = NetworkIndex::new(1);
+        index.apply_logs(&[bridge_event(0, 3)], BRIDGE, None);
+        index.last_block = Some(3);
+        store.save(&index).unwrap();
+        assert_eq!(store.load(1).unwrap(), Some(index));
+    }
+
+    #[test]
+    fn test_open_imports_legacy_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("index");
+        let store = IndexStore::new(&root);
+        let mut legacy = NetworkIndex::new(1);
+        legacy.apply_logs(&[bridge_event(0, 3), bridge_event(1, 4)], BRIDGE, None);
+        legacy.last_block = Some(4);
+        let mut json = serde_json::to_value(&legacy).unwrap();
+        // Indexes written before indexing times were recorded
+        json["deposits"][0]["indexed_at"] = serde_json::Value::Null;
+        json["deposits"][1]
+            .as_object_mut()
+            .unwrap()
+            .remove("indexed_at");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("network-1.json"), json.to_string()).unwrap();
+
+        let before = unix_now();
+        let index = store.load(1).unwrap().unwrap();
+        assert_eq!(index.last_block, Some(4));
+        assert_eq!(index.deposits.len(), 2);
+        assert!(index.deposits.iter().all(|d| d.indexed_at >= before));
+        assert!(!root.join("network-1.json").exists());
+        assert_eq!(store.load(1).unwrap(), Some(index));
+    }
+
+    #[test]
+    fn test_legacy_json_keeps_synced_networks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("index");
+        let store = IndexStore::new(&root);
+        let mut synced = NetworkIndex::new(1);
+        synced.apply_logs(&[bridge_event(0, 3)], BRIDGE, None);
+        synced.last_block = Some(3);
+        store.save(&synced).unwrap();
+
+        let mut legacy = NetworkIndex::new(1);
+        legacy.apply_logs(&[bridge_event(5, 8)], BRIDGE, None);
+        legacy.last_block = Some(8);
+        fs::write(
+            root.join("network-1.json"),
+            serde_json::to_string(&legacy).unwrap(),
+        )
+        .unwrap();
+        fs::write(root.join("network-2.json"), "{ not json").unwrap();
+
+        assert_eq!(store.load(1).unwrap(), Some(synced));
+        assert_eq!(store.load(2).unwrap(), None);
+        assert!(!root.join("network-1.json").exists());
+        assert!(!root.join("network-2.json").exists());
+    }
+
+    #[test]
+    fn test_insert_skips_indexed_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().join("index"));
+        let claim = log(
+            BRIDGE,
+            vec![ClaimEventFilter::signature()],
+            claim_data(1),
+            4,
+        );
+        let mut chunk = NetworkIndex::new(1);
+        chunk.apply_logs(&[bridge_event(0, 3), claim.clone()], BRIDGE, None);
+
+        let first = store.insert(&chunk).unwrap();
+        let second = store.insert(&chunk).unwrap();
+
+        assert_eq!((first.deposits, first.claims), (1, 1));
+        assert_eq!(second, InsertedRecords::default());
+        store.set_cursor(1, 4, None).unwrap();
+        let index = store.load(1).unwrap().unwrap();
+        assert_eq!(index.deposits.len(), 1);
+        assert_eq!(index.claims.len(), 1);
+        assert_eq!(index.claims[0].global_index, U256::from(1));
+    }
+
+    fn claim_data(global_index: u64) -> Vec<u8> {
+        encode(&[
+            Token::Uint(global_index.into()),
+            Token::Uint(0.into()),
+            Token::Address(Address::zero()),
+            Token::Address(Address::repeat_byte(0x22)),
+            Token::Uint(5.into()),
+        ])
+    }
+
+    /// JSON-RPC node serving a fixed chain: block hashes by number and its logs
+    struct Chain {
+        hashes: Vec<H256>,
+        logs: Vec<Log>,
+    }
+
+    impl wiremock::Respond for Chain {
+        fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            let request: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let block = |number: usize| serde_json::json!({ "number": U64::from(number), "hash": self.hashes[number] });
+            let result = match request["method"].as_str().unwrap() {
+                "eth_getBlockByNumber" => match request["params"][0].as_str().unwrap() {
+                    "latest" => block(self.hashes.len() - 1),
+                    number => block(U64::from_str(number).unwrap().as_usize()),
+                },
+                "eth_getLogs" => serde_json::to_value(&self.logs).unwrap(),
+                method => panic!("unexpected {method}"),
+            };
+            wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": result,
+            }))
+        }
+    }
+
+    async fn serve(server: &wiremock::MockServer, chain: Chain) {
+        server.reset().await;
+        wiremock::Mock::given(wiremock::matchers::method("POST"))
+            .respond_with(chain)
+            .mount(server)
+            .await;
+    }
+
+    fn chain_config(rpc_url: &str) -> Config {
+        let mut config = Config::default();
+        config.networks.l1.rpc_url = crate::types::RpcUrl::new(rpc_url).unwrap();
+        config.contracts.l1_contracts.insert(
+            "PolygonZkEVMBridge".to_string(),
+            crate::types::EthereumAddress::new(format!("{BRIDGE:#x}")).unwrap(),
+        );
+        config
+    }
+
+    #[tokio::test]
+    async fn test_sync_ignores_logs_delivered_twice() {
+        let server = wiremock::MockServer::start().await;
+        let config = chain_config(&server.uri());
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().join("index"));
+        let claim = log(
+            BRIDGE,
+            vec![ClaimEventFilter::signature()],
+            claim_data(1),
+            4,
+        );
+        let logs = vec![bridge_event(0, 3), claim];
+
+        let hashes = (0..5).map(|n| H256::repeat_byte(n as u8 + 1)).collect();
+        serve(
+            &server,
+            Chain {
+                hashes,
+                logs: logs.clone(),
+            },
+        )
+        .await;
+        let (_, first) = sync_network(&config, &store, 0).await.unwrap();
+        // The node returns the same logs again for the new blocks
+        let hashes = (0..7).map(|n| H256::repeat_byte(n as u8 + 1)).collect();
+        serve(&server, Chain { hashes, logs }).await;
+        let (index, second) = sync_network(&config, &store, 0).await.unwrap();
+
+        assert_eq!((first.new_deposits, first.new_claims), (1, 1));
+        assert_eq!((second.from_block, second.to_block), (5, 6));
+        assert_eq!((second.new_deposits, second.new_claims), (0, 0));
+        assert!(!second.rebuilt);
+        assert_eq!(index.deposits.len(), 1);
+        assert_eq!(index.claims.len(), 1);
+        assert_eq!(index.last_block, Some(6));
+    }
+
+    #[tokio::test]
+    async fn test_sync_rebuilds_after_reorg() {
+        let server = wiremock::MockServer::start().await;
+        let config = chain_config(&server.uri());
+        let dir = tempfile::tempdir().unwrap();
+        let store = IndexStore::new(dir.path().join("index"));
+        let claim = log(
+            BRIDGE,
+            vec![ClaimEventFilter::signature()],
+            claim_data(1),
+            4,
+        );
+
+        let hashes = (0..5).map(|n| H256::repeat_byte(n as u8 + 1)).collect();
+        serve(
+            &server,
+            Chain {
+                hashes,
+                logs: vec![bridge_event(0, 3), bridge_event(1, 4), claim],
+            },
+        )
+        .await;
+        sync_network(&config, &store, 0).await.unwrap();
+        // Block 4 was replaced: only the first deposit survives, mined in another block
+        let hashes = (0..6).map(|n| H256::repeat_byte(n as u8 + 0x10)).collect();
+        serve(
+            &server,
+            Chain {
+                hashes,
+                logs: vec![bridge_event(0, 2)],
+            },
+        )
+        .await;
+        let (index, summary) = sync_network(&config, &store, 0).await.unwrap();
+
+        assert!(summary.rebuilt);
+        assert_eq!(summary.from_block, 0);
+        assert_eq!(summary.new_deposits, 1);
+        assert_eq!(index.deposits.len(), 1);
+        assert_eq!(index.deposits[0].block_num, 2);
+        assert_eq!(index.deposits[0].bridge_tx_hash, H256::repeat_byte(2));
+        assert!(index.claims.is_empty());
+        assert_eq!(index.last_block_hash, Some(H256::repeat_byte(0x15)));
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("7d"), Ok(Duration::from_secs(7 * 86_400)));
+        assert_eq!(parse_age("90m"), Ok(Duration::from_secs(5_400)));
+        assert_eq!(parse_age(" 2w "), Ok(Duration::from_secs(14 * 86_400)));
+        assert!(parse_age("3").is_err());
+        assert!(parse_age("7y").is_err());
+        assert!(parse_age("d").is_err());
+    }
+
+    #[test]
+    fn test_block_ranges() {
+        assert_eq!(block_ranges(0, 4, 2), vec![(0, 1), (2, 3), (4, 4)]);
+        assert_eq!(block_ranges(5, 5, 2000), vec![(5, 5)]);
+        assert!(block_ranges(6, 5, 2000).is_empty());
+    }
+}
@@ -0,0 +1,373 @@
+//! Pre-flight simulation of BridgeExtension message claims
+//!
+//! Claiming the message of a bridge-and-call deploys a JumpPoint that calls
+//! the target with the bridged tokens and, when that call reverts, sends them
+//! to the fallback address instead. The claim itself succeeds either way, so
+//! a failing target only shows up as tokens at the fallback. Before the claim
+//! is sent it is traced with `debug_traceCall` and the call tracer, and the
+//! frame of the target call tells whether it would succeed and why not.
+
+use crate::ui;
+use ethers::abi::{decode, ParamType, Token};
+use ethers::prelude::*;
+use serde_json::Value;
+use tracing::debug;
+
+use super::claim_message::{claim_message_call, ClaimMessageArgs};
+use super::receipt::decode_revert;
+
+/// Message payload of a bridge-and-call, as encoded by the BridgeExtension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionMessage {
+    /// Deposit count of the asset deposit the message depends on
+    pub depends_on_index: u64,
+    pub call_address: Address,
+    pub fallback_address: Address,
+    pub asset_original_network: u32,
+    pub asset_original_address: Address,
+    pub call_data: Vec<u8>,
+}
+
+impl ExtensionMessage {
+    /// Decode the metadata of a message deposit, `None` if it is not a bridge-and-call
+    pub fn decode(metadata: &[u8]) -> Option<Self> {
+        let tokens = decode(
+            &[
+                ParamType::Uint(256),
+                ParamType::Address,
+                ParamType::Address,
+                ParamType::Uint(32),
+                ParamType::Address,
+                ParamType::Bytes,
+            ],
+            metadata,
+        )
+        .ok()?;
+        match tokens.as_slice() {
+            [Token::Uint(index), Token::Address(call), Token::Address(fallback), Token::Uint(network), Token::Address(asset), Token::Bytes(call_data)] => {
+                Some(Self {
+                    depends_on_index: u64::try_from(*index).ok()?,
+                    call_address: *call,
+                    fallback_address: *fallback,
+                    asset_original_network: u32::try_from(*network).ok()?,
+                    asset_original_address: *asset,
+                    call_data: call_data.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// What the claim would do with the bridged tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallOutcome {
+    /// The target call succeeds
+    Succeeds,
+    /// The target call reverts and the tokens go to the fallback address
+    Fallback {
+        reason: String,
+        /// Whether sending the tokens to the fallback address succeeds
+        fallback_succeeds: bool,
+    },
+    /// The claim transaction itself reverts
+    ClaimReverts { reason: String },
+    /// The trace never reaches the target call
+    NotReached,
+}
+
+/// Result of simulating a message claim
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessagePreflight {
+    pub message: ExtensionMessage,
+    /// Whether the asset deposit the message depends on is claimed
+    pub asset_claimed: bool,
+    pub outcome: CallOutcome,
+}
+
+impl MessagePreflight {
+    /// Whether the claim would succeed without running the target call
+    pub fn routes_to_fallback(&self) -> bool {
+        matches!(self.outcome, CallOutcome::Fallback { .. })
+    }
+
+    pub fn print(&self) {
+        let ui = ui::ui();
+        let target = format!("{:#x}", self.message.call_address);
+        if !self.asset_claimed {
+            ui.warning(&format!(
+                "Asset deposit {} of this message is not claimed yet, the call runs without its tokens; claim it first or use `aggsandbox bridge claim-bundle`",
+                self.message.depends_on_index
+            ));
+        }
+        match &self.outcome {
+            CallOutcome::Succeeds => {
+                ui.success(&format!("Pre-flight: the call to {target} succeeds"));
+            }
+            CallOutcome::Fallback {
+                reason,
+                fallback_succeeds,
+            } => {
+                ui.warning(&format!(
+                    "Pre-flight: the call to {target} would revert ({reason})"
+                ));
+                if *fallback_succeeds {
+                    ui.warning(&format!(
+                        "The claim would succeed and send the tokens to the fallback address {:#x}",
+                        self.message.fallback_address
+                    ));
+                } else {
+                    ui.warning(&format!(
+                        "Sending the tokens to the fallback address {:#x} would fail as well, they would stay in the JumpPoint",
+                        self.message.fallback_address
+                    ));
+                }
+            }
+            CallOutcome::ClaimReverts { reason } => {
+                ui.warning(&format!("Pre-flight: the claim would revert ({reason})"));
+            }
+            CallOutcome::NotReached => {
+                ui.warning(&format!(
+                    "Pre-flight: the claim does not reach the call to {target}"
+                ));
+            }
+        }
+    }
+}
+
+/// Simulate a BridgeExtension message claim, `None` when it cannot be traced
+///
+/// Nodes without `debug_traceCall` skip the pre-flight check.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn simulate(args: &ClaimMessageArgs<'_>) -> Option<MessagePreflight> {
+    let message = ExtensionMessage::decode(&args.metadata_bytes)?;
+    let client = args.bridge.client();
+
+    let asset_claimed = args
+        .bridge
+        .is_claimed(message.depends_on_index as u32, args.origin_network)
+        .call()
+        .await
+        .unwrap_or(false);
+
+    let call = claim_message_call(args);
+    let trace: Value = match client
+        .provider()
+        .request(
+            "debug_traceCall",
+            (
+                &call.tx,
+                "latest",
+                serde_json::json!({ "tracer": "callTracer" }),
+            ),
+        )
+        .await
+    {
+        Ok(trace) => trace,
+        Err(e) => {
+            debug!(error = %e, "debug_traceCall unavailable, skipping message pre-flight");
+            return None;
+        }
+    };
+
+    let outcome = outcome_from_trace(&trace, &message);
+    Some(MessagePreflight {
+        message,
+        asset_claimed,
+        outcome,
+    })
+}
+
+/// Outcome of the target call in a call tracer trace of the claim
+pub fn outcome_from_trace(trace: &Value, message: &ExtensionMessage) -> CallOutcome {
+    if trace.get("error").is_some() {
+        return CallOutcome::ClaimReverts {
+            reason: frame_revert_reason(trace),
+        };
+    }
+
+    let mut frames = Vec::new();
+    collect_frames(trace, &mut frames);
+    // Matched on the calldata too, the JumpPoint may also call the target
+    // as the bridged token (balanceOf, approve)
+    let call_data = hex::encode(&message.call_data);
+    let Some(position) = frames.iter().position(|frame| {
+        frame_to(frame) == Some(message.call_address)
+            && frame["input"].as_str().is_some_and(|input| {
+                input
+                    .trim_start_matches("0x")
+                    .eq_ignore_ascii_case(&call_data)
+            })
+    }) else {
+        return CallOutcome::NotReached;
+    };
+    let target = frames[position];
+    if target.get("error").is_none() {
+        return CallOutcome::Succeeds;
+    }
+
+    // The JumpPoint sends ETH straight to the fallback address, and tokens
+    // with a transfer to it
+    let fallback_word = hex::encode(H256::from(message.fallback_address));
+    let fallback_succeeds = frames[position + 1..]
+        .iter()
+        .find(|frame| {
+            frame_to(frame) == Some(message.fallback_address)
+                || frame["input"]
+                    .as_str()
+                    .is_some_and(|input| input.to_lowercase().contains(&fallback_word))
+        })
+        .is_some_and(|frame| frame.get("error").is_none());
+    CallOutcome::Fallback {
+        reason: frame_revert_reason(target),
+        fallback_succeeds,
+    }
+}
+
+/// Frames of a call tracer trace in execution order
+fn collect_frames<'a>(frame: &'a Value, frames: &mut Vec<&'a Value>) {
+    frames.push(frame);
+    for call in frame["calls"].as_array().into_iter().flatten() {
+        collect_frames(call, frames);
+    }
+}
+
+fn frame_to(frame: &Value) -> Option<Address> {
+    frame["to"].as_str()?.parse().ok()
+}
+
+/// Decoded revert reason of a failed frame
+fn frame_revert_reason(frame: &Value) -> String {
+    let output = frame["output"]
+        .as_str()
+        .and_then(|output| hex::decode(output.trim_start_matches("0x")).ok())
+        .unwrap_or_default();
+    if let Some(reason) = decode_revert(&output) {
+        return reason;
+    }
+    if let Some(reason) = frame["revertReason"].as_str() {
+        return format!("reverted with reason \"{reason}\"");
+    }
+    let error = frame["error"].as_str().unwrap_or("reverted");
+    if output.is_empty() {
+        error.to_string()
+    } else {
+        format!("{error}, revert data 0x{}", hex::encode(&output))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use ethers::abi::encode;
+    use serde_json::json;
+
+    fn message() -> ExtensionMessage {
+        ExtensionMessage {
+            depends_on_index: 4,
+            call_address: Address::repeat_byte(0xca),
+            fallback_address: Address::repeat_byte(0xfb),
+            asset_original_network: 0,
+            asset_original_address: Address::zero(),
+            call_data: vec![0xd0, 0x9d, 0xe0, 0x8a],
+        }
+    }
+
+    fn trace(target: Value, fallback: Option<Value>) -> Value {
+        // The bridged token is the target too: balanceOf comes first
+        let mut calls = vec![json!({
+            "type": "STATICCALL",
+            "to": format!("{:#x}", message().call_address),
+            "input": "0x70a08231",
+        })];
+        calls.push(target);
+        calls.extend(fallback);
+        json!({
+            "type": "CALL",
+            "to": "0x5fbdb2315678afecb367f032d93f642f64180aa3",
+            "calls": [{
+                "type": "CALL",
+                "to": "0x1111111111111111111111111111111111111111",
+                "calls": [{ "type": "CREATE2", "calls": calls }],
+            }],
+        })
+    }
+
+    #[test]
+    fn test_decode_extension_message() {
+        let message = message();
+        let metadata = encode(&[
+            Token::Uint(message.depends_on_index.into()),
+            Token::Address(message.call_address),
+            Token::Address(message.fallback_address),
+            Token::Uint(message.asset_original_network.into()),
+            Token::Address(message.asset_original_address),
+            Token::Bytes(message.call_data.clone()),
+        ]);
+
+        assert_eq!(ExtensionMessage::decode(&metadata), Some(message));
+        assert_eq!(ExtensionMessage::decode(b"plain message"), None);
+    }
+
+    #[test]
+    fn test_target_call_succeeds() {
+        let target = json!({
+            "type": "CALL",
+            "to": format!("{:#x}", message().call_address),
+            "input": "0xd09de08a",
+        });
+        let trace = trace(target, None);
+        assert_eq!(
+            outcome_from_trace(&trace, &message()),
+            CallOutcome::Succeeds
+        );
+    }
+
+    #[test]
+    fn test_target_call_reverts_to_fallback() {
+        let mut reason = ethers::utils::id("Error(string)")[..4].to_vec();
+        reason.extend(encode(&[Token::String("not allowed".to_string())]));
+        let target = json!({
+            "type": "CALL",
+            "to": format!("{:#x}", message().call_address),
+            "input": "0xd09de08a",
+            "error": "execution reverted",
+            "output": format!("0x{}", hex::encode(reason)),
+        });
+        let fallback =
+            json!({ "type": "CALL", "to": format!("{:#x}", message().fallback_address) });
+
+        assert_eq!(
+            outcome_from_trace(&trace(target.clone(), Some(fallback)), &message()),
+            CallOutcome::Fallback {
+                reason: "reverted with reason \"not allowed\"".to_string(),
+                fallback_succeeds: true,
+            }
+        );
+        assert!(matches!(
+            outcome_from_trace(&trace(target, None), &message()),
+            CallOutcome::Fallback {
+                fallback_succeeds: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_claim_reverts() {
+        let trace = json!({
+            "type": "CALL",
+            "error": "execution reverted",
+            "output": format!("0x{}", hex::encode(&ethers::utils::id("GlobalExitRootInvalid()")[..4])),
+        });
+        let CallOutcome::ClaimReverts { reason } = outcome_from_trace(&trace, &message()) else {
+            panic!("expected the claim to revert");
+        };
+        assert!(reason.starts_with("GlobalExitRootInvalid"));
+        assert_eq!(
+            outcome_from_trace(&json!({ "type": "CALL" }), &message()),
+            CallOutcome::NotReached
+        );
+    }
+}
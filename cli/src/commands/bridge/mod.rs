@@ -1,24 +1,78 @@
 // Bridge command module
 pub mod bridge_asset;
 pub mod bridge_call;
+pub mod calldata;
+pub mod certificates;
+pub mod checkpoint;
+pub mod claim_all;
 pub mod claim_asset;
+pub mod claim_budget;
+pub mod claim_bundle;
 pub mod claim_message;
 pub mod common;
+pub mod exit_roots;
+pub mod faucet;
+pub mod fees;
+pub mod frontrun;
+pub mod gas_report;
+pub mod halt;
+pub mod idempotency;
+pub mod indexing;
+pub mod known_issues;
+pub mod l1_info_tree;
+pub mod last_used;
+pub mod local_index;
+pub mod message_preflight;
+pub mod native_message;
+pub mod nonce;
+pub mod offline_claim;
+pub mod permit;
+pub mod proof;
+pub mod proof_wait;
+pub mod receipt;
+pub mod rollups;
+pub mod signer;
+pub mod timing;
+pub mod token_deploy;
+pub mod token_metadata;
 pub mod utilities;
+pub mod watch;
+#[cfg(feature = "tui")]
+pub mod wizard;
+
+/// Stand-in for the interactive wizard in builds without prompts
+#[cfg(not(feature = "tui"))]
+pub mod wizard {
+    use crate::config::Config;
+    use crate::error::Result;
+
+    pub async fn run_wizard(_config: &Config, _private_key: Option<&str>) -> Result<()> {
+        Err(super::common::validation_error(
+            "The bridge wizard needs aggsandbox built with the 'tui' feature",
+        ))
+    }
+}
 
 // Re-export main types and functions
 pub use bridge_asset::{bridge_asset, BridgeAssetArgs, GasOptions};
 pub use bridge_call::{
     bridge_and_call_with_approval, bridge_message, BridgeAndCallArgs, BridgeMessageParams,
 };
+pub use claim_all::{claim_all, ClaimAllArgs};
 pub use claim_asset::{claim_asset, ClaimAssetArgs};
+use last_used::{or_last_used, LastUsed, LastUsedStore};
+pub use native_message::{bridge_native_message, NativeMessageArgs};
+pub use signer::{SandboxSigner, SignerKind};
 pub use utilities::{handle_utility_command, UtilityCommands};
 
-use crate::config::Config;
+use crate::config::{ChainConfig, Config};
 use crate::error::Result;
+use crate::ui::OutputFormat;
+use crate::validation::{parse_int, parse_network_id, parse_uint256};
 use ethers::prelude::*;
 use ethers::providers::{Http, Provider};
 use ethers::signers::LocalWallet;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::info;
@@ -33,6 +87,8 @@ abigen!(
         function decimals() external view returns (uint8)
         function name() external view returns (string)
         function symbol() external view returns (string)
+        function transfer(address to, uint256 amount) external returns (bool)
+        function mint(address to, uint256 amount) external
     ]"#,
 );
 
@@ -49,6 +105,8 @@ abigen!(
         function wrappedTokenToTokenInfo(address wrappedToken) external view returns (uint32, address)
         function isClaimed(uint32 leafIndex, uint32 sourceBridgeNetwork) external view returns (bool)
         function networkID() external view returns (uint32)
+        function depositCount() external view returns (uint256)
+        event ClaimEvent(uint256 globalIndex, uint32 originNetwork, address originAddress, address destinationAddress, uint256 amount)
     ]"#,
 );
 
@@ -77,34 +135,74 @@ Network IDs:
   • 1 = L2 AggLayer 1 (Chain ID 1101)
   • 2 = L2 AggLayer 2 (Chain ID 137, if multi-L2 enabled)
 
+Aliases such as l1, l2, l3 or configured chain names are accepted wherever a
+network ID is expected.
+
 Examples:
   aggsandbox bridge asset --network-id 0 --destination-network-id 1 --amount 100000000000000000 --token-address 0x0000000000000000000000000000000000000000
-  aggsandbox bridge asset -n 0 -d 1 -a 1500000000000000000 -t 0xA0b86a33E6776e39e6b37ddEC4F25B04Dd9Fc4DC --to-address 0x123...")]
+  aggsandbox bridge asset -n 0 -d 1 -a 1500000000000000000 -t 0xA0b86a33E6776e39e6b37ddEC4F25B04Dd9Fc4DC --to-address 0x123...
+  aggsandbox bridge asset -n 0 -d 1 -a 5000000000000000000 -t 0x5FbDB2315678afecb367f032d93F642f64180aa3 --account alice --faucet-if-needed
+
+With --faucet-if-needed, a missing ETH or sandbox token (AggERC20) balance is
+minted or transferred from the deployer account before bridging.
+
+With --use-permit, an EIP-2612 permit is signed and passed to bridgeAsset as
+permitData instead of sending an approve transaction, so the bridge takes a
+single transaction. The token must support permits, as the bridge's wrapped
+tokens do:
+  aggsandbox bridge asset -n 1 -d 0 -a 1000000000000000000 -t <wrapped token> --use-permit
+
+Omitted --network-id, --destination-network-id and --token-address default to
+the values of the last bridge command run in this directory (stored in
+.aggsandbox/last_used.json):
+  aggsandbox bridge asset -a 2000000000000000000   # Same route and token as last time")]
     Asset {
         /// Source network ID (0=L1, 1=L2, etc.)
-        #[arg(short = 'n', long, help = "Source network ID")]
-        network_id: u64,
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Source network ID (defaults to the last-used source network)")]
+        network_id: Option<u64>,
         /// Destination network ID
-        #[arg(short = 'd', long, help = "Destination network ID")]
-        destination_network_id: u64,
+        #[arg(short = 'd', long, value_parser = parse_network_id, help = "Destination network ID (defaults to the last-used destination network)")]
+        destination_network_id: Option<u64>,
         /// Amount to bridge (in wei)
-        #[arg(short, long, help = "Amount to bridge (in wei)")]
+        #[arg(short, long, value_parser = parse_uint256, help = "Amount to bridge (in wei, decimal or 0x hex)")]
         amount: String,
         /// Token contract address (use 0x0000000000000000000000000000000000000000 for ETH)
-        #[arg(short, long, help = "Token contract address")]
-        token_address: String,
+        #[arg(
+            short,
+            long,
+            help = "Token contract address (defaults to the last-used token)"
+        )]
+        token_address: Option<String>,
         /// Recipient address (defaults to sender if not specified)
         #[arg(long, help = "Recipient address on destination network")]
         to_address: Option<String>,
         /// Gas limit override
-        #[arg(long, help = "Gas limit for the transaction")]
+        #[arg(long, value_parser = parse_int::<u64>, help = "Gas limit for the transaction")]
         gas_limit: Option<u64>,
         /// Gas price override (in wei)
-        #[arg(long, help = "Gas price in wei")]
+        #[arg(long, value_parser = parse_uint256, conflicts_with_all = ["max_fee", "priority_fee"], help = "Gas price in wei")]
         gas_price: Option<String>,
+        /// EIP-1559 max fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max fee per gas in wei (estimated from the fee history if omitted)")]
+        max_fee: Option<String>,
+        /// EIP-1559 max priority fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max priority fee per gas in wei (estimated from the fee history if omitted)")]
+        priority_fee: Option<String>,
         /// Private key to use for the transaction (hex string with 0x prefix)
         #[arg(long, help = "Private key to use for the transaction")]
         private_key: Option<String>,
+        /// Fund a missing balance from the deployer account before bridging
+        #[arg(
+            long,
+            help = "Mint or transfer any missing ETH/sandbox token balance from the deployer first"
+        )]
+        faucet_if_needed: bool,
+        /// Sign an EIP-2612 permit instead of sending an approve transaction
+        #[arg(
+            long,
+            help = "Sign an EIP-2612 permit for the bridge instead of sending an approve transaction"
+        )]
+        use_permit: bool,
     },
     /// 📥 Claim bridged assets on destination network
     #[command(long_about = "Claim assets that were bridged from another network.
@@ -131,28 +229,56 @@ Use aggsandbox show bridges --network-id X to see all bridges and their deposit
 
 For BridgeExtension message claims, use --data to provide custom metadata.
 
+Before a bridge-and-call message is claimed, the claim is traced with
+debug_traceCall to check the call it runs. If the call would revert, its tokens
+would go to the fallback address while the claim succeeds; the reason is
+printed and the claim is not sent unless --allow-fallback is given.
+
+With --verify-proof the exit roots are recomputed from the SMT proof and the
+resulting global exit root is checked on the destination network first, so a
+stale proof fails before any gas is spent.
+
+With --auto-wait the bridge service is polled (every --interval seconds, for at
+most --timeout seconds) until the deposit is indexed and its L1 info tree index
+and claim proof are available, and the claim is sent as soon as they are.
+
+Omitted --network-id and --source-network-id default to the last-used
+destination and source networks of this directory.
+
+With --from-payload the claim is built from a file written by
+`bridge utils build-payload --export`, without querying the bridge service.
+Add --unsigned to print the filled, unsigned raw transaction and its signing
+hash for an external signer instead of sending it (--from sets the sender).
+
 Examples:
   aggsandbox bridge claim --network-id 1 --tx-hash 0xabc123... --source-network-id 0
+  aggsandbox bridge claim -n 1 -t 0xabc123... -s 0 --verify-proof   # Check the proof before claiming
+  aggsandbox bridge claim -n 1 -t 0xabc123... -s 0 --auto-wait --timeout 120   # Claim once the proof is ready
+  aggsandbox bridge claim --from-payload claim.json   # Claim an exported payload
+  aggsandbox bridge claim --from-payload claim.json --unsigned --from 0xf39F...   # Unsigned raw transaction
   aggsandbox bridge claim -n 1 -t 0xdef456... -s 0 --deposit-count 5   # Claim bridge #5 globally
-  aggsandbox bridge claim -n 1 -t 0xdef456... -s 0 --deposit-count 6 --data 0x123...  # Claim bridge #6 with data")]
+  aggsandbox bridge claim -n 1 -t 0xdef456... -s 0 --deposit-count 6 --data 0x123...  # Claim bridge #6 with data
+  aggsandbox bridge claim -n 1 -t 0xabc123... -s 0 --source local   # Find the deposit in the local index")]
     Claim {
         /// Network to claim assets on
-        #[arg(short = 'n', long, help = "Network ID to claim assets on")]
-        network_id: u64,
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID to claim assets on (defaults to the last-used destination network)")]
+        network_id: Option<u64>,
         /// Original bridge transaction hash
         #[arg(
             short,
             long,
+            required_unless_present = "from_payload",
             help = "Transaction hash of the original bridge operation"
         )]
-        tx_hash: String,
+        tx_hash: Option<String>,
         /// Source network of the original bridge
-        #[arg(short = 's', long, help = "Source network ID of original bridge")]
-        source_network_id: u64,
+        #[arg(short = 's', long, value_parser = parse_network_id, help = "Source network ID of original bridge (defaults to the last-used source network)")]
+        source_network_id: Option<u64>,
         /// Global deposit counter for the specific bridge (0=first bridge ever, 1=second bridge ever, etc.)
         #[arg(
             short = 'c',
             long,
+            value_parser = parse_int::<u64>,
             help = "Global deposit counter for the specific bridge (0=first bridge ever, 1=second bridge ever, etc.)"
         )]
         deposit_count: Option<u64>,
@@ -163,11 +289,17 @@ Examples:
         )]
         token_address: Option<String>,
         /// Gas limit override
-        #[arg(long, help = "Gas limit for the transaction")]
+        #[arg(long, value_parser = parse_int::<u64>, help = "Gas limit for the transaction")]
         gas_limit: Option<u64>,
         /// Gas price override (in wei)
-        #[arg(long, help = "Gas price in wei")]
+        #[arg(long, value_parser = parse_uint256, conflicts_with_all = ["max_fee", "priority_fee"], help = "Gas price in wei")]
         gas_price: Option<String>,
+        /// EIP-1559 max fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max fee per gas in wei (estimated from the fee history if omitted)")]
+        max_fee: Option<String>,
+        /// EIP-1559 max priority fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max priority fee per gas in wei (estimated from the fee history if omitted)")]
+        priority_fee: Option<String>,
         /// Private key to use for the transaction (hex string with 0x prefix)
         #[arg(long, help = "Private key to use for the transaction")]
         private_key: Option<String>,
@@ -180,9 +312,339 @@ Examples:
         /// ETH value to send with message bridge claim (in wei)
         #[arg(
             long,
+            value_parser = parse_uint256,
             help = "ETH value to send with contract call for message bridge claims (in wei)"
         )]
         msg_value: Option<String>,
+        /// Where the bridge transaction is looked up
+        #[arg(
+            long,
+            value_enum,
+            default_value = "api",
+            help = "Look the bridge transaction up in the bridge service (api) or the local index (local)"
+        )]
+        source: local_index::DataSource,
+        /// Recompute the proof and check it against the on-chain exit roots before claiming
+        #[arg(
+            long,
+            help = "Verify the claim proof against the on-chain exit roots before sending the claim"
+        )]
+        verify_proof: bool,
+        /// Poll until the claim proof is available before claiming
+        #[arg(
+            long,
+            help = "Wait for the deposit to be indexed and its claim proof to be available, then claim"
+        )]
+        auto_wait: bool,
+        /// Maximum time to wait for the claim proof (seconds)
+        #[arg(
+            long,
+            default_value = "300",
+            requires = "auto_wait",
+            help = "Seconds to wait for the claim proof with --auto-wait"
+        )]
+        timeout: u64,
+        /// Polling interval while waiting for the claim proof (seconds)
+        #[arg(
+            long,
+            default_value = "3",
+            requires = "auto_wait",
+            help = "Seconds between bridge service polls with --auto-wait"
+        )]
+        interval: u64,
+        /// Claim payload exported with `bridge utils build-payload --export`
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["tx_hash", "source_network_id", "deposit_count", "token_address", "data", "verify_proof", "auto_wait"],
+            help = "Build the claim from an exported payload file instead of querying the bridge service"
+        )]
+        from_payload: Option<PathBuf>,
+        /// Print the unsigned raw transaction instead of sending the claim
+        #[arg(
+            long,
+            requires = "from_payload",
+            help = "Print the unsigned raw claim transaction for external signing instead of sending it"
+        )]
+        unsigned: bool,
+        /// Sender of the unsigned transaction
+        #[arg(
+            long,
+            requires = "unsigned",
+            help = "Sender address of the unsigned transaction (defaults to the signing account)"
+        )]
+        from: Option<String>,
+        /// Claim bridge-and-call messages whose call would revert
+        #[arg(
+            long,
+            help = "Claim a bridge-and-call message even if the pre-flight check finds its call would revert and pay the fallback address"
+        )]
+        allow_fallback: bool,
+    },
+    /// 🔗 Claim both deposits of a bridge-and-call transaction in order
+    #[command(
+        long_about = "Claim the asset and the message deposit of a bridge-and-call transaction.
+
+bridgeAndCall makes two deposits in one transaction: the tokens, paid to the
+JumpPoint address, and a message whose claim deploys the JumpPoint and runs
+the call with them. The message has to be claimed after the asset claim is
+mined, or the call runs without its tokens. This command finds both deposits
+of the transaction, claims the asset, waits until the destination bridge
+reports it claimed and then claims the message with its bridged metadata.
+
+Deposits that are already claimed are skipped, so re-running the command
+after a failure picks up where it stopped.
+
+Examples:
+  aggsandbox bridge claim-bundle --network-id 1 --tx-hash 0xabc123... --source-network-id 0
+  aggsandbox bridge claim-bundle -n 1 -t 0xabc123... -s 0 --auto-wait   # Claim once the proofs are ready
+  aggsandbox bridge claim-bundle -n 1 -t 0xabc123... -s 0 --msg-value 1000   # ETH for the call"
+    )]
+    ClaimBundle {
+        /// Network to claim on
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID to claim on (defaults to the last-used destination network)")]
+        network_id: Option<u64>,
+        /// Bridge-and-call transaction hash
+        #[arg(
+            short,
+            long,
+            help = "Transaction hash of the bridge-and-call operation"
+        )]
+        tx_hash: String,
+        /// Source network of the bridge-and-call
+        #[arg(short = 's', long, value_parser = parse_network_id, help = "Source network ID of the bridge-and-call (defaults to the last-used source network)")]
+        source_network_id: Option<u64>,
+        /// Gas limit override
+        #[arg(long, value_parser = parse_int::<u64>, help = "Gas limit for each claim transaction")]
+        gas_limit: Option<u64>,
+        /// Gas price override (in wei)
+        #[arg(long, value_parser = parse_uint256, conflicts_with_all = ["max_fee", "priority_fee"], help = "Gas price in wei")]
+        gas_price: Option<String>,
+        /// EIP-1559 max fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max fee per gas in wei (estimated from the fee history if omitted)")]
+        max_fee: Option<String>,
+        /// EIP-1559 max priority fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max priority fee per gas in wei (estimated from the fee history if omitted)")]
+        priority_fee: Option<String>,
+        /// Private key to use for the transactions (hex string with 0x prefix)
+        #[arg(long, help = "Private key to use for the transactions")]
+        private_key: Option<String>,
+        /// ETH value to send with the message claim (in wei)
+        #[arg(
+            long,
+            value_parser = parse_uint256,
+            help = "ETH value to send with the message claim (in wei)"
+        )]
+        msg_value: Option<String>,
+        /// Where the bridge-and-call transaction is looked up
+        #[arg(
+            long,
+            value_enum,
+            default_value = "api",
+            help = "Look the deposits up in the bridge service (api) or the local index (local)"
+        )]
+        source: local_index::DataSource,
+        /// Poll until the claim proofs are available before claiming
+        #[arg(
+            long,
+            help = "Wait for the deposits to be indexed and their claim proofs to be available, then claim"
+        )]
+        auto_wait: bool,
+        /// Maximum time to wait for the claim proofs (seconds)
+        #[arg(
+            long,
+            default_value = "300",
+            requires = "auto_wait",
+            help = "Seconds to wait for the claim proofs with --auto-wait"
+        )]
+        timeout: u64,
+        /// Polling interval while waiting for the claim proofs (seconds)
+        #[arg(
+            long,
+            default_value = "3",
+            requires = "auto_wait",
+            help = "Seconds between bridge service polls with --auto-wait"
+        )]
+        interval: u64,
+        /// Claim the message even if its call would revert
+        #[arg(
+            long,
+            help = "Claim the message even if the pre-flight check finds its call would revert and pay the fallback address"
+        )]
+        allow_fallback: bool,
+    },
+    /// 📥 Claim every unclaimed deposit destined for a network
+    #[command(
+        long_about = "Claim all pending deposits destined for a network in one go.
+
+Queries the bridge service for deposits targeting the network, skips those
+already claimed on-chain (isClaimed on the destination bridge contract) and
+claims the rest in deposit_count order. A summary table reports the result of
+every claim; the command fails if any claim fails.
+
+With --parallel the claims are submitted concurrently. Claims signed by the
+same account can then race for nonces; re-run claim-all to retry failures, as
+deposits claimed in the meantime are skipped.
+
+With a claim gas budget (--budget or CLAIM_GAS_BUDGET_ETH) the gas the signer
+has already paid for claims on the network is checked first: a warning is
+printed at each threshold in CLAIM_GAS_BUDGET_WARN_AT (default 50,80 percent)
+and claiming stops once the budget is exhausted.
+
+The outcome of every claim is written to a checkpoint file (default:
+.aggsandbox/checkpoints/claim-all-<ID>.json). Ctrl-C stops the run after the
+claim in flight; --resume continues it, skipping the deposits it claimed and
+retrying the failed ones, and reports the totals across all runs.
+
+Examples:
+  aggsandbox bridge claim-all --network-id 1
+  aggsandbox bridge claim-all -n 1 --source-network-id 0
+  aggsandbox bridge claim-all -n 0 --parallel
+  aggsandbox bridge claim-all -n 1 --budget 0.5
+  aggsandbox bridge claim-all -n 1 --resume .aggsandbox/checkpoints/claim-all-1.json"
+    )]
+    ClaimAll {
+        /// Network to claim deposits on
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID to claim deposits on")]
+        network_id: u64,
+        /// Only claim deposits from this source network
+        #[arg(short = 's', long, value_parser = parse_network_id, help = "Only claim deposits from this source network (default: all)")]
+        source_network_id: Option<u64>,
+        /// Submit claims concurrently
+        #[arg(long, help = "Submit claims concurrently instead of sequentially")]
+        parallel: bool,
+        /// Gas limit override
+        #[arg(long, value_parser = parse_int::<u64>, help = "Gas limit for each claim transaction")]
+        gas_limit: Option<u64>,
+        /// Gas price override (in wei)
+        #[arg(long, value_parser = parse_uint256, conflicts_with_all = ["max_fee", "priority_fee"], help = "Gas price in wei")]
+        gas_price: Option<String>,
+        /// EIP-1559 max fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max fee per gas in wei (estimated from the fee history if omitted)")]
+        max_fee: Option<String>,
+        /// EIP-1559 max priority fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max priority fee per gas in wei (estimated from the fee history if omitted)")]
+        priority_fee: Option<String>,
+        /// Private key to use for the transactions (hex string with 0x prefix)
+        #[arg(long, help = "Private key to use for the transactions")]
+        private_key: Option<String>,
+        /// Claim gas budget in ETH
+        #[arg(
+            long,
+            help = "Claim gas budget in ETH (overrides CLAIM_GAS_BUDGET_ETH)"
+        )]
+        budget: Option<String>,
+        /// Checkpoint file to write
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Write the checkpoint to this file (default: .aggsandbox/checkpoints/claim-all-<ID>.json)"
+        )]
+        checkpoint: Option<PathBuf>,
+        /// Checkpoint of an interrupted run to continue
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with = "checkpoint",
+            help = "Continue the run recorded in this checkpoint file"
+        )]
+        resume: Option<PathBuf>,
+    },
+    /// ⛽ Show ETH spent on claim gas per network
+    #[command(long_about = "Sum the gas paid for claim transactions on each network.
+
+Claims are listed by the bridge service and their receipts read from the chain.
+With --sponsor (or CLAIM_SPONSOR_ADDRESS) only claims submitted by that account
+are counted, e.g. the aggkit claim sponsor or the account used by claim-all.
+
+When a budget is configured (--budget or CLAIM_GAS_BUDGET_ETH, per network)
+networks above a warning threshold in CLAIM_GAS_BUDGET_WARN_AT (default 50,80
+percent) are flagged, and the command fails if any budget is exhausted so it
+can be used as a health check for unattended sandboxes.
+
+Examples:
+  aggsandbox bridge claim-budget
+  aggsandbox bridge claim-budget --network-id 1 --sponsor 0x123...
+  aggsandbox bridge claim-budget --budget 0.25 --warn-at 50,75,90")]
+    ClaimBudget {
+        /// Only report this network
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID (default: all networks)")]
+        network_id: Option<u64>,
+        /// Only count claims submitted by this address
+        #[arg(
+            long,
+            help = "Only count claims sent by this address (overrides CLAIM_SPONSOR_ADDRESS)"
+        )]
+        sponsor: Option<String>,
+        /// Claim gas budget in ETH
+        #[arg(
+            long,
+            help = "Claim gas budget in ETH per network (overrides CLAIM_GAS_BUDGET_ETH)"
+        )]
+        budget: Option<String>,
+        /// Warning thresholds in percent
+        #[arg(
+            long,
+            help = "Comma-separated warning thresholds in percent (overrides CLAIM_GAS_BUDGET_WARN_AT)"
+        )]
+        warn_at: Option<String>,
+    },
+    /// 🧙 Interactively bridge assets step by step
+    #[command(
+        long_about = "Bridge assets by answering a few prompts instead of assembling flags.
+
+The wizard asks for the source and destination networks, the token (ETH, the
+deployed AggERC20 or a wrapped token, with your balance of each), the amount in
+whole token units and the recipient. It shows the equivalent `bridge asset`
+command, asks for confirmation, sends the transaction and prints the commands
+to claim it.
+
+Requires an interactive terminal.
+
+Examples:
+  aggsandbox bridge wizard
+  aggsandbox bridge wizard --private-key 0x..."
+    )]
+    Wizard {
+        /// Private key to use for the transactions (hex string with 0x prefix)
+        #[arg(long, help = "Private key to use for the transactions")]
+        private_key: Option<String>,
+    },
+    /// 👀 Watch deposits to a network become claimable and get claimed
+    #[command(
+        long_about = "Poll the bridge service and print each change in the state of deposits
+destined for a network, until interrupted with Ctrl-C.
+
+A deposit is pending once the bridge service indexes it, claimable once its L1
+info tree index is available on the source network, and claimed once a claim
+with its global index lands on the destination network.
+
+With --notify a desktop notification is shown whenever a deposit becomes
+claimable or is claimed, so you can switch tasks while waiting for slow proof
+generation (e.g. in fork mode). With --output json every change is printed as one
+JSON object per line.
+
+Examples:
+  aggsandbox bridge watch --network-id 1
+  aggsandbox bridge watch -n 0 --notify --interval 10"
+    )]
+    Watch {
+        /// Network the deposits are destined for
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Destination network ID to watch")]
+        network_id: u64,
+        /// Polling interval in seconds
+        #[arg(
+            long,
+            default_value = "5",
+            help = "Seconds between polls of the bridge service"
+        )]
+        interval: u64,
+        /// Show desktop notifications
+        #[arg(
+            long,
+            help = "Show a desktop notification when a deposit becomes claimable or is claimed"
+        )]
+        notify: bool,
     },
     /// 📬 Bridge message to destination network
     #[command(
@@ -197,17 +659,20 @@ AggKit to update the Global Exit Root (GER).
 The call data should be hex-encoded data that will be passed to the target address.
 This is useful for sending data or triggering specific actions on the destination chain.
 
+Omitted --network-id and --destination-network-id default to the last-used
+networks of this directory.
+
 Examples:
   aggsandbox bridge message --network-id 0 --destination-network-id 1 --target 0x123... --data 0xabc...
   aggsandbox bridge message -n 0 -d 1 -t 0x456... --data 0xdef... --amount 100000000000000000"
     )]
     Message {
         /// Source network ID
-        #[arg(short = 'n', long, help = "Source network ID")]
-        network_id: u64,
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Source network ID (defaults to the last-used source network)")]
+        network_id: Option<u64>,
         /// Destination network ID
-        #[arg(short = 'd', long, help = "Destination network ID")]
-        destination_network_id: u64,
+        #[arg(short = 'd', long, value_parser = parse_network_id, help = "Destination network ID (defaults to the last-used destination network)")]
+        destination_network_id: Option<u64>,
         /// Target contract address on destination network
         #[arg(short, long, help = "Target contract address")]
         target: String,
@@ -215,17 +680,87 @@ Examples:
         #[arg(long, help = "Contract call data (hex encoded)")]
         data: String,
         /// Amount of ETH to send with the call (in wei)
-        #[arg(short, long, help = "Amount of ETH to send (in wei)")]
+        #[arg(short, long, value_parser = parse_uint256, help = "Amount of ETH to send (in wei, decimal or 0x hex)")]
         amount: Option<String>,
         /// Fallback address if contract call fails
         #[arg(long, help = "Fallback address if call fails")]
         fallback_address: Option<String>,
         /// Gas limit override
-        #[arg(long, help = "Gas limit for the transaction")]
+        #[arg(long, value_parser = parse_int::<u64>, help = "Gas limit for the transaction")]
+        gas_limit: Option<u64>,
+        /// Gas price override (in wei)
+        #[arg(long, value_parser = parse_uint256, conflicts_with_all = ["max_fee", "priority_fee"], help = "Gas price in wei")]
+        gas_price: Option<String>,
+        /// EIP-1559 max fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max fee per gas in wei (estimated from the fee history if omitted)")]
+        max_fee: Option<String>,
+        /// EIP-1559 max priority fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max priority fee per gas in wei (estimated from the fee history if omitted)")]
+        priority_fee: Option<String>,
+        /// Private key to use for the transaction (hex string with 0x prefix)
+        #[arg(long, help = "Private key to use for the transaction")]
+        private_key: Option<String>,
+    },
+    /// ✉️  Bridge a metadata-only message with the native bridgeMessage function
+    #[command(
+        long_about = "Bridge a message directly through the PolygonZkEVMBridge contract.
+
+This calls bridgeMessage(uint32,address,bool,bytes) on the bridge contract without
+going through the BridgeExtension, so the metadata is delivered exactly as given.
+The resulting message leaf (leaf_type: 1) is claimed with claimMessage.
+
+Use --claim to wait for the bridge service to index the message and claim it on
+the destination network in one step.
+
+Examples:
+  aggsandbox bridge bridge-message --network-id 0 --destination-network-id 1 --to-address 0x123... --metadata 0xabc...
+  aggsandbox bridge bridge-message -n 0 -d 1 --to-address 0x123... --claim
+  aggsandbox bridge bridge-message -n 1 -d 0 --to-address 0x123... --metadata 0xdef... --amount 1000000000000000"
+    )]
+    BridgeMessage {
+        /// Source network ID
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Source network ID")]
+        network_id: u64,
+        /// Destination network ID
+        #[arg(short = 'd', long, value_parser = parse_network_id, help = "Destination network ID")]
+        destination_network_id: u64,
+        /// Address receiving the message on the destination network
+        #[arg(long, help = "Destination address that receives the message")]
+        to_address: String,
+        /// Message metadata (hex encoded)
+        #[arg(long, default_value = "0x", help = "Message metadata (hex encoded)")]
+        metadata: String,
+        /// Amount of ETH to send with the message (in wei)
+        #[arg(short, long, value_parser = parse_uint256, help = "Amount of ETH to send (in wei, decimal or 0x hex)")]
+        amount: Option<String>,
+        /// Skip updating the global exit root in the bridge transaction
+        #[arg(long, help = "Do not force a global exit root update")]
+        no_force_update: bool,
+        /// Wait for the message to be indexed and claim it on the destination network
+        #[arg(
+            long,
+            help = "Claim the message on the destination network once indexed"
+        )]
+        claim: bool,
+        /// Maximum time to wait for indexing when claiming (seconds)
+        #[arg(
+            long,
+            default_value = "120",
+            help = "Seconds to wait for the bridge service when using --claim"
+        )]
+        claim_timeout: u64,
+        /// Gas limit override
+        #[arg(long, value_parser = parse_int::<u64>, help = "Gas limit for the transaction")]
         gas_limit: Option<u64>,
         /// Gas price override (in wei)
-        #[arg(long, help = "Gas price in wei")]
+        #[arg(long, value_parser = parse_uint256, conflicts_with_all = ["max_fee", "priority_fee"], help = "Gas price in wei")]
         gas_price: Option<String>,
+        /// EIP-1559 max fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max fee per gas in wei (estimated from the fee history if omitted)")]
+        max_fee: Option<String>,
+        /// EIP-1559 max priority fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max priority fee per gas in wei (estimated from the fee history if omitted)")]
+        priority_fee: Option<String>,
         /// Private key to use for the transaction (hex string with 0x prefix)
         #[arg(long, help = "Private key to use for the transaction")]
         private_key: Option<String>,
@@ -248,41 +783,69 @@ Note: This creates TWO bridge transactions:
 
 Examples:
   aggsandbox bridge bridge-and-call --network-id 0 --destination-network-id 1 --token 0x123... --amount 10 --target 0x456... --data 0xabc... --fallback 0x789...
-  aggsandbox bridge bridge-and-call -n 0 -d 1 -t 0x123... -a 10 --target 0x456... --data 0xdef... --fallback 0x789..."
+  aggsandbox bridge bridge-and-call -n 0 -d 1 -t 0x123... -a 10 --target 0x456... --data 0xdef... --fallback 0x789...
+  aggsandbox bridge bridge-and-call -n 0 -d 1 -t 0x123... -a 10 --target 0x456... --call \"transfer(address,uint256)\" --call-arg 0xabc... --call-arg 10 --fallback 0x789..."
     )]
     BridgeAndCall {
         /// Source network ID (0=L1, 1=L2, etc.)
-        #[arg(short = 'n', long, help = "Source network ID")]
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Source network ID")]
         network_id: u64,
         /// Destination network ID
-        #[arg(short = 'd', long, help = "Destination network ID")]
+        #[arg(short = 'd', long, value_parser = parse_network_id, help = "Destination network ID")]
         destination_network_id: u64,
         /// Token contract address to bridge
         #[arg(short = 't', long, help = "Token contract address")]
         token: String,
         /// Amount to bridge (in wei)
-        #[arg(short, long, help = "Amount to bridge (in wei)")]
+        #[arg(short, long, value_parser = parse_uint256, help = "Amount to bridge (in wei, decimal or 0x hex)")]
         amount: String,
         /// Target contract address on destination network
         #[arg(long, help = "Target contract address for call")]
         target: String,
         /// Call data for the contract (hex encoded)
-        #[arg(long, help = "Contract call data (hex encoded)")]
-        data: String,
+        #[arg(
+            long,
+            required_unless_present = "call",
+            conflicts_with = "call",
+            help = "Contract call data (hex encoded)"
+        )]
+        data: Option<String>,
+        /// Function signature to encode as the call data
+        #[arg(
+            long,
+            value_name = "SIGNATURE",
+            help = "Encode the call data from this function signature, e.g. transfer(address,uint256)"
+        )]
+        call: Option<String>,
+        /// Arguments of the --call function, in order
+        #[arg(
+            long = "call-arg",
+            value_name = "ARG",
+            requires = "call",
+            allow_hyphen_values = true,
+            help = "Argument of the --call function (repeat in order)"
+        )]
+        call_args: Vec<String>,
         /// Fallback address if contract call fails
         #[arg(long, help = "Fallback address if call fails")]
         fallback: String,
         /// Gas limit override
-        #[arg(long, help = "Gas limit for the transaction")]
+        #[arg(long, value_parser = parse_int::<u64>, help = "Gas limit for the transaction")]
         gas_limit: Option<u64>,
         /// Gas price override (in wei)
-        #[arg(long, help = "Gas price in wei")]
+        #[arg(long, value_parser = parse_uint256, conflicts_with_all = ["max_fee", "priority_fee"], help = "Gas price in wei")]
         gas_price: Option<String>,
+        /// EIP-1559 max fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max fee per gas in wei (estimated from the fee history if omitted)")]
+        max_fee: Option<String>,
+        /// EIP-1559 max priority fee per gas (in wei)
+        #[arg(long, value_parser = parse_uint256, help = "EIP-1559 max priority fee per gas in wei (estimated from the fee history if omitted)")]
+        priority_fee: Option<String>,
         /// Private key to use for the transaction (hex string with 0x prefix)
         #[arg(long, help = "Private key to use for the transaction")]
         private_key: Option<String>,
         /// ETH value to send with the contract call on destination network (in wei)
-        #[arg(long, help = "ETH value to send with contract call (in wei)")]
+        #[arg(long, value_parser = parse_uint256, help = "ETH value to send with contract call (in wei)")]
         msg_value: Option<String>,
     },
     /// 🔧 Bridge utility functions
@@ -292,10 +855,43 @@ Examples:
 
 /// Handle bridge commands using direct Rust implementation
 #[allow(clippy::disallowed_methods)] // Allow tracing macros
-pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
-    let config = Config::load()?;
+pub async fn handle_bridge(
+    subcommand: BridgeCommands,
+    account: Option<&str>,
+    signer: SignerKind,
+    hd_path: Option<String>,
+    idempotency_key: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    config.accounts.hardware_signer = signer.hardware_signer(hd_path)?;
+    if let Some(name) = account {
+        if config.accounts.hardware_signer.is_some() {
+            return Err(common::validation_error(
+                "--account selects a local key and cannot be combined with a hardware --signer",
+            ));
+        }
+        crate::keystore::select_account(&mut config, name)?;
+    }
 
-    match subcommand {
+    let idempotent = match idempotency_key {
+        Some(key) => {
+            let command = idempotent_command(&subcommand).ok_or_else(|| {
+                common::validation_error(
+                    "--idempotency-key is supported by asset, claim, message, bridge-message and bridge-and-call",
+                )
+            })?;
+            let store = idempotency::IdempotencyStore::default_location();
+            if let Some(completed) = store.get(key) {
+                return idempotency::replay(key, command, &completed);
+            }
+            idempotency::take_result();
+            Some((key, command, store))
+        }
+        None => None,
+    };
+
+    let result = match subcommand {
         BridgeCommands::Asset {
             network_id,
             destination_network_id,
@@ -304,8 +900,27 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
             to_address,
             gas_limit,
             gas_price,
+            max_fee,
+            priority_fee,
             private_key,
+            faucet_if_needed,
+            use_permit,
         } => {
+            let last_used = LastUsedStore::default_location();
+            let last = last_used.load();
+            let network_id =
+                or_last_used(network_id, last.source_network.as_ref(), "--network-id")?;
+            let destination_network_id = or_last_used(
+                destination_network_id,
+                last.destination_network.as_ref(),
+                "--destination-network-id",
+            )?;
+            let token_address = or_last_used(
+                token_address,
+                last.token_address.as_ref(),
+                "--token-address",
+            )?;
+
             info!(
                 network = network_id,
                 destination_network = destination_network_id,
@@ -314,14 +929,17 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
                 "Executing bridge asset command"
             );
 
-            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref());
+            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref())
+                .with_fees(max_fee.as_deref(), priority_fee.as_deref());
             let mut builder = BridgeAssetArgs::builder()
                 .config(&config)
                 .source_network(network_id)
                 .destination_network(destination_network_id)
                 .amount(&amount)
                 .token_address(&token_address)
-                .gas_options(gas_options);
+                .gas_options(gas_options)
+                .faucet_if_needed(faucet_if_needed)
+                .use_permit(use_permit);
 
             if let Some(addr) = to_address.as_deref() {
                 builder = builder.recipient_address(addr);
@@ -331,7 +949,13 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
             }
 
             let args = builder.build_with_crate_error()?;
-            bridge_asset(args).await
+            bridge_asset(args).await?;
+            last_used.record(LastUsed {
+                source_network: Some(network_id),
+                destination_network: Some(destination_network_id),
+                token_address: Some(token_address),
+            });
+            Ok(())
         }
         BridgeCommands::Claim {
             network_id,
@@ -341,43 +965,266 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
             token_address,
             gas_limit,
             gas_price,
+            max_fee,
+            priority_fee,
             private_key,
             data,
             msg_value,
+            source,
+            verify_proof,
+            auto_wait,
+            timeout,
+            interval,
+            from_payload,
+            unsigned,
+            from,
+            allow_fallback,
         } => {
+            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref())
+                .with_fees(max_fee.as_deref(), priority_fee.as_deref());
+            if let Some(payload_path) = from_payload {
+                info!(
+                    network = ?network_id,
+                    payload = %payload_path.display(),
+                    unsigned = unsigned,
+                    "Executing bridge claim command from payload"
+                );
+                offline_claim::claim_from_payload(offline_claim::PayloadClaimArgs {
+                    config: &config,
+                    network: network_id,
+                    payload_path: &payload_path,
+                    gas_options,
+                    private_key: private_key.as_deref(),
+                    msg_value: msg_value.as_deref(),
+                    unsigned,
+                    from: from.as_deref(),
+                })
+                .await
+            } else {
+                let tx_hash =
+                    tx_hash.ok_or_else(|| common::validation_error("--tx-hash is required"))?;
+
+                let last_used = LastUsedStore::default_location();
+                let last = last_used.load();
+                let network_id = or_last_used(
+                    network_id,
+                    last.destination_network.as_ref(),
+                    "--network-id",
+                )?;
+                let source_network_id = or_last_used(
+                    source_network_id,
+                    last.source_network.as_ref(),
+                    "--source-network-id",
+                )?;
+
+                info!(
+                    network = network_id,
+                    tx_hash = %tx_hash,
+                    source_network = source_network_id,
+                    "Executing bridge claim command"
+                );
+
+                let mut builder = ClaimAssetArgs::builder()
+                    .config(&config)
+                    .network(network_id)
+                    .tx_hash(&tx_hash)
+                    .source_network(source_network_id)
+                    .gas_options(gas_options)
+                    .source(source)
+                    .verify_proof(verify_proof)
+                    .auto_wait(auto_wait.then(|| proof_wait::AutoWait::new(timeout, interval)))
+                    .allow_fallback(allow_fallback);
+
+                if let Some(count) = deposit_count {
+                    builder = builder.deposit_count(Some(count));
+                }
+                if let Some(addr) = token_address.as_deref() {
+                    builder = builder.token_address(Some(addr));
+                }
+                if let Some(key) = private_key.as_deref() {
+                    builder = builder.private_key(key);
+                }
+                if let Some(custom_data) = data.as_deref() {
+                    builder = builder.custom_data(Some(custom_data));
+                }
+                if let Some(value) = msg_value.as_deref() {
+                    builder = builder.msg_value(Some(value));
+                }
+
+                let args = builder.build_with_crate_error()?;
+                claim_asset(args).await?;
+                last_used.record(LastUsed {
+                    source_network: Some(source_network_id),
+                    destination_network: Some(network_id),
+                    token_address: None,
+                });
+                Ok(())
+            }
+        }
+        BridgeCommands::ClaimBundle {
+            network_id,
+            tx_hash,
+            source_network_id,
+            gas_limit,
+            gas_price,
+            max_fee,
+            priority_fee,
+            private_key,
+            msg_value,
+            source,
+            auto_wait,
+            timeout,
+            interval,
+            allow_fallback,
+        } => {
+            let last_used = LastUsedStore::default_location();
+            let last = last_used.load();
+            let network_id = or_last_used(
+                network_id,
+                last.destination_network.as_ref(),
+                "--network-id",
+            )?;
+            let source_network_id = or_last_used(
+                source_network_id,
+                last.source_network.as_ref(),
+                "--source-network-id",
+            )?;
+
             info!(
                 network = network_id,
                 tx_hash = %tx_hash,
                 source_network = source_network_id,
-                "Executing bridge claim command"
+                "Executing bridge claim-bundle command"
             );
 
-            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref());
-            let mut builder = ClaimAssetArgs::builder()
+            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref())
+                .with_fees(max_fee.as_deref(), priority_fee.as_deref());
+            claim_bundle::claim_bundle(claim_bundle::ClaimBundleArgs {
+                config: &config,
+                network: network_id,
+                tx_hash: &tx_hash,
+                source_network: source_network_id,
+                gas_options,
+                private_key: private_key.as_deref(),
+                msg_value: msg_value.as_deref(),
+                source,
+                auto_wait: auto_wait.then(|| proof_wait::AutoWait::new(timeout, interval)),
+                allow_fallback,
+            })
+            .await?;
+            last_used.record(LastUsed {
+                source_network: Some(source_network_id),
+                destination_network: Some(network_id),
+                token_address: None,
+            });
+            Ok(())
+        }
+        BridgeCommands::ClaimAll {
+            network_id,
+            source_network_id,
+            parallel,
+            gas_limit,
+            gas_price,
+            max_fee,
+            priority_fee,
+            private_key,
+            budget,
+            checkpoint,
+            resume,
+        } => {
+            info!(
+                network = network_id,
+                source_network = ?source_network_id,
+                parallel = parallel,
+                resume = ?resume,
+                "Executing bridge claim-all command"
+            );
+
+            let budget = claim_budget::ClaimBudget::resolve(budget.as_deref(), None)?;
+
+            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref())
+                .with_fees(max_fee.as_deref(), priority_fee.as_deref());
+            let mut builder = ClaimAllArgs::builder()
                 .config(&config)
                 .network(network_id)
-                .tx_hash(&tx_hash)
                 .source_network(source_network_id)
-                .gas_options(gas_options);
+                .parallel(parallel)
+                .gas_options(gas_options)
+                .budget(budget)
+                .checkpoint(checkpoint)
+                .resume(resume);
 
-            if let Some(count) = deposit_count {
-                builder = builder.deposit_count(Some(count));
-            }
-            if let Some(addr) = token_address.as_deref() {
-                builder = builder.token_address(Some(addr));
-            }
             if let Some(key) = private_key.as_deref() {
                 builder = builder.private_key(key);
             }
-            if let Some(custom_data) = data.as_deref() {
-                builder = builder.custom_data(Some(custom_data));
+
+            let summary = claim_all(builder.build_with_crate_error()?).await?;
+            if summary.interrupted {
+                return Err(common::validation_error(&format!(
+                    "Interrupted after {} of the pending claim(s); resume with --resume {}",
+                    summary.attempted, summary.checkpoint
+                )));
             }
-            if let Some(value) = msg_value.as_deref() {
-                builder = builder.msg_value(Some(value));
+            if summary.failed > 0 {
+                return Err(common::validation_error(&format!(
+                    "{} of {} claim(s) failed",
+                    summary.failed, summary.attempted
+                )));
+            }
+            Ok(())
+        }
+        BridgeCommands::ClaimBudget {
+            network_id,
+            sponsor,
+            budget,
+            warn_at,
+        } => {
+            info!(network = ?network_id, "Executing bridge claim-budget command");
+
+            let budget = claim_budget::ClaimBudget::resolve(budget.as_deref(), warn_at.as_deref())?;
+            let sponsor = claim_budget::resolve_sponsor(sponsor.as_deref())?;
+            let networks = match network_id {
+                Some(network) => vec![network],
+                None => config.networks.network_ids(),
+            };
+
+            let mut spends = Vec::with_capacity(networks.len());
+            for network in networks {
+                common::validate_network_id(network, "Network")?;
+                spends.push(
+                    claim_budget::get_claim_gas_spend(&config, network, sponsor, budget.as_ref())
+                        .await?,
+                );
             }
+            claim_budget::print_claim_gas_spend(&spends)?;
 
-            let args = builder.build_with_crate_error()?;
-            claim_asset(args).await
+            let exhausted = spends
+                .iter()
+                .filter(|spend| spend.status == claim_budget::BudgetStatus::Exceeded)
+                .count();
+            if exhausted > 0 {
+                return Err(common::validation_error(&format!(
+                    "Claim gas budget exhausted on {exhausted} network(s)"
+                )));
+            }
+            Ok(())
+        }
+        BridgeCommands::Wizard { private_key } => {
+            info!("Executing bridge wizard command");
+            wizard::run_wizard(&config, private_key.as_deref()).await
+        }
+        BridgeCommands::Watch {
+            network_id,
+            interval,
+            notify,
+        } => {
+            info!(
+                network = network_id,
+                interval = interval,
+                notify = notify,
+                "Executing bridge watch command"
+            );
+            watch::watch_deposits(&config, network_id, interval.max(1), notify).await
         }
         BridgeCommands::Message {
             network_id,
@@ -388,8 +1235,20 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
             fallback_address,
             gas_limit,
             gas_price,
+            max_fee,
+            priority_fee,
             private_key,
         } => {
+            let last_used = LastUsedStore::default_location();
+            let last = last_used.load();
+            let network_id =
+                or_last_used(network_id, last.source_network.as_ref(), "--network-id")?;
+            let destination_network_id = or_last_used(
+                destination_network_id,
+                last.destination_network.as_ref(),
+                "--destination-network-id",
+            )?;
+
             info!(
                 network = network_id,
                 destination_network = destination_network_id,
@@ -397,7 +1256,8 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
                 "Executing bridge message command"
             );
 
-            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref());
+            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref())
+                .with_fees(max_fee.as_deref(), priority_fee.as_deref());
             let mut builder = BridgeMessageParams::builder().target(&target).data(&data);
 
             if let Some(amt) = &amount {
@@ -416,7 +1276,59 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
                 gas_options,
                 private_key.as_deref(),
             )
-            .await
+            .await?;
+            last_used.record(LastUsed {
+                source_network: Some(network_id),
+                destination_network: Some(destination_network_id),
+                token_address: None,
+            });
+            Ok(())
+        }
+        BridgeCommands::BridgeMessage {
+            network_id,
+            destination_network_id,
+            to_address,
+            metadata,
+            amount,
+            no_force_update,
+            claim,
+            claim_timeout,
+            gas_limit,
+            gas_price,
+            max_fee,
+            priority_fee,
+            private_key,
+        } => {
+            info!(
+                network = network_id,
+                destination_network = destination_network_id,
+                to_address = %to_address,
+                claim = claim,
+                "Executing native bridge message command"
+            );
+
+            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref())
+                .with_fees(max_fee.as_deref(), priority_fee.as_deref());
+            let mut builder = NativeMessageArgs::builder()
+                .config(&config)
+                .source_network(network_id)
+                .destination_network(destination_network_id)
+                .destination_address(&to_address)
+                .metadata(&metadata)
+                .force_update_ger(!no_force_update)
+                .claim(claim)
+                .claim_timeout(claim_timeout)
+                .gas_options(gas_options);
+
+            if let Some(amt) = amount.as_deref() {
+                builder = builder.amount(amt);
+            }
+            if let Some(key) = private_key.as_deref() {
+                builder = builder.private_key(key);
+            }
+
+            let args = builder.build_with_crate_error()?;
+            bridge_native_message(args).await
         }
         BridgeCommands::BridgeAndCall {
             network_id,
@@ -425,9 +1337,13 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
             amount,
             target,
             data,
+            call,
+            call_args,
             fallback,
             gas_limit,
             gas_price,
+            max_fee,
+            priority_fee,
             private_key,
             msg_value,
         } => {
@@ -440,7 +1356,18 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
                 "Executing bridge and call command"
             );
 
-            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref());
+            let data = match (data, call) {
+                (Some(data), _) => data,
+                (None, Some(signature)) => {
+                    let call = calldata::encode_call(&signature, &call_args)?;
+                    info!(function = %call.signature, "Encoded call data");
+                    call.hex()
+                }
+                (None, None) => return Err(common::validation_error("Pass --data or --call")),
+            };
+
+            let gas_options = GasOptions::new(gas_limit, gas_price.as_deref())
+                .with_fees(max_fee.as_deref(), priority_fee.as_deref());
             let mut builder = BridgeAndCallArgs::builder()
                 .config(&config)
                 .source_network(network_id)
@@ -464,30 +1391,50 @@ pub async fn handle_bridge(subcommand: BridgeCommands) -> Result<()> {
         }
         BridgeCommands::Utils(utility_command) => {
             info!("Executing bridge utility command");
-            handle_utility_command(&config, utility_command).await
+            handle_utility_command(&config, utility_command, output).await
+        }
+    };
+
+    if let (Ok(()), Some((key, command, store))) = (&result, idempotent) {
+        if !common::is_dry_run() {
+            if let Some(completed) = idempotency::take_result() {
+                store.record(
+                    key,
+                    idempotency::CompletedOperation::new(command, completed),
+                )?;
+            }
         }
     }
+    result
+}
+
+/// Subcommand name recorded under an idempotency key, for operations that send funds or claims
+fn idempotent_command(subcommand: &BridgeCommands) -> Option<&'static str> {
+    match subcommand {
+        BridgeCommands::Asset { .. } => Some("asset"),
+        BridgeCommands::Claim { .. } => Some("claim"),
+        BridgeCommands::Message { .. } => Some("message"),
+        BridgeCommands::BridgeMessage { .. } => Some("bridge-message"),
+        BridgeCommands::BridgeAndCall { .. } => Some("bridge-and-call"),
+        _ => None,
+    }
+}
+
+/// Chain configuration of a network
+fn network_chain(config: &Config, network_id: u64) -> Result<&ChainConfig> {
+    config.networks.chain(network_id).ok_or_else(|| {
+        crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
+            &format!(
+                "Network {network_id} is not configured (set RPC_{} or add it to networks.extra_l2s)",
+                network_id + 1
+            ),
+        ))
+    })
 }
 
 /// Get provider for a network
 pub async fn get_provider(config: &Config, network_id: u64) -> Result<Arc<Provider<Http>>> {
-    let rpc_url = match network_id {
-        0 => config.networks.l1.rpc_url.as_str(),
-        1 => config.networks.l2.rpc_url.as_str(),
-        2 => config
-            .networks
-            .l3
-            .as_ref()
-            .map(|l3| l3.rpc_url.as_str())
-            .unwrap_or(""),
-        _ => {
-            return Err(crate::error::AggSandboxError::Config(
-                crate::error::ConfigError::validation_failed(&format!(
-                    "Unsupported network ID: {network_id}"
-                )),
-            ))
-        }
-    };
+    let rpc_url = network_chain(config, network_id)?.rpc_url.as_str();
 
     let provider = Provider::<Http>::try_from(rpc_url).map_err(|e| {
         crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
@@ -498,17 +1445,32 @@ pub async fn get_provider(config: &Config, network_id: u64) -> Result<Arc<Provid
     Ok(Arc::new(provider))
 }
 
+/// Signing client of a network, assigning nonces through [`nonce`]'s nonce manager
+pub type SandboxClient =
+    NonceManagerMiddleware<SignerMiddleware<Arc<Provider<Http>>, SandboxSigner>>;
+
 /// Get wallet with provider for a network
 pub async fn get_wallet_with_provider(
     config: &Config,
     network_id: u64,
     private_key: Option<&str>,
-) -> Result<SignerMiddleware<Arc<Provider<Http>>, LocalWallet>> {
+) -> Result<SandboxClient> {
     let provider = get_provider(config, network_id).await?;
 
-    // Use provided private key or default to first one from config
+    let chain_id = network_chain(config, network_id)?.chain_id.as_u64()?;
+
+    // A hardware wallet selected with --signer signs unless a key is passed explicitly
+    if let (None, Some(hardware)) = (private_key, &config.accounts.hardware_signer) {
+        let signer = signer::connect_hardware(hardware, chain_id).await?;
+        return nonce::with_nonce_manager(SignerMiddleware::new(provider, signer), network_id)
+            .await;
+    }
+
+    // Use provided private key, then the account selected with --account, then the first one from config
     let private_key_str = if let Some(pk) = private_key {
         pk
+    } else if let Some(selected) = config.accounts.selected_key.as_deref() {
+        selected
     } else {
         config.accounts.private_keys.first().ok_or_else(|| {
             crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(
@@ -523,51 +1485,20 @@ pub async fn get_wallet_with_provider(
         ))
     })?;
 
-    let chain_id = match network_id {
-        0 => config.networks.l1.chain_id.as_u64()?,
-        1 => config.networks.l2.chain_id.as_u64()?,
-        2 => config
-            .networks
-            .l3
-            .as_ref()
-            .map(|l3| l3.chain_id.as_u64())
-            .transpose()?
-            .unwrap_or(137),
-        _ => {
-            return Err(crate::error::AggSandboxError::Config(
-                crate::error::ConfigError::validation_failed(&format!(
-                    "Unsupported network ID: {network_id}"
-                )),
-            ))
-        }
-    };
-
-    let wallet_with_chain = wallet.with_chain_id(chain_id);
+    let wallet_with_chain = SandboxSigner::Local(wallet.with_chain_id(chain_id));
     let client = SignerMiddleware::new(provider, wallet_with_chain);
 
-    Ok(client)
+    nonce::with_nonce_manager(client, network_id).await
 }
 
 /// Get bridge contract address for a network
 pub fn get_bridge_contract_address(config: &Config, network_id: u64) -> Result<Address> {
-    let layer = match network_id {
-        0 => "l1",
-        1 => "l2",
-        2 => "l3",
-        _ => {
-            return Err(crate::error::AggSandboxError::Config(
-                crate::error::ConfigError::validation_failed(&format!(
-                    "Unsupported network ID: {network_id}"
-                )),
-            ))
-        }
-    };
-
-    let address_str = config.contracts.get_contract(layer, "PolygonZkEVMBridge");
+    let layer = faucet::layer_for_network(network_id)?;
+    let address_str = config.contracts.get_contract(&layer, "PolygonZkEVMBridge");
     if address_str == "Not deployed" {
         return Err(crate::error::AggSandboxError::Config(
             crate::error::ConfigError::validation_failed(&format!(
-                "Bridge contract not deployed on network {network_id}"
+                "Bridge contract not deployed on network {network_id}, run `aggsandbox contracts refresh` to discover it"
             )),
         ));
     }
@@ -581,24 +1512,12 @@ pub fn get_bridge_contract_address(config: &Config, network_id: u64) -> Result<A
 
 /// Get bridge extension contract address for a network
 pub fn get_bridge_extension_address(config: &Config, network_id: u64) -> Result<Address> {
-    let layer = match network_id {
-        0 => "l1",
-        1 => "l2",
-        2 => "l3",
-        _ => {
-            return Err(crate::error::AggSandboxError::Config(
-                crate::error::ConfigError::validation_failed(&format!(
-                    "Unsupported network ID: {network_id}"
-                )),
-            ))
-        }
-    };
-
-    let address_str = config.contracts.get_contract(layer, "BridgeExtension");
+    let layer = faucet::layer_for_network(network_id)?;
+    let address_str = config.contracts.get_contract(&layer, "BridgeExtension");
     if address_str == "Not deployed" {
         return Err(crate::error::AggSandboxError::Config(
             crate::error::ConfigError::validation_failed(&format!(
-                "Bridge extension contract not deployed on network {network_id}"
+                "Bridge extension contract not deployed on network {network_id}, run `aggsandbox contracts refresh` to discover it"
             )),
         ));
     }
@@ -0,0 +1,453 @@
+//! Native message bridging through `PolygonZkEVMBridge.bridgeMessage`
+//!
+//! Unlike `bridge bridge-and-call`, this path never touches the BridgeExtension
+//! contract. The metadata bytes are delivered as-is and the resulting leaf
+//! (leaf_type = 1) is claimed with `claimMessage` on the destination network.
+
+use crate::api_client::OptimizedApiClient;
+use crate::config::Config;
+use crate::error::{ApiError, Result};
+use crate::ui;
+use ethers::prelude::*;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::claim_asset::{claim_asset, ClaimAssetArgs};
+use super::common::{
+    contract::get_bridge_contract, rpc_error, send_or_simulate, validate_network_id,
+    validation_error, warn_if_large_metadata,
+};
+use super::receipt;
+use super::timing::{Stage, StageTimer};
+use super::GasOptions;
+
+/// Seconds to wait between polls while waiting for the bridge service to index the deposit
+const INDEX_POLL_INTERVAL_SECS: u64 = 2;
+
+/// Seconds to wait for AggKit to propagate the Global Exit Root before claiming
+const GER_UPDATE_DELAY_SECS: u64 = 5;
+
+/// Arguments for native bridgeMessage operations
+///
+/// Use the builder pattern to construct this struct:
+/// ```no_run
+/// # use aggsandbox::commands::bridge::NativeMessageArgs;
+/// # use aggsandbox::config::Config;
+/// # fn main() -> aggsandbox::error::Result<()> {
+/// # let config = Config::load()?;
+/// let args = NativeMessageArgs::builder()
+///     .config(&config)
+///     .source_network(0)
+///     .destination_network(1)
+///     .destination_address("0x1234567890123456789012345678901234567890")
+///     .metadata("0xdeadbeef")
+///     .claim(true)
+///     .build_with_crate_error()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct NativeMessageArgs<'a> {
+    pub config: &'a Config,
+    pub source_network: u64,
+    pub destination_network: u64,
+    pub destination_address: &'a str,
+    pub metadata: &'a str,
+    pub amount: Option<&'a str>,
+    pub force_update_ger: bool,
+    pub claim: bool,
+    pub claim_timeout: u64,
+    pub gas_options: GasOptions,
+    pub private_key: Option<&'a str>,
+}
+
+impl<'a> NativeMessageArgs<'a> {
+    /// Create a new builder instance
+    pub fn builder() -> NativeMessageArgsBuilder<'a> {
+        NativeMessageArgsBuilder::default()
+    }
+}
+
+/// Builder for constructing NativeMessageArgs with validation
+pub struct NativeMessageArgsBuilder<'a> {
+    config: Option<&'a Config>,
+    source_network: Option<u64>,
+    destination_network: Option<u64>,
+    destination_address: Option<&'a str>,
+    metadata: &'a str,
+    amount: Option<&'a str>,
+    force_update_ger: bool,
+    claim: bool,
+    claim_timeout: u64,
+    gas_options: GasOptions,
+    private_key: Option<&'a str>,
+}
+
+impl<'a> Default for NativeMessageArgsBuilder<'a> {
+    fn default() -> Self {
+        Self {
+            config: None,
+            source_network: None,
+            destination_network: None,
+            destination_address: None,
+            metadata: "0x",
+            amount: None,
+            force_update_ger: true,
+            claim: false,
+            claim_timeout: 120,
+            gas_options: GasOptions::new(None, None),
+            private_key: None,
+        }
+    }
+}
+
+impl<'a> NativeMessageArgsBuilder<'a> {
+    /// Set the configuration
+    pub fn config(mut self, config: &'a Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Set the source network ID
+    pub fn source_network(mut self, source_network: u64) -> Self {
+        self.source_network = Some(source_network);
+        self
+    }
+
+    /// Set the destination network ID
+    pub fn destination_network(mut self, destination_network: u64) -> Self {
+        self.destination_network = Some(destination_network);
+        self
+    }
+
+    /// Set the address that receives the message on the destination network
+    pub fn destination_address(mut self, destination_address: &'a str) -> Self {
+        self.destination_address = Some(destination_address);
+        self
+    }
+
+    /// Set the message metadata (hex encoded, defaults to empty)
+    pub fn metadata(mut self, metadata: &'a str) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Set the ETH amount to send with the message in wei (optional)
+    pub fn amount(mut self, amount: &'a str) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Set whether the global exit root is updated in the same transaction
+    pub fn force_update_ger(mut self, force_update_ger: bool) -> Self {
+        self.force_update_ger = force_update_ger;
+        self
+    }
+
+    /// Claim the message on the destination network once it is claimable
+    pub fn claim(mut self, claim: bool) -> Self {
+        self.claim = claim;
+        self
+    }
+
+    /// Set how long to wait for the message to become claimable (seconds)
+    pub fn claim_timeout(mut self, claim_timeout: u64) -> Self {
+        self.claim_timeout = claim_timeout;
+        self
+    }
+
+    /// Set gas options
+    pub fn gas_options(mut self, gas_options: GasOptions) -> Self {
+        self.gas_options = gas_options;
+        self
+    }
+
+    /// Set private key for signing transactions
+    pub fn private_key(mut self, private_key: &'a str) -> Self {
+        self.private_key = Some(private_key);
+        self
+    }
+
+    /// Build the NativeMessageArgs with validation
+    pub fn build(self) -> std::result::Result<NativeMessageArgs<'a>, &'static str> {
+        let config = self.config.ok_or("Config is required")?;
+        let source_network = self.source_network.ok_or("Source network is required")?;
+        let destination_network = self
+            .destination_network
+            .ok_or("Destination network is required")?;
+        let destination_address = self
+            .destination_address
+            .ok_or("Destination address is required")?;
+
+        if source_network == destination_network {
+            return Err("Source and destination networks must be different");
+        }
+
+        if Address::from_str(destination_address).is_err() {
+            return Err("Invalid destination address format");
+        }
+
+        if hex::decode(self.metadata.trim_start_matches("0x")).is_err() {
+            return Err("Invalid metadata hex format");
+        }
+
+        if let Some(amount) = self.amount {
+            if U256::from_dec_str(amount).is_err() {
+                return Err("Invalid amount format");
+            }
+        }
+
+        Ok(NativeMessageArgs {
+            config,
+            source_network,
+            destination_network,
+            destination_address,
+            metadata: self.metadata,
+            amount: self.amount,
+            force_update_ger: self.force_update_ger,
+            claim: self.claim,
+            claim_timeout: self.claim_timeout,
+            gas_options: self.gas_options,
+            private_key: self.private_key,
+        })
+    }
+
+    /// Build and convert to crate's Result type
+    pub fn build_with_crate_error(self) -> Result<NativeMessageArgs<'a>> {
+        self.build().map_err(|e| {
+            crate::error::AggSandboxError::Config(crate::error::ConfigError::validation_failed(e))
+        })
+    }
+}
+
+/// Bridge a message with the native bridgeMessage function and optionally claim it
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn bridge_native_message(args: NativeMessageArgs<'_>) -> Result<()> {
+    let mut timer = StageTimer::start();
+    validate_network_id(args.destination_network, "Destination network")?;
+    let bridge = get_bridge_contract(args.config, args.source_network, args.private_key).await?;
+
+    let destination_addr = Address::from_str(args.destination_address)
+        .map_err(|e| validation_error(&format!("Invalid destination address: {e}")))?;
+    let metadata = hex::decode(args.metadata.trim_start_matches("0x"))
+        .map_err(|e| validation_error(&format!("Invalid metadata hex: {e}")))?;
+    warn_if_large_metadata(&metadata);
+    let amount_wei = match args.amount {
+        Some(amount) => U256::from_dec_str(amount)
+            .map_err(|e| validation_error(&format!("Invalid amount: {e}")))?,
+        None => U256::zero(),
+    };
+
+    info!(
+        source_network = args.source_network,
+        destination_network = args.destination_network,
+        destination_address = %args.destination_address,
+        metadata_len = metadata.len(),
+        "Bridging native message"
+    );
+
+    let mut call = bridge.bridge_message(
+        args.destination_network as u32,
+        destination_addr,
+        args.force_update_ger,
+        metadata.into(),
+    );
+
+    if !amount_wei.is_zero() {
+        call = call.value(amount_wei);
+    }
+
+    let call = args
+        .gas_options
+        .apply_to_call_with_return(call, bridge.client_ref())
+        .await?;
+    let Some(pending_tx) = timer
+        .time(Stage::TxSubmission, send_or_simulate(&call))
+        .await
+        .map_err(|e| {
+            rpc_error(
+                "send bridge message transaction",
+                receipt::describe_error(&e),
+            )
+        })?
+    else {
+        return Ok(());
+    };
+    let tx_hash = pending_tx.tx_hash();
+
+    ui::ui().success(&format!(
+        "Bridge message transaction submitted: {tx_hash:#x}"
+    ));
+
+    let tx_hash_str = format!("{tx_hash:#x}");
+    if !args.claim {
+        timer
+            .time(
+                Stage::MiningWait,
+                receipt::wait_for_receipt(
+                    bridge.client().as_ref(),
+                    tx_hash,
+                    "Bridge message transaction",
+                ),
+            )
+            .await?;
+        ui::ui().tip(&format!(
+            "Use `aggsandbox bridge claim --network-id {} --tx-hash {tx_hash_str} --source-network-id {}` to claim message",
+            args.destination_network, args.source_network
+        ));
+        ui::ui().warning("Wait at least 5 seconds after bridging before claiming to allow AggKit to update the Global Exit Root (GER)");
+        timer.report("bridge message", bridge_details(&args, &tx_hash_str, None));
+        return Ok(());
+    }
+
+    let mined = timer
+        .time(Stage::MiningWait, pending_tx)
+        .await
+        .map_err(|e| rpc_error("wait for the bridge message transaction", e))?
+        .ok_or_else(|| {
+            rpc_error(
+                "wait for the bridge message transaction",
+                "dropped from the mempool",
+            )
+        })?;
+    receipt::ensure_success(
+        bridge.client().as_ref(),
+        &mined,
+        "Bridge message transaction",
+    )
+    .await?;
+
+    let deposit_count = timer
+        .time(
+            Stage::ApiLookup,
+            wait_for_indexed_deposit(
+                args.config,
+                args.source_network,
+                &tx_hash_str,
+                args.claim_timeout,
+            ),
+        )
+        .await?;
+    ui::ui().info(&format!(
+        "📇 Message indexed by bridge service (deposit_count: {deposit_count})"
+    ));
+
+    ui::ui().info(&format!(
+        "⏳ Waiting {GER_UPDATE_DELAY_SECS}s for AggKit to update the Global Exit Root..."
+    ));
+    tokio::time::sleep(Duration::from_secs(GER_UPDATE_DELAY_SECS)).await;
+    OptimizedApiClient::global()
+        .invalidate("bridges", Some(args.source_network))
+        .await;
+    timer.report(
+        "bridge message",
+        bridge_details(&args, &tx_hash_str, Some(deposit_count)),
+    );
+
+    let mut builder = ClaimAssetArgs::builder()
+        .config(args.config)
+        .network(args.destination_network)
+        .tx_hash(&tx_hash_str)
+        .source_network(args.source_network)
+        .deposit_count(Some(deposit_count))
+        .gas_options(args.gas_options.clone());
+    if let Some(key) = args.private_key {
+        builder = builder.private_key(key);
+    }
+
+    debug!(deposit_count = deposit_count, "Claiming native message");
+    claim_asset(builder.build_with_crate_error()?).await
+}
+
+/// Fields of the timing report of a bridged message
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn bridge_details(
+    args: &NativeMessageArgs<'_>,
+    tx_hash: &str,
+    deposit_count: Option<u64>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "tx_hash": tx_hash,
+        "source_network": args.source_network,
+        "destination_network": args.destination_network,
+        "deposit_count": deposit_count,
+    })
+}
+
+/// Poll the bridge service until the deposit created by `tx_hash` is indexed
+async fn wait_for_indexed_deposit(
+    config: &Config,
+    source_network: u64,
+    tx_hash: &str,
+    timeout_secs: u64,
+) -> Result<u64> {
+    let api_client = OptimizedApiClient::global();
+    let deadline = std::time::Instant::now() + Duration::from_secs(timeout_secs);
+
+    loop {
+        api_client.invalidate("bridges", Some(source_network)).await;
+        if let Ok(response) = api_client.get_bridges(config, source_network).await {
+            let deposit_count = response["bridges"].as_array().and_then(|bridges| {
+                bridges
+                    .iter()
+                    .find(|bridge| {
+                        bridge["bridge_tx_hash"].as_str() == Some(tx_hash)
+                            && bridge["leaf_type"].as_u64() == Some(1)
+                    })
+                    .and_then(|bridge| bridge["deposit_count"].as_u64())
+            });
+            if let Some(deposit_count) = deposit_count {
+                return Ok(deposit_count);
+            }
+        }
+
+        if std::time::Instant::now() >= deadline {
+            return Err(ApiError::network_error(&format!(
+                "Timed out after {timeout_secs}s waiting for the bridge service to index {tx_hash}"
+            ))
+            .into());
+        }
+        tokio::time::sleep(Duration::from_secs(INDEX_POLL_INTERVAL_SECS)).await;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    const RECIPIENT: &str = "0x1234567890123456789012345678901234567890";
+
+    #[test]
+    fn test_builder_defaults_to_empty_metadata() {
+        let config = Config::default();
+        let args = NativeMessageArgs::builder()
+            .config(&config)
+            .source_network(0)
+            .destination_network(1)
+            .destination_address(RECIPIENT)
+            .build()
+            .unwrap();
+
+        assert_eq!(args.metadata, "0x");
+        assert!(args.force_update_ger);
+        assert!(!args.claim);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_input() {
+        let config = Config::default();
+        let base = || {
+            NativeMessageArgs::builder()
+                .config(&config)
+                .source_network(0)
+                .destination_network(1)
+                .destination_address(RECIPIENT)
+        };
+
+        assert!(base().metadata("0xzz").build().is_err());
+        assert!(base().amount("not-a-number").build().is_err());
+        assert!(base().destination_network(0).build().is_err());
+        assert!(base().destination_address("0x123").build().is_err());
+    }
+}
@@ -0,0 +1,274 @@
+//! Nonce management and stuck-transaction recovery
+//!
+//! Signing clients assign nonces through a nonce manager that starts from the
+//! account's pending transaction count. A transaction still waiting in the
+//! mempool, typically because its gas price is below the base fee, then
+//! delays later transactions instead of having them fail with nonce errors or
+//! silently replace it. Such stuck transactions are reported when a client is
+//! created, and `aggsandbox tx bump` / `aggsandbox tx cancel` replace them with
+//! a higher gas price.
+
+use crate::error::Result;
+use crate::ui;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use std::sync::Arc;
+
+use super::common::{get_network_name, rpc_error, validation_error};
+use super::{SandboxClient, SandboxSigner};
+
+/// Minimum gas price increase, in percent, nodes accept for a replacement
+pub const MIN_BUMP_PERCENT: u64 = 10;
+/// Gas price increase applied by `tx bump` and `tx cancel` unless overridden
+pub const DEFAULT_BUMP_PERCENT: u64 = 20;
+
+/// Wrap a signing client in a nonce manager seeded with its pending nonce
+pub async fn with_nonce_manager(
+    client: SignerMiddleware<Arc<Provider<Http>>, SandboxSigner>,
+    network_id: u64,
+) -> Result<SandboxClient> {
+    let address = client.address();
+    let provider = Arc::clone(client.inner());
+    let action = format!(
+        "query nonces on network {network_id} ({})",
+        get_network_name(network_id)
+    );
+    let manager = NonceManagerMiddleware::new(client, address);
+    let pending = manager
+        .initialize_nonce(Some(BlockNumber::Pending.into()))
+        .await
+        .map_err(|e| rpc_error(&action, e))?;
+    let mined = provider
+        .get_transaction_count(address, Some(BlockNumber::Latest.into()))
+        .await
+        .map_err(|e| rpc_error(&action, e))?;
+
+    if let Some(warning) = stuck_warning(network_id, address, mined, pending) {
+        ui::ui().warning(&warning);
+    }
+    Ok(manager)
+}
+
+/// Warning about transactions of `address` waiting in the mempool
+pub fn stuck_warning(
+    network_id: u64,
+    address: Address,
+    mined: U256,
+    pending: U256,
+) -> Option<String> {
+    if pending <= mined {
+        return None;
+    }
+    let count = pending - mined;
+    let nonces = if count == U256::one() {
+        format!("nonce {mined}")
+    } else {
+        format!("nonces {mined} to {}", pending - 1)
+    };
+    Some(format!(
+        "{count} transaction(s) from {address:#x} are still pending on network {network_id} ({}) with {nonces}; new transactions queue behind them. Replace a stuck one with 'aggsandbox tx bump --tx-hash <HASH> -n {network_id}' or 'aggsandbox tx cancel --nonce {mined} -n {network_id}'",
+        get_network_name(network_id)
+    ))
+}
+
+/// `value` increased by `percent`, rounded up
+pub fn bump(value: U256, percent: u64) -> U256 {
+    let scaled = value * U256::from(100 + percent);
+    let bumped = (scaled + 99) / 100;
+    // Tiny values would not move when rounded; nodes require a strict increase
+    bumped.max(value + 1)
+}
+
+/// Gas price fields of a replacement for `stuck`
+///
+/// The fees of the stuck transaction are raised by `percent`, and never set
+/// below what the network currently asks for, so the replacement is both
+/// accepted as a replacement and mined.
+pub fn replacement_fees(
+    stuck: &Transaction,
+    percent: u64,
+    current_gas_price: U256,
+    current_priority_fee: U256,
+) -> (U256, Option<U256>) {
+    match (stuck.max_fee_per_gas, stuck.max_priority_fee_per_gas) {
+        (Some(max_fee), Some(priority_fee)) => (
+            bump(max_fee, percent).max(current_gas_price),
+            Some(bump(priority_fee, percent).max(current_priority_fee)),
+        ),
+        _ => (
+            bump(stuck.gas_price.unwrap_or_default(), percent).max(current_gas_price),
+            None,
+        ),
+    }
+}
+
+/// Transaction reusing the nonce of `stuck` with raised fees
+///
+/// With `cancel`, the replacement is a plain transfer of 0 ETH to the sender,
+/// which consumes the nonce without executing the stuck call.
+pub fn replacement(
+    stuck: &Transaction,
+    fees: (U256, Option<U256>),
+    cancel: bool,
+) -> TypedTransaction {
+    let (to, value, data, gas) = if cancel {
+        (
+            stuck.from,
+            U256::zero(),
+            Bytes::default(),
+            U256::from(21_000),
+        )
+    } else {
+        (
+            stuck.to.unwrap_or_default(),
+            stuck.value,
+            stuck.input.clone(),
+            stuck.gas,
+        )
+    };
+    match fees {
+        (max_fee, Some(priority_fee)) => Eip1559TransactionRequest::new()
+            .from(stuck.from)
+            .to(to)
+            .value(value)
+            .data(data)
+            .gas(gas)
+            .nonce(stuck.nonce)
+            .max_fee_per_gas(max_fee)
+            .max_priority_fee_per_gas(priority_fee)
+            .into(),
+        (gas_price, None) => TransactionRequest::new()
+            .from(stuck.from)
+            .to(to)
+            .value(value)
+            .data(data)
+            .gas(gas)
+            .nonce(stuck.nonce)
+            .gas_price(gas_price)
+            .into(),
+    }
+}
+
+/// Pending transaction of `address` with `nonce`, read from the node's txpool
+pub async fn pending_with_nonce(
+    provider: &Provider<Http>,
+    address: Address,
+    nonce: U256,
+) -> Option<Transaction> {
+    let content = provider.txpool_content().await.ok()?;
+    content
+        .pending
+        .into_iter()
+        .chain(content.queued)
+        .filter(|(sender, _)| *sender == address)
+        .flat_map(|(_, transactions)| transactions.into_values())
+        .find(|transaction| transaction.nonce == nonce)
+}
+
+/// Fail unless `nonce` of `address` is still waiting to be mined
+pub async fn ensure_nonce_pending(
+    provider: &Provider<Http>,
+    network_id: u64,
+    address: Address,
+    nonce: U256,
+) -> Result<()> {
+    let mined = provider
+        .get_transaction_count(address, Some(BlockNumber::Latest.into()))
+        .await
+        .map_err(|e| {
+            rpc_error(
+                &format!("query the nonce of {address:#x} on network {network_id}"),
+                e,
+            )
+        })?;
+    if nonce < mined {
+        return Err(validation_error(&format!(
+            "Nonce {nonce} of {address:#x} was already used by a mined transaction (next nonce is {mined})"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn legacy(gas_price: u64) -> Transaction {
+        Transaction {
+            from: Address::repeat_byte(0x01),
+            to: Some(Address::repeat_byte(0x02)),
+            nonce: U256::from(7),
+            value: U256::from(5),
+            gas: U256::from(80_000),
+            gas_price: Some(U256::from(gas_price)),
+            input: Bytes::from(vec![0xab, 0xcd]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_bump() {
+        assert_eq!(bump(U256::from(100), 20), U256::from(120));
+        assert_eq!(bump(U256::from(101), 10), U256::from(112));
+        assert_eq!(bump(U256::from(1), 10), U256::from(2));
+        assert_eq!(bump(U256::zero(), 20), U256::one());
+    }
+
+    #[test]
+    fn test_replacement_fees() {
+        let stuck = legacy(1_000);
+        assert_eq!(
+            replacement_fees(&stuck, 20, U256::from(900), U256::zero()),
+            (U256::from(1_200), None)
+        );
+        // Never below the current gas price
+        assert_eq!(
+            replacement_fees(&stuck, 20, U256::from(5_000), U256::zero()).0,
+            U256::from(5_000)
+        );
+
+        let dynamic = Transaction {
+            max_fee_per_gas: Some(U256::from(2_000)),
+            max_priority_fee_per_gas: Some(U256::from(100)),
+            ..legacy(0)
+        };
+        assert_eq!(
+            replacement_fees(&dynamic, 10, U256::from(1_000), U256::from(500)),
+            (U256::from(2_200), Some(U256::from(500)))
+        );
+    }
+
+    #[test]
+    fn test_replacement_keeps_nonce() {
+        let stuck = legacy(1_000);
+        let bumped = replacement(&stuck, (U256::from(1_200), None), false);
+        assert_eq!(bumped.nonce(), Some(&U256::from(7)));
+        assert_eq!(bumped.value(), Some(&U256::from(5)));
+        assert_eq!(bumped.gas_price(), Some(U256::from(1_200)));
+        assert_eq!(
+            bumped.data().map(|data| data.to_vec()),
+            Some(vec![0xab, 0xcd])
+        );
+
+        let cancel = replacement(&stuck, (U256::from(1_200), Some(U256::from(10))), true);
+        assert_eq!(cancel.nonce(), Some(&U256::from(7)));
+        assert_eq!(cancel.to_addr(), Some(&stuck.from));
+        assert_eq!(cancel.value(), Some(&U256::zero()));
+        assert_eq!(cancel.gas(), Some(&U256::from(21_000)));
+    }
+
+    #[test]
+    fn test_stuck_warning() {
+        let address = Address::repeat_byte(0x01);
+        assert!(stuck_warning(1, address, U256::from(3), U256::from(3)).is_none());
+
+        let one = stuck_warning(1, address, U256::from(3), U256::from(4)).unwrap();
+        assert!(one.contains("with nonce 3;"));
+        assert!(one.contains("tx cancel --nonce 3 -n 1"));
+
+        let several = stuck_warning(0, address, U256::from(3), U256::from(6)).unwrap();
+        assert!(several.starts_with("3 transaction(s)"));
+        assert!(several.contains("nonces 3 to 5"));
+    }
+}
@@ -0,0 +1,417 @@
+//! Claims built from exported claim payloads
+//!
+//! `bridge utils build-payload --export` writes everything a claim needs to a
+//! JSON file. `bridge claim --from-payload` turns such a file back into the
+//! claimAsset/claimMessage transaction without asking the bridge service, so
+//! a payload fetched on one machine can be claimed from another. With
+//! `--unsigned` the transaction is only filled (nonce, gas and fees from the
+//! RPC node) and printed as an unsigned raw transaction for external signers.
+
+use super::claim_asset::{check_claim_network, execute_claim_asset, AssetClaimParams};
+use super::claim_message::{execute_claim_message, ClaimMessageArgs};
+use super::common::{get_network_name, rpc_error, serialize_json, validation_error};
+use super::frontrun;
+use super::timing::{Stage, StageTimer};
+use super::utilities::{decode_global_index, ClaimPayload};
+use super::{
+    get_bridge_contract_address, get_provider, get_wallet_with_provider, BridgeContract, GasOptions,
+};
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui;
+use ethers::prelude::*;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Arguments for claiming from an exported payload
+pub struct PayloadClaimArgs<'a> {
+    pub config: &'a Config,
+    /// Network to claim on, the payload's destination network if omitted
+    pub network: Option<u64>,
+    pub payload_path: &'a Path,
+    pub gas_options: GasOptions,
+    pub private_key: Option<&'a str>,
+    pub msg_value: Option<&'a str>,
+    /// Print an unsigned raw transaction instead of sending the claim
+    pub unsigned: bool,
+    /// Sender of the unsigned transaction, the signing account if omitted
+    pub from: Option<&'a str>,
+}
+
+/// Unsigned claim transaction for external signing
+#[derive(Debug, Serialize)]
+pub struct UnsignedClaim {
+    pub function: String,
+    pub network: u64,
+    pub chain_id: u64,
+    pub from: Address,
+    pub to: Address,
+    pub nonce: U256,
+    pub gas: U256,
+    pub value: U256,
+    pub calldata: Bytes,
+    /// RLP encoding of the unsigned transaction, typed transactions prefixed with their type
+    pub raw_transaction: Bytes,
+    /// Hash the external signer has to sign
+    pub signing_hash: H256,
+}
+
+/// Write a claim payload to a JSON file
+pub fn export_payload(path: &Path, payload: &ClaimPayload) -> Result<()> {
+    fs::write(path, serialize_json(payload)?).map_err(|e| {
+        AggSandboxError::Other(format!(
+            "Failed to write claim payload {}: {e}",
+            path.display()
+        ))
+    })
+}
+
+/// Read a claim payload exported with `build-payload --export`
+pub fn load_payload(path: &Path) -> Result<ClaimPayload> {
+    let json = fs::read_to_string(path).map_err(|e| {
+        AggSandboxError::Other(format!(
+            "Failed to read claim payload {}: {e}",
+            path.display()
+        ))
+    })?;
+    serde_json::from_str(&json).map_err(|e| {
+        validation_error(&format!(
+            "{} is not a claim payload exported with build-payload --export: {e}",
+            path.display()
+        ))
+    })
+}
+
+/// Claim arguments of a payload
+///
+/// Like `bridge claim`, the local deposit index is passed to the bridge as
+/// the claim's global index; the payload's global index only tells where the
+/// deposit was made.
+pub fn claim_params(payload: &ClaimPayload) -> Result<AssetClaimParams> {
+    let root = |value: &str, field: &str| {
+        H256::from_str(value)
+            .map_err(|e| validation_error(&format!("Invalid {field} '{value}' in payload: {e}")))
+    };
+    let address = |value: &str, field: &str| {
+        Address::from_str(value)
+            .map_err(|e| validation_error(&format!("Invalid {field} '{value}' in payload: {e}")))
+    };
+    let (_, deposit_count) = decode_global_index(payload_global_index(payload)?);
+
+    Ok(AssetClaimParams {
+        deposit_count: deposit_count.into(),
+        mainnet_root: root(&payload.mainnet_exit_root, "mainnet_exit_root")?,
+        rollup_root: root(&payload.rollup_exit_root, "rollup_exit_root")?,
+        origin_network: payload.origin_network,
+        origin_addr: address(&payload.origin_token_address, "origin_token_address")?,
+        destination_network_id: payload.destination_network,
+        dest_addr: address(&payload.destination_address, "destination_address")?,
+        amount_wei: U256::from_dec_str(&payload.amount).map_err(|e| {
+            validation_error(&format!(
+                "Invalid amount '{}' in payload: {e}",
+                payload.amount
+            ))
+        })?,
+        metadata_bytes: hex::decode(payload.metadata.trim_start_matches("0x"))
+            .map_err(|e| validation_error(&format!("Invalid metadata hex in payload: {e}")))?,
+    })
+}
+
+/// Global index of a payload
+fn payload_global_index(payload: &ClaimPayload) -> Result<U256> {
+    U256::from_dec_str(&payload.global_index).map_err(|e| {
+        validation_error(&format!(
+            "Invalid global_index '{}' in payload: {e}",
+            payload.global_index
+        ))
+    })
+}
+
+/// Claim a deposit from an exported payload, or print the unsigned claim
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn claim_from_payload(args: PayloadClaimArgs<'_>) -> Result<()> {
+    let payload = load_payload(args.payload_path)?;
+    let params = claim_params(&payload)?;
+    let network = args
+        .network
+        .unwrap_or_else(|| payload.destination_network.into());
+    let (source_network, _) = decode_global_index(payload_global_index(&payload)?);
+    let msg_value = args
+        .msg_value
+        .map(|value| {
+            U256::from_dec_str(value)
+                .map_err(|e| validation_error(&format!("Invalid msg_value: {e}")))
+        })
+        .transpose()?;
+    if msg_value.is_some() && payload.leaf_type == 0 {
+        return Err(validation_error(
+            "--msg-value only applies to message claims, the payload is an asset deposit",
+        ));
+    }
+
+    let bridge_address = get_bridge_contract_address(args.config, network)?;
+    let provider = get_provider(args.config, network).await?;
+    let contract_network_id = BridgeContract::new(bridge_address, provider.clone())
+        .network_id()
+        .call()
+        .await
+        .map_err(|e| rpc_error("get bridge network ID", e))?;
+    check_claim_network(
+        contract_network_id,
+        network,
+        Some(payload.destination_network.into()),
+        source_network,
+    )?;
+
+    if args.unsigned {
+        let unsigned = unsigned_claim(
+            &args,
+            network,
+            provider,
+            bridge_address,
+            params,
+            payload.leaf_type,
+            msg_value,
+        )
+        .await?;
+        print_unsigned_claim(&unsigned);
+        return Ok(());
+    }
+
+    let mut timer = StageTimer::start();
+    let deposit_count = params.deposit_count;
+    let client = get_wallet_with_provider(args.config, network, args.private_key).await?;
+    let bridge = BridgeContract::new(bridge_address, Arc::new(client));
+    let tx_hash = if payload.leaf_type == 0 {
+        ui::ui().info(&format!(
+            "💰 Claiming asset from payload: {} tokens to {:#x} on network {network} ({})",
+            params.amount_wei,
+            params.dest_addr,
+            get_network_name(network)
+        ));
+        execute_claim_asset(&bridge, params, &args.gas_options).await?
+    } else {
+        ui::ui().info(&format!(
+            "📨 Claiming message from payload to {:#x} on network {network} ({})",
+            params.dest_addr,
+            get_network_name(network)
+        ));
+        let message_args = ClaimMessageArgs::builder()
+            .bridge(&bridge)
+            .deposit_count(params.deposit_count)
+            .mainnet_root(params.mainnet_root)
+            .rollup_root(params.rollup_root)
+            .origin_network(params.origin_network)
+            .origin_addr(params.origin_addr)
+            .destination_network_id(params.destination_network_id)
+            .dest_addr(params.dest_addr)
+            .amount_wei(params.amount_wei)
+            .metadata_bytes(params.metadata_bytes)
+            .gas_options(&args.gas_options)
+            .msg_value(msg_value)
+            .build_with_crate_error()?;
+        execute_claim_message(message_args).await?
+    };
+
+    let Some(tx_hash) = tx_hash else {
+        return Ok(());
+    };
+
+    ui::ui().success(&format!("Claim transaction submitted: {tx_hash:#x}"));
+    timer
+        .time(
+            Stage::MiningWait,
+            frontrun::check_claim_receipt(&bridge, tx_hash, source_network, deposit_count),
+        )
+        .await?;
+    timer.report(
+        "claim",
+        serde_json::json!({
+            "tx_hash": format!("{tx_hash:#x}"),
+            "network": network,
+            "source_network": source_network,
+            "deposit_count": deposit_count,
+        }),
+    );
+    Ok(())
+}
+
+/// Fill the claim transaction of a payload without signing it
+async fn unsigned_claim(
+    args: &PayloadClaimArgs<'_>,
+    network: u64,
+    provider: Arc<Provider<Http>>,
+    bridge_address: Address,
+    params: AssetClaimParams,
+    leaf_type: u8,
+    msg_value: Option<U256>,
+) -> Result<UnsignedClaim> {
+    let from = match args.from {
+        Some(from) => super::common::validate_address(from, "--from")?,
+        None => get_wallet_with_provider(args.config, network, args.private_key)
+            .await?
+            .default_sender()
+            .ok_or_else(|| validation_error("No signing account to claim from, use --from"))?,
+    };
+
+    let bridge = BridgeContract::new(bridge_address, provider.clone());
+    let metadata = Bytes::from(params.metadata_bytes);
+    let mut call = if leaf_type == 0 {
+        bridge.claim_asset(
+            params.deposit_count.into(),
+            params.mainnet_root.into(),
+            params.rollup_root.into(),
+            params.origin_network,
+            params.origin_addr,
+            params.destination_network_id,
+            params.dest_addr,
+            params.amount_wei,
+            metadata,
+        )
+    } else {
+        bridge.claim_message(
+            params.deposit_count.into(),
+            params.mainnet_root.into(),
+            params.rollup_root.into(),
+            params.origin_network,
+            params.origin_addr,
+            params.destination_network_id,
+            params.dest_addr,
+            params.amount_wei,
+            metadata,
+        )
+    }
+    .from(from);
+    if let Some(value) = msg_value {
+        call = call.value(value);
+    }
+    if args.gas_options.gas_limit.is_none() {
+        call = call.gas(3_000_000u64); // Default high gas limit for claims
+    }
+    let call = args
+        .gas_options
+        .apply_to_call_with_return(call, bridge.client_ref())
+        .await?;
+
+    let fill_error = |e: ProviderError| rpc_error("fill the claim transaction", e);
+    let chain_id = provider.get_chainid().await.map_err(fill_error)?.as_u64();
+    let nonce = provider
+        .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+        .await
+        .map_err(fill_error)?;
+    let mut tx = call.tx.clone();
+    tx.set_chain_id(chain_id);
+    tx.set_nonce(nonce);
+    provider
+        .fill_transaction(&mut tx, None)
+        .await
+        .map_err(fill_error)?;
+
+    Ok(UnsignedClaim {
+        function: call.function.signature(),
+        network,
+        chain_id,
+        from,
+        to: bridge_address,
+        nonce,
+        gas: tx.gas().copied().unwrap_or_default(),
+        value: tx.value().copied().unwrap_or_default(),
+        calldata: tx.data().cloned().unwrap_or_default(),
+        raw_transaction: tx.rlp(),
+        signing_hash: tx.sighash(),
+    })
+}
+
+/// Print an unsigned claim transaction
+fn print_unsigned_claim(unsigned: &UnsignedClaim) {
+    let ui = ui::ui();
+    if ui.is_json() {
+        ui.json(&serde_json::to_value(unsigned).unwrap_or_default());
+        return;
+    }
+    let network = format!(
+        "{} ({})",
+        unsigned.network,
+        get_network_name(unsigned.network)
+    );
+    let rows = [
+        ("Function", unsigned.function.clone()),
+        ("Network", network),
+        ("Chain ID", unsigned.chain_id.to_string()),
+        ("From", format!("{:#x}", unsigned.from)),
+        ("To", format!("{:#x}", unsigned.to)),
+        ("Nonce", unsigned.nonce.to_string()),
+        ("Gas", unsigned.gas.to_string()),
+        ("Value (wei)", unsigned.value.to_string()),
+        ("Signing hash", format!("{:#x}", unsigned.signing_hash)),
+    ];
+    let rows: Vec<(&str, &str)> = rows
+        .iter()
+        .map(|(label, value)| (*label, value.as_str()))
+        .collect();
+    ui.table("✍️  Unsigned Claim Transaction", &rows);
+    ui.info(&format!("Raw transaction: {}", unsigned.raw_transaction));
+    ui.tip("Sign the raw transaction externally and broadcast it with eth_sendRawTransaction (e.g. aggsandbox rpc)");
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn payload() -> ClaimPayload {
+        ClaimPayload {
+            smt_proof: vec![format!("{:#x}", H256::zero()); 32],
+            smt_proof_rollup: None,
+            global_index: "18446744073709551623".to_string(),
+            mainnet_exit_root: format!("{:#x}", H256::repeat_byte(1)),
+            rollup_exit_root: format!("{:#x}", H256::repeat_byte(2)),
+            origin_network: 0,
+            origin_token_address: format!("{:#x}", Address::zero()),
+            destination_network: 1,
+            destination_address: format!("{:#x}", Address::repeat_byte(3)),
+            amount: "1000".to_string(),
+            metadata: "0x".to_string(),
+            leaf_type: 0,
+            global_exit_root: None,
+        }
+    }
+
+    #[test]
+    fn test_claim_params_from_payload() {
+        let params = claim_params(&payload()).unwrap();
+        assert_eq!(params.deposit_count, 7);
+        assert_eq!(params.mainnet_root, H256::repeat_byte(1));
+        assert_eq!(params.rollup_root, H256::repeat_byte(2));
+        assert_eq!(params.dest_addr, Address::repeat_byte(3));
+        assert_eq!(params.amount_wei, U256::from(1000));
+        assert!(params.metadata_bytes.is_empty());
+    }
+
+    #[test]
+    fn test_claim_params_rejects_bad_fields() {
+        let mut bad_root = payload();
+        bad_root.mainnet_exit_root = "0x1234".to_string();
+        assert!(claim_params(&bad_root).is_err());
+
+        let mut bad_amount = payload();
+        bad_amount.amount = "lots".to_string();
+        assert!(claim_params(&bad_amount).is_err());
+    }
+
+    #[test]
+    fn test_export_and_load_payload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("payload.json");
+        export_payload(&path, &payload()).unwrap();
+        let loaded = load_payload(&path).unwrap();
+        assert_eq!(loaded.global_index, payload().global_index);
+        assert_eq!(loaded.destination_network, 1);
+
+        fs::write(&path, "{}").unwrap();
+        assert!(load_payload(&path).is_err());
+    }
+}
@@ -0,0 +1,468 @@
+//! Claim proof path recomputed locally
+//!
+//! A claim proves its deposit leaf up to a global exit root: the leaf hashes up
+//! the 32-level local exit tree of its network to a local exit root. For L1
+//! deposits that root is the mainnet exit root; for L2 deposits it is itself a
+//! leaf of the rollup exit tree, at index `network_id - 1`, whose root is the
+//! rollup exit root. The global exit root is `keccak256(mainnet, rollup)`.
+//! Recomputing every node shows where a proof stops matching the roots it was
+//! built against, and checking that root on-chain catches stale proofs before
+//! a claim transaction reverts.
+
+use ethers::prelude::*;
+use ethers::utils::keccak256;
+use serde::Serialize;
+use std::str::FromStr;
+
+use super::common::{get_network_name, validation_error};
+use super::exit_roots::{fetch_exit_roots, global_exit_root_known};
+use super::utilities::{decode_global_index, ClaimPayload};
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui::UI;
+
+/// Levels of the local and rollup exit trees
+pub const TREE_DEPTH: usize = 32;
+
+/// Leaf hash of a deposit, as computed by `getLeafValue` of the bridge
+pub fn leaf_hash(
+    leaf_type: u8,
+    origin_network: u32,
+    origin_address: Address,
+    destination_network: u32,
+    destination_address: Address,
+    amount: U256,
+    metadata: &[u8],
+) -> H256 {
+    let mut amount_bytes = [0u8; 32];
+    amount.to_big_endian(&mut amount_bytes);
+    let metadata_hash = keccak256(metadata);
+    let origin_network = origin_network.to_be_bytes();
+    let destination_network = destination_network.to_be_bytes();
+    let parts: [&[u8]; 7] = [
+        &[leaf_type],
+        &origin_network,
+        origin_address.as_bytes(),
+        &destination_network,
+        destination_address.as_bytes(),
+        &amount_bytes,
+        &metadata_hash,
+    ];
+    let packed = parts.concat();
+    H256(keccak256(packed))
+}
+
+/// Root of a tree from a leaf, its index and its siblings, bottom up
+pub fn merkle_root(leaf: H256, index: u32, siblings: &[H256]) -> H256 {
+    siblings
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (height, sibling)| {
+            let (left, right) = if (index >> height) & 1 == 1 {
+                (sibling, &node)
+            } else {
+                (&node, sibling)
+            };
+            H256(keccak256([left.as_bytes(), right.as_bytes()].concat()))
+        })
+}
+
+/// Global exit root of a mainnet and a rollup exit root
+pub fn global_exit_root(mainnet_exit_root: H256, rollup_exit_root: H256) -> H256 {
+    H256(keccak256(
+        [mainnet_exit_root.as_bytes(), rollup_exit_root.as_bytes()].concat(),
+    ))
+}
+
+/// One node of the claim path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathNode {
+    pub label: String,
+    /// How the node is derived from the previous one
+    pub via: Option<String>,
+    pub computed: H256,
+    /// Value the proof was built against, if there is one to compare with
+    pub expected: Option<H256>,
+    /// Why the node could not be computed properly
+    pub issue: Option<String>,
+}
+
+impl PathNode {
+    /// Whether the node matches its expected value, `None` when unchecked
+    pub fn matches(&self) -> Option<bool> {
+        if self.issue.is_some() {
+            return Some(false);
+        }
+        self.expected.map(|expected| expected == self.computed)
+    }
+}
+
+fn parse_hash(value: &str, what: &str) -> Result<H256> {
+    H256::from_str(value).map_err(|_| validation_error(&format!("Invalid {what}: {value}")))
+}
+
+fn parse_siblings(proof: &[String], what: &str) -> Result<Vec<H256>> {
+    proof
+        .iter()
+        .map(|sibling| parse_hash(sibling, what))
+        .collect()
+}
+
+fn proof_issue(siblings: &[H256], tree: &str) -> Option<String> {
+    (siblings.len() != TREE_DEPTH).then(|| {
+        format!(
+            "{tree} proof has {} of {TREE_DEPTH} siblings",
+            siblings.len()
+        )
+    })
+}
+
+/// Recompute every node from the deposit leaf to the global exit root
+pub fn claim_path(payload: &ClaimPayload) -> Result<Vec<PathNode>> {
+    let global_index = U256::from_dec_str(&payload.global_index).map_err(|_| {
+        validation_error(&format!("Invalid global index: {}", payload.global_index))
+    })?;
+    let (source_network, deposit_count) = decode_global_index(global_index);
+    let origin_address = Address::from_str(&payload.origin_token_address)
+        .map_err(|_| validation_error("Invalid origin token address in payload"))?;
+    let destination_address = Address::from_str(&payload.destination_address)
+        .map_err(|_| validation_error("Invalid destination address in payload"))?;
+    let amount = U256::from_dec_str(&payload.amount)
+        .map_err(|_| validation_error(&format!("Invalid amount: {}", payload.amount)))?;
+    let metadata = Bytes::from_str(&payload.metadata)
+        .map_err(|_| validation_error("Invalid metadata in payload"))?;
+    let mainnet_exit_root = parse_hash(&payload.mainnet_exit_root, "mainnet exit root")?;
+    let rollup_exit_root = parse_hash(&payload.rollup_exit_root, "rollup exit root")?;
+
+    let leaf = leaf_hash(
+        payload.leaf_type,
+        payload.origin_network,
+        origin_address,
+        payload.destination_network,
+        destination_address,
+        amount,
+        &metadata,
+    );
+    let local_siblings = parse_siblings(&payload.smt_proof, "local exit tree sibling")?;
+    let local_root = merkle_root(leaf, deposit_count, &local_siblings);
+    let local_via = format!("local exit tree of network {source_network}, leaf {deposit_count}");
+
+    let mut nodes = vec![PathNode {
+        label: "Deposit leaf".to_string(),
+        via: None,
+        computed: leaf,
+        expected: None,
+        issue: None,
+    }];
+
+    if source_network == 0 {
+        nodes.push(PathNode {
+            label: "Mainnet exit root".to_string(),
+            via: Some(local_via),
+            computed: local_root,
+            expected: Some(mainnet_exit_root),
+            issue: proof_issue(&local_siblings, "Local exit tree"),
+        });
+    } else {
+        nodes.push(PathNode {
+            label: "Local exit root".to_string(),
+            via: Some(local_via),
+            computed: local_root,
+            expected: None,
+            issue: proof_issue(&local_siblings, "Local exit tree"),
+        });
+        let rollup_siblings = parse_siblings(
+            payload.smt_proof_rollup.as_deref().unwrap_or_default(),
+            "rollup exit tree sibling",
+        )?;
+        let rollup_index = (source_network - 1) as u32;
+        nodes.push(PathNode {
+            label: "Rollup exit root".to_string(),
+            via: Some(format!("rollup exit tree, leaf {rollup_index}")),
+            computed: merkle_root(local_root, rollup_index, &rollup_siblings),
+            expected: Some(rollup_exit_root),
+            issue: proof_issue(&rollup_siblings, "Rollup exit tree"),
+        });
+    }
+
+    let expected_ger = payload
+        .global_exit_root
+        .as_deref()
+        .map(|root| parse_hash(root, "global exit root"))
+        .transpose()?;
+    nodes.push(PathNode {
+        label: "Global exit root".to_string(),
+        via: Some("keccak256(mainnet exit root, rollup exit root)".to_string()),
+        computed: global_exit_root(mainnet_exit_root, rollup_exit_root),
+        expected: expected_ger,
+        issue: None,
+    });
+    Ok(nodes)
+}
+
+/// Index of the first node that does not match its expected value
+pub fn first_mismatch(nodes: &[PathNode]) -> Option<usize> {
+    nodes.iter().position(|node| node.matches() == Some(false))
+}
+
+/// Print the claim path, highlighting the first mismatching node
+pub fn print_claim_path(ui: &UI, nodes: &[PathNode]) {
+    let mismatch = first_mismatch(nodes);
+    ui.info("🧭 Claim proof path");
+    for (index, node) in nodes.iter().enumerate() {
+        if let Some(via) = &node.via {
+            ui.info(&format!("   │ {via}"));
+            ui.info("   ▼");
+        }
+        let line = format!("{}: {:#x}", node.label, node.computed);
+        match node.matches() {
+            Some(true) => ui.success(&format!("{line} (matches)")),
+            Some(false) => {
+                let marker = if mismatch == Some(index) {
+                    " ← first mismatch"
+                } else {
+                    ""
+                };
+                ui.error(&format!("{line}{marker}"));
+                if let Some(issue) = &node.issue {
+                    ui.error(&format!("   {issue}"));
+                }
+                if let Some(expected) = node.expected {
+                    ui.error(&format!("   expected {expected:#x}"));
+                }
+            }
+            None => ui.info(&line),
+        }
+    }
+    if mismatch.is_none() {
+        ui.success("Proof verifies up to the global exit root");
+    }
+}
+
+/// Claim proof checked locally and against the destination network
+#[derive(Debug, Clone, Serialize)]
+pub struct ProofVerification {
+    pub verified: bool,
+    pub destination_network: u64,
+    pub global_exit_root: H256,
+    /// Whether the destination's exit root manager knows the global exit root
+    pub global_exit_root_on_chain: bool,
+    /// Current exit roots of the L1 manager
+    pub l1_mainnet_exit_root: Option<H256>,
+    pub l1_rollup_exit_root: H256,
+    pub proof_path: Vec<PathNode>,
+}
+
+impl ProofVerification {
+    /// Why the claim would revert, if it would
+    pub fn failure(&self) -> Option<String> {
+        if let Some(index) = first_mismatch(&self.proof_path) {
+            let node = &self.proof_path[index];
+            let reason = node.issue.clone().unwrap_or_else(|| {
+                format!(
+                    "recomputed {:#x}, proof built against {:#x}",
+                    node.computed,
+                    node.expected.unwrap_or_default()
+                )
+            });
+            return Some(format!("{} does not match: {reason}", node.label));
+        }
+        if !self.global_exit_root_on_chain {
+            return Some(format!(
+                "Global exit root {:#x} is unknown to network {} ({}); the proof is stale or the root has not been injected yet",
+                self.global_exit_root,
+                self.destination_network,
+                get_network_name(self.destination_network)
+            ));
+        }
+        None
+    }
+}
+
+/// Verify a claim proof locally and against the on-chain exit roots
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn verify_claim_proof(
+    config: &Config,
+    payload: &ClaimPayload,
+) -> Result<ProofVerification> {
+    let proof_path = claim_path(payload)?;
+    let global_exit_root = proof_path
+        .last()
+        .map(|node| node.computed)
+        .unwrap_or_default();
+    let destination_network = u64::from(payload.destination_network);
+    let l1 = fetch_exit_roots(config, 0, None).await?;
+    let global_exit_root_on_chain =
+        global_exit_root_known(config, destination_network, global_exit_root).await?;
+    tracing::debug!(
+        destination_network,
+        global_exit_root = ?global_exit_root,
+        global_exit_root_on_chain,
+        "Verified claim proof"
+    );
+
+    let mut verification = ProofVerification {
+        verified: false,
+        destination_network,
+        global_exit_root,
+        global_exit_root_on_chain,
+        l1_mainnet_exit_root: l1.mainnet_exit_root,
+        l1_rollup_exit_root: l1.rollup_exit_root,
+        proof_path,
+    };
+    verification.verified = verification.failure().is_none();
+    Ok(verification)
+}
+
+/// Print a proof verification, its path and the current L1 exit roots
+pub fn print_verification(ui: &UI, verification: &ProofVerification) {
+    print_claim_path(ui, &verification.proof_path);
+    let hash = |root: Option<H256>| root.map_or_else(|| "n/a".to_string(), |r| format!("{r:#x}"));
+    let on_chain = if verification.global_exit_root_on_chain {
+        "yes"
+    } else {
+        "no"
+    };
+    let on_chain_label = format!("Known on network {}", verification.destination_network);
+    let mainnet = hash(verification.l1_mainnet_exit_root);
+    let rollup = format!("{:#x}", verification.l1_rollup_exit_root);
+    ui.table(
+        "⛓️  On-chain Exit Roots",
+        &[
+            (on_chain_label.as_str(), on_chain),
+            ("L1 mainnet exit root", mainnet.as_str()),
+            ("L1 rollup exit root", rollup.as_str()),
+        ],
+    );
+    match verification.failure() {
+        Some(failure) => ui.error(&failure),
+        None => ui.success("Claim proof verified against the on-chain exit roots"),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn zero_siblings() -> Vec<H256> {
+        let mut siblings = vec![H256::zero()];
+        for height in 1..TREE_DEPTH {
+            let below = siblings[height - 1];
+            siblings.push(H256(keccak256(
+                [below.as_bytes(), below.as_bytes()].concat(),
+            )));
+        }
+        siblings
+    }
+
+    fn payload(siblings: &[H256], mainnet_exit_root: H256) -> ClaimPayload {
+        ClaimPayload {
+            smt_proof: siblings.iter().map(|s| format!("{s:#x}")).collect(),
+            smt_proof_rollup: None,
+            global_index: (U256::one() << 64).to_string(),
+            mainnet_exit_root: format!("{mainnet_exit_root:#x}"),
+            rollup_exit_root: format!("{:#x}", H256::zero()),
+            origin_network: 0,
+            origin_token_address: format!("{:#x}", Address::zero()),
+            destination_network: 1,
+            destination_address: format!("{:#x}", Address::repeat_byte(0x11)),
+            amount: "1000".to_string(),
+            metadata: "0x".to_string(),
+            leaf_type: 0,
+            global_exit_root: None,
+        }
+    }
+
+    #[test]
+    fn test_verification_failure_reasons() {
+        let siblings = zero_siblings();
+        let nodes = claim_path(&payload(&siblings, H256::repeat_byte(9))).unwrap();
+        let mut verification = ProofVerification {
+            verified: false,
+            destination_network: 1,
+            global_exit_root: nodes[2].computed,
+            global_exit_root_on_chain: true,
+            l1_mainnet_exit_root: None,
+            l1_rollup_exit_root: H256::zero(),
+            proof_path: nodes,
+        };
+        assert!(verification
+            .failure()
+            .unwrap()
+            .starts_with("Mainnet exit root does not match"));
+
+        verification.proof_path.truncate(1);
+        verification.global_exit_root_on_chain = false;
+        assert!(verification
+            .failure()
+            .unwrap()
+            .contains("is unknown to network 1"));
+    }
+
+    #[test]
+    fn test_merkle_root_left_and_right() {
+        let leaf = H256::repeat_byte(1);
+        let sibling = H256::repeat_byte(2);
+        let hash = |a: H256, b: H256| H256(keccak256([a.as_bytes(), b.as_bytes()].concat()));
+
+        assert_eq!(merkle_root(leaf, 0, &[sibling]), hash(leaf, sibling));
+        assert_eq!(merkle_root(leaf, 1, &[sibling]), hash(sibling, leaf));
+    }
+
+    #[test]
+    fn test_claim_path_matches_single_deposit_tree() {
+        let siblings = zero_siblings();
+        let leaf = leaf_hash(
+            0,
+            0,
+            Address::zero(),
+            1,
+            Address::repeat_byte(0x11),
+            U256::from(1000),
+            &[],
+        );
+        let root = merkle_root(leaf, 0, &siblings);
+        let nodes = claim_path(&payload(&siblings, root)).unwrap();
+
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[1].matches(), Some(true));
+        assert_eq!(nodes[2].computed, global_exit_root(root, H256::zero()));
+        assert_eq!(first_mismatch(&nodes), None);
+    }
+
+    #[test]
+    fn test_claim_path_reports_first_mismatch() {
+        let mut siblings = zero_siblings();
+        siblings.truncate(31);
+        let nodes = claim_path(&payload(&siblings, H256::repeat_byte(9))).unwrap();
+
+        assert_eq!(first_mismatch(&nodes), Some(1));
+        assert_eq!(
+            nodes[1].issue.as_deref(),
+            Some("Local exit tree proof has 31 of 32 siblings")
+        );
+    }
+
+    #[test]
+    fn test_claim_path_for_rollup_deposit() {
+        let siblings = zero_siblings();
+        let mut payload = payload(&siblings, H256::zero());
+        payload.global_index = ((U256::one() << 32) + 5).to_string();
+        payload.smt_proof_rollup = Some(payload.smt_proof.clone());
+        let nodes = claim_path(&payload).unwrap();
+
+        let labels: Vec<&str> = nodes.iter().map(|node| node.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Deposit leaf",
+                "Local exit root",
+                "Rollup exit root",
+                "Global exit root"
+            ]
+        );
+        assert_eq!(nodes[2].via.as_deref(), Some("rollup exit tree, leaf 1"));
+        assert_eq!(first_mismatch(&nodes), Some(2));
+    }
+}
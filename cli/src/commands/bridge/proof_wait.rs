@@ -0,0 +1,208 @@
+//! Waiting for a deposit to become claimable
+//!
+//! Right after a bridge transaction AggKit has neither indexed the deposit nor
+//! inserted a global exit root covering it, so an immediate `bridge claim`
+//! fails on a missing deposit, L1 info tree index or proof. With `--auto-wait`
+//! the bridge service is polled until all three are available, and the claim is
+//! sent right after. The wait fails early when the source chain or L1 halts.
+
+use crate::api_client::OptimizedApiClient;
+use crate::config::Config;
+use crate::error::Result;
+use crate::progress::ProgressBar;
+use crate::ui;
+use serde_json::Value;
+use std::time::{Duration, Instant};
+
+use super::common::validation_error;
+use super::halt::ChainWatch;
+use super::local_index::{self, DataSource};
+
+/// Polling of `--auto-wait`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoWait {
+    pub timeout: Duration,
+    pub interval: Duration,
+}
+
+impl AutoWait {
+    pub fn new(timeout_secs: u64, interval_secs: u64) -> Self {
+        Self {
+            timeout: Duration::from_secs(timeout_secs),
+            interval: Duration::from_secs(interval_secs.max(1)),
+        }
+    }
+}
+
+/// How far a deposit is from being claimable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Readiness {
+    NotIndexed,
+    NoInfoTreeIndex,
+    NoProof,
+    Ready,
+}
+
+impl Readiness {
+    fn describe(self) -> &'static str {
+        match self {
+            Readiness::NotIndexed => "the deposit is not indexed by the bridge service yet",
+            Readiness::NoInfoTreeIndex => {
+                "no L1 info tree index covers the deposit yet (the global exit root is not updated)"
+            }
+            Readiness::NoProof => "the claim proof is not available yet",
+            Readiness::Ready => "the deposit is claimable",
+        }
+    }
+}
+
+/// Deposit count of the deposit made by `tx_hash`
+///
+/// Without a deposit count the asset leaf is preferred, as `bridge claim` does
+/// for bridge-and-call transactions.
+pub fn find_deposit(bridges: &Value, tx_hash: &str, deposit_count: Option<u64>) -> Option<u64> {
+    let matching: Vec<&Value> = bridges["bridges"]
+        .as_array()?
+        .iter()
+        .filter(|bridge| bridge["bridge_tx_hash"].as_str() == Some(tx_hash))
+        .filter(|bridge| {
+            deposit_count.is_none() || bridge["deposit_count"].as_u64() == deposit_count
+        })
+        .collect();
+    matching
+        .iter()
+        .find(|bridge| bridge["leaf_type"].as_u64() == Some(0))
+        .or_else(|| matching.first())
+        .and_then(|bridge| bridge["deposit_count"].as_u64())
+}
+
+/// Poll until the deposit of `tx_hash` has an L1 info tree index and a claim proof
+#[allow(clippy::too_many_arguments)]
+pub async fn wait_until_claimable(
+    config: &Config,
+    bridge_tx_network: u64,
+    proof_source_network: u64,
+    tx_hash: &str,
+    deposit_count: Option<u64>,
+    source: DataSource,
+    auto_wait: AutoWait,
+) -> Result<()> {
+    let show_spinner = !ui::ui().is_json();
+    let mut spinner = ProgressBar::new(format!("Waiting for {tx_hash} to become claimable"));
+    let spinner = if show_spinner {
+        Some(spinner.start().await)
+    } else {
+        None
+    };
+
+    // The deposit is indexed from the source chain and its global exit root
+    // is updated on L1
+    let mut networks = vec![bridge_tx_network];
+    if bridge_tx_network != 0 {
+        networks.push(0);
+    }
+    let mut chains = ChainWatch::new(config, &networks).await?;
+
+    let deadline = Instant::now() + auto_wait.timeout;
+    let readiness = loop {
+        let readiness = check_readiness(
+            config,
+            bridge_tx_network,
+            proof_source_network,
+            tx_hash,
+            deposit_count,
+            source,
+        )
+        .await;
+        if readiness == Readiness::Ready || Instant::now() >= deadline {
+            break readiness;
+        }
+        if let Err(halt) = chains.check().await {
+            if let Some(spinner) = spinner {
+                spinner.finish_with_error("Chain halted").await;
+            }
+            return Err(halt);
+        }
+        tokio::time::sleep(auto_wait.interval).await;
+    };
+
+    if readiness == Readiness::Ready {
+        if let Some(spinner) = spinner {
+            spinner.finish_with_message("Claim proof available").await;
+        }
+        return Ok(());
+    }
+    if let Some(spinner) = spinner {
+        spinner.finish_with_error("Deposit not claimable").await;
+    }
+    Err(validation_error(&format!(
+        "Timed out after {}s waiting for {tx_hash}: {}",
+        auto_wait.timeout.as_secs(),
+        readiness.describe()
+    )))
+}
+
+async fn check_readiness(
+    config: &Config,
+    bridge_tx_network: u64,
+    proof_source_network: u64,
+    tx_hash: &str,
+    deposit_count: Option<u64>,
+    source: DataSource,
+) -> Readiness {
+    let api_client = OptimizedApiClient::global();
+    api_client
+        .invalidate("bridges", Some(bridge_tx_network))
+        .await;
+
+    let Ok(bridges) = local_index::get_bridges(config, bridge_tx_network, source).await else {
+        return Readiness::NotIndexed;
+    };
+    let Some(deposit_count) = find_deposit(&bridges, tx_hash, deposit_count) else {
+        return Readiness::NotIndexed;
+    };
+
+    let Ok(leaf_index) = api_client
+        .get_typed_l1_info_tree_index(config, proof_source_network, deposit_count)
+        .await
+    else {
+        return Readiness::NoInfoTreeIndex;
+    };
+
+    match api_client
+        .get_typed_claim_proof(config, proof_source_network, leaf_index, deposit_count)
+        .await
+    {
+        Ok(_) => Readiness::Ready,
+        Err(_) => Readiness::NoProof,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_deposit() {
+        let bridges = json!({
+            "bridges": [
+                { "bridge_tx_hash": "0xbb", "deposit_count": 4, "leaf_type": 0 },
+                { "bridge_tx_hash": "0xaa", "deposit_count": 3, "leaf_type": 1 },
+                { "bridge_tx_hash": "0xaa", "deposit_count": 2, "leaf_type": 0 },
+            ]
+        });
+
+        assert_eq!(find_deposit(&bridges, "0xaa", None), Some(2));
+        assert_eq!(find_deposit(&bridges, "0xaa", Some(3)), Some(3));
+        assert_eq!(find_deposit(&bridges, "0xaa", Some(4)), None);
+        assert_eq!(find_deposit(&bridges, "0xcc", None), None);
+        assert_eq!(find_deposit(&json!({}), "0xaa", None), None);
+    }
+
+    #[test]
+    fn test_auto_wait_interval_floor() {
+        assert_eq!(AutoWait::new(60, 0).interval, Duration::from_secs(1));
+    }
+}
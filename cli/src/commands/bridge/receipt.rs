@@ -0,0 +1,287 @@
+//! Receipt waiting and revert-reason decoding for bridge transactions
+//!
+//! Bridge commands wait for the receipt of the transactions they send unless
+//! `--no-wait` is given, so a revert is reported by the command that caused it
+//! instead of surfacing later as a missing deposit or claim. The revert data is
+//! decoded against the bridge's custom errors (`AlreadyClaimed()`,
+//! `InvalidSmtProof()`, `GlobalExitRootInvalid()`, ...) as well as the
+//! standard `Error(string)` and `Panic(uint256)` reverts.
+
+use crate::error::{AggSandboxError, Result};
+use crate::rpc::NewBlocks;
+use crate::ui;
+use ethers::abi::{decode, ParamType, Token};
+use ethers::prelude::*;
+use ethers::providers::MiddlewareError;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::debug;
+
+use super::common::validation_error;
+use super::gas_report;
+
+/// Whether bridge commands wait for the receipt of their transactions
+static WAIT_FOR_RECEIPT: AtomicBool = AtomicBool::new(true);
+
+/// How long a transaction is waited for before it is left pending
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Selector of `Error(string)`
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Custom errors of the bridge contract and what they mean
+const BRIDGE_ERRORS: &[(&str, &str)] = &[
+    ("AlreadyClaimed()", "the deposit has already been claimed"),
+    (
+        "InvalidSmtProof()",
+        "the Merkle proof does not lead to the given exit root",
+    ),
+    (
+        "GlobalExitRootInvalid()",
+        "the global exit root is not known on the destination network yet; wait for AggKit to sync it",
+    ),
+    (
+        "DestinationNetworkInvalid()",
+        "the destination network is the source network or not a valid network",
+    ),
+    (
+        "AmountDoesNotMatchMsgValue()",
+        "the ETH sent does not match the bridged amount",
+    ),
+    (
+        "MsgValueNotZero()",
+        "ETH was sent along with a token bridge",
+    ),
+    (
+        "EtherTransferFailed()",
+        "sending ETH to the destination address failed",
+    ),
+    (
+        "MessageFailed()",
+        "the message call on the destination address reverted",
+    ),
+    ("NotValidAmount()", "the amount is not valid"),
+    ("NotValidOwner()", "the permit owner is not the sender"),
+    ("NotValidSpender()", "the permit spender is not the bridge"),
+    ("NotValidSignature()", "the permit signature is not valid"),
+    (
+        "TokenNotMapped()",
+        "the token has no wrapped counterpart on this network",
+    ),
+    (
+        "OnlyNotEmergencyState()",
+        "the bridge is in emergency state",
+    ),
+    ("OnlyEmergencyState()", "the bridge is not in emergency state"),
+    (
+        "OnlyRollupManager()",
+        "only the rollup manager may call this function",
+    ),
+    ("MerkleTreeFull()", "the deposit tree is full"),
+    (
+        "NoValueInMessagesOnGasTokenNetworks()",
+        "messages cannot carry value on networks with a custom gas token",
+    ),
+    (
+        "GasTokenNetworkMustBeZeroOnEther()",
+        "the gas token network must be 0 when the gas token is ETH",
+    ),
+    (
+        "FailedTokenWrappedDeployment()",
+        "deploying the wrapped token failed",
+    ),
+    (
+        "InvalidZeroAddress()",
+        "an address argument is the zero address",
+    ),
+];
+
+/// Wait for receipts (`--wait`, the default) or return once a transaction is sent
+pub fn set_wait(wait: bool) {
+    WAIT_FOR_RECEIPT.store(wait, Ordering::Relaxed);
+}
+
+pub fn is_waiting() -> bool {
+    WAIT_FOR_RECEIPT.load(Ordering::Relaxed)
+}
+
+/// Human-readable reason of a revert, `None` for unknown revert data
+pub fn decode_revert(data: &[u8]) -> Option<String> {
+    let (selector, payload) = (data.get(..4)?, &data[4..]);
+
+    if selector == ERROR_STRING_SELECTOR {
+        return match decode(&[ParamType::String], payload).ok()?.pop()? {
+            Token::String(reason) => Some(format!("reverted with reason \"{reason}\"")),
+            _ => None,
+        };
+    }
+    if selector == PANIC_SELECTOR {
+        return match decode(&[ParamType::Uint(256)], payload).ok()?.pop()? {
+            Token::Uint(code) => Some(format!("panicked with code {code:#x}")),
+            _ => None,
+        };
+    }
+
+    BRIDGE_ERRORS
+        .iter()
+        .find(|(signature, _)| ethers::utils::id(signature)[..4] == *selector)
+        .map(|(signature, meaning)| format!("{}: {meaning}", signature.trim_end_matches("()")))
+}
+
+/// Error message of a failed contract call with the decoded revert reason
+pub fn describe_error<M: Middleware>(error: &ContractError<M>) -> String {
+    match error.as_revert().and_then(|data| decode_revert(data)) {
+        Some(reason) => format!("{error} ({reason})"),
+        None => error.to_string(),
+    }
+}
+
+/// Wait for a sent transaction to be mined and fail if it reverted
+///
+/// Returns `None` without waiting under `--no-wait`, and when the transaction
+/// is still pending after two minutes. The receipt is polled again as soon as
+/// a new block arrives when the network has a WebSocket endpoint.
+pub async fn wait_for_receipt<M: Middleware>(
+    client: &M,
+    tx_hash: H256,
+    what: &str,
+) -> Result<Option<TransactionReceipt>> {
+    if !is_waiting() {
+        return Ok(None);
+    }
+
+    let started = std::time::Instant::now();
+    // Subscribed to new blocks only once the receipt is not there right away
+    let mut new_blocks: Option<NewBlocks> = None;
+    let receipt = loop {
+        let receipt = client
+            .get_transaction_receipt(tx_hash)
+            .await
+            .map_err(|e| AggSandboxError::Other(format!("Failed to fetch receipt: {e}")))?;
+        if let Some(receipt) = receipt {
+            break receipt;
+        }
+        if started.elapsed() >= RECEIPT_TIMEOUT {
+            ui::ui().warning(&format!(
+                "{what} {tx_hash:#x} is not mined after {}s; check it later",
+                RECEIPT_TIMEOUT.as_secs()
+            ));
+            return Ok(None);
+        }
+        if new_blocks.is_none() {
+            new_blocks = Some(NewBlocks::for_client(client).await);
+        }
+        if let Some(new_blocks) = &mut new_blocks {
+            new_blocks.wait(RECEIPT_POLL_INTERVAL).await;
+        }
+    };
+
+    ensure_success(client, &receipt, what).await?;
+    ui::ui().success(&format!(
+        "{what} mined in block {} (gas used {})",
+        receipt.block_number.unwrap_or_default(),
+        receipt.gas_used.unwrap_or_default()
+    ));
+    Ok(Some(receipt))
+}
+
+/// Fail with the decoded revert reason if a mined transaction reverted
+///
+/// Successful transactions count toward the operation's gas report.
+pub async fn ensure_success<M: Middleware>(
+    client: &M,
+    receipt: &TransactionReceipt,
+    what: &str,
+) -> Result<()> {
+    if receipt.status != Some(U64::zero()) {
+        gas_report::record_receipt(receipt);
+        return Ok(());
+    }
+    let reason = revert_reason(client, receipt).await;
+    Err(validation_error(&format!(
+        "{what} {:#x} reverted in block {}: {reason}",
+        receipt.transaction_hash,
+        receipt.block_number.unwrap_or_default()
+    )))
+}
+
+/// Revert reason of a mined transaction, found by replaying it
+///
+/// Receipts carry no revert data, so the transaction is re-run with
+/// `eth_call` on the state before its block.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn revert_reason<M: Middleware>(client: &M, receipt: &TransactionReceipt) -> String {
+    const UNKNOWN: &str = "no revert reason available";
+
+    let tx = match client.get_transaction(receipt.transaction_hash).await {
+        Ok(Some(tx)) => tx,
+        Ok(None) => return UNKNOWN.to_string(),
+        Err(e) => {
+            debug!(error = %e, "Failed to fetch the reverted transaction");
+            return UNKNOWN.to_string();
+        }
+    };
+
+    let mut request = TransactionRequest::new()
+        .from(tx.from)
+        .data(tx.input)
+        .value(tx.value)
+        .gas(tx.gas);
+    if let Some(to) = tx.to {
+        request = request.to(to);
+    }
+    let call: TypedTransaction = request.into();
+    let block = receipt
+        .block_number
+        .map(|number| BlockId::from(number.saturating_sub(U64::one())));
+
+    match client.call(&call, block).await {
+        Ok(_) => "the transaction does not revert when replayed (it may depend on its position in the block)".to_string(),
+        Err(e) => e
+            .as_error_response()
+            .and_then(|response| response.as_revert_data())
+            .and_then(|data| decode_revert(&data))
+            .unwrap_or_else(|| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use ethers::abi::encode;
+
+    #[test]
+    fn test_decode_bridge_errors() {
+        let selector = |signature: &str| ethers::utils::id(signature)[..4].to_vec();
+
+        let reason = decode_revert(&selector("InvalidSmtProof()")).unwrap();
+        assert!(reason.starts_with("InvalidSmtProof: "));
+        let reason = decode_revert(&selector("GlobalExitRootInvalid()")).unwrap();
+        assert!(reason.starts_with("GlobalExitRootInvalid: "));
+        assert_eq!(
+            hex::encode(selector("AlreadyClaimed()")),
+            &super::super::frontrun::ALREADY_CLAIMED_SELECTOR[2..]
+        );
+        assert!(decode_revert(&[0xde, 0xad, 0xbe, 0xef]).is_none());
+        assert!(decode_revert(&[0x01]).is_none());
+    }
+
+    #[test]
+    fn test_decode_standard_reverts() {
+        let mut data = ERROR_STRING_SELECTOR.to_vec();
+        data.extend(encode(&[Token::String("insufficient allowance".into())]));
+        assert_eq!(
+            decode_revert(&data).unwrap(),
+            "reverted with reason \"insufficient allowance\""
+        );
+
+        let mut data = PANIC_SELECTOR.to_vec();
+        data.extend(encode(&[Token::Uint(U256::from(0x11))]));
+        assert_eq!(decode_revert(&data).unwrap(), "panicked with code 0x11");
+    }
+}
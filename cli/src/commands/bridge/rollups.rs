@@ -0,0 +1,357 @@
+//! Rollups registered in the L1 `PolygonRollupManager`
+//!
+//! Every rollup contributes its last verified local exit root as one leaf of
+//! the rollup exit tree, whose root the manager pushes to the L1 global exit
+//! root manager. A deposit made on a rollup only becomes claimable elsewhere
+//! once a verification settled a local exit root that includes it, so
+//! comparing the settled root with the rollup bridge's current one shows
+//! whether its deposits are part of the rollup exit root yet.
+
+use crate::config::Config;
+use crate::error::Result;
+use ethers::prelude::*;
+use serde::Serialize;
+use std::str::FromStr;
+use tracing::debug;
+
+use super::common::{get_network_name, rpc_error, validation_error};
+use super::exit_roots::fetch_exit_roots;
+use super::{get_bridge_contract_address, get_provider};
+
+// `rollupIDToRollupDataV2` returns a struct of static fields, which is encoded
+// exactly like the flat list of return values below.
+abigen!(
+    RollupManager,
+    r#"[
+        function rollupCount() external view returns (uint32)
+        function getRollupExitRoot() external view returns (bytes32)
+        function rollupIDToRollupDataV2(uint32 rollupID) external view returns (address rollupContract, uint64 chainID, address verifier, uint64 forkID, bytes32 lastLocalExitRoot, uint64 lastBatchSequenced, uint64 lastVerifiedBatch, uint64 lastVerifiedBatchBeforeUpgrade, uint64 rollupTypeID, uint8 rollupVerifierType, bytes32 lastPessimisticRoot, bytes32 programVKey)
+    ]"#,
+);
+
+abigen!(
+    LocalExitTree,
+    r#"[
+        function getRoot() external view returns (bytes32)
+    ]"#,
+);
+
+/// How a rollup's deposits are reflected in the rollup exit root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Contribution {
+    /// No local exit root was verified yet, the rollup's leaf is empty
+    None,
+    /// The verified local exit root matches the rollup bridge
+    Current,
+    /// The rollup bridge holds deposits that were not verified yet
+    Behind,
+    /// The rollup is not a configured network, so its bridge was not read
+    Unknown,
+}
+
+impl Contribution {
+    pub fn new(settled: H256, current: Option<H256>) -> Self {
+        match (settled.is_zero(), current) {
+            (_, Some(current)) if current == settled => Self::Current,
+            (true, _) => Self::None,
+            (false, Some(_)) => Self::Behind,
+            (false, None) => Self::Unknown,
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Self::None => "none, no exit root verified yet",
+            Self::Current => "up to date",
+            Self::Behind => "behind, newer deposits not verified yet",
+            Self::Unknown => "unknown (not a configured network)",
+        }
+    }
+}
+
+/// State of one registered rollup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RollupInfo {
+    pub rollup_id: u32,
+    pub rollup_contract: Address,
+    pub chain_id: u64,
+    pub verifier: Address,
+    pub verifier_type: String,
+    pub fork_id: u64,
+    pub rollup_type_id: u64,
+    pub last_batch_sequenced: u64,
+    pub last_verified_batch: u64,
+    /// Local exit root settled by the last verification, the rollup's leaf
+    pub last_local_exit_root: H256,
+    /// Current local exit root of the rollup's bridge, for configured networks
+    pub bridge_local_exit_root: Option<H256>,
+    pub contribution: Contribution,
+}
+
+/// Rollup manager state and its registered rollups
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RollupManagerState {
+    pub manager: Address,
+    pub rollup_count: u32,
+    /// Root of the rollup exit tree computed by the rollup manager
+    pub rollup_exit_root: H256,
+    /// Rollup exit root the L1 global exit root manager last received
+    pub global_rollup_exit_root: H256,
+    pub rollups: Vec<RollupInfo>,
+}
+
+fn verifier_type_name(verifier_type: u8) -> String {
+    match verifier_type {
+        0 => "state-transition".to_string(),
+        1 => "pessimistic".to_string(),
+        other => format!("unknown ({other})"),
+    }
+}
+
+/// Current local exit root of a configured network's bridge
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn bridge_local_exit_root(config: &Config, network_id: u64) -> Option<H256> {
+    if !config.networks.network_ids().contains(&network_id) {
+        return None;
+    }
+    let bridge = get_bridge_contract_address(config, network_id).ok()?;
+    let provider = get_provider(config, network_id).await.ok()?;
+    LocalExitTree::new(bridge, provider)
+        .get_root()
+        .call()
+        .await
+        .map(H256::from)
+        .map_err(|e| debug!(network_id, error = %e, "Could not read the local exit root"))
+        .ok()
+}
+
+/// Read the rollup manager and every rollup registered in it
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn fetch_rollups(config: &Config) -> Result<RollupManagerState> {
+    let address = config.contracts.get_contract("l1", "PolygonRollupManager");
+    let manager = Address::from_str(&address).map_err(|_| {
+        validation_error("No PolygonRollupManager configured on L1 (POLYGON_ROLLUP_MANAGER_L1)")
+    })?;
+    let provider = get_provider(config, 0).await?;
+    let contract = RollupManager::new(manager, provider);
+    debug!(manager = ?manager, "Reading rollup manager");
+
+    let rollup_count = contract
+        .rollup_count()
+        .call()
+        .await
+        .map_err(|e| rpc_error("query the rollup manager on L1", e))?;
+    let rollup_exit_root = H256::from(
+        contract
+            .get_rollup_exit_root()
+            .call()
+            .await
+            .map_err(|e| rpc_error("query the rollup manager on L1", e))?,
+    );
+    let global_rollup_exit_root = fetch_exit_roots(config, 0, None).await?.rollup_exit_root;
+
+    let mut rollups = Vec::with_capacity(rollup_count as usize);
+    // Rollup IDs start at 1; ID 0 is the mainnet
+    for rollup_id in 1..=rollup_count {
+        let (
+            rollup_contract,
+            chain_id,
+            verifier,
+            fork_id,
+            last_local_exit_root,
+            last_batch_sequenced,
+            last_verified_batch,
+            _last_verified_batch_before_upgrade,
+            rollup_type_id,
+            verifier_type,
+            _last_pessimistic_root,
+            _program_v_key,
+        ) = contract
+            .rollup_id_to_rollup_data_v2(rollup_id)
+            .call()
+            .await
+            .map_err(|e| rpc_error("query the rollup manager on L1", e))?;
+        let last_local_exit_root = H256::from(last_local_exit_root);
+        // The network ID of a rollup is its rollup ID
+        let bridge_local_exit_root = bridge_local_exit_root(config, u64::from(rollup_id)).await;
+
+        rollups.push(RollupInfo {
+            rollup_id,
+            rollup_contract,
+            chain_id,
+            verifier,
+            verifier_type: verifier_type_name(verifier_type),
+            fork_id,
+            rollup_type_id,
+            last_batch_sequenced,
+            last_verified_batch,
+            last_local_exit_root,
+            bridge_local_exit_root,
+            contribution: Contribution::new(last_local_exit_root, bridge_local_exit_root),
+        });
+    }
+
+    Ok(RollupManagerState {
+        manager,
+        rollup_count,
+        rollup_exit_root,
+        global_rollup_exit_root,
+        rollups,
+    })
+}
+
+/// Display rows of one rollup
+pub fn rollup_rows(rollup: &RollupInfo) -> Vec<(String, String)> {
+    let mut rows = vec![
+        ("Chain ID".to_string(), rollup.chain_id.to_string()),
+        (
+            "Rollup contract".to_string(),
+            format!("{:#x}", rollup.rollup_contract),
+        ),
+        (
+            "Verifier".to_string(),
+            format!("{:#x} ({})", rollup.verifier, rollup.verifier_type),
+        ),
+        (
+            "Fork / rollup type".to_string(),
+            format!("{} / {}", rollup.fork_id, rollup.rollup_type_id),
+        ),
+        (
+            "Last verified batch".to_string(),
+            format!(
+                "{} of {} sequenced",
+                rollup.last_verified_batch, rollup.last_batch_sequenced
+            ),
+        ),
+        (
+            "Verified exit root".to_string(),
+            format!("{:#x}", rollup.last_local_exit_root),
+        ),
+    ];
+    if let Some(root) = rollup.bridge_local_exit_root {
+        rows.push(("Bridge exit root".to_string(), format!("{root:#x}")));
+    }
+    rows.push((
+        "Exit root contribution".to_string(),
+        rollup.contribution.describe().to_string(),
+    ));
+    rows
+}
+
+/// Explanations of why deposits may be missing from the global exit root
+pub fn diagnose(state: &RollupManagerState) -> Vec<String> {
+    let mut findings = Vec::new();
+    if state.rollup_count == 0 {
+        findings.push(
+            "No rollups are registered, so the rollup exit root stays empty and L2 deposits cannot be claimed on L1".to_string(),
+        );
+    }
+    if state.rollup_exit_root != state.global_rollup_exit_root {
+        findings.push(format!(
+            "The rollup manager's rollup exit root {:#x} has not reached the global exit root manager yet (it holds {:#x})",
+            state.rollup_exit_root, state.global_rollup_exit_root
+        ));
+    }
+    for rollup in &state.rollups {
+        let network = get_network_name(u64::from(rollup.rollup_id));
+        match rollup.contribution {
+            Contribution::None => findings.push(format!(
+                "Rollup {} ({network}) has no verified local exit root; its deposits are not in the rollup exit root",
+                rollup.rollup_id
+            )),
+            Contribution::Behind => findings.push(format!(
+                "Rollup {} ({network}) has deposits newer than its last verified batch {}; they join the rollup exit root once verified",
+                rollup.rollup_id, rollup.last_verified_batch
+            )),
+            Contribution::Current | Contribution::Unknown => {}
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn rollup(settled: H256, current: Option<H256>) -> RollupInfo {
+        RollupInfo {
+            rollup_id: 1,
+            rollup_contract: Address::repeat_byte(0x01),
+            chain_id: 1101,
+            verifier: Address::repeat_byte(0x02),
+            verifier_type: verifier_type_name(1),
+            fork_id: 12,
+            rollup_type_id: 1,
+            last_batch_sequenced: 9,
+            last_verified_batch: 7,
+            last_local_exit_root: settled,
+            bridge_local_exit_root: current,
+            contribution: Contribution::new(settled, current),
+        }
+    }
+
+    fn state(rollups: Vec<RollupInfo>) -> RollupManagerState {
+        RollupManagerState {
+            manager: Address::repeat_byte(0xaa),
+            rollup_count: rollups.len() as u32,
+            rollup_exit_root: H256::repeat_byte(0x10),
+            global_rollup_exit_root: H256::repeat_byte(0x10),
+            rollups,
+        }
+    }
+
+    #[test]
+    fn test_contribution() {
+        let root = H256::repeat_byte(0x01);
+        let newer = H256::repeat_byte(0x02);
+        assert_eq!(Contribution::new(root, Some(root)), Contribution::Current);
+        assert_eq!(Contribution::new(root, Some(newer)), Contribution::Behind);
+        assert_eq!(Contribution::new(root, None), Contribution::Unknown);
+        assert_eq!(Contribution::new(H256::zero(), None), Contribution::None);
+        assert_eq!(
+            Contribution::new(H256::zero(), Some(newer)),
+            Contribution::None
+        );
+        // A rollup without deposits has nothing to contribute
+        assert_eq!(
+            Contribution::new(H256::zero(), Some(H256::zero())),
+            Contribution::Current
+        );
+    }
+
+    #[test]
+    fn test_verifier_type_name() {
+        assert_eq!(verifier_type_name(0), "state-transition");
+        assert_eq!(verifier_type_name(1), "pessimistic");
+        assert_eq!(verifier_type_name(7), "unknown (7)");
+    }
+
+    #[test]
+    fn test_rollup_rows() {
+        let rows = rollup_rows(&rollup(H256::repeat_byte(0x01), None));
+        assert_eq!(rows[0], ("Chain ID".to_string(), "1101".to_string()));
+        assert_eq!(rows[4].1, "7 of 9 sequenced");
+        assert!(rows.iter().all(|(label, _)| label != "Bridge exit root"));
+        assert_eq!(rows.last().unwrap().1, "unknown (not a configured network)");
+
+        let rows = rollup_rows(&rollup(H256::zero(), Some(H256::repeat_byte(0x02))));
+        assert_eq!(rows[6].0, "Bridge exit root");
+        assert_eq!(rows[7].1, "none, no exit root verified yet");
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let root = H256::repeat_byte(0x01);
+        assert!(diagnose(&state(vec![rollup(root, Some(root))])).is_empty());
+        assert_eq!(diagnose(&state(Vec::new())).len(), 1);
+
+        let mut pending = state(vec![rollup(root, Some(H256::repeat_byte(0x02)))]);
+        pending.global_rollup_exit_root = H256::zero();
+        let findings = diagnose(&pending);
+        assert_eq!(findings.len(), 2);
+        assert!(findings[0].contains("has not reached the global exit root manager"));
+        assert!(findings[1].contains("newer than its last verified batch 7"));
+    }
+}
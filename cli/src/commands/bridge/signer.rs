@@ -0,0 +1,236 @@
+//! Transaction signers for bridge commands
+//!
+//! Transactions are signed with a local key (`--private-key`, `--account` or
+//! the default Anvil account) or, in builds with the `hardware-wallets`
+//! feature, with a Ledger or Trezor selected with `--signer` and `--hd-path`.
+//! Hardware wallets are meant for fork mode, where the sandbox talks to real
+//! networks and keys should never leave the device.
+
+use crate::config::{HardwareSigner, HardwareWallet};
+use crate::error::Result;
+use async_trait::async_trait;
+use ethers::signers::{LocalWallet, Signer, WalletError};
+use ethers::types::transaction::eip2718::TypedTransaction;
+use ethers::types::transaction::eip712::Eip712;
+use ethers::types::{Address, Signature};
+
+#[cfg(feature = "hardware-wallets")]
+use ethers::signers::{HDPath, Ledger, LedgerError, Trezor, TrezorError, TrezorHDPath};
+
+use super::common::validation_error;
+
+/// Signer selected with `--signer`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SignerKind {
+    /// Private key, named account or the default Anvil account
+    #[default]
+    Local,
+    /// Ledger device with the Ethereum app open
+    Ledger,
+    /// Trezor device
+    Trezor,
+}
+
+impl SignerKind {
+    /// Hardware signer for this kind, `None` for local keys
+    pub fn hardware_signer(self, hd_path: Option<String>) -> Result<Option<HardwareSigner>> {
+        let wallet = match self {
+            SignerKind::Local => {
+                if hd_path.is_some() {
+                    return Err(validation_error(
+                        "--hd-path only applies to --signer ledger or --signer trezor",
+                    ));
+                }
+                return Ok(None);
+            }
+            SignerKind::Ledger => HardwareWallet::Ledger,
+            SignerKind::Trezor => HardwareWallet::Trezor,
+        };
+        if let Some(path) = &hd_path {
+            validate_hd_path(path)?;
+        }
+        Ok(Some(HardwareSigner { wallet, hd_path }))
+    }
+}
+
+/// Local key or hardware wallet signing bridge transactions
+#[derive(Debug)]
+pub enum SandboxSigner {
+    Local(LocalWallet),
+    #[cfg(feature = "hardware-wallets")]
+    Ledger(Ledger),
+    #[cfg(feature = "hardware-wallets")]
+    Trezor(Trezor),
+}
+
+/// Error raised by the underlying signer
+#[derive(Debug, thiserror::Error)]
+pub enum SandboxSignerError {
+    #[error(transparent)]
+    Local(#[from] WalletError),
+    #[cfg(feature = "hardware-wallets")]
+    #[error(transparent)]
+    Ledger(#[from] LedgerError),
+    #[cfg(feature = "hardware-wallets")]
+    #[error(transparent)]
+    Trezor(#[from] TrezorError),
+}
+
+/// Run the same expression against whichever signer is wrapped
+macro_rules! with_signer {
+    ($self:expr, $signer:ident => $body:expr) => {
+        match $self {
+            SandboxSigner::Local($signer) => $body,
+            #[cfg(feature = "hardware-wallets")]
+            SandboxSigner::Ledger($signer) => $body,
+            #[cfg(feature = "hardware-wallets")]
+            SandboxSigner::Trezor($signer) => $body,
+        }
+    };
+}
+
+#[async_trait]
+impl Signer for SandboxSigner {
+    type Error = SandboxSignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> std::result::Result<Signature, Self::Error> {
+        with_signer!(self, signer => Ok(signer.sign_message(message).await?))
+    }
+
+    async fn sign_transaction(
+        &self,
+        message: &TypedTransaction,
+    ) -> std::result::Result<Signature, Self::Error> {
+        with_signer!(self, signer => Ok(signer.sign_transaction(message).await?))
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        payload: &T,
+    ) -> std::result::Result<Signature, Self::Error> {
+        with_signer!(self, signer => Ok(signer.sign_typed_data(payload).await?))
+    }
+
+    fn address(&self) -> Address {
+        with_signer!(self, signer => signer.address())
+    }
+
+    fn chain_id(&self) -> u64 {
+        with_signer!(self, signer => signer.chain_id())
+    }
+
+    fn with_chain_id<T: Into<u64>>(self, chain_id: T) -> Self {
+        match self {
+            SandboxSigner::Local(signer) => SandboxSigner::Local(signer.with_chain_id(chain_id)),
+            #[cfg(feature = "hardware-wallets")]
+            SandboxSigner::Ledger(signer) => SandboxSigner::Ledger(signer.with_chain_id(chain_id)),
+            #[cfg(feature = "hardware-wallets")]
+            SandboxSigner::Trezor(signer) => SandboxSigner::Trezor(signer.with_chain_id(chain_id)),
+        }
+    }
+}
+
+/// Connect to the hardware wallet selected with `--signer`
+#[cfg(feature = "hardware-wallets")]
+pub async fn connect_hardware(signer: &HardwareSigner, chain_id: u64) -> Result<SandboxSigner> {
+    match signer.wallet {
+        HardwareWallet::Ledger => {
+            let path = match &signer.hd_path {
+                Some(path) => HDPath::Other(path.clone()),
+                None => HDPath::LedgerLive(0),
+            };
+            Ledger::new(path, chain_id)
+                .await
+                .map(SandboxSigner::Ledger)
+                .map_err(|e| {
+                    validation_error(&format!(
+                        "Failed to connect to Ledger: {e}. Unlock the device and open the Ethereum app"
+                    ))
+                })
+        }
+        HardwareWallet::Trezor => {
+            let path = match &signer.hd_path {
+                Some(path) => TrezorHDPath::Other(path.clone()),
+                None => TrezorHDPath::TrezorLive(0),
+            };
+            Trezor::new(path, chain_id, None)
+                .await
+                .map(SandboxSigner::Trezor)
+                .map_err(|e| validation_error(&format!("Failed to connect to Trezor: {e}")))
+        }
+    }
+}
+
+/// Connect to the hardware wallet selected with `--signer`
+#[cfg(not(feature = "hardware-wallets"))]
+pub async fn connect_hardware(signer: &HardwareSigner, _chain_id: u64) -> Result<SandboxSigner> {
+    Err(validation_error(&format!(
+        "{:?} signing is not available in this build; reinstall with `cargo install --path cli --features hardware-wallets`",
+        signer.wallet
+    )))
+}
+
+/// Check that a derivation path looks like `m/44'/60'/0'/0/0`
+fn validate_hd_path(path: &str) -> Result<()> {
+    let valid = path.strip_prefix("m/").is_some_and(|rest| {
+        rest.split('/').all(|segment| {
+            let index = segment.strip_suffix('\'').unwrap_or(segment);
+            !index.is_empty() && index.chars().all(|c| c.is_ascii_digit())
+        })
+    });
+    if valid {
+        Ok(())
+    } else {
+        Err(validation_error(&format!(
+            "Invalid HD path '{path}', expected a derivation path such as m/44'/60'/0'/0/0"
+        )))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_validate_hd_path() {
+        assert!(validate_hd_path("m/44'/60'/0'/0/0").is_ok());
+        assert!(validate_hd_path("m/44'/60'/1'/0/3").is_ok());
+        assert!(validate_hd_path("44'/60'/0'/0/0").is_err());
+        assert!(validate_hd_path("m/44'/x/0").is_err());
+        assert!(validate_hd_path("m//0").is_err());
+    }
+
+    #[test]
+    fn test_hardware_signer_selection() {
+        assert_eq!(SignerKind::Local.hardware_signer(None).unwrap(), None);
+        assert!(SignerKind::Local
+            .hardware_signer(Some("m/44'/60'/0'/0/0".to_string()))
+            .is_err());
+
+        let ledger = SignerKind::Ledger
+            .hardware_signer(Some("m/44'/60'/0'/0/1".to_string()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(ledger.wallet, HardwareWallet::Ledger);
+        assert_eq!(ledger.hd_path.as_deref(), Some("m/44'/60'/0'/0/1"));
+    }
+
+    #[tokio::test]
+    async fn test_local_signer_delegates() {
+        let wallet = LocalWallet::from_str(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let signer = SandboxSigner::Local(wallet.clone()).with_chain_id(1101u64);
+
+        assert_eq!(signer.address(), wallet.address());
+        assert_eq!(signer.chain_id(), 1101);
+        let signature = signer.sign_message("sandbox").await.unwrap();
+        assert_eq!(signature, wallet.sign_message("sandbox").await.unwrap());
+    }
+}
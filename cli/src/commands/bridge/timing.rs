@@ -0,0 +1,205 @@
+//! Stage timings of bridge and claim operations
+//!
+//! When the sandbox is slow it is rarely obvious whether the bridge service,
+//! the RPC node or block production is to blame. Operations time their bridge
+//! service lookups, proof fetches, transaction submissions and mining waits,
+//! and print the breakdown when they finish: as one compact line, or as a
+//! `timings` object in JSON output.
+
+use super::{gas_report, idempotency};
+use crate::commands::bridge::common::format_amount;
+use crate::ui;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::{info_span, Instrument};
+
+/// Stage of a bridge or claim operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Bridge service lookups (bridges, L1 info tree index, indexing)
+    ApiLookup,
+    /// Claim proof from the bridge service
+    ProofFetch,
+    /// Sending transactions to the RPC node
+    TxSubmission,
+    /// Waiting for transactions to be mined
+    MiningWait,
+}
+
+impl Stage {
+    const ALL: [Stage; 4] = [
+        Stage::ApiLookup,
+        Stage::ProofFetch,
+        Stage::TxSubmission,
+        Stage::MiningWait,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Stage::ApiLookup => "api",
+            Stage::ProofFetch => "proof",
+            Stage::TxSubmission => "submit",
+            Stage::MiningWait => "mining",
+        }
+    }
+
+    fn json_key(self) -> &'static str {
+        match self {
+            Stage::ApiLookup => "api_lookup_ms",
+            Stage::ProofFetch => "proof_fetch_ms",
+            Stage::TxSubmission => "tx_submission_ms",
+            Stage::MiningWait => "mining_wait_ms",
+        }
+    }
+}
+
+/// Accumulated time per stage since the operation started
+#[derive(Debug, Clone)]
+pub struct StageTimer {
+    started: Instant,
+    stages: [Option<Duration>; 4],
+}
+
+impl StageTimer {
+    pub fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            stages: [None; 4],
+        }
+    }
+
+    /// Await `future` in a span of the stage, adding its duration to `stage`
+    #[allow(clippy::disallowed_methods)] // Allow tracing macros
+    pub async fn time<F: Future>(&mut self, stage: Stage, future: F) -> F::Output {
+        let start = Instant::now();
+        let output = future
+            .instrument(info_span!("stage", stage = stage.label()))
+            .await;
+        self.add(stage, start.elapsed());
+        output
+    }
+
+    pub fn add(&mut self, stage: Stage, duration: Duration) {
+        let total = &mut self.stages[stage as usize];
+        *total = Some(total.unwrap_or_default() + duration);
+    }
+
+    /// One line breakdown of the stages that ran, e.g. `api 42ms · submit 8ms · total 61ms`
+    pub fn summary(&self, total: Duration) -> String {
+        let mut parts: Vec<String> = Stage::ALL
+            .iter()
+            .filter_map(|&stage| {
+                let duration = self.stages[stage as usize]?;
+                Some(format!("{} {}", stage.label(), format_duration(duration)))
+            })
+            .collect();
+        parts.push(format!("total {}", format_duration(total)));
+        parts.join(" · ")
+    }
+
+    /// Milliseconds per stage that ran, plus the total
+    pub fn to_json(&self, total: Duration) -> serde_json::Value {
+        let mut timings = serde_json::Map::new();
+        for stage in Stage::ALL {
+            if let Some(duration) = self.stages[stage as usize] {
+                timings.insert(stage.json_key().to_string(), duration_ms(duration).into());
+            }
+        }
+        timings.insert("total_ms".to_string(), duration_ms(total).into());
+        serde_json::Value::Object(timings)
+    }
+
+    /// Print the breakdown of a finished operation
+    ///
+    /// In JSON mode `details` (an object) is printed together with the timings.
+    /// It is also the result recorded under an idempotency key. The gas of the
+    /// transactions mined during the operation is added to the gas report.
+    pub fn report(&self, operation: &str, mut details: serde_json::Value) {
+        let total = self.started.elapsed();
+        details["operation"] = operation.into();
+        let network = details["network"]
+            .as_u64()
+            .or_else(|| details["source_network"].as_u64());
+        let gas = gas_report::finish_operation(operation, network);
+        if let Some(gas) = &gas {
+            details["gas"] = gas.to_json();
+        }
+        idempotency::capture(&details);
+        if ui::ui().is_json() {
+            details["timings"] = self.to_json(total);
+            ui::ui().json(&details);
+        } else {
+            ui::ui().info(&format!("⏱️  {operation}: {}", self.summary(total)));
+            if let Some(gas) = &gas {
+                ui::ui().info(&format!(
+                    "⛽ gas used {} · {} gwei · cost {} ETH",
+                    gas.gas_used(),
+                    format_amount(gas.effective_gas_price(), 9),
+                    format_amount(gas.cost(), 18)
+                ));
+            }
+        }
+    }
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+fn format_duration(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{}ms", duration.as_millis())
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_lists_stages_that_ran() {
+        let mut timer = StageTimer::start();
+        timer.add(Stage::ApiLookup, Duration::from_millis(40));
+        timer.add(Stage::ApiLookup, Duration::from_millis(2));
+        timer.add(Stage::MiningWait, Duration::from_millis(1500));
+
+        assert_eq!(
+            timer.summary(Duration::from_millis(1600)),
+            "api 42ms · mining 1.50s · total 1.60s"
+        );
+    }
+
+    #[test]
+    fn test_to_json() {
+        let mut timer = StageTimer::start();
+        timer.add(Stage::ProofFetch, Duration::from_millis(120));
+        timer.add(Stage::TxSubmission, Duration::from_millis(8));
+
+        assert_eq!(
+            timer.to_json(Duration::from_millis(200)),
+            serde_json::json!({
+                "proof_fetch_ms": 120,
+                "tx_submission_ms": 8,
+                "total_ms": 200,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_time_adds_to_stage() {
+        let mut timer = StageTimer::start();
+        let value = timer
+            .time(Stage::ApiLookup, async {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                7
+            })
+            .await;
+
+        assert_eq!(value, 7);
+        assert!(timer.stages[Stage::ApiLookup as usize].unwrap() >= Duration::from_millis(5));
+        assert!(timer.stages[Stage::MiningWait as usize].is_none());
+    }
+}
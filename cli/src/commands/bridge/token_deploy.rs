@@ -0,0 +1,229 @@
+//! Deployment of test ERC20 tokens for `aggsandbox token deploy`
+//!
+//! Tokens are `SandboxERC20` contracts from `agglayer-contracts`, a mintable
+//! ERC20 with configurable decimals owned by the deploying account. The
+//! constructor ABI is embedded here and the creation bytecode is read from the
+//! Foundry build artifact. Deployed tokens are recorded in
+//! `.aggsandbox/tokens.json` so `fund` and `bridge asset --faucet-if-needed`
+//! can mint them like the tokens deployed at startup.
+
+use crate::artifacts::Artifacts;
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use ethers::abi::parse_abi;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::info;
+
+use super::common::validation_error;
+use super::get_wallet_with_provider;
+
+/// Contract deployed by `token deploy`
+const TOKEN_CONTRACT: &str = "SandboxERC20";
+/// Token registry, relative to the project root
+const REGISTRY_FILE: &str = ".aggsandbox/tokens.json";
+
+/// Functions of `SandboxERC20` used by the deployment
+const TOKEN_ABI: &[&str] = &[
+    "constructor(string name, string symbol, uint8 decimals, uint256 initialSupply, address initialOwner)",
+    "function decimals() external view returns (uint8)",
+    "function mint(address to, uint256 amount) external",
+];
+
+/// Parameters of a token deployment
+#[derive(Debug, Clone)]
+pub struct TokenDeployment {
+    pub network_id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Raw amount minted to the deployer
+    pub initial_supply: U256,
+    pub private_key: Option<String>,
+}
+
+/// Token deployed with `token deploy`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployedToken {
+    pub network_id: u64,
+    pub address: Address,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub owner: Address,
+    pub tx_hash: H256,
+    pub deployed_at: u64,
+}
+
+/// Tokens deployed from this working directory
+#[derive(Debug, Clone)]
+pub struct TokenRegistry {
+    path: PathBuf,
+}
+
+impl TokenRegistry {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Registry at `.aggsandbox/tokens.json` of the working directory
+    pub fn default_location() -> Self {
+        Self::new(REGISTRY_FILE)
+    }
+
+    /// Recorded tokens in deployment order, empty when nothing was deployed yet
+    pub fn list(&self) -> Vec<DeployedToken> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Token recorded on a network by address or (case-insensitive) symbol
+    pub fn find(&self, network_id: u64, token: &str) -> Option<DeployedToken> {
+        let address = token.parse::<Address>().ok();
+        self.list().into_iter().rev().find(|deployed| {
+            deployed.network_id == network_id
+                && (Some(deployed.address) == address
+                    || deployed.symbol.eq_ignore_ascii_case(token))
+        })
+    }
+
+    /// Whether `address` is a token recorded on the network
+    pub fn contains(&self, network_id: u64, address: Address) -> bool {
+        self.list()
+            .iter()
+            .any(|deployed| deployed.network_id == network_id && deployed.address == address)
+    }
+
+    pub fn record(&self, token: DeployedToken) -> Result<()> {
+        let mut tokens = self.list();
+        tokens.retain(|deployed| {
+            !(deployed.network_id == token.network_id && deployed.address == token.address)
+        });
+        tokens.push(token);
+
+        let io_error = |e: std::io::Error| {
+            AggSandboxError::Other(format!(
+                "Failed to write token registry {}: {e}",
+                self.path.display()
+            ))
+        };
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let json = serde_json::to_string_pretty(&tokens)
+            .map_err(|e| AggSandboxError::Other(format!("Failed to encode token registry: {e}")))?;
+        fs::write(&self.path, json).map_err(io_error)
+    }
+}
+
+/// Deploy a `SandboxERC20` and record it in the registry
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn deploy_token(
+    config: &Config,
+    registry: &TokenRegistry,
+    deployment: &TokenDeployment,
+) -> Result<DeployedToken> {
+    let bytecode = Artifacts::load().bytecode(TOKEN_CONTRACT).ok_or_else(|| {
+        validation_error(&format!(
+            "No {TOKEN_CONTRACT} build artifact found, run `forge build` in agglayer-contracts first"
+        ))
+    })?;
+    let abi = parse_abi(TOKEN_ABI)
+        .map_err(|e| AggSandboxError::Other(format!("Invalid {TOKEN_CONTRACT} ABI: {e}")))?;
+
+    let client = Arc::new(
+        get_wallet_with_provider(
+            config,
+            deployment.network_id,
+            deployment.private_key.as_deref(),
+        )
+        .await?,
+    );
+    let owner = client.inner().address();
+    info!(network_id = deployment.network_id, name = %deployment.name, symbol = %deployment.symbol, owner = ?owner, "Deploying token");
+
+    let (contract, receipt) = ContractFactory::new(abi, bytecode, client)
+        .deploy((
+            deployment.name.clone(),
+            deployment.symbol.clone(),
+            deployment.decimals,
+            deployment.initial_supply,
+            owner,
+        ))
+        .map_err(|e| validation_error(&format!("Failed to encode token deployment: {e}")))?
+        .send_with_receipt()
+        .await
+        .map_err(|e| validation_error(&format!("Token deployment failed: {e}")))?;
+
+    let token = DeployedToken {
+        network_id: deployment.network_id,
+        address: contract.address(),
+        name: deployment.name.clone(),
+        symbol: deployment.symbol.clone(),
+        decimals: deployment.decimals,
+        owner,
+        tx_hash: receipt.transaction_hash,
+        deployed_at: chrono::Utc::now().timestamp() as u64,
+    };
+    registry.record(token.clone())?;
+    info!(address = ?token.address, tx_hash = ?token.tx_hash, "Token deployed");
+    Ok(token)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn token(network_id: u64, byte: u8, symbol: &str) -> DeployedToken {
+        DeployedToken {
+            network_id,
+            address: Address::repeat_byte(byte),
+            name: format!("{symbol} Token"),
+            symbol: symbol.to_string(),
+            decimals: 6,
+            owner: Address::repeat_byte(0xf3),
+            tx_hash: H256::repeat_byte(byte),
+            deployed_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_token_abi() {
+        let abi = parse_abi(TOKEN_ABI).unwrap();
+        assert_eq!(abi.constructor().unwrap().inputs.len(), 5);
+        assert!(abi.function("mint").is_ok());
+    }
+
+    #[test]
+    fn test_registry_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = TokenRegistry::new(dir.path().join(".aggsandbox").join("tokens.json"));
+        assert!(registry.list().is_empty());
+
+        registry.record(token(0, 0x11, "USDC")).unwrap();
+        registry.record(token(1, 0x22, "USDC")).unwrap();
+        registry.record(token(0, 0x11, "USDC")).unwrap();
+
+        assert_eq!(registry.list().len(), 2);
+        assert!(registry.contains(0, Address::repeat_byte(0x11)));
+        assert!(!registry.contains(1, Address::repeat_byte(0x11)));
+        assert_eq!(
+            registry.find(1, "usdc").unwrap().address,
+            Address::repeat_byte(0x22)
+        );
+        assert_eq!(
+            registry
+                .find(0, &format!("{:#x}", Address::repeat_byte(0x11)))
+                .unwrap()
+                .symbol,
+            "USDC"
+        );
+        assert!(registry.find(2, "USDC").is_none());
+    }
+}
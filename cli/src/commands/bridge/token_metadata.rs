@@ -0,0 +1,593 @@
+//! Wrapped token metadata sync checks
+//!
+//! Compares the name, symbol and decimals of wrapped tokens deployed by the
+//! bridge on a destination network against their origin tokens, flagging
+//! mismatches and the placeholder values used when origin metadata could not
+//! be fetched (e.g. "Wrapped Token"/"WT" from precalculate flows).
+//!
+//! Claims and amount formatting read metadata through [`cached_token_metadata`],
+//! which keeps it per network and token in memory for the running command and
+//! in `.aggsandbox/token_metadata.json` across commands, so the three ERC20
+//! calls are made once per token, not once per claim. `aggsandbox token info`
+//! shows the cached metadata of a token and its wrapped counterparts.
+
+use crate::api_client::OptimizedApiClient;
+use crate::config::Config;
+use crate::error::Result;
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::debug;
+
+use super::common::{contract, rpc_error, validate_address, validate_network_id, validation_error};
+use super::{
+    get_bridge_contract_address, get_provider, get_wallet_with_provider, BridgeContract,
+    ERC20Contract,
+};
+
+const CACHE_FILE: &str = ".aggsandbox/token_metadata.json";
+
+/// Metadata read by the running command, in front of the on-disk cache
+#[allow(clippy::disallowed_types)] // Only locked briefly, never across an await
+static MEMORY: std::sync::Mutex<BTreeMap<String, TokenMetadata>> =
+    std::sync::Mutex::new(BTreeMap::new());
+
+/// Placeholder (name, symbol) pairs used when origin metadata is unavailable
+const PLACEHOLDER_METADATA: &[(&str, &str)] = &[("Wrapped Token", "WT"), ("AggERC20", "AGGERC20")];
+
+/// ERC20 metadata as read from a token contract
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenMetadata {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+}
+
+/// Result of comparing one wrapped token against its origin token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenMetadataCheck {
+    pub origin_network: u32,
+    pub origin_token_address: String,
+    pub wrapped_token_address: String,
+    pub origin: TokenMetadata,
+    pub wrapped: TokenMetadata,
+    pub issues: Vec<String>,
+}
+
+/// Cached metadata of a token on one network and its wrapped counterparts
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenInfo {
+    pub network: u64,
+    pub address: Address,
+    pub metadata: TokenMetadata,
+    /// Whether the metadata was cached before this lookup
+    pub cached: bool,
+    /// Whether the token was deployed by the bridge for an origin token
+    pub wrapped: bool,
+    pub origin_network: u64,
+    pub origin_address: Address,
+    /// Wrapped tokens of the origin token on the other networks
+    pub counterparts: Vec<WrappedCounterpart>,
+}
+
+/// Wrapped token deployed by the bridge of a network
+#[derive(Debug, Clone, Serialize)]
+pub struct WrappedCounterpart {
+    pub network: u64,
+    pub address: Address,
+    pub metadata: TokenMetadata,
+}
+
+impl TokenMetadata {
+    /// Whether every field could be read
+    pub fn is_complete(&self) -> bool {
+        self.name.is_some() && self.symbol.is_some() && self.decimals.is_some()
+    }
+
+    /// Metadata as encoded in the `metadata` field of a bridge leaf
+    ///
+    /// Unreadable fields fall back to the values the bridge service uses.
+    pub fn encode(&self) -> Vec<u8> {
+        use ethers::abi::{encode, Token};
+        encode(&[
+            Token::String(self.name.clone().unwrap_or_else(|| "AggERC20".to_string())),
+            Token::String(
+                self.symbol
+                    .clone()
+                    .unwrap_or_else(|| "AGGERC20".to_string()),
+            ),
+            Token::Uint(U256::from(self.decimals.unwrap_or(18))),
+        ])
+    }
+}
+
+/// Token metadata cached per network and token address
+///
+/// Only complete metadata is cached, so a token whose calls failed or were
+/// throttled is read again next time. `aggsandbox stop` clears the cache
+/// since a new chain may deploy different tokens at the same addresses.
+#[derive(Debug, Clone)]
+pub struct TokenMetadataCache {
+    path: PathBuf,
+}
+
+impl TokenMetadataCache {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Cache at `.aggsandbox/token_metadata.json` of the working directory
+    pub fn default_location() -> Self {
+        Self::new(CACHE_FILE)
+    }
+
+    fn key(network: u64, token_address: Address) -> String {
+        format!("{network}:{token_address:#x}")
+    }
+
+    fn load(&self) -> BTreeMap<String, TokenMetadata> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn get(&self, network: u64, token_address: Address) -> Option<TokenMetadata> {
+        self.load().remove(&Self::key(network, token_address))
+    }
+
+    /// Cache `metadata` when it is complete
+    ///
+    /// A failed write only costs the RPC calls next time, so it is logged.
+    #[allow(clippy::disallowed_methods)] // Allow tracing macros
+    pub fn insert(&self, network: u64, token_address: Address, metadata: &TokenMetadata) {
+        if !metadata.is_complete() {
+            return;
+        }
+        let mut entries = self.load();
+        entries.insert(Self::key(network, token_address), metadata.clone());
+
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(&entries).map_err(|e| e.to_string()))
+            .and_then(|json| fs::write(&self.path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            debug!(path = %self.path.display(), error = %e, "Failed to cache token metadata");
+        }
+    }
+
+    pub fn clear(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Metadata already read by the running command
+fn remembered(network: u64, token_address: Address) -> Option<TokenMetadata> {
+    let key = TokenMetadataCache::key(network, token_address);
+    MEMORY.lock().ok()?.get(&key).cloned()
+}
+
+/// Keep complete metadata for the rest of the running command
+fn remember(network: u64, token_address: Address, metadata: &TokenMetadata) {
+    if !metadata.is_complete() {
+        return;
+    }
+    if let Ok(mut memory) = MEMORY.lock() {
+        memory.insert(
+            TokenMetadataCache::key(network, token_address),
+            metadata.clone(),
+        );
+    }
+}
+
+impl TokenMetadataCheck {
+    /// Whether the wrapped token metadata matches its origin token
+    pub fn is_in_sync(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Arguments for checking wrapped token metadata
+#[derive(Debug, Clone)]
+pub struct CheckMetadataArgs<'a> {
+    pub config: &'a Config,
+    pub network: u64,
+    pub wrapped_token_address: Option<&'a str>,
+    pub private_key: Option<&'a str>,
+}
+
+/// Compare origin and wrapped token metadata, returning a description of every discrepancy
+pub fn compare_token_metadata(origin: &TokenMetadata, wrapped: &TokenMetadata) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    if wrapped.name.is_none() || wrapped.symbol.is_none() || wrapped.decimals.is_none() {
+        issues.push("Wrapped token metadata could not be read".to_string());
+    }
+    if origin.name.is_none() || origin.symbol.is_none() || origin.decimals.is_none() {
+        issues.push("Origin token metadata could not be read".to_string());
+    }
+
+    if let (Some(name), Some(symbol)) = (&wrapped.name, &wrapped.symbol) {
+        let is_placeholder =
+            PLACEHOLDER_METADATA
+                .iter()
+                .any(|(placeholder_name, placeholder_symbol)| {
+                    name == placeholder_name && symbol == placeholder_symbol
+                });
+        let origin_is_placeholder =
+            origin.name.as_ref() == Some(name) && origin.symbol.as_ref() == Some(symbol);
+        if is_placeholder && !origin_is_placeholder {
+            issues.push(format!(
+                "Wrapped token uses placeholder metadata \"{name}\"/\"{symbol}\""
+            ));
+        }
+    }
+
+    let mut compare = |field: &str, origin_value: Option<String>, wrapped_value: Option<String>| {
+        if let (Some(origin_value), Some(wrapped_value)) = (origin_value, wrapped_value) {
+            if origin_value != wrapped_value {
+                issues.push(format!(
+                    "{field} mismatch: origin \"{origin_value}\", wrapped \"{wrapped_value}\""
+                ));
+            }
+        }
+    };
+    compare("Name", origin.name.clone(), wrapped.name.clone());
+    compare("Symbol", origin.symbol.clone(), wrapped.symbol.clone());
+    compare(
+        "Decimals",
+        origin.decimals.map(|d| d.to_string()),
+        wrapped.decimals.map(|d| d.to_string()),
+    );
+
+    issues
+}
+
+/// Read ERC20 metadata from a token on the given network
+pub async fn fetch_token_metadata(
+    config: &Config,
+    network: u64,
+    token_address: Address,
+    private_key: Option<&str>,
+) -> Result<TokenMetadata> {
+    let client = get_wallet_with_provider(config, network, private_key).await?;
+    let token = ERC20Contract::new(token_address, Arc::new(client));
+
+    Ok(TokenMetadata {
+        name: token.name().call().await.ok(),
+        symbol: token.symbol().call().await.ok(),
+        decimals: token.decimals().call().await.ok(),
+    })
+}
+
+/// Whether metadata of a token is cached in memory or on disk
+pub fn is_cached(network: u64, token_address: Address) -> bool {
+    remembered(network, token_address).is_some()
+        || TokenMetadataCache::default_location()
+            .get(network, token_address)
+            .is_some()
+}
+
+/// ERC20 metadata of a token, from the cache when it was read before
+pub async fn cached_token_metadata(
+    config: &Config,
+    network: u64,
+    token_address: Address,
+) -> Result<TokenMetadata> {
+    if let Some(metadata) = remembered(network, token_address) {
+        return Ok(metadata);
+    }
+    let cache = TokenMetadataCache::default_location();
+    if let Some(metadata) = cache.get(network, token_address) {
+        debug!(network, %token_address, "Using cached token metadata");
+        remember(network, token_address, &metadata);
+        return Ok(metadata);
+    }
+
+    let token = ERC20Contract::new(token_address, get_provider(config, network).await?);
+    let metadata = TokenMetadata {
+        name: token.name().call().await.ok(),
+        symbol: token.symbol().call().await.ok(),
+        decimals: token.decimals().call().await.ok(),
+    };
+    cache.insert(network, token_address, &metadata);
+    remember(network, token_address, &metadata);
+    Ok(metadata)
+}
+
+/// Metadata and wrapped counterparts of a token on every network holding code at `address`
+pub async fn token_info(
+    config: &Config,
+    address: Address,
+    networks: &[u64],
+) -> Result<Vec<TokenInfo>> {
+    let mut infos = Vec::new();
+    for &network in networks {
+        let provider = get_provider(config, network).await?;
+        let has_code = provider
+            .get_code(address, None)
+            .await
+            .map(|code| !code.is_empty())
+            .unwrap_or(false);
+        if has_code {
+            infos.push(network_token_info(config, network, address).await?);
+        }
+    }
+    Ok(infos)
+}
+
+async fn network_token_info(config: &Config, network: u64, address: Address) -> Result<TokenInfo> {
+    let cached = is_cached(network, address);
+    let metadata = cached_token_metadata(config, network, address).await?;
+
+    let bridge = BridgeContract::new(
+        get_bridge_contract_address(config, network)?,
+        get_provider(config, network).await?,
+    );
+    let (origin_network, origin_token) = bridge
+        .wrapped_token_to_token_info(address)
+        .call()
+        .await
+        .map_err(|e| rpc_error("get origin token info", e))?;
+    let wrapped = !origin_token.is_zero();
+    let (origin_network, origin_address) = if wrapped {
+        (u64::from(origin_network), origin_token)
+    } else {
+        (network, address)
+    };
+
+    let mut counterparts = Vec::new();
+    for other in config.networks.network_ids() {
+        if other == network || other == origin_network {
+            continue;
+        }
+        let Ok(bridge_address) = get_bridge_contract_address(config, other) else {
+            continue;
+        };
+        let bridge = BridgeContract::new(bridge_address, get_provider(config, other).await?);
+        let counterpart = match bridge
+            .get_token_wrapped_address(origin_network as u32, origin_address)
+            .call()
+            .await
+        {
+            Ok(counterpart) if !counterpart.is_zero() => counterpart,
+            Ok(_) => continue,
+            Err(e) => {
+                debug!(network = other, error = %e, "Failed to get wrapped token address");
+                continue;
+            }
+        };
+        counterparts.push(WrappedCounterpart {
+            network: other,
+            address: counterpart,
+            metadata: cached_token_metadata(config, other, counterpart).await?,
+        });
+    }
+
+    Ok(TokenInfo {
+        network,
+        address,
+        metadata,
+        cached,
+        wrapped,
+        origin_network,
+        origin_address,
+        counterparts,
+    })
+}
+
+/// Check wrapped token metadata on a network against the origin tokens
+///
+/// When no wrapped token is given, every ERC20 bridged to the network (as seen
+/// by the bridge service) that already has a wrapped token deployed is checked.
+pub async fn check_wrapped_token_metadata(
+    args: CheckMetadataArgs<'_>,
+) -> Result<Vec<TokenMetadataCheck>> {
+    validate_network_id(args.network, "Network")?;
+    let bridge_contract =
+        contract::get_bridge_contract(args.config, args.network, args.private_key).await?;
+
+    let token_pairs = if let Some(wrapped) = args.wrapped_token_address {
+        let wrapped_address = validate_address(wrapped, "Wrapped token address")?;
+        let (origin_network, origin_token) = bridge_contract
+            .wrapped_token_to_token_info(wrapped_address)
+            .call()
+            .await
+            .map_err(|e| rpc_error("get origin token info", e))?;
+        if origin_token.is_zero() {
+            return Err(validation_error(&format!(
+                "{wrapped} is not a wrapped token on network {}",
+                args.network
+            )));
+        }
+        vec![(origin_network, origin_token, wrapped_address)]
+    } else {
+        let mut pairs = Vec::new();
+        for (origin_network, origin_token) in
+            discover_bridged_tokens(args.config, args.network).await?
+        {
+            let wrapped_address = bridge_contract
+                .get_token_wrapped_address(origin_network, origin_token)
+                .call()
+                .await
+                .map_err(|e| rpc_error("get wrapped token address", e))?;
+            if wrapped_address.is_zero() {
+                debug!(%origin_token, origin_network, "Wrapped token not deployed yet, skipping");
+                continue;
+            }
+            pairs.push((origin_network, origin_token, wrapped_address));
+        }
+        pairs
+    };
+
+    let mut checks = Vec::with_capacity(token_pairs.len());
+    for (origin_network, origin_token, wrapped_address) in token_pairs {
+        let origin = fetch_token_metadata(
+            args.config,
+            origin_network as u64,
+            origin_token,
+            args.private_key,
+        )
+        .await?;
+        let wrapped =
+            fetch_token_metadata(args.config, args.network, wrapped_address, args.private_key)
+                .await?;
+        let issues = compare_token_metadata(&origin, &wrapped);
+
+        checks.push(TokenMetadataCheck {
+            origin_network,
+            origin_token_address: format!("{origin_token:#x}"),
+            wrapped_token_address: format!("{wrapped_address:#x}"),
+            origin,
+            wrapped,
+            issues,
+        });
+    }
+
+    Ok(checks)
+}
+
+/// Collect (origin network, origin token) pairs of ERC20 deposits destined for `network`
+pub async fn discover_bridged_tokens(
+    config: &Config,
+    network: u64,
+) -> Result<BTreeSet<(u32, Address)>> {
+    let api_client = OptimizedApiClient::global();
+    let source_networks = config.networks.network_ids();
+
+    let mut tokens = BTreeSet::new();
+    for source_network in source_networks.into_iter().filter(|n| *n != network) {
+        let response = match api_client.get_bridges(config, source_network).await {
+            Ok(response) => response,
+            Err(e) => {
+                debug!(source_network, error = %e, "Skipping network without bridge data");
+                continue;
+            }
+        };
+
+        for bridge in response["bridges"].as_array().into_iter().flatten() {
+            let is_asset = bridge["leaf_type"].as_u64() == Some(0);
+            let to_network = bridge["destination_network"].as_u64() == Some(network);
+            let origin_network = bridge["origin_network"].as_u64();
+            let origin_token = bridge["origin_address"]
+                .as_str()
+                .and_then(|addr| addr.parse::<Address>().ok());
+
+            if let (true, true, Some(origin_network), Some(origin_token)) =
+                (is_asset, to_network, origin_network, origin_token)
+            {
+                // Native ETH and tokens that originate on this network are never wrapped here
+                if !origin_token.is_zero() && origin_network != network {
+                    tokens.insert((origin_network as u32, origin_token));
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn metadata(name: &str, symbol: &str, decimals: u8) -> TokenMetadata {
+        TokenMetadata {
+            name: Some(name.to_string()),
+            symbol: Some(symbol.to_string()),
+            decimals: Some(decimals),
+        }
+    }
+
+    #[test]
+    fn test_matching_metadata_has_no_issues() {
+        let origin = metadata("Test Token", "TT", 18);
+        assert!(compare_token_metadata(&origin, &origin.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_mismatched_metadata_is_reported() {
+        let origin = metadata("Test Token", "TT", 6);
+        let wrapped = metadata("Test Token", "TTX", 18);
+        let issues = compare_token_metadata(&origin, &wrapped);
+
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].starts_with("Symbol mismatch"));
+        assert!(issues[1].starts_with("Decimals mismatch"));
+    }
+
+    #[test]
+    fn test_placeholder_metadata_is_reported() {
+        let origin = metadata("Test Token", "TT", 18);
+        let wrapped = metadata("Wrapped Token", "WT", 18);
+        let issues = compare_token_metadata(&origin, &wrapped);
+
+        assert!(issues.iter().any(|issue| issue.contains("placeholder")));
+    }
+
+    #[test]
+    fn test_cache_keeps_complete_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache =
+            TokenMetadataCache::new(dir.path().join(".aggsandbox").join("token_metadata.json"));
+        let token = Address::repeat_byte(0x11);
+        assert!(cache.get(0, token).is_none());
+
+        cache.insert(0, token, &metadata("Test Token", "TT", 6));
+        cache.insert(
+            1,
+            token,
+            &TokenMetadata {
+                decimals: Some(18),
+                ..TokenMetadata::default()
+            },
+        );
+        assert_eq!(cache.get(0, token), Some(metadata("Test Token", "TT", 6)));
+        // Partial reads are retried instead of cached
+        assert!(cache.get(1, token).is_none());
+
+        cache.clear();
+        assert!(cache.get(0, token).is_none());
+    }
+
+    #[test]
+    fn test_memory_keeps_complete_metadata() {
+        let token = Address::repeat_byte(0x22);
+        remember(7, token, &metadata("Test Token", "TT", 6));
+        remember(
+            8,
+            token,
+            &TokenMetadata {
+                symbol: Some("TT".to_string()),
+                ..TokenMetadata::default()
+            },
+        );
+
+        assert_eq!(remembered(7, token), Some(metadata("Test Token", "TT", 6)));
+        assert!(remembered(8, token).is_none());
+        assert!(remembered(7, Address::repeat_byte(0x23)).is_none());
+    }
+
+    #[test]
+    fn test_encode_falls_back_to_service_defaults() {
+        assert_eq!(
+            TokenMetadata::default().encode(),
+            metadata("AggERC20", "AGGERC20", 18).encode()
+        );
+    }
+
+    #[test]
+    fn test_unreadable_metadata_is_reported() {
+        let origin = metadata("Test Token", "TT", 18);
+        let issues = compare_token_metadata(&origin, &TokenMetadata::default());
+
+        assert_eq!(issues, vec!["Wrapped token metadata could not be read"]);
+    }
+}
@@ -3,18 +3,24 @@
 //! This module provides standalone utility functions that can be used both
 //! programmatically and via CLI commands for bridge operations.
 
+use super::calldata::{decode_calldata, encode_call, parse_calldata, DecodedCall};
 use super::common::{
-    contract, get_network_name, serialize_json, validate_address, validate_network_id,
+    contract, get_network_name, rpc_error, serialize_json, validate_address, validate_network_id,
     validation_error,
 };
-use super::{get_wallet_with_provider, ERC20Contract};
+use super::offline_claim::export_payload;
+use super::proof::{claim_path, print_claim_path, print_verification, verify_claim_proof};
+use super::token_metadata::{
+    cached_token_metadata, check_wrapped_token_metadata, CheckMetadataArgs, TokenMetadata,
+    TokenMetadataCheck,
+};
 use crate::api_client::{CacheConfig, OptimizedApiClient};
 use crate::config::Config;
 use crate::error::Result;
 use crate::ui::{OutputFormat, UI};
+use crate::validation::{parse_int, parse_network_id, Validator};
 use ethers::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
 use tracing::info;
 
 /// Arguments for building claim payloads from transaction hashes
@@ -40,6 +46,12 @@ pub struct ClaimPayload {
     pub destination_address: String,
     pub amount: String,
     pub metadata: String,
+    /// 0 for asset deposits, 1 for messages
+    #[serde(default)]
+    pub leaf_type: u8,
+    /// Global exit root of the L1 info tree leaf the proof was built against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub global_exit_root: Option<String>,
 }
 
 /// Arguments for computing global bridge indices
@@ -102,6 +114,16 @@ pub struct ComputeIndexOutput {
     pub global_index: String,
 }
 
+/// JSON output structure for decode index
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DecodeIndexOutput {
+    pub global_index: String,
+    pub mainnet_flag: bool,
+    pub rollup_index: u32,
+    pub local_index: u32,
+    pub source_network: u64,
+}
+
 /// JSON output structure for mapped token info
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MappedTokenOutput {
@@ -136,6 +158,15 @@ pub struct NetworkIdOutput {
     pub contract_network_id: u32,
 }
 
+/// JSON output structure for wrapped token metadata checks
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MetadataCheckOutput {
+    pub network: u64,
+    pub checked: usize,
+    pub out_of_sync: usize,
+    pub tokens: Vec<TokenMetadataCheck>,
+}
+
 /// Build complete claim payload from transaction hash
 ///
 /// Extracts logic from claim_asset.rs to build the complete payload needed for claiming
@@ -149,96 +180,43 @@ pub async fn build_payload_for_claim(args: BuildPayloadArgs<'_>) -> Result<Claim
     let proof_source_network = if args.source_network == 0 { 1 } else { 0 };
 
     // Get bridges from the network where the transaction actually occurred
-    let bridges_response = api_client
-        .get_bridges(args.config, bridge_tx_network)
-        .await
-        .map_err(|e| validation_error(&format!("Failed to get bridges: {e}")))?;
-
-    let bridges = bridges_response["bridges"]
-        .as_array()
-        .ok_or_else(|| validation_error("Invalid bridges response"))?;
+    let deposits = api_client
+        .get_bridge_deposits(args.config, bridge_tx_network)
+        .await?;
+    let tx_hash = args
+        .tx_hash
+        .parse::<H256>()
+        .map_err(|e| validation_error(&format!("Invalid transaction hash: {e}")))?;
 
     // Find our bridge transaction
-    let bridge_info = if let Some(specific_deposit_count) = args.bridge_index {
-        bridges
-            .iter()
-            .find(|bridge| {
-                bridge["bridge_tx_hash"].as_str() == Some(args.tx_hash)
-                    && bridge["deposit_count"].as_u64() == Some(specific_deposit_count)
-            })
-            .ok_or_else(|| {
-                validation_error(&format!(
-                    "Bridge transaction {} with deposit_count {specific_deposit_count} not found",
-                    args.tx_hash
-                ))
-            })?
-    } else {
-        bridges
-            .iter()
-            .find(|bridge| bridge["bridge_tx_hash"].as_str() == Some(args.tx_hash))
-            .ok_or_else(|| {
-                validation_error(&format!("Bridge transaction {} not found", args.tx_hash))
-            })?
-    };
-
-    let deposit_count = bridge_info["deposit_count"]
-        .as_u64()
-        .ok_or_else(|| validation_error("Missing deposit_count in bridge info"))?;
+    let deposit = deposits
+        .iter()
+        .find(|deposit| {
+            deposit.bridge_tx_hash == tx_hash
+                && args
+                    .bridge_index
+                    .is_none_or(|deposit_count| deposit.deposit_count == deposit_count)
+        })
+        .ok_or_else(|| match args.bridge_index {
+            Some(deposit_count) => validation_error(&format!(
+                "Bridge transaction {} with deposit_count {deposit_count} not found",
+                args.tx_hash
+            )),
+            None => validation_error(&format!("Bridge transaction {} not found", args.tx_hash)),
+        })?;
+    let deposit_count = deposit.deposit_count;
 
     // Get L1 info tree index from the proof source network
-    let tree_index_response = api_client
-        .get_l1_info_tree_index(args.config, proof_source_network, deposit_count)
-        .await
-        .map_err(|e| validation_error(&format!("Failed to get L1 info tree index: {e}")))?;
-
-    let leaf_index = tree_index_response["l1_info_tree_index"]
-        .as_u64()
-        .unwrap_or(tree_index_response.as_u64().unwrap_or(0));
+    let leaf_index = api_client
+        .get_typed_l1_info_tree_index(args.config, proof_source_network, deposit_count)
+        .await?;
 
     // Get claim proof from the proof source network
-    let proof_response = api_client
-        .get_claim_proof(args.config, proof_source_network, leaf_index, deposit_count)
-        .await
-        .map_err(|e| validation_error(&format!("Failed to get claim proof: {e}")))?;
-
-    let l1_info_tree_leaf = &proof_response["l1_info_tree_leaf"];
-    let mainnet_exit_root = l1_info_tree_leaf["mainnet_exit_root"]
-        .as_str()
-        .ok_or_else(|| validation_error("Missing mainnet_exit_root in proof"))?;
-
-    let rollup_exit_root = l1_info_tree_leaf["rollup_exit_root"]
-        .as_str()
-        .ok_or_else(|| validation_error("Missing rollup_exit_root in proof"))?;
-
-    // Extract SMT proofs
-    let smt_proof = proof_response["smt_proof"]
-        .as_array()
-        .map(|arr| {
-            arr.iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect()
-        })
-        .unwrap_or_default();
-
-    let smt_proof_rollup = proof_response["smt_proof_rollup"].as_array().map(|arr| {
-        arr.iter()
-            .filter_map(|v| v.as_str().map(|s| s.to_string()))
-            .collect()
-    });
-
-    // Extract bridge parameters
-    let origin_network = bridge_info["origin_network"].as_u64().unwrap_or(0) as u32;
-    let origin_token_address = bridge_info["origin_address"]
-        .as_str()
-        .unwrap_or("0x0000000000000000000000000000000000000000")
-        .to_string();
-    let destination_network = bridge_info["destination_network"].as_u64().unwrap_or(0) as u32;
-    let destination_address = bridge_info["destination_address"]
-        .as_str()
-        .unwrap_or("0x0000000000000000000000000000000000000000")
-        .to_string();
-    let amount = bridge_info["amount"].as_str().unwrap_or("0").to_string();
-    let metadata = bridge_info["metadata"].as_str().unwrap_or("0x").to_string();
+    let proof = api_client
+        .get_typed_claim_proof(args.config, proof_source_network, leaf_index, deposit_count)
+        .await?;
+    let hex_roots =
+        |roots: &[H256]| -> Vec<String> { roots.iter().map(|root| format!("{root:#x}")).collect() };
 
     // Compute global index
     let global_index_args = ComputeGlobalIndexArgs {
@@ -248,17 +226,26 @@ pub async fn build_payload_for_claim(args: BuildPayloadArgs<'_>) -> Result<Claim
     let global_index = compute_global_index(global_index_args);
 
     Ok(ClaimPayload {
-        smt_proof,
-        smt_proof_rollup,
+        smt_proof: hex_roots(&proof.smt_proof),
+        smt_proof_rollup: proof.smt_proof_rollup.as_deref().map(hex_roots),
         global_index: global_index.to_string(),
-        mainnet_exit_root: mainnet_exit_root.to_string(),
-        rollup_exit_root: rollup_exit_root.to_string(),
-        origin_network,
-        origin_token_address,
-        destination_network,
-        destination_address,
-        amount,
-        metadata,
+        mainnet_exit_root: format!("{:#x}", proof.l1_info_tree_leaf.mainnet_exit_root),
+        rollup_exit_root: format!("{:#x}", proof.l1_info_tree_leaf.rollup_exit_root),
+        origin_network: deposit
+            .origin_network
+            .ok_or_else(|| validation_error("Bridge info has no origin_network"))?,
+        origin_token_address: format!("{:#x}", deposit.origin_address),
+        destination_network: deposit
+            .destination_network
+            .ok_or_else(|| validation_error("Bridge info has no destination_network"))?,
+        destination_address: format!("{:#x}", deposit.destination_address),
+        amount: deposit.amount.to_string(),
+        metadata: format!("0x{}", hex::encode(&deposit.metadata)),
+        leaf_type: deposit.leaf_type,
+        global_exit_root: proof
+            .l1_info_tree_leaf
+            .global_exit_root()
+            .map(|root| format!("{root:#x}")),
     })
 }
 
@@ -278,6 +265,30 @@ pub fn compute_global_index(args: ComputeGlobalIndexArgs) -> U256 {
     }
 }
 
+/// Decode a global index into (source network ID, local deposit index)
+///
+/// Inverse of [`compute_global_index`]: bit 64 marks mainnet deposits, otherwise
+/// bits 32..64 hold the rollup index (network ID - 1).
+pub fn decode_global_index(global_index: U256) -> (u64, u32) {
+    let local_index = (global_index & U256::from(u32::MAX)).as_u32();
+    if global_index.bit(64) {
+        (0, local_index)
+    } else {
+        let rollup_index = ((global_index >> 32) & U256::from(u32::MAX)).as_u64();
+        (rollup_index + 1, local_index)
+    }
+}
+
+/// Split a global index into (mainnet flag, rollup index, local index)
+///
+/// Unlike [`decode_global_index`] the rollup index bits are returned as they
+/// are, so indices that set both the mainnet flag and a rollup index show up.
+pub fn global_index_parts(global_index: U256) -> (bool, u32, u32) {
+    let local_index = (global_index & U256::from(u32::MAX)).as_u32();
+    let rollup_index = ((global_index >> 32) & U256::from(u32::MAX)).as_u32();
+    (global_index.bit(64), rollup_index, local_index)
+}
+
 /// Get wrapped token address for an origin token
 pub async fn get_mapped_token_info(args: MappedTokenArgs<'_>) -> Result<Address> {
     let origin_token_address = validate_address(args.origin_token_address, "Origin token address")?;
@@ -288,7 +299,7 @@ pub async fn get_mapped_token_info(args: MappedTokenArgs<'_>) -> Result<Address>
         .get_token_wrapped_address(args.origin_network, origin_token_address)
         .call()
         .await
-        .map_err(|e| validation_error(&format!("Failed to get wrapped token address: {e}")))?;
+        .map_err(|e| rpc_error("get wrapped token address", e))?;
 
     Ok(wrapped_address)
 }
@@ -300,22 +311,15 @@ pub async fn precalculated_mapped_token_info(args: PrecalculatedTokenArgs<'_>) -
         contract::get_bridge_contract(args.config, args.network, args.private_key).await?;
 
     // Get token metadata from the origin network
-    let origin_client =
-        get_wallet_with_provider(args.config, args.origin_network as u64, args.private_key).await?;
-    let token_contract = ERC20Contract::new(origin_token_address, Arc::new(origin_client));
-
-    // Fetch real token metadata from the origin token contract
-    let token_name = token_contract
-        .name()
-        .call()
-        .await
-        .unwrap_or_else(|_| "Wrapped Token".to_string());
-    let token_symbol = token_contract
-        .symbol()
-        .call()
-        .await
-        .unwrap_or_else(|_| "WT".to_string());
-    let token_decimals = token_contract.decimals().call().await.unwrap_or(18u8);
+    let metadata = cached_token_metadata(
+        args.config,
+        args.origin_network as u64,
+        origin_token_address,
+    )
+    .await?;
+    let token_name = metadata.name.unwrap_or_else(|| "Wrapped Token".to_string());
+    let token_symbol = metadata.symbol.unwrap_or_else(|| "WT".to_string());
+    let token_decimals = metadata.decimals.unwrap_or(18);
 
     info!(
         token_address = %args.origin_token_address,
@@ -357,7 +361,7 @@ pub async fn get_origin_token_info(args: OriginTokenArgs<'_>) -> Result<OriginTo
         .wrapped_token_to_token_info(wrapped_token_address)
         .call()
         .await
-        .map_err(|e| validation_error(&format!("Failed to get origin token info: {e}")))?;
+        .map_err(|e| rpc_error("get origin token info", e))?;
 
     Ok(OriginTokenInfo {
         origin_network,
@@ -371,10 +375,7 @@ pub async fn is_claimed(args: IsClaimedArgs<'_>) -> Result<bool> {
 
     // Use the AggKit API claims data instead of contract call to avoid contract state issues
     let api_client = OptimizedApiClient::new(CacheConfig::default());
-    let claims_response = api_client
-        .get_claims(args.config, args.network)
-        .await
-        .map_err(|e| validation_error(&format!("Failed to get claims: {e}")))?;
+    let claims_response = api_client.get_claims(args.config, args.network).await?;
 
     let claims = claims_response["claims"]
         .as_array()
@@ -445,11 +446,24 @@ pub async fn get_network_id(args: NetworkIdArgs<'_>) -> Result<u32> {
         .network_id()
         .call()
         .await
-        .map_err(|e| validation_error(&format!("Failed to get network ID: {e}")))?;
+        .map_err(|e| rpc_error("get network ID", e))?;
 
     Ok(network_id)
 }
 
+/// Format token metadata as `name (symbol, N decimals)` for display
+fn format_token_metadata(metadata: &TokenMetadata) -> String {
+    format!(
+        "{} ({}, {} decimals)",
+        metadata.name.as_deref().unwrap_or("<unreadable>"),
+        metadata.symbol.as_deref().unwrap_or("<unreadable>"),
+        metadata
+            .decimals
+            .map(|d| d.to_string())
+            .unwrap_or_else(|| "?".to_string())
+    )
+}
+
 /// Bridge utility commands
 #[derive(Debug, clap::Subcommand)]
 pub enum UtilityCommands {
@@ -458,16 +472,36 @@ pub enum UtilityCommands {
     /// Extract complete claim payload data from a bridge transaction hash.
     /// This includes SMT proofs, exit roots, and all parameters needed for claiming.
     ///
+    /// With --show-path the proof is recomputed locally and drawn from the deposit
+    /// leaf through the exit trees to the global exit root, highlighting the first
+    /// node that does not match the roots the proof was built against.
+    ///
     /// Examples:
     ///   aggsandbox bridge utils build-payload -t 0xabc123... -s 0
     ///   aggsandbox bridge utils build-payload -t 0xdef456... -s 0 --bridge-index 1 --json
+    ///   aggsandbox bridge utils build-payload -t 0xabc123... -s 1 --show-path
+    ///   aggsandbox bridge utils build-payload -t 0xabc123... -s 0 --export claim.json
+    ///
+    /// With --export the payload is also written to a file that
+    /// `bridge claim --from-payload` claims without querying the bridge service.
     BuildPayload {
         #[arg(short, long, help = "Bridge transaction hash")]
         tx_hash: String,
-        #[arg(short = 's', long, help = "Source network ID")]
+        #[arg(short = 's', long, value_parser = parse_network_id, help = "Source network ID")]
         source_network_id: u64,
-        #[arg(long, help = "Bridge index for multi-bridge transactions")]
+        #[arg(long, value_parser = parse_int::<u64>, help = "Bridge index for multi-bridge transactions")]
         bridge_index: Option<u64>,
+        #[arg(
+            long,
+            help = "Recompute the proof and show its path to the global exit root"
+        )]
+        show_path: bool,
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "Write the payload to a JSON file for bridge claim --from-payload"
+        )]
+        export: Option<std::path::PathBuf>,
         #[arg(long, help = "Output as JSON")]
         json: bool,
     },
@@ -481,14 +515,30 @@ pub enum UtilityCommands {
     ///   aggsandbox bridge utils compute-index --local-index 42 --source-network-id 0
     ///   aggsandbox bridge utils compute-index --local-index 100 --source-network-id 1 --json
     ComputeIndex {
-        #[arg(long, help = "Local deposit index")]
+        #[arg(long, value_parser = parse_int::<u64>, help = "Local deposit index")]
         local_index: u64,
-        #[arg(short = 's', long, help = "Source network ID")]
+        #[arg(short = 's', long, value_parser = parse_network_id, help = "Source network ID")]
         source_network_id: u64,
         #[arg(long, help = "Output as JSON")]
         json: bool,
     },
 
+    /// Decode a global index
+    ///
+    /// Split a global index, as found in ClaimEvent logs and claim calls, into the
+    /// mainnet flag (bit 64), the rollup index (bits 32..64) and the local leaf
+    /// index (bits 0..32). The inverse of compute-index.
+    ///
+    /// Examples:
+    ///   aggsandbox bridge utils decode-index 18446744073709551658
+    ///   aggsandbox bridge utils decode-index 0x10000002a --json
+    DecodeIndex {
+        #[arg(help = "Global index (decimal or 0x hex)")]
+        global_index: String,
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+
     /// Get wrapped token address
     ///
     /// Query the bridge contract to get the wrapped token address for an origin token.
@@ -498,7 +548,7 @@ pub enum UtilityCommands {
     ///   aggsandbox bridge utils get-mapped -n 1 --origin-network 0 --origin-token 0xA0b86a33E6776e39e6b37ddEC4F25B04Dd9Fc4DC
     ///   aggsandbox bridge utils get-mapped -n 1 --origin-network 0 --origin-token 0xA0b86a33E6776e39e6b37ddEC4F25B04Dd9Fc4DC --json
     GetMapped {
-        #[arg(short = 'n', long, help = "Target network ID")]
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Target network ID")]
         network_id: u64,
         #[arg(long, help = "Origin network ID")]
         origin_network: u32,
@@ -519,7 +569,7 @@ pub enum UtilityCommands {
     ///   aggsandbox bridge utils precalculate -n 1 --origin-network 0 --origin-token 0xA0b86a33E6776e39e6b37ddEC4F25B04Dd9Fc4DC
     ///   aggsandbox bridge utils precalculate -n 1 --origin-network 0 --origin-token 0xA0b86a33E6776e39e6b37ddEC4F25B04Dd9Fc4DC --json
     Precalculate {
-        #[arg(short = 'n', long, help = "Target network ID")]
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Target network ID")]
         network_id: u64,
         #[arg(long, help = "Origin network ID")]
         origin_network: u32,
@@ -540,7 +590,7 @@ pub enum UtilityCommands {
     ///   aggsandbox bridge utils get-origin -n 1 --wrapped-token 0x742d35Cc6965C592342c6c16fb8eaeb90a23b5C0
     ///   aggsandbox bridge utils get-origin -n 1 --wrapped-token 0x742d35Cc6965C592342c6c16fb8eaeb90a23b5C0 --json
     GetOrigin {
-        #[arg(short = 'n', long, help = "Network ID")]
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID")]
         network_id: u64,
         #[arg(long, help = "Wrapped token address")]
         wrapped_token: String,
@@ -559,11 +609,11 @@ pub enum UtilityCommands {
     ///   aggsandbox bridge utils is-claimed -n 1 --index 0 --source-network 0
     ///   aggsandbox bridge utils is-claimed -n 1 --index 0 --source-network 0 --json
     IsClaimed {
-        #[arg(short = 'n', long, help = "Network ID")]
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID")]
         network_id: u64,
-        #[arg(long, help = "Bridge deposit index (deposit_count from bridge data)")]
+        #[arg(long, value_parser = parse_int::<u32>, help = "Bridge deposit index (deposit_count from bridge data)")]
         index: u32,
-        #[arg(long, help = "Source bridge network ID")]
+        #[arg(long, value_parser = parse_network_id, help = "Source bridge network ID")]
         source_network_id: u64,
         #[arg(long, help = "Output as JSON")]
         json: bool,
@@ -578,24 +628,181 @@ pub enum UtilityCommands {
     ///   aggsandbox bridge utils network-id -n 1
     ///   aggsandbox bridge utils network-id -n 0 --json
     NetworkId {
-        #[arg(short = 'n', long, help = "Network ID")]
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID")]
         network_id: u64,
         #[arg(long, help = "Private key (optional)")]
         private_key: Option<String>,
         #[arg(long, help = "Output as JSON")]
         json: bool,
     },
+
+    /// Check wrapped token metadata against origin tokens
+    ///
+    /// Compare name, symbol and decimals of wrapped tokens on a network with their
+    /// origin tokens and report discrepancies, including placeholder metadata such as
+    /// "Wrapped Token"/"WT". Without --wrapped-token, every ERC20 bridged to the
+    /// network that already has a wrapped token deployed is checked.
+    /// Exits with an error when any discrepancy is found.
+    ///
+    /// Examples:
+    ///   aggsandbox bridge utils check-metadata -n 1
+    ///   aggsandbox bridge utils check-metadata -n 1 --wrapped-token 0x742d35Cc6965C592342c6c16fb8eaeb90a23b5C0
+    ///   aggsandbox bridge utils check-metadata -n 2 --json
+    CheckMetadata {
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID holding the wrapped tokens")]
+        network_id: u64,
+        #[arg(long, help = "Check only this wrapped token address")]
+        wrapped_token: Option<String>,
+        #[arg(long, help = "Private key (optional)")]
+        private_key: Option<String>,
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+
+    /// Verify a claim proof before claiming
+    ///
+    /// Recompute the exit roots from the SMT proof returned by the bridge service,
+    /// compare them with the roots the proof was built against, and check that
+    /// the resulting global exit root is known to the destination network's
+    /// global exit root manager. Exits with an error when the claim would revert.
+    ///
+    /// Examples:
+    ///   aggsandbox bridge utils verify-proof -t 0xabc123... -s 0
+    ///   aggsandbox bridge utils verify-proof -t 0xdef456... -s 1 --bridge-index 3 --json
+    VerifyProof {
+        #[arg(short, long, help = "Bridge transaction hash")]
+        tx_hash: String,
+        #[arg(short = 's', long, value_parser = parse_network_id, help = "Source network ID")]
+        source_network_id: u64,
+        #[arg(long, value_parser = parse_int::<u64>, help = "Bridge index for multi-bridge transactions")]
+        bridge_index: Option<u64>,
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+
+    /// Decode calldata sent to the bridge, bridge extension or an ERC20
+    ///
+    /// Match the function selector of raw calldata, or of the input of a sent
+    /// transaction, against the bridge, bridge extension and ERC20 functions and
+    /// print the function with its decoded arguments. Bytes arguments holding a
+    /// call to one of these functions, such as the callData of bridgeAndCall,
+    /// are decoded as well.
+    ///
+    /// Examples:
+    ///   aggsandbox bridge utils decode-calldata --data 0xa9059cbb...
+    ///   aggsandbox bridge utils decode-calldata -t 0xabc123... -n 0
+    ///   aggsandbox bridge utils decode-calldata -t 0xabc123... -n 1 --json
+    DecodeCalldata {
+        #[arg(
+            long,
+            conflicts_with = "tx_hash",
+            required_unless_present = "tx_hash",
+            help = "Calldata as 0x hex"
+        )]
+        data: Option<String>,
+        #[arg(
+            short,
+            long,
+            requires = "network_id",
+            help = "Decode the input of this transaction"
+        )]
+        tx_hash: Option<String>,
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID the transaction was sent on")]
+        network_id: Option<u64>,
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+
+    /// ABI-encode a function call
+    ///
+    /// Build calldata from a function signature and its arguments, e.g. the
+    /// --data of bridge-and-call. Integers accept decimal or 0x hex; arrays and
+    /// tuples are written as [1,2] and (0xabc...,5). bridge-and-call can also
+    /// encode the call itself with --call and --call-arg.
+    ///
+    /// Examples:
+    ///   aggsandbox bridge utils encode-call "transfer(address,uint256)" 0x70997970C51812dc3A010C7d01b50e0d17dc79C8 100
+    ///   aggsandbox bridge utils encode-call "function setValue(uint256 value)" 0x2a --json
+    EncodeCall {
+        #[arg(help = "Function signature, e.g. transfer(address,uint256)")]
+        signature: String,
+        #[arg(allow_hyphen_values = true, help = "Function arguments in order")]
+        args: Vec<String>,
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+}
+
+/// Calldata to decode: given directly or read from a sent transaction
+pub async fn calldata_input(
+    config: &Config,
+    data: Option<&str>,
+    tx_hash: Option<&str>,
+    network_id: Option<u64>,
+) -> Result<Vec<u8>> {
+    if let Some(data) = data {
+        return parse_calldata(data);
+    }
+    let (Some(tx_hash), Some(network_id)) = (tx_hash, network_id) else {
+        return Err(validation_error(
+            "Pass --data, or --tx-hash with --network-id",
+        ));
+    };
+    let hash = tx_hash
+        .parse::<H256>()
+        .map_err(|_| validation_error(&format!("Invalid transaction hash: {tx_hash}")))?;
+    let provider = super::get_provider(config, network_id).await?;
+    let tx = provider
+        .get_transaction(hash)
+        .await
+        .map_err(|e| rpc_error(&format!("fetch transaction {tx_hash}"), e))?
+        .ok_or_else(|| {
+            validation_error(&format!(
+                "Transaction {tx_hash} not found on network {network_id}"
+            ))
+        })?;
+    Ok(tx.input.to_vec())
+}
+
+/// Print a decoded call and its nested calls as tables
+fn print_decoded_call(ui: &UI, title: &str, call: &DecodedCall) {
+    let selector = format!("0x{}", hex::encode(call.selector));
+    let mut rows = vec![
+        ("Contract", call.contract),
+        ("Function", call.signature.as_str()),
+        ("Selector", selector.as_str()),
+    ];
+    rows.extend(
+        call.arguments
+            .fields
+            .iter()
+            .map(|field| (field.label.as_str(), field.value.as_str())),
+    );
+    ui.table(title, &rows);
+    for note in &call.arguments.notes {
+        ui.warning(note);
+    }
+    for (key, nested) in &call.nested {
+        print_decoded_call(ui, &format!("↳ {key}"), nested);
+    }
 }
 
 /// Handle utility commands
-pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -> Result<()> {
+pub async fn handle_utility_command(
+    config: &Config,
+    command: UtilityCommands,
+    output: OutputFormat,
+) -> Result<()> {
     match command {
         UtilityCommands::BuildPayload {
             tx_hash,
             source_network_id,
             bridge_index,
+            show_path,
+            export,
             json,
         } => {
+            let json = json || output.is_json();
             info!(
                 tx_hash = %tx_hash,
                 source_network = source_network_id,
@@ -611,6 +818,14 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
             };
 
             let payload = build_payload_for_claim(args).await?;
+            if let Some(export) = &export {
+                export_payload(export, &payload)?;
+            }
+            let path = if show_path {
+                Some(claim_path(&payload)?)
+            } else {
+                None
+            };
 
             let ui = UI::new(if json {
                 OutputFormat::Json
@@ -620,7 +835,12 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
 
             if json {
                 let json_str = serialize_json(&payload)?;
-                ui.json(&serde_json::from_str::<serde_json::Value>(&json_str).unwrap_or_default());
+                let mut data =
+                    serde_json::from_str::<serde_json::Value>(&json_str).unwrap_or_default();
+                if let Some(path) = &path {
+                    data["proof_path"] = serde_json::to_value(path).unwrap_or_default();
+                }
+                ui.json(&data);
             } else {
                 let origin_network_str = format!(
                     "{} ({})",
@@ -650,6 +870,17 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
                     rows.push(("SMT Rollup Proof Len", rollup_proof_len_str.as_str()));
                 }
                 ui.table("🔍 Bridge Claim Payload", &rows);
+                if let Some(path) = &path {
+                    ui.blank_line();
+                    print_claim_path(&ui, path);
+                }
+                if let Some(export) = &export {
+                    ui.success(&format!(
+                        "Claim payload written to {}, claim it with: aggsandbox bridge claim --from-payload {}",
+                        export.display(),
+                        export.display()
+                    ));
+                }
             }
 
             Ok(())
@@ -659,6 +890,7 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
             source_network_id,
             json,
         } => {
+            let json = json || output.is_json();
             validate_network_id(source_network_id, "Source network")?;
 
             info!(
@@ -704,6 +936,55 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
 
             Ok(())
         }
+        UtilityCommands::DecodeIndex { global_index, json } => {
+            let json = json || output.is_json();
+            info!(global_index = %global_index, "Decoding global index");
+
+            let global_index = Validator::parse_uint(&global_index)?;
+            let (mainnet_flag, rollup_index, local_index) = global_index_parts(global_index);
+            let (source_network, _) = decode_global_index(global_index);
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+
+            if json {
+                let output = DecodeIndexOutput {
+                    global_index: global_index.to_string(),
+                    mainnet_flag,
+                    rollup_index,
+                    local_index,
+                    source_network,
+                };
+                let json_str = serialize_json(&output)?;
+                ui.json(&serde_json::from_str::<serde_json::Value>(&json_str).unwrap_or_default());
+            } else {
+                let global_index_str = format!("{global_index} ({global_index:#x})");
+                let mainnet_flag_str = mainnet_flag.to_string();
+                let rollup_index_str = rollup_index.to_string();
+                let local_index_str = local_index.to_string();
+                let source_network_str =
+                    format!("{source_network} ({})", get_network_name(source_network));
+                let rows = vec![
+                    ("Global Index", global_index_str.as_str()),
+                    ("Mainnet Flag", mainnet_flag_str.as_str()),
+                    ("Rollup Index", rollup_index_str.as_str()),
+                    ("Local Index", local_index_str.as_str()),
+                    ("Source Network", source_network_str.as_str()),
+                ];
+                ui.table("🧮 Global Index Decoding", &rows);
+            }
+
+            if mainnet_flag && rollup_index != 0 {
+                ui.warning("Mainnet flag is set together with a rollup index; the rollup index is ignored for mainnet deposits");
+            }
+            if global_index >> 65 != U256::zero() {
+                ui.warning("Bits above 64 are set; they are not part of the global index scheme");
+            }
+
+            Ok(())
+        }
         UtilityCommands::GetMapped {
             network_id,
             origin_network,
@@ -711,6 +992,7 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
             private_key,
             json,
         } => {
+            let json = json || output.is_json();
             info!(
                 network = network_id,
                 origin_network = origin_network,
@@ -767,6 +1049,7 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
             private_key,
             json,
         } => {
+            let json = json || output.is_json();
             info!(
                 network = network_id,
                 origin_network = origin_network,
@@ -822,6 +1105,7 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
             private_key,
             json,
         } => {
+            let json = json || output.is_json();
             info!(
                 network = network_id,
                 wrapped_token = %wrapped_token,
@@ -870,6 +1154,7 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
             source_network_id,
             json,
         } => {
+            let json = json || output.is_json();
             info!(
                 network = network_id,
                 index = index,
@@ -928,6 +1213,7 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
             private_key,
             json,
         } => {
+            let json = json || output.is_json();
             info!(network = network_id, "Getting bridge contract network ID");
 
             let args = NetworkIdArgs {
@@ -960,6 +1246,181 @@ pub async fn handle_utility_command(config: &Config, command: UtilityCommands) -
                 ui.table("🆔 Bridge Contract Network ID", &rows);
             }
 
+            Ok(())
+        }
+        UtilityCommands::CheckMetadata {
+            network_id,
+            wrapped_token,
+            private_key,
+            json,
+        } => {
+            let json = json || output.is_json();
+            info!(network = network_id, "Checking wrapped token metadata");
+
+            let args = CheckMetadataArgs {
+                config,
+                network: network_id,
+                wrapped_token_address: wrapped_token.as_deref(),
+                private_key: private_key.as_deref(),
+            };
+
+            let checks = check_wrapped_token_metadata(args).await?;
+            let checked = checks.len();
+            let out_of_sync = checks.iter().filter(|check| !check.is_in_sync()).count();
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+
+            if json {
+                let output = MetadataCheckOutput {
+                    network: network_id,
+                    checked,
+                    out_of_sync,
+                    tokens: checks,
+                };
+                let json_str = serialize_json(&output)?;
+                ui.json(&serde_json::from_str::<serde_json::Value>(&json_str).unwrap_or_default());
+            } else if checks.is_empty() {
+                ui.info(&format!(
+                    "No wrapped tokens found on network {network_id} ({})",
+                    get_network_name(network_id)
+                ));
+            } else {
+                for check in &checks {
+                    let origin_str = format!(
+                        "{} on {} ({})",
+                        check.origin_token_address,
+                        check.origin_network,
+                        get_network_name(check.origin_network as u64)
+                    );
+                    let origin_meta = format_token_metadata(&check.origin);
+                    let wrapped_meta = format_token_metadata(&check.wrapped);
+                    let status = if check.is_in_sync() {
+                        "✅ IN SYNC".to_string()
+                    } else {
+                        format!("❌ {}", check.issues.join("; "))
+                    };
+                    let rows = vec![
+                        ("Wrapped Token", check.wrapped_token_address.as_str()),
+                        ("Origin Token", origin_str.as_str()),
+                        ("Origin Metadata", origin_meta.as_str()),
+                        ("Wrapped Metadata", wrapped_meta.as_str()),
+                        ("Status", status.as_str()),
+                    ];
+                    ui.table("🏷️  Wrapped Token Metadata", &rows);
+                }
+            }
+
+            if out_of_sync > 0 {
+                return Err(validation_error(&format!(
+                    "{out_of_sync} of {} wrapped token(s) on network {network_id} have metadata discrepancies",
+                    checked
+                )));
+            }
+
+            Ok(())
+        }
+        UtilityCommands::VerifyProof {
+            tx_hash,
+            source_network_id,
+            bridge_index,
+            json,
+        } => {
+            let json = json || output.is_json();
+            info!(
+                tx_hash = %tx_hash,
+                source_network = source_network_id,
+                bridge_index = ?bridge_index,
+                "Verifying claim proof"
+            );
+
+            let args = BuildPayloadArgs {
+                config,
+                tx_hash: &tx_hash,
+                source_network: source_network_id,
+                bridge_index,
+            };
+            let payload = build_payload_for_claim(args).await?;
+            let verification = verify_claim_proof(config, &payload).await?;
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+
+            if json {
+                let json_str = serialize_json(&verification)?;
+                ui.json(&serde_json::from_str::<serde_json::Value>(&json_str).unwrap_or_default());
+            } else {
+                print_verification(&ui, &verification);
+            }
+
+            match verification.failure() {
+                Some(failure) => Err(validation_error(&failure)),
+                None => Ok(()),
+            }
+        }
+        UtilityCommands::DecodeCalldata {
+            data,
+            tx_hash,
+            network_id,
+            json,
+        } => {
+            let json = json || output.is_json();
+            info!(tx_hash = ?tx_hash, network = ?network_id, "Decoding calldata");
+
+            let calldata =
+                calldata_input(config, data.as_deref(), tx_hash.as_deref(), network_id).await?;
+            let call = decode_calldata(&calldata)?;
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+
+            if json {
+                ui.json(&call.to_json());
+            } else {
+                print_decoded_call(&ui, "🧩 Decoded Calldata", &call);
+            }
+
+            Ok(())
+        }
+        UtilityCommands::EncodeCall {
+            signature,
+            args,
+            json,
+        } => {
+            let json = json || output.is_json();
+            info!(signature = %signature, args = args.len(), "Encoding function call");
+
+            let call = encode_call(&signature, &args)?;
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+
+            if json {
+                ui.json(&call.to_json());
+            } else {
+                let selector = format!("0x{}", hex::encode(call.selector()));
+                let calldata = call.hex();
+                ui.table(
+                    "🧱 Encoded Call",
+                    &[
+                        ("Function", call.signature.as_str()),
+                        ("Selector", selector.as_str()),
+                        ("Calldata", calldata.as_str()),
+                    ],
+                );
+                ui.tip(&format!(
+                    "Pass it to bridge-and-call with --data {calldata}, or let bridge-and-call encode it with --call \"{signature}\" --call-arg ..."
+                ));
+            }
+
             Ok(())
         }
     }
@@ -991,6 +1452,21 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_decode_global_index_roundtrip() {
+        for source_network_id in 0..3 {
+            let global_index = compute_global_index(ComputeGlobalIndexArgs {
+                index_local: 42,
+                source_network_id,
+            });
+            assert_eq!(decode_global_index(global_index), (source_network_id, 42));
+            let (mainnet_flag, rollup_index, local_index) = global_index_parts(global_index);
+            assert_eq!(mainnet_flag, source_network_id == 0);
+            assert_eq!(u64::from(rollup_index), source_network_id.saturating_sub(1));
+            assert_eq!(local_index, 42);
+        }
+    }
+
     #[test]
     fn test_compute_global_index_l3() {
         let args = ComputeGlobalIndexArgs {
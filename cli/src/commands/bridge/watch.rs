@@ -0,0 +1,330 @@
+//! Delta watch over deposits destined for a network
+//!
+//! Every poll rebuilds the state of each deposit from the bridge service:
+//! indexed deposits are pending, become claimable once the L1 info tree index
+//! of the deposit is available on the source network, and are claimed once a
+//! matching claim (by global index) shows up on the destination network. Only
+//! state changes are printed, optionally as desktop notifications.
+
+use crate::api_client::OptimizedApiClient;
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui;
+use ethers::types::U256;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
+use tracing::debug;
+
+use super::claim_all::{pending_deposits_from_response, PendingDeposit};
+use super::common::{get_network_name, validate_network_id};
+use super::halt::ChainWatch;
+use super::utilities::decode_global_index;
+
+/// Progress of a deposit towards being claimed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DepositState {
+    Pending,
+    Claimable,
+    Claimed,
+}
+
+/// A deposit identified by its source network and deposit count
+pub type DepositKey = (u64, u64);
+
+/// Tracked deposits with their latest state
+pub type DepositStates = BTreeMap<DepositKey, (PendingDeposit, DepositState)>;
+
+/// A state change observed between two polls
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DepositTransition {
+    #[serde(flatten)]
+    pub deposit: PendingDeposit,
+    pub from: Option<DepositState>,
+    pub to: DepositState,
+}
+
+impl DepositTransition {
+    /// Human readable one-line summary
+    pub fn describe(&self) -> String {
+        let deposit = format!(
+            "Deposit #{} from network {} ({})",
+            self.deposit.deposit_count,
+            self.deposit.source_network,
+            get_network_name(self.deposit.source_network)
+        );
+        match self.to {
+            DepositState::Pending => format!("🆕 {deposit} bridged (tx {})", self.deposit.tx_hash),
+            DepositState::Claimable => format!("⏳ {deposit} is now claimable"),
+            DepositState::Claimed => format!("🎉 {deposit} has been claimed"),
+        }
+    }
+
+    /// Whether this change is worth a desktop notification
+    pub fn is_notable(&self) -> bool {
+        self.to != DepositState::Pending
+    }
+}
+
+/// State changes between the previous and the current poll
+///
+/// Deposits that disappear (e.g. after a sandbox restart) are dropped silently.
+pub fn diff_states(previous: &DepositStates, current: &DepositStates) -> Vec<DepositTransition> {
+    current
+        .iter()
+        .filter_map(|(key, (deposit, state))| {
+            let before = previous.get(key).map(|(_, state)| *state);
+            (before != Some(*state)).then(|| DepositTransition {
+                deposit: deposit.clone(),
+                from: before,
+                to: *state,
+            })
+        })
+        .collect()
+}
+
+/// Deposits claimed on a network, keyed by source network and deposit count
+pub fn claimed_deposits_from_response(response: &serde_json::Value) -> HashSet<DepositKey> {
+    response["claims"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(claim_deposit_key)
+        .collect()
+}
+
+/// Source network and deposit count of a claim, decoded from its global index
+pub fn claim_deposit_key(claim: &serde_json::Value) -> Option<DepositKey> {
+    let global_index = match &claim["global_index"] {
+        serde_json::Value::String(index) => U256::from_dec_str(index).ok()?,
+        serde_json::Value::Number(index) => U256::from(index.as_u64()?),
+        _ => return None,
+    };
+    let (source_network, deposit_count) = decode_global_index(global_index);
+    Some((source_network, u64::from(deposit_count)))
+}
+
+/// Poll the bridge service and print deposit state changes until Ctrl-C
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn watch_deposits(
+    config: &Config,
+    network: u64,
+    interval_secs: u64,
+    notify: bool,
+) -> Result<()> {
+    validate_network_id(network, "Network")?;
+    let json = ui::ui().is_json();
+    if !json {
+        ui::ui().info(&format!(
+            "👀 Watching deposits to network {network} ({}) every {interval_secs}s, press Ctrl-C to stop",
+            get_network_name(network)
+        ));
+    }
+
+    let mut chains = ChainWatch::new(config, &config.networks.network_ids()).await?;
+    let mut previous: Option<DepositStates> = None;
+    let mut notifications_available = notify;
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        match deposit_states(config, network, previous.as_ref()).await {
+            Ok(current) => {
+                match &previous {
+                    None if !json => {
+                        let claimable = current
+                            .values()
+                            .filter(|(_, state)| *state == DepositState::Claimable)
+                            .count();
+                        let pending = current
+                            .values()
+                            .filter(|(_, state)| *state == DepositState::Pending)
+                            .count();
+                        ui::ui().info(&format!(
+                            "{} deposit(s) tracked: {pending} pending, {claimable} claimable",
+                            current.len()
+                        ));
+                    }
+                    None => {}
+                    Some(previous) => {
+                        for transition in diff_states(previous, &current) {
+                            if json {
+                                let line = serde_json::to_value(&transition).unwrap_or_default();
+                                println!("{}", crate::schema::versioned(line));
+                            } else {
+                                ui::ui().info(&transition.describe());
+                            }
+                            if notifications_available && transition.is_notable() {
+                                notifications_available =
+                                    send_notification(network, &transition.describe());
+                            }
+                        }
+                    }
+                }
+                previous = Some(current);
+            }
+            Err(e) => {
+                debug!(network, error = %e, "Watch poll failed");
+                if !json {
+                    ui::ui().warning(&format!("Poll failed, retrying: {e}"));
+                }
+            }
+        }
+
+        chains.check().await?;
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            _ = tokio::time::sleep(Duration::from_secs(interval_secs)) => {}
+        }
+    }
+
+    if !json {
+        ui::ui().success("Stopped watching deposits");
+    }
+    Ok(())
+}
+
+/// Build the current state of every deposit destined for `network`
+///
+/// Claimable deposits stay claimable until claimed, so the L1 info tree index
+/// is only queried for deposits that were pending in the previous poll.
+pub async fn deposit_states(
+    config: &Config,
+    network: u64,
+    previous: Option<&DepositStates>,
+) -> Result<DepositStates> {
+    let api_client = OptimizedApiClient::global();
+    api_client.invalidate("claims", Some(network)).await;
+    api_client.invalidate("bridges", None).await;
+
+    let mut source_networks = config.networks.network_ids();
+    source_networks.retain(|source| *source != network);
+
+    let claimed = claimed_deposits_from_response(&api_client.get_claims(config, network).await?);
+    let mut states = DepositStates::new();
+
+    for source_network in source_networks {
+        let response = api_client.get_bridges(config, source_network).await?;
+        for deposit in pending_deposits_from_response(&response, source_network, network) {
+            let key = (deposit.source_network, deposit.deposit_count);
+            let was_claimable = previous
+                .and_then(|previous| previous.get(&key))
+                .is_some_and(|(_, state)| *state >= DepositState::Claimable);
+
+            let state = if claimed.contains(&key) {
+                DepositState::Claimed
+            } else if was_claimable
+                || api_client
+                    .get_l1_info_tree_index(config, source_network, deposit.deposit_count)
+                    .await
+                    .is_ok()
+            {
+                DepositState::Claimable
+            } else {
+                DepositState::Pending
+            };
+            states.insert(key, (deposit, state));
+        }
+    }
+
+    Ok(states)
+}
+
+/// Show a desktop notification, returning false when notifications are unavailable
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub fn send_notification(network: u64, body: &str) -> bool {
+    let summary = format!(
+        "aggsandbox: network {network} ({})",
+        get_network_name(network)
+    );
+    match show_notification(&summary, body) {
+        Ok(()) => true,
+        Err(e) => {
+            debug!(error = %e, "Desktop notification failed");
+            ui::ui().warning(&format!(
+                "Desktop notifications unavailable ({e}); continuing without --notify"
+            ));
+            false
+        }
+    }
+}
+
+#[cfg(feature = "tui")]
+fn show_notification(summary: &str, body: &str) -> std::result::Result<(), String> {
+    notify_rust::Notification::new()
+        .appname("aggsandbox")
+        .summary(summary)
+        .body(body)
+        .show()
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "tui"))]
+fn show_notification(_summary: &str, _body: &str) -> std::result::Result<(), String> {
+    Err("aggsandbox was built without the 'tui' feature".to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn deposit(source_network: u64, deposit_count: u64) -> PendingDeposit {
+        PendingDeposit {
+            source_network,
+            deposit_count,
+            leaf_type: 0,
+            tx_hash: format!("0x{deposit_count:02x}"),
+            amount: "1".to_string(),
+        }
+    }
+
+    fn states(entries: &[(u64, u64, DepositState)]) -> DepositStates {
+        entries
+            .iter()
+            .map(|(source, count, state)| ((*source, *count), (deposit(*source, *count), *state)))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_reports_only_changes() {
+        let previous = states(&[
+            (0, 0, DepositState::Claimable),
+            (0, 1, DepositState::Pending),
+        ]);
+        let current = states(&[
+            (0, 0, DepositState::Claimed),
+            (0, 1, DepositState::Pending),
+            (0, 2, DepositState::Pending),
+        ]);
+
+        let transitions = diff_states(&previous, &current);
+
+        assert_eq!(transitions.len(), 2);
+        assert_eq!(transitions[0].from, Some(DepositState::Claimable));
+        assert_eq!(transitions[0].to, DepositState::Claimed);
+        assert!(transitions[0].is_notable());
+        assert_eq!(transitions[1].from, None);
+        assert!(!transitions[1].is_notable());
+    }
+
+    #[test]
+    fn test_claimed_deposits_from_global_index() {
+        let response = json!({
+            "claims": [
+                {"global_index": "18446744073709551621"},
+                {"global_index": 4294967299u64},
+                {"global_index": null}
+            ]
+        });
+
+        let claimed = claimed_deposits_from_response(&response);
+
+        assert_eq!(claimed.len(), 2);
+        assert!(claimed.contains(&(0, 5)));
+        assert!(claimed.contains(&(2, 3)));
+    }
+}
@@ -0,0 +1,300 @@
+//! Interactive bridge wizard
+//!
+//! Prompts for the source and destination networks, the token, the amount and
+//! the recipient, then runs the regular `bridge_asset` flow and prints the
+//! follow-up commands. The equivalent non-interactive command is shown before
+//! the transaction is sent so it can be reused in scripts.
+
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui;
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::{Confirm, Input, Select};
+use ethers::prelude::*;
+use ethers::utils::{format_units, parse_units};
+use std::sync::Arc;
+use tracing::debug;
+
+use super::bridge_asset::{bridge_asset, BridgeAssetArgs};
+use super::common::{contract, get_network_name, validate_address, validation_error};
+use super::token_metadata::{cached_token_metadata, discover_bridged_tokens};
+use super::{get_wallet_with_provider, ERC20Contract};
+
+/// A token the wizard offers on the source network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenChoice {
+    pub label: String,
+    pub address: Address,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// Run the interactive bridge wizard
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn run_wizard(config: &Config, private_key: Option<&str>) -> Result<()> {
+    if !atty::is(atty::Stream::Stdin) {
+        return Err(validation_error(
+            "The bridge wizard needs an interactive terminal; use `aggsandbox bridge asset` in scripts",
+        ));
+    }
+    let theme = ColorfulTheme::default();
+
+    let networks = available_networks(config);
+    let labels: Vec<&str> = networks.iter().map(|(_, label)| label.as_str()).collect();
+    let source_index = Select::with_theme(&theme)
+        .with_prompt("Source network")
+        .items(&labels)
+        .default(0)
+        .interact()
+        .map_err(prompt_error)?;
+    let source_network = networks[source_index].0;
+
+    let destinations: Vec<&(u64, String)> = networks
+        .iter()
+        .filter(|(id, _)| *id != source_network)
+        .collect();
+    let labels: Vec<&str> = destinations
+        .iter()
+        .map(|(_, label)| label.as_str())
+        .collect();
+    let destination_index = Select::with_theme(&theme)
+        .with_prompt("Destination network")
+        .items(&labels)
+        .default(0)
+        .interact()
+        .map_err(prompt_error)?;
+    let destination_network = destinations[destination_index].0;
+
+    ui::ui().info("🔍 Looking up tokens on the source network...");
+    let client = get_wallet_with_provider(config, source_network, private_key).await?;
+    let sender = client.inner().address();
+    let tokens = token_choices(config, source_network, private_key, sender).await?;
+    let labels: Vec<&str> = tokens.iter().map(|token| token.label.as_str()).collect();
+    let token_index = Select::with_theme(&theme)
+        .with_prompt("Token")
+        .items(&labels)
+        .default(0)
+        .interact()
+        .map_err(prompt_error)?;
+    let token = &tokens[token_index];
+
+    let decimals = token.decimals;
+    let amount: String = Input::with_theme(&theme)
+        .with_prompt(format!("Amount in {}", token.symbol))
+        .validate_with(|input: &String| -> std::result::Result<(), String> {
+            match parse_units(input.trim(), u32::from(decimals)) {
+                Ok(value) if !U256::from(value).is_zero() => Ok(()),
+                Ok(_) => Err("Amount must be greater than zero".to_string()),
+                Err(_) => Err(format!("Not a valid amount with {decimals} decimals")),
+            }
+        })
+        .interact_text()
+        .map_err(prompt_error)?;
+    let amount_base_units = to_base_units(&amount, decimals)?;
+
+    let recipient: String = Input::with_theme(&theme)
+        .with_prompt("Recipient")
+        .default(format!("{sender:#x}"))
+        .validate_with(|input: &String| -> std::result::Result<(), String> {
+            validate_address(input.trim(), "Recipient")
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact_text()
+        .map_err(prompt_error)?;
+    let recipient = recipient.trim().to_string();
+
+    let token_address = format!("{:#x}", token.address);
+    let command = equivalent_command(
+        source_network,
+        destination_network,
+        &amount_base_units.to_string(),
+        &token_address,
+        &recipient,
+    );
+    ui::ui().info(&format!(
+        "Bridging {} {} from {} to {} for {recipient}",
+        amount.trim(),
+        token.symbol,
+        get_network_name(source_network),
+        get_network_name(destination_network)
+    ));
+    ui::ui().info(&format!("Equivalent command: {command}"));
+
+    let confirmed = Confirm::with_theme(&theme)
+        .with_prompt("Send the bridge transaction?")
+        .default(true)
+        .interact()
+        .map_err(prompt_error)?;
+    if !confirmed {
+        ui::ui().info("Bridge cancelled");
+        return Ok(());
+    }
+
+    let amount_wei = amount_base_units.to_string();
+    let mut builder = BridgeAssetArgs::builder()
+        .config(config)
+        .source_network(source_network)
+        .destination_network(destination_network)
+        .amount(&amount_wei)
+        .token_address(&token_address)
+        .recipient_address(&recipient);
+    if let Some(key) = private_key {
+        builder = builder.private_key(key);
+    }
+    debug!(command = %command, "Executing bridge from wizard");
+    bridge_asset(builder.build_with_crate_error()?).await?;
+
+    ui::ui().tip(&format!(
+        "Or claim every pending deposit with `aggsandbox bridge claim-all --network-id {destination_network}`, and follow progress with `aggsandbox bridge watch --network-id {destination_network}`"
+    ));
+    Ok(())
+}
+
+/// Networks from the configuration as (network ID, prompt label)
+fn available_networks(config: &Config) -> Vec<(u64, String)> {
+    config
+        .networks
+        .network_ids()
+        .into_iter()
+        .filter_map(|id| {
+            let chain = config.networks.chain(id)?;
+            Some((
+                id,
+                format!("{id} - {} ({})", chain.name, chain.rpc_url.as_str()),
+            ))
+        })
+        .collect()
+}
+
+/// ETH, the deployed AggERC20 and the wrapped tokens available on a network
+async fn token_choices(
+    config: &Config,
+    network: u64,
+    private_key: Option<&str>,
+    holder: Address,
+) -> Result<Vec<TokenChoice>> {
+    let client = Arc::new(get_wallet_with_provider(config, network, private_key).await?);
+    let eth_balance = client.get_balance(holder, None).await.unwrap_or_default();
+    let mut choices = vec![TokenChoice {
+        label: format!("ETH (native) - balance {}", format_balance(eth_balance, 18)),
+        address: Address::zero(),
+        symbol: "ETH".to_string(),
+        decimals: 18,
+    }];
+
+    let layer = match network {
+        0 => "l1",
+        1 => "l2",
+        _ => "l3",
+    };
+    let mut addresses = Vec::new();
+    if let Ok(agg_erc20) = config
+        .contracts
+        .get_contract(layer, "AggERC20")
+        .parse::<Address>()
+    {
+        addresses.push((agg_erc20, "deployed"));
+    }
+
+    let bridge = contract::get_bridge_contract(config, network, private_key).await?;
+    for (origin_network, origin_token) in discover_bridged_tokens(config, network).await? {
+        match bridge
+            .get_token_wrapped_address(origin_network, origin_token)
+            .call()
+            .await
+        {
+            Ok(wrapped)
+                if !wrapped.is_zero() && !addresses.iter().any(|(known, _)| *known == wrapped) =>
+            {
+                addresses.push((wrapped, "wrapped"))
+            }
+            Ok(_) => {}
+            Err(e) => debug!(origin_network, error = %e, "Could not look up wrapped token"),
+        }
+    }
+
+    for (address, kind) in addresses {
+        let metadata = cached_token_metadata(config, network, address).await?;
+        let symbol = metadata.symbol.unwrap_or_else(|| "TOKEN".to_string());
+        let decimals = metadata.decimals.unwrap_or(18);
+        let balance = ERC20Contract::new(address, client.clone())
+            .balance_of(holder)
+            .call()
+            .await
+            .unwrap_or_default();
+        choices.push(TokenChoice {
+            label: format!(
+                "{symbol} ({kind}) {address:#x} - balance {}",
+                format_balance(balance, decimals)
+            ),
+            address,
+            symbol,
+            decimals,
+        });
+    }
+
+    Ok(choices)
+}
+
+/// Convert an amount in whole token units to base units
+fn to_base_units(amount: &str, decimals: u8) -> Result<U256> {
+    parse_units(amount.trim(), u32::from(decimals))
+        .map(U256::from)
+        .map_err(|e| validation_error(&format!("Invalid amount '{amount}': {e}")))
+}
+
+fn format_balance(balance: U256, decimals: u8) -> String {
+    format_units(balance, u32::from(decimals)).unwrap_or_else(|_| balance.to_string())
+}
+
+/// The `bridge asset` command performing the same bridge
+fn equivalent_command(
+    source_network: u64,
+    destination_network: u64,
+    amount: &str,
+    token_address: &str,
+    recipient: &str,
+) -> String {
+    format!(
+        "aggsandbox bridge asset --network-id {source_network} --destination-network-id {destination_network} --amount {amount} --token-address {token_address} --to-address {recipient}"
+    )
+}
+
+fn prompt_error(e: dialoguer::Error) -> AggSandboxError {
+    AggSandboxError::Other(format!("Bridge wizard aborted: {e}"))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_base_units() {
+        assert_eq!(
+            to_base_units("0.1", 18).unwrap(),
+            U256::from(100_000_000_000_000_000u64)
+        );
+        assert_eq!(to_base_units("2.5", 6).unwrap(), U256::from(2_500_000));
+        assert!(to_base_units("abc", 18).is_err());
+    }
+
+    #[test]
+    fn test_equivalent_command() {
+        let command = equivalent_command(
+            0,
+            1,
+            "1000",
+            &format!("{:#x}", Address::zero()),
+            "0x1234567890123456789012345678901234567890",
+        );
+
+        assert_eq!(
+            command,
+            "aggsandbox bridge asset --network-id 0 --destination-network-id 1 --amount 1000 \
+             --token-address 0x0000000000000000000000000000000000000000 \
+             --to-address 0x1234567890123456789012345678901234567890"
+        );
+    }
+}
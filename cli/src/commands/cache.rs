@@ -0,0 +1,50 @@
+//! Bridge service response cache
+//!
+//! Claim proofs and L1 info tree indices are kept in `.aggsandbox/api-cache.json`
+//! between commands, deposit and claim lists only for the life of a process.
+//! `cache clear` drops both, for a sandbox whose chains changed under the CLI.
+
+use crate::api_client::{OptimizedApiClient, CACHE_FILE};
+use crate::error::Result;
+use crate::ui::{OutputFormat, UI};
+
+/// Cache subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum CacheCommands {
+    /// 🧹 Drop every cached bridge service response
+    #[command(long_about = "Drop every cached bridge service response.
+
+Claim proofs and L1 info tree indices are cached in .aggsandbox/api-cache.json
+until they expire or the sandbox is started again. Clear the cache when the
+chains were reset another way, or pass --no-cache to bypass it for a command.
+
+Examples:
+  aggsandbox cache clear
+  aggsandbox show claim-proof -n 0 -l 3 -d 7 --no-cache")]
+    Clear,
+}
+
+/// Handle cache subcommands
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn handle_cache(subcommand: CacheCommands, output: OutputFormat) -> Result<()> {
+    let ui = UI::new(output);
+    let client = OptimizedApiClient::global();
+
+    match subcommand {
+        CacheCommands::Clear => {
+            let cleared = client.cached_entries().await;
+            client.clear_cache_file().await;
+            if ui.is_json() {
+                ui.json(&serde_json::json!({
+                    "path": CACHE_FILE,
+                    "cleared": cleared,
+                }));
+            } else if cleared == 0 {
+                ui.info("The cache was already empty");
+            } else {
+                ui.success(&format!("Cleared {cleared} cached response(s)"));
+            }
+            Ok(())
+        }
+    }
+}
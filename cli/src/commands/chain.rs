@@ -0,0 +1,319 @@
+//! Time-travel controls for the sandbox Anvil nodes
+//!
+//! Bridge timeouts and exit-root propagation depend on block production and
+//! timestamps, which Anvil lets tests drive with cheat codes. These commands
+//! send `anvil_mine`, `evm_increaseTime`, `evm_setIntervalMining` and
+//! `evm_setAutomine` to the selected networks and report where each chain
+//! ended up.
+
+use crate::commands::bridge::common::{
+    get_network_name, node_request, rpc_action, rpc_error, validate_network_id,
+};
+use crate::commands::bridge::get_provider;
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui::{self, OutputFormat, UI};
+use crate::validation::parse_network_id;
+use ethers::prelude::*;
+use serde::Serialize;
+use tracing::info;
+
+/// Chain control subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum ChainCommands {
+    /// ⛏️ Mine blocks immediately
+    #[command(long_about = "Mine one or more blocks on the selected networks.
+
+Blocks are mined with anvil_mine. With --interval, consecutive blocks are
+spaced that many seconds apart, which also moves the chain time forward.
+
+Examples:
+  aggsandbox chain mine -n 1
+  aggsandbox chain mine -n 0 -n 1 --blocks 64
+  aggsandbox chain mine -n l1 --blocks 10 --interval 12")]
+    Mine {
+        /// Networks to mine on
+        #[arg(short = 'n', long = "network-id", alias = "network", required = true, value_parser = parse_network_id, help = "Network ID or alias, repeat for several networks")]
+        network_ids: Vec<u64>,
+        /// Number of blocks to mine
+        #[arg(short, long, default_value = "1", help = "Number of blocks to mine")]
+        blocks: u64,
+        /// Seconds between the timestamps of the mined blocks
+        #[arg(long, value_parser = parse_seconds, help = "Timestamp spacing between mined blocks (e.g. 12, 30s, 5m)")]
+        interval: Option<u64>,
+    },
+    /// ⏩ Move the chain time forward
+    #[command(long_about = "Advance the timestamp of the selected networks.
+
+The offset is applied with evm_increaseTime and a block is mined so the new
+time is visible on chain; use --no-mine to only set the offset for the next
+block. Durations accept s, m, h and d suffixes.
+
+Examples:
+  aggsandbox chain increase-time -n 0 3600
+  aggsandbox chain increase-time -n 0 -n 1 2h
+  aggsandbox chain increase-time -n l2 7d --no-mine")]
+    IncreaseTime {
+        /// Networks to advance
+        #[arg(short = 'n', long = "network-id", alias = "network", required = true, value_parser = parse_network_id, help = "Network ID or alias, repeat for several networks")]
+        network_ids: Vec<u64>,
+        /// Time to add to the chain clock
+        #[arg(value_parser = parse_seconds, help = "Duration to advance (e.g. 3600, 90s, 15m, 2h, 1d)")]
+        duration: u64,
+        /// Don't mine a block after increasing the time
+        #[arg(long, help = "Only set the offset, without mining a block")]
+        no_mine: bool,
+    },
+    /// ⏱️ Mine blocks at a fixed interval
+    #[command(
+        long_about = "Enable or disable interval mining on the selected networks.
+
+With an interval set, Anvil mines a block every that many seconds whether or
+not there are pending transactions. An interval of 0 turns interval mining off.
+
+Examples:
+  aggsandbox chain set-interval -n 0 12
+  aggsandbox chain set-interval -n 0 -n 1 2s
+  aggsandbox chain set-interval -n 0 0     # Disable interval mining"
+    )]
+    SetInterval {
+        /// Networks to configure
+        #[arg(short = 'n', long = "network-id", alias = "network", required = true, value_parser = parse_network_id, help = "Network ID or alias, repeat for several networks")]
+        network_ids: Vec<u64>,
+        /// Seconds between blocks, 0 to disable
+        #[arg(value_parser = parse_seconds, help = "Block interval (e.g. 12, 2s, 1m), 0 disables interval mining")]
+        interval: u64,
+    },
+    /// 🔁 Turn automatic mining of transactions on or off
+    #[command(long_about = "Turn automine on or off on the selected networks.
+
+With automine on (Anvil's default), every transaction is mined in its own
+block as soon as it is sent. Turn it off to batch transactions, for example to
+leave deposits pending until `chain mine` is called.
+
+Examples:
+  aggsandbox chain automine -n 1 off
+  aggsandbox chain automine -n 0 -n 1 on")]
+    Automine {
+        /// Networks to configure
+        #[arg(short = 'n', long = "network-id", alias = "network", required = true, value_parser = parse_network_id, help = "Network ID or alias, repeat for several networks")]
+        network_ids: Vec<u64>,
+        /// Whether automine is enabled
+        #[arg(value_enum, help = "on or off")]
+        mode: Toggle,
+    },
+}
+
+/// On/off switch for `chain automine`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Toggle {
+    On,
+    Off,
+}
+
+/// Chain head of a network after a control command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainHead {
+    pub network_id: u64,
+    pub block_number: u64,
+    pub timestamp: u64,
+}
+
+/// Handle chain control subcommands
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn handle_chain(subcommand: ChainCommands, output: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+
+    let (network_ids, action) = match &subcommand {
+        ChainCommands::Mine {
+            network_ids,
+            blocks,
+            interval,
+        } => {
+            let spacing = interval.map_or_else(String::new, |secs| format!(", {secs}s apart"));
+            (network_ids, format!("Mined {blocks} block(s){spacing}"))
+        }
+        ChainCommands::IncreaseTime {
+            network_ids,
+            duration,
+            no_mine,
+        } => {
+            let pending = if *no_mine {
+                " (applies to the next block)"
+            } else {
+                ""
+            };
+            (
+                network_ids,
+                format!("Advanced time by {duration}s{pending}"),
+            )
+        }
+        ChainCommands::SetInterval {
+            network_ids,
+            interval,
+        } => match interval {
+            0 => (network_ids, "Disabled interval mining".to_string()),
+            secs => (network_ids, format!("Mining a block every {secs}s")),
+        },
+        ChainCommands::Automine { network_ids, mode } => {
+            let state = match mode {
+                Toggle::On => "on",
+                Toggle::Off => "off",
+            };
+            (network_ids, format!("Automine {state}"))
+        }
+    };
+
+    let mut heads = Vec::new();
+    for &network_id in network_ids {
+        validate_network_id(network_id, "Network")?;
+        let provider = get_provider(&config, network_id).await?;
+        info!(network_id, subcommand = ?subcommand, "Sending chain control request");
+        apply(&provider, network_id, &subcommand).await?;
+        heads.push(chain_head(&provider, network_id).await?);
+    }
+
+    if output.is_json() {
+        UI::new(output).json(&serde_json::json!({
+            "action": action,
+            "networks": heads,
+        }));
+    } else {
+        for head in &heads {
+            ui::ui().success(&format!(
+                "{action} on network {} ({}): block {}, time {}",
+                head.network_id,
+                get_network_name(head.network_id),
+                head.block_number,
+                format_timestamp(head.timestamp)
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Send the cheat codes for a subcommand to one network
+async fn apply(
+    provider: &Provider<Http>,
+    network_id: u64,
+    subcommand: &ChainCommands,
+) -> Result<()> {
+    match subcommand {
+        ChainCommands::Mine {
+            blocks, interval, ..
+        } => {
+            let params = match interval {
+                Some(secs) => vec![U256::from(*blocks), U256::from(*secs)],
+                None => vec![U256::from(*blocks)],
+            };
+            node_request::<_, serde_json::Value>(provider, network_id, "anvil_mine", params)
+                .await?;
+        }
+        ChainCommands::IncreaseTime {
+            duration, no_mine, ..
+        } => {
+            node_request::<_, serde_json::Value>(
+                provider,
+                network_id,
+                "evm_increaseTime",
+                [duration],
+            )
+            .await?;
+            if !no_mine {
+                node_request::<_, serde_json::Value>(provider, network_id, "evm_mine", ()).await?;
+            }
+        }
+        ChainCommands::SetInterval { interval, .. } => {
+            node_request::<_, serde_json::Value>(
+                provider,
+                network_id,
+                "evm_setIntervalMining",
+                [interval],
+            )
+            .await?;
+        }
+        ChainCommands::Automine { mode, .. } => {
+            node_request::<_, serde_json::Value>(
+                provider,
+                network_id,
+                "evm_setAutomine",
+                [*mode == Toggle::On],
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+async fn chain_head(provider: &Provider<Http>, network_id: u64) -> Result<ChainHead> {
+    let block = provider
+        .get_block(BlockNumber::Latest)
+        .await
+        .map_err(|e| rpc_error(&rpc_action(network_id, "eth_getBlockByNumber"), e))?
+        .ok_or_else(|| {
+            rpc_error(
+                &rpc_action(network_id, "eth_getBlockByNumber"),
+                "no latest block",
+            )
+        })?;
+    Ok(ChainHead {
+        network_id,
+        block_number: block.number.map_or(0, |number| number.as_u64()),
+        timestamp: block.timestamp.as_u64(),
+    })
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Parse a duration in seconds, with an optional s, m, h or d suffix
+pub fn parse_seconds(value: &str) -> std::result::Result<u64, String> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, "s"),
+    };
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+            "invalid duration '{value}', expected seconds or a number with an s, m, h or d suffix"
+        ))
+        }
+    };
+    number
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .ok_or_else(|| format!("invalid duration '{value}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_seconds() {
+        assert_eq!(parse_seconds("90"), Ok(90));
+        assert_eq!(parse_seconds("90s"), Ok(90));
+        assert_eq!(parse_seconds("15m"), Ok(900));
+        assert_eq!(parse_seconds("2h"), Ok(7200));
+        assert_eq!(parse_seconds("1d"), Ok(86400));
+        assert_eq!(parse_seconds("0"), Ok(0));
+        assert!(parse_seconds("").is_err());
+        assert!(parse_seconds("m").is_err());
+        assert!(parse_seconds("5w").is_err());
+        assert!(parse_seconds("1.5h").is_err());
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+    }
+}
@@ -0,0 +1,1183 @@
+//! Inspection and editing of the CLI configuration
+//!
+//! `config show` prints the effective configuration with the source of every
+//! value (config file, environment, contract cache or built-in default),
+//! `config validate` checks a config file without starting anything,
+//! `config init` scaffolds one and `config set` changes a single key in it.
+//!
+//! `config import` writes an `aggsandbox.toml` describing a stack that was
+//! not started by the sandbox's compose files, so bridge, claim and show
+//! commands can be pointed at it. Two sources are supported:
+//!
+//! - a Kurtosis enclave (e.g. kurtosis-cdk): RPC URLs come from
+//!   `kurtosis port print` and contract addresses from the `combined.json`
+//!   written by the contracts deployment service
+//! - a JSON mapping file listing the networks and contracts explicitly
+//!
+//! Chain IDs missing from the source are read from the RPC endpoints.
+
+use crate::config::{ChainConfig, Config, ENV_VARS};
+use crate::error::{AggSandboxError, Result};
+use crate::types::{ChainId, EthereumAddress, RpcUrl};
+use crate::ui::{OutputFormat, UI};
+use ethers::providers::{Http, Middleware, Provider};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, info};
+
+use super::bridge::common::validation_error;
+
+/// Layers a mapping can describe, in network ID order
+const LAYERS: [&str; 3] = ["l1", "l2", "l3"];
+/// Kurtosis service running the contract deployment
+const KURTOSIS_CONTRACTS_SERVICE: &str = "contracts-001";
+/// Deployment summary written by the kurtosis-cdk contracts service
+const KURTOSIS_COMBINED_JSON: &str = "/opt/zkevm/combined.json";
+
+/// `combined.json` keys and the contract names they are imported as
+const KURTOSIS_CONTRACTS: &[(&str, &str, &str)] = &[
+    ("l1", "PolygonZkEVMBridge", "polygonZkEVMBridgeAddress"),
+    (
+        "l1",
+        "PolygonZkEVMGlobalExitRoot",
+        "polygonZkEVMGlobalExitRootAddress",
+    ),
+    (
+        "l1",
+        "GlobalExitRootManager",
+        "polygonZkEVMGlobalExitRootAddress",
+    ),
+    ("l1", "PolygonRollupManager", "polygonRollupManagerAddress"),
+    ("l1", "PolygonZkEVMTimelock", "timelockContractAddress"),
+    ("l1", "PolygonZkEVM", "rollupAddress"),
+    ("l1", "FflonkVerifier", "verifierAddress"),
+    ("l2", "PolygonZkEVMBridge", "polygonZkEVML2BridgeAddress"),
+    (
+        "l2",
+        "GlobalExitRootManager",
+        "polygonZkEVMGlobalExitRootL2Address",
+    ),
+];
+
+/// Config file written by `config init` and `config set` when none exists
+const DEFAULT_CONFIG_FILE: &str = "aggsandbox.toml";
+
+/// Config subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum ConfigCommands {
+    /// 🔎 Show the effective configuration and where each value comes from
+    #[command(
+        long_about = "Print the configuration the CLI resolves, key by key, with its source:
+
+  file     the config file in the working directory (aggsandbox.toml, ...)
+  env      an environment variable or .env, which override the config file
+  cache    contract addresses discovered by `contracts refresh`
+  default  a built-in default
+
+Private keys are redacted unless --show-secrets is given.
+
+Examples:
+  aggsandbox config show
+  aggsandbox config show --output json | jq '.settings[] | select(.source == \"env\")'"
+    )]
+    Show {
+        /// Print private keys instead of redacting them
+        #[arg(long, help = "Print private keys instead of redacting them")]
+        show_secrets: bool,
+    },
+    /// ✅ Validate a config file without starting anything
+    #[command(
+        long_about = "Parse a config file and check its values: URLs, chain IDs, addresses and
+extra L2 network IDs. Environment overrides are not applied, so the file is
+checked as written. Without a path, the config file in the working directory
+is validated, or the environment configuration when there is none.
+
+Exits with an error when the configuration is invalid.
+
+Examples:
+  aggsandbox config validate
+  aggsandbox config validate staging.yaml"
+    )]
+    Validate {
+        /// Config file to validate
+        #[arg(help = "Config file to validate (default: the one in the working directory)")]
+        path: Option<PathBuf>,
+    },
+    /// 🆕 Write a config file with the default configuration
+    #[command(
+        long_about = "Scaffold a config file with the default configuration: the sandbox
+networks, test accounts and the contract addresses currently known from
+the environment. Edit it by hand or with `config set`.
+
+Examples:
+  aggsandbox config init
+  aggsandbox config init --to .aggsandbox.yaml --force"
+    )]
+    Init {
+        /// Config file to write
+        #[arg(
+            long,
+            help = "Config file to write, .toml, .yaml or .yml (default: aggsandbox.toml in the project directory)"
+        )]
+        to: Option<PathBuf>,
+        /// Overwrite an existing config file
+        #[arg(short, long, help = "Overwrite an existing config file")]
+        force: bool,
+    },
+    /// ✏️ Set a single key in the config file
+    #[command(
+        long_about = "Set one configuration key, given by its dotted path as printed by
+`config show`, and write the config file back. The value is parsed as the
+type of the key; the file is validated before it is written.
+
+The config file in the working directory is edited, or a new aggsandbox.toml
+with the default configuration when there is none. Comments and keys the CLI
+does not know are not preserved.
+
+Examples:
+  aggsandbox config set networks.l2.rpc_url http://localhost:9545
+  aggsandbox config set api.timeout 60000
+  aggsandbox config set contracts.l1_contracts.MyToken 0x5FbDB2315678afecb367f032d93F642f64180aa3
+  aggsandbox config set networks.extra_l2s.0.api_url http://localhost:5579 --file staging.toml"
+    )]
+    Set {
+        /// Dotted key such as networks.l1.rpc_url
+        #[arg(help = "Dotted key such as networks.l1.rpc_url")]
+        key: String,
+        /// New value
+        #[arg(allow_hyphen_values = true, help = "New value")]
+        value: String,
+        /// Config file to edit
+        #[arg(
+            long,
+            help = "Config file to edit (default: the one in the working directory)"
+        )]
+        file: Option<PathBuf>,
+    },
+    /// 📥 Import networks and contracts from another Agglayer environment
+    #[command(
+        long_about = "Write an aggsandbox.toml pointing at an existing Agglayer dev stack.
+
+RPC URLs, chain IDs, the bridge service URL and contract addresses are
+extracted from a Kurtosis enclave or from a JSON mapping file, so bridge,
+claim and show commands work against stacks not started with `aggsandbox start`.
+
+Kurtosis services are given as SERVICE:PORT and default to the kurtosis-cdk
+names. Chain IDs that the source does not provide are read from the RPC
+endpoints, so the stack must be running.
+
+JSON mapping format:
+  {
+    \"api_base_url\": \"http://localhost:5577\",
+    \"networks\": {
+      \"l1\": { \"name\": \"Kurtosis L1\", \"rpc_url\": \"http://localhost:32769\", \"chain_id\": 271828 },
+      \"l2\": { \"rpc_url\": \"http://localhost:32780\" }
+    },
+    \"contracts\": {
+      \"l1\": { \"PolygonZkEVMBridge\": \"0x...\" },
+      \"l2\": { \"PolygonZkEVMBridge\": \"0x...\" }
+    }
+  }
+
+Examples:
+  aggsandbox config import --from kurtosis cdk
+  aggsandbox config import --from kurtosis cdk --l2-service op-el-1-op-geth-op-node-001:rpc
+  aggsandbox config import --from json stack.json --to .aggsandbox.yaml --force"
+    )]
+    Import {
+        /// Source environment type
+        #[arg(long, value_enum, help = "Source to import from (kurtosis, json)")]
+        from: ImportSource,
+        /// Enclave name for Kurtosis, mapping file for JSON
+        #[arg(help = "Kurtosis enclave name or JSON mapping file")]
+        source: String,
+        /// Config file to write
+        #[arg(
+            long,
+            help = "Config file to write, .toml, .yaml or .yml (default: aggsandbox.toml in the project directory)"
+        )]
+        to: Option<PathBuf>,
+        /// Overwrite an existing config file
+        #[arg(short, long, help = "Overwrite an existing config file")]
+        force: bool,
+        /// L1 RPC service and port in the enclave
+        #[arg(
+            long,
+            default_value = "el-1-geth-lighthouse:rpc",
+            help = "Kurtosis L1 RPC as SERVICE:PORT"
+        )]
+        l1_service: String,
+        /// L2 RPC service and port in the enclave
+        #[arg(
+            long,
+            default_value = "cdk-erigon-rpc-001:rpc",
+            help = "Kurtosis L2 RPC as SERVICE:PORT"
+        )]
+        l2_service: String,
+        /// Bridge service REST API in the enclave
+        #[arg(
+            long,
+            default_value = "zkevm-bridge-service-001:rpc",
+            help = "Kurtosis bridge service API as SERVICE:PORT"
+        )]
+        api_service: String,
+    },
+}
+
+/// Environment types `config import` understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportSource {
+    /// Running Kurtosis enclave
+    Kurtosis,
+    /// JSON mapping file
+    Json,
+}
+
+/// Chain ID given either as a number or a string
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChainIdValue {
+    Number(u64),
+    Text(String),
+}
+
+impl ChainIdValue {
+    fn to_chain_id(&self) -> Result<ChainId> {
+        match self {
+            ChainIdValue::Number(id) => ChainId::new(id.to_string()),
+            ChainIdValue::Text(id) => ChainId::new(id.clone()),
+        }
+    }
+}
+
+/// One network of an import mapping
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NetworkMapping {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub rpc_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<ChainIdValue>,
+}
+
+/// Networks and contracts of an external environment, keyed by layer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportMapping {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_base_url: Option<String>,
+    pub networks: BTreeMap<String, NetworkMapping>,
+    #[serde(default)]
+    pub contracts: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl ImportMapping {
+    /// Parse and check a JSON mapping
+    pub fn from_json(content: &str) -> Result<Self> {
+        let mapping: ImportMapping = serde_json::from_str(content)
+            .map_err(|e| validation_error(&format!("Invalid import mapping: {e}")))?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    fn validate(&self) -> Result<()> {
+        for layer in self.networks.keys().chain(self.contracts.keys()) {
+            if !LAYERS.contains(&layer.as_str()) {
+                return Err(validation_error(&format!(
+                    "Unknown layer '{layer}' in import mapping, expected l1, l2 or l3"
+                )));
+            }
+        }
+        for layer in ["l1", "l2"] {
+            if !self.networks.contains_key(layer) {
+                return Err(validation_error(&format!(
+                    "Import mapping has no '{layer}' network"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Build a config for the imported stack on top of `base`
+    ///
+    /// Networks and contracts are replaced entirely, since addresses of the
+    /// sandbox deployment mean nothing on another stack; API and account
+    /// settings not in the mapping are kept.
+    pub fn apply(&self, base: Config) -> Result<Config> {
+        let mut config = base;
+        if let Some(url) = &self.api_base_url {
+            config.api.base_url = RpcUrl::new(url.clone())?;
+        }
+
+        config.networks.l1 = self.chain_config("l1")?;
+        config.networks.l2 = self.chain_config("l2")?;
+        config.networks.l3 = if self.networks.contains_key("l3") {
+            Some(self.chain_config("l3")?)
+        } else {
+            None
+        };
+        config.networks.extra_l2s.clear();
+
+        config.contracts.l1_contracts = self.layer_contracts("l1")?;
+        config.contracts.l2_contracts = self.layer_contracts("l2")?;
+        config.contracts.l3_contracts = self.layer_contracts("l3")?;
+        config.contracts.extra_contracts.clear();
+        Ok(config)
+    }
+
+    fn chain_config(&self, layer: &str) -> Result<ChainConfig> {
+        let network = self
+            .networks
+            .get(layer)
+            .ok_or_else(|| validation_error(&format!("Import mapping has no '{layer}' network")))?;
+        let chain_id = network.chain_id.as_ref().ok_or_else(|| {
+            validation_error(&format!("No chain ID known for the '{layer}' network"))
+        })?;
+        Ok(ChainConfig {
+            name: network
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("Imported-{}", layer.to_uppercase())),
+            chain_id: chain_id.to_chain_id()?,
+            rpc_url: RpcUrl::new(network.rpc_url.clone())?,
+            fork_url: None,
+            ws_url: None,
+        })
+    }
+
+    fn layer_contracts(&self, layer: &str) -> Result<HashMap<String, EthereumAddress>> {
+        self.contracts
+            .get(layer)
+            .into_iter()
+            .flatten()
+            .map(|(name, address)| -> Result<(String, EthereumAddress)> {
+                let address = EthereumAddress::new(address.clone()).map_err(|_| {
+                    validation_error(&format!(
+                        "Invalid address '{address}' for {layer} contract {name}"
+                    ))
+                })?;
+                Ok((name.clone(), address))
+            })
+            .collect()
+    }
+
+    /// Fill in chain IDs missing from the mapping by asking the RPC endpoints
+    #[allow(clippy::disallowed_methods)] // Allow tracing macros
+    async fn resolve_chain_ids(&mut self) -> Result<()> {
+        for (layer, network) in self.networks.iter_mut() {
+            if network.chain_id.is_some() {
+                continue;
+            }
+            let provider = Provider::<Http>::try_from(network.rpc_url.as_str()).map_err(|e| {
+                validation_error(&format!("Invalid {layer} RPC URL {}: {e}", network.rpc_url))
+            })?;
+            let chain_id = provider.get_chainid().await.map_err(|e| {
+                AggSandboxError::Other(format!(
+                    "Failed to read the {layer} chain ID from {}: {e}",
+                    network.rpc_url
+                ))
+            })?;
+            debug!(layer = %layer, chain_id = %chain_id, "Resolved chain ID from RPC");
+            network.chain_id = Some(ChainIdValue::Number(chain_id.as_u64()));
+        }
+        Ok(())
+    }
+}
+
+/// Handle config subcommands
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn handle_config(subcommand: ConfigCommands, output: OutputFormat) -> Result<()> {
+    match subcommand {
+        ConfigCommands::Show { show_secrets } => {
+            info!("Showing effective configuration");
+            let config_file = Config::config_file();
+            let file_tree = config_file.map(read_tree).transpose()?;
+            let settings = resolve_settings(
+                &to_tree(&Config::load()?)?,
+                &to_tree(&Config::load_without_cache(false)?)?,
+                file_tree.as_ref(),
+            );
+            print_settings(config_file, &settings, show_secrets, output);
+            Ok(())
+        }
+        ConfigCommands::Validate { path } => {
+            let path = path.or_else(|| Config::config_file().map(Path::to_path_buf));
+            info!(path = ?path, "Validating configuration");
+            let config = match &path {
+                Some(path) => Config::read_file(path)?,
+                None => Config::load_from_env()?,
+            };
+            config.validate()?;
+            config.validate_values()?;
+            print_validation(path.as_deref(), &config, output);
+            Ok(())
+        }
+        ConfigCommands::Init { to, force } => {
+            let to = to.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE));
+            if to.exists() && !force {
+                return Err(validation_error(&format!(
+                    "{} already exists, use --force to overwrite it",
+                    to.display()
+                )));
+            }
+            info!(path = %to.display(), "Writing default configuration");
+            Config::default().save_to_file(&to)?;
+
+            let ui = UI::new(output);
+            if output.is_json() {
+                ui.json(&serde_json::json!({ "config_file": to.display().to_string() }));
+            } else {
+                ui.success(&format!(
+                    "Default configuration written to {}",
+                    to.display()
+                ));
+                ui.tip("Change single keys with `aggsandbox config set <KEY> <VALUE>`");
+            }
+            Ok(())
+        }
+        ConfigCommands::Set { key, value, file } => {
+            let path = file
+                .or_else(|| Config::config_file().map(Path::to_path_buf))
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE));
+            info!(path = %path.display(), key = %key, "Setting configuration key");
+
+            let config = if path.exists() {
+                Config::read_file(&path)?
+            } else {
+                Config::default()
+            };
+            let (config, previous, updated) = set_key(&config, &key, &value)?;
+            config.save_to_file(&path)?;
+
+            let ui = UI::new(output);
+            if output.is_json() {
+                ui.json(&serde_json::json!({
+                    "config_file": path.display().to_string(),
+                    "key": key,
+                    "previous": previous,
+                    "value": updated,
+                }));
+            } else {
+                ui.success(&format!(
+                    "{key} = {} in {}",
+                    display_value(&updated),
+                    path.display()
+                ));
+                if let Some(var) = env_var_for(&key).filter(|var| std::env::var(var).is_ok()) {
+                    ui.warning(&format!("{var} is set and overrides this value"));
+                }
+            }
+            Ok(())
+        }
+        ConfigCommands::Import {
+            from,
+            source,
+            to,
+            force,
+            l1_service,
+            l2_service,
+            api_service,
+        } => {
+            let to = to.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE));
+            if to.exists() && !force {
+                return Err(validation_error(&format!(
+                    "{} already exists, use --force to overwrite it",
+                    to.display()
+                )));
+            }
+
+            let mut mapping = match from {
+                ImportSource::Kurtosis => {
+                    info!(enclave = %source, "Importing configuration from Kurtosis enclave");
+                    kurtosis_mapping(&source, &l1_service, &l2_service, &api_service)?
+                }
+                ImportSource::Json => {
+                    info!(path = %source, "Importing configuration from JSON mapping");
+                    let content = std::fs::read_to_string(&source).map_err(|e| {
+                        validation_error(&format!("Failed to read mapping file {source}: {e}"))
+                    })?;
+                    ImportMapping::from_json(&content)?
+                }
+            };
+            mapping.resolve_chain_ids().await?;
+
+            let config = mapping.apply(Config::default())?;
+            config.save_to_file(&to)?;
+            print_import(&mapping, &to, output);
+            Ok(())
+        }
+    }
+}
+
+/// Where a configuration value comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    File,
+    Env,
+    Cache,
+    Default,
+}
+
+impl Source {
+    fn label(self) -> &'static str {
+        match self {
+            Source::File => "file",
+            Source::Env => "env",
+            Source::Cache => "cache",
+            Source::Default => "default",
+        }
+    }
+}
+
+/// One leaf of the effective configuration
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Setting {
+    pub key: String,
+    pub value: Value,
+    pub source: Source,
+    /// Environment variable the value was read from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variable: Option<String>,
+}
+
+/// Configuration as a JSON tree, the shape keys are addressed in
+fn to_tree(config: &Config) -> Result<Value> {
+    serde_json::to_value(config)
+        .map_err(|e| validation_error(&format!("Failed to encode configuration: {e}")))
+}
+
+/// Config file contents as written, without the defaults serde fills in
+fn read_tree(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        validation_error(&format!(
+            "Failed to read config file {}: {e}",
+            path.display()
+        ))
+    })?;
+    let tree = if path
+        .extension()
+        .is_some_and(|extension| extension == "toml")
+    {
+        toml::from_str(&content).map_err(|e| e.to_string())
+    } else {
+        serde_yaml::from_str(&content).map_err(|e| e.to_string())
+    };
+    tree.map_err(|e| validation_error(&format!("Invalid config file {}: {e}", path.display())))
+}
+
+/// Leaves of a tree keyed by dotted path, array elements by their index
+fn flatten(tree: &Value) -> BTreeMap<String, Value> {
+    fn walk(value: &Value, prefix: &str, leaves: &mut BTreeMap<String, Value>) {
+        let join = |key: &str| {
+            if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{prefix}.{key}")
+            }
+        };
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    walk(value, &join(key), leaves);
+                }
+            }
+            Value::Array(items) => {
+                for (index, value) in items.iter().enumerate() {
+                    walk(value, &join(&index.to_string()), leaves);
+                }
+            }
+            _ => {
+                leaves.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+
+    let mut leaves = BTreeMap::new();
+    walk(tree, "", &mut leaves);
+    leaves
+}
+
+/// Environment variable setting a key of the first three networks or the API
+fn env_var_for(key: &str) -> Option<&'static str> {
+    ENV_VARS
+        .iter()
+        .find(|(config_key, _)| *config_key == key)
+        .map(|(_, var)| *var)
+}
+
+/// Annotate every leaf of the effective configuration with its source
+///
+/// Values that match the config file come from it; the environment is the
+/// only thing overriding the file, so differing values come from there.
+/// Without a file, networks and contracts are read from the environment.
+fn resolve_settings(
+    effective: &Value,
+    uncached: &Value,
+    file_tree: Option<&Value>,
+) -> Vec<Setting> {
+    let uncached = flatten(uncached);
+    let file = file_tree.map(flatten);
+
+    flatten(effective)
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| {
+            let variable = env_var_for(&key)
+                .filter(|var| std::env::var(var).is_ok())
+                .map(str::to_string);
+            // Extra L2s missing from the file and, without a file, contracts
+            // have no built-in default: they are read from the environment
+            let from_env = key.starts_with("networks.extra_l2s.")
+                || (file.is_none() && key.starts_with("contracts."));
+            let source = if !uncached.contains_key(&key) {
+                Source::Cache
+            } else if let Some(written) = file.as_ref().and_then(|file| file.get(&key)) {
+                if *written == value {
+                    Source::File
+                } else {
+                    Source::Env
+                }
+            } else if variable.is_some() || from_env {
+                Source::Env
+            } else {
+                Source::Default
+            };
+            Setting {
+                variable: variable.filter(|_| source == Source::Env),
+                key,
+                value,
+                source,
+            }
+        })
+        .collect()
+}
+
+/// Value as shown in tables and messages
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => "(unset)".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Private keys are shown with their first bytes only
+fn redact(key: &str, value: Value) -> Value {
+    match value {
+        Value::String(secret) if key.starts_with("accounts.private_keys.") => {
+            let visible: String = secret.chars().take(6).collect();
+            Value::String(format!("{visible}… (redacted)"))
+        }
+        other => other,
+    }
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_settings(
+    config_file: Option<&Path>,
+    settings: &[Setting],
+    show_secrets: bool,
+    output: OutputFormat,
+) {
+    let settings: Vec<Setting> = settings
+        .iter()
+        .cloned()
+        .map(|setting| Setting {
+            value: if show_secrets {
+                setting.value
+            } else {
+                redact(&setting.key, setting.value)
+            },
+            ..setting
+        })
+        .collect();
+
+    let ui = UI::new(output);
+    if output.is_json() {
+        ui.json(&serde_json::json!({
+            "config_file": config_file.map(|path| path.display().to_string()),
+            "settings": settings,
+        }));
+        return;
+    }
+
+    match config_file {
+        Some(path) => ui.info(&format!("Config file: {}", path.display())),
+        None => ui.info("No config file, using environment variables and defaults"),
+    }
+    let mut sections: BTreeMap<&str, Vec<(&str, String)>> = BTreeMap::new();
+    for setting in &settings {
+        let (section, key) = setting
+            .key
+            .split_once('.')
+            .unwrap_or((setting.key.as_str(), ""));
+        let source = match &setting.variable {
+            Some(var) => format!("env: {var}"),
+            None => setting.source.label().to_string(),
+        };
+        sections.entry(section).or_default().push((
+            key,
+            format!("{}  [{source}]", display_value(&setting.value)),
+        ));
+    }
+    for (section, rows) in &sections {
+        let rows: Vec<(&str, &str)> = rows
+            .iter()
+            .map(|(key, value)| (*key, value.as_str()))
+            .collect();
+        ui.table(&format!("⚙️  {section}"), &rows);
+    }
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_validation(path: Option<&Path>, config: &Config, output: OutputFormat) {
+    let ui = UI::new(output);
+    let networks = config.networks.network_ids();
+    let contracts: usize = config
+        .contracts
+        .layers()
+        .iter()
+        .map(|(_, contracts)| contracts.len())
+        .sum();
+    if output.is_json() {
+        ui.json(&serde_json::json!({
+            "config_file": path.map(|path| path.display().to_string()),
+            "valid": true,
+            "networks": networks,
+            "contracts": contracts,
+        }));
+        return;
+    }
+
+    match path {
+        Some(path) => ui.success(&format!("{} is valid", path.display())),
+        None => ui.success("No config file; the environment configuration is valid"),
+    }
+    ui.info(&format!(
+        "  {} networks ({}), {contracts} contract addresses",
+        networks.len(),
+        networks
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    ));
+}
+
+/// Parse a command line value as the type of the value it replaces
+fn parse_value(key: &str, current: Option<&Value>, raw: &str) -> Result<Value> {
+    let invalid = |expected: &str| {
+        validation_error(&format!(
+            "Invalid value '{raw}' for {key}: expected {expected}"
+        ))
+    };
+    match current {
+        Some(Value::Number(_)) => raw
+            .trim()
+            .parse::<u64>()
+            .map(Value::from)
+            .map_err(|_| invalid("a non-negative integer")),
+        Some(Value::Bool(_)) => raw
+            .trim()
+            .parse::<bool>()
+            .map(Value::from)
+            .map_err(|_| invalid("true or false")),
+        Some(Value::Object(_)) | Some(Value::Array(_)) => Err(validation_error(&format!(
+            "{key} is a section, set one of its keys instead (see `aggsandbox config show`)"
+        ))),
+        Some(Value::String(_)) | Some(Value::Null) | None => Ok(Value::String(raw.to_string())),
+    }
+}
+
+/// Set a dotted key in a configuration, returning the new configuration with
+/// the previous and new value of the key
+///
+/// Keys must already exist, except optional values (e.g. `ws_url`) and new
+/// entries of maps such as `contracts.l1_contracts`.
+fn set_key(config: &Config, key: &str, raw: &str) -> Result<(Config, Value, Value)> {
+    let unknown = || {
+        validation_error(&format!(
+            "Unknown configuration key '{key}' (see `aggsandbox config show` for the keys)"
+        ))
+    };
+    let mut tree = to_tree(config)?;
+    let (parent_path, leaf) = key.rsplit_once('.').ok_or_else(unknown)?;
+
+    let mut parent = &mut tree;
+    for segment in parent_path.split('.') {
+        parent = match parent {
+            Value::Object(map) => map.get_mut(segment),
+            Value::Array(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get_mut(index)),
+            _ => None,
+        }
+        .ok_or_else(unknown)?;
+    }
+    let Value::Object(map) = parent else {
+        return Err(unknown());
+    };
+    let previous = map.get(leaf).cloned().unwrap_or(Value::Null);
+    let value = parse_value(key, map.get(leaf), raw)?;
+    map.insert(leaf.to_string(), value.clone());
+
+    let updated: Config = serde_json::from_value(tree)
+        .map_err(|e| validation_error(&format!("Invalid value '{raw}' for {key}: {e}")))?;
+    // Fields the configuration does not know are dropped when deserializing
+    if flatten(&to_tree(&updated)?).get(key) != Some(&value) {
+        return Err(unknown());
+    }
+    updated.validate()?;
+    updated.validate_values()?;
+    Ok((updated, previous, value))
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_import(mapping: &ImportMapping, path: &Path, output: OutputFormat) {
+    let ui = UI::new(output);
+    if output.is_json() {
+        ui.json(&serde_json::json!({
+            "config_file": path.display().to_string(),
+            "mapping": mapping,
+        }));
+        return;
+    }
+
+    ui.success(&format!(
+        "Imported configuration written to {}",
+        path.display()
+    ));
+    for (layer, network) in &mapping.networks {
+        let chain_id = match &network.chain_id {
+            Some(ChainIdValue::Number(id)) => id.to_string(),
+            Some(ChainIdValue::Text(id)) => id.clone(),
+            None => "unknown".to_string(),
+        };
+        let contracts = mapping.contracts.get(layer).map_or(0, BTreeMap::len);
+        ui.info(&format!(
+            "  {layer}: {} (chain ID {chain_id}, {contracts} contracts)",
+            network.rpc_url
+        ));
+    }
+    if let Some(url) = &mapping.api_base_url {
+        ui.info(&format!("  bridge service: {url}"));
+    }
+    if Path::new(".env").exists() {
+        ui.warning("RPC_* and CHAIN_ID_* variables in .env still override the imported networks");
+    }
+    ui.tip("Sign with keys funded on the imported stack using --account or --private-key");
+}
+
+/// Build a mapping from a running Kurtosis enclave
+fn kurtosis_mapping(
+    enclave: &str,
+    l1_service: &str,
+    l2_service: &str,
+    api_service: &str,
+) -> Result<ImportMapping> {
+    let mut networks = BTreeMap::new();
+    networks.insert(
+        "l1".to_string(),
+        NetworkMapping {
+            name: Some(format!("{enclave}-L1")),
+            rpc_url: kurtosis_port_url(enclave, l1_service)?,
+            chain_id: None,
+        },
+    );
+    networks.insert(
+        "l2".to_string(),
+        NetworkMapping {
+            name: Some(format!("{enclave}-L2")),
+            rpc_url: kurtosis_port_url(enclave, l2_service)?,
+            chain_id: None,
+        },
+    );
+
+    let combined = run_kurtosis(&[
+        "service",
+        "exec",
+        enclave,
+        KURTOSIS_CONTRACTS_SERVICE,
+        &format!("cat {KURTOSIS_COMBINED_JSON}"),
+    ])?;
+
+    let mapping = ImportMapping {
+        api_base_url: Some(kurtosis_port_url(enclave, api_service)?),
+        networks,
+        contracts: contracts_from_combined_json(&combined)?,
+    };
+    mapping.validate()?;
+    Ok(mapping)
+}
+
+/// Public URL of `SERVICE:PORT` in an enclave
+fn kurtosis_port_url(enclave: &str, service_port: &str) -> Result<String> {
+    let (service, port) = service_port.split_once(':').ok_or_else(|| {
+        validation_error(&format!(
+            "Invalid Kurtosis service '{service_port}', expected SERVICE:PORT"
+        ))
+    })?;
+    let printed = run_kurtosis(&["port", "print", enclave, service, port])?;
+    Ok(normalize_port_url(&printed))
+}
+
+/// `kurtosis port print` omits the scheme for ports without an application protocol
+fn normalize_port_url(printed: &str) -> String {
+    let url = printed.trim();
+    if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("http://{url}")
+    }
+}
+
+/// Contract addresses from the kurtosis-cdk `combined.json`
+///
+/// `kurtosis service exec` surrounds the command output with status lines,
+/// so the JSON object is cut out of the output first.
+fn contracts_from_combined_json(
+    output: &str,
+) -> Result<BTreeMap<String, BTreeMap<String, String>>> {
+    let json = match (output.find('{'), output.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &output[start..=end],
+        _ => {
+            return Err(validation_error(&format!(
+                "No JSON found in {KURTOSIS_COMBINED_JSON} of {KURTOSIS_CONTRACTS_SERVICE}"
+            )))
+        }
+    };
+    let combined: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| validation_error(&format!("Invalid {KURTOSIS_COMBINED_JSON}: {e}")))?;
+
+    let mut contracts: BTreeMap<String, BTreeMap<String, String>> = BTreeMap::new();
+    for (layer, name, key) in KURTOSIS_CONTRACTS {
+        if let Some(address) = combined.get(key).and_then(|value| value.as_str()) {
+            contracts
+                .entry(layer.to_string())
+                .or_default()
+                .insert(name.to_string(), address.to_string());
+        }
+    }
+    Ok(contracts)
+}
+
+fn run_kurtosis(args: &[&str]) -> Result<String> {
+    let output = Command::new("kurtosis").args(args).output().map_err(|e| {
+        AggSandboxError::Other(format!(
+            "Failed to run kurtosis: {e}. Is the Kurtosis CLI installed?"
+        ))
+    })?;
+    if !output.status.success() {
+        return Err(AggSandboxError::Other(format!(
+            "`kurtosis {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    const MAPPING: &str = r#"{
+        "api_base_url": "http://localhost:32801",
+        "networks": {
+            "l1": { "name": "Kurtosis L1", "rpc_url": "http://localhost:32769", "chain_id": 271828 },
+            "l2": { "rpc_url": "http://localhost:32780", "chain_id": "10101" }
+        },
+        "contracts": {
+            "l1": { "PolygonZkEVMBridge": "0x83F138B325164b162b320F797b57f6f7E235ABAC" },
+            "l2": { "PolygonZkEVMBridge": "0x83F138B325164b162b320F797b57f6f7E235ABAC" }
+        }
+    }"#;
+
+    #[test]
+    fn test_apply_mapping() {
+        let mapping = ImportMapping::from_json(MAPPING).unwrap();
+
+        let config = mapping.apply(Config::default()).unwrap();
+
+        assert_eq!(config.api.base_url.as_str(), "http://localhost:32801");
+        assert_eq!(config.networks.l1.name, "Kurtosis L1");
+        assert_eq!(config.networks.l1.chain_id.as_str(), "271828");
+        assert_eq!(config.networks.l2.name, "Imported-L2");
+        assert_eq!(config.networks.l2.chain_id.as_str(), "10101");
+        assert!(config.networks.l3.is_none());
+        assert_eq!(config.contracts.l1_contracts.len(), 1);
+        assert!(config.contracts.l3_contracts.is_empty());
+        assert_ne!(
+            config.contracts.get_contract("l2", "PolygonZkEVMBridge"),
+            "Not deployed"
+        );
+    }
+
+    #[test]
+    fn test_mapping_validation() {
+        assert!(
+            ImportMapping::from_json(r#"{"networks": {"l1": {"rpc_url": "http://a:1"}}}"#).is_err()
+        );
+        assert!(ImportMapping::from_json(
+            r#"{"networks": {"l1": {"rpc_url": "http://a:1"}, "l2": {"rpc_url": "http://b:2"}, "l9": {"rpc_url": "http://c:3"}}}"#
+        )
+        .is_err());
+        assert!(ImportMapping::from_json(r#"{"networks": {}, "rpc": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_apply_requires_chain_ids() {
+        let mapping = ImportMapping::from_json(
+            r#"{"networks": {"l1": {"rpc_url": "http://a:1"}, "l2": {"rpc_url": "http://b:2"}}}"#,
+        )
+        .unwrap();
+
+        assert!(mapping.apply(Config::default()).is_err());
+    }
+
+    #[test]
+    fn test_contracts_from_combined_json() {
+        let output = r#"The command was successfully executed and returned '0'. Output was:
+============
+{
+  "polygonZkEVMBridgeAddress": "0x83F138B325164b162b320F797b57f6f7E235ABAC",
+  "polygonZkEVML2BridgeAddress": "0x83F138B325164b162b320F797b57f6f7E235ABAC",
+  "polygonRollupManagerAddress": "0x2F50ef6b8e8Ee4E579B17619A92dE3E2ffbD8AD2",
+  "bridgeGenBlockNumber": 31
+}
+============"#;
+
+        let contracts = contracts_from_combined_json(output).unwrap();
+
+        assert_eq!(contracts["l1"].len(), 2);
+        assert_eq!(
+            contracts["l2"]["PolygonZkEVMBridge"],
+            "0x83F138B325164b162b320F797b57f6f7E235ABAC"
+        );
+        assert!(contracts_from_combined_json("no output").is_err());
+    }
+
+    #[test]
+    fn test_flatten_addresses_leaves_by_dotted_key() {
+        let tree = serde_json::json!({
+            "api": { "timeout": 30000 },
+            "accounts": { "accounts": ["0xa", "0xb"] },
+        });
+        let leaves = flatten(&tree);
+        assert_eq!(leaves["api.timeout"], 30000);
+        assert_eq!(leaves["accounts.accounts.1"], "0xb");
+        assert_eq!(leaves.len(), 3);
+    }
+
+    #[test]
+    fn test_resolve_settings_sources() {
+        let effective = serde_json::json!({
+            "accounts": { "private_keys": ["0x01", "0x02"] },
+            "contracts": { "l1_contracts": { "Bridge": "0xb", "Token": "0xt" } },
+        });
+        let uncached = serde_json::json!({
+            "accounts": { "private_keys": ["0x01", "0x02"] },
+            "contracts": { "l1_contracts": { "Bridge": "0xb" } },
+        });
+        let file = serde_json::json!({ "accounts": { "private_keys": ["0x01"] } });
+
+        let sources = |file: Option<&Value>| -> BTreeMap<String, Source> {
+            resolve_settings(&effective, &uncached, file)
+                .into_iter()
+                .map(|setting| (setting.key, setting.source))
+                .collect()
+        };
+
+        let with_file = sources(Some(&file));
+        assert_eq!(with_file["accounts.private_keys.0"], Source::File);
+        assert_eq!(with_file["accounts.private_keys.1"], Source::Default);
+        assert_eq!(with_file["contracts.l1_contracts.Bridge"], Source::Default);
+        assert_eq!(with_file["contracts.l1_contracts.Token"], Source::Cache);
+
+        let without_file = sources(None);
+        assert_eq!(without_file["contracts.l1_contracts.Bridge"], Source::Env);
+        assert_eq!(without_file["accounts.private_keys.0"], Source::Default);
+
+        let overridden = serde_json::json!({ "accounts": { "private_keys": ["0x09"] } });
+        assert_eq!(
+            sources(Some(&overridden))["accounts.private_keys.0"],
+            Source::Env
+        );
+    }
+
+    #[test]
+    fn test_redact_private_keys() {
+        let redacted = redact("accounts.private_keys.0", Value::from("0xac0974bec39a17e3"));
+        assert_eq!(redacted, "0xac09… (redacted)");
+        assert_eq!(redact("api.timeout", Value::from(1)), 1);
+    }
+
+    #[test]
+    fn test_set_key_parses_by_type() {
+        let config = Config::default();
+
+        let (updated, previous, value) = set_key(&config, "api.timeout", "60000").unwrap();
+        assert_eq!(updated.api.timeout.as_millis(), 60000);
+        assert_eq!(previous, 30000);
+        assert_eq!(value, 60000);
+
+        let (updated, _, _) =
+            set_key(&config, "networks.l2.rpc_url", "http://localhost:9545").unwrap();
+        assert_eq!(
+            updated.networks.l2.rpc_url.as_str(),
+            "http://localhost:9545"
+        );
+
+        let (updated, previous, _) =
+            set_key(&config, "networks.l1.ws_url", "ws://localhost:8545").unwrap();
+        assert!(previous.is_null());
+        assert_eq!(
+            updated
+                .networks
+                .l1
+                .ws_url
+                .map(|url| url.as_str().to_string()),
+            Some("ws://localhost:8545".to_string())
+        );
+
+        let (updated, _, _) = set_key(
+            &config,
+            "contracts.l1_contracts.MyToken",
+            "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+        )
+        .unwrap();
+        assert!(updated.contracts.l1_contracts.contains_key("MyToken"));
+    }
+
+    #[test]
+    fn test_set_key_rejects_invalid_keys_and_values() {
+        let config = Config::default();
+        assert!(set_key(&config, "api.timeout", "soon").is_err());
+        assert!(set_key(&config, "api.timeout", "0").is_err());
+        assert!(set_key(&config, "api.unknown", "1").is_err());
+        assert!(set_key(&config, "networks.l9.rpc_url", "http://a:1").is_err());
+        assert!(set_key(&config, "networks", "x").is_err());
+        assert!(set_key(&config, "networks.l1", "x").is_err());
+        assert!(set_key(&config, "networks.l1.rpc_url", "").is_err());
+        assert!(set_key(&config, "contracts.l1_contracts.MyToken", "0x12").is_err());
+    }
+
+    #[test]
+    fn test_normalize_port_url() {
+        assert_eq!(
+            normalize_port_url("http://127.0.0.1:32769\n"),
+            "http://127.0.0.1:32769"
+        );
+        assert_eq!(
+            normalize_port_url("127.0.0.1:32801"),
+            "http://127.0.0.1:32801"
+        );
+    }
+}
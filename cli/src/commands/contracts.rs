@@ -0,0 +1,708 @@
+use crate::artifacts::IMPLEMENTATION_SLOT;
+use crate::commands::bridge::common::{get_network_name, validate_network_id, validation_error};
+use crate::commands::bridge::get_provider;
+use crate::commands::registry::{list_entries, STANDARD_ENTRIES};
+use crate::config::{layer_name, Config, ContractCache, ContractConfig, CONTRACT_CACHE_FILE};
+use crate::error::{AggSandboxError, DockerError, Result};
+use crate::types::EthereumAddress;
+use crate::ui::{OutputFormat, UI};
+use crate::validation::parse_network_id;
+use ethers::prelude::*;
+use ethers::types::transaction::eip2718::TypedTransaction;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tracing::debug;
+
+/// EIP-170 limit on deployed bytecode size
+const MAX_CODE_SIZE: usize = 24_576;
+/// Share of the limit above which a contract is flagged as close to it
+const NEAR_LIMIT_PERCENT: usize = 90;
+
+/// EIP-1967 admin slot: `bytes32(uint256(keccak256("eip1967.proxy.admin")) - 1)`
+const ADMIN_SLOT: &str = "0xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103";
+/// EIP-1967 beacon slot: `bytes32(uint256(keccak256("eip1967.proxy.beacon")) - 1)`
+const BEACON_SLOT: &str = "0xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50";
+/// Selector of `owner()`
+const OWNER_SELECTOR: [u8; 4] = [0x8d, 0xa5, 0xcb, 0x5b];
+
+/// Compose service deploying the sandbox contracts
+#[cfg(feature = "docker")]
+const DEPLOYER_SERVICE: &str = "contract-deployer";
+/// Env file the deployer writes the deployed addresses to, inside its container
+#[cfg(feature = "docker")]
+const DEPLOYER_ARTIFACT: &str = "/app/output/deployed-contracts.env";
+/// Local copy of the deployer's env file
+#[cfg(feature = "docker")]
+const DEPLOYER_ARTIFACT_COPY: &str = ".aggsandbox/deployed-contracts.env";
+
+/// Contract inspection subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum ContractsCommands {
+    /// 📐 Report bytecode sizes, proxies and owners of deployed contracts
+    #[command(
+        long_about = "List the contracts deployed by the sandbox with their structure.
+
+For every contract in the configuration the report shows:
+  • the deployed bytecode size and how close it is to the 24576-byte EIP-170 limit
+  • for EIP-1967 proxies, the implementation (and its size), proxy admin and beacon
+  • the owner() of Ownable contracts
+
+Contracts without code, above the size limit or whose proxy points to an
+address without code are flagged.
+
+Examples:
+  aggsandbox contracts report
+  aggsandbox contracts report --network-id 1
+  aggsandbox contracts report --output json"
+    )]
+    Report {
+        /// Only report this network
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID (default: all networks)")]
+        network_id: Option<u64>,
+    },
+    /// 📋 List contract addresses and where they come from
+    #[command(long_about = "List the address of every sandbox contract.
+
+Addresses come from the configuration (the variables the deployer writes to
+.env, or a config file) and, for contracts it lacks, from the cache written by
+`aggsandbox contracts refresh`. The source of each address is shown.
+
+Examples:
+  aggsandbox contracts list
+  aggsandbox contracts list --network-id 1
+  aggsandbox contracts list --output json")]
+    List {
+        /// Only list this network
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID (default: all networks)")]
+        network_id: Option<u64>,
+    },
+    /// 🔍 Discover the deployed contracts and cache their addresses
+    #[command(
+        long_about = "Discover the addresses of the deployed contracts without relying on
+environment variables, and cache them in .aggsandbox/contracts.json.
+
+Two sources are read:
+  • the env file the contract-deployer container wrote its deployment to
+  • the on-chain registry at its CREATE2 address on every network
+    (see `aggsandbox registry`)
+
+Every command then fills contracts missing from the configuration from the
+cache, so a missing variable no longer shows up as \"Not deployed\".
+Configured addresses always take precedence over discovered ones.
+
+Examples:
+  aggsandbox contracts refresh
+  aggsandbox contracts refresh --output json"
+    )]
+    Refresh,
+}
+
+/// Where the address of a contract comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContractSource {
+    /// Environment variables or a config file
+    Configured,
+    /// Discovery cache written by `contracts refresh`
+    Discovered,
+}
+
+/// Address of one contract, as listed by `contracts list`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContractEntry {
+    pub network_id: u64,
+    pub name: String,
+    pub address: String,
+    pub source: ContractSource,
+}
+
+/// Structure of one deployed contract
+#[derive(Debug, Clone, Serialize)]
+pub struct ContractReport {
+    pub network_id: u64,
+    pub name: String,
+    pub address: Address,
+    pub code_size: usize,
+    pub implementation: Option<Address>,
+    pub implementation_code_size: Option<usize>,
+    pub proxy_admin: Option<Address>,
+    pub beacon: Option<Address>,
+    pub owner: Option<Address>,
+    pub issues: Vec<String>,
+}
+
+impl ContractReport {
+    /// One-line summary used in the human readable table
+    fn summary(&self) -> String {
+        let mut parts = vec![format!("{:#x}", self.address), format_size(self.code_size)];
+        if let Some(implementation) = self.implementation {
+            parts.push(format!(
+                "proxy → {implementation:#x} ({})",
+                self.implementation_code_size
+                    .map_or_else(|| "?".to_string(), format_size)
+            ));
+        }
+        if let Some(beacon) = self.beacon {
+            parts.push(format!("beacon {beacon:#x}"));
+        }
+        if let Some(admin) = self.proxy_admin {
+            parts.push(format!("admin {admin:#x}"));
+        }
+        if let Some(owner) = self.owner {
+            parts.push(format!("owner {owner:#x}"));
+        }
+        parts.join(" | ")
+    }
+}
+
+/// Handle contracts commands
+pub async fn handle_contracts(subcommand: ContractsCommands, output: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+
+    match subcommand {
+        ContractsCommands::List { network_id } => {
+            if let Some(network) = network_id {
+                validate_network_id(network, "Network")?;
+            }
+            let configured = Config::load_without_cache(false)?.contracts;
+            let entries = contract_entries(&config.contracts, &configured, network_id);
+            let cache = ContractCache::load(Path::new(CONTRACT_CACHE_FILE));
+            print_entries(&entries, cache.as_ref(), output);
+            Ok(())
+        }
+        ContractsCommands::Refresh => {
+            let (cache, failures) = discover_contracts(&config).await?;
+            let path = Path::new(CONTRACT_CACHE_FILE);
+            cache.save(path)?;
+            let filled = Config::load_without_cache(false)?
+                .contracts
+                .fill_missing(&cache.contracts);
+            print_refresh(&cache, filled, &failures, output);
+            Ok(())
+        }
+        ContractsCommands::Report { network_id } => {
+            let networks = match network_id {
+                Some(network) => {
+                    validate_network_id(network, "Network")?;
+                    vec![network]
+                }
+                None => config.networks.network_ids(),
+            };
+
+            let mut reports = Vec::new();
+            for network in networks {
+                reports.extend(report_network(&config, network).await?);
+            }
+            print_reports(&reports, output);
+            Ok(())
+        }
+    }
+}
+
+/// Number of contracts over all layers
+fn contract_count(contracts: &ContractConfig) -> usize {
+    contracts
+        .layers()
+        .iter()
+        .map(|(_, contracts)| contracts.len())
+        .sum()
+}
+
+/// Network ID of a layer name (`l1` is network 0)
+fn network_of_layer(layer: &str) -> Option<u64> {
+    layer.strip_prefix('l')?.parse::<u64>().ok()?.checked_sub(1)
+}
+
+/// Every contract of `contracts`, marked discovered when `configured` lacks it
+pub fn contract_entries(
+    contracts: &ContractConfig,
+    configured: &ContractConfig,
+    network_id: Option<u64>,
+) -> Vec<ContractEntry> {
+    let configured_layers: BTreeMap<&str, _> = configured.layers().into_iter().collect();
+    let mut entries = Vec::new();
+    for (layer, layer_contracts) in contracts.layers() {
+        let Some(network) = network_of_layer(layer) else {
+            continue;
+        };
+        if network_id.is_some_and(|id| id != network) {
+            continue;
+        }
+        // BTreeMap keeps each network sorted by contract name
+        let sorted: BTreeMap<&String, &EthereumAddress> = layer_contracts.iter().collect();
+        for (name, address) in sorted {
+            let is_configured = configured_layers
+                .get(layer)
+                .is_some_and(|contracts| contracts.contains_key(name));
+            entries.push(ContractEntry {
+                network_id: network,
+                name: name.clone(),
+                address: address.to_string(),
+                source: if is_configured {
+                    ContractSource::Configured
+                } else {
+                    ContractSource::Discovered
+                },
+            });
+        }
+    }
+    entries
+}
+
+/// Discover the deployed contracts from the deployer's artifact and the registry
+///
+/// Addresses from the deployer take precedence. Returns the discovered
+/// addresses along with the sources that could not be read, and fails only
+/// when no source yields any address.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn discover_contracts(config: &Config) -> Result<(ContractCache, Vec<String>)> {
+    let mut contracts = ContractConfig::default();
+    let mut sources = Vec::new();
+    let mut failures = Vec::new();
+
+    match deployer_contracts() {
+        Ok(found) => {
+            contracts.fill_missing(&found);
+            sources.push("deployer".to_string());
+        }
+        Err(e) => {
+            debug!(error = %e, "Deployer artifact not available");
+            failures.push(format!("deployer: {e}"));
+        }
+    }
+
+    let mut registry_found = 0;
+    for network_id in config.networks.network_ids() {
+        match list_entries(config, network_id).await {
+            Ok(entries) => {
+                let layer = contracts.layer_mut(&layer_name(network_id));
+                for (entry, address) in entries {
+                    let Some(&(_, name)) = STANDARD_ENTRIES.iter().find(|(key, _)| *key == entry)
+                    else {
+                        continue;
+                    };
+                    let Ok(address) = EthereumAddress::new(format!("{address:#x}")) else {
+                        continue;
+                    };
+                    if !layer.contains_key(name) {
+                        layer.insert(name.to_string(), address);
+                        registry_found += 1;
+                    }
+                }
+            }
+            Err(e) => {
+                debug!(network_id, error = %e, "Registry not available");
+                failures.push(format!("registry on network {network_id}: {e}"));
+            }
+        }
+    }
+    if registry_found > 0 {
+        sources.push("registry".to_string());
+    }
+    // Extra L2s without any discovered contract
+    contracts
+        .extra_contracts
+        .retain(|_, contracts| !contracts.is_empty());
+
+    if contract_count(&contracts) == 0 {
+        return Err(validation_error(&format!(
+            "No contract addresses discovered ({}). Is the sandbox running?",
+            failures.join("; ")
+        )));
+    }
+
+    let discovered_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    Ok((
+        ContractCache {
+            discovered_at,
+            sources,
+            contracts,
+        },
+        failures,
+    ))
+}
+
+/// Addresses in the env file written by the contract-deployer container
+#[cfg(feature = "docker")]
+fn deployer_contracts() -> Result<ContractConfig> {
+    use crate::docker::create_auto_docker_builder;
+
+    let destination = Path::new(DEPLOYER_ARTIFACT_COPY);
+    if let Some(dir) = destination.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let output = create_auto_docker_builder()
+        .build_cp_command(DEPLOYER_SERVICE, DEPLOYER_ARTIFACT, destination)
+        .output()
+        .map_err(|e| DockerError::command_failed("cp", &e.to_string()))?;
+    if !output.status.success() {
+        return Err(DockerError::command_failed(
+            "cp",
+            String::from_utf8_lossy(&output.stderr).trim(),
+        )
+        .into());
+    }
+
+    let content = std::fs::read_to_string(destination)?;
+    let contracts = ContractConfig::from_env_map(Config::parse_env(&content));
+    if contract_count(&contracts) == 0 {
+        return Err(validation_error(&format!(
+            "{DEPLOYER_ARTIFACT} lists no contract addresses"
+        )));
+    }
+    Ok(contracts)
+}
+
+/// The deployer container cannot be read without the `docker` feature
+#[cfg(not(feature = "docker"))]
+fn deployer_contracts() -> Result<ContractConfig> {
+    Err(DockerError::command_failed("cp", "built without the docker feature").into())
+}
+
+/// Inspect every configured contract of a network
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+async fn report_network(config: &Config, network_id: u64) -> Result<Vec<ContractReport>> {
+    let contracts = match network_id {
+        0 => &config.contracts.l1_contracts,
+        1 => &config.contracts.l2_contracts,
+        _ => &config.contracts.l3_contracts,
+    };
+    let provider = get_provider(config, network_id).await?;
+
+    // BTreeMap keeps the report sorted by contract name
+    let sorted: BTreeMap<&String, _> = contracts.iter().collect();
+    let mut reports = Vec::with_capacity(sorted.len());
+    for (name, address) in sorted {
+        let Ok(address) = address.as_str().parse::<Address>() else {
+            debug!(name = %name, "Skipping contract with invalid address");
+            continue;
+        };
+        reports.push(inspect_contract(&provider, network_id, name, address).await?);
+    }
+    Ok(reports)
+}
+
+/// Read bytecode size, EIP-1967 slots and owner of a contract
+async fn inspect_contract(
+    provider: &Provider<Http>,
+    network_id: u64,
+    name: &str,
+    address: Address,
+) -> Result<ContractReport> {
+    let code_size = code_size_of(provider, address).await?;
+    let implementation = read_address_slot(provider, address, IMPLEMENTATION_SLOT).await;
+    let implementation_code_size = match implementation {
+        Some(implementation) => Some(code_size_of(provider, implementation).await?),
+        None => None,
+    };
+    let proxy_admin = read_address_slot(provider, address, ADMIN_SLOT).await;
+    let beacon = read_address_slot(provider, address, BEACON_SLOT).await;
+    let owner = read_owner(provider, address).await;
+
+    let mut issues = size_issues(code_size);
+    if code_size == 0 {
+        issues.push("no code deployed at this address".to_string());
+    }
+    if let Some(size) = implementation_code_size {
+        if size == 0 {
+            issues.push("proxy implementation has no code".to_string());
+        }
+        issues.extend(
+            size_issues(size)
+                .into_iter()
+                .map(|issue| format!("implementation {issue}")),
+        );
+    }
+
+    Ok(ContractReport {
+        network_id,
+        name: name.to_string(),
+        address,
+        code_size,
+        implementation,
+        implementation_code_size,
+        proxy_admin,
+        beacon,
+        owner,
+        issues,
+    })
+}
+
+async fn code_size_of(provider: &Provider<Http>, address: Address) -> Result<usize> {
+    provider
+        .get_code(address, None)
+        .await
+        .map(|code| code.len())
+        .map_err(|e| AggSandboxError::Other(format!("Failed to read code of {address:#x}: {e}")))
+}
+
+/// Read an address stored in a storage slot, `None` when the slot is empty
+async fn read_address_slot(
+    provider: &Provider<Http>,
+    address: Address,
+    slot: &str,
+) -> Option<Address> {
+    let slot = slot.parse::<H256>().ok()?;
+    let value = provider.get_storage_at(address, slot, None).await.ok()?;
+    address_from_word(value.as_bytes())
+}
+
+/// Call `owner()`, `None` for contracts that are not Ownable
+async fn read_owner(provider: &Provider<Http>, address: Address) -> Option<Address> {
+    let call: TypedTransaction = TransactionRequest::new()
+        .to(address)
+        .data(Bytes::from(OWNER_SELECTOR.to_vec()))
+        .into();
+    let result = provider.call(&call, None).await.ok()?;
+    if result.len() != 32 {
+        return None;
+    }
+    address_from_word(&result)
+}
+
+/// Interpret a 32-byte word as a left-padded address, `None` when zero or not an address
+fn address_from_word(word: &[u8]) -> Option<Address> {
+    if word.len() != 32 || word[..12].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    let address = Address::from_slice(&word[12..]);
+    (!address.is_zero()).then_some(address)
+}
+
+/// Flag bytecode above or close to the EIP-170 limit
+fn size_issues(code_size: usize) -> Vec<String> {
+    if code_size > MAX_CODE_SIZE {
+        vec![format!(
+            "bytecode size {code_size} exceeds the {MAX_CODE_SIZE}-byte EIP-170 limit"
+        )]
+    } else if code_size * 100 >= MAX_CODE_SIZE * NEAR_LIMIT_PERCENT {
+        vec![format!(
+            "bytecode size {code_size} is within {}% of the EIP-170 limit",
+            100 - NEAR_LIMIT_PERCENT
+        )]
+    } else {
+        Vec::new()
+    }
+}
+
+fn format_size(code_size: usize) -> String {
+    format!(
+        "{:.1} KB ({}% of limit)",
+        code_size as f64 / 1024.0,
+        code_size * 100 / MAX_CODE_SIZE
+    )
+}
+
+fn print_reports(reports: &[ContractReport], output: OutputFormat) {
+    let ui = UI::new(output);
+    if output.is_json() {
+        ui.json(&serde_json::to_value(reports).unwrap_or_default());
+        return;
+    }
+
+    let mut networks: Vec<u64> = reports.iter().map(|report| report.network_id).collect();
+    networks.dedup();
+    for network_id in networks {
+        let network_reports: Vec<&ContractReport> = reports
+            .iter()
+            .filter(|report| report.network_id == network_id)
+            .collect();
+        let summaries: Vec<String> = network_reports.iter().map(|r| r.summary()).collect();
+        let rows: Vec<(&str, &str)> = network_reports
+            .iter()
+            .zip(&summaries)
+            .map(|(report, summary)| (report.name.as_str(), summary.as_str()))
+            .collect();
+        ui.table(
+            &format!(
+                "📐 Contracts on network {network_id} ({})",
+                get_network_name(network_id)
+            ),
+            &rows,
+        );
+    }
+
+    for report in reports {
+        for issue in &report.issues {
+            ui.warning(&format!(
+                "{} on network {}: {issue}",
+                report.name, report.network_id
+            ));
+        }
+    }
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_entries(entries: &[ContractEntry], cache: Option<&ContractCache>, output: OutputFormat) {
+    let ui = UI::new(output);
+    let discovered_at = cache.and_then(|cache| {
+        chrono::DateTime::from_timestamp(cache.discovered_at as i64, 0)
+            .map(|time| time.to_rfc3339())
+    });
+    if output.is_json() {
+        ui.json(&serde_json::json!({
+            "contracts": entries,
+            "cache": cache.map(|cache| serde_json::json!({
+                "path": CONTRACT_CACHE_FILE,
+                "discovered_at": discovered_at,
+                "sources": cache.sources,
+            })),
+        }));
+        return;
+    }
+
+    if entries.is_empty() {
+        ui.warning("No contract addresses known");
+        ui.tip("Run `aggsandbox contracts refresh` to discover the deployed contracts");
+        return;
+    }
+
+    let mut networks: Vec<u64> = entries.iter().map(|entry| entry.network_id).collect();
+    networks.dedup();
+    for network_id in networks {
+        let values: Vec<(&str, String)> = entries
+            .iter()
+            .filter(|entry| entry.network_id == network_id)
+            .map(|entry| match entry.source {
+                ContractSource::Configured => (entry.name.as_str(), entry.address.clone()),
+                ContractSource::Discovered => (
+                    entry.name.as_str(),
+                    format!("{} (discovered)", entry.address),
+                ),
+            })
+            .collect();
+        let rows: Vec<(&str, &str)> = values
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect();
+        ui.table(
+            &format!(
+                "📋 Contracts on network {network_id} ({})",
+                get_network_name(network_id)
+            ),
+            &rows,
+        );
+    }
+
+    if let (Some(cache), Some(discovered_at)) = (cache, discovered_at) {
+        ui.info(&format!(
+            "Discovered addresses from {} cached at {discovered_at}",
+            cache.sources.join(" and ")
+        ));
+    }
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_refresh(cache: &ContractCache, filled: usize, failures: &[String], output: OutputFormat) {
+    let ui = UI::new(output);
+    if output.is_json() {
+        ui.json(&serde_json::json!({
+            "path": CONTRACT_CACHE_FILE,
+            "sources": cache.sources,
+            "contracts": contract_count(&cache.contracts),
+            "filled": filled,
+            "failures": failures,
+        }));
+        return;
+    }
+
+    ui.success(&format!(
+        "Discovered {} contract addresses from {}",
+        contract_count(&cache.contracts),
+        cache.sources.join(" and ")
+    ));
+    ui.info(&format!(
+        "Cached in {CONTRACT_CACHE_FILE}, {filled} of them missing from the configuration"
+    ));
+    for failure in failures {
+        ui.warning(&format!("Not read: {failure}"));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eip1967_slots() {
+        let slot = |label: &str| {
+            let slot = U256::from_big_endian(&ethers::utils::keccak256(label)) - U256::one();
+            let mut bytes = [0u8; 32];
+            slot.to_big_endian(&mut bytes);
+            format!("0x{}", hex::encode(bytes))
+        };
+
+        assert_eq!(slot("eip1967.proxy.implementation"), IMPLEMENTATION_SLOT);
+        assert_eq!(slot("eip1967.proxy.admin"), ADMIN_SLOT);
+        assert_eq!(slot("eip1967.proxy.beacon"), BEACON_SLOT);
+    }
+
+    #[test]
+    fn test_address_from_word() {
+        let mut word = [0u8; 32];
+        assert_eq!(address_from_word(&word), None);
+
+        word[31] = 0x42;
+        assert_eq!(
+            address_from_word(&word),
+            Some(Address::from_low_u64_be(0x42))
+        );
+
+        word[0] = 1;
+        assert_eq!(address_from_word(&word), None);
+    }
+
+    #[test]
+    fn test_size_issues() {
+        assert!(size_issues(10_000).is_empty());
+        assert_eq!(size_issues(23_000).len(), 1);
+        assert!(size_issues(30_000)[0].contains("exceeds"));
+    }
+
+    #[test]
+    fn test_contract_entries_mark_discovered_addresses() {
+        let address = |a: &str| EthereumAddress::new(a).unwrap();
+        let mut configured = ContractConfig::default();
+        configured.l2_contracts.insert(
+            "PolygonZkEVMBridge".to_string(),
+            address("0x1111111111111111111111111111111111111111"),
+        );
+        let mut contracts = configured.clone();
+        contracts.l2_contracts.insert(
+            "AggERC20".to_string(),
+            address("0x2222222222222222222222222222222222222222"),
+        );
+        contracts.l1_contracts.insert(
+            "AggERC20".to_string(),
+            address("0x3333333333333333333333333333333333333333"),
+        );
+
+        let entries = contract_entries(&contracts, &configured, Some(1));
+
+        assert_eq!(
+            entries,
+            vec![
+                ContractEntry {
+                    network_id: 1,
+                    name: "AggERC20".to_string(),
+                    address: "0x2222222222222222222222222222222222222222".to_string(),
+                    source: ContractSource::Discovered,
+                },
+                ContractEntry {
+                    network_id: 1,
+                    name: "PolygonZkEVMBridge".to_string(),
+                    address: "0x1111111111111111111111111111111111111111".to_string(),
+                    source: ContractSource::Configured,
+                },
+            ]
+        );
+        assert_eq!(contract_entries(&contracts, &configured, None).len(), 3);
+        assert_eq!(network_of_layer("l4"), Some(3));
+        assert_eq!(network_of_layer("l0"), None);
+    }
+}
@@ -0,0 +1,893 @@
+//! Live terminal dashboard (`aggsandbox dashboard`)
+//!
+//! One screen with what is otherwise spread over `status`, `show claims
+//! --pending`, `events` and `show exit-roots`: the compose services, the head
+//! of every chain, the deposits between the networks and whether they are
+//! claimed, the latest events and how far the L1 global exit root has been
+//! injected. A background task rebuilds the snapshot every `--interval`
+//! seconds, so keys stay responsive while the services are queried.
+
+use crate::commands::bridge::claim_all::PendingDeposit;
+use crate::commands::bridge::common::{get_network_name, validation_error};
+use crate::commands::bridge::exit_roots::{fetch_all_exit_roots, ExitRoots};
+use crate::commands::bridge::watch::{deposit_states, DepositState, DepositStates};
+use crate::commands::events::network_id_to_chain;
+use crate::commands::status::{sandbox_status, SandboxStatus};
+use crate::config::Config;
+use crate::docker::create_auto_docker_builder;
+use crate::error::Result;
+use crate::events::events_json;
+use ethers::types::H256;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Cell, Clear, List, ListItem, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Notify};
+use tokio::task::JoinHandle;
+
+/// Keys of the dashboard, shown in its footer
+const KEY_HELP: &str =
+    "q quit · tab switch panel · ↑↓ select · enter bridge details · r restart service · f refresh";
+
+/// How long a key press is waited for before the screen is redrawn
+const KEY_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Events of each network kept in the recent events panel
+const RECENT_EVENTS: usize = 20;
+
+/// Event of one of the last scanned blocks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentEvent {
+    pub network_id: u64,
+    pub block_number: u64,
+    /// Event name, or its topic when the event is unknown
+    pub event: String,
+    pub contract: String,
+    pub tx_hash: Option<String>,
+}
+
+/// Deposit destined for a network with its claim state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeRow {
+    pub destination_network: u64,
+    pub deposit: PendingDeposit,
+    pub state: DepositState,
+}
+
+/// Everything the dashboard shows, rebuilt on every refresh
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    /// `None` until the first refresh, or when Docker Compose failed
+    pub status: Option<SandboxStatus>,
+    pub bridges: Vec<BridgeRow>,
+    pub events: Vec<RecentEvent>,
+    pub exit_roots: Vec<ExitRoots>,
+    /// Queries that failed during the refresh
+    pub errors: Vec<String>,
+    pub refreshed_at: Option<chrono::DateTime<chrono::Local>>,
+}
+
+impl Snapshot {
+    pub fn count(&self, state: DepositState) -> usize {
+        self.bridges.iter().filter(|row| row.state == state).count()
+    }
+}
+
+/// Handle the dashboard command
+pub async fn handle_dashboard(interval: u64, blocks: u64) -> Result<()> {
+    if interval == 0 {
+        return Err(validation_error("--interval must be at least 1 second"));
+    }
+    let config = Config::load()?;
+
+    let (snapshots_tx, mut snapshots) = watch::channel(Snapshot::default());
+    let refresh = Arc::new(Notify::new());
+    let refresher = tokio::spawn(refresh_snapshots(
+        config,
+        blocks,
+        Duration::from_secs(interval),
+        snapshots_tx,
+        refresh.clone(),
+    ));
+
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut snapshots, &refresh, interval).await;
+    ratatui::restore();
+    refresher.abort();
+    result
+}
+
+/// Rebuild the snapshot every `interval`, or right away when notified
+async fn refresh_snapshots(
+    config: Config,
+    blocks: u64,
+    interval: Duration,
+    snapshots: watch::Sender<Snapshot>,
+    refresh: Arc<Notify>,
+) {
+    let mut deposits = BTreeMap::new();
+    loop {
+        let snapshot = collect_snapshot(&config, blocks, &mut deposits).await;
+        if snapshots.send(snapshot).is_err() {
+            return;
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = refresh.notified() => {}
+        }
+    }
+}
+
+/// Query every panel of the dashboard
+///
+/// `deposits` keeps the deposit states of the previous refresh by destination
+/// network, so claimable deposits are not checked again.
+async fn collect_snapshot(
+    config: &Config,
+    blocks: u64,
+    deposits: &mut BTreeMap<u64, DepositStates>,
+) -> Snapshot {
+    let mut errors = Vec::new();
+    let status = match sandbox_status().await {
+        Ok(status) => Some(status),
+        Err(e) => {
+            errors.push(format!("services: {e}"));
+            None
+        }
+    };
+
+    let networks = config.networks.network_ids();
+    for &network in &networks {
+        match deposit_states(config, network, deposits.get(&network)).await {
+            Ok(states) => {
+                deposits.insert(network, states);
+            }
+            Err(e) => errors.push(format!("bridges to network {network}: {e}")),
+        }
+    }
+
+    let mut events = Vec::new();
+    for &network in &networks {
+        let document = match network_id_to_chain(network) {
+            Ok(chain) => events_json(&chain, blocks, None, false).await,
+            Err(e) => Err(e),
+        };
+        match document {
+            Ok(document) => events.extend(recent_events(network, &document, RECENT_EVENTS)),
+            Err(e) => errors.push(format!("events of network {network}: {e}")),
+        }
+    }
+
+    let exit_roots = fetch_all_exit_roots(config, &networks)
+        .await
+        .unwrap_or_else(|e| {
+            errors.push(format!("exit roots: {e}"));
+            Vec::new()
+        });
+
+    Snapshot {
+        status,
+        bridges: bridge_rows(deposits),
+        events,
+        exit_roots,
+        errors,
+        refreshed_at: Some(chrono::Local::now()),
+    }
+}
+
+/// Deposits of every destination network, unclaimed ones first
+pub fn bridge_rows(deposits: &BTreeMap<u64, DepositStates>) -> Vec<BridgeRow> {
+    let mut rows: Vec<BridgeRow> = deposits
+        .iter()
+        .flat_map(|(&destination_network, states)| {
+            states.values().map(move |(deposit, state)| BridgeRow {
+                destination_network,
+                deposit: deposit.clone(),
+                state: *state,
+            })
+        })
+        .collect();
+    rows.sort_by_key(|row| {
+        (
+            row.state,
+            row.destination_network,
+            row.deposit.source_network,
+            std::cmp::Reverse(row.deposit.deposit_count),
+        )
+    });
+    rows
+}
+
+/// Last `limit` events of an `events_json` document, newest first
+pub fn recent_events(
+    network_id: u64,
+    document: &serde_json::Value,
+    limit: usize,
+) -> Vec<RecentEvent> {
+    let events = document["events"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    events
+        .iter()
+        .rev()
+        .take(limit)
+        .map(|event| {
+            let name = event["event"]
+                .as_str()
+                .map(|signature| signature.split('(').next().unwrap_or(signature))
+                .or_else(|| event["signature"].as_str())
+                .unwrap_or("unknown");
+            RecentEvent {
+                network_id,
+                block_number: event["block_number"].as_u64().unwrap_or_default(),
+                event: name.to_string(),
+                contract: event["contract"].as_str().unwrap_or_default().to_string(),
+                tx_hash: event["transaction_hash"].as_str().map(str::to_string),
+            }
+        })
+        .collect()
+}
+
+/// Service being restarted and the task restarting it
+type RunningRestart = (String, JoinHandle<std::result::Result<(), String>>);
+
+/// Restart one compose service, with Docker's error output on failure
+fn restart_service(service: &str) -> std::result::Result<(), String> {
+    let mut builder = create_auto_docker_builder();
+    builder.add_service(service);
+    // Captured, Docker's output would draw over the dashboard
+    let output = builder
+        .build_restart_command()
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Draw the dashboard and handle keys until it is quit
+async fn run(
+    terminal: &mut DefaultTerminal,
+    snapshots: &mut watch::Receiver<Snapshot>,
+    refresh: &Notify,
+    interval: u64,
+) -> Result<()> {
+    let mut dashboard = Dashboard {
+        interval,
+        ..Dashboard::default()
+    };
+    let mut restart: Option<RunningRestart> = None;
+
+    loop {
+        if snapshots.has_changed().unwrap_or(false) {
+            dashboard.update(snapshots.borrow_and_update().clone());
+        }
+        if restart.as_ref().is_some_and(|(_, task)| task.is_finished()) {
+            if let Some((service, task)) = restart.take() {
+                dashboard.message = Some(match task.await {
+                    Ok(Ok(())) => format!("✓ Restarted {service}"),
+                    Ok(Err(e)) => format!("⚠ Restarting {service} failed: {e}"),
+                    Err(e) => format!("⚠ Restarting {service} failed: {e}"),
+                });
+                refresh.notify_one();
+            }
+        }
+
+        terminal.draw(|frame| dashboard.render(frame))?;
+
+        // Blocks this worker only, the snapshots are refreshed on another one
+        if !tokio::task::block_in_place(|| event::poll(KEY_POLL_INTERVAL))? {
+            continue;
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match dashboard.handle_key(key.code) {
+            Action::Quit => return Ok(()),
+            Action::Refresh => {
+                dashboard.message = Some("Refreshing…".to_string());
+                refresh.notify_one();
+            }
+            Action::Restart(_) if restart.is_some() => {
+                dashboard.message = Some("A restart is already running".to_string());
+            }
+            Action::Restart(service) => {
+                dashboard.message = Some(format!("Restarting {service}…"));
+                let task = {
+                    let service = service.clone();
+                    tokio::task::spawn_blocking(move || restart_service(&service))
+                };
+                restart = Some((service, task));
+            }
+            Action::None => {}
+        }
+    }
+}
+
+/// Panel the arrow keys move the selection in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Panel {
+    #[default]
+    Bridges,
+    Services,
+}
+
+/// What a key asks the dashboard loop to do
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Action {
+    None,
+    Quit,
+    Refresh,
+    /// Restart the compose service with this name
+    Restart(String),
+}
+
+/// Screen state between redraws
+#[derive(Debug, Default)]
+struct Dashboard {
+    snapshot: Snapshot,
+    /// Refresh interval in seconds, shown in the header
+    interval: u64,
+    panel: Panel,
+    bridge: usize,
+    service: usize,
+    /// Whether the details of the selected bridge are open
+    details: bool,
+    /// Outcome of the last action, shown in the footer
+    message: Option<String>,
+}
+
+impl Dashboard {
+    fn update(&mut self, snapshot: Snapshot) {
+        self.snapshot = snapshot;
+        self.bridge = self
+            .bridge
+            .min(self.snapshot.bridges.len().saturating_sub(1));
+        self.service = self.service.min(self.services().len().saturating_sub(1));
+        if self.snapshot.bridges.is_empty() {
+            self.details = false;
+        }
+    }
+
+    fn services(&self) -> &[crate::commands::status::ServiceStatus] {
+        self.snapshot
+            .status
+            .as_ref()
+            .map(|status| status.services.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn selected_bridge(&self) -> Option<&BridgeRow> {
+        self.snapshot.bridges.get(self.bridge)
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> Action {
+        if self.details {
+            // Any key closes the details, q still quits
+            self.details = false;
+            return if matches!(code, KeyCode::Char('q')) {
+                Action::Quit
+            } else {
+                Action::None
+            };
+        }
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('f') => return Action::Refresh,
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.panel = match self.panel {
+                    Panel::Bridges => Panel::Services,
+                    Panel::Services => Panel::Bridges,
+                };
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Enter if self.selected_bridge().is_some() => {
+                self.panel = Panel::Bridges;
+                self.details = true;
+            }
+            KeyCode::Char('r') => {
+                if self.panel != Panel::Services {
+                    self.message = Some("Select a service with tab first".to_string());
+                } else if let Some(service) = self.services().get(self.service) {
+                    return Action::Restart(service.service.clone());
+                }
+            }
+            _ => {}
+        }
+        Action::None
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let (selected, len) = match self.panel {
+            Panel::Bridges => (&mut self.bridge, self.snapshot.bridges.len()),
+            Panel::Services => {
+                let len = self.services().len();
+                (&mut self.service, len)
+            }
+        };
+        *selected = selected
+            .saturating_add_signed(delta)
+            .min(len.saturating_sub(1));
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let services = self.services();
+        let chain_count = self
+            .snapshot
+            .status
+            .as_ref()
+            .map_or(0, |status| status.chains.len());
+        // Header row and borders around the taller of the two tables
+        let top_height = services.len().max(chain_count).max(1) as u16 + 3;
+        let [header, top, bridges, events, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(top_height),
+            Constraint::Min(6),
+            Constraint::Length(RECENT_EVENTS.min(10) as u16 + 2),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [services_area, chains_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(top);
+
+        frame.render_widget(Paragraph::new(self.header()), header);
+        self.render_services(frame, services_area);
+        self.render_chains(frame, chains_area);
+        self.render_bridges(frame, bridges);
+        self.render_events(frame, events);
+        frame.render_widget(Paragraph::new(self.footer()), footer);
+
+        if self.details {
+            if let Some(row) = self.selected_bridge() {
+                render_details(frame, row);
+            }
+        }
+    }
+
+    fn header(&self) -> Line<'static> {
+        let refreshed = match self.snapshot.refreshed_at {
+            Some(time) => format!(
+                "refreshed {} · every {}s",
+                time.format("%H:%M:%S"),
+                self.interval
+            ),
+            None => "loading…".to_string(),
+        };
+        Line::from(vec![
+            Span::styled(
+                "aggsandbox dashboard",
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!("  {refreshed}")),
+        ])
+    }
+
+    fn footer(&self) -> Line<'static> {
+        if let Some(message) = &self.message {
+            return Line::from(message.clone());
+        }
+        match self.snapshot.errors.first() {
+            Some(error) => Line::styled(
+                format!("⚠ {error} ({} failed)", self.snapshot.errors.len()),
+                Style::new().fg(Color::Red),
+            ),
+            None => Line::styled(KEY_HELP, Style::new().fg(Color::DarkGray)),
+        }
+    }
+
+    fn render_services(&self, frame: &mut Frame, area: Rect) {
+        let rows = self.services().iter().map(|service| {
+            let style = if service.is_healthy() {
+                Style::new().fg(Color::Green)
+            } else {
+                Style::new().fg(Color::Red)
+            };
+            Row::new(vec![
+                Cell::from(service.service.clone()),
+                Cell::from(service.state.clone()).style(style),
+                Cell::from(service.health.clone().unwrap_or_else(|| "-".to_string())),
+            ])
+        });
+        let title = match &self.snapshot.status {
+            Some(status) if !status.running => " Services (not running) ",
+            _ => " Services ",
+        };
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(10),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header_row(&["SERVICE", "STATE", "HEALTH"]))
+        .block(panel_block(title, self.panel == Panel::Services))
+        .row_highlight_style(selected_style(self.panel == Panel::Services));
+        let mut state = TableState::default().with_selected(Some(self.service));
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    fn render_chains(&self, frame: &mut Frame, area: Rect) {
+        let Some(status) = &self.snapshot.status else {
+            frame.render_widget(Block::bordered().title(" Chains "), area);
+            return;
+        };
+        let rows = status.chains.iter().map(|chain| {
+            let head = match chain.head_block {
+                Some(block) => Cell::from(block.to_string()),
+                None => Cell::from("unreachable").style(Style::new().fg(Color::Red)),
+            };
+            let indexing = status
+                .indexing
+                .iter()
+                .find(|indexing| indexing.network_id == chain.network_id)
+                .map_or(Cell::from("-"), |indexing| {
+                    let text = format!(
+                        "{}/{} deposits",
+                        indexing.indexed_deposit_count, indexing.contract_deposit_count
+                    );
+                    if indexing.is_lagging() {
+                        Cell::from(text).style(Style::new().fg(Color::Yellow))
+                    } else {
+                        Cell::from(text)
+                    }
+                });
+            Row::new(vec![
+                Cell::from(format!("{} {}", chain.network_id, chain.name)),
+                head,
+                indexing,
+                self.exit_root_cell(chain.network_id),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(12),
+                Constraint::Length(16),
+                Constraint::Length(22),
+            ],
+        )
+        .header(header_row(&["NETWORK", "HEAD", "INDEXED", "EXIT ROOTS"]))
+        .block(Block::bordered().title(" Chains "));
+        frame.render_widget(table, area);
+    }
+
+    /// L1 global exit root, or whether an L2 has it injected
+    fn exit_root_cell(&self, network_id: u64) -> Cell<'static> {
+        let Some(roots) = self
+            .snapshot
+            .exit_roots
+            .iter()
+            .find(|roots| roots.network_id == network_id)
+        else {
+            return Cell::from("-");
+        };
+        match roots.l1_root_injected {
+            Some(true) => Cell::from("L1 GER injected").style(Style::new().fg(Color::Green)),
+            Some(false) => Cell::from("L1 GER pending").style(Style::new().fg(Color::Yellow)),
+            None => Cell::from(format!(
+                "GER {}",
+                roots
+                    .global_exit_root
+                    .map_or_else(|| "n/a".to_string(), short_hash)
+            )),
+        }
+    }
+
+    fn render_bridges(&self, frame: &mut Frame, area: Rect) {
+        let title = format!(
+            " Bridges: {} pending · {} claimable · {} claimed ",
+            self.snapshot.count(DepositState::Pending),
+            self.snapshot.count(DepositState::Claimable),
+            self.snapshot.count(DepositState::Claimed)
+        );
+        let rows = self.snapshot.bridges.iter().map(|row| {
+            Row::new(vec![
+                Cell::from(format!(
+                    "{} → {}",
+                    row.deposit.source_network, row.destination_network
+                )),
+                Cell::from(row.deposit.deposit_count.to_string()),
+                Cell::from(leaf_type_name(row.deposit.leaf_type)),
+                Cell::from(row.deposit.amount.clone()),
+                Cell::from(state_name(row.state)).style(state_style(row.state)),
+                Cell::from(row.deposit.tx_hash.clone()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(7),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(24),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header_row(&[
+            "ROUTE",
+            "DEPOSIT",
+            "TYPE",
+            "AMOUNT",
+            "STATE",
+            "BRIDGE TX",
+        ]))
+        .block(panel_block(&title, self.panel == Panel::Bridges))
+        .row_highlight_style(selected_style(self.panel == Panel::Bridges));
+        let mut state = TableState::default().with_selected(Some(self.bridge));
+        frame.render_stateful_widget(table, area, &mut state);
+    }
+
+    fn render_events(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .snapshot
+            .events
+            .iter()
+            .map(|event| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("n{} #{:<8}", event.network_id, event.block_number),
+                        Style::new().fg(Color::DarkGray),
+                    ),
+                    Span::styled(format!("{:<28}", event.event), Style::new().fg(Color::Cyan)),
+                    Span::raw(event.contract.clone()),
+                ]))
+            })
+            .collect();
+        frame.render_widget(
+            List::new(items).block(Block::bordered().title(" Recent events ")),
+            area,
+        );
+    }
+}
+
+/// Popup with everything known about a deposit and how to claim it
+fn render_details(frame: &mut Frame, row: &BridgeRow) {
+    let deposit = &row.deposit;
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(
+                format!("{label:<20}"),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(value),
+        ])
+    };
+    let mut lines = vec![
+        field(
+            "Source network",
+            format!(
+                "{} ({})",
+                deposit.source_network,
+                get_network_name(deposit.source_network)
+            ),
+        ),
+        field(
+            "Destination network",
+            format!(
+                "{} ({})",
+                row.destination_network,
+                get_network_name(row.destination_network)
+            ),
+        ),
+        field("Deposit count", deposit.deposit_count.to_string()),
+        field("Type", leaf_type_name(deposit.leaf_type).to_string()),
+        field("Amount", deposit.amount.clone()),
+        field("Bridge tx", deposit.tx_hash.clone()),
+        Line::from(vec![
+            Span::styled(
+                format!("{:<20}", "State"),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(state_name(row.state), state_style(row.state)),
+        ]),
+        Line::default(),
+    ];
+    lines.push(Line::from(match row.state {
+        DepositState::Pending => {
+            "Waiting for the global exit root covering it to reach the destination".to_string()
+        }
+        DepositState::Claimable => format!(
+            "Claim with `aggsandbox bridge claim --network-id {} --tx-hash {} --source-network-id {}`",
+            row.destination_network, deposit.tx_hash, deposit.source_network
+        ),
+        DepositState::Claimed => "Claimed on the destination network".to_string(),
+    }));
+    lines.push(Line::styled(
+        "Press any key to close",
+        Style::new().fg(Color::DarkGray),
+    ));
+
+    let area = centered(frame.area(), 100, lines.len() as u16 + 2);
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::bordered().title(format!(
+            " Deposit {} from network {} ",
+            deposit.deposit_count, deposit.source_network
+        ))),
+        area,
+    );
+}
+
+/// Area of at most `width` x `height` in the middle of `area`
+fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+fn panel_block(title: &str, focused: bool) -> Block<'static> {
+    let block = Block::bordered().title(title.to_string());
+    if focused {
+        block.border_style(Style::new().fg(Color::Cyan))
+    } else {
+        block
+    }
+}
+
+fn header_row(cells: &[&'static str]) -> Row<'static> {
+    Row::new(cells.to_vec()).style(Style::new().add_modifier(Modifier::BOLD))
+}
+
+fn selected_style(focused: bool) -> Style {
+    if focused {
+        Style::new().add_modifier(Modifier::REVERSED)
+    } else {
+        Style::new()
+    }
+}
+
+fn leaf_type_name(leaf_type: u8) -> &'static str {
+    if leaf_type == 0 {
+        "asset"
+    } else {
+        "message"
+    }
+}
+
+fn state_name(state: DepositState) -> &'static str {
+    match state {
+        DepositState::Pending => "pending",
+        DepositState::Claimable => "claimable",
+        DepositState::Claimed => "claimed",
+    }
+}
+
+fn state_style(state: DepositState) -> Style {
+    match state {
+        DepositState::Pending => Style::new().fg(Color::Yellow),
+        DepositState::Claimable => Style::new().fg(Color::Cyan),
+        DepositState::Claimed => Style::new().fg(Color::Green),
+    }
+}
+
+/// Hash shortened to its first 4 bytes
+fn short_hash(hash: H256) -> String {
+    format!("{hash:#x}")[..10].to_string()
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn deposit(source_network: u64, deposit_count: u64) -> PendingDeposit {
+        PendingDeposit {
+            source_network,
+            deposit_count,
+            leaf_type: 0,
+            tx_hash: format!("0x{deposit_count:064x}"),
+            amount: "1000".to_string(),
+        }
+    }
+
+    fn states(entries: &[(u64, u64, DepositState)]) -> DepositStates {
+        entries
+            .iter()
+            .map(|&(source, count, state)| ((source, count), (deposit(source, count), state)))
+            .collect()
+    }
+
+    #[test]
+    fn test_bridge_rows_list_unclaimed_first() {
+        let deposits = BTreeMap::from([
+            (
+                1,
+                states(&[
+                    (0, 0, DepositState::Claimed),
+                    (0, 1, DepositState::Pending),
+                    (0, 2, DepositState::Claimable),
+                ]),
+            ),
+            (0, states(&[(1, 0, DepositState::Pending)])),
+        ]);
+
+        let rows = bridge_rows(&deposits);
+        let order: Vec<(u64, u64, DepositState)> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.destination_network,
+                    row.deposit.deposit_count,
+                    row.state,
+                )
+            })
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (0, 0, DepositState::Pending),
+                (1, 1, DepositState::Pending),
+                (1, 2, DepositState::Claimable),
+                (1, 0, DepositState::Claimed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recent_events_newest_first() {
+        let document = json!({
+            "events": [
+                { "block_number": 10, "event": "BridgeEvent(uint8,uint32,address)", "contract": "0xaa" },
+                { "block_number": 12, "event": null, "signature": "0x1234", "contract": "0xbb", "transaction_hash": "0xcc" },
+                { "block_number": 15, "event": "ClaimEvent(uint256,uint32,address,address,uint256)", "contract": "0xaa" },
+            ]
+        });
+
+        let events = recent_events(1, &document, 2);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].event, "ClaimEvent");
+        assert_eq!(events[0].block_number, 15);
+        assert_eq!(events[1].event, "0x1234");
+        assert_eq!(events[1].tx_hash.as_deref(), Some("0xcc"));
+        assert!(recent_events(1, &json!({}), 5).is_empty());
+    }
+
+    #[test]
+    fn test_keys_select_and_open_bridges() {
+        let mut dashboard = Dashboard::default();
+        dashboard.update(Snapshot {
+            bridges: bridge_rows(&BTreeMap::from([(
+                1,
+                states(&[(0, 0, DepositState::Pending), (0, 1, DepositState::Pending)]),
+            )])),
+            ..Snapshot::default()
+        });
+
+        assert_eq!(dashboard.handle_key(KeyCode::Down), Action::None);
+        assert_eq!(dashboard.handle_key(KeyCode::Down), Action::None);
+        assert_eq!(dashboard.bridge, 1);
+        assert_eq!(dashboard.handle_key(KeyCode::Up), Action::None);
+        assert_eq!(dashboard.bridge, 0);
+
+        dashboard.handle_key(KeyCode::Enter);
+        assert!(dashboard.details);
+        assert_eq!(dashboard.handle_key(KeyCode::Char('r')), Action::None);
+        assert!(!dashboard.details);
+
+        // No services to restart without a status
+        dashboard.handle_key(KeyCode::Tab);
+        assert_eq!(dashboard.handle_key(KeyCode::Char('r')), Action::None);
+        assert_eq!(dashboard.handle_key(KeyCode::Char('q')), Action::Quit);
+    }
+}
@@ -0,0 +1,496 @@
+//! Environment diagnostics for `aggsandbox doctor`
+//!
+//! Every check yields a pass, warning or failure together with the command
+//! or edit that fixes it, so setup problems can be diagnosed in one run
+//! instead of being discovered one error at a time.
+
+use crate::api_client::OptimizedApiClient;
+use crate::commands::bridge::get_provider;
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::types::NetworkId;
+use crate::ui::{OutputFormat, UI};
+use ethers::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+/// Ports published by the sandbox services
+const SANDBOX_PORTS: [(u16, &str); 5] = [
+    (8545, "anvil-l1"),
+    (8546, "anvil-l2"),
+    (8547, "anvil-l3"),
+    (5577, "aggkit bridge service"),
+    (5578, "aggkit-l3 bridge service"),
+];
+/// Variables every `.env` needs, as in `.env.example`
+const REQUIRED_ENV_VARS: [&str; 6] = [
+    "RPC_1",
+    "RPC_2",
+    "CHAIN_ID_MAINNET",
+    "CHAIN_ID_AGGLAYER_1",
+    "ACCOUNT_ADDRESS_1",
+    "PRIVATE_KEY_1",
+];
+/// Contract addresses the bridge commands rely on, written by the deployment scripts
+const CONTRACT_ENV_VARS: [&str; 5] = [
+    "POLYGON_ZKEVM_BRIDGE_L1",
+    "POLYGON_ZKEVM_GLOBAL_EXIT_ROOT_L1",
+    "POLYGON_ROLLUP_MANAGER_L1",
+    "POLYGON_ZKEVM_BRIDGE_L2",
+    "GLOBAL_EXIT_ROOT_MANAGER_L2",
+];
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One line of the doctor checklist
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            fix: None,
+        }
+    }
+
+    fn warn(name: &str, detail: impl Into<String>, fix: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            fix: Some(fix.to_string()),
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>, fix: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            fix: Some(fix.to_string()),
+        }
+    }
+}
+
+/// Handle the doctor command
+pub async fn handle_doctor(output: OutputFormat) -> Result<()> {
+    let mut checks = vec![
+        check_compose_file(),
+        check_docker_daemon(),
+        check_compose_version(),
+    ];
+
+    let running = sandbox_running();
+    checks.extend(check_ports(running));
+
+    let env_content = std::fs::read_to_string(".env").ok();
+    checks.push(check_env_file(env_content.as_deref()));
+    checks.push(check_contract_addresses(|var| std::env::var(var).ok()));
+
+    match Config::load() {
+        Ok(config) => {
+            checks.push(Check::pass("Configuration", "loaded"));
+            checks.extend(check_rpc_endpoints(&config).await);
+            checks.extend(check_api_endpoints(&config).await);
+        }
+        Err(e) => checks.push(Check::fail(
+            "Configuration",
+            e.to_string(),
+            "Correct the reported variable in .env or your environment",
+        )),
+    }
+
+    print_checks(&checks, output);
+
+    let failed = checks
+        .iter()
+        .filter(|check| check.status == CheckStatus::Fail)
+        .count();
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(AggSandboxError::Other(format!(
+            "{failed} doctor check(s) failed"
+        )))
+    }
+}
+
+fn check_compose_file() -> Check {
+    let name = "Project directory";
+    if Path::new("docker-compose.yml").exists() {
+        Check::pass(name, "docker-compose.yml found")
+    } else {
+        Check::fail(
+            name,
+            "docker-compose.yml not found in the current directory",
+            "cd into the agg-sandbox checkout before running aggsandbox",
+        )
+    }
+}
+
+fn check_docker_daemon() -> Check {
+    let name = "Docker daemon";
+    match command_output("docker", &["info", "--format", "{{.ServerVersion}}"]) {
+        Ok(version) => Check::pass(name, format!("running (server {version})")),
+        Err(e) => Check::fail(
+            name,
+            e,
+            "Start Docker Desktop or the docker service (`sudo systemctl start docker`)",
+        ),
+    }
+}
+
+fn check_compose_version() -> Check {
+    let name = "Docker Compose";
+    command_output("docker", &["compose", "version", "--short"])
+        .map(|version| Check::pass(name, format!("docker compose {version}")))
+        .or_else(|_| {
+            command_output("docker-compose", &["version", "--short"])
+                .map(|version| Check::pass(name, format!("docker-compose {version}")))
+        })
+        .unwrap_or_else(|e| {
+            Check::fail(
+                name,
+                e,
+                "Install the Docker Compose plugin: https://docs.docker.com/compose/install/",
+            )
+        })
+}
+
+/// Whether sandbox containers are up, in which case their ports are expected to be taken
+#[cfg(feature = "docker")]
+fn sandbox_running() -> bool {
+    let cmd = crate::docker::create_auto_docker_builder().build_ps_json_command();
+    crate::docker::execute_docker_command_with_output(cmd)
+        .map(|output| !crate::docker::parse_ps_json(&output).is_empty())
+        .unwrap_or(false)
+}
+
+/// Containers are not listed without the `docker` feature
+#[cfg(not(feature = "docker"))]
+fn sandbox_running() -> bool {
+    false
+}
+
+fn check_ports(sandbox_running: bool) -> Vec<Check> {
+    let busy: HashSet<u16> = SANDBOX_PORTS
+        .iter()
+        .map(|(port, _)| *port)
+        .filter(|port| TcpListener::bind(("127.0.0.1", *port)).is_err())
+        .collect();
+    port_checks(&busy, sandbox_running)
+}
+
+fn port_checks(busy: &HashSet<u16>, sandbox_running: bool) -> Vec<Check> {
+    SANDBOX_PORTS
+        .iter()
+        .map(|(port, service)| {
+            let name = format!("Port {port}");
+            match (busy.contains(port), sandbox_running) {
+                (false, _) => Check::pass(&name, format!("free for {service}")),
+                (true, true) => Check::pass(&name, format!("in use, sandbox is running ({service})")),
+                (true, false) => Check::fail(
+                    &name,
+                    format!("in use by another process, {service} cannot bind it"),
+                    &format!(
+                        "Stop the process listening on {port} (`lsof -i :{port}`) or change the port in docker-compose.yml"
+                    ),
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Check `.env` for missing required variables and malformed or duplicated lines
+fn check_env_file(content: Option<&str>) -> Check {
+    let name = ".env file";
+    let Some(content) = content else {
+        return Check::fail(
+            name,
+            ".env not found",
+            "Create it from the template: `cp .env.example .env`",
+        );
+    };
+
+    let mut keys = HashSet::new();
+    let mut problems = Vec::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        match line.split_once('=') {
+            Some((key, _)) if !key.trim().is_empty() => {
+                if !keys.insert(key.trim().to_string()) {
+                    problems.push(format!("{} is set twice (line {})", key.trim(), number + 1));
+                }
+            }
+            _ => problems.push(format!("line {} is not KEY=VALUE", number + 1)),
+        }
+    }
+
+    let missing: Vec<&str> = REQUIRED_ENV_VARS
+        .iter()
+        .copied()
+        .filter(|var| !keys.contains(*var))
+        .collect();
+    if !missing.is_empty() {
+        problems.insert(0, format!("missing {}", missing.join(", ")));
+    }
+
+    if problems.is_empty() {
+        Check::pass(name, format!("{} variables", keys.len()))
+    } else if missing.is_empty() {
+        Check::warn(
+            name,
+            problems.join("; "),
+            "Remove the duplicated or malformed lines from .env",
+        )
+    } else {
+        Check::fail(
+            name,
+            problems.join("; "),
+            "Copy the missing variables from .env.example",
+        )
+    }
+}
+
+/// Check that the deployment scripts wrote the contract addresses
+fn check_contract_addresses(lookup: impl Fn(&str) -> Option<String>) -> Check {
+    let name = "Contract addresses";
+    let mut unset = Vec::new();
+    let mut invalid = Vec::new();
+    for var in CONTRACT_ENV_VARS {
+        match lookup(var).filter(|value| !value.trim().is_empty()) {
+            None => unset.push(var),
+            Some(value) if value.trim().parse::<Address>().is_err() => invalid.push(var),
+            Some(_) => {}
+        }
+    }
+
+    if !invalid.is_empty() {
+        Check::fail(
+            name,
+            format!("not valid addresses: {}", invalid.join(", ")),
+            "Restart the sandbox to redeploy and rewrite the addresses: `aggsandbox restart`",
+        )
+    } else if !unset.is_empty() {
+        Check::warn(
+            name,
+            format!("not set: {}", unset.join(", ")),
+            "Addresses are written by the deployment once the sandbox starts: `aggsandbox start --detach`",
+        )
+    } else {
+        Check::pass(name, "bridge and exit root contracts configured")
+    }
+}
+
+fn configured_networks(config: &Config) -> Vec<(u64, Option<u64>)> {
+    config
+        .networks
+        .network_ids()
+        .into_iter()
+        .map(|network_id| {
+            let chain_id = config
+                .networks
+                .chain(network_id)
+                .and_then(|chain| chain.chain_id.as_u64().ok());
+            (network_id, chain_id)
+        })
+        .collect()
+}
+
+/// Query the chain ID of every configured RPC endpoint
+async fn check_rpc_endpoints(config: &Config) -> Vec<Check> {
+    let mut checks = Vec::new();
+    for (network_id, expected) in configured_networks(config) {
+        let name = format!("RPC network {network_id}");
+        let chain_id = match get_provider(config, network_id).await {
+            Ok(provider) => tokio::time::timeout(PROBE_TIMEOUT, provider.get_chainid())
+                .await
+                .map_err(|_| "timed out".to_string())
+                .and_then(|result| result.map_err(|e| e.to_string())),
+            Err(e) => Err(e.to_string()),
+        };
+        checks.push(match chain_id {
+            Ok(chain_id) if expected.is_some_and(|id| U256::from(id) != chain_id) => Check::warn(
+                &name,
+                format!(
+                    "chain ID {chain_id} does not match the configured {}",
+                    expected.unwrap_or_default()
+                ),
+                "Update the CHAIN_ID_* variables in .env to match the node",
+            ),
+            Ok(chain_id) => Check::pass(&name, format!("reachable, chain ID {chain_id}")),
+            Err(e) => Check::fail(
+                &name,
+                format!("unreachable: {e}"),
+                "Start the sandbox with `aggsandbox start --detach` and wait 30-60s; check `aggsandbox logs anvil-l1`",
+            ),
+        });
+    }
+    checks
+}
+
+/// Probe the bridge service of every configured network
+async fn check_api_endpoints(config: &Config) -> Vec<Check> {
+    let client = OptimizedApiClient::global();
+    let mut checks = Vec::new();
+    for (network_id, _) in configured_networks(config) {
+        let name = format!("Bridge API network {network_id}");
+        let Ok(network) = NetworkId::new(network_id) else {
+            continue;
+        };
+        let base_url = config.get_api_base_url(network);
+        let url = format!("{base_url}/bridge/v1/bridges?network_id={network_id}");
+        checks.push(match client.get_with_timeout(&url, PROBE_TIMEOUT).await {
+            Ok(_) => Check::pass(&name, format!("healthy ({base_url})")),
+            Err(e) => Check::fail(
+                &name,
+                e.to_string(),
+                "Wait for aggkit to finish starting, then check `aggsandbox logs aggkit`",
+            ),
+        });
+    }
+    checks
+}
+
+/// Run a command and return its trimmed stdout, or a description of the failure
+fn command_output(program: &str, args: &[&str]) -> std::result::Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("`{program}` not available: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(stderr
+            .lines()
+            .next()
+            .unwrap_or("command failed")
+            .trim()
+            .to_string())
+    }
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_checks(checks: &[Check], output: OutputFormat) {
+    let ui = UI::new(output);
+    let count = |status| checks.iter().filter(|c| c.status == status).count();
+    let (passed, warnings, failed) = (
+        count(CheckStatus::Pass),
+        count(CheckStatus::Warn),
+        count(CheckStatus::Fail),
+    );
+
+    if output.is_json() {
+        ui.json(&serde_json::json!({
+            "checks": checks,
+            "passed": passed,
+            "warnings": warnings,
+            "failed": failed,
+        }));
+        return;
+    }
+
+    ui.info("🩺 Sandbox environment check");
+    for check in checks {
+        let line = format!("{}: {}", check.name, check.detail);
+        match check.status {
+            CheckStatus::Pass => ui.success(&line),
+            CheckStatus::Warn => ui.warning(&line),
+            CheckStatus::Fail => ui.error(&line),
+        }
+        if let Some(fix) = &check.fix {
+            ui.tip(fix);
+        }
+    }
+    ui.blank_line();
+    ui.info(&format!(
+        "{passed} passed, {warnings} warning(s), {failed} failed"
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_file_checks() {
+        assert_eq!(check_env_file(None).status, CheckStatus::Fail);
+
+        let complete = REQUIRED_ENV_VARS
+            .iter()
+            .map(|var| format!("{var}=1"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            check_env_file(Some(&format!("# comment\n{complete}\n"))).status,
+            CheckStatus::Pass
+        );
+
+        let duplicated = format!("{complete}\nRPC_1=http://localhost:9999\nnot a variable");
+        let check = check_env_file(Some(&duplicated));
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.detail.contains("RPC_1 is set twice"));
+        assert!(check.detail.contains("is not KEY=VALUE"));
+
+        let check = check_env_file(Some("RPC_1=http://localhost:8545"));
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.detail.contains("missing RPC_2"));
+    }
+
+    #[test]
+    fn test_contract_address_checks() {
+        let valid = "0x63ee8865a8b25919b5103d02586aaaf078ee9102".to_string();
+
+        let all_set = check_contract_addresses(|_| Some(valid.clone()));
+        assert_eq!(all_set.status, CheckStatus::Pass);
+
+        let unset = check_contract_addresses(|var| {
+            (var != "POLYGON_ZKEVM_BRIDGE_L2").then(|| valid.clone())
+        });
+        assert_eq!(unset.status, CheckStatus::Warn);
+        assert!(unset.detail.contains("POLYGON_ZKEVM_BRIDGE_L2"));
+
+        let invalid = check_contract_addresses(|_| Some("0x1234".to_string()));
+        assert_eq!(invalid.status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn test_port_checks() {
+        let busy: HashSet<u16> = [8545].into_iter().collect();
+
+        let stopped = port_checks(&busy, false);
+        assert_eq!(stopped[0].status, CheckStatus::Fail);
+        assert_eq!(stopped[1].status, CheckStatus::Pass);
+
+        let running = port_checks(&busy, true);
+        assert!(running
+            .iter()
+            .all(|check| check.status == CheckStatus::Pass));
+    }
+}
@@ -0,0 +1,467 @@
+//! Built-in end-to-end smoke test (`aggsandbox test e2e`)
+//!
+//! Runs the bridge flows users rely on against the running sandbox and checks
+//! balances and claim status after every step, so a new sandbox image can be
+//! gated in CI with a single command:
+//!
+//! 1. bridge ETH L1 → L2 and claim it
+//! 2. bridge the AggERC20 test token L1 → L2 and claim it
+//! 3. bridge-and-call L1 → L2 and claim both legs
+//! 4. bridge ETH back L2 → L1 and claim it
+//!
+//! Claims poll the bridge service until their proof is available. The run
+//! stops at the first failing step and exits non-zero.
+
+use ethers::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::future::Future;
+use std::time::Instant;
+
+use crate::commands::assert::get_balance;
+use crate::commands::bridge::common::{get_network_name, is_dry_run, validation_error};
+use crate::commands::bridge::faucet::resolve_sandbox_token;
+use crate::commands::bridge::local_index::{self, DataSource};
+use crate::commands::bridge::proof_wait::AutoWait;
+use crate::commands::bridge::utilities::{get_mapped_token_info, MappedTokenArgs};
+use crate::commands::bridge::{
+    bridge_and_call_with_approval, bridge_asset, claim_asset, get_bridge_contract_address,
+    get_provider, idempotency, BridgeAndCallArgs, BridgeAssetArgs, BridgeContract, ClaimAssetArgs,
+};
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui::{self, OutputFormat};
+use crate::validation::parse_network_id;
+
+const ETH: &str = "0x0000000000000000000000000000000000000000";
+const L1: u64 = 0;
+
+/// Built-in test suites
+#[derive(Debug, clap::Subcommand)]
+pub enum TestCommands {
+    /// 🔁 Run the end-to-end bridge flow against the running sandbox
+    #[command(
+        long_about = "Bridge and claim through every common flow and check the results.
+
+Steps: bridge ETH L1 → L2 and claim, bridge the AggERC20 test token and claim,
+bridge-and-call and claim both legs, and bridge ETH back L2 → L1 and claim.
+After each claim the recipient's balance and the claim status are checked.
+
+Transactions are sent by the first sandbox account to the second one. The run
+stops at the first failing step and exits with a non-zero status.
+
+Examples:
+  aggsandbox test e2e
+  aggsandbox test e2e --network-id 2 --timeout 600
+  aggsandbox test e2e --output json"
+    )]
+    E2e {
+        /// L2 network the flows bridge to and from
+        #[arg(short = 'n', long, default_value = "1", value_parser = parse_network_id, help = "L2 network ID")]
+        network_id: u64,
+        /// Amount bridged in every step
+        #[arg(long, default_value = "10000000000000000", value_parser = crate::validation::parse_uint256, help = "Amount bridged per step in wei or token units (decimal or 0x hex)")]
+        amount: String,
+        /// ERC20 token bridged in the token step
+        #[arg(long, help = "L1 ERC20 token address (default: AggERC20)")]
+        token: Option<String>,
+        /// Time each claim waits for its proof
+        #[arg(
+            long,
+            default_value = "300",
+            help = "Seconds each claim waits for its proof"
+        )]
+        timeout: u64,
+    },
+}
+
+/// Outcome of one step, printed in JSON output mode
+#[derive(Debug, Serialize)]
+struct StepResult {
+    step: String,
+    passed: bool,
+    duration_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Steps run so far
+#[derive(Debug, Default)]
+struct Run {
+    steps: Vec<StepResult>,
+}
+
+impl Run {
+    /// Run one step and record its outcome
+    async fn step<T>(&mut self, name: &str, step: impl Future<Output = Result<T>>) -> Result<T> {
+        let ui = ui::ui();
+        if !ui.is_json() {
+            ui.info(&format!("▶ {name}"));
+        }
+        let started = Instant::now();
+        let result = step.await;
+        self.steps.push(StepResult {
+            step: name.to_string(),
+            passed: result.is_ok(),
+            duration_ms: started.elapsed().as_millis(),
+            error: result.as_ref().err().map(|e| e.to_string()),
+        });
+        if result.is_ok() && !ui.is_json() {
+            ui.success(&format!("{name} ({:.1}s)", started.elapsed().as_secs_f64()));
+        }
+        result
+    }
+
+    fn passed(&self) -> bool {
+        self.steps.iter().all(|step| step.passed)
+    }
+}
+
+/// Accounts and settings shared by the steps
+struct Flow<'a> {
+    config: &'a Config,
+    l2: u64,
+    amount: &'a str,
+    recipient_str: String,
+    auto_wait: AutoWait,
+}
+
+/// Handle test commands
+pub async fn handle_test(subcommand: TestCommands, output: OutputFormat) -> Result<()> {
+    match subcommand {
+        TestCommands::E2e {
+            network_id,
+            amount,
+            token,
+            timeout,
+        } => run_e2e(network_id, &amount, token.as_deref(), timeout, output).await,
+    }
+}
+
+async fn run_e2e(
+    l2: u64,
+    amount: &str,
+    token: Option<&str>,
+    timeout: u64,
+    output: OutputFormat,
+) -> Result<()> {
+    if is_dry_run() {
+        return Err(validation_error(
+            "`test e2e` checks real balances and cannot run with --dry-run",
+        ));
+    }
+    if l2 == L1 {
+        return Err(validation_error("--network-id must be an L2 network"));
+    }
+
+    let config = Config::load()?;
+    let recipient = config
+        .accounts
+        .accounts
+        .get(1)
+        .ok_or_else(|| validation_error("`test e2e` needs at least two sandbox accounts"))?
+        .as_str()
+        .parse::<Address>()
+        .map_err(|e| validation_error(&format!("Invalid recipient account: {e}")))?;
+    let token = match token {
+        Some(token) => token.to_string(),
+        None => resolve_sandbox_token(&config.contracts, "l1", "AGG")
+            .map(|address| format!("{address:#x}"))
+            .ok_or_else(|| validation_error("No AggERC20 token deployed on L1; pass --token"))?,
+    };
+    let flow = Flow {
+        config: &config,
+        l2,
+        amount,
+        recipient_str: format!("{recipient:#x}"),
+        auto_wait: AutoWait::new(timeout, 3),
+    };
+
+    let mut run = Run::default();
+    let result = run_steps(&flow, &token, &mut run).await;
+    report(&run, l2, output);
+    result
+}
+
+async fn run_steps(flow: &Flow<'_>, token: &str, run: &mut Run) -> Result<()> {
+    let l2 = flow.l2;
+    let l2_name = get_network_name(l2);
+
+    // ETH L1 → L2
+    let before = get_balance(flow.config, l2, &flow.recipient_str, None).await?;
+    let tx_hash = run
+        .step("Bridge ETH L1 → L2", flow.bridge(L1, l2, ETH))
+        .await?;
+    let deposit = run
+        .step("Claim ETH on L2", flow.claim(l2, L1, &tx_hash, None))
+        .await?;
+    run.step(
+        &format!("Check ETH balance and claim on {l2_name}"),
+        flow.check(l2, None, before, L1, deposit),
+    )
+    .await?;
+
+    // ERC20 L1 → L2
+    let wrapped_before = flow.wrapped_token(token).await?;
+    let before = flow.token_balance(wrapped_before).await?;
+    let tx_hash = run
+        .step("Bridge ERC20 L1 → L2", flow.bridge(L1, l2, token))
+        .await?;
+    let deposit = run
+        .step("Claim ERC20 on L2", flow.claim(l2, L1, &tx_hash, None))
+        .await?;
+    let wrapped = flow.wrapped_token(token).await?;
+    run.step(
+        &format!("Check wrapped token balance and claim on {l2_name}"),
+        flow.check(l2, Some(wrapped), before, L1, deposit),
+    )
+    .await?;
+
+    // Bridge-and-call L1 → L2
+    let before = get_balance(flow.config, l2, &flow.recipient_str, None).await?;
+    let tx_hash = run
+        .step("Bridge-and-call L1 → L2", flow.bridge_and_call(L1, l2))
+        .await?;
+    let asset_deposit = run
+        .step(
+            "Claim bridge-and-call asset leg",
+            flow.claim(l2, L1, &tx_hash, None),
+        )
+        .await?;
+    let message_deposit = run
+        .step("Claim bridge-and-call message leg", async {
+            let bridges = local_index::get_bridges(flow.config, L1, DataSource::Api).await?;
+            let deposit = message_deposit(&bridges, &tx_hash).ok_or_else(|| {
+                validation_error(&format!("No message leg indexed for {tx_hash}"))
+            })?;
+            flow.claim(l2, L1, &tx_hash, Some(deposit)).await
+        })
+        .await?;
+    run.step(
+        "Check bridge-and-call claims and call target balance",
+        async {
+            flow.check_claimed(l2, L1, asset_deposit).await?;
+            flow.check(l2, None, before, L1, message_deposit).await
+        },
+    )
+    .await?;
+
+    // ETH L2 → L1
+    let before = get_balance(flow.config, L1, &flow.recipient_str, None).await?;
+    let tx_hash = run
+        .step("Bridge ETH L2 → L1", flow.bridge(l2, L1, ETH))
+        .await?;
+    let deposit = run
+        .step("Claim ETH on L1", flow.claim(L1, l2, &tx_hash, None))
+        .await?;
+    run.step(
+        "Check ETH balance and claim on L1",
+        flow.check(L1, None, before, l2, deposit),
+    )
+    .await?;
+
+    Ok(())
+}
+
+impl Flow<'_> {
+    /// Bridge `amount` of `token` to the recipient and return the transaction hash
+    async fn bridge(&self, source: u64, destination: u64, token: &str) -> Result<String> {
+        let args = BridgeAssetArgs::builder()
+            .config(self.config)
+            .source_network(source)
+            .destination_network(destination)
+            .amount(self.amount)
+            .token_address(token)
+            .recipient_address(&self.recipient_str)
+            .faucet_if_needed(true)
+            .build_with_crate_error()?;
+        bridge_asset(args).await?;
+        reported_tx_hash()
+    }
+
+    /// Bridge ETH to the recipient with an empty call and return the transaction hash
+    async fn bridge_and_call(&self, source: u64, destination: u64) -> Result<String> {
+        let fallback = self.config.accounts.accounts[0].as_str().to_string();
+        let args = BridgeAndCallArgs::builder()
+            .config(self.config)
+            .source_network(source)
+            .destination_network(destination)
+            .token_address(ETH)
+            .amount(self.amount)
+            .target(&self.recipient_str)
+            .data("0x")
+            .fallback(&fallback)
+            .build_with_crate_error()?;
+        bridge_and_call_with_approval(args).await?;
+        reported_tx_hash()
+    }
+
+    /// Claim a deposit once its proof is available and return its deposit count
+    async fn claim(
+        &self,
+        network: u64,
+        source: u64,
+        tx_hash: &str,
+        deposit_count: Option<u64>,
+    ) -> Result<u64> {
+        let args = ClaimAssetArgs::builder()
+            .config(self.config)
+            .network(network)
+            .tx_hash(tx_hash)
+            .source_network(source)
+            .deposit_count(deposit_count)
+            .auto_wait(Some(self.auto_wait))
+            .build_with_crate_error()?;
+        claim_asset(args).await?;
+        idempotency::take_result()
+            .and_then(|result| result["deposit_count"].as_u64())
+            .ok_or_else(|| AggSandboxError::Other("The claim reported no deposit count".into()))
+    }
+
+    /// Check that the recipient received `amount` and the deposit is claimed
+    async fn check(
+        &self,
+        network: u64,
+        token: Option<Address>,
+        before: U256,
+        source: u64,
+        deposit_count: u64,
+    ) -> Result<()> {
+        let after = match token {
+            Some(token) => self.token_balance(token).await?,
+            None => get_balance(self.config, network, &self.recipient_str, None).await?,
+        };
+        let expected = before + U256::from_dec_str(self.amount).unwrap_or_default();
+        if after != expected {
+            return Err(AggSandboxError::Other(format!(
+                "Balance of {} on network {network}: expected {expected}, got {after}",
+                self.recipient_str
+            )));
+        }
+        self.check_claimed(network, source, deposit_count).await
+    }
+
+    async fn check_claimed(&self, network: u64, source: u64, deposit_count: u64) -> Result<()> {
+        let provider = get_provider(self.config, network).await?;
+        let bridge =
+            BridgeContract::new(get_bridge_contract_address(self.config, network)?, provider);
+        let claimed = bridge
+            .is_claimed(deposit_count as u32, source as u32)
+            .call()
+            .await
+            .map_err(|e| AggSandboxError::Other(format!("Failed to check claim status: {e}")))?;
+        if !claimed {
+            return Err(AggSandboxError::Other(format!(
+                "Deposit {deposit_count} from network {source} is not claimed on network {network}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Wrapped address of an L1 token on the L2, zero before its first claim
+    async fn wrapped_token(&self, token: &str) -> Result<Address> {
+        get_mapped_token_info(MappedTokenArgs {
+            config: self.config,
+            network: self.l2,
+            origin_network: L1 as u32,
+            origin_token_address: token,
+            private_key: None,
+        })
+        .await
+    }
+
+    async fn token_balance(&self, token: Address) -> Result<U256> {
+        if token.is_zero() {
+            return Ok(U256::zero());
+        }
+        get_balance(
+            self.config,
+            self.l2,
+            &self.recipient_str,
+            Some(&format!("{token:#x}")),
+        )
+        .await
+    }
+}
+
+/// Transaction hash reported by the bridge operation that just finished
+pub fn reported_tx_hash() -> Result<String> {
+    idempotency::take_result()
+        .and_then(|result| result["tx_hash"].as_str().map(str::to_string))
+        .ok_or_else(|| AggSandboxError::Other("The bridge reported no transaction hash".into()))
+}
+
+/// Deposit count of the message leg of a bridge-and-call transaction
+fn message_deposit(bridges: &Value, tx_hash: &str) -> Option<u64> {
+    bridges["bridges"]
+        .as_array()?
+        .iter()
+        .find(|bridge| {
+            bridge["bridge_tx_hash"].as_str() == Some(tx_hash)
+                && bridge["leaf_type"].as_u64() == Some(1)
+        })
+        .and_then(|bridge| bridge["deposit_count"].as_u64())
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn report(run: &Run, l2: u64, output: OutputFormat) {
+    let ui = ui::ui();
+    if output.is_json() {
+        ui.json(&serde_json::json!({
+            "suite": "e2e",
+            "network_id": l2,
+            "passed": run.passed(),
+            "steps": run.steps,
+        }));
+        return;
+    }
+
+    let passed = run.steps.iter().filter(|step| step.passed).count();
+    if run.passed() {
+        ui.success(&format!("End-to-end test passed ({passed} steps)"));
+    } else if let Some(failed) = run.steps.iter().find(|step| !step.passed) {
+        ui.error(&format!(
+            "End-to-end test failed at '{}' after {passed} passing steps: {}",
+            failed.step,
+            failed.error.as_deref().unwrap_or("unknown error")
+        ));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_message_deposit() {
+        let bridges = json!({
+            "bridges": [
+                { "bridge_tx_hash": "0xaa", "deposit_count": 4, "leaf_type": 0 },
+                { "bridge_tx_hash": "0xaa", "deposit_count": 5, "leaf_type": 1 },
+                { "bridge_tx_hash": "0xbb", "deposit_count": 6, "leaf_type": 1 },
+            ]
+        });
+        assert_eq!(message_deposit(&bridges, "0xaa"), Some(5));
+        assert_eq!(message_deposit(&bridges, "0xcc"), None);
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_at_failure() {
+        // Steps report their progress through the UI
+        crate::ui::init_ui(crate::ui::OutputFormat::Json, true);
+        let mut run = Run::default();
+        assert_eq!(run.step("first", async { Ok(1) }).await.unwrap(), 1);
+        let failed: Result<()> = run
+            .step("second", async {
+                Err(AggSandboxError::Other("boom".to_string()))
+            })
+            .await;
+        assert!(failed.is_err());
+
+        assert!(!run.passed());
+        assert_eq!(run.steps.len(), 2);
+        assert!(run.steps[1].error.as_deref().unwrap().contains("boom"));
+    }
+}
@@ -0,0 +1,171 @@
+//! Endpoint matrix of the configured networks
+//!
+//! `show config-endpoints` gathers what is otherwise spread over `.env`, the
+//! compose files and the code: per network its RPC and WebSocket URLs, chain
+//! ID, bridge contracts and the aggkit bridge service that indexes it.
+
+use crate::commands::bridge::common::get_network_name;
+use crate::commands::bridge::{get_bridge_contract_address, get_bridge_extension_address};
+use crate::config::Config;
+use crate::types::NetworkId;
+use serde::Serialize;
+
+/// Endpoints and contracts of one network
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkEndpoints {
+    pub network_id: u64,
+    pub name: String,
+    pub chain_id: String,
+    pub rpc_url: String,
+    /// WebSocket URL; Anvil serves it on the RPC port
+    pub ws_url: String,
+    pub bridge: Option<String>,
+    pub bridge_extension: Option<String>,
+    /// Bridge service (aggkit REST API) serving the network
+    pub api_url: Option<String>,
+}
+
+/// Endpoints of every configured network, in network ID order
+pub fn endpoint_matrix(config: &Config) -> Vec<NetworkEndpoints> {
+    config
+        .networks
+        .network_ids()
+        .into_iter()
+        .filter_map(|network_id| {
+            let chain = config.networks.chain(network_id)?;
+            Some(NetworkEndpoints {
+                network_id,
+                name: get_network_name(network_id),
+                chain_id: chain.chain_id.to_string(),
+                rpc_url: chain.rpc_url.to_string(),
+                ws_url: chain
+                    .ws_url
+                    .as_ref()
+                    .map_or_else(|| ws_url(chain.rpc_url.as_str()), ToString::to_string),
+                bridge: get_bridge_contract_address(config, network_id)
+                    .ok()
+                    .map(|address| format!("{address:#x}")),
+                bridge_extension: get_bridge_extension_address(config, network_id)
+                    .ok()
+                    .map(|address| format!("{address:#x}")),
+                api_url: NetworkId::new(network_id)
+                    .ok()
+                    .map(|id| config.get_api_base_url(id)),
+            })
+        })
+        .collect()
+}
+
+/// WebSocket URL served next to an HTTP RPC URL, shown when none is configured
+pub fn ws_url(rpc_url: &str) -> String {
+    if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        rpc_url.to_string()
+    }
+}
+
+fn or_dash(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "-".to_string())
+}
+
+/// Row label of the matrix and the cell of a network
+type MatrixRow = (&'static str, fn(&NetworkEndpoints) -> String);
+
+/// Matrix with one column per network and one row per endpoint
+pub fn render_matrix(networks: &[NetworkEndpoints]) -> String {
+    let fields: [MatrixRow; 8] = [
+        ("Network", |n| format!("{} ({})", n.network_id, n.name)),
+        ("Chain ID", |n| n.chain_id.clone()),
+        ("RPC URL", |n| n.rpc_url.clone()),
+        ("WS URL", |n| n.ws_url.clone()),
+        ("Bridge", |n| or_dash(&n.bridge)),
+        ("Bridge extension", |n| or_dash(&n.bridge_extension)),
+        ("Network ID", |n| n.network_id.to_string()),
+        ("Aggkit API", |n| or_dash(&n.api_url)),
+    ];
+
+    let lines: Vec<Vec<String>> = fields
+        .iter()
+        .map(|(label, value)| {
+            std::iter::once(label.to_string())
+                .chain(networks.iter().map(value))
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..=networks.len())
+        .map(|column| {
+            lines
+                .iter()
+                .map(|line| line[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let separator = widths
+        .iter()
+        .map(|width| "─".repeat(width + 2))
+        .collect::<Vec<_>>();
+    let mut output = format!("┌{}┐\n", separator.join("┬"));
+    for (index, line) in lines.iter().enumerate() {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!(" {cell:<width$} "))
+            .collect();
+        output.push_str(&format!("│{}│\n", cells.join("│")));
+        if index == 0 {
+            output.push_str(&format!("├{}┤\n", separator.join("┼")));
+        }
+    }
+    output.push_str(&format!("└{}┘", separator.join("┴")));
+    output
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn network(network_id: u64, bridge: Option<&str>) -> NetworkEndpoints {
+        NetworkEndpoints {
+            network_id,
+            name: format!("net-{network_id}"),
+            chain_id: "1101".to_string(),
+            rpc_url: "http://localhost:8546".to_string(),
+            ws_url: "ws://localhost:8546".to_string(),
+            bridge: bridge.map(str::to_string),
+            bridge_extension: None,
+            api_url: Some("http://localhost:5577".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_ws_url() {
+        assert_eq!(ws_url("http://localhost:8545"), "ws://localhost:8545");
+        assert_eq!(
+            ws_url("https://rpc.example.org/v1"),
+            "wss://rpc.example.org/v1"
+        );
+    }
+
+    #[test]
+    fn test_render_matrix_has_a_column_per_network() {
+        let matrix = render_matrix(&[network(0, Some("0xabc")), network(1, None)]);
+        let lines: Vec<&str> = matrix.lines().collect();
+        // Borders, header, separator and seven endpoint rows
+        assert_eq!(lines.len(), 11);
+        assert!(lines[1].contains("0 (net-0)") && lines[1].contains("1 (net-1)"));
+        let bridge = lines
+            .iter()
+            .find(|line| line.starts_with("│ Bridge ") && !line.contains("extension"))
+            .unwrap();
+        assert!(bridge.contains("0xabc") && bridge.contains(" - "));
+        // Every line has the same width
+        let width = lines[0].chars().count();
+        assert!(lines.iter().all(|line| line.chars().count() == width));
+    }
+}
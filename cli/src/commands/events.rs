@@ -1,57 +1,113 @@
+use crate::commands::bridge::common::validation_error;
+use crate::concurrency::dedup_networks;
+use crate::config::Config;
 use crate::error::Result;
-use crate::events;
-use crate::validation::Validator;
+use crate::events::{self, BlockBound, BlockRange, EventFormat};
+use crate::types::MAX_NETWORK_ID;
+use crate::ui::OutputFormat;
+use crate::validation::{Validator, ALL_NETWORKS};
 
 /// Handle the events command
+#[allow(clippy::too_many_arguments)] // Mirrors the flags of the events command
 pub async fn handle_events(
-    network_id: Option<u64>,
+    network_ids: Vec<u64>,
     chain: Option<String>,
     blocks: u64,
+    from_block: Option<BlockBound>,
+    to_block: Option<BlockBound>,
     address: Option<String>,
+    format: EventFormat,
+    follow: bool,
+    interval: u64,
+    interleave: bool,
+    proxy_resolve: bool,
+    output: OutputFormat,
 ) -> Result<()> {
-    // Determine which parameter to use and validate
-    let resolved_chain = match (network_id, chain) {
-        (Some(net_id), Some(_chain_name)) => {
-            // Both provided - prefer network_id and warn about chain
-            eprintln!("⚠️  Both --network-id and --chain provided. Using --network-id={net_id}. Please use --network-id only as --chain is deprecated.");
-            let validated_net_id = Validator::validate_network_id(net_id)?;
-            network_id_to_chain(validated_net_id)?
-        }
-        (Some(net_id), None) => {
-            // Network ID provided - validate and convert
-            let validated_net_id = Validator::validate_network_id(net_id)?;
-            network_id_to_chain(validated_net_id)?
+    let network_ids = expand_networks(&network_ids)?;
+    let range = BlockRange {
+        blocks,
+        from: from_block,
+        to: to_block,
+    };
+    if network_ids.len() > 1 {
+        if chain.is_some() {
+            return Err(validation_error(
+                "--chain cannot be combined with several --network-id values",
+            ));
         }
-        (None, Some(chain_name)) => {
-            // Chain provided - warn about deprecation
-            eprintln!("⚠️  --chain parameter is deprecated. Please use --network-id instead (0=L1, 1=L2, 2=L3).");
-            chain_name
+        if follow {
+            return Err(validation_error("--follow takes a single --network-id"));
         }
-        (None, None) => {
-            return Err(crate::error::ConfigError::missing_required(
-                "Either --network-id or --chain must be provided",
-            )
-            .into());
+        let mut networks = Vec::with_capacity(network_ids.len());
+        for network_id in network_ids {
+            let network_id = Validator::validate_network_id(network_id)?;
+            networks.push((network_id, network_id_to_chain(network_id)?));
         }
-    };
+        return events::fetch_and_display_events_for_chains(
+            &networks,
+            range,
+            address,
+            format,
+            output.is_json(),
+            proxy_resolve,
+            interleave,
+        )
+        .await;
+    }
+
+    // Resolve --network-id (or the deprecated --chain) through the shared resolver
+    let network_id = network_ids.first().copied();
+    let net_id = Validator::resolve_network_selection(network_id, chain.as_deref())?;
+    let validated_net_id = Validator::validate_network_id(net_id)?;
+    let resolved_chain = network_id_to_chain(validated_net_id)?;
 
-    events::fetch_and_display_events(&resolved_chain, blocks, address).await
+    if follow {
+        let json = output.is_json() || format == EventFormat::Json;
+        return events::follow_events(
+            &resolved_chain,
+            blocks,
+            from_block,
+            address,
+            interval,
+            json,
+            proxy_resolve,
+        )
+        .await;
+    }
+
+    events::fetch_and_display_events(
+        &resolved_chain,
+        range,
+        address,
+        format,
+        output.is_json(),
+        proxy_resolve,
+    )
+    .await
+}
+
+/// Deduplicate the requested networks, expanding `all` to every configured network
+fn expand_networks(network_ids: &[u64]) -> Result<Vec<u64>> {
+    if !network_ids.contains(&ALL_NETWORKS) {
+        return Ok(dedup_networks(network_ids));
+    }
+    let mut expanded = Config::load()?.networks.network_ids();
+    expanded.extend(network_ids.iter().filter(|&&id| id != ALL_NETWORKS));
+    Ok(dedup_networks(&expanded))
 }
 
 /// Convert network ID to chain name
-fn network_id_to_chain(network_id: u64) -> Result<String> {
+pub fn network_id_to_chain(network_id: u64) -> Result<String> {
     match network_id {
-        0 => Ok("anvil-l1".to_string()),     // L1 Ethereum
-        1 => Ok("anvil-l2".to_string()),     // First L2
-        2 => Ok("anvil-l3".to_string()),     // Second L2
-        3 => Ok("anvil-l3".to_string()),     // Third L2 (maps to same chain for now)
+        // L1 and the L2s, network N runs on anvil-l<N+1>
+        0..=MAX_NETWORK_ID => Ok(format!("anvil-l{}", network_id + 1)),
         31337 => Ok("anvil-l1".to_string()), // Local dev L1
         31338 => Ok("anvil-l2".to_string()), // Local dev L2
         31339 => Ok("anvil-l3".to_string()), // Local dev L3
         _ => Err(crate::error::ConfigError::invalid_value(
             "network_id",
             &network_id.to_string(),
-            "Unsupported network ID for events command. Use 0 (L1), 1 (L2), or 2-3 (L3)",
+            "Unsupported network ID for events command. Use 0 (L1) or an L2 network ID",
         )
         .into()),
     }
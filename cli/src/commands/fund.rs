@@ -0,0 +1,186 @@
+//! Funding of test accounts for setup scripts
+//!
+//! ETH is credited with `anvil_setBalance`, so no account has to pay for it,
+//! and sandbox tokens are minted by the deployer account. This replaces the
+//! `cast rpc anvil_setBalance` and `cast send ... mint` calls setup scripts
+//! would otherwise hand-craft.
+
+use crate::commands::bridge::common::{
+    get_network_name, node_request, rpc_action, rpc_error, validate_address, validate_network_id,
+    validation_error,
+};
+use crate::commands::bridge::faucet::{
+    is_sandbox_token, layer_for_network, mint_from_deployer, resolve_sandbox_token,
+};
+use crate::commands::bridge::get_provider;
+use crate::commands::bridge::token_deploy::TokenRegistry;
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui::{self, OutputFormat, UI};
+use crate::validation::Validator;
+use ethers::prelude::*;
+use ethers::utils::{format_ether, parse_ether, parse_units};
+use tracing::info;
+
+/// Options of the fund command
+#[derive(Debug)]
+pub struct FundOptions {
+    pub network_id: u64,
+    pub address: String,
+    pub eth: Option<String>,
+    pub token: Option<String>,
+    pub amount: Option<String>,
+}
+
+/// Handle the fund command
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn handle_fund(options: FundOptions, output: OutputFormat) -> Result<()> {
+    validate_network_id(options.network_id, "Network")?;
+    let config = Config::load()?;
+    let network_id = options.network_id;
+    let address = validate_address(&options.address, "Address")?;
+    let mut funded = serde_json::Map::new();
+
+    if let Some(eth) = &options.eth {
+        let amount = parse_ether(eth.trim())
+            .map_err(|_| validation_error(&format!("Invalid ETH amount '{eth}'")))?;
+        let balance = credit_eth(&config, network_id, address, amount).await?;
+        info!(network_id, address = ?address, amount = %amount, "Credited ETH");
+        funded.insert(
+            "eth_balance_wei".to_string(),
+            serde_json::json!(balance.to_string()),
+        );
+        if !output.is_json() {
+            ui::ui().success(&format!(
+                "Credited {eth} ETH to {address:#x} on network {network_id} ({}), balance now {} ETH",
+                get_network_name(network_id),
+                format_ether(balance)
+            ));
+        }
+    }
+
+    if let (Some(token), Some(amount)) = (&options.token, &options.amount) {
+        let token_address = resolve_token(&config, network_id, token)?;
+        let amount = parse_token_amount(amount)?;
+        let tx_hash =
+            mint_from_deployer(&config, network_id, address, token_address, amount).await?;
+        info!(network_id, address = ?address, token = ?token_address, amount = %amount, "Minted tokens");
+        funded.insert(
+            "token".to_string(),
+            serde_json::json!(format!("{token_address:#x}")),
+        );
+        funded.insert(
+            "token_amount".to_string(),
+            serde_json::json!(amount.to_string()),
+        );
+        funded.insert(
+            "token_tx_hash".to_string(),
+            serde_json::json!(format!("{tx_hash:#x}")),
+        );
+        if !output.is_json() {
+            ui::ui().success(&format!(
+                "Minted {amount} of {token} ({token_address:#x}) to {address:#x}: {tx_hash:#x}"
+            ));
+        }
+    }
+
+    if output.is_json() {
+        funded.insert("network_id".to_string(), serde_json::json!(network_id));
+        funded.insert(
+            "address".to_string(),
+            serde_json::json!(format!("{address:#x}")),
+        );
+        UI::new(output).json(&serde_json::Value::Object(funded));
+    }
+    Ok(())
+}
+
+/// Add `amount` to the ETH balance of `address`, returning the new balance
+async fn credit_eth(
+    config: &Config,
+    network_id: u64,
+    address: Address,
+    amount: U256,
+) -> Result<U256> {
+    let provider = get_provider(config, network_id).await?;
+    let balance = provider
+        .get_balance(address, None)
+        .await
+        .map_err(|e| rpc_error(&rpc_action(network_id, "eth_getBalance"), e))?;
+    let new_balance = balance.saturating_add(amount);
+    node_request::<_, serde_json::Value>(
+        &provider,
+        network_id,
+        "anvil_setBalance",
+        (address, new_balance),
+    )
+    .await?;
+    Ok(new_balance)
+}
+
+/// Sandbox or `token deploy` token given by address, contract name or symbol
+fn resolve_token(config: &Config, network_id: u64, token: &str) -> Result<Address> {
+    let layer = layer_for_network(network_id)?;
+    let registry = TokenRegistry::default_location();
+    let address = if token.starts_with("0x") {
+        validate_address(token, "Token address")?
+    } else {
+        resolve_sandbox_token(&config.contracts, &layer, token)
+            .or_else(|| registry.find(network_id, token).map(|deployed| deployed.address))
+            .ok_or_else(|| {
+                validation_error(&format!(
+                    "Unknown token '{token}' on network {network_id}, use AGG, the symbol of a token from `token deploy` or a token address"
+                ))
+            })?
+    };
+    if !is_sandbox_token(&config.contracts, &layer, address)
+        && !registry.contains(network_id, address)
+    {
+        return Err(validation_error(&format!(
+            "{address:#x} is not a sandbox-deployed token on network {network_id} and cannot be minted"
+        )));
+    }
+    Ok(address)
+}
+
+/// Parse a raw token amount in decimal, 0x-prefixed hex or scientific notation (`1e18`, `2.5e6`)
+fn parse_token_amount(value: &str) -> Result<U256> {
+    let value = value.trim();
+    let invalid = || validation_error(&format!("Invalid token amount '{value}'"));
+    if value.starts_with("0x") {
+        return Validator::parse_uint(value).map_err(|_| invalid());
+    }
+    match value.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => {
+            let exponent: u32 = exponent.parse().map_err(|_| invalid())?;
+            // parse_units silently drops the digits the exponent cannot hold
+            let fraction_digits = mantissa.split_once('.').map_or(0, |(_, f)| f.len());
+            if fraction_digits > exponent as usize {
+                return Err(invalid());
+            }
+            parse_units(mantissa, exponent)
+                .map(U256::from)
+                .map_err(|_| invalid())
+        }
+        None => U256::from_dec_str(value).map_err(|_| invalid()),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token_amount() {
+        let one_token = U256::exp10(18);
+        assert_eq!(parse_token_amount("1e18").unwrap(), one_token);
+        assert_eq!(parse_token_amount("1E18").unwrap(), one_token);
+        assert_eq!(parse_token_amount("2.5e6").unwrap(), U256::from(2_500_000));
+        assert_eq!(parse_token_amount("100").unwrap(), U256::from(100));
+        assert_eq!(parse_token_amount("0x64").unwrap(), U256::from(100));
+        assert!(parse_token_amount("1.5").is_err());
+        assert!(parse_token_amount("1e").is_err());
+        assert!(parse_token_amount("1.2345e2").is_err());
+    }
+}
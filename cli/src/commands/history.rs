@@ -0,0 +1,431 @@
+//! Bridge history of an address across networks (`aggsandbox show history`)
+//!
+//! Deposits made by or to the address and claims paying it are collected from
+//! the bridges and claims of every network, labelled with their direction and
+//! status, and ordered by block time. Records without a `block_timestamp` (the
+//! local index and the chain fallback) get the time of their block over RPC.
+
+use ethers::prelude::*;
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use crate::commands::bridge::common::format_amount;
+use crate::commands::bridge::get_provider;
+use crate::commands::bridge::local_index::{self, DataSource};
+use crate::commands::bridge::token_metadata::cached_token_metadata;
+use crate::commands::bridge::watch::{claim_deposit_key, DepositKey};
+use crate::concurrency::query_networks;
+use crate::config::Config;
+use crate::error::Result;
+use crate::types::BridgeDeposit;
+
+/// Whether a history entry is a deposit or a claim
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    Deposit,
+    Claim,
+}
+
+/// One deposit or claim involving the address
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    pub kind: EntryKind,
+    /// Network the transaction was sent on
+    pub network_id: u64,
+    pub source_network: u64,
+    /// Destination of the deposit, unknown for claims the service reports without it
+    pub destination_network: Option<u64>,
+    pub deposit_count: u64,
+    pub tx_hash: H256,
+    pub from: Option<Address>,
+    pub to: Address,
+    pub origin_network: u32,
+    pub origin_address: Address,
+    /// Token symbol, `ETH` or `message`
+    pub token: String,
+    pub amount: String,
+    /// `pending` or `claimed` for deposits, `completed` for claims
+    pub status: String,
+    pub block_num: Option<u64>,
+    /// Unix time of the block
+    pub timestamp: Option<u64>,
+    #[serde(skip)]
+    decimals: u8,
+}
+
+impl HistoryEntry {
+    /// Route of the funds, e.g. `0 → 1`
+    pub fn direction(&self) -> String {
+        match self.destination_network {
+            Some(destination) => format!("{} → {destination}", self.source_network),
+            None => format!("{} → {}", self.source_network, self.network_id),
+        }
+    }
+}
+
+/// Bridges and claims of one network
+struct NetworkRecords {
+    deposits: Vec<(BridgeDeposit, serde_json::Value)>,
+    claims: Vec<serde_json::Value>,
+}
+
+/// Every deposit and claim involving `address` on `networks`, oldest first
+pub async fn fetch_history(
+    config: &Config,
+    networks: &[u64],
+    address: Address,
+    source: DataSource,
+) -> Result<Vec<HistoryEntry>> {
+    let results = query_networks(networks, |network_id| async move {
+        let bridges = local_index::get_bridges(config, network_id, source).await?;
+        let claims = local_index::get_claims(config, network_id, source).await?;
+        let deposits = bridges["bridges"]
+            .as_array()
+            .map(|records| {
+                records
+                    .iter()
+                    .filter_map(|record| {
+                        BridgeDeposit::from_value(record)
+                            .ok()
+                            .map(|deposit| (deposit, record.clone()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let claims = claims["claims"].as_array().cloned().unwrap_or_default();
+        Ok::<_, crate::error::AggSandboxError>(NetworkRecords { deposits, claims })
+    })
+    .await;
+
+    let mut records = Vec::with_capacity(results.len());
+    for (network_id, result) in results {
+        records.push((network_id, result?));
+    }
+
+    let claimed: HashSet<DepositKey> = records
+        .iter()
+        .flat_map(|(_, records)| records.claims.iter().filter_map(claim_deposit_key))
+        .collect();
+
+    let mut entries = Vec::new();
+    for (network_id, records) in &records {
+        for (deposit, record) in &records.deposits {
+            let from = record_address(record, "from_address");
+            if deposit.destination_address != address && from != Some(address) {
+                continue;
+            }
+            let key = (*network_id, deposit.deposit_count);
+            entries.push(HistoryEntry {
+                kind: EntryKind::Deposit,
+                network_id: *network_id,
+                source_network: *network_id,
+                destination_network: deposit.destination_network.map(u64::from),
+                deposit_count: deposit.deposit_count,
+                tx_hash: deposit.bridge_tx_hash,
+                from,
+                to: deposit.destination_address,
+                origin_network: deposit.origin_network.unwrap_or(*network_id as u32),
+                origin_address: deposit.origin_address,
+                token: if deposit.is_asset() {
+                    String::new()
+                } else {
+                    "message".to_string()
+                },
+                amount: deposit.amount.to_string(),
+                status: if claimed.contains(&key) {
+                    "claimed"
+                } else {
+                    "pending"
+                }
+                .to_string(),
+                block_num: deposit.block_num,
+                timestamp: record["block_timestamp"].as_u64(),
+                decimals: 18,
+            });
+        }
+        for record in &records.claims {
+            let Some(entry) = claim_entry(*network_id, record, address) else {
+                continue;
+            };
+            entries.push(entry);
+        }
+    }
+
+    label_tokens(config, &mut entries).await;
+    fill_timestamps(config, &mut entries).await;
+    sort_chronologically(&mut entries);
+    Ok(entries)
+}
+
+/// History entry of a claim paying or sent by `address`
+fn claim_entry(
+    network_id: u64,
+    record: &serde_json::Value,
+    address: Address,
+) -> Option<HistoryEntry> {
+    let to = record_address(record, "destination_address")?;
+    let from = record_address(record, "from_address");
+    if to != address && from != Some(address) {
+        return None;
+    }
+    let (source_network, deposit_count) = claim_deposit_key(record)?;
+    let tx_hash = ["claim_tx_hash", "tx_hash"].iter().find_map(|field| {
+        record[field]
+            .as_str()
+            .and_then(|hash| H256::from_str(hash).ok())
+    })?;
+    let amount = match &record["amount"] {
+        serde_json::Value::String(amount) => amount.clone(),
+        amount => amount.to_string(),
+    };
+    Some(HistoryEntry {
+        kind: EntryKind::Claim,
+        network_id,
+        source_network,
+        destination_network: Some(network_id),
+        deposit_count,
+        tx_hash,
+        from,
+        to,
+        origin_network: record["origin_network"].as_u64().unwrap_or_default() as u32,
+        origin_address: record_address(record, "origin_address").unwrap_or_default(),
+        token: String::new(),
+        amount,
+        status: "completed".to_string(),
+        block_num: record["block_num"].as_u64(),
+        timestamp: record["block_timestamp"].as_u64(),
+        decimals: 18,
+    })
+}
+
+fn record_address(record: &serde_json::Value, field: &str) -> Option<Address> {
+    record[field]
+        .as_str()
+        .and_then(|address| Address::from_str(address).ok())
+}
+
+/// Name the token of every asset entry after its origin token's symbol
+async fn label_tokens(config: &Config, entries: &mut [HistoryEntry]) {
+    let mut symbols: HashMap<(u32, Address), (String, u8)> = HashMap::new();
+    for entry in entries.iter_mut().filter(|entry| entry.token.is_empty()) {
+        let key = (entry.origin_network, entry.origin_address);
+        if let Entry::Vacant(vacant) = symbols.entry(key) {
+            let label = if entry.origin_address.is_zero() {
+                ("ETH".to_string(), 18)
+            } else {
+                match cached_token_metadata(
+                    config,
+                    entry.origin_network.into(),
+                    entry.origin_address,
+                )
+                .await
+                {
+                    Ok(metadata) => (
+                        metadata
+                            .symbol
+                            .unwrap_or_else(|| format!("{:#x}", entry.origin_address)),
+                        metadata.decimals.unwrap_or(18),
+                    ),
+                    Err(_) => (format!("{:#x}", entry.origin_address), 18),
+                }
+            };
+            vacant.insert(label);
+        }
+        if let Some((symbol, decimals)) = symbols.get(&key) {
+            entry.token = symbol.clone();
+            entry.decimals = *decimals;
+        }
+    }
+}
+
+/// Read the block time of entries whose record had none
+async fn fill_timestamps(config: &Config, entries: &mut [HistoryEntry]) {
+    let mut blocks: HashMap<(u64, u64), Option<u64>> = HashMap::new();
+    for entry in entries.iter().filter(|entry| entry.timestamp.is_none()) {
+        if let Some(block_num) = entry.block_num {
+            blocks.insert((entry.network_id, block_num), None);
+        }
+    }
+    for ((network_id, block_num), timestamp) in blocks.iter_mut() {
+        let Ok(provider) = get_provider(config, *network_id).await else {
+            continue;
+        };
+        *timestamp = provider
+            .get_block(*block_num)
+            .await
+            .ok()
+            .flatten()
+            .map(|block| block.timestamp.as_u64());
+    }
+    for entry in entries.iter_mut().filter(|entry| entry.timestamp.is_none()) {
+        entry.timestamp = entry.block_num.and_then(|block_num| {
+            blocks
+                .get(&(entry.network_id, block_num))
+                .copied()
+                .flatten()
+        });
+    }
+}
+
+/// Order entries by block time, entries without one last
+///
+/// Entries of the same time keep deposits before the claims of the same
+/// deposit, as happens on a sandbox that claims within the same second.
+fn sort_chronologically(entries: &mut [HistoryEntry]) {
+    entries.sort_by_key(|entry| {
+        (
+            entry.timestamp.is_none(),
+            entry.timestamp,
+            entry.kind == EntryKind::Claim,
+            entry.network_id,
+            entry.block_num,
+        )
+    });
+}
+
+/// Table of the entries in chronological order
+pub fn render_table(entries: &[HistoryEntry]) -> String {
+    let header = [
+        "Time", "Type", "Route", "Token", "Amount", "Status", "Deposit", "Tx",
+    ];
+    let mut lines = vec![header
+        .iter()
+        .map(|cell| cell.to_string())
+        .collect::<Vec<_>>()];
+    for entry in entries {
+        let time = entry
+            .timestamp
+            .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp as i64, 0))
+            .map_or_else(
+                || "-".to_string(),
+                |time| time.format("%Y-%m-%d %H:%M:%S").to_string(),
+            );
+        let amount = U256::from_dec_str(&entry.amount)
+            .map(|amount| format_amount(amount, entry.decimals))
+            .unwrap_or_else(|_| entry.amount.clone());
+        lines.push(vec![
+            time,
+            match entry.kind {
+                EntryKind::Deposit => "deposit".to_string(),
+                EntryKind::Claim => "claim".to_string(),
+            },
+            entry.direction(),
+            entry.token.clone(),
+            amount,
+            entry.status.clone(),
+            entry.deposit_count.to_string(),
+            format!("{:#x}", entry.tx_hash),
+        ]);
+    }
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            lines
+                .iter()
+                .map(|line| line[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let separator = widths
+        .iter()
+        .map(|width| "─".repeat(width + 2))
+        .collect::<Vec<_>>();
+    let mut output = format!("┌{}┐\n", separator.join("┬"));
+    for (index, line) in lines.iter().enumerate() {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!(" {cell:<width$} "))
+            .collect();
+        output.push_str(&format!("│{}│\n", cells.join("│")));
+        if index == 0 {
+            output.push_str(&format!("├{}┤\n", separator.join("┼")));
+        }
+    }
+    output.push_str(&format!("└{}┘", separator.join("┴")));
+    output
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn entry(kind: EntryKind, timestamp: Option<u64>) -> HistoryEntry {
+        HistoryEntry {
+            kind,
+            network_id: 0,
+            source_network: 0,
+            destination_network: Some(1),
+            deposit_count: 3,
+            tx_hash: H256::zero(),
+            from: None,
+            to: Address::repeat_byte(1),
+            origin_network: 0,
+            origin_address: Address::zero(),
+            token: "ETH".to_string(),
+            amount: "1500000000000000000".to_string(),
+            status: "pending".to_string(),
+            block_num: Some(10),
+            timestamp,
+            decimals: 18,
+        }
+    }
+
+    #[test]
+    fn test_claim_entry_matches_recipient() {
+        let address = Address::repeat_byte(1);
+        let record = serde_json::json!({
+            "claim_tx_hash": format!("{:#x}", H256::repeat_byte(7)),
+            "global_index": "18446744073709551619",
+            "origin_network": 0,
+            "origin_address": format!("{:#x}", Address::zero()),
+            "destination_address": format!("{address:#x}"),
+            "amount": "1000",
+            "block_num": 42,
+        });
+        let entry = claim_entry(1, &record, address).unwrap();
+        assert_eq!(entry.kind, EntryKind::Claim);
+        assert_eq!((entry.source_network, entry.deposit_count), (0, 3));
+        assert_eq!(entry.direction(), "0 → 1");
+        assert_eq!(entry.tx_hash, H256::repeat_byte(7));
+        assert!(claim_entry(1, &record, Address::repeat_byte(2)).is_none());
+    }
+
+    #[test]
+    fn test_sort_chronologically() {
+        let mut entries = vec![
+            entry(EntryKind::Deposit, None),
+            entry(EntryKind::Claim, Some(200)),
+            entry(EntryKind::Deposit, Some(200)),
+            entry(EntryKind::Deposit, Some(100)),
+        ];
+        sort_chronologically(&mut entries);
+        let order: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.kind, entry.timestamp))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (EntryKind::Deposit, Some(100)),
+                (EntryKind::Deposit, Some(200)),
+                (EntryKind::Claim, Some(200)),
+                (EntryKind::Deposit, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_table() {
+        let table = render_table(&[entry(EntryKind::Deposit, Some(0))]);
+        assert!(table.contains("1970-01-01 00:00:00"));
+        assert!(table.contains("0 → 1"));
+        assert!(table.contains("1.5"));
+        assert!(table.contains("pending"));
+    }
+}
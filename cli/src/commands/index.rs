@@ -0,0 +1,291 @@
+//! Local bridge index maintenance
+//!
+//! `index sync` fetches the bridge and exit-root events of the sandbox
+//! networks into `.aggsandbox/index/`, once or continuously with `--follow`.
+//! Commands taking `--source local` sync the networks they read on their own,
+//! so syncing by hand is only needed to inspect or pre-warm the index.
+
+use crate::commands::bridge::common::{get_network_name, validate_network_id};
+use crate::commands::bridge::local_index::{sync_network, IndexStore, NetworkIndex, SyncSummary};
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui::{OutputFormat, UI};
+use crate::validation::parse_network_id;
+use std::time::Duration;
+
+/// Index subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum IndexCommands {
+    /// 🔄 Sync the local bridge index from chain events
+    #[command(
+        long_about = "Fetch new bridge, claim and exit-root events into the local index.
+
+Only the blocks after the last indexed one are read. When the last indexed
+block is no longer part of the chain (the sandbox was restarted or a snapshot
+restored) the index of that network is rebuilt from block 0.
+
+Examples:
+  aggsandbox index sync                      # Sync every configured network
+  aggsandbox index sync -n 0 -n 1            # Sync L1 and the first L2
+  aggsandbox index sync --follow             # Keep syncing until Ctrl-C"
+    )]
+    Sync {
+        /// Networks to sync
+        #[arg(short = 'n', long = "network-id", alias = "network", value_parser = parse_network_id, help = "Network ID or alias to sync, repeatable (defaults to every configured network)")]
+        network_ids: Vec<u64>,
+        /// Keep syncing new blocks
+        #[arg(long, help = "Keep syncing until Ctrl-C")]
+        follow: bool,
+        /// Seconds between syncs with --follow
+        #[arg(
+            long,
+            default_value = "5",
+            requires = "follow",
+            help = "Seconds between syncs with --follow"
+        )]
+        interval: u64,
+    },
+    /// 📊 Show what the local bridge index contains
+    #[command(
+        long_about = "Show the last indexed block and the number of indexed deposits,
+claims and exit-root updates of every configured network.
+
+Examples:
+  aggsandbox index status
+  aggsandbox index status --output json"
+    )]
+    Status,
+    /// 🧹 Delete the local bridge index
+    #[command(
+        long_about = "Delete the local index of some or all networks. It is rebuilt by
+the next sync.
+
+Examples:
+  aggsandbox index reset                     # Reset every network
+  aggsandbox index reset -n 1"
+    )]
+    Reset {
+        /// Networks to reset
+        #[arg(short = 'n', long = "network-id", alias = "network", value_parser = parse_network_id, help = "Network ID or alias to reset, repeatable (defaults to every configured network)")]
+        network_ids: Vec<u64>,
+    },
+}
+
+/// Handle index subcommands
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn handle_index(subcommand: IndexCommands, output: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+    let store = IndexStore::default_location();
+    let ui = UI::new(output);
+
+    match subcommand {
+        IndexCommands::Sync {
+            network_ids,
+            follow,
+            interval,
+        } => {
+            let networks = selected_networks(&config, network_ids)?;
+            if !follow {
+                for &network_id in &networks {
+                    let (_, summary) = sync_network(&config, &store, network_id).await?;
+                    print_summary(&ui, &summary);
+                }
+                return Ok(());
+            }
+
+            if !ui.is_json() {
+                ui.info(&format!(
+                    "🔄 Syncing networks {networks:?} every {}s, press Ctrl-C to stop",
+                    interval.max(1)
+                ));
+            }
+            let ctrl_c = tokio::signal::ctrl_c();
+            tokio::pin!(ctrl_c);
+            loop {
+                for &network_id in &networks {
+                    match sync_network(&config, &store, network_id).await {
+                        Ok((_, summary)) if has_changes(&summary) => print_summary(&ui, &summary),
+                        Ok(_) => {}
+                        Err(e) => ui.warning(&format!(
+                            "Sync of network {network_id} failed, retrying: {e}"
+                        )),
+                    }
+                }
+                tokio::select! {
+                    _ = &mut ctrl_c => break,
+                    _ = tokio::time::sleep(Duration::from_secs(interval.max(1))) => {}
+                }
+            }
+            if !ui.is_json() {
+                ui.success("Stopped syncing the bridge index");
+            }
+            Ok(())
+        }
+        IndexCommands::Status => {
+            let mut indexes = Vec::new();
+            for network_id in config.networks.network_ids() {
+                indexes.push((network_id, store.load(network_id)?));
+            }
+            print_status(&ui, &indexes);
+            Ok(())
+        }
+        IndexCommands::Reset { network_ids } => {
+            let networks = selected_networks(&config, network_ids)?;
+            let mut removed = Vec::new();
+            for network_id in networks {
+                if store.remove(network_id)? {
+                    removed.push(network_id);
+                }
+            }
+            if ui.is_json() {
+                ui.json(&serde_json::json!({ "removed": removed }));
+            } else if removed.is_empty() {
+                ui.info("No local bridge index to reset");
+            } else {
+                ui.success(&format!(
+                    "Reset the local bridge index of networks {removed:?}"
+                ));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Requested networks, or every configured network when none were given
+pub fn selected_networks(config: &Config, network_ids: Vec<u64>) -> Result<Vec<u64>> {
+    if network_ids.is_empty() {
+        return Ok(config.networks.network_ids());
+    }
+    for &network_id in &network_ids {
+        validate_network_id(network_id, "Network")?;
+    }
+    let mut networks = network_ids;
+    networks.sort_unstable();
+    networks.dedup();
+    Ok(networks)
+}
+
+fn has_changes(summary: &SyncSummary) -> bool {
+    summary.rebuilt
+        || summary.new_deposits > 0
+        || summary.new_claims > 0
+        || summary.new_exit_roots > 0
+        || summary.pruned > 0
+}
+
+fn print_summary(ui: &UI, summary: &SyncSummary) {
+    if ui.is_json() {
+        ui.json(&serde_json::to_value(summary).unwrap_or_default());
+        return;
+    }
+    if summary.rebuilt {
+        ui.warning(&format!(
+            "Network {} no longer matched its index, rebuilt it",
+            summary.network_id
+        ));
+    }
+    ui.success(&format!(
+        "Network {} ({}): blocks {}-{}, {} deposit(s), {} claim(s), {} exit root update(s)",
+        summary.network_id,
+        get_network_name(summary.network_id),
+        summary.from_block,
+        summary.to_block,
+        summary.new_deposits,
+        summary.new_claims,
+        summary.new_exit_roots
+    ));
+    if summary.pruned > 0 {
+        ui.info(&format!(
+            "Archived {} record(s) older than the INDEX_RETENTION policy",
+            summary.pruned
+        ));
+    }
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn status_json(network_id: u64, index: Option<&NetworkIndex>) -> serde_json::Value {
+    match index {
+        Some(index) => serde_json::json!({
+            "network_id": network_id,
+            "last_block": index.last_block,
+            "deposits": index.deposits.len(),
+            "claims": index.claims.len(),
+            "exit_roots": index.exit_roots.len(),
+        }),
+        None => serde_json::json!({ "network_id": network_id, "last_block": null }),
+    }
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_status(ui: &UI, indexes: &[(u64, Option<NetworkIndex>)]) {
+    if ui.is_json() {
+        let networks: Vec<serde_json::Value> = indexes
+            .iter()
+            .map(|(network_id, index)| status_json(*network_id, index.as_ref()))
+            .collect();
+        ui.json(&serde_json::json!({ "networks": networks }));
+        return;
+    }
+
+    let labels: Vec<String> = indexes
+        .iter()
+        .map(|(network_id, _)| format!("{network_id} ({})", get_network_name(*network_id)))
+        .collect();
+    let summaries: Vec<String> = indexes
+        .iter()
+        .map(|(_, index)| match index {
+            Some(index) => format!(
+                "block {} | {} deposits | {} claims | {} exit roots",
+                index
+                    .last_block
+                    .map_or_else(|| "n/a".to_string(), |block| block.to_string()),
+                index.deposits.len(),
+                index.claims.len(),
+                index.exit_roots.len()
+            ),
+            None => "not indexed".to_string(),
+        })
+        .collect();
+    let rows: Vec<(&str, &str)> = labels
+        .iter()
+        .zip(&summaries)
+        .map(|(label, summary)| (label.as_str(), summary.as_str()))
+        .collect();
+    ui.table("🗂️  Local Bridge Index", &rows);
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selected_networks() {
+        let mut config = Config::default();
+        config.networks.l3 = None;
+
+        assert_eq!(selected_networks(&config, vec![]).unwrap(), vec![0, 1]);
+        assert_eq!(
+            selected_networks(&config, vec![1, 0, 1]).unwrap(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn test_status_json() {
+        let mut index = NetworkIndex::new(1);
+        index.last_block = Some(42);
+
+        assert_eq!(
+            status_json(1, Some(&index)),
+            serde_json::json!({
+                "network_id": 1,
+                "last_block": 42,
+                "deposits": 0,
+                "claims": 0,
+                "exit_roots": 0,
+            })
+        );
+        assert_eq!(status_json(2, None)["last_block"], serde_json::Value::Null);
+    }
+}
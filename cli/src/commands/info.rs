@@ -2,7 +2,7 @@ use crate::config::Config;
 use crate::docker::{create_auto_docker_builder, execute_docker_command_with_output};
 use crate::error::Result;
 use crate::logs;
-use crate::ui;
+use crate::ui::{self, OutputFormat, UI};
 
 /// Detect the actual running mode by checking which services are running
 fn detect_running_mode() -> (bool, bool, bool) {
@@ -28,14 +28,29 @@ fn detect_running_mode() -> (bool, bool, bool) {
 }
 
 /// Handle the info command
-pub async fn handle_info() -> Result<()> {
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn handle_info(output: OutputFormat) -> Result<()> {
     let config = Config::load()?;
 
-    ui::ui().info("📋 Agglayer Sandbox Information");
-
     // Detect the actual running mode by checking which services are running
     let (is_multi_l2_running, _, is_sandbox_running) = detect_running_mode();
 
+    if output.is_json() {
+        let is_fork_mode = detect_fork_mode(&config);
+        UI::new(output).json(&serde_json::json!({
+            "running": is_sandbox_running,
+            "fork_mode": is_sandbox_running && is_fork_mode,
+            "multi_l2": is_multi_l2_running,
+            "api_base_url": config.api.base_url.as_str(),
+            "networks": config.networks,
+            "accounts": config.accounts,
+            "contracts": config.contracts,
+        }));
+        return Ok(());
+    }
+
+    ui::ui().info("📋 Agglayer Sandbox Information");
+
     // Check if sandbox is actually running
     if !is_sandbox_running {
         ui::ui().blank_line();
@@ -50,18 +65,7 @@ pub async fn handle_info() -> Result<()> {
         return Ok(());
     }
 
-    // Detect fork mode by checking URL patterns
-    let is_fork_mode = config.networks.l1.rpc_url.as_str().contains("alchemy.com")
-        || config.networks.l1.rpc_url.as_str().contains("infura.io")
-        || config.networks.l1.rpc_url.as_str().contains("mainnet")
-        || config.networks.l2.rpc_url.as_str().contains("alchemy.com")
-        || config.networks.l2.rpc_url.as_str().contains("infura.io")
-        || config
-            .networks
-            .l2
-            .rpc_url
-            .as_str()
-            .contains("polygon-mainnet");
+    let is_fork_mode = detect_fork_mode(&config);
 
     // Choose the appropriate display function based on actual running mode
     if is_multi_l2_running {
@@ -74,3 +78,18 @@ pub async fn handle_info() -> Result<()> {
 
     Ok(())
 }
+
+/// Detect fork mode by checking URL patterns
+fn detect_fork_mode(config: &Config) -> bool {
+    config.networks.l1.rpc_url.as_str().contains("alchemy.com")
+        || config.networks.l1.rpc_url.as_str().contains("infura.io")
+        || config.networks.l1.rpc_url.as_str().contains("mainnet")
+        || config.networks.l2.rpc_url.as_str().contains("alchemy.com")
+        || config.networks.l2.rpc_url.as_str().contains("infura.io")
+        || config
+            .networks
+            .l2
+            .rpc_url
+            .as_str()
+            .contains("polygon-mainnet")
+}
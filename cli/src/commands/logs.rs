@@ -1,50 +1,253 @@
-use crate::error::Result;
+use crate::error::{ConfigError, DockerError, Result};
 use crate::validation::Validator;
 use colored::*;
+use regex::Regex;
+use std::io::{BufRead, BufReader};
+use std::process::Stdio;
+
+/// Options of the logs command
+#[derive(Debug, Default)]
+pub struct LogOptions {
+    pub follow: bool,
+    /// Services to show, all services when empty
+    pub services: Vec<String>,
+    /// Regular expression lines must match
+    pub grep: Option<String>,
+    /// Only logs newer than a duration (`10m`, `1h30m`) or an RFC 3339 timestamp
+    pub since: Option<String>,
+    /// Minimum level of structured log lines
+    pub level: Option<String>,
+}
+
+/// Severity of a structured log line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    pub fn parse(level: &str) -> Option<Self> {
+        match level.to_ascii_lowercase().as_str() {
+            "trace" | "trc" => Some(LogLevel::Trace),
+            "debug" | "dbg" => Some(LogLevel::Debug),
+            "info" | "inf" => Some(LogLevel::Info),
+            "warn" | "warning" | "wrn" => Some(LogLevel::Warn),
+            "error" | "err" | "eror" => Some(LogLevel::Error),
+            "fatal" | "panic" | "dpanic" | "crit" => Some(LogLevel::Fatal),
+            _ => None,
+        }
+    }
+
+    /// Level of a log line: the `level` field of a JSON line, or the first
+    /// level word of a text line (aggkit logs `<time>\tINFO\t<caller>\t<msg>`)
+    pub fn of_line(line: &str) -> Option<Self> {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('{') {
+            if let Ok(entry) = serde_json::from_str::<serde_json::Value>(trimmed) {
+                return ["level", "lvl", "severity"]
+                    .iter()
+                    .find_map(|key| entry[*key].as_str())
+                    .and_then(Self::parse);
+            }
+        }
+        trimmed
+            .split(|c: char| c.is_whitespace() || c == '[' || c == ']' || c == '|')
+            .take(6)
+            // Level words are upper case in text logs; "info" in a message is not a level
+            .filter(|word| !word.is_empty() && word.chars().all(|c| c.is_ascii_uppercase()))
+            .find_map(Self::parse)
+    }
+}
+
+/// Line filter built from `--grep` and `--level`
+#[derive(Debug, Default)]
+pub struct LogFilter {
+    grep: Option<Regex>,
+    level: Option<LogLevel>,
+}
+
+impl LogFilter {
+    pub fn new(grep: Option<&str>, level: Option<&str>) -> Result<Self> {
+        let grep = grep
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    ConfigError::invalid_value("grep", pattern, &format!("Invalid regex: {e}"))
+                })
+            })
+            .transpose()?;
+        let level = level
+            .map(|level| {
+                LogLevel::parse(level).ok_or_else(|| {
+                    ConfigError::invalid_value(
+                        "level",
+                        level,
+                        "Expected trace, debug, info, warn, error or fatal",
+                    )
+                })
+            })
+            .transpose()?;
+        Ok(Self { grep, level })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.grep.is_none() && self.level.is_none()
+    }
+
+    pub fn matches(&self, line: &str) -> bool {
+        // Skip the `service-1  | ` prefix docker compose adds
+        let message = line.split_once(" | ").map_or(line, |(_, message)| message);
+        if let Some(minimum) = self.level {
+            if LogLevel::of_line(message).is_none_or(|level| level < minimum) {
+                return false;
+            }
+        }
+        self.grep.as_ref().is_none_or(|grep| grep.is_match(line))
+    }
+}
+
+/// Validate a `--since` value as docker accepts it: a duration made of
+/// `<number><unit>` parts (`ms`, `s`, `m`, `h`) or an RFC 3339 timestamp
+#[allow(clippy::disallowed_methods)] // Allow unwrap for hardcoded regex
+pub fn validate_since(since: &str) -> Result<()> {
+    let duration = Regex::new(r"^(\d+(ms|s|m|h))+$").unwrap(); // Safe: hardcoded regex pattern is always valid
+    if duration.is_match(since) || chrono::DateTime::parse_from_rfc3339(since).is_ok() {
+        return Ok(());
+    }
+    Err(ConfigError::invalid_value(
+        "since",
+        since,
+        "Expected a duration such as 10m or 1h30m, or an RFC 3339 timestamp",
+    )
+    .into())
+}
 
 /// Handle the logs command
-pub fn handle_logs(follow: bool, service: Option<String>) -> Result<()> {
+pub fn handle_logs(options: LogOptions) -> Result<()> {
     use crate::docker::{
         create_auto_docker_builder, execute_docker_command, execute_docker_command_with_output,
     };
 
-    // Validate service name if provided
-    let validated_service = if let Some(svc) = service {
-        Some(Validator::validate_service_name(&svc)?)
+    // Validate service names if provided
+    let services = options
+        .services
+        .iter()
+        .map(|service| Validator::validate_service_name(service))
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(since) = options.since.as_deref() {
+        validate_since(since)?;
+    }
+    let filter = LogFilter::new(options.grep.as_deref(), options.level.as_deref())?;
+
+    let service_names = if services.is_empty() {
+        "all services".to_string()
     } else {
-        None
+        services.join(", ")
     };
-
-    let service_name = validated_service.as_deref().unwrap_or("all services");
     println!(
         "{} {}",
         "📋 Showing logs for:".blue().bold(),
-        service_name.cyan()
+        service_names.cyan()
     );
 
     // Create Docker builder that auto-detects configuration
     let mut docker_builder = create_auto_docker_builder();
-
-    // Add service if specified
-    if let Some(svc) = validated_service {
-        docker_builder.add_service(svc);
+    for service in services {
+        docker_builder.add_service(service);
     }
 
-    let cmd = docker_builder.build_logs_command(follow);
+    let mut cmd = docker_builder.build_logs_command(options.follow, options.since.as_deref(), None);
 
     // Handle follow vs non-follow modes differently
-    if follow {
+    if options.follow && filter.is_empty() {
         // For follow mode, we need real-time output
         execute_docker_command(cmd, false).inspect_err(|_e| {
             eprintln!("{}", "❌ Failed to show logs".red());
         })?
+    } else if options.follow {
+        // Filter the stream line by line as it arrives
+        let mut child = cmd.stdout(Stdio::piped()).spawn().map_err(|e| {
+            eprintln!("{}", "❌ Failed to show logs".red());
+            DockerError::command_failed("logs", &e.to_string())
+        })?;
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+                if filter.matches(&line) {
+                    println!("{line}");
+                }
+            }
+        }
+        child
+            .wait()
+            .map_err(|e| DockerError::command_failed("logs", &e.to_string()))?;
     } else {
         // For non-follow mode, capture and display output
         let output = execute_docker_command_with_output(cmd).inspect_err(|_e| {
             eprintln!("{}", "❌ Failed to show logs".red());
         })?;
-        print!("{output}");
+        if filter.is_empty() {
+            print!("{output}");
+        } else {
+            for line in output.lines().filter(|line| filter.matches(line)) {
+                println!("{line}");
+            }
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_of_line() {
+        assert_eq!(
+            LogLevel::of_line("2025-01-01T00:00:00.000Z\tWARN\tsync/driver.go:42\treorg detected"),
+            Some(LogLevel::Warn)
+        );
+        assert_eq!(
+            LogLevel::of_line(r#"{"level":"error","msg":"claim failed"}"#),
+            Some(LogLevel::Error)
+        );
+        assert_eq!(LogLevel::of_line("eth_blockNumber"), None);
+        assert_eq!(LogLevel::of_line("info about nothing"), None);
+    }
+
+    #[test]
+    fn test_filter_by_level_and_grep() {
+        let filter = LogFilter::new(Some("claim"), Some("warn")).unwrap();
+        assert!(
+            filter.matches("aggkit-1  | 2025-01-01T00:00:00Z\tERROR\tclaimsponsor\tclaim failed")
+        );
+        assert!(!filter.matches("aggkit-1  | 2025-01-01T00:00:00Z\tINFO\tclaimsponsor\tclaim sent"));
+        assert!(!filter.matches("aggkit-1  | 2025-01-01T00:00:00Z\tERROR\tbridgesync\treorg"));
+        // Lines without a level are dropped once a level is requested
+        assert!(!filter.matches("anvil-l1-1  | claim"));
+
+        let grep_only = LogFilter::new(Some("eth_(call|send)"), None).unwrap();
+        assert!(grep_only.matches("anvil-l1-1  | eth_call"));
+        assert!(!grep_only.matches("anvil-l1-1  | eth_blockNumber"));
+    }
+
+    #[test]
+    fn test_invalid_filters() {
+        assert!(LogFilter::new(Some("("), None).is_err());
+        assert!(LogFilter::new(None, Some("loud")).is_err());
+    }
+
+    #[test]
+    fn test_validate_since() {
+        assert!(validate_since("10m").is_ok());
+        assert!(validate_since("1h30m").is_ok());
+        assert!(validate_since("2025-01-01T00:00:00Z").is_ok());
+        assert!(validate_since("yesterday").is_err());
+        assert!(validate_since("10").is_err());
+    }
+}
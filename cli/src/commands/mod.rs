@@ -2,26 +2,95 @@
 ///
 /// This module contains all command handlers, extracted from main.rs
 /// for better code organization and maintainability.
+pub mod assert;
+pub mod balances;
 pub mod bridge;
+pub mod cache;
+pub mod chain;
+pub mod config;
+pub mod contracts;
+#[cfg(all(feature = "docker", feature = "tui"))]
+pub mod dashboard;
+pub mod doctor;
+pub mod e2e;
+pub mod endpoints;
 pub mod events;
+pub mod fund;
+pub mod history;
+pub mod index;
+#[cfg(feature = "docker")]
 pub mod info;
+#[cfg(feature = "docker")]
 pub mod logs;
+#[cfg(feature = "metrics")]
+pub mod monitor;
+pub mod readiness;
+pub mod registry;
+pub mod report;
+#[cfg(feature = "docker")]
 pub mod restart;
+pub mod rpc;
+#[cfg(feature = "daemon")]
+pub mod serve;
+#[cfg(feature = "tui")]
+pub mod shell;
 pub mod show;
+pub mod snapshot;
+#[cfg(feature = "docker")]
 pub mod start;
+#[cfg(feature = "docker")]
+pub mod start_state;
+pub mod state;
 pub mod status;
+#[cfg(feature = "docker")]
 pub mod stop;
+pub mod token;
+pub mod tx;
+#[cfg(feature = "docker")]
+pub mod upgrade;
 
 #[cfg(test)]
 mod tests;
 
 // Re-export command handlers for easier access
+pub use assert::{handle_assert, AssertCommands};
 pub use bridge::{handle_bridge, BridgeCommands};
+pub use cache::{handle_cache, CacheCommands};
+pub use chain::{handle_chain, ChainCommands};
+pub use config::{handle_config, ConfigCommands};
+pub use contracts::{handle_contracts, ContractsCommands};
+#[cfg(all(feature = "docker", feature = "tui"))]
+pub use dashboard::handle_dashboard;
+pub use doctor::handle_doctor;
+pub use e2e::{handle_test, TestCommands};
 pub use events::handle_events;
+pub use fund::{handle_fund, FundOptions};
+pub use index::{handle_index, IndexCommands};
+#[cfg(feature = "docker")]
 pub use info::handle_info;
-pub use logs::handle_logs;
+#[cfg(feature = "docker")]
+pub use logs::{handle_logs, LogOptions};
+#[cfg(feature = "metrics")]
+pub use monitor::{handle_monitor, MonitorCommands};
+pub use registry::{handle_registry, RegistryCommands};
+pub use report::handle_report;
+#[cfg(feature = "docker")]
 pub use restart::handle_restart;
+pub use rpc::handle_rpc;
+#[cfg(feature = "daemon")]
+pub use serve::handle_serve;
+#[cfg(feature = "tui")]
+pub use shell::run_shell;
 pub use show::{handle_show, ShowCommands};
+pub use snapshot::{handle_snapshot, SnapshotCommands};
+#[cfg(feature = "docker")]
 pub use start::handle_start;
+pub use state::{handle_state, StateCommands};
+#[cfg(feature = "docker")]
 pub use status::handle_status;
+#[cfg(feature = "docker")]
 pub use stop::handle_stop;
+pub use token::{handle_token, TokenCommands};
+pub use tx::{handle_tx, TxCommands};
+#[cfg(feature = "docker")]
+pub use upgrade::handle_upgrade;
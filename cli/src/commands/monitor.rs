@@ -0,0 +1,469 @@
+//! Latency SLO monitoring of a long-lived sandbox (`aggsandbox monitor slo`)
+//!
+//! A canary round trip, bridging a tiny ETH amount and claiming it on the
+//! destination network, is repeated at a fixed interval. The time from sending
+//! the bridge transaction to the claim receipt is the end-to-end latency of the
+//! sandbox; every round is appended to `.aggsandbox/slo.jsonl`, and rounds that
+//! fail or exceed the threshold raise the configured alerts.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::commands::bridge::common::{get_network_name, is_dry_run, validation_error};
+use crate::commands::bridge::proof_wait::AutoWait;
+use crate::commands::bridge::{bridge_asset, claim_asset, BridgeAssetArgs, ClaimAssetArgs};
+use crate::commands::e2e::reported_tx_hash;
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui::{self, OutputFormat};
+use crate::validation::parse_network_id;
+
+const SLO_LOG_FILE: &str = ".aggsandbox/slo.jsonl";
+const ETH: &str = "0x0000000000000000000000000000000000000000";
+
+/// Monitoring subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum MonitorCommands {
+    /// ⏱️ Measure bridge+claim latency continuously and alert on breaches
+    #[command(
+        long_about = "Run a canary bridge and claim round trip at a fixed interval.
+
+Each round bridges a tiny ETH amount from the source to the destination network,
+waits for the claim proof and claims it. The time from the bridge transaction to
+the claim receipt is recorded in .aggsandbox/slo.jsonl. A round that fails or
+takes longer than --threshold raises every --alert:
+
+  log      print a warning and keep monitoring (default)
+  webhook  POST the round as JSON to --webhook-url
+  exit     stop monitoring with a non-zero status
+
+Examples:
+  aggsandbox monitor slo --threshold 90
+  aggsandbox monitor slo -s 0 -n 1 --interval 60 --alert log --alert webhook --webhook-url https://hooks.example.com/sandbox
+  aggsandbox monitor slo --rounds 1 --alert exit"
+    )]
+    Slo {
+        /// Network the canary is bridged from
+        #[arg(short = 's', long, default_value = "0", value_parser = parse_network_id, help = "Source network ID")]
+        source_network_id: u64,
+        /// Network the canary is claimed on
+        #[arg(short = 'n', long, default_value = "1", value_parser = parse_network_id, help = "Destination network ID")]
+        network_id: u64,
+        #[arg(
+            long,
+            default_value = "300",
+            help = "Seconds between the start of two rounds"
+        )]
+        interval: u64,
+        #[arg(
+            long,
+            default_value = "120",
+            help = "Latency in seconds above which a round breaches the SLO"
+        )]
+        threshold: u64,
+        #[arg(
+            long,
+            default_value = "600",
+            help = "Seconds a round waits for the claim proof before failing"
+        )]
+        timeout: u64,
+        #[arg(long, default_value = "1", value_parser = crate::validation::parse_uint256, help = "Amount of the canary in wei (decimal or 0x hex)")]
+        amount: String,
+        #[arg(long, value_enum, default_values_t = [SloAlert::Log], help = "Alert raised on a breach (repeatable)")]
+        alert: Vec<SloAlert>,
+        #[arg(long, help = "URL the webhook alert posts to")]
+        webhook_url: Option<String>,
+        #[arg(long, help = "Stop after this many rounds (default: run until Ctrl-C)")]
+        rounds: Option<u64>,
+    },
+}
+
+/// How a breach is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SloAlert {
+    Log,
+    Webhook,
+    Exit,
+}
+
+/// One canary round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SloSample {
+    pub timestamp: String,
+    pub source_network: u64,
+    pub network: u64,
+    /// Bridge transaction to claim receipt, `None` when the round failed
+    pub latency_ms: Option<u64>,
+    pub threshold_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SloSample {
+    /// Whether the round failed or took longer than the threshold
+    pub fn is_breach(&self) -> bool {
+        self.latency_ms
+            .is_none_or(|latency| latency > self.threshold_ms)
+    }
+
+    fn describe(&self) -> String {
+        match (self.latency_ms, &self.error) {
+            (Some(latency), _) => format!(
+                "bridge {} → {} took {} (threshold {})",
+                self.source_network,
+                self.network,
+                format_ms(latency),
+                format_ms(self.threshold_ms)
+            ),
+            (None, error) => format!(
+                "bridge {} → {} failed: {}",
+                self.source_network,
+                self.network,
+                error.as_deref().unwrap_or("unknown error")
+            ),
+        }
+    }
+}
+
+/// Recorded rounds in the working directory
+#[derive(Debug, Clone)]
+pub struct SloLog {
+    path: PathBuf,
+}
+
+impl SloLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Log at `.aggsandbox/slo.jsonl` of the working directory
+    pub fn default_location() -> Self {
+        Self::new(SLO_LOG_FILE)
+    }
+
+    /// Recorded rounds, oldest first; unreadable lines are skipped
+    pub fn load(&self) -> Vec<SloSample> {
+        fs::read_to_string(&self.path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    pub fn append(&self, sample: &SloSample) -> Result<()> {
+        self.path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string(sample).map_err(|e| e.to_string()))
+            .and_then(|line| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .and_then(|mut file| writeln!(file, "{line}"))
+                    .map_err(|e| e.to_string())
+            })
+            .map_err(|e| {
+                AggSandboxError::Other(format!(
+                    "Failed to record the SLO round in {}: {e}",
+                    self.path.display()
+                ))
+            })
+    }
+}
+
+/// Settings of `monitor slo`
+struct SloMonitor<'a> {
+    config: &'a Config,
+    source_network: u64,
+    network: u64,
+    amount: &'a str,
+    threshold: Duration,
+    auto_wait: AutoWait,
+    alerts: Vec<SloAlert>,
+    webhook_url: Option<String>,
+}
+
+/// Handle monitor commands
+pub async fn handle_monitor(subcommand: MonitorCommands, _output: OutputFormat) -> Result<()> {
+    match subcommand {
+        MonitorCommands::Slo {
+            source_network_id,
+            network_id,
+            interval,
+            threshold,
+            timeout,
+            amount,
+            alert,
+            webhook_url,
+            rounds,
+        } => {
+            if is_dry_run() {
+                return Err(validation_error(
+                    "`monitor slo` measures real round trips and cannot run with --dry-run",
+                ));
+            }
+            if source_network_id == network_id {
+                return Err(validation_error(
+                    "The source and destination networks must differ",
+                ));
+            }
+            if alert.contains(&SloAlert::Webhook) && webhook_url.is_none() {
+                return Err(validation_error("--alert webhook requires --webhook-url"));
+            }
+
+            let config = Config::load()?;
+            let monitor = SloMonitor {
+                config: &config,
+                source_network: source_network_id,
+                network: network_id,
+                amount: &amount,
+                threshold: Duration::from_secs(threshold),
+                auto_wait: AutoWait::new(timeout, 3),
+                alerts: alert,
+                webhook_url,
+            };
+            monitor
+                .run(Duration::from_secs(interval.max(1)), rounds)
+                .await
+        }
+    }
+}
+
+impl SloMonitor<'_> {
+    async fn run(&self, interval: Duration, rounds: Option<u64>) -> Result<()> {
+        let ui = ui::ui();
+        let json = ui.is_json();
+        if !json {
+            ui.info(&format!(
+                "⏱️ Monitoring bridge latency {} ({}) → {} ({}) every {}s, threshold {}s; press Ctrl-C to stop",
+                self.source_network,
+                get_network_name(self.source_network),
+                self.network,
+                get_network_name(self.network),
+                interval.as_secs(),
+                self.threshold.as_secs()
+            ));
+        }
+
+        let log = SloLog::default_location();
+        let earlier = log.load();
+        if !json && !earlier.is_empty() {
+            ui.info(&format!(
+                "Appending to {SLO_LOG_FILE}: {} earlier round(s), {} breach(es)",
+                earlier.len(),
+                earlier.iter().filter(|sample| sample.is_breach()).count()
+            ));
+        }
+        let mut latencies = Vec::new();
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        let mut round = 0;
+        loop {
+            round += 1;
+            let started = Instant::now();
+            let sample = self.round().await;
+            if let Some(latency) = sample.latency_ms {
+                latencies.push(latency);
+            }
+            if let Err(e) = log.append(&sample) {
+                ui.warning(&e.to_string());
+            }
+
+            if json {
+                let line = serde_json::to_value(&sample).unwrap_or_default();
+                println!("{}", crate::schema::versioned(line));
+            } else if !sample.is_breach() {
+                ui.success(&format!(
+                    "Round {round}: {} ({})",
+                    sample.describe(),
+                    summarize(&latencies)
+                ));
+            }
+
+            if sample.is_breach() && self.alert(round, &sample).await {
+                return Err(AggSandboxError::Other(format!(
+                    "SLO breached in round {round}: {}",
+                    sample.describe()
+                )));
+            }
+            if rounds.is_some_and(|rounds| round >= rounds) {
+                break;
+            }
+
+            tokio::select! {
+                _ = &mut ctrl_c => break,
+                _ = tokio::time::sleep(interval.saturating_sub(started.elapsed())) => {}
+            }
+        }
+
+        if !json {
+            ui.success(&format!(
+                "Stopped monitoring after {round} round(s): {}",
+                summarize(&latencies)
+            ));
+        }
+        Ok(())
+    }
+
+    /// Bridge the canary and claim it, timing the round trip
+    async fn round(&self) -> SloSample {
+        let started = Instant::now();
+        let result = self.round_trip().await;
+        SloSample {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            source_network: self.source_network,
+            network: self.network,
+            latency_ms: result
+                .as_ref()
+                .ok()
+                .map(|_| started.elapsed().as_millis() as u64),
+            threshold_ms: self.threshold.as_millis() as u64,
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+
+    async fn round_trip(&self) -> Result<()> {
+        let args = BridgeAssetArgs::builder()
+            .config(self.config)
+            .source_network(self.source_network)
+            .destination_network(self.network)
+            .amount(self.amount)
+            .token_address(ETH)
+            .faucet_if_needed(true)
+            .build_with_crate_error()?;
+        bridge_asset(args).await?;
+        let tx_hash = reported_tx_hash()?;
+
+        let args = ClaimAssetArgs::builder()
+            .config(self.config)
+            .network(self.network)
+            .tx_hash(&tx_hash)
+            .source_network(self.source_network)
+            .auto_wait(Some(self.auto_wait))
+            .build_with_crate_error()?;
+        claim_asset(args).await
+    }
+
+    /// Raise the alerts of a breach, returning whether monitoring stops
+    #[allow(clippy::disallowed_methods)] // Allow tracing macros
+    async fn alert(&self, round: u64, sample: &SloSample) -> bool {
+        let message = format!("SLO breach in round {round}: {}", sample.describe());
+        for alert in &self.alerts {
+            match alert {
+                SloAlert::Log => {
+                    warn!(round, latency_ms = ?sample.latency_ms, "SLO breach");
+                    ui::ui().warning(&message);
+                }
+                SloAlert::Webhook => {
+                    if let Some(url) = &self.webhook_url {
+                        if let Err(e) = post_webhook(url, &message, sample).await {
+                            debug!(url = %url, error = %e, "SLO webhook failed");
+                            ui::ui().warning(&format!("SLO webhook to {url} failed: {e}"));
+                        }
+                    }
+                }
+                SloAlert::Exit => {}
+            }
+        }
+        self.alerts.contains(&SloAlert::Exit)
+    }
+}
+
+/// Post a breach as `{"text": ..., "sample": ...}`, which chat webhooks display
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+async fn post_webhook(
+    url: &str,
+    message: &str,
+    sample: &SloSample,
+) -> std::result::Result<(), reqwest::Error> {
+    reqwest::Client::new()
+        .post(url)
+        .timeout(Duration::from_secs(10))
+        .json(&serde_json::json!({ "text": message, "sample": sample }))
+        .send()
+        .await?
+        .error_for_status()
+        .map(|_| ())
+}
+
+/// Value at percentile `p` (0-100) of unsorted latencies, nearest rank
+pub fn percentile(latencies: &[u64], p: u8) -> Option<u64> {
+    if latencies.is_empty() {
+        return None;
+    }
+    let mut sorted = latencies.to_vec();
+    sorted.sort_unstable();
+    let rank = (usize::from(p.min(100)) * sorted.len())
+        .div_ceil(100)
+        .max(1);
+    sorted.get(rank - 1).copied()
+}
+
+fn summarize(latencies: &[u64]) -> String {
+    match (percentile(latencies, 50), percentile(latencies, 95)) {
+        (Some(p50), Some(p95)) => format!(
+            "p50 {}, p95 {}, {} successful round(s)",
+            format_ms(p50),
+            format_ms(p95),
+            latencies.len()
+        ),
+        _ => "no successful rounds".to_string(),
+    }
+}
+
+fn format_ms(ms: u64) -> String {
+    format!("{:.1}s", ms as f64 / 1000.0)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn sample(latency_ms: Option<u64>) -> SloSample {
+        SloSample {
+            timestamp: "2025-01-01T00:00:00Z".to_string(),
+            source_network: 0,
+            network: 1,
+            latency_ms,
+            threshold_ms: 60_000,
+            error: latency_ms.is_none().then(|| "claim timed out".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_breach() {
+        assert!(!sample(Some(60_000)).is_breach());
+        assert!(sample(Some(60_001)).is_breach());
+        assert!(sample(None).is_breach());
+        assert!(sample(None).describe().contains("claim timed out"));
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[], 50), None);
+        assert_eq!(percentile(&[7], 95), Some(7));
+        let latencies = [5, 1, 4, 2, 3, 6, 7, 8, 9, 10];
+        assert_eq!(percentile(&latencies, 50), Some(5));
+        assert_eq!(percentile(&latencies, 95), Some(10));
+        assert_eq!(percentile(&latencies, 0), Some(1));
+    }
+
+    #[test]
+    fn test_log_append_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = SloLog::new(dir.path().join(".aggsandbox").join("slo.jsonl"));
+        assert!(log.load().is_empty());
+
+        log.append(&sample(Some(1_500))).unwrap();
+        log.append(&sample(None)).unwrap();
+        assert_eq!(log.load(), vec![sample(Some(1_500)), sample(None)]);
+    }
+}
@@ -0,0 +1,146 @@
+//! Readiness gating of `start --detach`
+//!
+//! `docker compose up --detach` returns as soon as the containers run, while
+//! the Anvil nodes and aggkit need another 30-60s before they answer. `start`
+//! polls every node's RPC and every network's bridge service until they
+//! respond, showing one progress line per service, and fails with the
+//! service that never became healthy instead of leaving the first bridge
+//! command to find out.
+
+use crate::api_client::OptimizedApiClient;
+use crate::commands::bridge::get_provider;
+use crate::config::Config;
+use crate::error::{ApiError, EventError, Result};
+use crate::progress::ProgressBar;
+use crate::types::NetworkId;
+use ethers::prelude::*;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Default of `start --wait-timeout`, in seconds
+pub const DEFAULT_WAIT_TIMEOUT: u64 = 120;
+/// Time between two probes of a service that is not ready yet
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Timeout of a single probe
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Service whose health gates the start
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Service {
+    /// JSON-RPC endpoint of a network's Anvil node
+    Rpc { network_id: u64 },
+    /// Bridge service (aggkit REST API) indexing a network
+    BridgeApi { network_id: u64 },
+}
+
+impl Service {
+    pub fn name(&self) -> String {
+        match self {
+            Service::Rpc { network_id } => format!("anvil-l{} RPC", network_id + 1),
+            Service::BridgeApi { network_id } => {
+                format!("bridge API of network {network_id}")
+            }
+        }
+    }
+}
+
+/// Services to wait for, nodes first since aggkit indexes them
+pub fn services(network_ids: &[u64]) -> Vec<Service> {
+    let nodes = network_ids
+        .iter()
+        .map(|&network_id| Service::Rpc { network_id });
+    let apis = network_ids
+        .iter()
+        .map(|&network_id| Service::BridgeApi { network_id });
+    nodes.chain(apis).collect()
+}
+
+/// Probe a service once
+pub async fn probe(config: &Config, service: &Service) -> std::result::Result<(), String> {
+    match service {
+        Service::Rpc { network_id } => {
+            let provider = get_provider(config, *network_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            tokio::time::timeout(PROBE_TIMEOUT, provider.get_block_number())
+                .await
+                .map_err(|_| "timed out".to_string())?
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+        Service::BridgeApi { network_id } => {
+            let network = NetworkId::new(*network_id).map_err(|e| e.to_string())?;
+            let url = format!(
+                "{}/bridge/v1/bridges?network_id={network_id}",
+                config.get_api_base_url(network)
+            );
+            OptimizedApiClient::global()
+                .get_with_timeout(&url, PROBE_TIMEOUT)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Wait until every service of the configured networks responds
+///
+/// All services share one deadline `timeout` from now. Fails with the first
+/// service still unhealthy at the deadline.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn wait_until_ready(config: &Config, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    for service in services(&config.networks.network_ids()) {
+        let name = service.name();
+        let mut progress = ProgressBar::new(format!("Waiting for {name}..."));
+        let handle = progress.start().await;
+        loop {
+            let error = match probe(config, &service).await {
+                Ok(()) => {
+                    handle
+                        .finish_with_message(&format!("{name} is ready"))
+                        .await;
+                    break;
+                }
+                Err(e) => e,
+            };
+            debug!(service = %name, error = %error, "Service not ready yet");
+            if Instant::now() + POLL_INTERVAL > deadline {
+                handle
+                    .finish_with_error(&format!("{name} is not healthy"))
+                    .await;
+                let message = format!(
+                    "{name} did not become healthy within {}s: {error}",
+                    timeout.as_secs()
+                );
+                return Err(match service {
+                    Service::Rpc { .. } => EventError::rpc_connection_failed(&message).into(),
+                    Service::BridgeApi { .. } => ApiError::network_error(&message).into(),
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nodes_come_before_bridge_apis() {
+        let services = services(&[0, 1]);
+        assert_eq!(
+            services,
+            vec![
+                Service::Rpc { network_id: 0 },
+                Service::Rpc { network_id: 1 },
+                Service::BridgeApi { network_id: 0 },
+                Service::BridgeApi { network_id: 1 },
+            ]
+        );
+        assert_eq!(services[1].name(), "anvil-l2 RPC");
+        assert_eq!(services[2].name(), "bridge API of network 0");
+    }
+}
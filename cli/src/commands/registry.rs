@@ -0,0 +1,522 @@
+//! On-chain registry of the sandbox contract addresses
+//!
+//! `SandboxRegistry` from `agglayer-contracts` is deployed on every network
+//! through the CREATE2 deployer that Anvil ships at
+//! `0x4e59b44847b379578588920cA78FbF26c0B4956C`, with a fixed salt. Its address
+//! only depends on the contract bytecode, so it is the same on all networks and
+//! across restarts, and external scripts and contracts can look the sandbox
+//! contracts up on-chain instead of reading the `.env` file.
+//!
+//! `start` publishes the standard entries (`bridge`, `bridgeExtension`,
+//! `aggERC20`, `globalExitRootManager`); `registry set` adds others.
+
+use crate::artifacts::Artifacts;
+use crate::commands::bridge::common::{get_network_name, validate_network_id, validation_error};
+use crate::commands::bridge::faucet::layer_for_network;
+use crate::commands::bridge::{get_provider, get_wallet_with_provider};
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui::{OutputFormat, UI};
+use crate::validation::parse_network_id;
+use ethers::prelude::*;
+use ethers::utils::{get_create2_address, keccak256};
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Contract deployed as the registry
+const REGISTRY_CONTRACT: &str = "SandboxRegistry";
+/// Deterministic deployment proxy predeployed by Anvil
+const CREATE2_DEPLOYER: &str = "0x4e59b44847b379578588920cA78FbF26c0B4956C";
+/// Hashed into the CREATE2 salt
+const SALT_LABEL: &[u8] = b"aggsandbox.registry.v1";
+
+/// Registry names and the config contracts they point to
+pub const STANDARD_ENTRIES: &[(&str, &str)] = &[
+    ("bridge", "PolygonZkEVMBridge"),
+    ("bridgeExtension", "BridgeExtension"),
+    ("aggERC20", "AggERC20"),
+    ("globalExitRootManager", "GlobalExitRootManager"),
+];
+
+abigen!(
+    SandboxRegistry,
+    r#"[
+        function owner() external view returns (address)
+        function set(string name, address addr) external
+        function get(string name) external view returns (address)
+        function names() external view returns (string[])
+    ]"#,
+);
+
+/// Registry subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum RegistryCommands {
+    /// 📍 Show the registry address
+    #[command(
+        long_about = "Show the address of the on-chain registry and whether it is deployed
+on each network.
+
+The address is derived from the SandboxRegistry bytecode with CREATE2, so it is
+the same on every network.
+
+Examples:
+  aggsandbox registry address
+  aggsandbox registry address --output json"
+    )]
+    Address,
+    /// 📚 List the registry entries of a network
+    #[command(
+        long_about = "List the names and addresses stored in the registry of a network.
+
+Examples:
+  aggsandbox registry list -n 0
+  aggsandbox registry list -n l2 --output json"
+    )]
+    List {
+        /// Network to read
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, default_value = "0", help = "Network ID or alias (0/l1, 1/l2, 2/l3)")]
+        network_id: u64,
+    },
+    /// 🔎 Look a name up in the registry
+    #[command(long_about = "Print the address registered under a name on a network.
+
+Examples:
+  aggsandbox registry get bridge -n 1
+  aggsandbox registry get aggERC20 -n 0")]
+    Get {
+        /// Registry name
+        #[arg(help = "Registry name (e.g. bridge, bridgeExtension, aggERC20)")]
+        name: String,
+        /// Network to read
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, default_value = "0", help = "Network ID or alias (0/l1, 1/l2, 2/l3)")]
+        network_id: u64,
+    },
+    /// ✏️ Register an address under a name
+    #[command(
+        long_about = "Store an address under a name in the registry of a network.
+
+Only the registry owner, the account that deployed it (the first configured
+account), can write entries.
+
+Examples:
+  aggsandbox registry set myReceiver 0x1234... -n 1"
+    )]
+    Set {
+        /// Registry name
+        #[arg(help = "Registry name")]
+        name: String,
+        /// Address to register
+        #[arg(help = "Address to register under the name")]
+        address: String,
+        /// Network to write
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID or alias (0/l1, 1/l2, 2/l3)")]
+        network_id: u64,
+        /// Private key of the registry owner
+        #[arg(
+            long,
+            help = "Private key of the registry owner (defaults to the first configured account)"
+        )]
+        private_key: Option<String>,
+    },
+    /// 📤 Deploy the registry and publish the sandbox contracts
+    #[command(
+        long_about = "Deploy the registry where it is missing and write the standard entries
+(bridge, bridgeExtension, aggERC20, globalExitRootManager) from the current
+configuration. `aggsandbox start` does this automatically.
+
+Examples:
+  aggsandbox registry publish
+  aggsandbox registry publish -n 1"
+    )]
+    Publish {
+        /// Networks to publish to
+        #[arg(short = 'n', long = "network-id", alias = "network", value_parser = parse_network_id, help = "Network ID or alias, repeatable (defaults to every configured network)")]
+        network_ids: Vec<u64>,
+    },
+}
+
+/// Handle registry subcommands
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn handle_registry(subcommand: RegistryCommands, output: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+    let ui = UI::new(output);
+
+    match subcommand {
+        RegistryCommands::Address => {
+            let address = registry_address()?;
+            let mut deployed = Vec::new();
+            for network_id in config.networks.network_ids() {
+                let provider = get_provider(&config, network_id).await?;
+                deployed.push((network_id, has_code(&provider, address).await));
+            }
+            if ui.is_json() {
+                let networks: serde_json::Map<String, serde_json::Value> = deployed
+                    .iter()
+                    .map(|(network_id, deployed)| (network_id.to_string(), (*deployed).into()))
+                    .collect();
+                ui.json(&serde_json::json!({
+                    "address": format!("{address:#x}"),
+                    "deployed": networks,
+                }));
+                return Ok(());
+            }
+            ui.info(&format!("📍 Registry address: {address:#x}"));
+            for (network_id, deployed) in deployed {
+                let state = if deployed { "deployed" } else { "not deployed" };
+                ui.info(&format!(
+                    "  network {network_id} ({}): {state}",
+                    get_network_name(network_id)
+                ));
+            }
+            Ok(())
+        }
+        RegistryCommands::List { network_id } => {
+            validate_network_id(network_id, "Network")?;
+            let entries = list_entries(&config, network_id).await?;
+            if ui.is_json() {
+                let entries: serde_json::Map<String, serde_json::Value> = entries
+                    .iter()
+                    .map(|(name, address)| (name.clone(), format!("{address:#x}").into()))
+                    .collect();
+                ui.json(&serde_json::json!({ "network_id": network_id, "entries": entries }));
+                return Ok(());
+            }
+            if entries.is_empty() {
+                ui.info(&format!(
+                    "The registry on network {network_id} has no entries, run `aggsandbox registry publish`"
+                ));
+                return Ok(());
+            }
+            let addresses: Vec<String> = entries
+                .iter()
+                .map(|(_, address)| format!("{address:#x}"))
+                .collect();
+            let rows: Vec<(&str, &str)> = entries
+                .iter()
+                .zip(&addresses)
+                .map(|((name, _), address)| (name.as_str(), address.as_str()))
+                .collect();
+            ui.table(
+                &format!(
+                    "📚 Registry on network {network_id} ({})",
+                    get_network_name(network_id)
+                ),
+                &rows,
+            );
+            Ok(())
+        }
+        RegistryCommands::Get { name, network_id } => {
+            validate_network_id(network_id, "Network")?;
+            let registry = registry_contract(&config, network_id).await?;
+            let address = registry
+                .get(name.clone())
+                .call()
+                .await
+                .map_err(|e| registry_error(network_id, e))?;
+            if address == Address::zero() {
+                return Err(validation_error(&format!(
+                    "'{name}' is not registered on network {network_id}"
+                )));
+            }
+            if ui.is_json() {
+                ui.json(&serde_json::json!({
+                    "network_id": network_id,
+                    "name": name,
+                    "address": format!("{address:#x}"),
+                }));
+            } else {
+                println!("{address:#x}");
+            }
+            Ok(())
+        }
+        RegistryCommands::Set {
+            name,
+            address,
+            network_id,
+            private_key,
+        } => {
+            validate_network_id(network_id, "Network")?;
+            let address = Address::from_str(&address)
+                .map_err(|_| validation_error(&format!("Invalid address '{address}'")))?;
+            let tx_hash =
+                set_entry(&config, network_id, &name, address, private_key.as_deref()).await?;
+            if ui.is_json() {
+                ui.json(&serde_json::json!({
+                    "network_id": network_id,
+                    "name": name,
+                    "address": format!("{address:#x}"),
+                    "tx_hash": format!("{tx_hash:#x}"),
+                }));
+            } else {
+                ui.success(&format!(
+                    "Registered {name} = {address:#x} on network {network_id} (tx {tx_hash:#x})"
+                ));
+            }
+            Ok(())
+        }
+        RegistryCommands::Publish { network_ids } => {
+            let networks = if network_ids.is_empty() {
+                config.networks.network_ids()
+            } else {
+                for &network_id in &network_ids {
+                    validate_network_id(network_id, "Network")?;
+                }
+                network_ids
+            };
+            let mut published = Vec::new();
+            for network_id in networks {
+                let (address, written) = publish(&config, network_id).await?;
+                if !ui.is_json() {
+                    ui.success(&format!(
+                        "Registry at {address:#x} on network {network_id} ({}): {} entr{} updated",
+                        get_network_name(network_id),
+                        written.len(),
+                        if written.len() == 1 { "y" } else { "ies" }
+                    ));
+                }
+                published.push(serde_json::json!({
+                    "network_id": network_id,
+                    "address": format!("{address:#x}"),
+                    "updated": written,
+                }));
+            }
+            if ui.is_json() {
+                ui.json(&serde_json::json!({ "networks": published }));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn registry_bytecode() -> Result<Bytes> {
+    Artifacts::load().bytecode(REGISTRY_CONTRACT).ok_or_else(|| {
+        validation_error(&format!(
+            "No {REGISTRY_CONTRACT} build artifact found, run `forge build` in agglayer-contracts first"
+        ))
+    })
+}
+
+fn salt() -> [u8; 32] {
+    keccak256(SALT_LABEL)
+}
+
+fn create2_deployer() -> Address {
+    Address::from_str(CREATE2_DEPLOYER).unwrap_or_default()
+}
+
+/// CREATE2 address of a registry with the given creation bytecode
+fn address_for(bytecode: &Bytes) -> Address {
+    get_create2_address(create2_deployer(), salt(), bytecode.clone())
+}
+
+/// Address of the registry on every network
+pub fn registry_address() -> Result<Address> {
+    Ok(address_for(&registry_bytecode()?))
+}
+
+async fn has_code(provider: &Provider<Http>, address: Address) -> bool {
+    provider
+        .get_code(address, None)
+        .await
+        .is_ok_and(|code| !code.is_empty())
+}
+
+fn registry_error(network_id: u64, error: impl std::fmt::Display) -> AggSandboxError {
+    AggSandboxError::Other(format!(
+        "Registry call on network {network_id} ({}) failed: {error}",
+        get_network_name(network_id)
+    ))
+}
+
+fn owner_key(config: &Config, private_key: Option<&str>) -> Result<String> {
+    private_key
+        .or_else(|| config.accounts.private_keys.first().map(String::as_str))
+        .map(str::to_string)
+        .ok_or_else(|| validation_error("No private key configured for the registry owner"))
+}
+
+/// Registry contract of a network, failing when it is not deployed
+async fn registry_contract(
+    config: &Config,
+    network_id: u64,
+) -> Result<SandboxRegistry<Provider<Http>>> {
+    let address = registry_address()?;
+    let provider = get_provider(config, network_id).await?;
+    if !has_code(&provider, address).await {
+        return Err(validation_error(&format!(
+            "The registry is not deployed on network {network_id}, run `aggsandbox registry publish`"
+        )));
+    }
+    Ok(SandboxRegistry::new(address, provider))
+}
+
+/// Deploy the registry on a network unless it already is
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn ensure_deployed(config: &Config, network_id: u64) -> Result<Address> {
+    let bytecode = registry_bytecode()?;
+    let address = address_for(&bytecode);
+    let provider = get_provider(config, network_id).await?;
+    if has_code(&provider, address).await {
+        debug!(network_id, address = ?address, "Registry already deployed");
+        return Ok(address);
+    }
+    if !has_code(&provider, create2_deployer()).await {
+        return Err(validation_error(&format!(
+            "No CREATE2 deployer at {CREATE2_DEPLOYER} on network {network_id}, the registry cannot be deployed"
+        )));
+    }
+
+    let key = owner_key(config, None)?;
+    let client = get_wallet_with_provider(config, network_id, Some(&key)).await?;
+    let mut data = salt().to_vec();
+    data.extend_from_slice(&bytecode);
+    let tx = TransactionRequest::new()
+        .to(create2_deployer())
+        .data(Bytes::from(data));
+    info!(network_id, address = ?address, "Deploying registry");
+    client
+        .send_transaction(tx, None)
+        .await
+        .map_err(|e| registry_error(network_id, e))?
+        .await
+        .map_err(|e| registry_error(network_id, e))?;
+
+    if !has_code(&provider, address).await {
+        return Err(registry_error(
+            network_id,
+            format!("no code at {address:#x} after deployment"),
+        ));
+    }
+    Ok(address)
+}
+
+/// Write `address` under `name`, returning the transaction hash
+pub async fn set_entry(
+    config: &Config,
+    network_id: u64,
+    name: &str,
+    address: Address,
+    private_key: Option<&str>,
+) -> Result<H256> {
+    if name.is_empty() {
+        return Err(validation_error("Registry names cannot be empty"));
+    }
+    let registry_address = ensure_deployed(config, network_id).await?;
+    let key = owner_key(config, private_key)?;
+    let client = Arc::new(get_wallet_with_provider(config, network_id, Some(&key)).await?);
+    let registry = SandboxRegistry::new(registry_address, client.clone());
+
+    let owner = registry
+        .owner()
+        .call()
+        .await
+        .map_err(|e| registry_error(network_id, e))?;
+    if owner != client.inner().address() {
+        return Err(validation_error(&format!(
+            "Only the registry owner {owner:#x} can write entries, not {:#x}",
+            client.inner().address()
+        )));
+    }
+
+    let call = registry.set(name.to_string(), address);
+    let receipt = call
+        .send()
+        .await
+        .map_err(|e| registry_error(network_id, e))?
+        .await
+        .map_err(|e| registry_error(network_id, e))?
+        .ok_or_else(|| registry_error(network_id, "transaction dropped"))?;
+    Ok(receipt.transaction_hash)
+}
+
+/// Registered names and addresses of a network, in registration order
+pub async fn list_entries(config: &Config, network_id: u64) -> Result<Vec<(String, Address)>> {
+    let registry = registry_contract(config, network_id).await?;
+    let names = registry
+        .names()
+        .call()
+        .await
+        .map_err(|e| registry_error(network_id, e))?;
+    let mut entries = Vec::with_capacity(names.len());
+    for name in names {
+        let address = registry
+            .get(name.clone())
+            .call()
+            .await
+            .map_err(|e| registry_error(network_id, e))?;
+        entries.push((name, address));
+    }
+    Ok(entries)
+}
+
+/// Standard entries of a network from the configured contract addresses
+pub fn standard_entries(config: &Config, network_id: u64) -> Result<Vec<(&'static str, Address)>> {
+    let layer = layer_for_network(network_id)?;
+    Ok(STANDARD_ENTRIES
+        .iter()
+        .filter_map(|&(name, contract)| {
+            let address =
+                Address::from_str(&config.contracts.get_contract(&layer, contract)).ok()?;
+            Some((name, address))
+        })
+        .collect())
+}
+
+/// Deploy the registry if needed and write the standard entries that changed
+///
+/// Returns the registry address and the names that were written.
+pub async fn publish(config: &Config, network_id: u64) -> Result<(Address, Vec<String>)> {
+    let address = ensure_deployed(config, network_id).await?;
+    let current: std::collections::HashMap<String, Address> = list_entries(config, network_id)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut written = Vec::new();
+    for (name, entry) in standard_entries(config, network_id)? {
+        if current.get(name) == Some(&entry) {
+            continue;
+        }
+        set_entry(config, network_id, name, entry, None).await?;
+        written.push(name.to_string());
+    }
+    Ok((address, written))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_is_create2_of_bytecode() {
+        let bytecode: Bytes = vec![0x60, 0x00, 0x60, 0x00, 0xf3].into();
+        let address = address_for(&bytecode);
+
+        assert_eq!(address, address_for(&bytecode));
+        assert_ne!(address, address_for(&vec![0x00].into()));
+        assert_eq!(
+            create2_deployer(),
+            "0x4e59b44847b379578588920cA78FbF26c0B4956C"
+                .parse::<Address>()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_standard_entries_skip_undeployed_contracts() {
+        let mut config = Config::default();
+        config.contracts.l2_contracts.clear();
+        config.contracts.l2_contracts.insert(
+            "PolygonZkEVMBridge".to_string(),
+            "0x1111111111111111111111111111111111111111"
+                .parse()
+                .unwrap(),
+        );
+
+        let entries = standard_entries(&config, 1).unwrap();
+
+        assert_eq!(entries, vec![("bridge", Address::repeat_byte(0x11))]);
+    }
+}
@@ -0,0 +1,434 @@
+//! Bug report bundle (`aggsandbox report`)
+//!
+//! Collects what a bug report against aggkit or the bridge contracts needs
+//! into one markdown file: the services and their image digests, the endpoint
+//! matrix, the contract addresses, per network the latest bridges, claims and
+//! contract events, and the tail of every service's log. Each section is
+//! gathered independently; one that fails records its error in the report
+//! instead of aborting it, since reports are most useful when something is
+//! broken.
+
+use crate::api_client::OptimizedApiClient;
+use crate::commands::bridge::common::get_network_name;
+use crate::commands::endpoints::{endpoint_matrix, render_matrix, NetworkEndpoints};
+use crate::commands::events::network_id_to_chain;
+use crate::commands::status::{render_services, sandbox_status, SandboxStatus};
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui::{self, OutputFormat, UI};
+use serde_json::Value;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Default of `report --log-lines`
+pub const DEFAULT_LOG_LINES: usize = 200;
+/// Default of `report --blocks`
+pub const DEFAULT_EVENT_BLOCKS: u64 = 100;
+/// Bridges, claims and events listed per network
+const RECENT_ENTRIES: usize = 10;
+
+/// Outcome of gathering one section, failures are kept as their message
+type Section<T> = std::result::Result<T, String>;
+
+/// Bridge service and chain state of one network
+#[derive(Debug)]
+pub struct NetworkReport {
+    pub network_id: u64,
+    pub name: String,
+    pub bridges: Section<Value>,
+    pub claims: Section<Value>,
+    pub events: Section<Value>,
+}
+
+/// Everything written to the report
+#[derive(Debug)]
+pub struct Report {
+    pub generated_at: String,
+    pub status: Section<SandboxStatus>,
+    pub endpoints: Section<Vec<NetworkEndpoints>>,
+    /// Contract addresses as `(layer, name, address)`
+    pub contracts: Vec<(String, String, String)>,
+    pub networks: Vec<NetworkReport>,
+    /// Last log lines by service
+    pub logs: Vec<(String, Section<String>)>,
+}
+
+impl Report {
+    /// Number of sections that could not be gathered
+    pub fn failed_sections(&self) -> usize {
+        let networks: usize = self
+            .networks
+            .iter()
+            .map(|network| {
+                [&network.bridges, &network.claims, &network.events]
+                    .iter()
+                    .filter(|section| section.is_err())
+                    .count()
+            })
+            .sum();
+        let logs = self.logs.iter().filter(|(_, log)| log.is_err()).count();
+        usize::from(self.status.is_err()) + usize::from(self.endpoints.is_err()) + networks + logs
+    }
+}
+
+/// Handle the report command
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn handle_report(
+    output_file: Option<PathBuf>,
+    log_lines: usize,
+    blocks: u64,
+    output: OutputFormat,
+) -> Result<()> {
+    let generated_at = chrono::Utc::now();
+    let path = output_file.unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "aggsandbox-report-{}.md",
+            generated_at.format("%Y%m%d-%H%M%S")
+        ))
+    });
+
+    if !output.is_json() {
+        ui::ui().info("📦 Collecting sandbox report...");
+    }
+    let report = collect_report(generated_at.to_rfc3339(), log_lines, blocks).await;
+    std::fs::write(&path, render_markdown(&report))?;
+
+    let failed = report.failed_sections();
+    if output.is_json() {
+        UI::new(output).json(&serde_json::json!({
+            "path": path.display().to_string(),
+            "networks": report.networks.len(),
+            "services": report.logs.len(),
+            "failed_sections": failed,
+        }));
+        return Ok(());
+    }
+
+    ui::ui().success(&format!("Report written to {}", path.display()));
+    if failed > 0 {
+        ui::ui().warning(&format!(
+            "{failed} section(s) could not be collected, their errors are in the report"
+        ));
+    }
+    ui::ui().tip("Review the report before attaching it, logs may contain addresses and keys");
+    Ok(())
+}
+
+/// Gather every section of the report
+pub async fn collect_report(generated_at: String, log_lines: usize, blocks: u64) -> Report {
+    let status = sandbox_status().await.map_err(|e| e.to_string());
+    let config = Config::load().map_err(|e| e.to_string());
+
+    let (endpoints, contracts, networks) = match &config {
+        Ok(config) => {
+            let mut networks = Vec::new();
+            for network_id in config.networks.network_ids() {
+                networks.push(network_report(config, network_id, blocks).await);
+            }
+            (Ok(endpoint_matrix(config)), contract_rows(config), networks)
+        }
+        Err(e) => (Err(e.clone()), Vec::new(), Vec::new()),
+    };
+
+    let services: Vec<String> = status
+        .as_ref()
+        .map(|status| {
+            status
+                .services
+                .iter()
+                .map(|service| service.service.clone())
+                .collect()
+        })
+        .unwrap_or_default();
+    let logs = services
+        .into_iter()
+        .map(|service| {
+            let log = service_log(&service, log_lines);
+            (service, log)
+        })
+        .collect();
+
+    Report {
+        generated_at,
+        status,
+        endpoints,
+        contracts,
+        networks,
+        logs,
+    }
+}
+
+async fn network_report(config: &Config, network_id: u64, blocks: u64) -> NetworkReport {
+    let client = OptimizedApiClient::global();
+    let bridges = client
+        .get_bridges(config, network_id)
+        .await
+        .map_err(|e| e.to_string());
+    let claims = client
+        .get_claims(config, network_id)
+        .await
+        .map_err(|e| e.to_string());
+    let events = match network_id_to_chain(network_id) {
+        Ok(chain) => crate::events::events_json(&chain, blocks, None, false)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    NetworkReport {
+        network_id,
+        name: get_network_name(network_id),
+        bridges,
+        claims,
+        events,
+    }
+}
+
+/// Contract addresses of every layer, sorted by layer and name
+fn contract_rows(config: &Config) -> Vec<(String, String, String)> {
+    let mut rows = Vec::new();
+    for (layer, contracts) in config.contracts.layers() {
+        let mut names: Vec<&String> = contracts.keys().collect();
+        names.sort();
+        for name in names {
+            rows.push((layer.to_string(), name.clone(), contracts[name].to_string()));
+        }
+    }
+    rows
+}
+
+/// Last `lines` lines of a service's log
+#[cfg(feature = "docker")]
+fn service_log(service: &str, lines: usize) -> Section<String> {
+    use crate::docker::{create_auto_docker_builder, execute_docker_command_with_output};
+
+    let mut builder = create_auto_docker_builder();
+    builder.add_service(service);
+    let cmd = builder.build_logs_command(false, None, Some(lines));
+    execute_docker_command_with_output(cmd).map_err(|e| e.to_string())
+}
+
+/// Service logs are not collected without the `docker` feature
+#[cfg(not(feature = "docker"))]
+fn service_log(_service: &str, _lines: usize) -> Section<String> {
+    Err("built without the docker feature".to_string())
+}
+
+/// Render the report as markdown
+pub fn render_markdown(report: &Report) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# aggsandbox report\n");
+    let _ = writeln!(out, "- Generated: {}", report.generated_at);
+    let _ = writeln!(out, "- aggsandbox version: {}", env!("CARGO_PKG_VERSION"));
+    let running = report.status.as_ref().map(|status| status.running);
+    let _ = writeln!(
+        out,
+        "- Sandbox running: {}\n",
+        running.map_or("unknown".to_string(), |running| running.to_string())
+    );
+
+    let _ = writeln!(out, "## Services\n");
+    match &report.status {
+        Ok(status) if status.services.is_empty() => {
+            let _ = writeln!(out, "No services are running.\n");
+        }
+        Ok(status) => {
+            out.push_str(&code_block(&render_services(&status.services)));
+            let _ = writeln!(out, "\n### Chain heads\n");
+            let _ = writeln!(out, "| Network | Name | RPC | Head block |");
+            let _ = writeln!(out, "|---|---|---|---|");
+            for chain in &status.chains {
+                let head = chain.head_block.map_or_else(
+                    || chain.error.clone().unwrap_or_else(|| "-".to_string()),
+                    |block| block.to_string(),
+                );
+                let _ = writeln!(
+                    out,
+                    "| {} | {} | {} | {} |",
+                    chain.network_id, chain.name, chain.rpc_url, head
+                );
+            }
+            let _ = writeln!(out);
+        }
+        Err(e) => out.push_str(&failure(e)),
+    }
+
+    let _ = writeln!(out, "## Configuration\n");
+    match &report.endpoints {
+        Ok(endpoints) => out.push_str(&code_block(&render_matrix(endpoints))),
+        Err(e) => out.push_str(&failure(e)),
+    }
+
+    let _ = writeln!(out, "## Contract addresses\n");
+    if report.contracts.is_empty() {
+        let _ = writeln!(out, "No contract addresses configured.\n");
+    } else {
+        let _ = writeln!(out, "| Layer | Contract | Address |");
+        let _ = writeln!(out, "|---|---|---|");
+        for (layer, name, address) in &report.contracts {
+            let _ = writeln!(out, "| {layer} | {name} | {address} |");
+        }
+        let _ = writeln!(out);
+    }
+
+    for network in &report.networks {
+        let _ = writeln!(
+            out,
+            "## Network {} ({})\n",
+            network.network_id, network.name
+        );
+        render_entries(
+            &mut out,
+            "Latest bridges",
+            &network.bridges,
+            "bridges",
+            &[
+                "deposit_count",
+                "bridge_tx_hash",
+                "amount",
+                "destination_network",
+                "leaf_type",
+            ],
+            false,
+        );
+        render_entries(
+            &mut out,
+            "Latest claims",
+            &network.claims,
+            "claims",
+            &["global_index", "status", "claim_tx_hash", "amount"],
+            false,
+        );
+        render_entries(
+            &mut out,
+            "Recent events",
+            &network.events,
+            "events",
+            &["block_number", "transaction_hash", "event", "contract"],
+            true,
+        );
+    }
+
+    let _ = writeln!(out, "## Logs\n");
+    if report.logs.is_empty() {
+        let _ = writeln!(out, "No service logs collected.\n");
+    }
+    for (service, log) in &report.logs {
+        let _ = writeln!(out, "### {service}\n");
+        match log {
+            Ok(log) => out.push_str(&code_block(log)),
+            Err(e) => out.push_str(&failure(e)),
+        }
+    }
+    out
+}
+
+/// Table of the first (or, with `newest_last`, the last) entries of a list
+fn render_entries(
+    out: &mut String,
+    title: &str,
+    section: &Section<Value>,
+    key: &str,
+    columns: &[&str],
+    newest_last: bool,
+) {
+    let _ = writeln!(out, "### {title}\n");
+    let entries = match section {
+        Ok(value) => value[key].as_array().cloned().unwrap_or_default(),
+        Err(e) => {
+            out.push_str(&failure(e));
+            return;
+        }
+    };
+    if entries.is_empty() {
+        let _ = writeln!(out, "None.\n");
+        return;
+    }
+
+    let shown = if newest_last {
+        &entries[entries.len().saturating_sub(RECENT_ENTRIES)..]
+    } else {
+        &entries[..entries.len().min(RECENT_ENTRIES)]
+    };
+    let _ = writeln!(
+        out,
+        "{} in total, showing {}.\n",
+        entries.len(),
+        shown.len()
+    );
+    let _ = writeln!(out, "| {} |", columns.join(" | "));
+    let _ = writeln!(out, "|{}", "---|".repeat(columns.len()));
+    for entry in shown {
+        let cells: Vec<String> = columns.iter().map(|column| cell(&entry[column])).collect();
+        let _ = writeln!(out, "| {} |", cells.join(" | "));
+    }
+    let _ = writeln!(out);
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(text) => text.replace('|', "\\|"),
+        other => other.to_string(),
+    }
+}
+
+fn code_block(text: &str) -> String {
+    format!("```text\n{}\n```\n\n", text.trim_end())
+}
+
+fn failure(error: &str) -> String {
+    format!("_Not collected: {error}_\n\n")
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn report() -> Report {
+        Report {
+            generated_at: "2025-01-01T00:00:00+00:00".to_string(),
+            status: Err("docker is not running".to_string()),
+            endpoints: Ok(Vec::new()),
+            contracts: vec![(
+                "l1".to_string(),
+                "PolygonZkEVMBridge".to_string(),
+                "0x0000000000000000000000000000000000000001".to_string(),
+            )],
+            networks: vec![NetworkReport {
+                network_id: 0,
+                name: "Ethereum-L1".to_string(),
+                bridges: Ok(json!({
+                    "bridges": (0..15)
+                        .map(|i| json!({"deposit_count": i, "amount": "1"}))
+                        .collect::<Vec<_>>()
+                })),
+                claims: Err("connection refused".to_string()),
+                events: Ok(json!({"events": []})),
+            }],
+            logs: vec![("aggkit".to_string(), Ok("line 1\nline 2\n".to_string()))],
+        }
+    }
+
+    #[test]
+    fn test_failed_sections_are_reported_in_place() {
+        let report = report();
+        assert_eq!(report.failed_sections(), 2);
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains("_Not collected: docker is not running_"));
+        assert!(markdown.contains("_Not collected: connection refused_"));
+        assert!(markdown
+            .contains("| l1 | PolygonZkEVMBridge | 0x0000000000000000000000000000000000000001 |"));
+        assert!(markdown.contains("### aggkit\n\n```text\nline 1\nline 2\n```"));
+    }
+
+    #[test]
+    fn test_entries_are_limited() {
+        let markdown = render_markdown(&report());
+        assert!(markdown.contains("15 in total, showing 10."));
+        assert!(markdown.contains("| 9 | - | 1 | - | - |"));
+        assert!(!markdown.contains("| 10 | - |"));
+        assert!(markdown.contains("### Recent events\n\nNone."));
+    }
+}
@@ -1,7 +1,7 @@
 use colored::*;
 
 /// Handle the restart command
-pub async fn handle_restart() {
+pub async fn handle_restart(offline: bool) {
     println!(
         "{}",
         "🔄 Restarting Agglayer sandbox environment..."
@@ -13,7 +13,16 @@ pub async fn handle_restart() {
     super::stop::handle_stop(false);
 
     // Then start in basic local mode
-    super::start::handle_start(true, false, false, false, false).await;
+    super::start::handle_start(
+        true,
+        false,
+        false,
+        1,
+        false,
+        offline,
+        super::readiness::DEFAULT_WAIT_TIMEOUT,
+    )
+    .await;
 
     println!("{}", "✅ Sandbox restarted successfully".green());
 }
@@ -0,0 +1,83 @@
+use crate::commands::bridge::common::{get_network_name, validate_network_id};
+use crate::commands::bridge::get_provider;
+use crate::config::Config;
+use crate::error::{AggSandboxError, EventError, Result};
+use crate::ui::OutputFormat;
+use ethers::providers::RpcError;
+use tracing::info;
+
+/// Handle the rpc command: send a raw JSON-RPC request to a sandbox network
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn handle_rpc(
+    network_id: u64,
+    method: String,
+    params: Vec<String>,
+    output: OutputFormat,
+) -> Result<()> {
+    validate_network_id(network_id, "Network")?;
+    let config = Config::load()?;
+    let provider = get_provider(&config, network_id).await?;
+
+    let params: Vec<serde_json::Value> = params.iter().map(|param| parse_param(param)).collect();
+    info!(
+        network_id,
+        method = %method,
+        params = ?params,
+        "Sending JSON-RPC request"
+    );
+
+    let result: serde_json::Value = provider.request(&method, params).await.map_err(|e| match e
+        .as_error_response()
+    {
+        Some(response) => AggSandboxError::Other(format!(
+            "{method} failed on network {network_id} ({}): {} (code {})",
+            get_network_name(network_id),
+            response.message,
+            response.code
+        )),
+        None => EventError::rpc_connection_failed(&format!(
+            "{method} on network {network_id} ({}): {e}",
+            get_network_name(network_id)
+        ))
+        .into(),
+    })?;
+
+    if output.is_json() {
+        println!("{result}");
+    } else {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&result).unwrap_or_else(|_| result.to_string())
+        );
+    }
+    Ok(())
+}
+
+/// Interpret a positional parameter as JSON, falling back to a plain string
+///
+/// `false`, `12` and `{"to":"0x..."}` are passed as JSON values while
+/// `latest` or `0x10` are passed as strings, so block tags and hex quantities
+/// need no quoting.
+fn parse_param(param: &str) -> serde_json::Value {
+    serde_json::from_str(param).unwrap_or_else(|_| serde_json::Value::String(param.to_string()))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_param() {
+        assert_eq!(parse_param("latest"), json!("latest"));
+        assert_eq!(parse_param("0x10"), json!("0x10"));
+        assert_eq!(parse_param("false"), json!(false));
+        assert_eq!(parse_param("12"), json!(12));
+        assert_eq!(parse_param("\"12\""), json!("12"));
+        assert_eq!(
+            parse_param(r#"{"to":"0x0000000000000000000000000000000000000001"}"#),
+            json!({"to": "0x0000000000000000000000000000000000000001"})
+        );
+    }
+}
@@ -0,0 +1,377 @@
+//! HTTP control server (`aggsandbox serve`)
+//!
+//! Exposes sandbox operations as REST endpoints so web UIs and CI pipelines in
+//! other languages can drive the sandbox without wrapping the binary:
+//!
+//! | Endpoint                   | Operation                                        |
+//! | -------------------------- | ------------------------------------------------ |
+//! | `GET /health`              | Liveness check                                   |
+//! | `GET /status`              | `status --output json`                           |
+//! | `POST /start`, `POST /stop`| `start --detach`, `stop`                         |
+//! | `POST /bridge/<command>`   | `bridge asset`, `claim`, `message`, ...          |
+//! | `GET /bridges`, `/claims`  | Bridge service data of `?network_id=`           |
+//! | `GET /events`              | `events --output json` of `?network_id=&blocks=` |
+//!
+//! Request bodies are JSON objects whose keys are the command's long flags in
+//! snake case (`{"network_id": 0, "auto_wait": true}`), so every flag of the
+//! CLI is available and validated by the same parser. Bridge commands run one
+//! at a time through the regular command handlers and respond with the result
+//! they report. `start` and `stop` run as child processes, since their
+//! handlers exit the process on failure.
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use clap::Parser;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info};
+
+use crate::api_client::OptimizedApiClient;
+use crate::cli::{Cli, Commands};
+use crate::commands::bridge::common::set_dry_run;
+use crate::commands::bridge::idempotency;
+use crate::commands::events::network_id_to_chain;
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui::{self, OutputFormat};
+
+/// Bridge subcommands available under `POST /bridge/<command>`
+const BRIDGE_COMMANDS: &[&str] = &[
+    "asset",
+    "claim",
+    "claim-all",
+    "message",
+    "bridge-message",
+    "bridge-and-call",
+];
+
+/// Command handed to the task running the command handlers
+struct Job {
+    command: Commands,
+    dry_run: bool,
+    reply: oneshot::Sender<Result<Option<Value>>>,
+}
+
+#[derive(Clone)]
+struct ServerState {
+    jobs: mpsc::Sender<Job>,
+}
+
+/// Error response `{"error": "..."}`
+#[derive(Debug)]
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<AggSandboxError> for ApiError {
+    fn from(error: AggSandboxError) -> Self {
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, error.to_string())
+    }
+}
+
+type ApiResult = std::result::Result<Json<Value>, ApiError>;
+
+#[derive(Debug, Deserialize)]
+struct NetworkQuery {
+    #[serde(default)]
+    network_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct EventsQuery {
+    #[serde(default)]
+    network_id: u64,
+    #[serde(default = "default_blocks")]
+    blocks: u64,
+    address: Option<String>,
+    #[serde(default = "default_proxy_resolve")]
+    proxy_resolve: bool,
+}
+
+fn default_blocks() -> u64 {
+    10
+}
+
+fn default_proxy_resolve() -> bool {
+    true
+}
+
+/// Serve the REST API until Ctrl-C
+///
+/// Bridge commands run on this task, one at a time: they share the
+/// transaction nonces of the signing accounts and report their result through
+/// process-wide state.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn handle_serve(
+    host: &str,
+    port: u16,
+    output: OutputFormat,
+    offline: bool,
+) -> Result<()> {
+    let (jobs, mut queue) = mpsc::channel::<Job>(16);
+    let app = router(ServerState { jobs });
+
+    let listener = tokio::net::TcpListener::bind((host, port))
+        .await
+        .map_err(|e| AggSandboxError::Other(format!("Failed to listen on {host}:{port}: {e}")))?;
+    info!(host = host, port = port, "Serving the sandbox API");
+    ui::ui().success(&format!("Serving the sandbox API on http://{host}:{port}"));
+    ui::ui().tip("Press Ctrl-C to stop the server");
+
+    let server = tokio::spawn(async move { axum::serve(listener, app).await });
+
+    loop {
+        tokio::select! {
+            job = queue.recv() => {
+                let Some(job) = job else { break };
+                set_dry_run(job.dry_run);
+                idempotency::take_result();
+                let result = crate::cli::execute(job.command, output, offline)
+                    .await
+                    .map(|()| idempotency::take_result());
+                set_dry_run(false);
+                let _ = job.reply.send(result);
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    server.abort();
+    ui::ui().info("Server stopped");
+    Ok(())
+}
+
+fn router(state: ServerState) -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .route("/status", get(status))
+        .route("/start", post(start))
+        .route("/stop", post(stop))
+        .route("/bridge/:command", post(bridge))
+        .route("/bridges", get(bridges))
+        .route("/claims", get(claims))
+        .route("/events", get(events))
+        .with_state(state)
+}
+
+async fn health() -> Json<Value> {
+    Json(json!({ "ok": true }))
+}
+
+async fn status() -> ApiResult {
+    let status = super::status::status_json().await?;
+    Ok(Json(crate::schema::versioned(status)))
+}
+
+async fn start(body: Option<Json<Value>>) -> ApiResult {
+    let mut args = vec!["start".to_string(), "--detach".to_string()];
+    args.extend(body_to_args(body.as_deref())?);
+    run_child(args).await
+}
+
+async fn stop(body: Option<Json<Value>>) -> ApiResult {
+    let mut args = vec!["stop".to_string()];
+    args.extend(body_to_args(body.as_deref())?);
+    run_child(args).await
+}
+
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+async fn bridge(
+    State(state): State<ServerState>,
+    Path(command): Path<String>,
+    body: Option<Json<Value>>,
+) -> ApiResult {
+    if !BRIDGE_COMMANDS.contains(&command.as_str()) {
+        return Err(ApiError(
+            StatusCode::NOT_FOUND,
+            format!(
+                "Unknown bridge command '{command}' (expected one of: {})",
+                BRIDGE_COMMANDS.join(", ")
+            ),
+        ));
+    }
+
+    let mut args = vec!["bridge".to_string(), command];
+    args.extend(body_to_args(body.as_deref())?);
+    debug!(args = ?args, "Executing bridge request");
+    let cli = parse_cli(&args)?;
+
+    let (reply, response) = oneshot::channel();
+    let job = Job {
+        command: cli.command,
+        dry_run: cli.dry_run,
+        reply,
+    };
+    let unavailable = || {
+        ApiError(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "The server is shutting down".to_string(),
+        )
+    };
+    state.jobs.send(job).await.map_err(|_| unavailable())?;
+    let result = response.await.map_err(|_| unavailable())??;
+
+    Ok(Json(crate::schema::versioned(json!({
+        "ok": true,
+        "dry_run": cli.dry_run,
+        "result": result,
+    }))))
+}
+
+async fn bridges(Query(query): Query<NetworkQuery>) -> ApiResult {
+    let config = Config::load()?;
+    let bridges = OptimizedApiClient::global()
+        .get_bridges(&config, query.network_id)
+        .await?;
+    Ok(Json(crate::schema::versioned(bridges)))
+}
+
+async fn claims(Query(query): Query<NetworkQuery>) -> ApiResult {
+    let config = Config::load()?;
+    let claims = OptimizedApiClient::global()
+        .get_claims(&config, query.network_id)
+        .await?;
+    Ok(Json(crate::schema::versioned(claims)))
+}
+
+async fn events(Query(query): Query<EventsQuery>) -> ApiResult {
+    let chain = network_id_to_chain(query.network_id)?;
+    let events =
+        crate::events::events_json(&chain, query.blocks, query.address, query.proxy_resolve)
+            .await?;
+    Ok(Json(crate::schema::versioned(events)))
+}
+
+/// Run the CLI itself with `args`, validating them first
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+async fn run_child(args: Vec<String>) -> ApiResult {
+    parse_cli(&args)?;
+    let exe = std::env::current_exe()
+        .map_err(|e| AggSandboxError::Other(format!("Failed to locate the CLI binary: {e}")))?;
+    let output = tokio::process::Command::new(exe)
+        .args(&args)
+        .output()
+        .await
+        .map_err(|e| AggSandboxError::Other(format!("Failed to run '{}': {e}", args[0])))?;
+
+    let body = json!({
+        "ok": output.status.success(),
+        "exit_code": output.status.code(),
+        "stdout": String::from_utf8_lossy(&output.stdout),
+        "stderr": String::from_utf8_lossy(&output.stderr),
+    });
+    if output.status.success() {
+        Ok(Json(crate::schema::versioned(body)))
+    } else {
+        Err(ApiError(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("'aggsandbox {}' failed: {body}", args.join(" ")),
+        ))
+    }
+}
+
+fn parse_cli(args: &[String]) -> std::result::Result<Cli, ApiError> {
+    Cli::try_parse_from(std::iter::once("aggsandbox").chain(args.iter().map(String::as_str)))
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.render().to_string()))
+}
+
+/// Command-line flags for a JSON request body
+///
+/// `{"network_id": 1, "auto_wait": true, "deposit_count": null}` becomes
+/// `--network-id=1 --auto-wait`; arrays repeat their flag.
+fn body_to_args(body: Option<&Value>) -> std::result::Result<Vec<String>, ApiError> {
+    let bad_request = |message: String| ApiError(StatusCode::BAD_REQUEST, message);
+    let object = match body {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Object(object)) => object,
+        Some(_) => return Err(bad_request("The request body must be a JSON object".into())),
+    };
+
+    let mut args = Vec::new();
+    for (key, value) in object {
+        let flag = format!("--{}", key.replace('_', "-"));
+        let values = match value {
+            Value::Null | Value::Bool(false) => continue,
+            Value::Bool(true) => {
+                args.push(flag);
+                continue;
+            }
+            Value::Array(values) => values.iter().collect(),
+            value => vec![value],
+        };
+        for value in values {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                Value::Number(value) => value.to_string(),
+                _ => return Err(bad_request(format!("'{key}' must be a string or number"))),
+            };
+            args.push(format!("{flag}={value}"));
+        }
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_to_args() {
+        let body = json!({
+            "network_id": 1,
+            "tx_hash": "0xabc",
+            "auto_wait": true,
+            "verify_proof": false,
+            "deposit_count": null,
+            "network_ids": [0, 1],
+        });
+        let args = body_to_args(Some(&body)).unwrap();
+        assert_eq!(
+            args,
+            [
+                "--auto-wait",
+                "--network-id=1",
+                "--network-ids=0",
+                "--network-ids=1",
+                "--tx-hash=0xabc",
+            ]
+        );
+
+        assert!(body_to_args(None).unwrap().is_empty());
+        assert!(body_to_args(Some(&json!([1, 2]))).is_err());
+        assert!(body_to_args(Some(&json!({ "data": { "x": 1 } }))).is_err());
+    }
+
+    #[test]
+    fn test_bridge_requests_parse_as_cli() {
+        let mut args = vec!["bridge".to_string(), "claim".to_string()];
+        args.extend(
+            body_to_args(Some(&json!({
+                "network_id": 1,
+                "tx_hash": "0xb7118cfb20825861028ede1e9586814fc7ccf81745a325db5df355d382d96b4e",
+                "source_network_id": 0,
+                "auto_wait": true,
+            })))
+            .unwrap(),
+        );
+        let cli = parse_cli(&args).unwrap_or_else(|e| panic!("{}", e.1));
+        assert!(matches!(cli.command, Commands::Bridge { .. }));
+
+        let args = [
+            "bridge".to_string(),
+            "asset".to_string(),
+            "--nope=1".to_string(),
+        ];
+        assert!(parse_cli(&args).is_err());
+    }
+}
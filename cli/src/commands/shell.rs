@@ -0,0 +1,561 @@
+//! Interactive shell (`aggsandbox shell`)
+//!
+//! Lines are parsed with the regular command line definition, so every
+//! command works as it does outside the shell, minus the `aggsandbox` prefix.
+//! The current network and account are added as `--network-id` and
+//! `--account` to commands that accept them when not given explicitly, and are
+//! remembered between sessions together with the command history.
+
+use crate::commands::bridge::common::{
+    get_network_name, is_dry_run, set_dry_run, validation_error,
+};
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui::OutputFormat;
+use crate::validation::Validator;
+use clap::{CommandFactory, Parser};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator as LineValidator;
+use rustyline::{Context, Editor, Helper};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tracing::debug;
+
+/// Commands handled by the shell itself
+const BUILTINS: &[&str] = &[
+    "network",
+    "account",
+    "context",
+    "addresses",
+    "help",
+    "exit",
+    "quit",
+];
+
+/// Context carried between commands and sessions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShellContext {
+    pub network: Option<u64>,
+    pub account: Option<String>,
+}
+
+impl ShellContext {
+    fn load() -> Self {
+        state_file("shell.toml")
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    #[allow(clippy::disallowed_methods)] // Allow tracing macros
+    fn save(&self) {
+        let Some(path) = state_file("shell.toml") else {
+            return;
+        };
+        let result = toml::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|content| std::fs::write(&path, content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            debug!(error = %e, "Failed to save shell context");
+        }
+    }
+
+    fn prompt(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(network) = self.network {
+            parts.push(format!("n{network}"));
+        }
+        if let Some(account) = &self.account {
+            parts.push(account.clone());
+        }
+        if parts.is_empty() {
+            "aggsandbox> ".to_string()
+        } else {
+            format!("aggsandbox[{}]> ", parts.join("|"))
+        }
+    }
+}
+
+/// Named addresses offered by tab completion: accounts and deployed contracts
+#[derive(Debug, Clone, Default)]
+pub struct AddressBook {
+    entries: Vec<(String, String)>,
+}
+
+impl AddressBook {
+    pub fn from_config(config: &Config) -> Self {
+        let mut entries: Vec<(String, String)> = config
+            .accounts
+            .accounts
+            .iter()
+            .enumerate()
+            .map(|(i, address)| (format!("account {i}"), address.as_str().to_string()))
+            .collect();
+        for (layer, contracts) in [
+            ("l1", &config.contracts.l1_contracts),
+            ("l2", &config.contracts.l2_contracts),
+            ("l3", &config.contracts.l3_contracts),
+        ] {
+            let mut named: Vec<_> = contracts
+                .iter()
+                .map(|(name, address)| (format!("{layer} {name}"), address.as_str().to_string()))
+                .collect();
+            named.sort();
+            entries.extend(named);
+        }
+        Self { entries }
+    }
+
+    /// Addresses starting with `prefix`, compared case-insensitively
+    fn matching(&self, prefix: &str) -> Vec<Pair> {
+        let prefix = prefix.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|(_, address)| address.to_lowercase().starts_with(&prefix))
+            .map(|(label, address)| Pair {
+                display: format!("{address} ({label})"),
+                replacement: address.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Tab completion of builtins, subcommands, flags and addresses
+struct ShellHelper {
+    command: clap::Command,
+    addresses: AddressBook,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        let previous: Vec<&str> = line[..start].split_whitespace().collect();
+        Ok((
+            start,
+            complete_word(&self.command, &self.addresses, &previous, &line[start..]),
+        ))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl LineValidator for ShellHelper {}
+
+impl Helper for ShellHelper {}
+
+/// Run the interactive shell until `exit` or Ctrl-D
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn run_shell(output: OutputFormat, offline: bool) -> Result<()> {
+    let addresses = Config::load()
+        .map(|config| AddressBook::from_config(&config))
+        .unwrap_or_default();
+    let mut editor = Editor::<ShellHelper, DefaultHistory>::new()
+        .map_err(|e| validation_error(&format!("Failed to start the shell: {e}")))?;
+    editor.set_helper(Some(ShellHelper {
+        command: crate::cli::Cli::command(),
+        addresses: addresses.clone(),
+    }));
+    let history = state_file("shell_history");
+    if let Some(history) = &history {
+        let _ = editor.load_history(history);
+    }
+
+    let dry_run = is_dry_run();
+    let mut context = ShellContext::load();
+    println!("🐚 aggsandbox shell - type `help` for built-ins, Tab to complete, Ctrl-D to exit");
+    print_context(&context);
+
+    loop {
+        let line = match editor.readline(&context.prompt()) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(validation_error(&format!("Failed to read input: {e}"))),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line);
+
+        let args = match split_args(line) {
+            Ok(args) => args,
+            Err(e) => {
+                eprintln!("❌ {e}");
+                continue;
+            }
+        };
+        match run_builtin(&args, &mut context, &addresses) {
+            Some(Builtin::Exit) => break,
+            Some(Builtin::Handled) => continue,
+            None => {}
+        }
+
+        let args = with_context(strip_program(args), &context, &crate::cli::Cli::command());
+        debug!(args = ?args, "Executing shell command");
+        if let Some(result) = crate::cli::handle_report_flags(
+            std::iter::once("aggsandbox".to_string()).chain(args.iter().cloned()),
+        ) {
+            if let Err(e) = result {
+                crate::cli::print_error(&e);
+            }
+            continue;
+        }
+        match crate::cli::Cli::try_parse_from(std::iter::once("aggsandbox".to_string()).chain(args))
+        {
+            Ok(cli) => {
+                let output = if cli.output.is_json() {
+                    cli.output
+                } else {
+                    output
+                };
+                set_dry_run(dry_run || cli.dry_run);
+                if let Err(e) =
+                    crate::cli::execute(cli.command, output, offline || cli.offline).await
+                {
+                    crate::cli::print_error(&e);
+                }
+            }
+            Err(e) => {
+                let _ = e.print();
+            }
+        }
+    }
+
+    if let Some(history) = &history {
+        let _ = editor.save_history(history);
+    }
+    Ok(())
+}
+
+enum Builtin {
+    Handled,
+    Exit,
+}
+
+/// Handle shell built-ins, `None` for lines that are regular commands
+fn run_builtin(
+    args: &[String],
+    context: &mut ShellContext,
+    addresses: &AddressBook,
+) -> Option<Builtin> {
+    let (command, rest) = args.split_first()?;
+    match (command.as_str(), rest) {
+        ("exit" | "quit", _) => return Some(Builtin::Exit),
+        ("help", []) => print_help(),
+        ("context", []) => print_context(context),
+        ("addresses", []) => {
+            for pair in addresses.matching("") {
+                println!("  {}", pair.display);
+            }
+        }
+        ("network", []) | ("account", []) => print_context(context),
+        ("network", [value]) if value == "none" => {
+            context.network = None;
+            context.save();
+        }
+        ("network", [value]) => match Validator::resolve_network(value) {
+            Ok(network) => {
+                context.network = Some(network);
+                context.save();
+                println!("🌐 Network {network} ({})", get_network_name(network));
+            }
+            Err(e) => eprintln!("❌ {e}"),
+        },
+        ("account", [value]) if value == "none" => {
+            context.account = None;
+            context.save();
+        }
+        ("account", [value]) => {
+            context.account = Some(value.clone());
+            context.save();
+            println!("🔑 Bridge commands now sign with account '{value}'");
+        }
+        _ => return None,
+    }
+    Some(Builtin::Handled)
+}
+
+fn print_help() {
+    println!("Type any aggsandbox command without the `aggsandbox` prefix, e.g. `show bridges`.");
+    println!();
+    println!("Built-ins:");
+    println!("  network <id|alias|none>  Set the network added as --network-id");
+    println!("  account <name|none>      Set the account added as --account to bridge commands");
+    println!("  context                  Show the current network and account");
+    println!("  addresses                List accounts and deployed contracts");
+    println!("  help                     Show this help");
+    println!("  exit, quit               Leave the shell (or Ctrl-D)");
+}
+
+fn print_context(context: &ShellContext) {
+    let network = context.network.map_or_else(
+        || "none".to_string(),
+        |network| format!("{network} ({})", get_network_name(network)),
+    );
+    let account = context.account.as_deref().unwrap_or("default");
+    println!("📍 Network: {network} | 🔑 Account: {account}");
+}
+
+/// Completion candidates for `partial`, given the words typed before it
+fn complete_word(
+    root: &clap::Command,
+    addresses: &AddressBook,
+    previous: &[&str],
+    partial: &str,
+) -> Vec<Pair> {
+    if partial.starts_with("0x") {
+        return addresses.matching(partial);
+    }
+
+    let mut command = root;
+    for word in previous {
+        if let Some(subcommand) = command.find_subcommand(word) {
+            command = subcommand;
+        }
+    }
+
+    let mut words: Vec<String> = if partial.starts_with('-') {
+        command
+            .get_arguments()
+            .filter_map(|arg| arg.get_long())
+            .map(|long| format!("--{long}"))
+            .collect()
+    } else {
+        command
+            .get_subcommands()
+            .map(|subcommand| subcommand.get_name().to_string())
+            .filter(|name| name != "help")
+            .collect()
+    };
+    if previous.is_empty() {
+        words.extend(BUILTINS.iter().map(|builtin| builtin.to_string()));
+    }
+    words.sort();
+
+    words
+        .into_iter()
+        .filter(|word| word.starts_with(partial))
+        .map(|word| Pair {
+            display: word.clone(),
+            replacement: word,
+        })
+        .collect()
+}
+
+/// Add the current network and account to a command that accepts them
+///
+/// The flags are inserted right after the subcommand path so they are parsed
+/// by the innermost subcommand and never end up in trailing arguments.
+fn with_context(args: Vec<String>, context: &ShellContext, root: &clap::Command) -> Vec<String> {
+    let mut command = root;
+    let mut path_len = 0;
+    for arg in &args {
+        match command.find_subcommand(arg) {
+            Some(subcommand) => {
+                command = subcommand;
+                path_len += 1;
+            }
+            None => break,
+        }
+    }
+
+    let given = |flags: &[&str]| {
+        args.iter().any(|arg| {
+            flags
+                .iter()
+                .any(|flag| arg == flag || arg.starts_with(&format!("{flag}=")))
+        })
+    };
+    let accepts_network = command
+        .get_arguments()
+        .any(|arg| arg.get_long() == Some("network-id"));
+
+    let mut injected = Vec::new();
+    if let Some(network) = context.network {
+        if accepts_network && !given(&["-n", "--network-id", "--network"]) {
+            injected.extend(["--network-id".to_string(), network.to_string()]);
+        }
+    }
+    if let Some(account) = &context.account {
+        let is_bridge = args.first().is_some_and(|arg| arg == "bridge");
+        if is_bridge && path_len > 1 && !given(&["--account", "--private-key"]) {
+            injected.extend(["--account".to_string(), account.clone()]);
+        }
+    }
+
+    let mut args = args;
+    args.splice(path_len..path_len, injected);
+    args
+}
+
+/// Allow lines pasted from scripts that start with `aggsandbox`
+fn strip_program(mut args: Vec<String>) -> Vec<String> {
+    if args.first().is_some_and(|arg| arg == "aggsandbox") {
+        args.remove(0);
+    }
+    args
+}
+
+/// Split a line into words, honouring single and double quotes
+fn split_args(line: &str) -> std::result::Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if let Some(open) = quote {
+        return Err(format!("Unterminated {open} quote"));
+    }
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// File under `~/.aggsandbox`, creating the directory when needed
+fn state_file(name: &str) -> Option<PathBuf> {
+    let dir = PathBuf::from(std::env::var_os("HOME")?).join(".aggsandbox");
+    std::fs::create_dir_all(&dir).ok()?;
+    Some(dir.join(name))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        split_args(line).unwrap()
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(
+            args(r#"rpc -n 1 eth_call '{"to": "0x1"}' latest"#),
+            vec!["rpc", "-n", "1", "eth_call", r#"{"to": "0x1"}"#, "latest"]
+        );
+        assert_eq!(args(r#"show  bridges "" "#), vec!["show", "bridges", ""]);
+        assert!(split_args("bridge 'asset").is_err());
+    }
+
+    #[test]
+    fn test_context_is_injected_after_subcommands() {
+        let context = ShellContext {
+            network: Some(1),
+            account: Some("deployer".to_string()),
+        };
+        let root = crate::cli::Cli::command();
+
+        assert_eq!(
+            with_context(args("show bridges"), &context, &root),
+            args("show bridges --network-id 1")
+        );
+        assert_eq!(
+            with_context(args("rpc eth_blockNumber"), &context, &root),
+            args("rpc --network-id 1 eth_blockNumber")
+        );
+        assert_eq!(
+            with_context(args("bridge claim-all"), &context, &root),
+            args("bridge claim-all --network-id 1 --account deployer")
+        );
+        assert_eq!(
+            with_context(args("show bridges -n 0"), &context, &root),
+            args("show bridges -n 0")
+        );
+        assert_eq!(
+            with_context(args("status"), &context, &root),
+            args("status")
+        );
+    }
+
+    #[test]
+    fn test_complete_subcommands_and_flags() {
+        let root = crate::cli::Cli::command();
+        let book = AddressBook::default();
+
+        let top: Vec<String> = complete_word(&root, &book, &[], "sh")
+            .into_iter()
+            .map(|pair| pair.replacement)
+            .collect();
+        assert_eq!(top, vec!["shell", "show"]);
+
+        let flags: Vec<String> = complete_word(&root, &book, &["show", "bridges"], "--net")
+            .into_iter()
+            .map(|pair| pair.replacement)
+            .collect();
+        assert_eq!(flags, vec!["--network-id"]);
+    }
+
+    #[test]
+    fn test_complete_addresses() {
+        let book = AddressBook {
+            entries: vec![
+                (
+                    "account 0".to_string(),
+                    "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266".to_string(),
+                ),
+                (
+                    "l1 Bridge".to_string(),
+                    "0x70997970C51812dc3A010C7d01b50e0d17dc79C8".to_string(),
+                ),
+            ],
+        };
+
+        let matches = complete_word(&crate::cli::Cli::command(), &book, &["rpc"], "0xF3");
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0].replacement,
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert!(matches[0].display.contains("account 0"));
+    }
+
+    #[test]
+    fn test_prompt_shows_context() {
+        assert_eq!(ShellContext::default().prompt(), "aggsandbox> ");
+        let context = ShellContext {
+            network: Some(2),
+            account: Some("ops".to_string()),
+        };
+        assert_eq!(context.prompt(), "aggsandbox[n2|ops]> ");
+    }
+}
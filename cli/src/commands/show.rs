@@ -1,7 +1,36 @@
 use crate::api;
+use crate::api_client::{OptimizedApiClient, Page};
+use crate::commands::balances::{render_table, BalanceQuery};
+use crate::commands::bridge::certificates::{
+    certificate_rows, fetch_certificates, summary_rows as certificate_summary_rows,
+    CertificateStatus,
+};
+use crate::commands::bridge::common::{
+    get_network_name, validate_address, validate_network_id, validation_error, MetadataDiagnostics,
+    LARGE_METADATA_WARN_BYTES,
+};
+use crate::commands::bridge::exit_roots::{exit_root_rows, fetch_all_exit_roots};
+use crate::commands::bridge::gas_report::{self, GasReportStore};
+use crate::commands::bridge::known_issues::{self, Endpoint};
+use crate::commands::bridge::l1_info_tree;
+use crate::commands::bridge::local_index::{self, local_index, DataSource};
+use crate::commands::bridge::rollups::{diagnose, fetch_rollups, rollup_rows};
+use crate::commands::bridge::utilities::{compute_global_index, ComputeGlobalIndexArgs};
+use crate::commands::bridge::watch::{
+    claim_deposit_key, deposit_states, send_notification, DepositKey, DepositState, DepositStates,
+};
+use crate::commands::endpoints::{endpoint_matrix, render_matrix};
+use crate::commands::history::{fetch_history, render_table as render_history};
+use crate::concurrency::{dedup_networks, query_networks};
 use crate::config::Config;
 use crate::error::Result;
+use crate::types::BridgeDeposit;
 use crate::ui::{OutputFormat, UI};
+use crate::validation::{parse_int, parse_network_id};
+use ethers::types::Address;
+use std::collections::HashSet;
+use std::time::Duration;
+use tracing::debug;
 
 /// Bridge and blockchain data subcommands
 #[derive(Debug, clap::Subcommand)]
@@ -20,19 +49,150 @@ Network IDs:
 Examples:
   aggsandbox show bridges                    # Show L1 bridges
   aggsandbox show bridges --network-id 1    # Show first L2 bridges
-  aggsandbox show bridges --json             # Raw JSON output for scripting")]
+  aggsandbox show bridges --json             # Raw JSON output for scripting
+  aggsandbox show bridges --watch --bell     # Print new deposits as they appear
+  aggsandbox show bridges --source local     # Read deposits from the local index
+  aggsandbox show bridges -n 0,1,2           # Query several networks at once
+  aggsandbox show bridges --page 2 --page-size 50
+  aggsandbox show bridges --all-pages --json # Every deposit, one JSON object per line
+  aggsandbox show bridges -n 1 --destination-address 0x7099... --unclaimed
+  aggsandbox show bridges --token 0x5FbD... --from-block 120
+
+Each bridge includes metadata_diagnostics with the metadata size in bytes and
+the estimated extra gas it adds to the claim on the destination network.
+
+With --watch the bridges endpoint is polled until Ctrl-C and only bridges that
+were not seen before are printed, one JSON object per line with --json.
+
+With --source local the deposits are read from the local bridge index, which
+is synced from the bridge contract events first (see `aggsandbox index`).
+
+Several networks given as a comma-separated list are queried concurrently and
+printed grouped by network.
+
+The bridge service returns deposits in pages. --page fetches one page of
+--page-size deposits; --all-pages fetches every page of a single network and
+prints each as it arrives.
+
+--destination-address, --depositor, --token, --claimed/--unclaimed and
+--from-block keep only the matching deposits. --token matches the origin token
+(0x0000000000000000000000000000000000000000 for ETH), and --claimed/--unclaimed
+look the deposits up in the claims of the other networks.")]
     Bridges {
-        /// Network ID to query (0=L1, 1=first L2, etc.)
+        /// Network IDs to query (0=L1, 1=first L2, etc.)
         #[arg(
             short,
-            long,
+            long = "network-id",
+            alias = "network",
+            value_parser = parse_network_id,
+            value_delimiter = ',',
             default_value = "0",
-            help = "Network ID (0=L1 Ethereum, 1=first L2, 2=second L2, etc.)"
+            help = "Network ID, or several separated by commas (0=L1 Ethereum, 1=first L2, 2=second L2, etc.)"
         )]
-        network_id: u64,
+        network_ids: Vec<u64>,
         /// Output raw JSON without formatting (for scripting)
         #[arg(long, help = "Output raw JSON without decorative formatting")]
         json: bool,
+        /// Keep polling and print new bridges as they appear
+        #[arg(long, help = "Poll the bridge service and print only new bridges")]
+        watch: bool,
+        /// Polling interval in seconds
+        #[arg(
+            long,
+            default_value = "5",
+            requires = "watch",
+            help = "Seconds between polls with --watch"
+        )]
+        interval: u64,
+        /// Ring the terminal bell for new bridges
+        #[arg(
+            long,
+            requires = "watch",
+            help = "Ring the terminal bell for every new bridge"
+        )]
+        bell: bool,
+        /// Show desktop notifications for new bridges
+        #[arg(
+            long,
+            requires = "watch",
+            help = "Show a desktop notification for every new bridge"
+        )]
+        notify: bool,
+        /// Where the bridges are read from
+        #[arg(
+            long,
+            value_enum,
+            default_value = "api",
+            conflicts_with = "watch",
+            help = "Read bridges from the bridge service (api) or the local index (local)"
+        )]
+        source: DataSource,
+        /// Page of bridges to fetch
+        #[arg(
+            long,
+            value_parser = clap::value_parser!(u64).range(1..),
+            conflicts_with_all = ["watch", "all_pages"],
+            help = "Fetch only this page of bridges, numbered from 1"
+        )]
+        page: Option<u64>,
+        /// Entries per page
+        #[arg(
+            long,
+            default_value = "100",
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Bridges per page with --page or --all-pages"
+        )]
+        page_size: u64,
+        /// Fetch every page
+        #[arg(
+            long,
+            alias = "all",
+            conflicts_with = "watch",
+            help = "Fetch every page of bridges, printing each page as it arrives"
+        )]
+        all_pages: bool,
+        /// Only deposits to this address
+        #[arg(
+            long,
+            conflicts_with = "watch",
+            help = "Only show bridges to this destination address"
+        )]
+        destination_address: Option<String>,
+        /// Only deposits sent by this address
+        #[arg(
+            long,
+            conflicts_with = "watch",
+            help = "Only show bridges sent by this address (bridge service only)"
+        )]
+        depositor: Option<String>,
+        /// Only deposits of this origin token
+        #[arg(
+            long,
+            conflicts_with = "watch",
+            help = "Only show bridges of this origin token address (0x0000...0000 for ETH)"
+        )]
+        token: Option<String>,
+        /// Only claimed deposits
+        #[arg(
+            long,
+            conflicts_with_all = ["watch", "unclaimed"],
+            help = "Only show bridges that were claimed on their destination network"
+        )]
+        claimed: bool,
+        /// Only deposits not claimed yet
+        #[arg(
+            long,
+            conflicts_with = "watch",
+            help = "Only show bridges that were not claimed yet"
+        )]
+        unclaimed: bool,
+        /// Only deposits from this block on
+        #[arg(
+            long,
+            conflicts_with = "watch",
+            help = "Only show bridges made in this block or later"
+        )]
+        from_block: Option<u64>,
     },
     /// 📋 Show pending claims for a network
     #[command(
@@ -56,17 +216,28 @@ Examples:
   aggsandbox show claims --status pending                   # Show only pending claims
   aggsandbox show claims --claim-type asset                 # Show only asset claims
   aggsandbox show claims --address 0xdef...                 # Filter by destination address
-  aggsandbox show claims --json                             # Raw JSON output for scripting"
+  aggsandbox show claims --pending                          # Include unclaimed deposits
+  aggsandbox show claims --pending --status claimable       # Deposits ready to be claimed
+  aggsandbox show claims --source local                     # Read claims from the local index
+  aggsandbox show claims -n 1,2                             # Query several networks at once
+  aggsandbox show claims --page 3 --page-size 50            # One page of claims
+  aggsandbox show claims --all-pages --status completed     # Every claim, page by page
+  aggsandbox show claims --json                             # Raw JSON output for scripting
+
+--all keeps meaning --pending; every page of claims is fetched with --all-pages."
     )]
     Claims {
-        /// Network ID to query for claims
+        /// Network IDs to query for claims
         #[arg(
             short,
-            long,
+            long = "network-id",
+            alias = "network",
+            value_parser = parse_network_id,
+            value_delimiter = ',',
             default_value = "1",
-            help = "Network ID to query for claims"
+            help = "Network ID to query for claims, or several separated by commas"
         )]
-        network_id: u64,
+        network_ids: Vec<u64>,
         /// Filter by bridge transaction hash
         #[arg(long, help = "Filter claims by bridge transaction hash")]
         bridge_tx_hash: Option<String>,
@@ -76,8 +247,11 @@ Examples:
             help = "Filter claims by claim transaction hash (empty for pending claims)"
         )]
         claim_tx_hash: Option<String>,
-        /// Filter by claim status (pending, completed)
-        #[arg(long, help = "Filter claims by status (pending, completed)")]
+        /// Filter by claim status (pending, claimable, completed)
+        #[arg(
+            long,
+            help = "Filter claims by status (pending, completed; claimable with --pending)"
+        )]
         status: Option<String>,
         /// Filter by claim type (asset, message)
         #[arg(long, help = "Filter claims by type (asset, message)")]
@@ -85,6 +259,46 @@ Examples:
         /// Filter by destination address
         #[arg(long, help = "Filter claims by destination address")]
         address: Option<String>,
+        /// Include deposits that have not been claimed yet
+        #[arg(
+            long,
+            alias = "all",
+            conflicts_with = "address",
+            help = "Merge unclaimed deposits from the source networks, labelled pending, claimable or completed"
+        )]
+        pending: bool,
+        /// Where the claims are read from
+        #[arg(
+            long,
+            value_enum,
+            default_value = "api",
+            conflicts_with = "pending",
+            help = "Read claims from the bridge service (api) or the local index (local)"
+        )]
+        source: DataSource,
+        /// Page of claims to fetch
+        #[arg(
+            long,
+            value_parser = clap::value_parser!(u64).range(1..),
+            conflicts_with_all = ["pending", "all_pages"],
+            help = "Fetch only this page of claims, numbered from 1"
+        )]
+        page: Option<u64>,
+        /// Entries per page
+        #[arg(
+            long,
+            default_value = "100",
+            value_parser = clap::value_parser!(u64).range(1..),
+            help = "Claims per page with --page or --all-pages"
+        )]
+        page_size: u64,
+        /// Fetch every page
+        #[arg(
+            long,
+            conflicts_with = "pending",
+            help = "Fetch every page of claims, printing each page as it arrives"
+        )]
+        all_pages: bool,
         /// Output raw JSON without formatting (for scripting)
         #[arg(long, help = "Output raw JSON without decorative formatting")]
         json: bool,
@@ -111,6 +325,8 @@ Examples:
         #[arg(
             short,
             long,
+            alias = "network",
+            value_parser = parse_network_id,
             default_value = "0",
             help = "Target network ID for claiming"
         )]
@@ -119,6 +335,7 @@ Examples:
         #[arg(
             short,
             long,
+            value_parser = parse_int::<u64>,
             default_value = "0",
             help = "Leaf index in the global exit tree"
         )]
@@ -127,6 +344,7 @@ Examples:
         #[arg(
             short,
             long,
+            value_parser = parse_int::<u64>,
             default_value = "1",
             help = "Number of deposits when exit was created"
         )]
@@ -154,12 +372,13 @@ Examples:
     )]
     L1InfoTreeIndex {
         /// Network ID to query
-        #[arg(short, long, default_value = "0", help = "Network ID to query")]
+        #[arg(short, long, alias = "network", value_parser = parse_network_id, default_value = "0", help = "Network ID to query")]
         network_id: u64,
         /// Deposit count to get L1 info tree index for
         #[arg(
             short,
             long,
+            value_parser = parse_int::<u64>,
             default_value = "0",
             help = "Deposit count to lookup in L1 info tree"
         )]
@@ -168,58 +387,442 @@ Examples:
         #[arg(long, help = "Output raw JSON without decorative formatting")]
         json: bool,
     },
+    /// 🌿 Show the leaves of the L1 info tree
+    #[command(
+        long_about = "List the leaves of the L1 info tree indexed by the bridge service.
+
+Every global exit root update on L1 appends a leaf with the new global exit
+root, the hash of the previous block and the block timestamp. Claim proofs are
+rooted in one of these leaves. Without --leaf-index the most recent leaves are
+listed, newest first; leaves the service has not indexed yet are reported as
+missing.
+
+With --network-id of an L2 the leaves injected on that L2 are shown instead, a
+leaf whose global exit root was never injected there is reported as missing.
+
+Examples:
+  aggsandbox show l1-info-tree                     # Last 10 leaves on L1
+  aggsandbox show l1-info-tree --count 25          # Last 25 leaves
+  aggsandbox show l1-info-tree --leaf-index 7      # A single leaf
+  aggsandbox show l1-info-tree -n 1 --leaf-index 7 # Leaf 7 as injected on L2
+  aggsandbox show l1-info-tree --json              # Raw JSON output for scripting"
+    )]
+    L1InfoTree {
+        /// Network whose bridge service is queried
+        #[arg(short, long, alias = "network", value_parser = parse_network_id, default_value = "0", help = "Network whose bridge service is queried")]
+        network_id: u64,
+        /// Only show this leaf
+        #[arg(long, value_parser = parse_int::<u32>, help = "Only show the leaf at this L1 info tree index")]
+        leaf_index: Option<u32>,
+        /// Number of recent leaves to list
+        #[arg(
+            long,
+            value_parser = parse_int::<u32>,
+            default_value = "10",
+            conflicts_with = "leaf_index",
+            help = "Number of most recent leaves to list"
+        )]
+        count: u32,
+        /// Output raw JSON without formatting (for scripting)
+        #[arg(long, help = "Output raw JSON without decorative formatting")]
+        json: bool,
+    },
+    /// 🌐 Show the exit roots of L1 and the L2s side by side
+    #[command(long_about = "Read the global exit root managers of L1 and every L2.
+
+For L1 the current mainnet, rollup and global exit roots are shown; for the L2s
+their rollup exit root and the last global exit root injected from L1. Each
+network also shows its last exit root update, and every L2 whether the current
+L1 global exit root has reached it. A deposit can only be claimed on an L2 once
+a global exit root covering it has been injected there.
+
+Examples:
+  aggsandbox show exit-roots                 # L1 and every L2
+  aggsandbox show exit-roots --network-id 1  # L1 and the first L2
+  aggsandbox show exit-roots --json          # Raw JSON output for scripting")]
+    ExitRoots {
+        /// Only show L1 and this network
+        #[arg(short, long, alias = "network", value_parser = parse_network_id, help = "Only show L1 and this network")]
+        network_id: Option<u64>,
+        /// Output raw JSON without formatting (for scripting)
+        #[arg(long, help = "Output raw JSON without decorative formatting")]
+        json: bool,
+    },
+    /// 🧮 Show the rollups registered in the L1 rollup manager
+    #[command(
+        long_about = "Read the PolygonRollupManager on L1 and list every registered rollup.
+
+Each rollup shows its chain ID, consensus contract, verifier, fork, last
+verified batch and the local exit root settled by that verification, which is
+its leaf in the rollup exit tree. For rollups that are configured networks the
+current exit root of their bridge is read too: while it differs from the
+verified one, newer deposits are not part of the rollup exit root and cannot
+be claimed on other networks yet.
+
+The rollup manager's rollup exit root is also compared with the one the L1
+global exit root manager holds, and findings explaining missing roots are
+printed below the tables.
+
+Examples:
+  aggsandbox show rollups          # Every registered rollup
+  aggsandbox show rollups --json   # Raw JSON output for scripting"
+    )]
+    Rollups {
+        /// Output raw JSON without formatting (for scripting)
+        #[arg(long, help = "Output raw JSON without decorative formatting")]
+        json: bool,
+    },
+    /// 📜 Show the certificates the L2s submitted to the agglayer
+    #[command(
+        long_about = "Query the agglayer for the certificates submitted by the L2s.
+
+An L2 settles its state on L1 through certificates carrying the new local exit
+root of its bridge. For each network the latest known, pending and settled
+certificates are shown with their height, status (pending, proven, candidate,
+in error or settled) and local exit roots. The last settled local exit root is
+compared with the bridge's current one: while they differ, newer deposits are
+not settled on L1 and cannot be claimed on other networks yet.
+
+The agglayer is reached at AGGLAYER_URL (default http://localhost:4444).
+
+Examples:
+  aggsandbox show certificates                 # Every L2
+  aggsandbox show certificates --network-id 1  # The first L2
+  aggsandbox show certificates --json          # Raw JSON output for scripting"
+    )]
+    Certificates {
+        /// Only show this network
+        #[arg(short, long, alias = "network", value_parser = parse_network_id, help = "Only show this network")]
+        network_id: Option<u64>,
+        /// Output raw JSON without formatting (for scripting)
+        #[arg(long, help = "Output raw JSON without decorative formatting")]
+        json: bool,
+    },
+    /// 💰 Show ETH and token balances of the test accounts on every network
+    #[command(
+        long_about = "Print the ETH and sandbox token balances of the test accounts in one table.
+
+Tokens are AggERC20 and the tokens deployed with `aggsandbox token deploy`,
+shown as one column per origin token named SYMBOL@NETWORK (e.g. AGG@0). On
+the other networks the column holds the balance of the wrapped token, looked
+up with getTokenWrappedAddress, and '-' while the token was never bridged there.
+
+Examples:
+  aggsandbox show balances                          # All test accounts, all networks
+  aggsandbox show balances --address 0x7099...      # One address
+  aggsandbox show balances --token AGG -n 0,1       # AggERC20 columns on L1 and L2
+  aggsandbox show balances --token ETH --json       # Raw JSON output for scripting"
+    )]
+    Balances {
+        /// Networks to query (default: every network)
+        #[arg(
+            short,
+            long = "network-id",
+            alias = "network",
+            value_parser = parse_network_id,
+            value_delimiter = ',',
+            help = "Network ID, or several separated by commas (default: every network)"
+        )]
+        network_ids: Vec<u64>,
+        /// Addresses to show instead of the test accounts
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Address(es) to show (default: the ten test accounts)"
+        )]
+        address: Vec<String>,
+        /// Assets to show
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Assets to show: ETH, a symbol (AGG), a column (AGG@0) or an origin token address"
+        )]
+        token: Vec<String>,
+        /// Output raw JSON without formatting (for scripting)
+        #[arg(long, help = "Output raw JSON without decorative formatting")]
+        json: bool,
+    },
+    /// 📜 Show every deposit and claim involving an address
+    #[command(
+        long_about = "Print the bridging ledger of an address across every network.
+
+Deposits sent by or to the address and claims paying or sent by it are read
+from the bridges and claims of each network and listed oldest first, with
+their route (source → destination), token, amount, status and block time.
+Deposits are pending until a claim of them shows up on any network.
+
+Records of the local index carry no block time, so it is read from the block
+over RPC.
+
+Examples:
+  aggsandbox show history --address 0x7099...            # Every network
+  aggsandbox show history --address 0x7099... -n 0,1     # L1 and the first L2
+  aggsandbox show history --address 0x7099... --source local
+  aggsandbox show history --address 0x7099... --json     # Raw JSON output for scripting"
+    )]
+    History {
+        /// Address whose deposits and claims are shown
+        #[arg(long, help = "Address to show the deposits and claims of")]
+        address: String,
+        /// Networks to query (default: every network)
+        #[arg(
+            short,
+            long = "network-id",
+            alias = "network",
+            value_parser = parse_network_id,
+            value_delimiter = ',',
+            help = "Network ID, or several separated by commas (default: every network)"
+        )]
+        network_ids: Vec<u64>,
+        /// Where the bridges and claims are read from
+        #[arg(
+            long,
+            value_enum,
+            default_value = "api",
+            help = "Read bridges and claims from the bridge service (api) or the local index (local)"
+        )]
+        source: DataSource,
+        /// Output raw JSON without formatting (for scripting)
+        #[arg(long, help = "Output raw JSON without decorative formatting")]
+        json: bool,
+    },
+    /// ⛽ Show the gas used by bridge and claim operations of this session
+    #[command(
+        long_about = "Aggregate the gas of the bridge and claim commands run so far.
+
+Every bridge and claim command that waits for its transactions records their
+gas used and effective gas price (approvals included) when it finishes. This
+report groups the recorded operations by type and shows the number of runs and
+transactions, the average, minimum and maximum gas per run, the average gas
+price weighted by gas, and the total cost.
+
+Operations are recorded in .aggsandbox/gas_report.json of the working directory
+and cleared by 'aggsandbox stop' or --reset. Commands run with --no-wait or
+--dry-run mine nothing the CLI sees and are not recorded.
+
+Examples:
+  aggsandbox show gas-report                 # Table per operation type
+  aggsandbox show gas-report --json          # Raw JSON output for scripting
+  aggsandbox show gas-report --reset         # Start a new report"
+    )]
+    GasReport {
+        /// Clear the recorded operations
+        #[arg(long, help = "Clear the recorded operations after showing them")]
+        reset: bool,
+        /// Output raw JSON without formatting (for scripting)
+        #[arg(long, help = "Output raw JSON without decorative formatting")]
+        json: bool,
+    },
+    /// 🗺️ Show the RPC, WebSocket, contract and bridge service endpoints of every network
+    #[command(
+        long_about = "Print one matrix of the endpoints of every configured network.
+
+Each network shows its RPC URL, WebSocket URL, chain ID, network ID, bridge
+and bridge extension contracts, and the URL of the aggkit bridge service that
+indexes it. The values are read from the loaded configuration (aggsandbox.toml,
+.env and the environment), the same the other commands use; nothing is queried.
+The WebSocket URL is the RPC URL with a ws:// or wss:// scheme, which Anvil
+serves on the same port.
+
+Examples:
+  aggsandbox show config-endpoints          # Matrix of every network
+  aggsandbox show config-endpoints --json   # Raw JSON output for scripting"
+    )]
+    ConfigEndpoints {
+        /// Output raw JSON without formatting (for scripting)
+        #[arg(long, help = "Output raw JSON without decorative formatting")]
+        json: bool,
+    },
 }
 
 /// Handle the show command
-pub async fn handle_show(subcommand: ShowCommands) -> Result<()> {
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn handle_show(subcommand: ShowCommands, output: OutputFormat) -> Result<()> {
     let config = Config::load()?;
 
     match subcommand {
-        ShowCommands::Bridges { network_id, json } => {
+        ShowCommands::Bridges {
+            network_ids,
+            json,
+            watch,
+            interval,
+            bell,
+            notify,
+            source,
+            page,
+            page_size,
+            all_pages,
+            destination_address,
+            depositor,
+            token,
+            claimed,
+            unclaimed,
+            from_block,
+        } => {
+            let json = json || output.is_json();
             let ui = UI::new(if json {
                 OutputFormat::Json
             } else {
                 OutputFormat::Human
             });
-            let response = api::get_bridges(&config, network_id, json).await?;
+            let page = paging(source, page, page_size, all_pages)?;
+            let filter = BridgeFilter {
+                destination_address: destination_address
+                    .as_deref()
+                    .map(|address| validate_address(address, "Destination address"))
+                    .transpose()?,
+                depositor: depositor
+                    .as_deref()
+                    .map(|address| validate_address(address, "Depositor"))
+                    .transpose()?,
+                token: token
+                    .as_deref()
+                    .map(|address| validate_address(address, "Token"))
+                    .transpose()?,
+                claimed: (claimed || unclaimed).then_some(claimed),
+                from_block,
+            };
+            if filter.depositor.is_some() && source == DataSource::Local {
+                return Err(validation_error(
+                    "--depositor needs the sender the bridge service lists, not the local index",
+                ));
+            }
+            let networks = dedup_networks(&network_ids);
+            if let [network_id] = networks[..] {
+                if all_pages {
+                    let claimed = filter.claimed_deposits(&config, network_id).await?;
+                    return stream_pages(&config, network_id, "bridges", page_size, &ui, |page| {
+                        known_issues::check(Endpoint::Bridges, network_id, page);
+                        let page = filter.apply(
+                            &annotate_metadata_diagnostics(page),
+                            network_id,
+                            &claimed,
+                        );
+                        if !json {
+                            warn_large_metadata(&page);
+                        }
+                        page
+                    })
+                    .await;
+                }
+                if watch {
+                    let options = WatchBridgesOptions {
+                        interval: Duration::from_secs(interval.max(1)),
+                        bell,
+                        notify,
+                    };
+                    return watch_bridges(&config, network_id, &ui, options).await;
+                }
+                let data = bridges_data(&config, network_id, source, page, json).await?;
+                let claimed = filter.claimed_deposits(&config, network_id).await?;
+                let data = filter.apply(&data, network_id, &claimed);
+                if json {
+                    ui.json(&data);
+                } else {
+                    let display_data = filter_display_metadata(&data);
+                    ui.data("🌉 Bridge Information", &display_data);
+                    warn_large_metadata(&data);
+                }
+                return Ok(());
+            }
 
-            if json {
-                ui.json(&response.data);
-            } else {
-                let display_data = filter_display_metadata(&response.data);
-                ui.data("🌉 Bridge Information", &display_data);
+            if watch {
+                return Err(validation_error("--watch takes a single --network-id"));
+            }
+            if all_pages {
+                return Err(validation_error("--all-pages takes a single --network-id"));
             }
+            // Several networks: progress lines would interleave, so they are skipped
+            let config = &config;
+            let filter = &filter;
+            let results = query_networks(&networks, |network_id| async move {
+                let data = bridges_data(config, network_id, source, page, true).await?;
+                let claimed = filter.claimed_deposits(config, network_id).await?;
+                Ok::<_, crate::error::AggSandboxError>(filter.apply(&data, network_id, &claimed))
+            })
+            .await;
+            print_grouped(&ui, "🌉 Bridges", results, |data| {
+                if !json {
+                    warn_large_metadata(data);
+                }
+                data.clone()
+            })?;
         }
         ShowCommands::Claims {
-            network_id,
+            network_ids,
             bridge_tx_hash,
             claim_tx_hash,
             status,
             claim_type,
             address,
+            pending,
+            source,
+            page,
+            page_size,
+            all_pages,
             json,
         } => {
+            let json = json || output.is_json();
             let ui = UI::new(if json {
                 OutputFormat::Json
             } else {
                 OutputFormat::Human
             });
-            let response = api::get_claims(&config, network_id, json).await?;
-            let filtered_data = filter_claims(
-                &response.data,
-                bridge_tx_hash.as_deref(),
-                claim_tx_hash.as_deref(),
-                status.as_deref(),
-                claim_type.as_deref(),
-                address.as_deref(),
-            );
+            let filter = |data: &serde_json::Value| {
+                filter_claims(
+                    data,
+                    bridge_tx_hash.as_deref(),
+                    claim_tx_hash.as_deref(),
+                    status.as_deref(),
+                    claim_type.as_deref(),
+                    address.as_deref(),
+                )
+            };
+            // Without --pending the bridge service may not list pending claims at all
+            let check_pending = source == DataSource::Api
+                && !pending
+                && status
+                    .as_deref()
+                    .is_some_and(|status| status.eq_ignore_ascii_case("pending"));
+            let page = paging(source, page, page_size, all_pages)?;
+            let networks = dedup_networks(&network_ids);
+            if let [network_id] = networks[..] {
+                if all_pages {
+                    return stream_pages(&config, network_id, "claims", page_size, &ui, |page| {
+                        if check_pending {
+                            known_issues::check(Endpoint::PendingClaims, network_id, page);
+                        }
+                        filter(page)
+                    })
+                    .await;
+                }
+                let data = claims_data(&config, network_id, source, pending, page, json).await?;
+                if check_pending {
+                    known_issues::check(Endpoint::PendingClaims, network_id, &data);
+                }
+                let filtered_data = filter(&data);
+                if json {
+                    ui.json(&filtered_data);
+                } else {
+                    let display_data = filter_display_metadata(&filtered_data);
+                    ui.data("📋 Claims Information", &display_data);
+                }
+                return Ok(());
+            }
 
-            if json {
-                ui.json(&filtered_data);
-            } else {
-                let display_data = filter_display_metadata(&filtered_data);
-                ui.data("📋 Claims Information", &display_data);
+            if all_pages {
+                return Err(validation_error("--all-pages takes a single --network-id"));
             }
+            let config = &config;
+            let results = query_networks(&networks, |network_id| async move {
+                let data = claims_data(config, network_id, source, pending, page, true).await?;
+                if check_pending {
+                    known_issues::check(Endpoint::PendingClaims, network_id, &data);
+                }
+                Ok::<_, crate::error::AggSandboxError>(data)
+            })
+            .await;
+            print_grouped(&ui, "📋 Claims", results, filter)?;
         }
         ShowCommands::ClaimProof {
             network_id,
@@ -227,6 +830,7 @@ pub async fn handle_show(subcommand: ShowCommands) -> Result<()> {
             deposit_count,
             json,
         } => {
+            let json = json || output.is_json();
             let ui = UI::new(if json {
                 OutputFormat::Json
             } else {
@@ -247,6 +851,7 @@ pub async fn handle_show(subcommand: ShowCommands) -> Result<()> {
             deposit_count,
             json,
         } => {
+            let json = json || output.is_json();
             let ui = UI::new(if json {
                 OutputFormat::Json
             } else {
@@ -262,10 +867,658 @@ pub async fn handle_show(subcommand: ShowCommands) -> Result<()> {
                 ui.data("🌳 L1 Info Tree Index", &display_data);
             }
         }
+        ShowCommands::L1InfoTree {
+            network_id,
+            leaf_index,
+            count,
+            json,
+        } => {
+            let json = json || output.is_json();
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+            validate_network_id(network_id, "Network")?;
+
+            if let Some(leaf_index) = leaf_index {
+                let leaf = l1_info_tree::fetch_leaf(&config, network_id, leaf_index).await?;
+                let Some(leaf) = leaf else {
+                    return Err(validation_error(&format!(
+                        "L1 info tree leaf {leaf_index} is not known to the bridge service of network {network_id} ({}) yet",
+                        get_network_name(network_id)
+                    )));
+                };
+                if json {
+                    ui.json(&serde_json::to_value(&leaf).unwrap_or_default());
+                } else {
+                    if leaf.l1_info_tree_index != leaf_index {
+                        ui.warning(&format!(
+                            "Leaf {leaf_index} was not injected on network {network_id}, the next injected leaf is {}",
+                            leaf.l1_info_tree_index
+                        ));
+                    }
+                    let rows = l1_info_tree::leaf_rows(&leaf);
+                    let rows: Vec<(&str, &str)> = rows
+                        .iter()
+                        .map(|(label, value)| (label.as_str(), value.as_str()))
+                        .collect();
+                    ui.table(
+                        &format!("🌿 L1 Info Tree Leaf {}", leaf.l1_info_tree_index),
+                        &rows,
+                    );
+                }
+                return Ok(());
+            }
+
+            let tree = l1_info_tree::fetch_recent_leaves(&config, network_id, count).await?;
+            if json {
+                ui.json(&serde_json::to_value(&tree).unwrap_or_default());
+            } else {
+                ui.info(&format!(
+                    "L1 info tree has {} leaves, showing the last {} from network {network_id} ({})",
+                    tree.leaf_count,
+                    tree.leaves.len() + tree.missing.len(),
+                    get_network_name(network_id)
+                ));
+                for leaf in &tree.leaves {
+                    let rows = l1_info_tree::leaf_rows(leaf);
+                    let rows: Vec<(&str, &str)> = rows
+                        .iter()
+                        .map(|(label, value)| (label.as_str(), value.as_str()))
+                        .collect();
+                    ui.table(
+                        &format!("🌿 L1 Info Tree Leaf {}", leaf.l1_info_tree_index),
+                        &rows,
+                    );
+                }
+                if !tree.missing.is_empty() {
+                    let missing: Vec<String> =
+                        tree.missing.iter().map(|index| index.to_string()).collect();
+                    ui.warning(&format!(
+                        "Leaves not known to the bridge service of network {network_id}: {}",
+                        missing.join(", ")
+                    ));
+                }
+            }
+        }
+        ShowCommands::ExitRoots { network_id, json } => {
+            let json = json || output.is_json();
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+            let networks = match network_id {
+                Some(0) => vec![0],
+                Some(network_id) => {
+                    validate_network_id(network_id, "Network")?;
+                    vec![0, network_id]
+                }
+                None => config.networks.network_ids(),
+            };
+            let roots = fetch_all_exit_roots(&config, &networks).await?;
+
+            if json {
+                ui.json(&serde_json::to_value(&roots).unwrap_or_default());
+            } else {
+                for network in &roots {
+                    let rows = exit_root_rows(network);
+                    let rows: Vec<(&str, &str)> = rows
+                        .iter()
+                        .map(|(label, value)| (label.as_str(), value.as_str()))
+                        .collect();
+                    ui.table(
+                        &format!(
+                            "🌐 Network {} ({})",
+                            network.network_id,
+                            get_network_name(network.network_id)
+                        ),
+                        &rows,
+                    );
+                }
+                for network in roots.iter().filter(|n| n.l1_root_injected == Some(false)) {
+                    ui.warning(&format!(
+                        "The current L1 global exit root has not been injected on network {} yet, deposits it covers cannot be claimed there",
+                        network.network_id
+                    ));
+                }
+            }
+        }
+        ShowCommands::Rollups { json } => {
+            let json = json || output.is_json();
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+            let state = fetch_rollups(&config).await?;
+
+            if json {
+                ui.json(&serde_json::to_value(&state).unwrap_or_default());
+            } else {
+                let manager = format!("{:#x}", state.manager);
+                let count = state.rollup_count.to_string();
+                let rollup_exit_root = format!("{:#x}", state.rollup_exit_root);
+                let global_rollup_exit_root = format!("{:#x}", state.global_rollup_exit_root);
+                ui.table(
+                    "🧮 Rollup Manager",
+                    &[
+                        ("Address", manager.as_str()),
+                        ("Rollups", count.as_str()),
+                        ("Rollup exit root", rollup_exit_root.as_str()),
+                        (
+                            "In global exit root manager",
+                            global_rollup_exit_root.as_str(),
+                        ),
+                    ],
+                );
+                for rollup in &state.rollups {
+                    let rows = rollup_rows(rollup);
+                    let rows: Vec<(&str, &str)> = rows
+                        .iter()
+                        .map(|(label, value)| (label.as_str(), value.as_str()))
+                        .collect();
+                    ui.table(
+                        &format!(
+                            "🧩 Rollup {} ({})",
+                            rollup.rollup_id,
+                            get_network_name(u64::from(rollup.rollup_id))
+                        ),
+                        &rows,
+                    );
+                }
+                for finding in diagnose(&state) {
+                    ui.warning(&finding);
+                }
+            }
+        }
+        ShowCommands::Certificates { network_id, json } => {
+            let json = json || output.is_json();
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+            let networks = match network_id {
+                Some(0) => {
+                    return Err(validation_error(
+                        "L1 does not submit certificates, pass the network ID of an L2",
+                    ))
+                }
+                Some(network_id) => {
+                    validate_network_id(network_id, "Network")?;
+                    vec![network_id]
+                }
+                None => config
+                    .networks
+                    .network_ids()
+                    .into_iter()
+                    .filter(|network_id| *network_id != 0)
+                    .collect(),
+            };
+            let mut networks_certificates = Vec::with_capacity(networks.len());
+            for network_id in networks {
+                networks_certificates.push(fetch_certificates(&config, network_id).await?);
+            }
+
+            if json {
+                ui.json(&serde_json::to_value(&networks_certificates).unwrap_or_default());
+            } else {
+                for network in &networks_certificates {
+                    let title = format!(
+                        "📜 Network {} ({})",
+                        network.network_id,
+                        get_network_name(network.network_id)
+                    );
+                    let rows = certificate_summary_rows(network);
+                    let rows: Vec<(&str, &str)> = rows
+                        .iter()
+                        .map(|(label, value)| (label.as_str(), value.as_str()))
+                        .collect();
+                    ui.table(&title, &rows);
+
+                    if let Some(certificate) = &network.latest_known {
+                        let rows = certificate_rows(certificate);
+                        let rows: Vec<(&str, &str)> = rows
+                            .iter()
+                            .map(|(label, value)| (label.as_str(), value.as_str()))
+                            .collect();
+                        ui.table(
+                            &format!("📄 Latest certificate of network {}", network.network_id),
+                            &rows,
+                        );
+                        if certificate.status == CertificateStatus::InError {
+                            ui.warning(&format!(
+                                "Certificate at height {} of network {} is in error: {}",
+                                certificate.height,
+                                network.network_id,
+                                certificate.error.as_deref().unwrap_or("unknown error")
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        ShowCommands::Balances {
+            network_ids,
+            address,
+            token,
+            json,
+        } => {
+            let json = json || output.is_json();
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+            let networks = if network_ids.is_empty() {
+                config.networks.network_ids()
+            } else {
+                dedup_networks(&network_ids)
+            };
+            let query = BalanceQuery::new(&config, networks, &address, &token).await?;
+            let rows = query.fetch(&config).await?;
+
+            if json {
+                ui.json(&serde_json::json!({
+                    "tokens": query.tokens,
+                    "balances": rows,
+                }));
+            } else {
+                ui.info("💰 Balances");
+                println!("{}", render_table(&query, &rows));
+            }
+        }
+        ShowCommands::History {
+            address,
+            network_ids,
+            source,
+            json,
+        } => {
+            let json = json || output.is_json();
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+            let address = validate_address(&address, "Address")?;
+            let networks = if network_ids.is_empty() {
+                config.networks.network_ids()
+            } else {
+                dedup_networks(&network_ids)
+            };
+            let entries = fetch_history(&config, &networks, address, source).await?;
+
+            if json {
+                ui.json(&serde_json::json!({
+                    "address": format!("{address:#x}"),
+                    "entries": entries,
+                }));
+            } else if entries.is_empty() {
+                ui.info(&format!(
+                    "No deposits or claims involving {address:#x} on networks {networks:?}"
+                ));
+            } else {
+                ui.info(&format!("📜 Bridge history of {address:#x}"));
+                println!("{}", render_history(&entries));
+            }
+        }
+        ShowCommands::GasReport { reset, json } => {
+            let json = json || output.is_json();
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+            let store = GasReportStore::default_location();
+            let operations = store.load();
+            let summaries = gas_report::summarize(&operations);
+            if reset {
+                store.clear();
+            }
+
+            if json {
+                ui.json(&serde_json::json!({ "operations": summaries }));
+            } else if summaries.is_empty() {
+                ui.info("No bridge or claim operations recorded in this session");
+            } else {
+                ui.info("⛽ Gas Report");
+                println!("{}", gas_report::render_table(&summaries));
+            }
+            if reset && !json {
+                ui.success("Gas report cleared");
+            }
+        }
+        ShowCommands::ConfigEndpoints { json } => {
+            let json = json || output.is_json();
+            let ui = UI::new(if json {
+                OutputFormat::Json
+            } else {
+                OutputFormat::Human
+            });
+            let networks = endpoint_matrix(&config);
+
+            if json {
+                ui.json(&serde_json::json!({ "networks": networks }));
+            } else {
+                ui.info("🗺️ Network Endpoints");
+                println!("{}", render_matrix(&networks));
+            }
+        }
     }
     Ok(())
 }
 
+/// Filters of `show bridges`, applied to the typed deposits
+#[derive(Debug, Default)]
+struct BridgeFilter {
+    destination_address: Option<Address>,
+    depositor: Option<Address>,
+    /// Origin token, the zero address for ETH
+    token: Option<Address>,
+    /// Only claimed (`true`) or unclaimed (`false`) deposits
+    claimed: Option<bool>,
+    from_block: Option<u64>,
+}
+
+impl BridgeFilter {
+    fn is_empty(&self) -> bool {
+        self.destination_address.is_none()
+            && self.depositor.is_none()
+            && self.token.is_none()
+            && self.claimed.is_none()
+            && self.from_block.is_none()
+    }
+
+    /// Deposits of `network_id` claimed on any other network, only fetched
+    /// with `--claimed` or `--unclaimed`
+    async fn claimed_deposits(
+        &self,
+        config: &Config,
+        network_id: u64,
+    ) -> Result<HashSet<DepositKey>> {
+        let mut claimed = HashSet::new();
+        if self.claimed.is_none() {
+            return Ok(claimed);
+        }
+        let api_client = OptimizedApiClient::global();
+        for destination in config.networks.network_ids() {
+            if destination == network_id {
+                continue;
+            }
+            api_client
+                .for_each_page(
+                    "claims",
+                    config,
+                    destination,
+                    CLAIM_PAGE_SIZE,
+                    |_, claims| {
+                        claimed.extend(
+                            claims
+                                .iter()
+                                .filter_map(claim_deposit_key)
+                                .filter(|(source, _)| *source == network_id),
+                        );
+                        Ok(())
+                    },
+                )
+                .await?;
+        }
+        Ok(claimed)
+    }
+
+    fn matches(
+        &self,
+        deposit: &BridgeDeposit,
+        network_id: u64,
+        claimed: &HashSet<DepositKey>,
+    ) -> bool {
+        self.destination_address
+            .is_none_or(|address| deposit.destination_address == address)
+            && self
+                .depositor
+                .is_none_or(|address| deposit.from_address == Some(address))
+            && self
+                .token
+                .is_none_or(|token| deposit.is_asset() && deposit.origin_address == token)
+            && self.claimed.is_none_or(|want_claimed| {
+                claimed.contains(&(network_id, deposit.deposit_count)) == want_claimed
+            })
+            && self
+                .from_block
+                .is_none_or(|from_block| deposit.block_num.is_some_and(|block| block >= from_block))
+    }
+
+    /// Keep the bridges of a response matching the filter, updating `count`
+    ///
+    /// Bridges that do not parse as deposits never match a filter.
+    fn apply(
+        &self,
+        data: &serde_json::Value,
+        network_id: u64,
+        claimed: &HashSet<DepositKey>,
+    ) -> serde_json::Value {
+        if self.is_empty() {
+            return data.clone();
+        }
+        let Some(bridges) = data["bridges"].as_array() else {
+            return data.clone();
+        };
+        let bridges: Vec<serde_json::Value> = bridges
+            .iter()
+            .filter(|bridge| {
+                BridgeDeposit::from_value(bridge)
+                    .is_ok_and(|deposit| self.matches(&deposit, network_id, claimed))
+            })
+            .cloned()
+            .collect();
+        let mut result = data.clone();
+        result["count"] = bridges.len().into();
+        result["bridges"] = bridges.into();
+        result
+    }
+}
+
+/// Claims per request when looking up which deposits were claimed
+const CLAIM_PAGE_SIZE: u64 = 100;
+
+/// Page requested with `--page`, pagination being a bridge service feature
+fn paging(
+    source: DataSource,
+    page: Option<u64>,
+    page_size: u64,
+    all_pages: bool,
+) -> Result<Option<Page>> {
+    if source == DataSource::Local && (page.is_some() || all_pages) {
+        return Err(validation_error(
+            "--page and --all-pages read from the bridge service, not the local index",
+        ));
+    }
+    Ok(page.map(|number| Page {
+        number,
+        size: page_size,
+    }))
+}
+
+/// Print every page of bridges or claims of a network as it arrives
+///
+/// `field` is the list endpoint, `bridges` or `claims`, and `transform` is
+/// applied to each page. In JSON mode every entry is printed as one JSON
+/// object per line.
+async fn stream_pages(
+    config: &Config,
+    network_id: u64,
+    field: &str,
+    page_size: u64,
+    ui: &UI,
+    transform: impl Fn(&serde_json::Value) -> serde_json::Value,
+) -> Result<()> {
+    let title = if field == "claims" {
+        "📋 Claims"
+    } else {
+        "🌉 Bridges"
+    };
+    let mut entries = 0;
+    let pages = OptimizedApiClient::global()
+        .for_each_page(field, config, network_id, page_size, |page, items| {
+            let page_data =
+                serde_json::Map::from_iter([(field.to_string(), items.to_vec().into())]);
+            let data = transform(&page_data.into());
+            let items = data[field].as_array().map_or(&[][..], Vec::as_slice);
+            entries += items.len();
+            if ui.is_json() {
+                items.iter().for_each(|item| ui.json(item));
+            } else if !items.is_empty() {
+                ui.data(
+                    &format!("{title}, page {}", page.number),
+                    &filter_display_metadata(&data),
+                );
+            }
+            Ok(())
+        })
+        .await?;
+    if !ui.is_json() {
+        ui.info(&format!(
+            "{entries} {field} in {pages} page(s) of network {network_id}"
+        ));
+    }
+    Ok(())
+}
+
+/// Bridges of a network with metadata diagnostics
+async fn bridges_data(
+    config: &Config,
+    network_id: u64,
+    source: DataSource,
+    page: Option<Page>,
+    json: bool,
+) -> Result<serde_json::Value> {
+    if source == DataSource::Local {
+        let data = local_index(config, network_id).await?.bridges_json();
+        return Ok(annotate_metadata_diagnostics(&data));
+    }
+    let response = match page {
+        Some(page) => {
+            OptimizedApiClient::global()
+                .get_bridges_page(config, network_id, page)
+                .await
+        }
+        None => api::get_bridges(config, network_id, json)
+            .await
+            .map(|response| response.data),
+    };
+    let data = match response {
+        Ok(data) => {
+            known_issues::check(Endpoint::Bridges, network_id, &data);
+            data
+        }
+        Err(e) if local_index::is_api_unavailable(&e) => {
+            local_index::bridges_from_chain(config, network_id, &e).await?
+        }
+        Err(e) => return Err(e),
+    };
+    Ok(annotate_metadata_diagnostics(&data))
+}
+
+/// Claims of a network, merged with its unclaimed deposits with `pending`
+async fn claims_data(
+    config: &Config,
+    network_id: u64,
+    source: DataSource,
+    pending: bool,
+    page: Option<Page>,
+    json: bool,
+) -> Result<serde_json::Value> {
+    match source {
+        DataSource::Local => Ok(local_index(config, network_id).await?.claims_json()),
+        DataSource::Api => {
+            let response = match page {
+                Some(page) => {
+                    OptimizedApiClient::global()
+                        .get_claims_page(config, network_id, page)
+                        .await
+                }
+                None => api::get_claims(config, network_id, json)
+                    .await
+                    .map(|response| response.data),
+            };
+            let data = match response {
+                Ok(data) => data,
+                Err(e) if local_index::is_api_unavailable(&e) => {
+                    return local_index::claims_from_chain(config, network_id, &e).await;
+                }
+                Err(e) => return Err(e),
+            };
+            if !pending {
+                return Ok(data);
+            }
+            let states = deposit_states(config, network_id, None).await?;
+            Ok(merge_deposit_claims(&states, &data))
+        }
+    }
+}
+
+/// Print the results of several networks grouped by network
+///
+/// Networks that failed are reported in place and the first error is returned
+/// after everything was printed. In JSON mode one document is printed with a
+/// `networks` array holding each network's data, or its `error`.
+fn print_grouped(
+    ui: &UI,
+    title: &str,
+    results: Vec<(u64, Result<serde_json::Value>)>,
+    transform: impl Fn(&serde_json::Value) -> serde_json::Value,
+) -> Result<()> {
+    let mut first_error = None;
+    let mut grouped = Vec::with_capacity(results.len());
+    for (network_id, result) in results {
+        match result {
+            Ok(data) => grouped.push((network_id, Ok(transform(&data)))),
+            Err(e) => {
+                grouped.push((network_id, Err(e.to_string())));
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    if ui.is_json() {
+        ui.json(&grouped_json(&grouped));
+    } else {
+        for (network_id, data) in &grouped {
+            ui.blank_line();
+            ui.info(&format!(
+                "{title} on network {network_id} ({})",
+                get_network_name(*network_id)
+            ));
+            match data {
+                Ok(data) => ui.data(title, &filter_display_metadata(data)),
+                Err(e) => ui.warning(&format!("Query failed: {e}")),
+            }
+        }
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn grouped_json(
+    grouped: &[(u64, std::result::Result<serde_json::Value, String>)],
+) -> serde_json::Value {
+    let networks: Vec<serde_json::Value> = grouped
+        .iter()
+        .map(|(network_id, data)| {
+            let mut entry = match data {
+                Ok(serde_json::Value::Object(fields)) => serde_json::Value::Object(fields.clone()),
+                Ok(other) => serde_json::json!({ "data": other }),
+                Err(e) => serde_json::json!({ "error": e }),
+            };
+            entry["network_id"] = (*network_id).into();
+            entry
+        })
+        .collect();
+    serde_json::json!({ "networks": networks })
+}
+
 /// Filter claims based on provided criteria
 ///
 /// Filters claims array based on bridge_tx_hash, claim_tx_hash, status, type, and destination address.
@@ -350,7 +1603,11 @@ fn filter_claims(
 
                 // Filter by destination address
                 if let Some(addr) = address_filter {
-                    if let Some(dest_address) = claim.get("dest_address").and_then(|v| v.as_str()) {
+                    if let Some(dest_address) = claim
+                        .get("dest_address")
+                        .or_else(|| claim.get("destination_address"))
+                        .and_then(|v| v.as_str())
+                    {
                         if !dest_address.eq_ignore_ascii_case(addr) {
                             return false;
                         }
@@ -380,6 +1637,248 @@ fn filter_claims(
     result
 }
 
+/// Options of `show bridges --watch`
+struct WatchBridgesOptions {
+    interval: Duration,
+    bell: bool,
+    notify: bool,
+}
+
+/// Identity of a bridge record across polls
+type BridgeKey = (u64, String);
+
+fn bridge_key(bridge: &serde_json::Value) -> Option<BridgeKey> {
+    Some((
+        bridge["deposit_count"].as_u64()?,
+        bridge["bridge_tx_hash"].as_str()?.to_lowercase(),
+    ))
+}
+
+/// Bridges of a response that are not in `seen`, which is updated with them
+fn new_bridges(
+    seen: &mut HashSet<BridgeKey>,
+    response: &serde_json::Value,
+) -> Vec<serde_json::Value> {
+    let mut bridges: Vec<serde_json::Value> = response["bridges"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|bridge| bridge_key(bridge).is_some_and(|key| seen.insert(key)))
+        .cloned()
+        .collect();
+    bridges.sort_by_key(|bridge| bridge["deposit_count"].as_u64());
+    bridges
+}
+
+fn describe_bridge(bridge: &serde_json::Value) -> String {
+    format!(
+        "🆕 Deposit #{} → network {}: {} {} (tx {})",
+        bridge["deposit_count"],
+        bridge["destination_network"],
+        if bridge["leaf_type"].as_u64() == Some(1) {
+            "message"
+        } else {
+            "asset"
+        },
+        bridge["amount"].as_str().unwrap_or("0"),
+        bridge["bridge_tx_hash"].as_str().unwrap_or("?"),
+    )
+}
+
+/// Poll the bridges of a network and print new ones until Ctrl-C
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+async fn watch_bridges(
+    config: &Config,
+    network_id: u64,
+    ui: &UI,
+    options: WatchBridgesOptions,
+) -> Result<()> {
+    let api_client = OptimizedApiClient::global();
+    let json = ui.is_json();
+    if !json {
+        ui.info(&format!(
+            "👀 Watching bridges on network {network_id} every {}s, press Ctrl-C to stop",
+            options.interval.as_secs()
+        ));
+    }
+
+    // Bridges present at startup are only counted, the feed starts after them
+    let mut seen: Option<HashSet<BridgeKey>> = None;
+    let mut notifications_available = options.notify;
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        api_client.invalidate("bridges", Some(network_id)).await;
+        match api_client.get_bridges(config, network_id).await {
+            Ok(response) => match seen.as_mut() {
+                None => {
+                    let mut initial = HashSet::new();
+                    let count = new_bridges(&mut initial, &response).len();
+                    if !json {
+                        ui.info(&format!("{count} existing bridge(s), waiting for new ones"));
+                    }
+                    seen = Some(initial);
+                }
+                Some(seen) => {
+                    let fresh = new_bridges(seen, &annotate_metadata_diagnostics(&response));
+                    for bridge in &fresh {
+                        if json {
+                            ui.json(bridge);
+                        } else {
+                            ui.info(&describe_bridge(bridge));
+                        }
+                        if notifications_available {
+                            notifications_available =
+                                send_notification(network_id, &describe_bridge(bridge));
+                        }
+                    }
+                    if options.bell && !fresh.is_empty() {
+                        eprint!("\x07");
+                    }
+                }
+            },
+            Err(e) => {
+                debug!(network_id, error = %e, "Bridge watch poll failed");
+                if !json {
+                    ui.warning(&format!("Poll failed, retrying: {e}"));
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = &mut ctrl_c => break,
+            _ = tokio::time::sleep(options.interval) => {}
+        }
+    }
+
+    if !json {
+        ui.success("Stopped watching bridges");
+    }
+    Ok(())
+}
+
+/// Merge the deposits destined for a network with its claims
+///
+/// Every entry is labelled with a `status` of `pending` (not yet claimable),
+/// `claimable` or `completed`, and carries the deposit count and global index.
+/// Entries keep the field names of the bridge service claims so the regular
+/// claim filters apply to them.
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn merge_deposit_claims(
+    states: &DepositStates,
+    claims_response: &serde_json::Value,
+) -> serde_json::Value {
+    use serde_json::{json, Value};
+    use std::collections::BTreeMap;
+
+    let mut entries: BTreeMap<(u64, u64), Value> = BTreeMap::new();
+    for ((source_network, deposit_count), (deposit, state)) in states {
+        let status = match state {
+            DepositState::Pending => "pending",
+            DepositState::Claimable => "claimable",
+            DepositState::Claimed => "completed",
+        };
+        entries.insert(
+            (*source_network, *deposit_count),
+            json!({
+                "status": status,
+                "type": if deposit.leaf_type == 1 { "message" } else { "asset" },
+                "source_network": source_network,
+                "deposit_count": deposit_count,
+                "global_index": global_index_of(*source_network, *deposit_count),
+                "bridge_tx_hash": deposit.tx_hash,
+                "claim_tx_hash": "",
+                "amount": deposit.amount,
+            }),
+        );
+    }
+
+    // Claims complete their deposit, or stand alone when the deposit is no longer indexed
+    for claim in claims_response["claims"].as_array().into_iter().flatten() {
+        let Some((source_network, deposit_count)) = claim_deposit_key(claim) else {
+            continue;
+        };
+        let mut entry = entries
+            .remove(&(source_network, deposit_count))
+            .unwrap_or_else(|| json!({}));
+        if let (Some(entry), Some(claim)) = (entry.as_object_mut(), claim.as_object()) {
+            for (key, value) in claim {
+                entry.insert(key.clone(), value.clone());
+            }
+            entry.insert("status".to_string(), json!("completed"));
+            entry.insert("source_network".to_string(), json!(source_network));
+            entry.insert("deposit_count".to_string(), json!(deposit_count));
+            entry.insert(
+                "global_index".to_string(),
+                json!(global_index_of(source_network, deposit_count)),
+            );
+        }
+        entries.insert((source_network, deposit_count), entry);
+    }
+
+    let claims: Vec<Value> = entries.into_values().collect();
+    json!({ "claims": claims, "count": claims.len() })
+}
+
+fn global_index_of(source_network: u64, deposit_count: u64) -> String {
+    compute_global_index(ComputeGlobalIndexArgs {
+        index_local: deposit_count,
+        source_network_id: source_network,
+    })
+    .to_string()
+}
+
+/// Add metadata size and estimated claim gas to every bridge in the response
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn annotate_metadata_diagnostics(data: &serde_json::Value) -> serde_json::Value {
+    let mut result = data.clone();
+
+    if let Some(bridges) = result.get_mut("bridges").and_then(|v| v.as_array_mut()) {
+        for bridge in bridges.iter_mut() {
+            let diagnostics = bridge
+                .get("metadata")
+                .and_then(|v| v.as_str())
+                .and_then(MetadataDiagnostics::from_hex)
+                .unwrap_or_else(|| MetadataDiagnostics::from_bytes(&[]));
+
+            if let Some(bridge_obj) = bridge.as_object_mut() {
+                bridge_obj.insert(
+                    "metadata_diagnostics".to_string(),
+                    serde_json::json!({
+                        "byte_len": diagnostics.byte_len,
+                        "estimated_claim_gas": diagnostics.estimated_claim_gas,
+                    }),
+                );
+            }
+        }
+    }
+
+    result
+}
+
+/// Warn about bridges whose metadata may exceed destination gas expectations
+fn warn_large_metadata(data: &serde_json::Value) {
+    let Some(bridges) = data.get("bridges").and_then(|v| v.as_array()) else {
+        return;
+    };
+
+    for bridge in bridges {
+        let byte_len = bridge["metadata_diagnostics"]["byte_len"]
+            .as_u64()
+            .unwrap_or(0) as usize;
+        if byte_len > LARGE_METADATA_WARN_BYTES {
+            crate::ui::ui().warning(&format!(
+                "Bridge with deposit_count {} carries {byte_len} bytes of metadata (~{} extra claim gas)",
+                bridge["deposit_count"].as_u64().unwrap_or_default(),
+                bridge["metadata_diagnostics"]["estimated_claim_gas"]
+                    .as_u64()
+                    .unwrap_or_default()
+            ));
+        }
+    }
+}
+
 /// Remove sandbox_metadata from API response for cleaner display output
 ///
 /// Recursively filters out sandbox_metadata at any level while preserving all other data.
@@ -410,3 +1909,177 @@ fn filter_display_metadata(data: &serde_json::Value) -> serde_json::Value {
         _ => data.clone(),
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use crate::commands::bridge::claim_all::PendingDeposit;
+    use serde_json::json;
+
+    fn deposit(source_network: u64, deposit_count: u64) -> PendingDeposit {
+        PendingDeposit {
+            source_network,
+            deposit_count,
+            leaf_type: 0,
+            tx_hash: format!("0xbridge{deposit_count}"),
+            amount: "100".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_new_bridges_reports_each_bridge_once() {
+        let mut seen = HashSet::new();
+        let first = json!({"bridges": [
+            {"deposit_count": 1, "bridge_tx_hash": "0xAA"},
+            {"deposit_count": 0, "bridge_tx_hash": "0xbb"},
+        ]});
+        let second = json!({"bridges": [
+            {"deposit_count": 2, "bridge_tx_hash": "0xcc"},
+            {"deposit_count": 1, "bridge_tx_hash": "0xaa"},
+            {"deposit_count": 0, "bridge_tx_hash": "0xbb"},
+            {"deposit_count": 3},
+        ]});
+
+        let initial = new_bridges(&mut seen, &first);
+        assert_eq!(initial.len(), 2);
+        assert_eq!(initial[0]["deposit_count"], 0);
+
+        let fresh = new_bridges(&mut seen, &second);
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(fresh[0]["bridge_tx_hash"], "0xcc");
+        assert!(new_bridges(&mut seen, &second).is_empty());
+    }
+
+    #[test]
+    fn test_merge_deposit_claims() {
+        let states: DepositStates = [
+            ((0, 1), (deposit(0, 1), DepositState::Pending)),
+            ((0, 2), (deposit(0, 2), DepositState::Claimable)),
+            ((0, 3), (deposit(0, 3), DepositState::Claimed)),
+        ]
+        .into_iter()
+        .collect();
+        let claims = json!({
+            "claims": [{
+                "global_index": "18446744073709551619",
+                "claim_tx_hash": "0xclaim3",
+                "dest_address": "0xabc"
+            }]
+        });
+
+        let merged = merge_deposit_claims(&states, &claims);
+        let entries = merged["claims"].as_array().unwrap();
+
+        assert_eq!(merged["count"], 3);
+        assert_eq!(entries[0]["status"], "pending");
+        assert_eq!(entries[0]["global_index"], "18446744073709551617");
+        assert_eq!(entries[1]["status"], "claimable");
+        assert_eq!(entries[1]["deposit_count"], 2);
+        assert_eq!(entries[2]["status"], "completed");
+        assert_eq!(entries[2]["claim_tx_hash"], "0xclaim3");
+        assert_eq!(entries[2]["bridge_tx_hash"], "0xbridge3");
+    }
+
+    #[test]
+    fn test_grouped_json() {
+        let grouped = vec![
+            (
+                0,
+                Ok(json!({"count": 1, "bridges": [{"deposit_count": 0}]})),
+            ),
+            (2, Err("connection refused".to_string())),
+        ];
+
+        assert_eq!(
+            grouped_json(&grouped),
+            json!({"networks": [
+                {"network_id": 0, "count": 1, "bridges": [{"deposit_count": 0}]},
+                {"network_id": 2, "error": "connection refused"},
+            ]})
+        );
+    }
+
+    #[test]
+    fn test_filter_claims_by_local_index_address() {
+        let claims = json!({
+            "count": 2,
+            "claims": [
+                {"claim_tx_hash": "0x01", "destination_address": "0xAbC"},
+                {"claim_tx_hash": "0x02", "destination_address": "0xdef"},
+            ],
+            "source": "local"
+        });
+
+        let filtered = filter_claims(&claims, None, None, None, None, Some("0xabc"));
+
+        assert_eq!(filtered["count"], 1);
+        assert_eq!(filtered["claims"][0]["claim_tx_hash"], "0x01");
+    }
+
+    #[test]
+    fn test_bridge_filter() {
+        let receiver = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+        let token = "0x5fbdb2315678afecb367f032d93f642f64180aa3";
+        let bridge = |deposit_count: u64, destination: &str, origin: &str, block: u64| {
+            json!({
+                "bridge_tx_hash": format!("0x{deposit_count:064x}"),
+                "deposit_count": deposit_count,
+                "leaf_type": 0,
+                "origin_address": origin,
+                "destination_address": destination,
+                "from_address": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+                "amount": "1",
+                "block_num": block,
+            })
+        };
+        let zero = "0x0000000000000000000000000000000000000000";
+        let bridges = json!({
+            "count": 3,
+            "bridges": [
+                bridge(0, receiver, zero, 10),
+                bridge(1, receiver, token, 20),
+                bridge(2, zero, token, 30),
+            ],
+        });
+        let claimed = HashSet::from([(0, 1)]);
+        let deposit_counts = |filter: BridgeFilter| -> Vec<u64> {
+            filter.apply(&bridges, 0, &claimed)["bridges"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|bridge| bridge["deposit_count"].as_u64().unwrap())
+                .collect()
+        };
+
+        assert_eq!(deposit_counts(BridgeFilter::default()), vec![0, 1, 2]);
+        let to_receiver = BridgeFilter {
+            destination_address: Some(receiver.parse().unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(deposit_counts(to_receiver), vec![0, 1]);
+        let of_token = BridgeFilter {
+            token: Some(token.parse().unwrap()),
+            from_block: Some(25),
+            ..Default::default()
+        };
+        assert_eq!(deposit_counts(of_token), vec![2]);
+        let unclaimed_to_receiver = BridgeFilter {
+            destination_address: Some(receiver.parse().unwrap()),
+            claimed: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(deposit_counts(unclaimed_to_receiver), vec![0]);
+        let from_other = BridgeFilter {
+            depositor: Some(receiver.parse().unwrap()),
+            ..Default::default()
+        };
+        assert!(deposit_counts(from_other).is_empty());
+        let filtered = BridgeFilter {
+            claimed: Some(true),
+            ..Default::default()
+        }
+        .apply(&bridges, 0, &claimed);
+        assert_eq!(filtered["count"], 1);
+    }
+}
@@ -0,0 +1,578 @@
+//! Sandbox state snapshots
+//!
+//! A snapshot stores, per network, the full Anvil state (`anvil_dumpState`)
+//! and an `evm_snapshot` ID, plus a `metadata.json` with the block number,
+//! bridge deposit count and contract addresses at save time. Snapshots live in
+//! `.aggsandbox/snapshots/<name>/`.
+//!
+//! Restoring first tries `evm_revert`, which is instant but only works while
+//! the node that took the snapshot is still running, and falls back to
+//! `anvil_loadState` otherwise. The bridge service keeps its own index, so it
+//! is not rewound with the chains.
+
+use crate::api_client::OptimizedApiClient;
+use crate::commands::bridge::common::{
+    get_network_name, node_request, rpc_action, rpc_error, validate_network_id,
+};
+use crate::commands::bridge::{get_bridge_contract_address, get_provider, BridgeContract};
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui::{self, OutputFormat, UI};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// Snapshot directory, relative to the project root
+const SNAPSHOT_DIR: &str = ".aggsandbox/snapshots";
+const METADATA_FILE: &str = "metadata.json";
+
+/// Snapshot subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum SnapshotCommands {
+    /// 💾 Save the state of every sandbox network
+    #[command(
+        long_about = "Save the current state of every sandbox network under a name.
+
+The full chain state of each Anvil node is dumped together with the block
+number, bridge deposit count and contract addresses, so a prepared scenario
+(tokens bridged, claims pending) can be restored without replaying it.
+
+Examples:
+  aggsandbox snapshot save bridged-usdc
+  aggsandbox snapshot save pending-claims --description \"3 deposits, unclaimed\"
+  aggsandbox snapshot save bridged-usdc --force    # Overwrite an existing snapshot"
+    )]
+    Save {
+        /// Snapshot name
+        #[arg(help = "Snapshot name (letters, digits, '-' and '_')")]
+        name: String,
+        /// Free-form description shown by `snapshot list`
+        #[arg(short, long, help = "Description shown by `snapshot list`")]
+        description: Option<String>,
+        /// Overwrite an existing snapshot with the same name
+        #[arg(short, long, help = "Overwrite an existing snapshot")]
+        force: bool,
+    },
+    /// ⏪ Restore a saved snapshot
+    #[command(long_about = "Restore the networks to a saved snapshot.
+
+Uses evm_revert while the nodes that took the snapshot are still running and
+anvil_loadState otherwise. Deposit counts and contract addresses are compared
+with the snapshot afterwards and differences are reported.
+
+The bridge service index is not part of the snapshot: deposits made after the
+snapshot stay indexed until the bridge service is restarted.
+
+Examples:
+  aggsandbox snapshot restore bridged-usdc")]
+    Restore {
+        /// Snapshot name
+        #[arg(help = "Name of the snapshot to restore")]
+        name: String,
+    },
+    /// 📚 List saved snapshots
+    #[command(
+        long_about = "List saved snapshots with their networks and deposit counts.
+
+Examples:
+  aggsandbox snapshot list
+  aggsandbox snapshot list --output json"
+    )]
+    List,
+}
+
+/// State of one network when the snapshot was taken
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkSnapshot {
+    pub network_id: u64,
+    pub chain_id: u64,
+    pub block_number: u64,
+    /// Bridge deposit count, `None` when the bridge is not deployed
+    pub deposit_count: Option<u64>,
+    /// `evm_snapshot` ID, only valid while the node keeps running
+    pub evm_snapshot_id: Option<String>,
+    /// File holding the `anvil_dumpState` output, relative to the snapshot directory
+    pub state_file: String,
+}
+
+/// Contents of `metadata.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotMetadata {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Unix timestamp of the save
+    pub created_at: u64,
+    pub networks: Vec<NetworkSnapshot>,
+    /// Contract addresses per layer (`l1`, `l2`, `l3`)
+    pub contracts: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl SnapshotMetadata {
+    fn created_at_display(&self) -> String {
+        chrono::DateTime::from_timestamp(self.created_at as i64, 0)
+            .map(|time| time.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_else(|| self.created_at.to_string())
+    }
+}
+
+/// Snapshots stored on disk
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    root: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Store under `.aggsandbox/snapshots` of the working directory
+    pub fn default_location() -> Self {
+        Self::new(SNAPSHOT_DIR)
+    }
+
+    fn dir(&self, name: &str) -> PathBuf {
+        self.root.join(name)
+    }
+
+    pub fn exists(&self, name: &str) -> bool {
+        self.dir(name).join(METADATA_FILE).is_file()
+    }
+
+    /// Write a snapshot, `states` maps state file names to their contents
+    pub fn write(&self, metadata: &SnapshotMetadata, states: &[(String, String)]) -> Result<()> {
+        let dir = self.dir(&metadata.name);
+        fs::create_dir_all(&dir).map_err(|e| io_error(&dir, e))?;
+        for (file, state) in states {
+            let path = dir.join(file);
+            fs::write(&path, state).map_err(|e| io_error(&path, e))?;
+        }
+        let path = dir.join(METADATA_FILE);
+        let json = serde_json::to_string_pretty(metadata)
+            .map_err(|e| AggSandboxError::Other(format!("Failed to encode snapshot: {e}")))?;
+        fs::write(&path, json).map_err(|e| io_error(&path, e))
+    }
+
+    pub fn metadata(&self, name: &str) -> Result<SnapshotMetadata> {
+        let path = self.dir(name).join(METADATA_FILE);
+        let content = fs::read_to_string(&path).map_err(|_| {
+            snapshot_error(&format!(
+                "Snapshot '{name}' not found, see `aggsandbox snapshot list`"
+            ))
+        })?;
+        serde_json::from_str(&content).map_err(|e| {
+            snapshot_error(&format!(
+                "Invalid snapshot metadata {}: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    pub fn state(&self, name: &str, network: &NetworkSnapshot) -> Result<String> {
+        let path = self.dir(name).join(&network.state_file);
+        fs::read_to_string(&path).map_err(|e| io_error(&path, e))
+    }
+
+    /// Every readable snapshot, oldest first
+    pub fn list(&self) -> Vec<SnapshotMetadata> {
+        let mut snapshots: Vec<SnapshotMetadata> = fs::read_dir(&self.root)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                self.metadata(&name).ok()
+            })
+            .collect();
+        snapshots.sort_by(|a, b| (a.created_at, &a.name).cmp(&(b.created_at, &b.name)));
+        snapshots
+    }
+}
+
+/// Handle snapshot commands
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn handle_snapshot(subcommand: SnapshotCommands, output: OutputFormat) -> Result<()> {
+    let store = SnapshotStore::default_location();
+
+    match subcommand {
+        SnapshotCommands::Save {
+            name,
+            description,
+            force,
+        } => {
+            validate_snapshot_name(&name)?;
+            if store.exists(&name) && !force {
+                return Err(snapshot_error(&format!(
+                    "Snapshot '{name}' already exists, use --force to overwrite it"
+                )));
+            }
+            let config = Config::load()?;
+            let metadata = save_snapshot(&config, &store, &name, description).await?;
+            if output.is_json() {
+                UI::new(output).json(&serde_json::to_value(&metadata).unwrap_or_default());
+            } else {
+                ui::ui().success(&format!(
+                    "Saved snapshot '{name}' ({} network(s)) to {}",
+                    metadata.networks.len(),
+                    store.dir(&name).display()
+                ));
+            }
+            Ok(())
+        }
+        SnapshotCommands::Restore { name } => {
+            validate_snapshot_name(&name)?;
+            let config = Config::load()?;
+            let drift = restore_snapshot(&config, &store, &name).await?;
+            // Cached proofs may describe deposits the restore rewound
+            OptimizedApiClient::global().clear_cache_file().await;
+            if output.is_json() {
+                UI::new(output).json(&serde_json::json!({
+                    "name": name,
+                    "restored": true,
+                    "warnings": drift,
+                }));
+            } else {
+                for warning in &drift {
+                    ui::ui().warning(warning);
+                }
+                ui::ui().success(&format!("Restored snapshot '{name}'"));
+                ui::ui().tip(
+                    "The bridge service index is not rewound; restart it if it reports deposits made after the snapshot",
+                );
+            }
+            Ok(())
+        }
+        SnapshotCommands::List => {
+            print_snapshots(&store.list(), output);
+            Ok(())
+        }
+    }
+}
+
+/// Dump every configured network and write the snapshot
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+async fn save_snapshot(
+    config: &Config,
+    store: &SnapshotStore,
+    name: &str,
+    description: Option<String>,
+) -> Result<SnapshotMetadata> {
+    let mut networks = Vec::new();
+    let mut states = Vec::new();
+
+    for network_id in config.networks.network_ids() {
+        let provider = get_provider(config, network_id).await?;
+        let state: String = node_request(&provider, network_id, "anvil_dumpState", ()).await?;
+        let evm_snapshot_id: Option<String> =
+            node_request(&provider, network_id, "evm_snapshot", ())
+                .await
+                .map_err(|e| debug!(network_id, error = %e, "evm_snapshot unavailable"))
+                .ok();
+        let chain_id = provider
+            .get_chainid()
+            .await
+            .map_err(|e| rpc_error(&rpc_action(network_id, "eth_chainId"), e))?
+            .as_u64();
+        let block_number = provider
+            .get_block_number()
+            .await
+            .map_err(|e| rpc_error(&rpc_action(network_id, "eth_blockNumber"), e))?
+            .as_u64();
+
+        let state_file = format!("network-{network_id}.state");
+        info!(network_id, block_number, "Dumped network state");
+        networks.push(NetworkSnapshot {
+            network_id,
+            chain_id,
+            block_number,
+            deposit_count: deposit_count(config, &provider, network_id).await,
+            evm_snapshot_id,
+            state_file: state_file.clone(),
+        });
+        states.push((state_file, state));
+    }
+
+    let metadata = SnapshotMetadata {
+        name: name.to_string(),
+        description,
+        created_at: chrono::Utc::now().timestamp() as u64,
+        networks,
+        contracts: contract_addresses(config),
+    };
+    store.write(&metadata, &states)?;
+    Ok(metadata)
+}
+
+/// Restore every network of a snapshot, returning the differences found afterwards
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+async fn restore_snapshot(
+    config: &Config,
+    store: &SnapshotStore,
+    name: &str,
+) -> Result<Vec<String>> {
+    let mut metadata = store.metadata(name)?;
+    let mut warnings = Vec::new();
+
+    for network in &mut metadata.networks {
+        let network_id = network.network_id;
+        validate_network_id(network_id, "Snapshot network")?;
+        let provider = get_provider(config, network_id).await?;
+
+        let chain_id = provider
+            .get_chainid()
+            .await
+            .map_err(|e| rpc_error(&rpc_action(network_id, "eth_chainId"), e))?
+            .as_u64();
+        if chain_id != network.chain_id {
+            return Err(snapshot_error(&format!(
+                "Network {network_id} runs chain ID {chain_id} but the snapshot was taken on chain ID {}",
+                network.chain_id
+            )));
+        }
+
+        // evm_revert consumes the snapshot, so take a new one to allow restoring again
+        let reverted = match &network.evm_snapshot_id {
+            Some(id) => node_request::<_, bool>(&provider, network_id, "evm_revert", [id])
+                .await
+                .unwrap_or(false),
+            None => false,
+        };
+        if reverted {
+            info!(network_id, "Reverted to EVM snapshot");
+            network.evm_snapshot_id = node_request(&provider, network_id, "evm_snapshot", ())
+                .await
+                .ok();
+        } else {
+            let state = store.state(name, network)?;
+            let loaded: bool =
+                node_request(&provider, network_id, "anvil_loadState", [state]).await?;
+            if !loaded {
+                return Err(snapshot_error(&format!(
+                    "anvil_loadState was rejected by network {network_id}"
+                )));
+            }
+            info!(network_id, "Loaded dumped state");
+        }
+
+        let current = deposit_count(config, &provider, network_id).await;
+        if current != network.deposit_count {
+            warnings.push(format!(
+                "Network {network_id} ({}): bridge deposit count is {} but was {} when the snapshot was taken",
+                get_network_name(network_id),
+                format_count(current),
+                format_count(network.deposit_count)
+            ));
+        }
+    }
+
+    warnings.extend(contract_drift(
+        &metadata.contracts,
+        &contract_addresses(config),
+    ));
+
+    // Persist the replacement evm_snapshot IDs
+    store.write(&metadata, &[])?;
+    Ok(warnings)
+}
+
+async fn deposit_count(
+    config: &Config,
+    provider: &Arc<Provider<Http>>,
+    network_id: u64,
+) -> Option<u64> {
+    let address = get_bridge_contract_address(config, network_id).ok()?;
+    let bridge = BridgeContract::new(address, Arc::clone(provider));
+    bridge
+        .deposit_count()
+        .call()
+        .await
+        .ok()
+        .map(|count| count.as_u64())
+}
+
+fn contract_addresses(config: &Config) -> BTreeMap<String, BTreeMap<String, String>> {
+    [
+        ("l1", &config.contracts.l1_contracts),
+        ("l2", &config.contracts.l2_contracts),
+        ("l3", &config.contracts.l3_contracts),
+    ]
+    .into_iter()
+    .filter(|(_, contracts)| !contracts.is_empty())
+    .map(|(layer, contracts)| {
+        let contracts = contracts
+            .iter()
+            .map(|(name, address)| (name.clone(), address.as_str().to_string()))
+            .collect();
+        (layer.to_string(), contracts)
+    })
+    .collect()
+}
+
+/// Contracts whose configured address differs from the snapshot
+fn contract_drift(
+    saved: &BTreeMap<String, BTreeMap<String, String>>,
+    current: &BTreeMap<String, BTreeMap<String, String>>,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for (layer, contracts) in saved {
+        for (name, address) in contracts {
+            let configured = current.get(layer).and_then(|c| c.get(name));
+            if configured.is_none_or(|configured| !configured.eq_ignore_ascii_case(address)) {
+                warnings.push(format!(
+                    "{name} on {layer} was {address} in the snapshot but is configured as {}; update .env to match",
+                    configured.map_or("unset", String::as_str)
+                ));
+            }
+        }
+    }
+    warnings
+}
+
+fn print_snapshots(snapshots: &[SnapshotMetadata], output: OutputFormat) {
+    let ui = UI::new(output);
+    if output.is_json() {
+        ui.json(&serde_json::to_value(snapshots).unwrap_or_default());
+        return;
+    }
+    if snapshots.is_empty() {
+        ui.info("No snapshots saved yet, create one with `aggsandbox snapshot save <name>`");
+        return;
+    }
+
+    let summaries: Vec<String> = snapshots
+        .iter()
+        .map(|snapshot| {
+            let networks: Vec<String> = snapshot
+                .networks
+                .iter()
+                .map(|network| {
+                    format!(
+                        "n{} block {} deposits {}",
+                        network.network_id,
+                        network.block_number,
+                        format_count(network.deposit_count)
+                    )
+                })
+                .collect();
+            let mut summary = format!(
+                "{} | {}",
+                snapshot.created_at_display(),
+                networks.join(", ")
+            );
+            if let Some(description) = &snapshot.description {
+                summary.push_str(&format!(" | {description}"));
+            }
+            summary
+        })
+        .collect();
+    let rows: Vec<(&str, &str)> = snapshots
+        .iter()
+        .zip(&summaries)
+        .map(|(snapshot, summary)| (snapshot.name.as_str(), summary.as_str()))
+        .collect();
+    ui.table("💾 Snapshots", &rows);
+}
+
+fn format_count(count: Option<u64>) -> String {
+    count.map_or_else(|| "n/a".to_string(), |count| count.to_string())
+}
+
+fn validate_snapshot_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(snapshot_error(&format!(
+            "Invalid snapshot name '{name}': use letters, digits, '-' and '_'"
+        )))
+    }
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> AggSandboxError {
+    AggSandboxError::Other(format!("Failed to access {}: {error}", path.display()))
+}
+
+fn snapshot_error(message: &str) -> AggSandboxError {
+    AggSandboxError::Config(crate::error::ConfigError::validation_failed(message))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    fn metadata(name: &str, created_at: u64) -> SnapshotMetadata {
+        SnapshotMetadata {
+            name: name.to_string(),
+            description: None,
+            created_at,
+            networks: vec![NetworkSnapshot {
+                network_id: 0,
+                chain_id: 1,
+                block_number: 42,
+                deposit_count: Some(3),
+                evm_snapshot_id: Some("0x1".to_string()),
+                state_file: "network-0.state".to_string(),
+            }],
+            contracts: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_snapshot_name() {
+        assert!(validate_snapshot_name("bridged-usdc_1").is_ok());
+        assert!(validate_snapshot_name("").is_err());
+        assert!(validate_snapshot_name("../escape").is_err());
+        assert!(validate_snapshot_name("with space").is_err());
+    }
+
+    #[test]
+    fn test_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path());
+        let saved = metadata("later", 200);
+
+        store
+            .write(
+                &saved,
+                &[("network-0.state".to_string(), "0x1f8b".to_string())],
+            )
+            .unwrap();
+        store.write(&metadata("earlier", 100), &[]).unwrap();
+
+        assert!(store.exists("later"));
+        assert_eq!(store.metadata("later").unwrap(), saved);
+        assert_eq!(store.state("later", &saved.networks[0]).unwrap(), "0x1f8b");
+        let names: Vec<String> = store.list().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, ["earlier", "later"]);
+        assert!(store.metadata("missing").is_err());
+    }
+
+    #[test]
+    fn test_contract_drift() {
+        let layer = |address: &str| {
+            BTreeMap::from([(
+                "l1".to_string(),
+                BTreeMap::from([("PolygonZkEVMBridge".to_string(), address.to_string())]),
+            )])
+        };
+        let saved = layer("0x63ee8865a8b25919b5103d02586aaaf078ee9102");
+
+        assert!(
+            contract_drift(&saved, &layer("0x63EE8865A8B25919B5103D02586AAAF078EE9102")).is_empty()
+        );
+        assert_eq!(
+            contract_drift(&saved, &layer("0x0000000000000000000000000000000000000001")).len(),
+            1
+        );
+        assert_eq!(contract_drift(&saved, &BTreeMap::new()).len(), 1);
+    }
+}
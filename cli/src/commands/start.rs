@@ -1,14 +1,36 @@
+use crate::commands::readiness;
+use crate::commands::registry;
+use crate::commands::start_state::{self, PartialStart, StartStateStore};
 use crate::config::Config;
+use crate::error::exit_code;
 use crate::logs;
 use crate::progress::{MultiStepProgress, ProgressBar, StatusReporter};
 use colored::*;
+use std::path::Path;
 use std::time::{Duration, Instant};
 use tracing::{error, info};
 
 /// Handle the start command
 #[allow(clippy::disallowed_methods)] // Allow std::process::exit and tracing macros
-pub async fn handle_start(detach: bool, build: bool, fork: bool, multi_l2: bool, claim_all: bool) {
-    handle_start_async(detach, build, fork, multi_l2, claim_all).await;
+pub async fn handle_start(
+    detach: bool,
+    build: bool,
+    fork: bool,
+    l2_count: u64,
+    claim_all: bool,
+    offline: bool,
+    wait_timeout: u64,
+) {
+    handle_start_async(
+        detach,
+        build,
+        fork,
+        l2_count,
+        claim_all,
+        offline,
+        wait_timeout,
+    )
+    .await;
 }
 
 /// Async implementation of start command with progress tracking
@@ -17,12 +39,20 @@ async fn handle_start_async(
     detach: bool,
     build: bool,
     fork: bool,
-    multi_l2: bool,
+    l2_count: u64,
     claim_all: bool,
+    offline: bool,
+    wait_timeout: u64,
 ) {
     use crate::docker::{execute_docker_command, SandboxConfig};
 
     let reporter = StatusReporter::new();
+    let multi_l2 = l2_count > 1;
+
+    // Responses cached from a previous sandbox describe other chains
+    crate::api_client::OptimizedApiClient::global()
+        .clear_cache_file()
+        .await;
 
     // Setup progress tracking
     let steps = vec![
@@ -40,20 +70,24 @@ async fn handle_start_async(
         } else {
             "Waiting for contract deployment...".to_string()
         },
+        "Waiting for services to become healthy".to_string(),
     ];
 
     let mut progress = MultiStepProgress::new(steps);
 
     // Create sandbox configuration
-    let config = SandboxConfig::new(fork, multi_l2, claim_all);
+    let config = SandboxConfig::new(fork, multi_l2, claim_all)
+        .with_l2_count(l2_count)
+        .with_offline(offline);
 
     info!(
         mode = %config.mode_description(),
         detach = detach,
         build = build,
         fork = fork,
-        multi_l2 = multi_l2,
+        l2_count = l2_count,
         claim_all = claim_all,
+        offline = offline,
         "Starting Agglayer sandbox environment"
     );
 
@@ -79,11 +113,14 @@ async fn handle_start_async(
             reporter
                 .tip("Please set the required fork URLs in your .env file")
                 .await;
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
         progress.complete_step(handle);
     }
 
+    let start_state = StartStateStore::default_location();
+    start_state::resume_partial_start(&start_state, &config);
+
     // Step 2: Setup environment
     if let Some(handle) = progress.start_step("Setting up environment") {
         // Display fork URLs if in fork mode
@@ -92,6 +129,22 @@ async fn handle_start_async(
             display_fork_urls(multi_l2);
         }
 
+        // More than two L2s run from a generated compose file
+        if let Err(e) = prepare_scaled_services(l2_count) {
+            progress.fail_step(handle, &e.to_string());
+            error!(error = %e, "Failed to generate the compose file");
+            reporter.error(&e.to_string()).await;
+            std::process::exit(e.exit_code());
+        }
+        if config.is_generated() {
+            reporter
+                .info(&format!(
+                    "Generated {} with {l2_count} L2 chains",
+                    crate::docker::GENERATED_COMPOSE_FILE
+                ))
+                .await;
+        }
+
         // Create Docker builder with proper configuration
         info!("Creating Docker configuration");
         let docker_builder = config.create_docker_builder();
@@ -117,10 +170,19 @@ async fn handle_start_async(
             if detach {
                 // Execute in detached mode with progress
                 info!("Executing Docker command in detached mode");
+                let mut partial = PartialStart::new(&config, "Starting services");
+                start_state.save(&partial);
+                let interrupt_handler =
+                    start_state::spawn_interrupt_handler(start_state.clone(), config.clone());
+
                 let mut progress_bar = ProgressBar::new("Starting sandbox services...".to_string());
                 let progress_handle = progress_bar.start().await;
 
                 if execute_docker_command(cmd, true).is_err() {
+                    if start_state::interrupted() {
+                        // The interrupt handler rolls back or keeps the partial start and exits
+                        std::future::pending::<()>().await;
+                    }
                     progress_handle
                         .finish_with_error("Failed to start sandbox services")
                         .await;
@@ -129,7 +191,7 @@ async fn handle_start_async(
                     reporter
                         .error("Failed to start sandbox in detached mode")
                         .await;
-                    std::process::exit(1);
+                    std::process::exit(exit_code::DOCKER);
                 }
 
                 progress_handle
@@ -150,6 +212,8 @@ async fn handle_start_async(
 
                     if let Some(deploy_handle) = progress.start_step(wait_msg) {
                         info!("Waiting for contract deployment services to complete");
+                        partial.stage = wait_msg.to_string();
+                        start_state.save(&partial);
 
                         // Smart wait for deployment - check for contract addresses in .env file
                         info!("Waiting for contract deployment to complete");
@@ -166,6 +230,38 @@ async fn handle_start_async(
                         info!("Contract deployment completed successfully");
                     }
 
+                    interrupt_handler.abort();
+                    start_state.clear();
+
+                    if let Some(ready_handle) =
+                        progress.start_step("Waiting for services to become healthy")
+                    {
+                        if wait_timeout == 0 {
+                            progress.skip_step(ready_handle, "--wait-timeout 0");
+                        } else {
+                            let readiness = match Config::load_with_env_refresh(true) {
+                                Ok(config) => {
+                                    readiness::wait_until_ready(
+                                        &config,
+                                        Duration::from_secs(wait_timeout),
+                                    )
+                                    .await
+                                }
+                                Err(e) => Err(e),
+                            };
+                            if let Err(e) = readiness {
+                                progress.fail_step(ready_handle, &e.to_string());
+                                error!(error = %e, "Sandbox services did not become healthy");
+                                reporter.error(&e.to_string()).await;
+                                reporter
+                                    .tip("Check the services with `aggsandbox status` and `aggsandbox logs <service>`")
+                                    .await;
+                                std::process::exit(e.exit_code());
+                            }
+                            progress.complete_step(ready_handle);
+                        }
+                    }
+
                     // Display success message
                     let success_msg = match (fork, multi_l2) {
                         (true, true) => "Multi-L2 sandbox started in fork mode (detached)",
@@ -186,6 +282,7 @@ async fn handle_start_async(
                 // Load config with environment refresh to pick up deployed contract addresses
                 info!("Loading configuration for info display after contract deployment");
                 if let Ok(config) = Config::load_with_env_refresh(true) {
+                    publish_registry(&config, &reporter).await;
                     match (fork, multi_l2) {
                         (_, true) => {
                             info!("Displaying multi-L2 configuration info");
@@ -215,7 +312,7 @@ async fn handle_start_async(
                     reporter
                         .error("Failed to start sandbox in foreground mode")
                         .await;
-                    std::process::exit(1);
+                    std::process::exit(exit_code::DOCKER);
                 } else {
                     progress.complete_step(handle);
                     info!("Sandbox stopped gracefully");
@@ -226,6 +323,51 @@ async fn handle_start_async(
     }
 }
 
+/// Write or remove the generated compose file and point the CLI at every L2
+///
+/// The endpoints of the L2s beyond the second one go to `.env`, which the
+/// contract deployer updates in place, so later commands pick them up.
+fn prepare_scaled_services(l2_count: u64) -> crate::error::Result<()> {
+    use crate::docker::{scaled_env, update_env_file, write_generated_compose};
+
+    write_generated_compose(l2_count)?;
+    let vars = scaled_env(l2_count);
+    if vars.is_empty() {
+        return Ok(());
+    }
+    update_env_file(Path::new(".env"), &vars).map_err(|e| {
+        crate::error::ConfigError::validation_failed(&format!("Failed to update .env: {e}"))
+    })?;
+    for (key, value) in &vars {
+        std::env::set_var(key, value);
+    }
+    Ok(())
+}
+
+/// Publish the deployed contracts to the on-chain registry of every network
+///
+/// Failures only warn: the sandbox is usable without the registry.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+async fn publish_registry(config: &Config, reporter: &StatusReporter) {
+    for network_id in config.networks.network_ids() {
+        match registry::publish(config, network_id).await {
+            Ok((address, written)) => info!(
+                network_id,
+                address = ?address,
+                updated = written.len(),
+                "Published contracts to the registry"
+            ),
+            Err(e) => {
+                reporter
+                    .warning(&format!(
+                        "Could not publish contracts to the registry on network {network_id}: {e}"
+                    ))
+                    .await
+            }
+        }
+    }
+}
+
 #[allow(clippy::disallowed_methods)] // Allow tracing macros
 fn display_fork_urls(multi_l2: bool) {
     let fork_mainnet = std::env::var("FORK_URL_MAINNET").unwrap_or_default();
@@ -259,6 +401,7 @@ fn display_fork_urls(multi_l2: bool) {
 
 /// Wait for contract deployment by checking if contract addresses appear in .env file
 /// Returns true if contracts are deployed within timeout, false if timeout exceeded
+#[allow(clippy::disallowed_methods)] // Allow std::thread::sleep while polling
 fn wait_for_contract_deployment(timeout_secs: u64) -> bool {
     let start_time = Instant::now();
     let timeout_duration = Duration::from_secs(timeout_secs);
@@ -0,0 +1,264 @@
+//! State of a `start` that did not run to completion
+//!
+//! A detached `start` records its mode and progress in
+//! `.aggsandbox/start.json` while it runs and removes the file once the
+//! sandbox is up. When Ctrl-C interrupts it, the user can roll the partially
+//! created containers and volumes back or keep them; a kept partial start is
+//! picked up by the next `start`, which reuses the containers of the same mode
+//! and removes those of a different one instead of failing on name conflicts.
+
+use crate::docker::{execute_docker_command, SandboxConfig};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::ui;
+
+const START_STATE_FILE: &str = ".aggsandbox/start.json";
+
+/// Set once Ctrl-C interrupted the running start
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Mode and progress of a start in flight
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PartialStart {
+    pub fork: bool,
+    pub multi_l2: bool,
+    pub claim_all: bool,
+    /// L2 chains started, 0 in states written before `--scale` existed
+    #[serde(default)]
+    pub l2_count: u64,
+    /// Last step the start reached
+    pub stage: String,
+    /// Whether the start was interrupted rather than still running or failed
+    #[serde(default)]
+    pub interrupted: bool,
+    pub started_at: String,
+}
+
+impl PartialStart {
+    pub fn new(config: &SandboxConfig, stage: &str) -> Self {
+        Self {
+            fork: config.fork_mode,
+            multi_l2: config.multi_l2_mode,
+            claim_all: config.claim_all,
+            l2_count: config.l2_count,
+            stage: stage.to_string(),
+            interrupted: false,
+            started_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Sandbox configuration the partial start ran with
+    pub fn sandbox_config(&self) -> SandboxConfig {
+        let config = SandboxConfig::new(self.fork, self.multi_l2, self.claim_all);
+        if self.l2_count == 0 {
+            config
+        } else {
+            config.with_l2_count(self.l2_count)
+        }
+    }
+
+    /// Whether `config` uses the same compose files, so its containers can be reused
+    pub fn same_services(&self, config: &SandboxConfig) -> bool {
+        self.sandbox_config().l2_count == config.l2_count
+    }
+}
+
+/// Partial start state stored in the working directory
+#[derive(Debug, Clone)]
+pub struct StartStateStore {
+    path: PathBuf,
+}
+
+impl StartStateStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Store at `.aggsandbox/start.json` of the working directory
+    pub fn default_location() -> Self {
+        Self::new(START_STATE_FILE)
+    }
+
+    pub fn load(&self) -> Option<PartialStart> {
+        fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Record the progress of the running start
+    #[allow(clippy::disallowed_methods)] // Allow tracing macros
+    pub fn save(&self, state: &PartialStart) {
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| e.to_string())
+            .and_then(|_| serde_json::to_string_pretty(state).map_err(|e| e.to_string()))
+            .and_then(|json| fs::write(&self.path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            debug!(path = %self.path.display(), error = %e, "Failed to record start state");
+        }
+    }
+
+    /// Forget the partial start once the sandbox is up or removed
+    pub fn clear(&self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Prepare for a start, dealing with the leftovers of an interrupted one
+///
+/// Containers of the same mode are reused by `docker compose up`; those of a
+/// different mode would collide on container names and are removed first.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub fn resume_partial_start(store: &StartStateStore, config: &SandboxConfig) {
+    let Some(previous) = store.load() else {
+        return;
+    };
+    let what = if previous.interrupted {
+        "interrupted"
+    } else {
+        "unfinished"
+    };
+
+    if previous.same_services(config) {
+        ui::ui().info(&format!(
+            "Resuming the {what} start of {} (reached '{}'); existing containers are reused",
+            previous.started_at, previous.stage
+        ));
+        return;
+    }
+
+    ui::ui().warning(&format!(
+        "Removing the containers of the {what} start in {} before starting in {}",
+        previous.sandbox_config().mode_description(),
+        config.mode_description()
+    ));
+    let cmd = previous
+        .sandbox_config()
+        .create_docker_builder()
+        .build_down_command(false);
+    if let Err(e) = execute_docker_command(cmd, true) {
+        warn!(error = %e, "Failed to remove the containers of the partial start");
+    }
+    store.clear();
+}
+
+/// Handle Ctrl-C while a detached start is running
+///
+/// The handler asks whether to roll the partially created containers and
+/// volumes back, then exits the process. Abort the returned task once the
+/// start completed.
+#[allow(clippy::disallowed_methods)] // Allow std::process::exit after the rollback
+pub fn spawn_interrupt_handler(store: StartStateStore, config: SandboxConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        let _ = tokio::task::spawn_blocking(move || handle_interrupt(&store, &config)).await;
+        std::process::exit(130);
+    })
+}
+
+/// Whether Ctrl-C interrupted the running start
+///
+/// A Docker command failing because of the interrupt is not reported as a
+/// start failure; the interrupt handler takes over instead.
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+#[cfg(feature = "tui")]
+fn confirm_roll_back() -> bool {
+    dialoguer::Confirm::new()
+        .with_prompt("Remove the partially created containers and volumes?")
+        .default(false)
+        .interact()
+        .unwrap_or(false)
+}
+
+/// Without the `tui` feature the partial start is kept, as when declining
+#[cfg(not(feature = "tui"))]
+fn confirm_roll_back() -> bool {
+    false
+}
+
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+fn handle_interrupt(store: &StartStateStore, config: &SandboxConfig) {
+    ui::ui().blank_line();
+    ui::ui().warning("Start interrupted; the sandbox is only partially started");
+
+    let roll_back = atty::is(atty::Stream::Stdin) && confirm_roll_back();
+
+    if roll_back {
+        let cmd = config.create_docker_builder().build_down_command(true);
+        match execute_docker_command(cmd, true) {
+            Ok(()) => {
+                store.clear();
+                ui::ui().success("Partial start rolled back");
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to roll back the partial start");
+                ui::ui().error(&format!("Failed to roll back the partial start: {e}"));
+                mark_interrupted(store, config);
+            }
+        }
+    } else {
+        mark_interrupted(store, config);
+        ui::ui().tip(
+            "Run 'aggsandbox start' again to resume, or 'aggsandbox stop --volumes' to remove it",
+        );
+    }
+}
+
+fn mark_interrupted(store: &StartStateStore, config: &SandboxConfig) {
+    let mut state = store
+        .load()
+        .unwrap_or_else(|| PartialStart::new(config, "Starting services"));
+    state.interrupted = true;
+    store.save(&state);
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = StartStateStore::new(dir.path().join(".aggsandbox").join("start.json"));
+        assert!(store.load().is_none());
+
+        let config = SandboxConfig::new(false, true, false);
+        let state = PartialStart::new(&config, "Starting services");
+        store.save(&state);
+        assert_eq!(store.load(), Some(state));
+
+        store.clear();
+        assert!(store.load().is_none());
+    }
+
+    #[test]
+    fn test_same_services() {
+        let state = PartialStart::new(
+            &SandboxConfig::new(false, false, false),
+            "Starting services",
+        );
+        assert!(state.same_services(&SandboxConfig::new(true, false, true)));
+        assert!(!state.same_services(&SandboxConfig::new(false, true, false)));
+
+        let scaled = PartialStart::new(
+            &SandboxConfig::new(false, false, false).with_l2_count(4),
+            "Starting services",
+        );
+        assert!(scaled.sandbox_config().is_generated());
+        assert!(!scaled.same_services(&SandboxConfig::new(false, true, false)));
+    }
+}
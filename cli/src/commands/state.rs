@@ -0,0 +1,108 @@
+//! Maintenance of the local state under `.aggsandbox/`
+//!
+//! Benchmarks and soak tests against a long-running sandbox index thousands of
+//! deposits and claims. `state prune` moves the old ones out of the local
+//! bridge index into an archive so `--source local` lookups stay fast.
+
+use crate::commands::bridge::common::get_network_name;
+use crate::commands::bridge::local_index::{self, parse_age, IndexStore, RETENTION_ENV};
+use crate::commands::index::selected_networks;
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui::{OutputFormat, UI};
+use crate::validation::parse_network_id;
+use std::time::Duration;
+
+/// State subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum StateCommands {
+    /// 🧹 Prune old records from the local bridge index
+    #[command(
+        long_about = "Remove deposits, claims and exit-root updates indexed longer ago than
+--older-than from the local bridge index.
+
+Pruned records are appended to .aggsandbox/index/archive/network-<id>.jsonl
+unless --discard is given. Without --older-than the INDEX_RETENTION policy is
+used; when it is set, every index sync also prunes on its own.
+
+Examples:
+  aggsandbox state prune --older-than 7d
+  aggsandbox state prune --older-than 12h -n 1 --discard
+  INDEX_RETENTION=3d aggsandbox state prune"
+    )]
+    Prune {
+        /// Maximum age of the records to keep
+        #[arg(
+            long,
+            value_parser = parse_age,
+            help = "Prune records indexed longer ago than this, e.g. 90m, 12h, 7d or 2w (default: INDEX_RETENTION)"
+        )]
+        older_than: Option<Duration>,
+        /// Networks to prune
+        #[arg(short = 'n', long = "network-id", alias = "network", value_parser = parse_network_id, help = "Network ID or alias to prune, repeatable (defaults to every configured network)")]
+        network_ids: Vec<u64>,
+        /// Drop the pruned records instead of archiving them
+        #[arg(long, help = "Delete pruned records instead of archiving them")]
+        discard: bool,
+    },
+}
+
+/// Handle state subcommands
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn handle_state(subcommand: StateCommands, output: OutputFormat) -> Result<()> {
+    let config = Config::load()?;
+    let ui = UI::new(output);
+
+    match subcommand {
+        StateCommands::Prune {
+            older_than,
+            network_ids,
+            discard,
+        } => {
+            let max_age = match older_than {
+                Some(age) => age,
+                None => local_index::retention()?.ok_or_else(|| {
+                    AggSandboxError::Other(format!(
+                        "Give the age of the records to prune with --older-than or set {RETENTION_ENV}"
+                    ))
+                })?,
+            };
+            let store = IndexStore::default_location();
+
+            let mut networks = Vec::new();
+            for network_id in selected_networks(&config, network_ids)? {
+                let pruned = store.prune(network_id, max_age, discard)?;
+                networks.push(serde_json::json!({
+                    "network_id": network_id,
+                    "deposits": pruned.deposits.len(),
+                    "claims": pruned.claims.len(),
+                    "exit_roots": pruned.exit_roots.len(),
+                }));
+                if !ui.is_json() && !pruned.is_empty() {
+                    ui.success(&format!(
+                        "Network {network_id} ({}): pruned {} deposit(s), {} claim(s), {} exit root update(s)",
+                        get_network_name(network_id),
+                        pruned.deposits.len(),
+                        pruned.claims.len(),
+                        pruned.exit_roots.len()
+                    ));
+                }
+            }
+
+            if ui.is_json() {
+                ui.json(&serde_json::json!({
+                    "older_than_secs": max_age.as_secs(),
+                    "archived": !discard,
+                    "networks": networks,
+                }));
+            } else if networks.iter().all(|network| {
+                network["deposits"] == 0 && network["claims"] == 0 && network["exit_roots"] == 0
+            }) {
+                ui.info("No indexed records old enough to prune");
+            } else if !discard {
+                ui.info("Pruned records were archived in .aggsandbox/index/archive/");
+            }
+            Ok(())
+        }
+    }
+}
@@ -1,21 +1,467 @@
-use crate::ui;
+//! Sandbox status (`aggsandbox status`)
+//!
+//! Combines what `docker compose ps` knows about the containers (state, health
+//! check, published ports, image digest) with what only the services can tell:
+//! the head block of every chain, whether each network's bridge service
+//! answers and how far it is behind. Rendered as tables, or as one JSON
+//! document for monitoring scripts.
+
+use crate::commands::bridge::common::get_network_name;
+use crate::commands::bridge::get_provider;
+use crate::commands::bridge::indexing::{get_all_indexing_status, IndexingStatus};
+use crate::commands::readiness::{self, Service, PROBE_TIMEOUT};
+use crate::config::Config;
+use crate::error::{exit_code, Result};
+use crate::types::NetworkId;
+use crate::ui::{self, OutputFormat, UI};
+use ethers::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::Command;
+
+/// Container of one compose service
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ServiceStatus {
+    pub service: String,
+    pub container: String,
+    /// Container state, e.g. `running` or `exited`
+    pub state: String,
+    /// Health check result, `None` for services without a health check
+    pub health: Option<String>,
+    /// Published ports as `host:port->container/protocol`
+    pub ports: Vec<String>,
+    pub image: String,
+    /// Registry digest of the image, or its local ID when it was built locally
+    pub image_digest: Option<String>,
+}
+
+impl ServiceStatus {
+    /// Parse one entry of `docker compose ps --format json`
+    pub fn from_ps(entry: &Value) -> Self {
+        let text = |key: &str| entry[key].as_str().unwrap_or_default().to_string();
+        let mut publishers: Vec<(&str, u64, u64, &str)> = entry["Publishers"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|publisher| {
+                (
+                    publisher["URL"].as_str().unwrap_or_default(),
+                    publisher["PublishedPort"].as_u64().unwrap_or(0),
+                    publisher["TargetPort"].as_u64().unwrap_or(0),
+                    publisher["Protocol"].as_str().unwrap_or("tcp"),
+                )
+            })
+            .filter(|(_, published, _, _)| *published > 0)
+            .collect();
+        // IPv4 and IPv6 bindings of the same port are listed separately
+        publishers
+            .dedup_by_key(|(_, published, target, protocol)| (*published, *target, *protocol));
+        let ports = publishers
+            .iter()
+            .map(|(url, published, target, protocol)| {
+                format!("{url}:{published}->{target}/{protocol}")
+            })
+            .collect();
+
+        Self {
+            service: text("Service"),
+            container: text("Name"),
+            state: text("State"),
+            health: Some(text("Health")).filter(|health| !health.is_empty()),
+            ports,
+            image: text("Image"),
+            image_digest: None,
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.state == "running" && self.health.as_deref().is_none_or(|h| h == "healthy")
+    }
+}
+
+/// Head of one chain
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainStatus {
+    pub network_id: u64,
+    pub name: String,
+    pub rpc_url: String,
+    /// Latest block, `None` when the node did not answer
+    pub head_block: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Reachability of one network's bridge service
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiStatus {
+    pub network_id: u64,
+    pub url: String,
+    pub reachable: bool,
+    pub error: Option<String>,
+}
+
+/// Everything `status` reports
+#[derive(Debug, Clone, Serialize)]
+pub struct SandboxStatus {
+    pub running: bool,
+    pub services: Vec<ServiceStatus>,
+    pub chains: Vec<ChainStatus>,
+    pub apis: Vec<ApiStatus>,
+    pub indexing: Vec<IndexingStatus>,
+}
 
 /// Handle the status command
 #[allow(clippy::disallowed_methods)] // Allow std::process::exit for command handler
-pub fn handle_status() {
-    use crate::docker::{create_auto_docker_builder, execute_docker_command_with_output};
+pub async fn handle_status(output: OutputFormat) {
+    let status = match sandbox_status().await {
+        Ok(status) => status,
+        Err(e) => {
+            ui::ui().error(&format!("Failed to get service status: {e}"));
+            std::process::exit(exit_code::DOCKER);
+        }
+    };
+
+    if output.is_json() {
+        UI::new(output).json(&serde_json::to_value(&status).unwrap_or_default());
+        return;
+    }
 
     ui::ui().info("📊 Sandbox service status:");
+    if !status.running {
+        ui::ui().warning(
+            "No sandbox services are running; start them with `aggsandbox start --detach`",
+        );
+        return;
+    }
+    println!("{}", render_services(&status.services));
+    let unhealthy: Vec<&str> = status
+        .services
+        .iter()
+        .filter(|service| !service.is_healthy())
+        .map(|service| service.service.as_str())
+        .collect();
+    if !unhealthy.is_empty() {
+        ui::ui().warning(&format!(
+            "Unhealthy services: {}; check them with `aggsandbox logs <service>`",
+            unhealthy.join(", ")
+        ));
+    }
+
+    ui::ui().blank_line();
+    ui::ui().info("⛓️  Chains:");
+    for chain in &status.chains {
+        match chain.head_block {
+            Some(block) => ui::ui().success(&format!(
+                "Network {} ({}) at block {block} - {}",
+                chain.network_id, chain.name, chain.rpc_url
+            )),
+            None => ui::ui().error(&format!(
+                "Network {} ({}) unreachable at {}: {}",
+                chain.network_id,
+                chain.name,
+                chain.rpc_url,
+                chain.error.as_deref().unwrap_or("no answer")
+            )),
+        }
+    }
+
+    ui::ui().blank_line();
+    ui::ui().info("🌉 Bridge service:");
+    for api in &status.apis {
+        if api.reachable {
+            ui::ui().success(&format!(
+                "Network {} reachable at {}",
+                api.network_id, api.url
+            ));
+        } else {
+            ui::ui().error(&format!(
+                "Network {} unreachable at {}: {}",
+                api.network_id,
+                api.url,
+                api.error.as_deref().unwrap_or("no answer")
+            ));
+        }
+    }
+
+    display_indexing_status(&status.indexing);
+}
+
+/// Status of the sandbox, as printed by `status --output json`
+pub async fn sandbox_status() -> Result<SandboxStatus> {
+    let mut services = compose_services()?;
+    let digests = image_digests(&services);
+    for service in &mut services {
+        service.image_digest = digests.get(&service.image).cloned();
+    }
+
+    let running = !services.is_empty();
+    let config = Config::load().ok().filter(|_| running);
+    let (chains, apis, indexing) = match &config {
+        Some(config) => (
+            chain_statuses(config).await,
+            api_statuses(config).await,
+            get_all_indexing_status(config).await,
+        ),
+        None => Default::default(),
+    };
+
+    Ok(SandboxStatus {
+        running,
+        services,
+        chains,
+        apis,
+        indexing,
+    })
+}
+
+/// Containers `docker compose ps` lists for the sandbox
+#[cfg(feature = "docker")]
+fn compose_services() -> Result<Vec<ServiceStatus>> {
+    use crate::docker::{create_auto_docker_builder, execute_docker_command_with_output};
+
+    let cmd = create_auto_docker_builder().build_ps_json_command();
+    Ok(
+        crate::docker::parse_ps_json(&execute_docker_command_with_output(cmd)?)
+            .iter()
+            .map(ServiceStatus::from_ps)
+            .collect(),
+    )
+}
 
-    // Create Docker builder that auto-detects configuration
-    let docker_builder = create_auto_docker_builder();
-    let cmd = docker_builder.build_ps_command();
+/// Containers cannot be listed without the `docker` feature
+#[cfg(not(feature = "docker"))]
+fn compose_services() -> Result<Vec<ServiceStatus>> {
+    Err(crate::error::DockerError::command_failed("ps", "built without the docker feature").into())
+}
+
+/// Running services and bridge service indexing as a JSON document
+pub async fn status_json() -> Result<Value> {
+    Ok(serde_json::to_value(sandbox_status().await?)?)
+}
+
+/// Digest of every image the services run, by image reference
+fn image_digests(services: &[ServiceStatus]) -> HashMap<String, String> {
+    let mut images: Vec<&str> = services
+        .iter()
+        .map(|service| service.image.as_str())
+        .filter(|image| !image.is_empty())
+        .collect();
+    images.sort();
+    images.dedup();
+    if images.is_empty() {
+        return HashMap::new();
+    }
+
+    let Ok(output) = Command::new("docker")
+        .args([
+            "image",
+            "inspect",
+            "--format",
+            "{{.Id}} {{json .RepoDigests}}",
+        ])
+        .args(&images)
+        .output()
+    else {
+        return HashMap::new();
+    };
+    // One line per image, in argument order
+    images
+        .iter()
+        .zip(String::from_utf8_lossy(&output.stdout).lines())
+        .filter_map(|(image, line)| Some((image.to_string(), parse_image_digest(line)?)))
+        .collect()
+}
+
+/// Digest from a `{{.Id}} {{json .RepoDigests}}` line, preferring the registry digest
+pub fn parse_image_digest(line: &str) -> Option<String> {
+    let (id, repo_digests) = line.trim().split_once(' ')?;
+    let repo_digest = serde_json::from_str::<Vec<String>>(repo_digests)
+        .ok()
+        .and_then(|digests| {
+            digests
+                .first()
+                .and_then(|digest| digest.split_once('@'))
+                .map(|(_, digest)| digest.to_string())
+        });
+    repo_digest.or_else(|| (!id.is_empty()).then(|| id.to_string()))
+}
+
+async fn chain_statuses(config: &Config) -> Vec<ChainStatus> {
+    let mut chains = Vec::new();
+    for network_id in config.networks.network_ids() {
+        let rpc_url = config
+            .networks
+            .chain(network_id)
+            .map(|chain| chain.rpc_url.to_string())
+            .unwrap_or_default();
+        let head = match get_provider(config, network_id).await {
+            Ok(provider) => tokio::time::timeout(PROBE_TIMEOUT, provider.get_block_number())
+                .await
+                .map_err(|_| "timed out".to_string())
+                .and_then(|result| result.map_err(|e| e.to_string())),
+            Err(e) => Err(e.to_string()),
+        };
+        chains.push(ChainStatus {
+            network_id,
+            name: get_network_name(network_id),
+            rpc_url,
+            head_block: head.as_ref().ok().map(|block| block.as_u64()),
+            error: head.err(),
+        });
+    }
+    chains
+}
+
+async fn api_statuses(config: &Config) -> Vec<ApiStatus> {
+    let mut apis = Vec::new();
+    for network_id in config.networks.network_ids() {
+        let Ok(network) = NetworkId::new(network_id) else {
+            continue;
+        };
+        let result = readiness::probe(config, &Service::BridgeApi { network_id }).await;
+        apis.push(ApiStatus {
+            network_id,
+            url: config.get_api_base_url(network),
+            reachable: result.is_ok(),
+            error: result.err(),
+        });
+    }
+    apis
+}
+
+/// Services as a table with one row per container
+pub fn render_services(services: &[ServiceStatus]) -> String {
+    let header = ["SERVICE", "STATE", "HEALTH", "PORTS", "DIGEST"];
+    let rows: Vec<[String; 5]> = services
+        .iter()
+        .map(|service| {
+            [
+                service.service.clone(),
+                service.state.clone(),
+                service.health.clone().unwrap_or_else(|| "-".to_string()),
+                if service.ports.is_empty() {
+                    "-".to_string()
+                } else {
+                    service.ports.join(", ")
+                },
+                service
+                    .image_digest
+                    .as_deref()
+                    .map(short_digest)
+                    .unwrap_or("-")
+                    .to_string(),
+            ]
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .map(|row| row[column].chars().count())
+                .chain(std::iter::once(header[column].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    std::iter::once(line(header.to_vec()))
+        .chain(
+            rows.iter()
+                .map(|row| line(row.iter().map(String::as_str).collect())),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `sha256:` digest shortened to 12 hex characters, as `docker images` shows it
+fn short_digest(digest: &str) -> &str {
+    let hex = digest.strip_prefix("sha256:").unwrap_or(digest);
+    &hex[..hex.len().min(12)]
+}
+
+/// Show how far each bridge service instance is behind its chain
+fn display_indexing_status(statuses: &[IndexingStatus]) {
+    if statuses.is_empty() {
+        return;
+    }
+
+    ui::ui().blank_line();
+    ui::ui().info("🔎 Bridge service indexing:");
+    for status in statuses {
+        if status.is_lagging() {
+            ui::ui().warning(&status.describe());
+        } else {
+            ui::ui().success(&status.describe());
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn ps_entry() -> Value {
+        json!({
+            "Name": "aggsandbox-anvil-l1-1",
+            "Service": "anvil-l1",
+            "State": "running",
+            "Health": "",
+            "Image": "ghcr.io/foundry-rs/foundry:stable",
+            "Publishers": [
+                { "URL": "0.0.0.0", "TargetPort": 8545, "PublishedPort": 8545, "Protocol": "tcp" },
+                { "URL": "::", "TargetPort": 8545, "PublishedPort": 8545, "Protocol": "tcp" },
+                { "URL": "", "TargetPort": 9000, "PublishedPort": 0, "Protocol": "tcp" }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_service_status_from_ps() {
+        let service = ServiceStatus::from_ps(&ps_entry());
+        assert_eq!(service.service, "anvil-l1");
+        assert_eq!(service.health, None);
+        assert_eq!(service.ports, vec!["0.0.0.0:8545->8545/tcp"]);
+        assert!(service.is_healthy());
+
+        let mut unhealthy = ps_entry();
+        unhealthy["Health"] = json!("unhealthy");
+        assert!(!ServiceStatus::from_ps(&unhealthy).is_healthy());
+    }
+
+    #[test]
+    fn test_parse_image_digest() {
+        assert_eq!(
+            parse_image_digest(r#"sha256:aaa ["ghcr.io/agglayer/aggkit@sha256:bbb"]"#),
+            Some("sha256:bbb".to_string())
+        );
+        assert_eq!(
+            parse_image_digest("sha256:aaa []"),
+            Some("sha256:aaa".to_string())
+        );
+        assert_eq!(parse_image_digest(""), None);
+    }
 
-    // Execute the status command and display output
-    if let Ok(output) = execute_docker_command_with_output(cmd) {
-        print!("{output}");
-    } else {
-        ui::ui().error("Failed to get service status");
-        std::process::exit(1);
+    #[test]
+    fn test_render_services() {
+        let mut service = ServiceStatus::from_ps(&ps_entry());
+        service.image_digest = Some("sha256:0123456789abcdef".to_string());
+        let table = render_services(&[service]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert!(lines[0].starts_with("SERVICE "));
+        assert!(lines[1].contains("anvil-l1"));
+        assert!(lines[1].contains("0123456789ab"));
+        assert!(!lines[1].contains("0123456789abc"));
     }
 }
@@ -1,3 +1,7 @@
+use crate::commands::bridge::gas_report::GasReportStore;
+use crate::commands::bridge::token_metadata::TokenMetadataCache;
+use crate::commands::start_state::StartStateStore;
+use crate::error::exit_code;
 use crate::ui;
 
 /// Handle the stop command
@@ -14,8 +18,11 @@ pub fn handle_stop(volumes: bool) {
     // Execute the stop command
     if execute_docker_command(cmd, true).is_err() {
         ui::ui().error("Failed to stop sandbox");
-        std::process::exit(1);
+        std::process::exit(exit_code::DOCKER);
     } else {
+        StartStateStore::default_location().clear();
+        TokenMetadataCache::default_location().clear();
+        GasReportStore::default_location().clear();
         ui::ui().success("Sandbox stopped successfully");
     }
 }
@@ -0,0 +1,396 @@
+//! Test token deployment
+//!
+//! `token deploy` puts a fresh mintable ERC20 on a sandbox network, so
+//! scenarios needing specific decimals or several distinct tokens don't have
+//! to deploy them with forge scripts. Deployed tokens are listed by
+//! `token list`, and `token info` shows the cached metadata of any token
+//! together with its wrapped counterparts.
+
+use crate::commands::bridge::common::{
+    get_network_name, validate_address, validate_network_id, validation_error,
+};
+use crate::commands::bridge::token_deploy::{
+    deploy_token, DeployedToken, TokenDeployment, TokenRegistry,
+};
+use crate::commands::bridge::token_metadata::{token_info, TokenInfo, TokenMetadata};
+use crate::concurrency::dedup_networks;
+use crate::config::Config;
+use crate::error::Result;
+use crate::ui::{self, OutputFormat, UI};
+use crate::validation::parse_network_id;
+use ethers::types::U256;
+use ethers::utils::parse_units;
+
+/// Token subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum TokenCommands {
+    /// 🪙 Deploy a mintable ERC20 test token
+    #[command(long_about = "Deploy a new mintable ERC20 token on a sandbox network.
+
+The token is owned by the signing account, which also receives the initial
+supply. Its address is recorded in .aggsandbox/tokens.json, so `fund` and
+`bridge asset --faucet-if-needed` can mint it, and the bridge commands for it
+are printed. The contract is SandboxERC20 from agglayer-contracts and must be
+built with `forge build` first.
+
+Examples:
+  aggsandbox token deploy -n 0 --name \"Test USD\" --symbol TUSD --decimals 6
+  aggsandbox token deploy -n l2 --name Wrapped --symbol WRP --initial-supply 0
+  aggsandbox token deploy -n 0 --name Test --symbol TST --account alice")]
+    Deploy {
+        /// Network to deploy on
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID or alias (0/l1, 1/l2, 2/l3)")]
+        network_id: u64,
+        /// Token name
+        #[arg(long, help = "Token name")]
+        name: String,
+        /// Token symbol
+        #[arg(long, help = "Token symbol")]
+        symbol: String,
+        /// Token decimals
+        #[arg(long, default_value = "18", help = "Token decimals")]
+        decimals: u8,
+        /// Supply minted to the deployer, in whole tokens
+        #[arg(
+            long,
+            default_value = "1000000",
+            help = "Supply minted to the deployer, in whole tokens (e.g. 1000000 or 2.5)"
+        )]
+        initial_supply: String,
+        /// Private key to deploy with
+        #[arg(
+            long,
+            help = "Private key to deploy with (defaults to the selected account)"
+        )]
+        private_key: Option<String>,
+    },
+    /// 📚 List tokens deployed with `token deploy`
+    #[command(long_about = "List the tokens recorded in .aggsandbox/tokens.json.
+
+Examples:
+  aggsandbox token list
+  aggsandbox token list --output json")]
+    List,
+    /// 🔎 Show the metadata of a token and its wrapped counterparts
+    #[command(
+        long_about = "Show the name, symbol and decimals of a token and where it is bridged.
+
+The metadata is read from the token metadata cache (.aggsandbox/token_metadata.json),
+or from the token contract and then cached. The token is looked up on every
+network holding code at the address. A wrapped token shows its origin token;
+every token shows the wrapped tokens deployed for its origin token by the
+bridges of the other networks.
+
+Examples:
+  aggsandbox token info 0x5FbDB2315678afecb367f032d93F642f64180aa3
+  aggsandbox token info 0x5FbDB2315678afecb367f032d93F642f64180aa3 -n 0
+  aggsandbox token info 0x5FbDB2315678afecb367f032d93F642f64180aa3 --output json"
+    )]
+    Info {
+        /// Token address
+        #[arg(help = "Token address")]
+        address: String,
+        /// Networks to look the token up on (default: every network)
+        #[arg(
+            short = 'n',
+            long = "network-id",
+            alias = "network",
+            value_parser = parse_network_id,
+            value_delimiter = ',',
+            help = "Network ID, or several separated by commas (default: every network)"
+        )]
+        network_ids: Vec<u64>,
+    },
+}
+
+/// Handle token subcommands
+pub async fn handle_token(subcommand: TokenCommands, output: OutputFormat) -> Result<()> {
+    let registry = TokenRegistry::default_location();
+
+    match subcommand {
+        TokenCommands::Deploy {
+            network_id,
+            name,
+            symbol,
+            decimals,
+            initial_supply,
+            private_key,
+        } => {
+            validate_network_id(network_id, "Network")?;
+            if name.trim().is_empty() || symbol.trim().is_empty() {
+                return Err(validation_error("Token name and symbol cannot be empty"));
+            }
+            let config = Config::load()?;
+            let initial_supply = parse_supply(&initial_supply, decimals)?;
+            let deployment = TokenDeployment {
+                network_id,
+                name,
+                symbol,
+                decimals,
+                initial_supply,
+                private_key,
+            };
+            let token = deploy_token(&config, &registry, &deployment).await?;
+            print_deployment(&config, &token, initial_supply, output);
+            Ok(())
+        }
+        TokenCommands::List => {
+            print_tokens(&registry.list(), output);
+            Ok(())
+        }
+        TokenCommands::Info {
+            address,
+            network_ids,
+        } => {
+            let address = validate_address(&address, "Token address")?;
+            let config = Config::load()?;
+            let networks = if network_ids.is_empty() {
+                config.networks.network_ids()
+            } else {
+                dedup_networks(&network_ids)
+            };
+            let infos = token_info(&config, address, &networks).await?;
+            if infos.is_empty() {
+                return Err(validation_error(&format!(
+                    "No contract at {address:#x} on networks {networks:?}"
+                )));
+            }
+            print_token_info(&infos, output);
+            Ok(())
+        }
+    }
+}
+
+/// Raw amount of a supply given in whole tokens
+fn parse_supply(value: &str, decimals: u8) -> Result<U256> {
+    let invalid = || validation_error(&format!("Invalid initial supply '{value}'"));
+    // parse_units silently drops the digits the decimals cannot hold
+    let fraction_digits = value.trim().split_once('.').map_or(0, |(_, f)| f.len());
+    if fraction_digits > usize::from(decimals) {
+        return Err(invalid());
+    }
+    parse_units(value.trim(), u32::from(decimals))
+        .map(U256::from)
+        .map_err(|_| invalid())
+}
+
+/// `bridge asset` commands moving the token to the other configured networks
+fn bridge_commands(config: &Config, token: &DeployedToken) -> Vec<String> {
+    let amount = U256::exp10(usize::from(token.decimals));
+    config
+        .networks
+        .network_ids()
+        .into_iter()
+        .filter(|&destination| destination != token.network_id)
+        .map(|destination| {
+            format!(
+                "aggsandbox bridge asset --network-id {} --destination-network-id {destination} --amount {amount} --token-address {:#x}",
+                token.network_id, token.address
+            )
+        })
+        .collect()
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_deployment(config: &Config, token: &DeployedToken, supply: U256, output: OutputFormat) {
+    let commands = bridge_commands(config, token);
+    if output.is_json() {
+        let mut value = serde_json::to_value(token).unwrap_or_default();
+        value["initial_supply"] = serde_json::json!(supply.to_string());
+        value["bridge_commands"] = serde_json::json!(commands);
+        UI::new(output).json(&value);
+        return;
+    }
+
+    let ui = ui::ui();
+    ui.success(&format!(
+        "Deployed {} ({}) on network {} ({}) at {:#x}",
+        token.name,
+        token.symbol,
+        token.network_id,
+        get_network_name(token.network_id),
+        token.address
+    ));
+    ui.info(&format!(
+        "Minted {supply} to owner {:#x}, transaction {:#x}",
+        token.owner, token.tx_hash
+    ));
+    ui.info(&format!(
+        "Bridge one {} ({} decimals) with:",
+        token.symbol, token.decimals
+    ));
+    for command in &commands {
+        ui.info(&format!("  {command}"));
+    }
+}
+
+fn print_tokens(tokens: &[DeployedToken], output: OutputFormat) {
+    let ui = UI::new(output);
+    if output.is_json() {
+        ui.json(&serde_json::to_value(tokens).unwrap_or_default());
+        return;
+    }
+    if tokens.is_empty() {
+        ui.info("No tokens deployed yet, deploy one with `aggsandbox token deploy`");
+        return;
+    }
+
+    let labels: Vec<String> = tokens
+        .iter()
+        .map(|token| format!("n{} {}", token.network_id, token.symbol))
+        .collect();
+    let summaries: Vec<String> = tokens
+        .iter()
+        .map(|token| {
+            format!(
+                "{:#x} | {} | {} decimals",
+                token.address, token.name, token.decimals
+            )
+        })
+        .collect();
+    let rows: Vec<(&str, &str)> = labels
+        .iter()
+        .zip(&summaries)
+        .map(|(label, summary)| (label.as_str(), summary.as_str()))
+        .collect();
+    ui.table("🪙 Tokens", &rows);
+}
+
+/// `name (SYMBOL), N decimals` with `?` for fields that could not be read
+fn describe_metadata(metadata: &TokenMetadata) -> String {
+    format!(
+        "{} ({}), {} decimals",
+        metadata.name.as_deref().unwrap_or("?"),
+        metadata.symbol.as_deref().unwrap_or("?"),
+        metadata
+            .decimals
+            .map_or_else(|| "?".to_string(), |decimals| decimals.to_string())
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_token_info(infos: &[TokenInfo], output: OutputFormat) {
+    let ui = UI::new(output);
+    if output.is_json() {
+        ui.json(&serde_json::json!({ "tokens": infos }));
+        return;
+    }
+
+    for info in infos {
+        let network = format!("{} ({})", info.network, get_network_name(info.network));
+        let address = format!("{:#x}", info.address);
+        let metadata = describe_metadata(&info.metadata);
+        let origin = if info.wrapped {
+            format!(
+                "{:#x} on network {} ({})",
+                info.origin_address,
+                info.origin_network,
+                get_network_name(info.origin_network)
+            )
+        } else {
+            "native to this network".to_string()
+        };
+        let source = if info.cached {
+            "cached"
+        } else {
+            "read from the token, now cached"
+        };
+        ui.table(
+            "🔎 Token",
+            &[
+                ("Network", network.as_str()),
+                ("Address", address.as_str()),
+                ("Metadata", metadata.as_str()),
+                ("Origin", origin.as_str()),
+                ("Source", source),
+            ],
+        );
+
+        if info.counterparts.is_empty() {
+            ui.info("No wrapped counterparts deployed on the other networks");
+            continue;
+        }
+        let labels: Vec<String> = info
+            .counterparts
+            .iter()
+            .map(|counterpart| {
+                format!(
+                    "{} ({})",
+                    counterpart.network,
+                    get_network_name(counterpart.network)
+                )
+            })
+            .collect();
+        let summaries: Vec<String> = info
+            .counterparts
+            .iter()
+            .map(|counterpart| {
+                format!(
+                    "{:#x} | {}",
+                    counterpart.address,
+                    describe_metadata(&counterpart.metadata)
+                )
+            })
+            .collect();
+        let rows: Vec<(&str, &str)> = labels
+            .iter()
+            .zip(&summaries)
+            .map(|(label, summary)| (label.as_str(), summary.as_str()))
+            .collect();
+        ui.table("🔁 Wrapped counterparts", &rows);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_supply() {
+        assert_eq!(parse_supply("1000000", 6).unwrap(), U256::exp10(12));
+        assert_eq!(
+            parse_supply("2.5", 18).unwrap(),
+            U256::from(25) * U256::exp10(17)
+        );
+        assert_eq!(parse_supply("0", 18).unwrap(), U256::zero());
+        assert!(parse_supply("1.5", 0).is_err());
+        assert!(parse_supply("lots", 18).is_err());
+    }
+
+    #[test]
+    fn test_describe_metadata() {
+        let metadata = TokenMetadata {
+            name: Some("Test USD".to_string()),
+            symbol: Some("TUSD".to_string()),
+            decimals: Some(6),
+        };
+        assert_eq!(describe_metadata(&metadata), "Test USD (TUSD), 6 decimals");
+        assert_eq!(
+            describe_metadata(&TokenMetadata::default()),
+            "? (?), ? decimals"
+        );
+    }
+
+    #[test]
+    fn test_bridge_commands() {
+        let token = DeployedToken {
+            network_id: 1,
+            address: ethers::types::Address::repeat_byte(0x11),
+            name: "Test USD".to_string(),
+            symbol: "TUSD".to_string(),
+            decimals: 6,
+            owner: ethers::types::Address::repeat_byte(0xf3),
+            tx_hash: ethers::types::H256::zero(),
+            deployed_at: 0,
+        };
+        let mut config = Config::default();
+        config.networks.l3 = None;
+
+        let commands = bridge_commands(&config, &token);
+
+        assert_eq!(commands.len(), 1);
+        assert!(commands[0].contains("--network-id 1 --destination-network-id 0 --amount 1000000"));
+        assert!(commands[0].ends_with("0x1111111111111111111111111111111111111111"));
+    }
+}
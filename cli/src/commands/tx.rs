@@ -0,0 +1,360 @@
+//! Recovery of stuck transactions
+//!
+//! A transaction sent with a gas price below the base fee stays in the
+//! mempool and holds up every later transaction of its account. `tx bump`
+//! resends it with the same nonce and a higher gas price; `tx cancel` spends
+//! the nonce on a 0 ETH transfer to the sender instead.
+
+use crate::commands::bridge::common::{get_network_name, is_dry_run, rpc_error, validation_error};
+use crate::commands::bridge::nonce::{
+    ensure_nonce_pending, pending_with_nonce, replacement, replacement_fees, DEFAULT_BUMP_PERCENT,
+    MIN_BUMP_PERCENT,
+};
+use crate::commands::bridge::{get_provider, get_wallet_with_provider, receipt};
+use crate::config::Config;
+use crate::error::{AggSandboxError, Result};
+use crate::ui::{self, OutputFormat};
+use crate::validation::{parse_int, parse_network_id};
+use ethers::prelude::*;
+use serde::Serialize;
+use std::str::FromStr;
+use tracing::info;
+
+/// Transaction recovery subcommands
+#[derive(Debug, clap::Subcommand)]
+pub enum TxCommands {
+    /// ⛽ Resend a pending transaction with a higher gas price
+    #[command(
+        long_about = "Replace a pending transaction with a copy paying more gas.
+
+The replacement reuses the nonce, recipient, value, data and gas limit of the
+stuck transaction and raises its gas price (or max fee and priority fee) by
+--percent, and at least to the network's current gas price. The transaction
+must have been sent by the signing account.
+
+Examples:
+  aggsandbox tx bump --tx-hash 0xabc... -n 1
+  aggsandbox tx bump --tx-hash 0xabc... -n 0 --percent 50
+  aggsandbox tx --account alice bump --tx-hash 0xabc... -n 1"
+    )]
+    Bump {
+        /// Hash of the stuck transaction
+        #[arg(short, long, help = "Hash of the pending transaction to replace")]
+        tx_hash: String,
+        /// Network the transaction was sent on
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID or alias the transaction was sent on")]
+        network_id: u64,
+        /// Gas price increase in percent
+        #[arg(long, value_parser = parse_int::<u64>, default_value_t = DEFAULT_BUMP_PERCENT, help = "Gas price increase in percent (at least 10)")]
+        percent: u64,
+        /// Private key of the sender
+        #[arg(long, help = "Private key that sent the transaction")]
+        private_key: Option<String>,
+    },
+    /// 🚫 Cancel a pending transaction by reusing its nonce
+    #[command(
+        long_about = "Spend the nonce of a pending transaction on a 0 ETH transfer to yourself.
+
+Once the cancellation is mined the stuck transaction can no longer be
+included. Its gas price is raised by --percent over the pending transaction
+found in the node's txpool, or over the current gas price when the txpool
+cannot be read. The nonce must not be used by a mined transaction yet.
+
+Examples:
+  aggsandbox tx cancel --nonce 12 -n 1
+  aggsandbox tx cancel --nonce 12 -n 0 --percent 100"
+    )]
+    Cancel {
+        /// Nonce of the stuck transaction
+        #[arg(long, value_parser = parse_int::<u64>, help = "Nonce of the pending transaction to cancel")]
+        nonce: u64,
+        /// Network the transaction was sent on
+        #[arg(short = 'n', long, alias = "network", value_parser = parse_network_id, help = "Network ID or alias the transaction was sent on")]
+        network_id: u64,
+        /// Gas price increase in percent
+        #[arg(long, value_parser = parse_int::<u64>, default_value_t = DEFAULT_BUMP_PERCENT, help = "Gas price increase in percent (at least 10)")]
+        percent: u64,
+        /// Private key of the sender
+        #[arg(long, help = "Private key that sent the transaction")]
+        private_key: Option<String>,
+    },
+}
+
+/// Replacement sent by `tx bump` or `tx cancel`
+#[derive(Debug, Clone, Serialize)]
+pub struct Replacement {
+    pub action: &'static str,
+    pub network_id: u64,
+    pub from: Address,
+    pub nonce: U256,
+    /// Transaction replaced, when it was found
+    pub replaced_tx_hash: Option<H256>,
+    /// `None` with `--dry-run`
+    pub tx_hash: Option<H256>,
+    pub gas_price: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+}
+
+/// Handle tx subcommands
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn handle_tx(
+    subcommand: TxCommands,
+    account: Option<&str>,
+    output: OutputFormat,
+) -> Result<()> {
+    let mut config = Config::load()?;
+    if let Some(name) = account {
+        crate::keystore::select_account(&mut config, name)?;
+    }
+
+    let replacement = match subcommand {
+        TxCommands::Bump {
+            tx_hash,
+            network_id,
+            percent,
+            private_key,
+        } => {
+            info!(tx_hash = %tx_hash, network_id, percent, "Executing tx bump command");
+            ensure_percent(percent)?;
+            let tx_hash = H256::from_str(&tx_hash)
+                .map_err(|_| validation_error(&format!("Invalid transaction hash '{tx_hash}'")))?;
+            bump(
+                &config,
+                network_id,
+                tx_hash,
+                percent,
+                private_key.as_deref(),
+            )
+            .await?
+        }
+        TxCommands::Cancel {
+            nonce,
+            network_id,
+            percent,
+            private_key,
+        } => {
+            info!(nonce, network_id, percent, "Executing tx cancel command");
+            ensure_percent(percent)?;
+            cancel(
+                &config,
+                network_id,
+                U256::from(nonce),
+                percent,
+                private_key.as_deref(),
+            )
+            .await?
+        }
+    };
+
+    print_replacement(&replacement, output);
+    Ok(())
+}
+
+fn ensure_percent(percent: u64) -> Result<()> {
+    if percent < MIN_BUMP_PERCENT {
+        return Err(validation_error(&format!(
+            "--percent must be at least {MIN_BUMP_PERCENT}; nodes reject smaller replacements"
+        )));
+    }
+    Ok(())
+}
+
+async fn bump(
+    config: &Config,
+    network_id: u64,
+    tx_hash: H256,
+    percent: u64,
+    private_key: Option<&str>,
+) -> Result<Replacement> {
+    let client = get_wallet_with_provider(config, network_id, private_key).await?;
+    let provider = get_provider(config, network_id).await?;
+    let stuck = provider
+        .get_transaction(tx_hash)
+        .await
+        .map_err(|e| AggSandboxError::Other(format!("Failed to fetch transaction: {e}")))?
+        .ok_or_else(|| {
+            validation_error(&format!(
+                "Transaction {tx_hash:#x} is not known on network {network_id} ({})",
+                get_network_name(network_id)
+            ))
+        })?;
+    if let Some(block) = stuck.block_number {
+        return Err(validation_error(&format!(
+            "Transaction {tx_hash:#x} was already mined in block {block}; there is nothing to replace"
+        )));
+    }
+    let signer = client.inner().address();
+    if stuck.from != signer {
+        return Err(validation_error(&format!(
+            "Transaction {tx_hash:#x} was sent by {:#x}, not by the signing account {signer:#x}; select it with --account or --private-key",
+            stuck.from
+        )));
+    }
+
+    send_replacement(client.inner(), network_id, &stuck, percent, "bump").await
+}
+
+async fn cancel(
+    config: &Config,
+    network_id: u64,
+    nonce: U256,
+    percent: u64,
+    private_key: Option<&str>,
+) -> Result<Replacement> {
+    let client = get_wallet_with_provider(config, network_id, private_key).await?;
+    let provider = get_provider(config, network_id).await?;
+    let address = client.inner().address();
+    ensure_nonce_pending(&provider, network_id, address, nonce).await?;
+
+    let stuck = match pending_with_nonce(&provider, address, nonce).await {
+        Some(stuck) => stuck,
+        None => {
+            let gas_price = provider
+                .get_gas_price()
+                .await
+                .map_err(|e| AggSandboxError::Other(format!("Failed to fetch gas price: {e}")))?;
+            Transaction {
+                from: address,
+                nonce,
+                gas_price: Some(gas_price),
+                ..Default::default()
+            }
+        }
+    };
+
+    send_replacement(client.inner(), network_id, &stuck, percent, "cancel").await
+}
+
+/// Send the replacement of `stuck`, or only describe it with `--dry-run`
+///
+/// `client` must not be the nonce manager, which would move a replacement
+/// whose nonce was mined in the meantime to a fresh nonce.
+async fn send_replacement<M: Middleware>(
+    client: &M,
+    network_id: u64,
+    stuck: &Transaction,
+    percent: u64,
+    action: &'static str,
+) -> Result<Replacement> {
+    let current_gas_price = client
+        .get_gas_price()
+        .await
+        .map_err(|e| AggSandboxError::Other(format!("Failed to fetch gas price: {e}")))?;
+    let current_priority_fee = match stuck.max_fee_per_gas {
+        Some(_) => client
+            .estimate_eip1559_fees(None)
+            .await
+            .map(|(_, priority_fee)| priority_fee)
+            .unwrap_or_default(),
+        None => U256::zero(),
+    };
+    let fees = replacement_fees(stuck, percent, current_gas_price, current_priority_fee);
+    let tx = replacement(stuck, fees, action == "cancel");
+
+    let tx_hash = if is_dry_run() {
+        None
+    } else {
+        let pending = client.send_transaction(tx, None).await.map_err(|e| {
+            rpc_error(
+                &format!("send the replacement for nonce {}", stuck.nonce),
+                e,
+            )
+        })?;
+        let tx_hash = pending.tx_hash();
+        receipt::wait_for_receipt(client, tx_hash, "Replacement transaction").await?;
+        Some(tx_hash)
+    };
+
+    let (max_fee, priority_fee) = fees;
+    Ok(Replacement {
+        action,
+        network_id,
+        from: stuck.from,
+        nonce: stuck.nonce,
+        replaced_tx_hash: (!stuck.hash.is_zero()).then_some(stuck.hash),
+        tx_hash,
+        gas_price: priority_fee.is_none().then_some(max_fee),
+        max_fee_per_gas: priority_fee.map(|_| max_fee),
+        max_priority_fee_per_gas: priority_fee,
+    })
+}
+
+fn print_replacement(replacement: &Replacement, output: OutputFormat) {
+    if output.is_json() {
+        ui::ui().json(&serde_json::to_value(replacement).unwrap_or_default());
+        return;
+    }
+
+    let hash = |hash: Option<H256>| hash.map_or_else(|| "-".to_string(), |h| format!("{h:#x}"));
+    let fee = |fee: Option<U256>| fee.map(|wei| format!("{wei} wei"));
+    let mut rows = vec![
+        (
+            "Network".to_string(),
+            format!(
+                "{} ({})",
+                replacement.network_id,
+                get_network_name(replacement.network_id)
+            ),
+        ),
+        ("From".to_string(), format!("{:#x}", replacement.from)),
+        ("Nonce".to_string(), replacement.nonce.to_string()),
+        ("Replaced".to_string(), hash(replacement.replaced_tx_hash)),
+    ];
+    if let Some(gas_price) = fee(replacement.gas_price) {
+        rows.push(("Gas price".to_string(), gas_price));
+    }
+    if let Some(max_fee) = fee(replacement.max_fee_per_gas) {
+        rows.push(("Max fee".to_string(), max_fee));
+    }
+    if let Some(priority_fee) = fee(replacement.max_priority_fee_per_gas) {
+        rows.push(("Priority fee".to_string(), priority_fee));
+    }
+    rows.push(("Transaction".to_string(), hash(replacement.tx_hash)));
+    let rows: Vec<(&str, &str)> = rows
+        .iter()
+        .map(|(label, value)| (label.as_str(), value.as_str()))
+        .collect();
+
+    let title = if replacement.action == "cancel" {
+        "🚫 Cancellation"
+    } else {
+        "⛽ Replacement"
+    };
+    ui::ui().table(title, &rows);
+    if replacement.tx_hash.is_none() {
+        ui::ui().info("Dry run: the replacement was not broadcast");
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ensure_percent() {
+        assert!(ensure_percent(MIN_BUMP_PERCENT).is_ok());
+        assert!(ensure_percent(DEFAULT_BUMP_PERCENT).is_ok());
+        assert!(ensure_percent(5).is_err());
+    }
+
+    #[test]
+    fn test_replacement_json() {
+        let replacement = Replacement {
+            action: "bump",
+            network_id: 1,
+            from: Address::repeat_byte(0x01),
+            nonce: U256::from(4),
+            replaced_tx_hash: Some(H256::repeat_byte(0x02)),
+            tx_hash: None,
+            gas_price: Some(U256::from(1_200)),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+        };
+        let json = serde_json::to_value(&replacement).unwrap();
+        assert_eq!(json["action"], "bump");
+        assert_eq!(json["nonce"], "0x4");
+        assert!(json["tx_hash"].is_null());
+    }
+}
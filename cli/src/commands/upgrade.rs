@@ -0,0 +1,544 @@
+//! Updates for the sandbox images and the CLI itself
+//!
+//! `aggsandbox upgrade` pulls newer tags of the images the compose files
+//! reference and reports which digests changed. `--check` only compares the
+//! local digests against Docker Hub, and `--cli` also replaces the running
+//! binary with the latest GitHub release.
+
+use crate::commands::status::parse_image_digest;
+use crate::docker::{
+    create_auto_docker_builder, execute_docker_command, execute_docker_command_with_output,
+};
+use crate::error::{ApiError, ConfigError, Result};
+use crate::ui::{OutputFormat, UI};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// Latest release of the CLI
+const RELEASES_URL: &str = "https://api.github.com/repos/agglayer/aggsandbox/releases/latest";
+/// Docker Hub tag metadata, by namespace, repository and tag
+const DOCKER_HUB_TAGS_URL: &str = "https://hub.docker.com/v2/repositories";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+/// Version of the running CLI
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// How an image changed, or would change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageStatus {
+    UpToDate,
+    Updated,
+    UpdateAvailable,
+    New,
+    Unknown,
+}
+
+impl ImageStatus {
+    fn label(self) -> &'static str {
+        match self {
+            ImageStatus::UpToDate => "up to date",
+            ImageStatus::Updated => "updated",
+            ImageStatus::UpdateAvailable => "update available",
+            ImageStatus::New => "new",
+            ImageStatus::Unknown => "unknown",
+        }
+    }
+}
+
+/// Digests of one image before and after the upgrade
+///
+/// With `--check` the "after" digest is the one published on Docker Hub.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageUpdate {
+    pub image: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub status: ImageStatus,
+}
+
+/// Running CLI version against the latest release
+#[derive(Debug, Clone, Serialize)]
+pub struct CliUpdate {
+    pub current: String,
+    pub latest: String,
+    pub update_available: bool,
+    /// Path of the replaced binary, when `--cli` installed the release
+    pub installed: Option<String>,
+    pub release_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Handle the upgrade command
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn handle_upgrade(
+    check: bool,
+    cli: bool,
+    offline: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    if offline {
+        return Err(ConfigError::validation_failed(
+            "upgrade needs registry access and cannot be used with --offline",
+        )
+        .into());
+    }
+    let ui = UI::new(output);
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("aggsandbox/", env!("CARGO_PKG_VERSION")))
+        .timeout(HTTP_TIMEOUT)
+        .build()
+        .map_err(ApiError::from)?;
+
+    let images = if Path::new("docker-compose.yml").exists() {
+        compose_images()?
+    } else {
+        ui.warning("docker-compose.yml not found, skipping the sandbox images");
+        Vec::new()
+    };
+    let before = local_digests(&images);
+    let after = if check {
+        let mut remote = HashMap::new();
+        for image in &images {
+            match registry_digest(&client, image).await {
+                Ok(Some(digest)) => {
+                    remote.insert(image.clone(), digest);
+                }
+                Ok(None) => tracing::debug!(image = %image, "Image is not on Docker Hub"),
+                Err(e) => ui.warning(&format!("Could not check {image}: {e}")),
+            }
+        }
+        remote
+    } else if images.is_empty() {
+        HashMap::new()
+    } else {
+        if !ui.is_json() {
+            ui.info("🐳 Pulling sandbox images...");
+        }
+        let pull = create_auto_docker_builder().build_pull_command();
+        execute_docker_command(pull, ui.is_json())?;
+        local_digests(&images)
+    };
+    let updates: Vec<ImageUpdate> = images
+        .iter()
+        .map(|image| {
+            let before = before.get(image).cloned();
+            let after = after.get(image).cloned();
+            ImageUpdate {
+                image: image.clone(),
+                status: image_status(before.as_deref(), after.as_deref(), check),
+                before,
+                after,
+            }
+        })
+        .collect();
+
+    let release = match latest_release(&client).await {
+        Ok(release) => Some(release),
+        Err(e) if cli => return Err(e),
+        Err(e) => {
+            ui.warning(&format!("Could not check for a newer CLI release: {e}"));
+            None
+        }
+    };
+    let mut cli_update = release.as_ref().map(|release| CliUpdate {
+        current: CURRENT_VERSION.to_string(),
+        latest: release.tag_name.trim_start_matches('v').to_string(),
+        update_available: is_newer(CURRENT_VERSION, &release.tag_name),
+        installed: None,
+        release_url: release.html_url.clone(),
+    });
+    if let (true, Some(release), Some(update)) = (cli, &release, &mut cli_update) {
+        if update.update_available {
+            let asset = platform_asset(
+                &release.assets,
+                std::env::consts::OS,
+                std::env::consts::ARCH,
+            )
+            .ok_or_else(|| {
+                ConfigError::validation_failed(&format!(
+                    "release {} has no binary for {}-{}, build it from source with `make install`",
+                    release.tag_name,
+                    std::env::consts::OS,
+                    std::env::consts::ARCH
+                ))
+            })?;
+            if !ui.is_json() {
+                ui.info(&format!("🦀 Downloading {}...", asset.name));
+            }
+            let binary = client
+                .get(&asset.browser_download_url)
+                .send()
+                .await
+                .map_err(ApiError::from)?
+                .error_for_status()
+                .map_err(ApiError::from)?
+                .bytes()
+                .await
+                .map_err(ApiError::from)?;
+            let path = install_binary(&binary)?;
+            update.installed = Some(path.display().to_string());
+        }
+    }
+
+    print_upgrade(&ui, &updates, cli_update.as_ref(), check, cli);
+    Ok(())
+}
+
+/// Image references of every compose service, in file order
+fn compose_images() -> Result<Vec<String>> {
+    let listing = execute_docker_command_with_output(
+        create_auto_docker_builder().build_config_images_command(),
+    )?;
+    let mut images: Vec<String> = Vec::new();
+    for image in listing
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        if !images.iter().any(|known| known == image) {
+            images.push(image.to_string());
+        }
+    }
+    Ok(images)
+}
+
+/// Local digest of every image that is present, by image reference
+fn local_digests(images: &[String]) -> HashMap<String, String> {
+    // One inspect per image, since a missing image shifts a combined listing
+    images
+        .iter()
+        .filter_map(|image| {
+            let output = Command::new("docker")
+                .args([
+                    "image",
+                    "inspect",
+                    "--format",
+                    "{{.Id}} {{json .RepoDigests}}",
+                    image,
+                ])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())?;
+            let line = String::from_utf8_lossy(&output.stdout);
+            Some((image.clone(), parse_image_digest(line.lines().next()?)?))
+        })
+        .collect()
+}
+
+/// Status of an image from its digests before and after
+fn image_status(before: Option<&str>, after: Option<&str>, check: bool) -> ImageStatus {
+    match (before, after) {
+        (_, None) => ImageStatus::Unknown,
+        (None, Some(_)) => ImageStatus::New,
+        (Some(before), Some(after)) if before == after => ImageStatus::UpToDate,
+        (Some(_), Some(_)) if check => ImageStatus::UpdateAvailable,
+        (Some(_), Some(_)) => ImageStatus::Updated,
+    }
+}
+
+/// Docker Hub `(namespace, repository, tag)` of an image, `None` for other registries
+pub fn parse_image_ref(image: &str) -> Option<(String, String, String)> {
+    if image.contains('@') {
+        return None;
+    }
+    let (name, tag) = match image.rsplit_once(':') {
+        Some((name, tag)) if !tag.contains('/') => (name, tag),
+        _ => (image, "latest"),
+    };
+    let name = name
+        .strip_prefix("docker.io/")
+        .or_else(|| name.strip_prefix("index.docker.io/"))
+        .unwrap_or(name);
+    let mut parts: Vec<&str> = name.split('/').collect();
+    // A first component with a dot, a port or "localhost" names another registry
+    if parts.len() > 1
+        && (parts[0].contains('.') || parts[0].contains(':') || parts[0] == "localhost")
+    {
+        return None;
+    }
+    match parts.len() {
+        1 => parts.insert(0, "library"),
+        2 => {}
+        _ => return None,
+    }
+    Some((parts[0].to_string(), parts[1].to_string(), tag.to_string()))
+}
+
+/// Digest Docker Hub publishes for an image tag
+async fn registry_digest(client: &reqwest::Client, image: &str) -> Result<Option<String>> {
+    let Some((namespace, repository, tag)) = parse_image_ref(image) else {
+        return Ok(None);
+    };
+    let url = format!("{DOCKER_HUB_TAGS_URL}/{namespace}/{repository}/tags/{tag}");
+    let response = client.get(&url).send().await.map_err(ApiError::from)?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(ApiError::request_failed(&url, status.as_u16(), status.as_str()).into());
+    }
+    let body: serde_json::Value = response.json().await.map_err(ApiError::from)?;
+    Ok(body
+        .get("digest")
+        .and_then(|digest| digest.as_str())
+        .map(str::to_string))
+}
+
+async fn latest_release(client: &reqwest::Client) -> Result<Release> {
+    let response = client
+        .get(RELEASES_URL)
+        .send()
+        .await
+        .map_err(ApiError::from)?;
+    if !response.status().is_success() {
+        let status = response.status();
+        return Err(
+            ApiError::request_failed(RELEASES_URL, status.as_u16(), status.as_str()).into(),
+        );
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| ApiError::json_parse_error(&e.to_string()).into())
+}
+
+/// `major.minor.patch` of a version or release tag, ignoring a `v` prefix and pre-release suffix
+pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse::<u64>().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+/// Whether `latest` is a higher version than `current`
+pub fn is_newer(current: &str, latest: &str) -> bool {
+    match (parse_version(current), parse_version(latest)) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => false,
+    }
+}
+
+/// Release binary built for an OS and architecture, as named by `std::env::consts`
+fn platform_asset<'a>(
+    assets: &'a [ReleaseAsset],
+    os: &str,
+    arch: &str,
+) -> Option<&'a ReleaseAsset> {
+    let os_names: &[&str] = match os {
+        "macos" => &["darwin", "macos", "apple"],
+        other => &[other],
+    };
+    let arch_names: &[&str] = match arch {
+        "x86_64" => &["x86_64", "amd64"],
+        "aarch64" => &["aarch64", "arm64"],
+        other => &[other],
+    };
+    // Checksums, signatures and archives cannot replace the binary directly
+    const SKIPPED: [&str; 6] = [".sha256", ".sig", ".asc", ".tar.gz", ".tgz", ".zip"];
+    assets.iter().find(|asset| {
+        let name = asset.name.to_lowercase();
+        name.contains("aggsandbox")
+            && !SKIPPED.iter().any(|suffix| name.ends_with(suffix))
+            && os_names.iter().any(|os| name.contains(os))
+            && arch_names.iter().any(|arch| name.contains(arch))
+    })
+}
+
+/// Replace the running binary, writing next to it first so the swap is a rename
+fn install_binary(binary: &[u8]) -> Result<PathBuf> {
+    let current = std::env::current_exe()?;
+    let staged = current.with_extension("upgrade");
+    std::fs::write(&staged, binary)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+    // Windows cannot overwrite a running executable but can rename it
+    #[cfg(windows)]
+    std::fs::rename(&current, current.with_extension("old"))?;
+    std::fs::rename(&staged, &current)?;
+    Ok(current)
+}
+
+/// Shortened `sha256:` digest for tables
+fn short_digest(digest: Option<&str>) -> String {
+    match digest {
+        Some(digest) => {
+            let hex = digest.strip_prefix("sha256:").unwrap_or(digest);
+            hex.chars().take(12).collect()
+        }
+        None => "-".to_string(),
+    }
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn print_upgrade(
+    ui: &UI,
+    updates: &[ImageUpdate],
+    cli_update: Option<&CliUpdate>,
+    check: bool,
+    cli: bool,
+) {
+    if ui.is_json() {
+        ui.json(&serde_json::json!({
+            "images": updates,
+            "cli": cli_update,
+        }));
+        return;
+    }
+
+    if !updates.is_empty() {
+        let lines: Vec<(String, String)> = updates
+            .iter()
+            .map(|update| {
+                (
+                    update.image.clone(),
+                    format!(
+                        "{} → {} ({})",
+                        short_digest(update.before.as_deref()),
+                        short_digest(update.after.as_deref()),
+                        update.status.label()
+                    ),
+                )
+            })
+            .collect();
+        let rows: Vec<(&str, &str)> = lines
+            .iter()
+            .map(|(image, change)| (image.as_str(), change.as_str()))
+            .collect();
+        ui.table("🐳 Sandbox Images", &rows);
+    }
+
+    if let Some(update) = cli_update {
+        let status = match (&update.installed, update.update_available) {
+            (Some(path), _) => format!("installed to {path}"),
+            (None, true) => "update available".to_string(),
+            (None, false) => "up to date".to_string(),
+        };
+        ui.table(
+            "🦀 CLI",
+            &[
+                ("Current", update.current.as_str()),
+                ("Latest", update.latest.as_str()),
+                ("Status", status.as_str()),
+                ("Release", update.release_url.as_str()),
+            ],
+        );
+        if update.update_available && update.installed.is_none() && !cli {
+            ui.tip("Run `aggsandbox upgrade --cli` to install the latest release");
+        }
+    }
+
+    let pending = |status| updates.iter().any(|update| update.status == status);
+    if check && (pending(ImageStatus::UpdateAvailable) || pending(ImageStatus::New)) {
+        ui.tip("Run `aggsandbox upgrade` to pull the newer images");
+    } else if pending(ImageStatus::Updated) {
+        ui.tip("Run `aggsandbox restart` to run the updated images");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> ReleaseAsset {
+        ReleaseAsset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.com/{name}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("0.1.0"), Some((0, 1, 0)));
+        assert_eq!(parse_version("v1.12.3"), Some((1, 12, 3)));
+        assert_eq!(parse_version("v2.0.0-rc.1"), Some((2, 0, 0)));
+        assert_eq!(parse_version("1.2"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+        assert_eq!(parse_version("latest"), None);
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.1.0", "v0.2.0"));
+        assert!(is_newer("0.9.9", "0.10.0"));
+        assert!(!is_newer("0.2.0", "v0.2.0"));
+        assert!(!is_newer("0.3.0", "v0.2.9"));
+        assert!(!is_newer("0.1.0", "nightly"));
+    }
+
+    #[test]
+    fn test_parse_image_ref() {
+        let hub = |ns: &str, repo: &str, tag: &str| {
+            Some((ns.to_string(), repo.to_string(), tag.to_string()))
+        };
+        assert_eq!(
+            parse_image_ref("ametelnethermind/aggkit:latest"),
+            hub("ametelnethermind", "aggkit", "latest")
+        );
+        assert_eq!(
+            parse_image_ref("alpine"),
+            hub("library", "alpine", "latest")
+        );
+        assert_eq!(
+            parse_image_ref("docker.io/library/alpine:3.20"),
+            hub("library", "alpine", "3.20")
+        );
+        assert_eq!(parse_image_ref("ghcr.io/agglayer/aggkit:0.5.0"), None);
+        assert_eq!(parse_image_ref("localhost:5000/aggkit"), None);
+        assert_eq!(parse_image_ref("alpine@sha256:abc"), None);
+    }
+
+    #[test]
+    fn test_image_status() {
+        assert_eq!(
+            image_status(Some("a"), Some("a"), false),
+            ImageStatus::UpToDate
+        );
+        assert_eq!(
+            image_status(Some("a"), Some("b"), false),
+            ImageStatus::Updated
+        );
+        assert_eq!(
+            image_status(Some("a"), Some("b"), true),
+            ImageStatus::UpdateAvailable
+        );
+        assert_eq!(image_status(None, Some("b"), true), ImageStatus::New);
+        assert_eq!(image_status(Some("a"), None, true), ImageStatus::Unknown);
+    }
+
+    #[test]
+    fn test_platform_asset() {
+        let assets = vec![
+            asset("aggsandbox-linux-amd64.tar.gz"),
+            asset("aggsandbox-linux-amd64"),
+            asset("aggsandbox-linux-amd64.sha256"),
+            asset("aggsandbox-darwin-arm64"),
+        ];
+        assert_eq!(
+            platform_asset(&assets, "linux", "x86_64").map(|a| a.name.as_str()),
+            Some("aggsandbox-linux-amd64")
+        );
+        assert_eq!(
+            platform_asset(&assets, "macos", "aarch64").map(|a| a.name.as_str()),
+            Some("aggsandbox-darwin-arm64")
+        );
+        assert!(platform_asset(&assets, "windows", "x86_64").is_none());
+    }
+}
@@ -0,0 +1,87 @@
+//! Concurrent queries against several sandbox networks
+//!
+//! Commands accepting `--network-id 0,1,2` query the networks at the same time
+//! instead of one after the other. The futures are polled together on the
+//! current task, so they may borrow the configuration and need not be `Send`.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+
+/// Await all futures concurrently, returning their outputs in input order
+pub async fn join_all<F: Future>(futures: Vec<F>) -> Vec<F::Output> {
+    let mut futures: Vec<Pin<Box<F>>> = futures.into_iter().map(Box::pin).collect();
+    let mut outputs: Vec<Option<F::Output>> = futures.iter().map(|_| None).collect();
+
+    std::future::poll_fn(|cx| {
+        let mut pending = false;
+        for (future, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if output.is_some() {
+                continue;
+            }
+            match future.as_mut().poll(cx) {
+                Poll::Ready(value) => *output = Some(value),
+                Poll::Pending => pending = true,
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    })
+    .await;
+
+    outputs.into_iter().flatten().collect()
+}
+
+/// Run `query` for every network concurrently, pairing each result with its network
+pub async fn query_networks<T, F, Fut>(networks: &[u64], query: F) -> Vec<(u64, T)>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = T>,
+{
+    let results = join_all(
+        networks
+            .iter()
+            .map(|&network_id| query(network_id))
+            .collect(),
+    )
+    .await;
+    networks.iter().copied().zip(results).collect()
+}
+
+/// Networks of a `--network-id` list in the order given, without duplicates
+pub fn dedup_networks(networks: &[u64]) -> Vec<u64> {
+    let mut unique = Vec::with_capacity(networks.len());
+    for &network_id in networks {
+        if !unique.contains(&network_id) {
+            unique.push(network_id);
+        }
+    }
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn test_join_all_runs_concurrently_in_order() {
+        let start = Instant::now();
+        let results = query_networks(&[0, 1, 2], |network_id| async move {
+            tokio::time::sleep(Duration::from_millis(60 - network_id * 20)).await;
+            network_id * 10
+        })
+        .await;
+
+        assert_eq!(results, vec![(0, 0), (1, 10), (2, 20)]);
+        assert!(start.elapsed() < Duration::from_millis(110));
+    }
+
+    #[test]
+    fn test_dedup_networks() {
+        assert_eq!(dedup_networks(&[1, 0, 1, 2, 0]), vec![1, 0, 2]);
+    }
+}
@@ -1,12 +1,22 @@
 use crate::error::{ConfigError, Result};
-use crate::types::{ChainId, EthereumAddress, NetworkId, RpcUrl};
+use crate::types::{ChainId, EthereumAddress, NetworkId, RpcUrl, WsUrl};
 use crate::validation::Validator;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+/// Config files looked up in the working directory, in order of precedence
+pub const CONFIG_FILES: [&str; 6] = [
+    "aggsandbox.toml",
+    "aggsandbox.yaml",
+    "aggsandbox.yml",
+    ".aggsandbox.toml",
+    ".aggsandbox.yaml",
+    ".aggsandbox.yml",
+];
+
 /// Main configuration structure for the CLI application
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -21,10 +31,35 @@ pub struct Config {
 pub struct ApiConfig {
     pub base_url: RpcUrl,
     #[serde(with = "duration_serde")]
-    #[allow(dead_code)]
     pub timeout: Duration,
-    #[allow(dead_code)]
+    /// Total attempts per request, including the first one
     pub retry_attempts: u32,
+    /// Requests in flight to one bridge service host at a time
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: usize,
+    /// Requests started per second on one bridge service host, 0 for no limit
+    #[serde(default = "default_rate_limit")]
+    pub rate_limit: u32,
+    /// JSON-RPC endpoint of the agglayer node certificates are submitted to
+    #[serde(default = "default_agglayer_url")]
+    pub agglayer_url: RpcUrl,
+}
+
+const DEFAULT_MAX_IN_FLIGHT: usize = 8;
+const DEFAULT_RATE_LIMIT: u32 = 20;
+const DEFAULT_AGGLAYER_URL: &str = "http://localhost:4444";
+
+fn default_max_in_flight() -> usize {
+    DEFAULT_MAX_IN_FLIGHT
+}
+
+fn default_rate_limit() -> u32 {
+    DEFAULT_RATE_LIMIT
+}
+
+#[allow(clippy::disallowed_methods)] // Allow unwrap for hardcoded defaults
+fn default_agglayer_url() -> RpcUrl {
+    RpcUrl::new(DEFAULT_AGGLAYER_URL).unwrap()
 }
 
 /// Network configuration for all supported chains
@@ -33,6 +68,24 @@ pub struct NetworkConfig {
     pub l1: ChainConfig,
     pub l2: ChainConfig,
     pub l3: Option<ChainConfig>,
+    /// L2 chains beyond the first two, network ID 3 onwards
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_l2s: Vec<ExtraChainConfig>,
+}
+
+/// An additional L2 chain and the Agglayer network ID it is registered with
+///
+/// From the environment, network `n` is configured by `RPC_<n+1>` (continuing
+/// `RPC_1`..`RPC_3`), `CHAIN_ID_AGGLAYER_<n>`, `FORK_URL_AGGLAYER_<n>` and
+/// `API_BASE_URL_AGGLAYER_<n>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtraChainConfig {
+    pub network_id: u64,
+    /// Bridge service of this chain, when it runs its own aggkit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<RpcUrl>,
+    #[serde(flatten)]
+    pub chain: ChainConfig,
 }
 
 /// Individual chain configuration
@@ -43,6 +96,9 @@ pub struct ChainConfig {
     pub rpc_url: RpcUrl,
     #[allow(dead_code)]
     pub fork_url: Option<RpcUrl>,
+    /// WebSocket endpoint, preferred for subscriptions when set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ws_url: Option<WsUrl>,
 }
 
 /// Account configuration with pre-configured test accounts
@@ -50,14 +106,106 @@ pub struct ChainConfig {
 pub struct AccountConfig {
     pub accounts: Vec<EthereumAddress>,
     pub private_keys: Vec<String>, // Keep as String since private keys have different format
+    /// Named signing accounts selectable with `--account <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub named: BTreeMap<String, NamedAccount>,
+    /// Private key of the account selected with `--account`, used instead of `private_keys`
+    #[serde(skip)]
+    pub selected_key: Option<String>,
+    /// Hardware wallet selected with `--signer`, used instead of any local key
+    #[serde(skip)]
+    pub hardware_signer: Option<HardwareSigner>,
+}
+
+/// Supported hardware wallets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HardwareWallet {
+    Ledger,
+    Trezor,
+}
+
+/// Hardware wallet and derivation path used to sign transactions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HardwareSigner {
+    pub wallet: HardwareWallet,
+    /// Derivation path such as `m/44'/60'/0'/0/0`, the first Ledger Live / Trezor account when unset
+    pub hd_path: Option<String>,
 }
 
+/// Where the key of a named account comes from (`[accounts.named.<name>]`)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamedAccount {
+    /// Encrypted JSON keystore file
+    pub keystore: Option<PathBuf>,
+    /// Environment variable holding the keystore password
+    pub password_env: Option<String>,
+    /// Environment variable holding the private key
+    pub private_key_env: Option<String>,
+}
+
+/// Contract addresses discovered by `contracts refresh`, relative to the project root
+pub const CONTRACT_CACHE_FILE: &str = ".aggsandbox/contracts.json";
+
 /// Contract addresses configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ContractConfig {
     pub l1_contracts: HashMap<String, EthereumAddress>,
     pub l2_contracts: HashMap<String, EthereumAddress>,
     pub l3_contracts: HashMap<String, EthereumAddress>,
+    /// Contracts of the extra L2s keyed by layer (`l4`, `l5`, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_contracts: BTreeMap<String, HashMap<String, EthereumAddress>>,
+}
+
+/// Contract addresses cached by `contracts refresh`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractCache {
+    /// Unix time of the discovery
+    pub discovered_at: u64,
+    /// Where the addresses were found, e.g. `deployer` or `registry`
+    pub sources: Vec<String>,
+    pub contracts: ContractConfig,
+}
+
+impl ContractCache {
+    /// Cached addresses, `None` when nothing was discovered yet
+    pub fn load(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let io_error = |e: std::io::Error| {
+            ConfigError::validation_failed(&format!(
+                "Failed to write contract cache {}: {e}",
+                path.display()
+            ))
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(io_error)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| {
+            ConfigError::validation_failed(&format!("Failed to encode contract cache: {e}"))
+        })?;
+        fs::write(path, json).map_err(io_error)?;
+        Ok(())
+    }
+}
+
+/// Environment variable prefixes and names of the contracts deployed on every L2
+const L2_CONTRACT_VARS: [(&str, &str); 5] = [
+    ("POLYGON_ZKEVM_BRIDGE", "PolygonZkEVMBridge"),
+    ("POLYGON_ZKEVM_TIMELOCK", "PolygonZkEVMTimelock"),
+    ("AGG_ERC20", "AggERC20"),
+    ("BRIDGE_EXTENSION", "BridgeExtension"),
+    ("GLOBAL_EXIT_ROOT_MANAGER", "GlobalExitRootManager"),
+];
+
+/// Layer name of an Agglayer network ID (`l1` for network 0, `l4` for network 3)
+pub fn layer_name(network_id: u64) -> String {
+    format!("l{}", network_id + 1)
 }
 
 /// Custom serialization for Duration to support TOML/YAML
@@ -102,6 +250,14 @@ impl ConfigFormat {
 impl Config {
     /// Get the appropriate API base URL for a given network ID
     pub fn get_api_base_url(&self, network_id: NetworkId) -> String {
+        if let Some(api_url) = self
+            .networks
+            .extra_l2(network_id.as_u64())
+            .and_then(|extra| extra.api_url.as_ref())
+        {
+            return api_url.as_str().to_string();
+        }
+
         match network_id.as_u64() {
             // Network ID 2+ served by aggkit-l3 (port 5578)
             2..=3 => {
@@ -136,26 +292,36 @@ impl Config {
 
     /// Parse .env file directly into a HashMap
     fn parse_env_file() -> Option<std::collections::HashMap<String, String>> {
-        use std::fs;
-        if let Ok(content) = fs::read_to_string(".env") {
-            let mut env_map = std::collections::HashMap::new();
-            for line in content.lines() {
-                let line = line.trim();
-                if line.is_empty() || line.starts_with('#') {
-                    continue;
-                }
-                if let Some((key, value)) = line.split_once('=') {
-                    env_map.insert(key.trim().to_string(), value.trim().to_string());
-                }
+        fs::read_to_string(".env")
+            .ok()
+            .map(|content| Self::parse_env(&content))
+    }
+
+    /// Parse `KEY=value` lines, skipping blank lines and comments
+    pub fn parse_env(content: &str) -> std::collections::HashMap<String, String> {
+        let mut env_map = std::collections::HashMap::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                env_map.insert(key.trim().to_string(), value.trim().to_string());
             }
-            Some(env_map)
-        } else {
-            None
         }
+        env_map
     }
 
     /// Load configuration with explicit environment refresh
+    ///
+    /// Contracts missing from the configuration are filled in from the
+    /// addresses cached by `contracts refresh`.
     pub fn load_with_env_refresh(force_env_refresh: bool) -> Result<Self> {
+        Ok(Self::load_without_cache(force_env_refresh)?.with_cached_contracts())
+    }
+
+    /// Load configuration ignoring the discovered contract cache
+    pub fn load_without_cache(force_env_refresh: bool) -> Result<Self> {
         // Load .env file if it exists
         if Path::new(".env").exists() {
             if force_env_refresh {
@@ -170,26 +336,30 @@ impl Config {
         }
 
         // Try to load from configuration files first
-        let config_paths = [
-            "aggsandbox.toml",
-            "aggsandbox.yaml",
-            "aggsandbox.yml",
-            ".aggsandbox.toml",
-            ".aggsandbox.yaml",
-            ".aggsandbox.yml",
-        ];
-
-        for path_str in &config_paths {
-            let path = Path::new(path_str);
-            if path.exists() {
-                return Self::load_from_file(path);
-            }
+        if let Some(path) = Self::config_file() {
+            return Self::load_from_file(path);
         }
 
         // Fallback to environment variables and defaults
         Self::load_from_env()
     }
 
+    /// Fill contracts missing from the configuration from the discovery cache
+    pub fn with_cached_contracts(mut self) -> Self {
+        if let Some(cache) = ContractCache::load(Path::new(CONTRACT_CACHE_FILE)) {
+            self.contracts.fill_missing(&cache.contracts);
+        }
+        self
+    }
+
+    /// Config file in the working directory, in order of precedence
+    pub fn config_file() -> Option<&'static Path> {
+        CONFIG_FILES
+            .iter()
+            .map(Path::new)
+            .find(|path| path.exists())
+    }
+
     /// Load configuration from environment variables and defaults
     pub fn load_from_env() -> Result<Self> {
         let api = ApiConfig::load()?;
@@ -228,6 +398,17 @@ impl Config {
 
     /// Load configuration from a specific file
     pub fn load_from_file(path: &Path) -> Result<Self> {
+        let mut config = Self::read_file(path)?;
+
+        // Merge with environment variables (env vars take precedence)
+        config.merge_from_env();
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Parse a config file as written, without environment overrides
+    pub fn read_file(path: &Path) -> Result<Self> {
         let content = fs::read_to_string(path).map_err(|e| {
             ConfigError::validation_failed(&format!(
                 "Failed to read config file {}: {e}",
@@ -242,7 +423,7 @@ impl Config {
             ))
         })?;
 
-        let mut config: Config = match format {
+        let config: Config = match format {
             ConfigFormat::Toml => toml::from_str(&content).map_err(|e| {
                 ConfigError::validation_failed(&format!("Invalid TOML in {}: {e}", path.display()))
             })?,
@@ -251,10 +432,6 @@ impl Config {
             })?,
         };
 
-        // Merge with environment variables (env vars take precedence)
-        config.merge_from_env();
-        config.validate()?;
-
         Ok(config)
     }
 
@@ -276,6 +453,23 @@ impl Config {
                 self.api.retry_attempts = retry_attempts;
             }
         }
+        if let Ok(max_in_flight_str) = std::env::var("API_MAX_IN_FLIGHT") {
+            if let Ok(max_in_flight) = max_in_flight_str.parse::<usize>() {
+                if max_in_flight > 0 {
+                    self.api.max_in_flight = max_in_flight;
+                }
+            }
+        }
+        if let Ok(rate_limit_str) = std::env::var("API_RATE_LIMIT") {
+            if let Ok(rate_limit) = rate_limit_str.parse::<u32>() {
+                self.api.rate_limit = rate_limit;
+            }
+        }
+        if let Ok(agglayer_url) = std::env::var("AGGLAYER_URL") {
+            if let Ok(rpc_url) = RpcUrl::new(agglayer_url) {
+                self.api.agglayer_url = rpc_url;
+            }
+        }
 
         // Network configuration overrides
         if let Ok(rpc_1) = std::env::var("RPC_1") {
@@ -295,6 +489,15 @@ impl Config {
                 }
             }
         }
+        if let Some(url) = ws_url_from_env("WS_RPC_1") {
+            self.networks.l1.ws_url = Some(url);
+        }
+        if let Some(url) = ws_url_from_env("WS_RPC_2") {
+            self.networks.l2.ws_url = Some(url);
+        }
+        if let (Some(l3), Some(url)) = (&mut self.networks.l3, ws_url_from_env("WS_RPC_3")) {
+            l3.ws_url = Some(url);
+        }
 
         // Chain ID overrides
         if let Ok(chain_id) = std::env::var("CHAIN_ID_MAINNET") {
@@ -314,10 +517,20 @@ impl Config {
                 }
             }
         }
+
+        // Extra L2s from the file take env overrides, env-only ones are added
+        for extra in &mut self.networks.extra_l2s {
+            extra.apply_env();
+        }
+        for extra in ExtraChainConfig::from_env() {
+            if self.networks.extra_l2(extra.network_id).is_none() {
+                self.networks.extra_l2s.push(extra);
+            }
+        }
     }
 
     /// Validate configuration values
-    fn validate(&self) -> Result<()> {
+    pub fn validate(&self) -> Result<()> {
         // API configuration is already validated by RpcUrl constructor
         if self.api.timeout.as_millis() == 0 {
             return Err(ConfigError::validation_failed("API timeout cannot be zero").into());
@@ -327,11 +540,30 @@ impl Config {
         // Chain IDs are already validated by ChainId constructor
         // Accounts are already validated by EthereumAddress constructor
 
+        let mut seen = Vec::new();
+        for extra in &self.networks.extra_l2s {
+            if extra.network_id < 3 {
+                return Err(ConfigError::invalid_value(
+                    "networks.extra_l2s.network_id",
+                    &extra.network_id.to_string(),
+                    "networks 0-2 are configured as l1, l2 and l3",
+                )
+                .into());
+            }
+            if seen.contains(&extra.network_id) {
+                return Err(ConfigError::validation_failed(&format!(
+                    "Network {} is configured more than once in networks.extra_l2s",
+                    extra.network_id
+                ))
+                .into());
+            }
+            seen.push(extra.network_id);
+        }
+
         Ok(())
     }
 
     /// Save configuration to a file
-    #[allow(dead_code)]
     pub fn save_to_file(&self, path: &Path) -> Result<()> {
         let format = ConfigFormat::from_path(path).ok_or_else(|| {
             ConfigError::validation_failed(&format!(
@@ -366,7 +598,13 @@ impl Config {
             "anvil-l1" | "l1" => Some(&self.networks.l1),
             "anvil-l2" | "l2" => Some(&self.networks.l2),
             "anvil-l3" | "l3" => self.networks.l3.as_ref(),
-            _ => None,
+            _ => name
+                .strip_prefix("anvil-")
+                .unwrap_or(name)
+                .strip_prefix('l')
+                .and_then(|layer| layer.parse::<u64>().ok())
+                .filter(|&layer| layer > 0)
+                .and_then(|layer| self.networks.chain(layer - 1)),
         }
     }
 
@@ -406,6 +644,9 @@ impl Default for ApiConfig {
             base_url: RpcUrl::new("http://localhost:5577").unwrap(), // Safe: hardcoded default URL
             timeout: Duration::from_millis(30000),
             retry_attempts: 3,
+            max_in_flight: DEFAULT_MAX_IN_FLIGHT,
+            rate_limit: DEFAULT_RATE_LIMIT,
+            agglayer_url: default_agglayer_url(),
         }
     }
 }
@@ -435,10 +676,38 @@ impl ApiConfig {
         })?;
         let validated_retry_attempts = Validator::validate_retry_attempts(retry_attempts)?;
 
+        let max_in_flight_str =
+            get_env_var("API_MAX_IN_FLIGHT", &DEFAULT_MAX_IN_FLIGHT.to_string());
+        let max_in_flight = max_in_flight_str
+            .parse::<usize>()
+            .ok()
+            .filter(|max| *max > 0)
+            .ok_or_else(|| {
+                ConfigError::invalid_value(
+                    "API_MAX_IN_FLIGHT",
+                    &max_in_flight_str,
+                    "must be a positive number of requests",
+                )
+            })?;
+
+        let rate_limit_str = get_env_var("API_RATE_LIMIT", &DEFAULT_RATE_LIMIT.to_string());
+        let rate_limit = rate_limit_str.parse::<u32>().map_err(|_| {
+            ConfigError::invalid_value(
+                "API_RATE_LIMIT",
+                &rate_limit_str,
+                "must be a number of requests per second, 0 for no limit",
+            )
+        })?;
+
+        let agglayer_url = RpcUrl::new(get_env_var("AGGLAYER_URL", DEFAULT_AGGLAYER_URL))?;
+
         Ok(ApiConfig {
             base_url,
             timeout: Duration::from_millis(validated_timeout_ms),
             retry_attempts: validated_retry_attempts,
+            max_in_flight,
+            rate_limit,
+            agglayer_url,
         })
     }
 }
@@ -453,6 +722,7 @@ impl NetworkConfig {
             fork_url: std::env::var("FORK_URL_MAINNET")
                 .ok()
                 .and_then(|url| RpcUrl::new(url).ok()),
+            ws_url: ws_url_from_env("WS_RPC_1"),
         };
 
         let l2 = ChainConfig {
@@ -462,6 +732,7 @@ impl NetworkConfig {
             fork_url: std::env::var("FORK_URL_AGGLAYER_1")
                 .ok()
                 .and_then(|url| RpcUrl::new(url).ok()),
+            ws_url: ws_url_from_env("WS_RPC_2"),
         };
 
         // L3 is optional for multi-L2 mode
@@ -473,12 +744,123 @@ impl NetworkConfig {
                 fork_url: std::env::var("FORK_URL_AGGLAYER_2")
                     .ok()
                     .and_then(|url| RpcUrl::new(url).ok()),
+                ws_url: ws_url_from_env("WS_RPC_3"),
             })
         } else {
             None
         };
 
-        NetworkConfig { l1, l2, l3 }
+        NetworkConfig {
+            l1,
+            l2,
+            l3,
+            extra_l2s: ExtraChainConfig::from_env(),
+        }
+    }
+
+    /// Chain of an Agglayer network ID, if configured
+    pub fn chain(&self, network_id: u64) -> Option<&ChainConfig> {
+        match network_id {
+            0 => Some(&self.l1),
+            1 => Some(&self.l2),
+            2 => self.l3.as_ref(),
+            _ => self.extra_l2(network_id).map(|extra| &extra.chain),
+        }
+    }
+
+    /// Extra L2 registered with a network ID
+    pub fn extra_l2(&self, network_id: u64) -> Option<&ExtraChainConfig> {
+        self.extra_l2s
+            .iter()
+            .find(|extra| extra.network_id == network_id)
+    }
+
+    /// Network IDs of every configured chain, in ascending order
+    pub fn network_ids(&self) -> Vec<u64> {
+        let mut network_ids = vec![0, 1];
+        if self.l3.is_some() {
+            network_ids.push(2);
+        }
+        let mut extra: Vec<u64> = self
+            .extra_l2s
+            .iter()
+            .map(|extra| extra.network_id)
+            .filter(|&network_id| network_id > 2)
+            .collect();
+        extra.sort_unstable();
+        extra.dedup();
+        network_ids.extend(extra);
+        network_ids
+    }
+}
+
+impl ExtraChainConfig {
+    /// Extra L2s configured through `RPC_<n+1>` for network IDs 3 onwards
+    fn from_env() -> Vec<Self> {
+        let mut network_ids: Vec<u64> = std::env::vars()
+            .filter_map(|(key, _)| key.strip_prefix("RPC_")?.parse::<u64>().ok())
+            .filter(|&index| index >= 4)
+            .map(|index| index - 1)
+            .collect();
+        network_ids.sort_unstable();
+        network_ids.dedup();
+
+        network_ids
+            .into_iter()
+            .filter_map(|network_id| {
+                let rpc_url =
+                    RpcUrl::new(std::env::var(format!("RPC_{}", network_id + 1)).ok()?).ok()?;
+                let chain_id = std::env::var(format!("CHAIN_ID_AGGLAYER_{network_id}"))
+                    .ok()
+                    .and_then(|id| ChainId::new(id).ok())
+                    .or_else(|| ChainId::new((1100 + network_id).to_string()).ok())?;
+                let mut extra = ExtraChainConfig {
+                    network_id,
+                    api_url: None,
+                    chain: ChainConfig {
+                        name: format!("Agglayer-{network_id}"),
+                        chain_id,
+                        rpc_url,
+                        fork_url: None,
+                        ws_url: None,
+                    },
+                };
+                extra.apply_env();
+                Some(extra)
+            })
+            .collect()
+    }
+
+    /// Apply the environment overrides of this network
+    fn apply_env(&mut self) {
+        let network_id = self.network_id;
+        if let Some(url) = std::env::var(format!("RPC_{}", network_id + 1))
+            .ok()
+            .and_then(|url| RpcUrl::new(url).ok())
+        {
+            self.chain.rpc_url = url;
+        }
+        if let Some(id) = std::env::var(format!("CHAIN_ID_AGGLAYER_{network_id}"))
+            .ok()
+            .and_then(|id| ChainId::new(id).ok())
+        {
+            self.chain.chain_id = id;
+        }
+        if let Some(url) = std::env::var(format!("FORK_URL_AGGLAYER_{network_id}"))
+            .ok()
+            .and_then(|url| RpcUrl::new(url).ok())
+        {
+            self.chain.fork_url = Some(url);
+        }
+        if let Some(url) = ws_url_from_env(&format!("WS_RPC_{}", network_id + 1)) {
+            self.chain.ws_url = Some(url);
+        }
+        if let Some(url) = std::env::var(format!("API_BASE_URL_AGGLAYER_{network_id}"))
+            .ok()
+            .and_then(|url| RpcUrl::new(url).ok())
+        {
+            self.api_url = Some(url);
+        }
     }
 }
 
@@ -515,6 +897,9 @@ impl AccountConfig {
         AccountConfig {
             accounts,
             private_keys,
+            named: BTreeMap::new(),
+            selected_key: None,
+            hardware_signer: None,
         }
     }
 }
@@ -658,11 +1043,97 @@ impl ContractConfig {
             &env_override,
         );
 
+        // Extra L2 contracts follow the L3 naming with their layer number (L4, L5, ...)
+        let mut extra_contracts = BTreeMap::new();
+        for layer in Self::extra_layers(&env_override) {
+            let mut contracts = HashMap::new();
+            for (prefix, name) in L2_CONTRACT_VARS {
+                add_contract(
+                    &mut contracts,
+                    &format!("{prefix}_L{layer}"),
+                    name,
+                    &env_override,
+                );
+            }
+            if !contracts.is_empty() {
+                extra_contracts.insert(format!("l{layer}"), contracts);
+            }
+        }
+
         ContractConfig {
             l1_contracts,
             l2_contracts,
             l3_contracts,
+            extra_contracts,
+        }
+    }
+
+    /// Contract addresses from the variables of a deployment env file
+    pub fn from_env_map(env_map: std::collections::HashMap<String, String>) -> Self {
+        Self::load_with_env_override(Some(env_map))
+    }
+
+    /// Contracts of every layer, `l1` to `l3` first and then the extra L2s
+    pub fn layers(&self) -> Vec<(&str, &HashMap<String, EthereumAddress>)> {
+        let mut layers = vec![
+            ("l1", &self.l1_contracts),
+            ("l2", &self.l2_contracts),
+            ("l3", &self.l3_contracts),
+        ];
+        layers.extend(
+            self.extra_contracts
+                .iter()
+                .map(|(layer, contracts)| (layer.as_str(), contracts)),
+        );
+        layers
+    }
+
+    /// Contracts of a layer, created empty for an extra L2 without any yet
+    pub fn layer_mut(&mut self, layer: &str) -> &mut HashMap<String, EthereumAddress> {
+        match layer {
+            "l1" => &mut self.l1_contracts,
+            "l2" => &mut self.l2_contracts,
+            "l3" => &mut self.l3_contracts,
+            _ => self.extra_contracts.entry(layer.to_string()).or_default(),
+        }
+    }
+
+    /// Add the contracts of `other` this configuration lacks, returning how many
+    pub fn fill_missing(&mut self, other: &ContractConfig) -> usize {
+        let mut added = 0;
+        for (layer, contracts) in other.layers() {
+            if contracts.is_empty() {
+                continue;
+            }
+            let target = self.layer_mut(layer);
+            for (name, address) in contracts {
+                if !target.contains_key(name) {
+                    target.insert(name.clone(), address.clone());
+                    added += 1;
+                }
+            }
         }
+        added
+    }
+
+    /// Layer numbers from 4 onwards with a bridge address in the environment
+    fn extra_layers(env_override: &Option<std::collections::HashMap<String, String>>) -> Vec<u64> {
+        let keys: Vec<String> = match env_override {
+            Some(env_map) => env_map.keys().cloned().collect(),
+            None => std::env::vars().map(|(key, _)| key).collect(),
+        };
+        let mut layers: Vec<u64> = keys
+            .iter()
+            .filter_map(|key| {
+                key.strip_prefix("POLYGON_ZKEVM_BRIDGE_L")?
+                    .parse::<u64>()
+                    .ok()
+            })
+            .filter(|&layer| layer >= 4)
+            .collect();
+        layers.sort_unstable();
+        layers.dedup();
+        layers
     }
 
     /// Get contract address with fallback to "Not deployed"
@@ -683,18 +1154,95 @@ impl ContractConfig {
                 .get(name)
                 .map(|addr| addr.as_str().to_string())
                 .unwrap_or_else(|| "Not deployed".to_string()),
-            _ => "Not deployed".to_string(),
+            _ => self
+                .extra_contracts
+                .get(layer)
+                .and_then(|contracts| contracts.get(name))
+                .map(|addr| addr.as_str().to_string())
+                .unwrap_or_else(|| "Not deployed".to_string()),
         }
     }
 }
 
+/// Environment variables setting configuration keys of the first three networks
+///
+/// Extra L2s follow the same scheme with their network ID, see [`ExtraChainConfig`].
+pub const ENV_VARS: &[(&str, &str)] = &[
+    ("api.base_url", "API_BASE_URL"),
+    ("api.timeout", "API_TIMEOUT_MS"),
+    ("api.retry_attempts", "API_RETRY_ATTEMPTS"),
+    ("api.max_in_flight", "API_MAX_IN_FLIGHT"),
+    ("api.rate_limit", "API_RATE_LIMIT"),
+    ("api.agglayer_url", "AGGLAYER_URL"),
+    ("networks.l1.chain_id", "CHAIN_ID_MAINNET"),
+    ("networks.l1.rpc_url", "RPC_1"),
+    ("networks.l1.fork_url", "FORK_URL_MAINNET"),
+    ("networks.l1.ws_url", "WS_RPC_1"),
+    ("networks.l2.chain_id", "CHAIN_ID_AGGLAYER_1"),
+    ("networks.l2.rpc_url", "RPC_2"),
+    ("networks.l2.fork_url", "FORK_URL_AGGLAYER_1"),
+    ("networks.l2.ws_url", "WS_RPC_2"),
+    ("networks.l3.chain_id", "CHAIN_ID_AGGLAYER_2"),
+    ("networks.l3.rpc_url", "RPC_3"),
+    ("networks.l3.fork_url", "FORK_URL_AGGLAYER_2"),
+    ("networks.l3.ws_url", "WS_RPC_3"),
+];
+
 /// Helper function to get environment variable with fallback
 fn get_env_var(key: &str, fallback: &str) -> String {
     std::env::var(key).unwrap_or_else(|_| fallback.to_string())
 }
 
+/// WebSocket URL from an environment variable, ignoring invalid values
+fn ws_url_from_env(key: &str) -> Option<WsUrl> {
+    std::env::var(key).ok().and_then(|url| WsUrl::new(url).ok())
+}
+
 /// Validation helpers
 impl Config {
+    /// Re-check values that config files deserialize without their constructors
+    ///
+    /// URLs, chain IDs and addresses read from a file are taken as written;
+    /// `config validate` runs them through the same checks as the environment.
+    pub fn validate_values(&self) -> Result<()> {
+        RpcUrl::new(self.api.base_url.as_str())?;
+        if self.api.max_in_flight == 0 {
+            return Err(ConfigError::invalid_value(
+                "api.max_in_flight",
+                "0",
+                "must be a positive number of requests",
+            )
+            .into());
+        }
+        for network_id in self.networks.network_ids() {
+            let Some(chain) = self.networks.chain(network_id) else {
+                continue;
+            };
+            ChainId::new(chain.chain_id.as_str())?;
+            RpcUrl::new(chain.rpc_url.as_str())?;
+            if let Some(fork_url) = &chain.fork_url {
+                RpcUrl::new(fork_url.as_str())?;
+            }
+            if let Some(ws_url) = &chain.ws_url {
+                WsUrl::new(ws_url.as_str())?;
+            }
+        }
+        for extra in &self.networks.extra_l2s {
+            if let Some(api_url) = &extra.api_url {
+                RpcUrl::new(api_url.as_str())?;
+            }
+        }
+        for account in &self.accounts.accounts {
+            EthereumAddress::new(account.as_str())?;
+        }
+        for (_, contracts) in self.contracts.layers() {
+            for address in contracts.values() {
+                EthereumAddress::new(address.as_str())?;
+            }
+        }
+        Ok(())
+    }
+
     /// Validate fork mode configuration
     #[allow(dead_code)]
     pub fn validate_fork_mode(&self, multi_l2: bool) -> Result<()> {
@@ -722,6 +1270,46 @@ impl Config {
         Ok(())
     }
 
+    /// Validate that every configured endpoint stays on the local machine
+    ///
+    /// Fork URLs are not checked here because they are only contacted in fork
+    /// mode, which offline mode rejects up front.
+    pub fn validate_offline(&self) -> Result<()> {
+        Validator::validate_local_url(self.api.base_url.as_str(), "API_BASE_URL")?;
+        Validator::validate_local_url(self.api.agglayer_url.as_str(), "AGGLAYER_URL")?;
+        Validator::validate_local_url(self.networks.l1.rpc_url.as_str(), "RPC_1")?;
+        Validator::validate_local_url(self.networks.l2.rpc_url.as_str(), "RPC_2")?;
+        if let Some(l3) = &self.networks.l3 {
+            Validator::validate_local_url(l3.rpc_url.as_str(), "RPC_3")?;
+        }
+        for extra in &self.networks.extra_l2s {
+            Validator::validate_local_url(
+                extra.chain.rpc_url.as_str(),
+                &format!("RPC_{}", extra.network_id + 1),
+            )?;
+            if let Some(api_url) = &extra.api_url {
+                Validator::validate_local_url(
+                    api_url.as_str(),
+                    &format!("API_BASE_URL_AGGLAYER_{}", extra.network_id),
+                )?;
+            }
+        }
+        for network_id in self.networks.network_ids() {
+            if let Some(ws_url) = self
+                .networks
+                .chain(network_id)
+                .and_then(|c| c.ws_url.as_ref())
+            {
+                Validator::validate_local_url(
+                    ws_url.as_str(),
+                    &format!("WS_RPC_{}", network_id + 1),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get fork URLs for display
     #[allow(dead_code)]
     pub fn get_fork_urls(&self, multi_l2: bool) -> Vec<(String, String)> {
@@ -760,6 +1348,7 @@ impl Default for Config {
 }
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod tests {
     use super::*;
 
@@ -894,6 +1483,32 @@ mod tests {
         assert_eq!(loaded_yaml.api.base_url, original_config.api.base_url);
     }
 
+    #[test]
+    fn test_validate_offline() {
+        let mut config = Config::default();
+        assert!(config.validate_offline().is_ok());
+
+        config.networks.l1.rpc_url = RpcUrl::new("https://eth.llamarpc.com").unwrap();
+        assert!(config.validate_offline().is_err());
+    }
+
+    #[test]
+    fn test_validate_offline_agglayer_endpoints() {
+        let mut config = Config::default();
+        config.api.agglayer_url = RpcUrl::new("https://agglayer.example.com").unwrap();
+        let error = config.validate_offline().unwrap_err().to_string();
+        assert!(error.contains("AGGLAYER_URL"), "{error}");
+
+        let mut config = Config::default();
+        config.networks.extra_l2s.push(ExtraChainConfig {
+            network_id: 3,
+            api_url: Some(RpcUrl::new("https://bridge.example.com").unwrap()),
+            chain: config.networks.l2.clone(),
+        });
+        let error = config.validate_offline().unwrap_err().to_string();
+        assert!(error.contains("API_BASE_URL_AGGLAYER_3"), "{error}");
+    }
+
     #[test]
     fn test_get_api_base_url() {
         let config = Config::load().unwrap();
@@ -974,4 +1589,133 @@ mod tests {
             "http://api.example.com:5578"
         );
     }
+
+    fn extra_l2(network_id: u64) -> ExtraChainConfig {
+        ExtraChainConfig {
+            network_id,
+            api_url: Some(RpcUrl::new(format!("http://localhost:{}", 5576 + network_id)).unwrap()),
+            chain: ChainConfig {
+                name: format!("Agglayer-{network_id}"),
+                chain_id: ChainId::new((1100 + network_id).to_string()).unwrap(),
+                rpc_url: RpcUrl::new(format!("http://localhost:{}", 8545 + network_id)).unwrap(),
+                fork_url: None,
+                ws_url: None,
+            },
+        }
+    }
+
+    #[test]
+    fn test_extra_l2_networks() {
+        let mut config = Config::default();
+        config.networks.l3 = None;
+        config.networks.extra_l2s = vec![extra_l2(4), extra_l2(3)];
+
+        assert_eq!(config.networks.network_ids(), vec![0, 1, 3, 4]);
+        assert_eq!(
+            config.networks.chain(3).unwrap().rpc_url.as_str(),
+            "http://localhost:8548"
+        );
+        assert!(config.networks.chain(2).is_none());
+        assert!(config.networks.chain(5).is_none());
+        assert_eq!(config.get_chain("anvil-l5").unwrap().name, "Agglayer-4");
+        assert_eq!(
+            config.get_api_base_url(NetworkId::new(3).unwrap()),
+            "http://localhost:5579"
+        );
+        assert!(config.validate().is_ok());
+
+        config.networks.extra_l2s.push(extra_l2(3));
+        assert!(config.validate().is_err());
+        config.networks.extra_l2s = vec![extra_l2(2)];
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_extra_contracts_from_env_map() {
+        let env_map = HashMap::from([
+            (
+                "POLYGON_ZKEVM_BRIDGE_L4".to_string(),
+                "0x2279B7A0a67DB372996a5FaB50D91eAA73d2eBe6".to_string(),
+            ),
+            (
+                "AGG_ERC20_L4".to_string(),
+                "0x8A791620dd6260079BF849Dc5567aDC3F2FdC318".to_string(),
+            ),
+            (
+                "AGG_ERC20_L5".to_string(),
+                "0x610178dA211FEF7D417bC0e6FeD39F05609AD788".to_string(),
+            ),
+        ]);
+        let contracts = ContractConfig::load_with_env_override(Some(env_map));
+
+        assert_eq!(layer_name(3), "l4");
+        assert_eq!(
+            contracts.get_contract("l4", "PolygonZkEVMBridge"),
+            "0x2279B7A0a67DB372996a5FaB50D91eAA73d2eBe6"
+        );
+        assert_eq!(
+            contracts.get_contract("l4", "AggERC20"),
+            "0x8A791620dd6260079BF849Dc5567aDC3F2FdC318"
+        );
+        // Without a bridge address the layer is not picked up
+        assert_eq!(contracts.get_contract("l5", "AggERC20"), "Not deployed");
+    }
+
+    #[test]
+    fn test_extra_l2_toml_round_trip() {
+        let mut config = Config::default();
+        config.networks.extra_l2s = vec![extra_l2(3)];
+
+        let toml_str = toml::to_string(&config).unwrap();
+        assert!(toml_str.contains("[[networks.extra_l2s]]"));
+        let deserialized: Config = toml::from_str(&toml_str).unwrap();
+        let extra = deserialized.networks.extra_l2(3).unwrap();
+        assert_eq!(extra.chain.chain_id.as_str(), "1103");
+        assert_eq!(
+            extra.api_url.as_ref().unwrap().as_str(),
+            "http://localhost:5579"
+        );
+    }
+
+    #[test]
+    fn test_cached_contracts_only_fill_missing_entries() {
+        let address = |a: &str| EthereumAddress::new(a).unwrap();
+        let mut configured = ContractConfig::default();
+        configured.l1_contracts.insert(
+            "PolygonZkEVMBridge".to_string(),
+            address("0x1111111111111111111111111111111111111111"),
+        );
+
+        let mut discovered = ContractConfig::default();
+        discovered.l1_contracts.insert(
+            "PolygonZkEVMBridge".to_string(),
+            address("0x2222222222222222222222222222222222222222"),
+        );
+        discovered.layer_mut("l4").insert(
+            "AggERC20".to_string(),
+            address("0x3333333333333333333333333333333333333333"),
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".aggsandbox").join("contracts.json");
+        assert!(ContractCache::load(&path).is_none());
+        ContractCache {
+            discovered_at: 1,
+            sources: vec!["registry".to_string()],
+            contracts: discovered,
+        }
+        .save(&path)
+        .unwrap();
+        let cache = ContractCache::load(&path).unwrap();
+
+        assert_eq!(configured.fill_missing(&cache.contracts), 1);
+        assert_eq!(
+            configured.get_contract("l1", "PolygonZkEVMBridge"),
+            "0x1111111111111111111111111111111111111111"
+        );
+        assert_eq!(
+            configured.get_contract("l4", "AggERC20"),
+            "0x3333333333333333333333333333333333333333"
+        );
+    }
 }
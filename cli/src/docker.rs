@@ -1,8 +1,17 @@
 use crate::error::{DockerError, Result};
 use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+/// Compose file written by `start --scale l2=<n>` for more than two L2s
+pub const GENERATED_COMPOSE_FILE: &str = "docker-compose.generated.yml";
+
+/// Most L2s `--scale` starts; beyond, host ports of the anvil and aggkit
+/// services would overlap
+pub const MAX_SCALED_L2S: u64 = 8;
+
 /// Detect which Docker Compose command is available
 /// Returns "docker" if `docker compose` is available, otherwise "docker-compose"
 fn get_compose_command() -> &'static str {
@@ -59,6 +68,7 @@ pub struct DockerComposeBuilder {
     files: Vec<String>,
     env_vars: HashMap<String, String>,
     services: Vec<String>,
+    offline: bool,
 }
 
 impl DockerComposeBuilder {
@@ -68,6 +78,7 @@ impl DockerComposeBuilder {
             files: vec!["docker-compose.yml".to_string()],
             env_vars: HashMap::new(),
             services: Vec::new(),
+            offline: false,
         }
     }
 
@@ -95,6 +106,12 @@ impl DockerComposeBuilder {
         self
     }
 
+    /// Never pull images from a registry (used by `--offline` mode)
+    pub fn set_offline(&mut self, offline: bool) -> &mut Self {
+        self.offline = offline;
+        self
+    }
+
     /// Build a docker-compose up command
     pub fn build_up_command(&self, detach: bool, build: bool) -> Command {
         let (program, base_args) = get_compose_command_parts();
@@ -120,6 +137,10 @@ impl DockerComposeBuilder {
             cmd.arg("--build");
         }
 
+        if self.offline {
+            cmd.args(["--pull", "never"]);
+        }
+
         // Add environment variables
         for (key, value) in &self.env_vars {
             cmd.env(key, value);
@@ -182,8 +203,48 @@ impl DockerComposeBuilder {
         cmd
     }
 
-    /// Build a docker-compose logs command
-    pub fn build_logs_command(&self, follow: bool) -> Command {
+    /// Build a docker-compose cp command copying a file out of a service's container
+    pub fn build_cp_command(&self, service: &str, source: &str, destination: &Path) -> Command {
+        let (program, base_args) = get_compose_command_parts();
+        let mut cmd = Command::new(program);
+
+        // Add base arguments (e.g., "compose" for modern docker command)
+        for arg in base_args {
+            cmd.arg(arg);
+        }
+
+        // Add compose files
+        for file in &self.files {
+            cmd.arg("-f").arg(file);
+        }
+
+        cmd.arg("cp")
+            .arg(format!("{service}:{source}"))
+            .arg(destination);
+
+        // Add environment variables
+        for (key, value) in &self.env_vars {
+            cmd.env(key, value);
+        }
+
+        cmd
+    }
+
+    /// Build a docker-compose ps command with JSON output
+    pub fn build_ps_json_command(&self) -> Command {
+        let mut cmd = self.build_ps_command();
+        cmd.args(["--format", "json"]);
+        cmd
+    }
+
+    /// Build a docker-compose logs command, optionally limited to logs newer than `since`
+    /// and to the last `tail` lines of each service
+    pub fn build_logs_command(
+        &self,
+        follow: bool,
+        since: Option<&str>,
+        tail: Option<usize>,
+    ) -> Command {
         let (program, base_args) = get_compose_command_parts();
         let mut cmd = Command::new(program);
 
@@ -203,6 +264,14 @@ impl DockerComposeBuilder {
             cmd.arg("-f");
         }
 
+        if let Some(since) = since {
+            cmd.args(["--since", since]);
+        }
+
+        if let Some(tail) = tail {
+            cmd.arg("--tail").arg(tail.to_string());
+        }
+
         // Add services if specified
         for service in &self.services {
             cmd.arg(service);
@@ -215,6 +284,40 @@ impl DockerComposeBuilder {
 
         cmd
     }
+
+    /// Build a docker-compose config command listing the images of the services
+    pub fn build_config_images_command(&self) -> Command {
+        self.build_command(&["config", "--images"])
+    }
+
+    /// Build a docker-compose pull command for the targeted (or all) services
+    pub fn build_pull_command(&self) -> Command {
+        let mut cmd = self.build_command(&["pull"]);
+        cmd.args(&self.services);
+        cmd
+    }
+
+    /// Build a docker-compose restart command for the targeted (or all) services
+    pub fn build_restart_command(&self) -> Command {
+        let mut cmd = self.build_command(&["restart"]);
+        cmd.args(&self.services);
+        cmd
+    }
+
+    /// Compose command with the files and environment of this builder
+    fn build_command(&self, args: &[&str]) -> Command {
+        let (program, base_args) = get_compose_command_parts();
+        let mut cmd = Command::new(program);
+        cmd.args(base_args);
+        for file in &self.files {
+            cmd.arg("-f").arg(file);
+        }
+        cmd.args(args);
+        for (key, value) in &self.env_vars {
+            cmd.env(key, value);
+        }
+        cmd
+    }
 }
 
 impl Default for DockerComposeBuilder {
@@ -229,6 +332,9 @@ pub struct SandboxConfig {
     pub fork_mode: bool,
     pub multi_l2_mode: bool,
     pub claim_all: bool,
+    pub offline: bool,
+    /// Number of L2 chains, more than two run from [`GENERATED_COMPOSE_FILE`]
+    pub l2_count: u64,
 }
 
 impl SandboxConfig {
@@ -238,9 +344,29 @@ impl SandboxConfig {
             fork_mode,
             multi_l2_mode,
             claim_all,
+            offline: false,
+            l2_count: if multi_l2_mode { 2 } else { 1 },
         }
     }
 
+    /// Run `l2_count` L2 chains (`start --scale l2=<n>`)
+    pub fn with_l2_count(mut self, l2_count: u64) -> Self {
+        self.l2_count = l2_count.max(1);
+        self.multi_l2_mode = self.l2_count > 1;
+        self
+    }
+
+    /// Whether the services come from [`GENERATED_COMPOSE_FILE`]
+    pub fn is_generated(&self) -> bool {
+        self.l2_count > 2
+    }
+
+    /// Enable offline mode, which forbids fork mode and image pulls
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
     /// Get the mode description for display
     pub fn mode_description(&self) -> &'static str {
         match (self.fork_mode, self.multi_l2_mode) {
@@ -257,6 +383,13 @@ impl SandboxConfig {
             return Ok(());
         }
 
+        if self.offline {
+            return Err(DockerError::compose_validation_failed(
+                "Fork mode requires internet access and cannot be used with --offline",
+            )
+            .into());
+        }
+
         // Check required fork URLs
         let fork_mainnet = std::env::var("FORK_URL_MAINNET").unwrap_or_default();
         let fork_agglayer_1 = std::env::var("FORK_URL_AGGLAYER_1").unwrap_or_default();
@@ -276,12 +409,12 @@ impl SandboxConfig {
         }
 
         // Additional validation for multi-L2 fork mode
-        if self.multi_l2_mode {
-            let fork_agglayer_2 = std::env::var("FORK_URL_AGGLAYER_2").unwrap_or_default();
-            if fork_agglayer_2.is_empty() {
-                return Err(DockerError::compose_validation_failed(
-                    "FORK_URL_AGGLAYER_2 environment variable is not set for multi-L2 fork mode",
-                )
+        for network_id in 2..=self.l2_count {
+            let var = format!("FORK_URL_AGGLAYER_{network_id}");
+            if std::env::var(&var).unwrap_or_default().is_empty() {
+                return Err(DockerError::compose_validation_failed(&format!(
+                    "{var} environment variable is not set for multi-L2 fork mode"
+                ))
                 .into());
             }
         }
@@ -293,8 +426,10 @@ impl SandboxConfig {
     pub fn create_docker_builder(&self) -> DockerComposeBuilder {
         let mut builder = DockerComposeBuilder::new();
 
-        // In multi-L2 mode, use only the multi-L2 compose file
-        if self.multi_l2_mode {
+        // In multi-L2 mode, use only the multi-L2 (or generated) compose file
+        if self.is_generated() {
+            builder.set_files(vec![GENERATED_COMPOSE_FILE]);
+        } else if self.multi_l2_mode {
             builder.set_files(vec!["docker-compose.multi-l2.yml"]);
         }
 
@@ -309,9 +444,10 @@ impl SandboxConfig {
             if let Ok(fork_agglayer_1) = std::env::var("FORK_URL_AGGLAYER_1") {
                 builder.add_env("FORK_URL_AGGLAYER_1", fork_agglayer_1);
             }
-            if self.multi_l2_mode {
-                if let Ok(fork_agglayer_2) = std::env::var("FORK_URL_AGGLAYER_2") {
-                    builder.add_env("FORK_URL_AGGLAYER_2", fork_agglayer_2);
+            for network_id in 2..=self.l2_count {
+                let var = format!("FORK_URL_AGGLAYER_{network_id}");
+                if let Ok(fork_url) = std::env::var(&var) {
+                    builder.add_env(var, fork_url);
                 }
             }
         } else {
@@ -322,6 +458,8 @@ impl SandboxConfig {
             builder.add_env("AGGKIT_CLAIMSPONSOR_CLAIM_ALL", "true");
         }
 
+        builder.set_offline(self.offline);
+
         // Set chain IDs
         let chain_id_mainnet =
             std::env::var("CHAIN_ID_MAINNET").unwrap_or_else(|_| "1".to_string());
@@ -331,20 +469,309 @@ impl SandboxConfig {
         builder.add_env("CHAIN_ID_MAINNET", chain_id_mainnet);
         builder.add_env("CHAIN_ID_AGGLAYER_1", chain_id_agglayer_1);
 
-        if self.multi_l2_mode {
-            let chain_id_agglayer_2 =
-                std::env::var("CHAIN_ID_AGGLAYER_2").unwrap_or_else(|_| "1102".to_string());
-            builder.add_env("CHAIN_ID_AGGLAYER_2", chain_id_agglayer_2);
+        for network_id in 2..=self.l2_count {
+            let var = format!("CHAIN_ID_AGGLAYER_{network_id}");
+            let chain_id =
+                std::env::var(&var).unwrap_or_else(|_| default_chain_id(network_id).to_string());
+            builder.add_env(var, chain_id);
         }
 
         builder
     }
 }
 
+/// Parse a `--scale` value such as `l2=3` into the number of L2 chains
+pub fn parse_scale(value: &str) -> std::result::Result<u64, String> {
+    let count = value
+        .strip_prefix("l2=")
+        .ok_or_else(|| format!("expected l2=<n>, got '{value}'"))?;
+    let count: u64 = count
+        .parse()
+        .map_err(|_| format!("'{count}' is not a number of L2 chains"))?;
+    if !(1..=MAX_SCALED_L2S).contains(&count) {
+        return Err(format!(
+            "the number of L2 chains must be between 1 and {MAX_SCALED_L2S}"
+        ));
+    }
+    Ok(count)
+}
+
+/// Default chain ID of the L2 with an Agglayer network ID, as in [`crate::config`]
+fn default_chain_id(network_id: u64) -> u64 {
+    1100 + network_id
+}
+
+/// Host port of the RPC node of an L2
+fn l2_rpc_port(network_id: u64) -> u64 {
+    8545 + network_id
+}
+
+/// Host port of the bridge service (aggkit REST API) of an L2
+fn l2_api_port(network_id: u64) -> u64 {
+    5576 + network_id
+}
+
+/// Compose service suffix of an L2: network 1 is `l2`, network 2 `l3` and so on
+fn l2_suffix(network_id: u64) -> String {
+    format!("l{}", network_id + 1)
+}
+
+const ANVIL_HEALTHCHECK: &str = r#"    healthcheck:
+      test: ["CMD-SHELL", "timeout 1 bash -c '</dev/tcp/localhost/8545'"]
+      interval: 5s
+      timeout: 3s
+      retries: 5
+      start_period: 10s
+"#;
+
+const AGGKIT_WAIT_SERVICE: &str = r#"  # Contract verification service for AggKit startup
+  aggkit-wait:
+    image: alpine:latest
+    pull_policy: missing
+    depends_on:
+      contract-deployer:
+        condition: service_completed_successfully
+    networks:
+      - anvil-network
+    command:
+      - sh
+      - -c
+      - |
+        apk add curl jq
+        echo 'Waiting for contracts to be deployed and accessible...'
+        for i in $$(seq 1 30); do
+          if [ -f '/app/output/deployed-contracts.env' ]; then
+            source /app/output/deployed-contracts.env
+            if [ -n "$$POLYGON_ROLLUP_MANAGER_L1" ]; then
+              RESPONSE=$$(curl -s -X POST http://anvil-l1:8545 -H 'Content-Type: application/json' -d "{\"jsonrpc\":\"2.0\",\"method\":\"eth_getCode\",\"params\":[\"$$POLYGON_ROLLUP_MANAGER_L1\",\"latest\"],\"id\":1}" 2>/dev/null || echo '{"result":"0x"}')
+              CODE=$$(echo "$$RESPONSE" | jq -r '.result' 2>/dev/null || echo "0x")
+              if echo "$$CODE" | grep -q '^0x[0-9a-fA-F]\{3,\}$$'; then
+                echo "Contract verified at address $$POLYGON_ROLLUP_MANAGER_L1"
+                exit 0
+              fi
+            fi
+          fi
+          echo "Attempt $$i/30: Contract not ready, waiting..."
+          sleep 5
+        done
+        echo 'Contract verification failed after 150s'
+        exit 1
+    volumes:
+      - ./.env:/app/output/deployed-contracts.env:ro
+"#;
+
+/// Compose file running L1 and `l2_count` L2 chains, each with its own aggkit
+///
+/// The layout follows `docker-compose.multi-l2.yml`: the L2 with network ID
+/// `n` runs as `anvil-l<n+1>` on host port `8545+n`, and its aggkit as
+/// `aggkit-l<n+1>` serving the REST API on `5576+n`, RPC on `8554+n` and
+/// telemetry on `8079+n`.
+pub fn generate_compose(l2_count: u64) -> String {
+    let networks: Vec<u64> = (1..=l2_count).collect();
+    let mut file =
+        String::from("# Generated by `aggsandbox start --scale`, do not edit\nservices:\n");
+
+    let _ = write!(
+        file,
+        r#"  anvil-l1:
+    image: ametelnethermind/aggsandbox-anvil:latest
+    ports:
+      - "8545:8545"
+    networks:
+      - anvil-network
+    environment:
+      - ENABLE_FORK_MODE=${{ENABLE_FORK_MODE:-false}}
+      - FORK_URL_MAINNET=${{FORK_URL_MAINNET:-}}
+      - CHAIN_ID_MAINNET=${{CHAIN_ID_MAINNET:-1}}
+{ANVIL_HEALTHCHECK}
+"#
+    );
+    for &network_id in &networks {
+        let suffix = l2_suffix(network_id);
+        let _ = write!(
+            file,
+            r#"  anvil-{suffix}:
+    image: ametelnethermind/aggsandbox-anvil:latest
+    ports:
+      - "{port}:8545"
+    networks:
+      - anvil-network
+    environment:
+      - ENABLE_FORK_MODE=${{ENABLE_FORK_MODE:-false}}
+      - FORK_URL_AGGLAYER_{network_id}=${{FORK_URL_AGGLAYER_{network_id}:-}}
+      - CHAIN_ID_AGGLAYER_{network_id}=${{CHAIN_ID_AGGLAYER_{network_id}:-{chain_id}}}
+{ANVIL_HEALTHCHECK}
+"#,
+            port = l2_rpc_port(network_id),
+            chain_id = default_chain_id(network_id),
+        );
+    }
+
+    file.push_str(
+        r#"  contract-deployer:
+    image: ametelnethermind/aggsandbox-deployer:latest
+    pull_policy: always
+    depends_on:
+      anvil-l1:
+        condition: service_healthy
+"#,
+    );
+    for &network_id in &networks {
+        let _ = writeln!(
+            file,
+            "      anvil-{}:\n        condition: service_healthy",
+            l2_suffix(network_id)
+        );
+    }
+    file.push_str("    networks:\n      - anvil-network\n    environment:\n      - RPC_URL_1=http://anvil-l1:8545\n");
+    for &network_id in &networks {
+        let _ = writeln!(
+            file,
+            "      - RPC_URL_{}=http://anvil-{}:8545",
+            network_id + 1,
+            l2_suffix(network_id)
+        );
+    }
+    // The deployer registers the rollups of the L2s with their chain IDs
+    for &network_id in &networks {
+        let _ = writeln!(
+            file,
+            "      - CHAIN_ID_AGGLAYER_{network_id}=${{CHAIN_ID_AGGLAYER_{network_id}:-{}}}",
+            default_chain_id(network_id)
+        );
+    }
+    file.push_str("    volumes:\n      - ./.env:/app/output/deployed-contracts.env\n\n");
+    file.push_str(AGGKIT_WAIT_SERVICE);
+
+    for &network_id in &networks {
+        let suffix = l2_suffix(network_id);
+        let _ = write!(
+            file,
+            r#"
+  aggkit-{suffix}:
+    image: ametelnethermind/aggkit:latest
+    pull_policy: always
+    depends_on:
+      contract-deployer:
+        condition: service_completed_successfully
+      aggkit-wait:
+        condition: service_completed_successfully
+    ports:
+      - "{rest}:{rest}" # REST API
+      - "{telemetry}:{telemetry}" # Telemetry
+      - "{rpc}:{rpc}" # RPC
+    networks:
+      - anvil-network
+    volumes:
+      - ./config:/app/config
+      - aggkit-{suffix}-data:/app/data
+      - ./.env:/app/.env:ro
+    environment:
+      - AGGKIT_SANDBOX_ENABLED=true
+      - AGGKIT_LOG_LEVEL=info
+      - AGGKIT_COMPONENTS=bridge,aggoracle,claim-sponsor
+      - AGGKIT_CLAIMSPONSOR_ENABLED=true
+      - AGGKIT_CLAIMSPONSOR_CLAIM_ALL=${{AGGKIT_CLAIMSPONSOR_CLAIM_ALL:-false}}
+      - AGGKIT_L1_URL=http://anvil-l1:8545
+      - AGGKIT_L2_URL=http://anvil-{suffix}:8545
+      - AGGKIT_L1_CHAIN_ID=${{CHAIN_ID_MAINNET:-1}}
+      - AGGKIT_L2_CHAIN_ID=${{CHAIN_ID_AGGLAYER_{network_id}:-{chain_id}}}
+      - AGGKIT_REST_PORT={rest}
+      - AGGKIT_RPC_PORT={rpc}
+      - AGGKIT_TELEMETRY_PORT={telemetry}
+      - AGGKIT_DATABASE_NAME=/app/data/aggkit_{suffix}.db
+      - AGGKIT_ENV_FILE=/app/.env
+    container_name: aggkit-{suffix}
+"#,
+            rest = l2_api_port(network_id),
+            telemetry = 8079 + network_id,
+            rpc = 8554 + network_id,
+            chain_id = default_chain_id(network_id),
+        );
+    }
+
+    file.push_str("\nnetworks:\n  anvil-network:\n    driver: bridge\n\nvolumes:\n");
+    for &network_id in &networks {
+        let _ = writeln!(file, "  aggkit-{}-data:", l2_suffix(network_id));
+    }
+    file
+}
+
+/// Write [`GENERATED_COMPOSE_FILE`] for `l2_count` L2s, or remove a stale one
+///
+/// With two L2s or fewer the static compose files are used, and a generated
+/// file left behind would make `stop` and `logs` target the wrong services.
+pub fn write_generated_compose(l2_count: u64) -> Result<()> {
+    let result = if l2_count > 2 {
+        fs::write(GENERATED_COMPOSE_FILE, generate_compose(l2_count))
+    } else {
+        match fs::remove_file(GENERATED_COMPOSE_FILE) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    };
+    result.map_err(|e| {
+        DockerError::compose_validation_failed(&format!(
+            "Failed to write {GENERATED_COMPOSE_FILE}: {e}"
+        ))
+        .into()
+    })
+}
+
+/// Environment pointing the CLI at the L2s beyond the second one
+///
+/// Networks 1 and 2 have fixed endpoints; from network 3 on they are read
+/// from `RPC_<n+1>` and `API_BASE_URL_AGGLAYER_<n>`.
+pub fn scaled_env(l2_count: u64) -> Vec<(String, String)> {
+    (3..=l2_count)
+        .flat_map(|network_id| {
+            [
+                (
+                    format!("RPC_{}", network_id + 1),
+                    format!("http://localhost:{}", l2_rpc_port(network_id)),
+                ),
+                (
+                    format!("API_BASE_URL_AGGLAYER_{network_id}"),
+                    format!("http://localhost:{}", l2_api_port(network_id)),
+                ),
+            ]
+        })
+        .collect()
+}
+
+/// Set `vars` in an env file, replacing existing assignments and keeping the rest
+pub fn update_env_file(path: &Path, vars: &[(String, String)]) -> std::io::Result<()> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    for (key, value) in vars {
+        let assignment = format!("{key}={value}");
+        match lines.iter_mut().find(|line| {
+            line.split_once('=')
+                .is_some_and(|(name, _)| name.trim() == key)
+        }) {
+            Some(line) => *line = assignment,
+            None => lines.push(assignment),
+        }
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    fs::write(path, content)
+}
+
 /// Create a DockerComposeBuilder that automatically detects multi-L2 configuration
 pub fn create_auto_docker_builder() -> DockerComposeBuilder {
     let mut builder = DockerComposeBuilder::new();
 
+    // A sandbox started with `--scale` runs entirely from the generated file
+    if Path::new(GENERATED_COMPOSE_FILE).exists() {
+        builder.set_files(vec![GENERATED_COMPOSE_FILE]);
+        return builder;
+    }
+
     // Check if multi-L2 compose file exists and add it
     if Path::new("docker-compose.multi-l2.yml").exists() {
         builder.add_file("docker-compose.multi-l2.yml");
@@ -410,7 +837,26 @@ pub fn execute_docker_command_with_output(mut command: Command) -> Result<String
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// Parse `docker compose ps --format json` output into one value per service
+///
+/// Older Compose releases print a single JSON array while newer ones print
+/// one JSON object per line, so both shapes are accepted.
+pub fn parse_ps_json(output: &str) -> Vec<serde_json::Value> {
+    let trimmed = output.trim();
+    if trimmed.starts_with('[') {
+        if let Ok(serde_json::Value::Array(services)) = serde_json::from_str(trimmed) {
+            return services;
+        }
+    }
+
+    trimmed
+        .lines()
+        .filter_map(|line| serde_json::from_str(line.trim()).ok())
+        .collect()
+}
+
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod tests {
     use super::*;
 
@@ -488,6 +934,149 @@ mod tests {
         assert!(args.contains(&std::ffi::OsStr::new("-d")));
     }
 
+    #[test]
+    fn test_parse_ps_json() {
+        let array =
+            r#"[{"Service":"anvil-l1","State":"running"},{"Service":"aggkit","State":"running"}]"#;
+        assert_eq!(parse_ps_json(array).len(), 2);
+
+        let ndjson = "{\"Service\":\"anvil-l1\",\"State\":\"running\"}\n{\"Service\":\"anvil-l2\",\"State\":\"exited\"}\n";
+        let services = parse_ps_json(ndjson);
+        assert_eq!(services.len(), 2);
+        assert_eq!(services[1]["State"], "exited");
+
+        assert!(parse_ps_json("").is_empty());
+    }
+
+    #[test]
+    fn test_service_commands_target_services() {
+        let mut builder = DockerComposeBuilder::new();
+        builder.add_service("aggkit");
+
+        let command = builder.build_pull_command();
+        let args: Vec<&std::ffi::OsStr> = command.get_args().collect();
+        let pull = args.iter().position(|arg| *arg == "pull").unwrap();
+        assert_eq!(args[pull + 1], "aggkit");
+        assert!(args.contains(&std::ffi::OsStr::new("docker-compose.yml")));
+
+        let command = builder.build_restart_command();
+        let args: Vec<&std::ffi::OsStr> = command.get_args().collect();
+        assert!(args.ends_with(&[
+            std::ffi::OsStr::new("restart"),
+            std::ffi::OsStr::new("aggkit")
+        ]));
+
+        let command = DockerComposeBuilder::new().build_config_images_command();
+        let args: Vec<&std::ffi::OsStr> = command.get_args().collect();
+        assert!(args.ends_with(&[
+            std::ffi::OsStr::new("config"),
+            std::ffi::OsStr::new("--images")
+        ]));
+    }
+
+    #[test]
+    fn test_offline_up_command_never_pulls() {
+        let config = SandboxConfig::new(false, false, false).with_offline(true);
+        let command = config.create_docker_builder().build_up_command(true, false);
+        let args: Vec<&std::ffi::OsStr> = command.get_args().collect();
+
+        assert!(args.contains(&std::ffi::OsStr::new("--pull")));
+        assert!(args.contains(&std::ffi::OsStr::new("never")));
+        assert!(SandboxConfig::new(true, false, false)
+            .with_offline(true)
+            .validate_fork_config()
+            .is_err());
+    }
+
+    #[test]
+    fn test_parse_scale() {
+        assert_eq!(parse_scale("l2=3"), Ok(3));
+        assert_eq!(parse_scale("l2=1"), Ok(1));
+        assert!(parse_scale("3").is_err());
+        assert!(parse_scale("l2=0").is_err());
+        assert!(parse_scale("l2=many").is_err());
+        assert!(parse_scale(&format!("l2={}", MAX_SCALED_L2S + 1)).is_err());
+    }
+
+    #[test]
+    fn test_generate_compose() {
+        let compose = generate_compose(4);
+        let parsed: serde_yaml::Value = serde_yaml::from_str(&compose).unwrap();
+        let services = parsed["services"].as_mapping().unwrap();
+
+        for service in [
+            "anvil-l1",
+            "anvil-l5",
+            "aggkit-l2",
+            "aggkit-l5",
+            "aggkit-wait",
+        ] {
+            assert!(services.contains_key(service), "missing {service}");
+        }
+        assert!(!services.contains_key("anvil-l6"));
+        assert_eq!(parsed["services"]["anvil-l5"]["ports"][0], "8549:8545");
+        assert_eq!(parsed["services"]["aggkit-l5"]["ports"][0], "5580:5580");
+        assert!(parsed["services"]["contract-deployer"]["environment"]
+            .as_sequence()
+            .unwrap()
+            .contains(&"RPC_URL_5=http://anvil-l5:8545".into()));
+        assert!(parsed["services"]["contract-deployer"]["environment"]
+            .as_sequence()
+            .unwrap()
+            .contains(&"CHAIN_ID_AGGLAYER_4=${CHAIN_ID_AGGLAYER_4:-1104}".into()));
+        assert!(parsed["volumes"]
+            .as_mapping()
+            .unwrap()
+            .contains_key("aggkit-l5-data"));
+    }
+
+    #[test]
+    fn test_scaled_config_uses_generated_file() {
+        let config = SandboxConfig::new(false, false, false).with_l2_count(4);
+        assert!(config.multi_l2_mode);
+        let command = config.create_docker_builder().build_up_command(true, false);
+        let args: Vec<&std::ffi::OsStr> = command.get_args().collect();
+        assert!(args.contains(&std::ffi::OsStr::new(GENERATED_COMPOSE_FILE)));
+        assert!(!SandboxConfig::new(false, true, false).is_generated());
+
+        assert_eq!(
+            scaled_env(4),
+            vec![
+                ("RPC_4".to_string(), "http://localhost:8548".to_string()),
+                (
+                    "API_BASE_URL_AGGLAYER_3".to_string(),
+                    "http://localhost:5579".to_string()
+                ),
+                ("RPC_5".to_string(), "http://localhost:8549".to_string()),
+                (
+                    "API_BASE_URL_AGGLAYER_4".to_string(),
+                    "http://localhost:5580".to_string()
+                ),
+            ]
+        );
+        assert!(scaled_env(2).is_empty());
+    }
+
+    #[test]
+    fn test_update_env_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".env");
+        fs::write(&path, "# comment\nRPC_4=http://old:1\nOTHER=1\n").unwrap();
+
+        update_env_file(
+            &path,
+            &[
+                ("RPC_4".to_string(), "http://localhost:8548".to_string()),
+                ("RPC_5".to_string(), "http://localhost:8549".to_string()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "# comment\nRPC_4=http://localhost:8548\nOTHER=1\nRPC_5=http://localhost:8549\n"
+        );
+    }
+
     #[test]
     fn test_compose_command_detection() {
         // Test that we get one of the expected commands
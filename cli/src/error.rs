@@ -64,7 +64,7 @@ pub enum DockerError {
 }
 
 /// API-related errors
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone)]
 pub enum ApiError {
     /// HTTP request failed
     #[error("HTTP request to '{url}' failed with status {status}: {message}")]
@@ -81,7 +81,6 @@ pub enum ApiError {
     JsonParseError(String),
     /// API response validation failed
     #[error("API response validation failed: {0}")]
-    #[allow(dead_code)]
     ResponseValidationFailed(String),
     /// API endpoint not available
     #[error("API endpoint '{0}' is not available")]
@@ -105,6 +104,9 @@ pub enum EventError {
     /// RPC connection failed
     #[error("RPC connection failed: {0}")]
     RpcConnectionFailed(String),
+    /// Chain stopped producing blocks while a command waited on it
+    #[error("Chain halted: {0}")]
+    ChainHalted(String),
 }
 
 // Note: Basic From implementations are handled automatically by thiserror's #[from] attribute
@@ -150,6 +152,93 @@ impl From<serde_json::Error> for AggSandboxError {
     }
 }
 
+/// Process exit codes, one per error category
+///
+/// Scripts can tell e.g. a sandbox that is not running (docker, API or RPC
+/// codes) from invalid arguments without parsing the error message. Code 2
+/// matches the code clap exits with on usage errors.
+pub mod exit_code {
+    pub const GENERAL: i32 = 1;
+    pub const VALIDATION: i32 = 2;
+    pub const CONFIG: i32 = 3;
+    pub const DOCKER: i32 = 4;
+    pub const API: i32 = 5;
+    pub const RPC: i32 = 6;
+    pub const CHAIN_HALTED: i32 = 7;
+    pub const IO: i32 = 8;
+
+    /// Code, category and meaning of every exit code
+    pub const TABLE: &[(i32, &str, &str)] = &[
+        (0, "success", "The command completed"),
+        (
+            GENERAL,
+            "general",
+            "Any error without a more specific category",
+        ),
+        (
+            VALIDATION,
+            "validation",
+            "Invalid arguments or input values, including usage errors",
+        ),
+        (
+            CONFIG,
+            "config",
+            "Missing environment variables or configuration files",
+        ),
+        (
+            DOCKER,
+            "docker",
+            "Docker Compose failed or the sandbox services are not running",
+        ),
+        (
+            API,
+            "api",
+            "The bridge service (aggkit REST API) failed or is unreachable",
+        ),
+        (
+            RPC,
+            "rpc",
+            "A chain RPC endpoint failed, is unreachable or returned bad data",
+        ),
+        (
+            CHAIN_HALTED,
+            "chain-halted",
+            "A chain stopped producing blocks while the command waited on it",
+        ),
+        (IO, "io", "Reading or writing a local file failed"),
+    ];
+
+    /// Human-readable exit code table, as printed by `--exit-code-map`
+    pub fn render_table() -> String {
+        TABLE
+            .iter()
+            .map(|(code, category, meaning)| format!("  {code:>3}  {category:<13} {meaning}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl AggSandboxError {
+    /// Exit code of the error's category, see [`exit_code::TABLE`]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AggSandboxError::Config(
+                ConfigError::ValidationFailed(_) | ConfigError::InvalidValue { .. },
+            ) => exit_code::VALIDATION,
+            AggSandboxError::Config(_) => exit_code::CONFIG,
+            AggSandboxError::Docker(_) => exit_code::DOCKER,
+            AggSandboxError::Api(_) => exit_code::API,
+            AggSandboxError::Events(
+                EventError::InvalidChain(_) | EventError::InvalidAddress(_),
+            ) => exit_code::VALIDATION,
+            AggSandboxError::Events(EventError::ChainHalted(_)) => exit_code::CHAIN_HALTED,
+            AggSandboxError::Events(_) => exit_code::RPC,
+            AggSandboxError::Io(_) => exit_code::IO,
+            AggSandboxError::Other(_) => exit_code::GENERAL,
+        }
+    }
+}
+
 /// Result type alias for AggSandbox operations
 pub type Result<T> = std::result::Result<T, AggSandboxError>;
 
@@ -240,7 +329,6 @@ impl ApiError {
         ApiError::JsonParseError(msg.to_string())
     }
 
-    #[allow(dead_code)]
     pub fn response_validation_failed(msg: &str) -> Self {
         ApiError::ResponseValidationFailed(msg.to_string())
     }
@@ -268,9 +356,14 @@ impl EventError {
     pub fn rpc_connection_failed(msg: &str) -> Self {
         EventError::RpcConnectionFailed(msg.to_string())
     }
+
+    pub fn chain_halted(msg: &str) -> Self {
+        EventError::ChainHalted(msg.to_string())
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod tests {
     use super::*;
 
@@ -329,4 +422,33 @@ mod tests {
             .to_string()
             .contains("While reading config file"));
     }
+
+    #[test]
+    fn test_exit_codes_per_category() {
+        let validation: AggSandboxError = ConfigError::validation_failed("bad amount").into();
+        assert_eq!(validation.exit_code(), exit_code::VALIDATION);
+        let missing: AggSandboxError = ConfigError::env_var_not_found("RPC_1").into();
+        assert_eq!(missing.exit_code(), exit_code::CONFIG);
+        let docker: AggSandboxError = DockerError::command_failed("ps", "no daemon").into();
+        assert_eq!(docker.exit_code(), exit_code::DOCKER);
+        let api: AggSandboxError = ApiError::network_error("refused").into();
+        assert_eq!(api.exit_code(), exit_code::API);
+        let rpc: AggSandboxError = EventError::rpc_connection_failed("refused").into();
+        assert_eq!(rpc.exit_code(), exit_code::RPC);
+        let halted: AggSandboxError = EventError::chain_halted("stuck").into();
+        assert_eq!(halted.exit_code(), exit_code::CHAIN_HALTED);
+        assert_eq!(
+            AggSandboxError::Other("x".to_string()).exit_code(),
+            exit_code::GENERAL
+        );
+    }
+
+    #[test]
+    fn test_exit_code_table_is_unique() {
+        let mut codes: Vec<i32> = exit_code::TABLE.iter().map(|(code, _, _)| *code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), exit_code::TABLE.len());
+        assert!(exit_code::render_table().contains("chain-halted"));
+    }
 }
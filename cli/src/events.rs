@@ -1,223 +1,208 @@
+use crate::artifacts::{read_implementation, Artifacts};
+use crate::concurrency::join_all;
 use crate::error::{EventError, Result};
+use crate::rpc::NewBlocks;
 use crate::validation::Validator;
 use colored::*;
+use ethers::abi::{Abi, Event, ParamType, RawLog, Token};
 use ethers::prelude::*;
 use std::collections::HashMap;
-use std::sync::Arc;
-
-// Known event signatures for common contracts
-fn get_event_signatures() -> HashMap<&'static str, &'static str> {
-    let mut m = HashMap::new();
-
-    // ERC20 Events
-    m.insert(
-        "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
-        "Transfer(address,address,uint256)",
-    );
-    m.insert(
-        "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925",
-        "Approval(address,address,uint256)",
-    );
-    m.insert(
-        "0x8be0079c531659141344cd1fd0a4f28419497f9722a3daafe3b4186f6b6457e0",
-        "OwnershipTransferred(address,address)",
-    );
-
-    // Bridge Events
-    m.insert(
-        "0x501781209a1f8899323b96b4ef08b168df93e0a90c673d1e4cce39366cb62f9b",
-        "BridgeEvent(uint8,uint32,address,uint32,address,uint256,bytes,uint32)",
-    );
-    m.insert(
-        "0x1df3f2a973a00d6635911755c260704e95e8a5876997546798770f76396fda4d",
-        "ClaimEvent(uint256,uint32,address,address,uint256)",
-    );
-
-    // Initialization Events
-    m.insert(
-        "0xc7f505b2f371ae2175ee4913f4499e1f2633a7b5936321eed1cdaeb6115181d2",
-        "Initialized(uint64)",
-    );
-
-    // Emergency State Events
-    m.insert(
-        "0x2261efe5aef6fedc1fd1550b25facc9181745623049c7901287030b9ad1a5497",
-        "EmergencyStateActivated()",
-    );
-    m.insert(
-        "0x1e5e34eea33501aecf2ebec9fe0e884a40804275ea7fe10b2ba084c8374308b3",
-        "EmergencyStateDeactivated()",
-    );
-
-    // Access Control Events
-    m.insert(
-        "0x2f8788117e7eff1d82e926ec794901d17c78024a50270940304540a733656f0d",
-        "RoleGranted(bytes32,address,address)",
-    );
-    m.insert(
-        "0xf6391f5c32d9c69d2a47ea670b442974b53935d1edc7fd64eb21e047a839171b",
-        "RoleRevoked(bytes32,address,address)",
-    );
-    m.insert(
-        "0xbd79b86ffe0ab8e8776151514217cd7cacd52c909f66475c3af44e129f0b00ff",
-        "RoleAdminChanged(bytes32,bytes32,bytes32)",
-    );
-
-    // Rollup Manager Events
-    m.insert(
-        "0xd490df184152edba8455dac3228134939c71f8cb4c8f310c3145dec9037147ac",
-        "AddExistingRollup(uint32,uint64,address,uint64,uint8,uint64,bytes32)",
-    );
-    m.insert(
-        "0x9eaf2ecbddb14889c9e141a63175c55ac25e0cd7cdea312cdfbd0397976b383a",
-        "AddNewRollupType(uint32,address,address,uint64,uint8,bytes32,string,bytes32)",
-    );
-    m.insert(
-        "0x194c983456df6701c6a50830b90fe80e72b823411d0d524970c9590dc277a641",
-        "CreateNewRollup(uint32,uint32,address,uint64,address)",
-    );
-    m.insert(
-        "0x50cadc0c001f05dd4b81db1e92b98d77e718fd2f103fb7b77293e867d329a4c2",
-        "UpdateRollupManagerVersion(string)",
-    );
-
-    // Timelock Events
-    m.insert(
-        "0x11c24f4ead16507c69ac467fbd5e4eed5fb5c699626d2cc6d66421df253886d5",
-        "MinDelayChange(uint256,uint256)",
-    );
-    m.insert(
-        "0x4cf4410cc57040e44862ef0f45f3dd5a5e02db8eb8add648d4b0e236f1d07dca",
-        "CallScheduled(bytes32,uint256,address,uint256,bytes,bytes32,uint256)",
-    );
-    m.insert(
-        "0xc2617efa69bab66782fa219543714338489c4e9e178271560a91b82c3f612b58",
-        "CallExecuted(bytes32,uint256,address,uint256,bytes)",
-    );
-    m.insert(
-        "0xbaa1eb22f2a492ba1a5fea61b8df4d27c6c8b5f3971e63bb58fa14ff72eedb70",
-        "Cancelled(bytes32)",
-    );
-
-    // Bridge Management Events
-    m.insert(
-        "0x32cf74f8a6d5f88593984d2cd52be5592bfa6884f5896175801a5069ef09cd67",
-        "SetBridgeManager(address)",
-    );
-    m.insert(
-        "0x490e59a1701b938786ac72570a1efeac994a3dbe96e2e883e19e902ace6e6a39",
-        "NewWrappedToken(uint32,address,address,bytes)",
-    );
-    m.insert(
-        "0xdbe8a5da6a7a916d9adfda9160167a0f8a3da415ee6610e810e753853597fce7",
-        "SetSovereignTokenAddress(uint32,address,address,bool)",
-    );
-
-    // Global Exit Root Events
-    m.insert(
-        "0xb1b866fe5fac68e8f1a4ab2520c7a6b493a954934bbd0f054bd91d6674a4c0d5",
-        "InsertGlobalExitRoot(bytes32)",
-    );
-    m.insert(
-        "0x605764d0b65b62ecf05dc90f674a00a2e2531fabaf120fdde65790e407fcb7a2",
-        "RemoveLastGlobalExitRoot(bytes32)",
-    );
+use std::sync::{Arc, OnceLock};
+
+/// Events of the bridge, rollup manager, global exit root, timelock, token and
+/// receiver contracts deployed by the sandbox, in human readable ABI form
+/// (parameter names and `indexed` markers as in `agglayer-contracts/src`).
+///
+/// Logs are decoded from these definitions, so supporting a new event only
+/// takes adding its declaration here.
+const EVENT_ABI: &[&str] = &[
+    // ERC20 / Ownable
+    "event Transfer(address indexed from, address indexed to, uint256 value)",
+    "event Approval(address indexed owner, address indexed spender, uint256 value)",
+    "event OwnershipTransferred(address indexed previousOwner, address indexed newOwner)",
+    // Bridge
+    "event BridgeEvent(uint8 leafType, uint32 originNetwork, address originAddress, uint32 destinationNetwork, address destinationAddress, uint256 amount, bytes metadata, uint32 depositCount)",
+    "event ClaimEvent(uint256 globalIndex, uint32 originNetwork, address originAddress, address destinationAddress, uint256 amount)",
+    "event NewWrappedToken(uint32 originNetwork, address originTokenAddress, address wrappedTokenAddress, bytes metadata)",
+    "event SetBridgeManager(address bridgeManager)",
+    "event SetSovereignTokenAddress(uint32 originNetwork, address originTokenAddress, address sovereignTokenAddress, bool isNotMintable)",
+    "event EmergencyStateActivated()",
+    "event EmergencyStateDeactivated()",
+    // Initializable / AccessControl
+    "event Initialized(uint64 version)",
+    "event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender)",
+    "event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender)",
+    "event RoleAdminChanged(bytes32 indexed role, bytes32 indexed previousAdminRole, bytes32 indexed newAdminRole)",
+    // Rollup manager
+    "event AddExistingRollup(uint32 indexed rollupID, uint64 forkID, address rollupAddress, uint64 chainID, uint8 rollupVerifierType, uint64 lastVerifiedBatchBeforeUpgrade, bytes32 programVKey)",
+    "event AddNewRollupType(uint32 indexed rollupTypeID, address consensusImplementation, address verifier, uint64 forkID, uint8 rollupVerifierType, bytes32 genesis, string description, bytes32 programVKey)",
+    "event CreateNewRollup(uint32 indexed rollupID, uint32 rollupTypeID, address rollupAddress, uint64 chainID, address gasTokenAddress)",
+    "event UpdateRollupManagerVersion(string rollupManagerVersion)",
+    "event VerifyBatchesTrustedAggregator(uint32 indexed rollupID, uint64 numBatch, bytes32 stateRoot, bytes32 exitRoot, address indexed aggregator)",
+    // Timelock
+    "event MinDelayChange(uint256 oldDuration, uint256 newDuration)",
+    "event CallScheduled(bytes32 indexed id, uint256 indexed index, address target, uint256 value, bytes data, bytes32 predecessor, uint256 delay)",
+    "event CallExecuted(bytes32 indexed id, uint256 indexed index, address target, uint256 value, bytes data)",
+    "event Cancelled(bytes32 indexed id)",
+    // Global exit root manager
+    "event InsertGlobalExitRoot(bytes32 indexed newGlobalExitRoot)",
+    "event RemoveLastGlobalExitRoot(bytes32 indexed removedGlobalExitRoot)",
+    "event UpdateL1InfoTree(bytes32 indexed mainnetExitRoot, bytes32 indexed rollupExitRoot)",
+    "event UpdateL1InfoTreeV2(bytes32 currentL1InfoRoot, uint32 indexed leafCount, uint256 blockhash, uint64 minTimestamp)",
+    // Rollup consensus contracts
+    "event SetTrustedSequencer(address newTrustedSequencer)",
+    "event SetTrustedAggregator(address newTrustedAggregator)",
+    "event SequenceBatches(uint64 indexed numBatch)",
+    "event SequenceBatches(uint64 indexed numBatch, bytes32 l1InfoRoot)",
+    "event SequenceForceBatches(uint64 indexed numBatch)",
+    "event VerifyBatches(uint64 indexed numBatch, bytes32 stateRoot, address indexed aggregator)",
+    "event VerifyBatchesTrustedAggregator(uint64 indexed numBatch, bytes32 stateRoot, address indexed aggregator)",
+    "event ForceBatch(uint64 indexed forceBatchNum, bytes32 lastGlobalExitRoot, address sequencer, bytes transactions)",
+    "event SetForceBatchTimeout(uint64 newforceBatchTimeout)",
+    "event AcceptAdminRole(address newAdmin)",
+    "event TransferAdminRole(address newPendingAdmin)",
+    // AssetAndCallReceiver / Counter
+    "event MessageReceived(address indexed originAddress, uint32 originNetwork, bytes data, uint256 ethAmount)",
+    "event AssetReceived(address indexed sender, uint256 amount)",
+];
+
+/// Known events keyed by their topic0 signature hash
+fn known_events() -> &'static HashMap<H256, Event> {
+    static EVENTS: OnceLock<HashMap<H256, Event>> = OnceLock::new();
+    EVENTS.get_or_init(|| {
+        ethers::abi::parse_abi(EVENT_ABI)
+            .map(|abi| {
+                abi.events()
+                    .map(|event| (event.signature(), event.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    })
+}
 
-    // L1 Info Tree Events
-    m.insert(
-        "0xda61aa7823fcd807e37b95aabcbe17f03a6f3efd514176444dae191d27fd66b3",
-        "UpdateL1InfoTree(bytes32,bytes32)",
-    );
-    m.insert(
-        "0xaf6c6cd7790e0180a4d22eb8ed846e55846f54ed10e5946db19972b5a0813a59",
-        "UpdateL1InfoTreeV2(bytes32,uint32,uint256,uint64)",
-    );
+/// Look up the ABI definition of the event a log was emitted for
+fn lookup_event(log: &Log) -> Option<&'static Event> {
+    log.topics
+        .first()
+        .and_then(|topic| known_events().get(topic))
+}
 
-    // Global Exit Root Events
-    m.insert(
-        "0xb1b866fe5fac68e8f1a4ab2520c7a6b493a954934bbd0f054bd91d6674a4c0d5",
-        "InsertGlobalExitRoot(bytes32)",
-    );
+/// Look up the event of a log, preferring the ABI of the emitting proxy's implementation
+///
+/// Anonymous events have no signature topic, so they can only be matched from
+/// an implementation ABI, by trying to decode the log against each of them.
+fn lookup_event_with(log: &Log, abi: Option<&Abi>) -> Option<Event> {
+    let topic0 = log.topics.first();
+    if let (Some(abi), Some(topic0)) = (abi, topic0) {
+        if let Some(event) = abi
+            .events()
+            .find(|event| !event.anonymous && event.signature() == *topic0)
+        {
+            return Some(event.clone());
+        }
+    }
+    if let Some(event) = lookup_event(log) {
+        return Some(event.clone());
+    }
+    abi?.events()
+        .filter(|event| event.anonymous)
+        .find(|event| event.parse_log(raw_log(log)).is_ok())
+        .cloned()
+}
 
-    // Bridge Token Events
-    m.insert(
-        "0x490e59a1701b938786ac72570a1efeac994a3dbe96e2e883e19e902ace6e6a39",
-        "NewWrappedToken(uint32,address,address,bytes)",
-    );
+/// Implementation behind the EIP-1967 proxy that emitted a log
+#[derive(Debug, Clone)]
+pub struct ProxyImplementation {
+    pub address: Address,
+    /// Contract name from the deployment broadcasts
+    pub name: Option<String>,
+    abi: Option<Arc<Abi>>,
+}
 
-    // Sequencer Events
-    m.insert(
-        "0xf54144f9611984021529f814a1cb6a41e22c58351510a0d9f7e822618abb9cc0",
-        "SetTrustedSequencer(address)",
-    );
-    m.insert(
-        "0x61f8fec29495a3078e9271456f05fb0707fd4e41f7661865f80fc437d06681ca",
-        "SetTrustedAggregator(address)",
-    );
-    m.insert(
-        "0x303446e6a8cb73c83dff421c0b1d5e5ce0719dab1bff13660fc254e58cc17fce",
-        "SequenceBatches(uint64)",
-    );
-    m.insert(
-        "0x648a61dd2438f072f5a1960939abd30f37aea80d2e94c9792ad142d3e0a490a4",
-        "SequenceForceBatches(uint64)",
-    );
+impl ProxyImplementation {
+    fn describe(&self) -> String {
+        match &self.name {
+            Some(name) => format!("0x{:x} ({name})", self.address),
+            None => format!("0x{:x}", self.address),
+        }
+    }
+}
 
-    // Verification Events
-    m.insert(
-        "0x9c72852172521097ba7e1482e6b44b351323df0155f97f4ea18fcec28e1f5966",
-        "VerifyBatches(uint64,bytes32,address)",
-    );
-    m.insert(
-        "0xcb339b570a7f0b25afa7333371ff11192092a0aeace12b671f4c212f2815c6fe",
-        "VerifyBatchesTrustedAggregator(uint64,bytes32,address)",
-    );
-    m.insert(
-        "0xd1ec3a1216f08b6eff72e169ceb548b782db18a6614852618d86bb19f3f9b0d3",
-        "VerifyBatchesTrustedAggregator(uint32,uint64,bytes32,bytes32,address)",
-    );
+/// Resolves the implementation of log emitters, once per address
+pub struct ProxyResolver {
+    enabled: bool,
+    artifacts: Artifacts,
+    resolved: HashMap<Address, Option<ProxyImplementation>>,
+}
 
-    // Force Batch Events
-    m.insert(
-        "0xf94bb37db835f1ab585ee00041849a09b12cd081d77fa15ca070757619cbc931",
-        "ForceBatch(uint64,bytes32,address,bytes)",
-    );
-    m.insert(
-        "0xa7eb6cb8a613eb4e8bddc1ac3d61ec6cf10898760f0b187bcca794c6ca6fa40b",
-        "SetForceBatchTimeout(uint64)",
-    );
+impl ProxyResolver {
+    /// A resolver reading deployments from `agglayer-contracts`, or a no-op one when disabled
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            artifacts: if enabled {
+                Artifacts::load()
+            } else {
+                Artifacts::default()
+            },
+            resolved: HashMap::new(),
+        }
+    }
 
-    // Admin Events
-    m.insert(
-        "0x056dc487bbf0795d0bbb1b4f0af523a855503cff740bfb4d5475f7a90c091e8e",
-        "AcceptAdminRole(address)",
-    );
-    m.insert(
-        "0xa5b56b7906fd0a20e3f35120dd8343db1e12e037a6c90111c7e42885e82a1ce6",
-        "TransferAdminRole(address)",
-    );
+    /// Implementation behind `address`, `None` when it is not a proxy or resolution is disabled
+    async fn resolve(
+        &mut self,
+        client: &Provider<Http>,
+        address: Address,
+    ) -> Option<ProxyImplementation> {
+        if !self.enabled {
+            return None;
+        }
+        if let Some(resolved) = self.resolved.get(&address) {
+            return resolved.clone();
+        }
 
-    // AssetAndCallReceiver Events
-    m.insert(
-        "0x14b249705272b1faf0992521c9b70ab502f779d6688eb29cc0b65686f171c4e9",
-        "MessageReceived(address,uint32,bytes,uint256)",
-    );
-    m.insert(
-        "0x9487f004efd5b1c61d445fef51ea041e80ce3afe8c4c7853f833eaaba68fd036",
-        "AssetReceived(address,uint256)",
-    );
+        let resolved = match read_implementation(client, address).await {
+            Some(implementation) => {
+                let name = self
+                    .artifacts
+                    .contract_name(implementation)
+                    .map(str::to_string);
+                let abi = name.as_deref().and_then(|name| self.artifacts.abi(name));
+                Some(ProxyImplementation {
+                    address: implementation,
+                    name,
+                    abi,
+                })
+            }
+            None => None,
+        };
+        self.resolved.insert(address, resolved.clone());
+        resolved
+    }
+}
 
-    m
+/// Canonical signature of an event, e.g. `Transfer(address,address,uint256)`
+fn event_signature(event: &Event) -> String {
+    let types: Vec<String> = event
+        .inputs
+        .iter()
+        .map(|input| input.kind.to_string())
+        .collect();
+    format!("{}({})", event.name, types.join(","))
 }
 
 pub async fn fetch_and_display_events(
     chain: &str,
-    blocks: u64,
+    range: BlockRange,
     address: Option<String>,
+    format: EventFormat,
+    json: bool,
+    proxy_resolve: bool,
 ) -> Result<()> {
     // Validate inputs
     let validated_chain = Validator::validate_chain(chain)?;
-    let validated_blocks = Validator::validate_block_count(blocks)?;
+    let validated_range = range.validate()?;
 
     // Validate address if provided
     let validated_address = if let Some(addr) = address {
@@ -228,6 +213,18 @@ pub async fn fetch_and_display_events(
 
     let rpc_url = get_rpc_url(validated_chain.as_str())?;
 
+    if json || format == EventFormat::Json {
+        return fetch_events_json(
+            validated_chain.as_str(),
+            &rpc_url,
+            validated_range,
+            validated_address,
+            format == EventFormat::Json,
+            proxy_resolve,
+        )
+        .await;
+    }
+
     println!(
         "{}",
         format!("🔍 Fetching events from {} chain", validated_chain.as_str())
@@ -235,54 +232,551 @@ pub async fn fetch_and_display_events(
             .bold()
     );
     println!("{}", format!("📡 RPC URL: {rpc_url}").dimmed());
-    println!(
-        "{}",
-        format!("📊 Scanning last {validated_blocks} blocks").dimmed()
-    );
+    println!("{}", format!("📊 Scanning {validated_range}").dimmed());
 
     if let Some(addr) = &validated_address {
         println!("{}", format!("🎯 Filtering by contract: {addr}").dimmed());
     }
 
-    // Connect to the chain
-    let provider = Provider::<Http>::try_from(&rpc_url)
-        .map_err(|e| EventError::rpc_connection_failed(&e.to_string()))?;
+    let chain_logs = fetch_chain_logs(
+        validated_chain.as_str(),
+        &rpc_url,
+        validated_range,
+        validated_address.as_deref(),
+    )
+    .await?;
+    display_chain_logs(&chain_logs, proxy_resolve).await
+}
 
-    let client = Arc::new(provider);
+/// Fetch the events of several networks concurrently and print them
+///
+/// Only the log queries run concurrently. By default the events are printed
+/// grouped by chain; with `interleave` the events of all chains are merged into
+/// one timeline ordered by block timestamp (see [`print_interleaved`]). A chain
+/// that cannot be queried is reported in place and its error is returned once
+/// the other chains were printed. In JSON mode one document is printed with a
+/// `networks` array of per-chain documents, and in NDJSON mode every event
+/// carries its `chain` and `network_id`.
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub async fn fetch_and_display_events_for_chains(
+    networks: &[(u64, String)],
+    range: BlockRange,
+    address: Option<String>,
+    format: EventFormat,
+    json: bool,
+    proxy_resolve: bool,
+    interleave: bool,
+) -> Result<()> {
+    let validated_range = range.validate()?;
+    let validated_address = match address {
+        Some(addr) => Some(Validator::validate_ethereum_address(&addr)?),
+        None => None,
+    };
+    let mut targets = Vec::with_capacity(networks.len());
+    for (network_id, chain) in networks {
+        let validated_chain = Validator::validate_chain(chain)?;
+        let rpc_url = get_rpc_url(validated_chain.as_str())?;
+        targets.push((*network_id, validated_chain.as_str().to_string(), rpc_url));
+    }
 
-    // Get the latest block number
-    let latest_block = client.get_block_number().await.map_err(|e| {
-        EventError::rpc_connection_failed(&format!("Failed to get latest block: {e}"))
-    })?;
+    let results = join_all(
+        targets
+            .iter()
+            .map(|(_, chain, rpc_url)| {
+                fetch_chain_logs(
+                    chain,
+                    rpc_url,
+                    validated_range,
+                    validated_address.as_deref(),
+                )
+            })
+            .collect(),
+    )
+    .await;
+
+    let mut first_error = None;
+    let ndjson = format == EventFormat::Json;
+    let mut documents = Vec::new();
+    let mut fetched = Vec::with_capacity(targets.len());
+    for ((network_id, chain, _), result) in targets.iter().zip(results) {
+        let chain_logs = match result {
+            Ok(chain_logs) => chain_logs,
+            Err(e) => {
+                let error = serde_json::json!({
+                    "network_id": network_id,
+                    "chain": chain,
+                    "error": e.to_string(),
+                });
+                if ndjson {
+                    eprintln!("{error}");
+                } else if json {
+                    documents.push(error);
+                } else {
+                    println!(
+                        "{}",
+                        format!("⚠️  Failed to fetch events from {chain}: {e}").yellow()
+                    );
+                }
+                first_error.get_or_insert(e);
+                continue;
+            }
+        };
+
+        if interleave {
+            fetched.push((*network_id, chain_logs));
+        } else if ndjson {
+            for mut event in chain_events_json(&chain_logs, proxy_resolve).await {
+                event["chain"] = chain.as_str().into();
+                event["network_id"] = (*network_id).into();
+                println!("{}", crate::schema::versioned(event));
+            }
+        } else if json {
+            let mut document =
+                events_document(&chain_logs, validated_address.as_deref(), proxy_resolve).await;
+            document["network_id"] = (*network_id).into();
+            documents.push(document);
+        } else {
+            println!();
+            println!("{}", format!("🔍 Events from {chain} chain").cyan().bold());
+            display_chain_logs(&chain_logs, proxy_resolve).await?;
+        }
+    }
 
-    let from_block = if latest_block.as_u64() >= validated_blocks {
-        U64::from(latest_block.as_u64() - validated_blocks + 1)
-    } else {
-        U64::zero()
-    };
+    if interleave {
+        print_interleaved(&fetched, documents, format, json, proxy_resolve).await?;
+    } else if json && !ndjson {
+        println!(
+            "{}",
+            crate::schema::versioned(serde_json::json!({ "networks": documents }))
+        );
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Position of an event on the timeline shared by several chains
+///
+/// Events are ordered by block timestamp; events of the same second are
+/// ordered by network, then by their position within that chain. Events whose
+/// block timestamp could not be read are placed last.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimelineKey {
+    timestamp: Option<u64>,
+    network_id: u64,
+    block_number: Option<u64>,
+    log_index: Option<u64>,
+}
+
+impl TimelineKey {
+    fn sort_value(&self) -> (bool, u64, u64, Option<u64>, Option<u64>) {
+        (
+            self.timestamp.is_none(),
+            self.timestamp.unwrap_or_default(),
+            self.network_id,
+            self.block_number,
+            self.log_index,
+        )
+    }
+}
+
+/// Sort timeline entries by block time, interleaving the chains
+fn sort_timeline<T>(entries: &mut [(TimelineKey, T)]) {
+    entries.sort_by_key(|(key, _)| key.sort_value());
+}
+
+/// Timestamps of the blocks holding the fetched logs
+///
+/// The blocks are read concurrently; a block that cannot be read is missing
+/// from the map.
+async fn block_timestamps(chain_logs: &ChainLogs) -> HashMap<U64, u64> {
+    let mut block_numbers: Vec<U64> = chain_logs
+        .logs
+        .iter()
+        .filter_map(|log| log.block_number)
+        .collect();
+    block_numbers.sort_unstable();
+    block_numbers.dedup();
+
+    let blocks = join_all(
+        block_numbers
+            .iter()
+            .map(|&number| chain_logs.client.get_block(number))
+            .collect(),
+    )
+    .await;
+    block_numbers
+        .into_iter()
+        .zip(blocks)
+        .filter_map(|(number, block)| Some((number, block.ok()??.timestamp.as_u64())))
+        .collect()
+}
+
+/// Print the events of several chains as one timeline ordered by block time
+///
+/// Every event is tagged with its `chain`, `network_id` and block `timestamp`.
+/// In JSON mode one document `{networks, count, events}` is printed, where
+/// `networks` summarizes the scanned range of every chain (or its `error`,
+/// passed in as `errors`).
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+async fn print_interleaved(
+    fetched: &[(u64, ChainLogs)],
+    errors: Vec<serde_json::Value>,
+    format: EventFormat,
+    json: bool,
+    proxy_resolve: bool,
+) -> Result<()> {
+    let timestamps = join_all(
+        fetched
+            .iter()
+            .map(|(_, chain_logs)| block_timestamps(chain_logs))
+            .collect(),
+    )
+    .await;
+
+    let mut timeline = Vec::new();
+    for (position, ((network_id, chain_logs), timestamps)) in
+        fetched.iter().zip(&timestamps).enumerate()
+    {
+        for log in &chain_logs.logs {
+            let key = TimelineKey {
+                timestamp: log
+                    .block_number
+                    .and_then(|number| timestamps.get(&number).copied()),
+                network_id: *network_id,
+                block_number: log.block_number.map(|number| number.as_u64()),
+                log_index: log.log_index.map(|index| index.as_u64()),
+            };
+            timeline.push((key, (position, log)));
+        }
+    }
+    sort_timeline(&mut timeline);
+
+    // One resolver per chain, indexed like `fetched`
+    let mut resolvers: Vec<ProxyResolver> = fetched
+        .iter()
+        .map(|_| ProxyResolver::new(proxy_resolve))
+        .collect();
+
+    if json || format == EventFormat::Json {
+        let mut events = Vec::with_capacity(timeline.len());
+        for (key, (position, log)) in &timeline {
+            let chain_logs = &fetched[*position].1;
+            let implementation = resolvers[*position]
+                .resolve(&chain_logs.client, log.address)
+                .await;
+            let mut event = event_to_json(log, implementation.as_ref());
+            event["chain"] = chain_logs.chain.as_str().into();
+            event["network_id"] = key.network_id.into();
+            event["timestamp"] = key.timestamp.into();
+            events.push(event);
+        }
+
+        if format == EventFormat::Json {
+            for event in events {
+                println!("{}", crate::schema::versioned(event));
+            }
+            return Ok(());
+        }
+
+        let mut networks: Vec<serde_json::Value> = fetched
+            .iter()
+            .map(|(network_id, chain_logs)| {
+                serde_json::json!({
+                    "network_id": network_id,
+                    "chain": chain_logs.chain,
+                    "from_block": chain_logs.from_block.as_u64(),
+                    "to_block": chain_logs.to_block.as_u64(),
+                    "count": chain_logs.logs.len(),
+                })
+            })
+            .collect();
+        networks.extend(errors);
+        println!(
+            "{}",
+            crate::schema::versioned(serde_json::json!({
+                "networks": networks,
+                "count": events.len(),
+                "events": events,
+            }))
+        );
+        return Ok(());
+    }
+
+    println!();
+    for (network_id, chain_logs) in fetched {
+        println!(
+            "{}",
+            format!(
+                "🔍 Network {network_id} ({}): blocks {} to {}, {} events",
+                chain_logs.chain,
+                chain_logs.from_block,
+                chain_logs.to_block,
+                chain_logs.logs.len()
+            )
+            .green()
+        );
+    }
+
+    if timeline.is_empty() {
+        println!("{}", "📭 No events found in the specified range".yellow());
+        return Ok(());
+    }
 
     println!(
         "{}",
-        format!("🔍 Scanning blocks {from_block} to {latest_block}").green()
+        format!("📋 Found {} events, ordered by block time", timeline.len())
+            .green()
+            .bold()
+    );
+    println!("{}", "═".repeat(80).dimmed());
+    for (index, (key, (position, log))) in timeline.iter().enumerate() {
+        let chain_logs = &fetched[*position].1;
+        println!(
+            "🌐 Network: {}",
+            format!("{} ({})", key.network_id, chain_logs.chain).cyan()
+        );
+        display_event(
+            index + 1,
+            log,
+            &chain_logs.client,
+            &mut resolvers[*position],
+        )
+        .await?;
+
+        if index < timeline.len() - 1 {
+            println!("{}", "─".repeat(80).dimmed());
+        }
+    }
+    println!("{}", "═".repeat(80).dimmed());
+    println!(
+        "{}",
+        format!("✅ Displayed {} events", timeline.len())
+            .green()
+            .bold()
+    );
+
+    Ok(())
+}
+
+/// Blocks spanned by one `eth_getLogs` call
+///
+/// Larger ranges are split into several calls, staying below the range limit
+/// of hosted RPC providers.
+const LOG_CHUNK_BLOCKS: u64 = 2_000;
+
+/// Start or end of the scanned block range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockBound {
+    /// The genesis block
+    Earliest,
+    /// The chain head at the time of the query
+    Latest,
+    /// A block number
+    Number(u64),
+}
+
+impl BlockBound {
+    fn resolve(self, latest: u64) -> u64 {
+        match self {
+            BlockBound::Earliest => 0,
+            BlockBound::Latest => latest,
+            BlockBound::Number(number) => number,
+        }
+    }
+}
+
+impl std::fmt::Display for BlockBound {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockBound::Earliest => write!(f, "earliest"),
+            BlockBound::Latest => write!(f, "latest"),
+            BlockBound::Number(number) => write!(f, "{number}"),
+        }
+    }
+}
+
+impl std::str::FromStr for BlockBound {
+    type Err = String;
+
+    /// Parse `earliest`, `latest` or a block number in decimal or `0x` hex
+    fn from_str(input: &str) -> std::result::Result<Self, Self::Err> {
+        match input.trim().to_lowercase().as_str() {
+            "earliest" => Ok(BlockBound::Earliest),
+            "latest" => Ok(BlockBound::Latest),
+            _ => crate::validation::parse_int::<u64>(input).map(BlockBound::Number),
+        }
+    }
+}
+
+/// Blocks scanned for events
+///
+/// Without explicit bounds the last `blocks` blocks up to the chain head are
+/// scanned; `--from-block` replaces the `blocks` window and `--to-block` moves
+/// its end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRange {
+    pub blocks: u64,
+    pub from: Option<BlockBound>,
+    pub to: Option<BlockBound>,
+}
+
+impl BlockRange {
+    /// The last `blocks` blocks up to the chain head
+    pub fn last(blocks: u64) -> Self {
+        Self {
+            blocks,
+            from: None,
+            to: None,
+        }
+    }
+
+    /// Validate the `blocks` window, which only applies without `from`
+    fn validate(self) -> Result<Self> {
+        if self.from.is_none() {
+            Validator::validate_block_count(self.blocks)?;
+        }
+        if let (Some(BlockBound::Number(from)), Some(BlockBound::Number(to))) = (self.from, self.to)
+        {
+            if from > to {
+                return Err(from_after_to(from, to));
+            }
+        }
+        Ok(self)
+    }
+
+    /// First and last block of the range for the given chain head
+    fn resolve(&self, latest: u64) -> Result<(u64, u64)> {
+        let to = self.to.map_or(latest, |to| to.resolve(latest));
+        if to > latest {
+            return Err(crate::error::ConfigError::invalid_value(
+                "to_block",
+                &to.to_string(),
+                &format!("Must not be after the chain head (block {latest})"),
+            )
+            .into());
+        }
+        let from = match self.from {
+            Some(from) => from.resolve(latest),
+            None => to.saturating_sub(self.blocks.saturating_sub(1)),
+        };
+        if from > to {
+            return Err(from_after_to(from, to));
+        }
+        Ok((from, to))
+    }
+}
+
+impl std::fmt::Display for BlockRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.from, self.to) {
+            (None, None) => write!(f, "last {} blocks", self.blocks),
+            (None, Some(to)) => write!(f, "{} blocks up to block {to}", self.blocks),
+            (Some(from), to) => write!(f, "blocks {from} to {}", to.unwrap_or(BlockBound::Latest)),
+        }
+    }
+}
+
+fn from_after_to(from: u64, to: u64) -> crate::error::AggSandboxError {
+    crate::error::ConfigError::invalid_value(
+        "from_block",
+        &from.to_string(),
+        &format!("Must not be after the last block of the range ({to})"),
+    )
+    .into()
+}
+
+/// Split `from..=to` into consecutive ranges of at most `size` blocks
+fn block_chunks(from: u64, to: u64, size: u64) -> Vec<(u64, u64)> {
+    let size = size.max(1);
+    let mut chunks = Vec::new();
+    let mut start = from;
+    while start <= to {
+        let end = start.saturating_add(size - 1).min(to);
+        chunks.push((start, end));
+        if end == u64::MAX {
+            break;
+        }
+        start = end + 1;
+    }
+    chunks
+}
+
+/// Fetch the logs of `from..=to` matching `filter`, in chunks of [`LOG_CHUNK_BLOCKS`]
+async fn get_logs_chunked(
+    client: &Provider<Http>,
+    filter: &Filter,
+    from: u64,
+    to: u64,
+) -> std::result::Result<Vec<Log>, ProviderError> {
+    let mut logs = Vec::new();
+    for (start, end) in block_chunks(from, to, LOG_CHUNK_BLOCKS) {
+        let chunk = filter.clone().from_block(start).to_block(end);
+        logs.extend(client.get_logs(&chunk).await?);
+    }
+    Ok(logs)
+}
+
+/// Logs of a block range of a chain
+struct ChainLogs {
+    chain: String,
+    client: Arc<Provider<Http>>,
+    from_block: U64,
+    to_block: U64,
+    logs: Vec<Log>,
+}
+
+/// Fetch the logs of a block range, optionally of one contract
+async fn fetch_chain_logs(
+    chain: &str,
+    rpc_url: &str,
+    range: BlockRange,
+    address: Option<&str>,
+) -> Result<ChainLogs> {
+    let client = Arc::new(
+        Provider::<Http>::try_from(rpc_url)
+            .map_err(|e| EventError::rpc_connection_failed(&e.to_string()))?,
     );
 
-    // Create filter for events
-    let mut filter = Filter::new().from_block(from_block).to_block(latest_block);
+    let latest_block = client.get_block_number().await.map_err(|e| {
+        EventError::rpc_connection_failed(&format!("Failed to get latest block: {e}"))
+    })?;
+    let (from_block, to_block) = range.resolve(latest_block.as_u64())?;
 
-    // Add address filter if provided
-    if let Some(addr) = validated_address {
-        let address = addr
+    let mut filter = Filter::new();
+    if let Some(addr) = address {
+        let contract = addr
             .parse::<Address>()
-            .map_err(|_| EventError::invalid_address(&addr))?;
-        filter = filter.address(address);
+            .map_err(|_| EventError::invalid_address(addr))?;
+        filter = filter.address(contract);
     }
 
-    // Fetch logs
-    let logs = client
-        .get_logs(&filter)
+    let logs = get_logs_chunked(&client, &filter, from_block, to_block)
         .await
         .map_err(|e| EventError::rpc_connection_failed(&format!("Failed to fetch events: {e}")))?;
 
+    Ok(ChainLogs {
+        chain: chain.to_string(),
+        client,
+        from_block: U64::from(from_block),
+        to_block: U64::from(to_block),
+        logs,
+    })
+}
+
+/// Print fetched logs in the text format
+async fn display_chain_logs(chain_logs: &ChainLogs, proxy_resolve: bool) -> Result<()> {
+    let ChainLogs {
+        client,
+        from_block,
+        to_block,
+        logs,
+        ..
+    } = chain_logs;
+
+    println!(
+        "{}",
+        format!("🔍 Scanning blocks {from_block} to {to_block}").green()
+    );
+
     if logs.is_empty() {
         println!("{}", "📭 No events found in the specified range".yellow());
         return Ok(());
@@ -295,8 +789,9 @@ pub async fn fetch_and_display_events(
     println!("{}", "═".repeat(80).dimmed());
 
     // Process and display each log
+    let mut resolver = ProxyResolver::new(proxy_resolve);
     for (index, log) in logs.iter().enumerate() {
-        display_event(index + 1, log, &client).await?;
+        display_event(index + 1, log, client, &mut resolver).await?;
 
         if index < logs.len() - 1 {
             println!("{}", "─".repeat(80).dimmed());
@@ -312,7 +807,299 @@ pub async fn fetch_and_display_events(
     Ok(())
 }
 
-async fn display_event(index: usize, log: &Log, client: &Arc<Provider<Http>>) -> Result<()> {
+/// Fetch events and print them as JSON for scripting
+///
+/// With `ndjson` every event is printed as its own line, otherwise a single
+/// document wrapping all events is printed.
+async fn fetch_events_json(
+    chain: &str,
+    rpc_url: &str,
+    range: BlockRange,
+    address: Option<String>,
+    ndjson: bool,
+    proxy_resolve: bool,
+) -> Result<()> {
+    let chain_logs = fetch_chain_logs(chain, rpc_url, range, address.as_deref()).await?;
+
+    if ndjson {
+        for event in chain_events_json(&chain_logs, proxy_resolve).await {
+            println!("{}", crate::schema::versioned(event));
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        crate::schema::versioned(
+            events_document(&chain_logs, address.as_deref(), proxy_resolve).await
+        )
+    );
+    Ok(())
+}
+
+/// Events of the last `blocks` blocks of a chain as one JSON document
+pub async fn events_json(
+    chain: &str,
+    blocks: u64,
+    address: Option<String>,
+    proxy_resolve: bool,
+) -> Result<serde_json::Value> {
+    let chain = Validator::validate_chain(chain)?;
+    let range = BlockRange::last(blocks).validate()?;
+    let address = address
+        .map(|addr| Validator::validate_ethereum_address(&addr))
+        .transpose()?;
+    let rpc_url = get_rpc_url(chain.as_str())?;
+
+    let chain_logs = fetch_chain_logs(chain.as_str(), &rpc_url, range, address.as_deref()).await?;
+    Ok(events_document(&chain_logs, address.as_deref(), proxy_resolve).await)
+}
+
+/// JSON representation of every fetched log
+async fn chain_events_json(chain_logs: &ChainLogs, proxy_resolve: bool) -> Vec<serde_json::Value> {
+    let mut resolver = ProxyResolver::new(proxy_resolve);
+    let mut events = Vec::with_capacity(chain_logs.logs.len());
+    for log in &chain_logs.logs {
+        let implementation = resolver.resolve(&chain_logs.client, log.address).await;
+        events.push(event_to_json(log, implementation.as_ref()));
+    }
+    events
+}
+
+/// Single JSON document wrapping the fetched events of a chain
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+async fn events_document(
+    chain_logs: &ChainLogs,
+    address: Option<&str>,
+    proxy_resolve: bool,
+) -> serde_json::Value {
+    let events = chain_events_json(chain_logs, proxy_resolve).await;
+    serde_json::json!({
+        "chain": chain_logs.chain,
+        "from_block": chain_logs.from_block.as_u64(),
+        "to_block": chain_logs.to_block.as_u64(),
+        "address": address,
+        "count": events.len(),
+        "events": events,
+    })
+}
+
+/// Convert a log into the JSON representation used by JSON and NDJSON output
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn event_to_json(log: &Log, implementation: Option<&ProxyImplementation>) -> serde_json::Value {
+    let signature = log.topics.first().map(|topic| format!("0x{topic:x}"));
+    let event = lookup_event_with(log, implementation.and_then(|i| i.abi.as_deref()));
+    let event_name = event.as_ref().map(event_signature);
+    let decoded = event.as_ref().map(|event| decode_log(event, log).to_json());
+    serde_json::json!({
+        "block_number": log.block_number.map(|n| n.as_u64()),
+        "transaction_hash": log.transaction_hash.map(|hash| format!("0x{hash:x}")),
+        "log_index": log.log_index.map(|index| index.as_u64()),
+        "contract": format!("0x{:x}", log.address),
+        "implementation": implementation.map(|i| format!("0x{:x}", i.address)),
+        "implementation_name": implementation.and_then(|i| i.name.clone()),
+        "event": event_name,
+        "signature": signature,
+        "decoded": decoded,
+        "topics": log.topics.iter().map(|topic| format!("0x{topic:x}")).collect::<Vec<_>>(),
+        "data": format!("0x{}", hex::encode(&log.data)),
+    })
+}
+
+/// Consecutive failed polls after which `follow_events` gives up
+const MAX_FOLLOW_FAILURES: u32 = 30;
+
+/// Stream events as they are emitted until interrupted with Ctrl-C
+///
+/// The last `blocks` blocks (or the blocks from `from_block` on) are replayed
+/// first, then the chain is polled every `interval_secs` seconds for new
+/// blocks. RPC failures (e.g. the sandbox being restarted) are retried with a
+/// fresh provider; if the chain head moves backwards the chain was reset and
+/// streaming resumes from the new head. When the chain has a WebSocket
+/// endpoint, new blocks are polled as soon as they are announced.
+/// In JSON mode every event is printed as one JSON object per line.
+pub async fn follow_events(
+    chain: &str,
+    blocks: u64,
+    from_block: Option<BlockBound>,
+    address: Option<String>,
+    interval_secs: u64,
+    json: bool,
+    proxy_resolve: bool,
+) -> Result<()> {
+    let validated_chain = Validator::validate_chain(chain)?;
+    let validated_range = BlockRange {
+        blocks,
+        from: from_block,
+        to: None,
+    }
+    .validate()?;
+    let contract = match address {
+        Some(addr) => {
+            let validated = Validator::validate_ethereum_address(&addr)?;
+            Some(
+                validated
+                    .parse::<Address>()
+                    .map_err(|_| EventError::invalid_address(&validated))?,
+            )
+        }
+        None => None,
+    };
+    let rpc_url = get_rpc_url(validated_chain.as_str())?;
+    let interval = std::time::Duration::from_secs(interval_secs.max(1));
+
+    let connect = || -> Result<Arc<Provider<Http>>> {
+        Ok(Arc::new(Provider::<Http>::try_from(&rpc_url).map_err(
+            |e| EventError::rpc_connection_failed(&e.to_string()),
+        )?))
+    };
+    let mut client = connect()?;
+
+    let latest_block = client.get_block_number().await.map_err(|e| {
+        EventError::rpc_connection_failed(&format!("Failed to get latest block: {e}"))
+    })?;
+    let (mut next_block, _) = validated_range.resolve(latest_block.as_u64())?;
+
+    // New blocks trigger a poll right away when the chain has a WebSocket endpoint
+    let chain_config = crate::config::Config::load()
+        .ok()
+        .and_then(|config| config.get_chain(validated_chain.as_str()).cloned());
+    let mut new_blocks = match &chain_config {
+        Some(chain) => NewBlocks::subscribe(chain).await,
+        None => NewBlocks::polling(),
+    };
+
+    if !json {
+        println!(
+            "{}",
+            format!(
+                "👀 Following events on {} chain from block {next_block} (Ctrl-C to stop)",
+                validated_chain.as_str()
+            )
+            .cyan()
+            .bold()
+        );
+        println!("{}", format!("📡 RPC URL: {rpc_url}").dimmed());
+        if new_blocks.is_streaming() {
+            println!("{}", "⚡ Streaming new blocks over WebSocket".dimmed());
+        }
+        if let Some(contract) = &contract {
+            println!(
+                "{}",
+                format!("🎯 Filtering by contract: 0x{contract:x}").dimmed()
+            );
+        }
+    }
+
+    let shutdown = tokio::signal::ctrl_c();
+    tokio::pin!(shutdown);
+    let mut resolver = ProxyResolver::new(proxy_resolve);
+
+    let mut displayed = 0usize;
+    let mut failures = 0u32;
+    loop {
+        let latest = match client.get_block_number().await {
+            Ok(latest) => Some(latest.as_u64()),
+            Err(e) => {
+                failures += 1;
+                if failures >= MAX_FOLLOW_FAILURES {
+                    return Err(EventError::rpc_connection_failed(&format!(
+                        "Giving up after {failures} failed attempts: {e}"
+                    ))
+                    .into());
+                }
+                if !json {
+                    eprintln!(
+                        "{}",
+                        format!("⚠️  RPC unavailable ({e}), reconnecting...").yellow()
+                    );
+                }
+                client = connect()?;
+                if let (false, Some(chain)) = (new_blocks.is_streaming(), &chain_config) {
+                    new_blocks = NewBlocks::subscribe(chain).await;
+                }
+                None
+            }
+        };
+
+        if let Some(latest) = latest {
+            failures = 0;
+            if latest + 1 < next_block {
+                // Chain head moved backwards: the sandbox was restarted or reset
+                if !json {
+                    println!(
+                        "{}",
+                        format!("🔄 Chain reset detected, resuming from block {latest}").yellow()
+                    );
+                }
+                next_block = latest;
+            }
+
+            if latest >= next_block {
+                let mut filter = Filter::new();
+                if let Some(contract) = contract {
+                    filter = filter.address(contract);
+                }
+
+                match get_logs_chunked(&client, &filter, next_block, latest).await {
+                    Ok(logs) => {
+                        for log in &logs {
+                            if json {
+                                let implementation = resolver.resolve(&client, log.address).await;
+                                println!(
+                                    "{}",
+                                    crate::schema::versioned(event_to_json(
+                                        log,
+                                        implementation.as_ref()
+                                    ))
+                                );
+                            } else {
+                                if displayed > 0 {
+                                    println!("{}", "─".repeat(80).dimmed());
+                                }
+                                display_event(displayed + 1, log, &client, &mut resolver).await?;
+                            }
+                            displayed += 1;
+                        }
+                        next_block = latest + 1;
+                    }
+                    Err(e) => {
+                        if !json {
+                            eprintln!(
+                                "{}",
+                                format!("⚠️  Failed to fetch events ({e}), retrying...").yellow()
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = &mut shutdown => break,
+            _ = new_blocks.wait(interval) => {}
+        }
+    }
+
+    if !json {
+        println!();
+        println!(
+            "{}",
+            format!("✅ Stopped following, displayed {displayed} events")
+                .green()
+                .bold()
+        );
+    }
+
+    Ok(())
+}
+
+async fn display_event(
+    index: usize,
+    log: &Log,
+    client: &Arc<Provider<Http>>,
+    resolver: &mut ProxyResolver,
+) -> Result<()> {
     println!("{}", format!("📝 Event #{index}").blue().bold());
 
     // Get block information
@@ -337,18 +1124,21 @@ async fn display_event(index: usize, log: &Log, client: &Arc<Provider<Http>>) ->
     }
 
     println!("📍 Contract: {}", format!("0x{:x}", log.address).yellow());
+    let implementation = resolver.resolve(client, log.address).await;
+    if let Some(implementation) = &implementation {
+        println!("🔀 Implementation: {}", implementation.describe().yellow());
+    }
 
     // Decode the event
-    if !log.topics.is_empty() {
-        let event_signature = format!("0x{:x}", log.topics[0]);
-
-        let event_signatures = get_event_signatures();
-        if let Some(&event_name) = event_signatures.get(event_signature.as_str()) {
-            println!("🎯 Event: {}", event_name.green().bold());
-            decode_known_event(event_name, log);
+    let event = lookup_event_with(log, implementation.as_ref().and_then(|i| i.abi.as_deref()));
+    if !log.topics.is_empty() || event.is_some() {
+        if let Some(event) = event {
+            println!("🎯 Event: {}", event_signature(&event).green().bold());
+            decode_log(&event, log).print();
         } else {
+            let raw_signature = format!("0x{:x}", log.topics[0]);
             println!("🎯 Event: {}", "Unknown Event".red());
-            println!("📋 Raw Signature: {}", event_signature.dimmed());
+            println!("📋 Raw Signature: {}", raw_signature.dimmed());
         }
     }
 
@@ -365,355 +1155,496 @@ async fn display_event(index: usize, log: &Log, client: &Arc<Provider<Http>>) ->
     Ok(())
 }
 
-fn decode_known_event(event_name: &str, log: &Log) {
-    match event_name {
-        "Transfer(address,address,uint256)" => {
-            decode_transfer_event(log);
-        }
-        "Approval(address,address,uint256)" => {
-            decode_approval_event(log);
-        }
-        "OwnershipTransferred(address,address)" => {
-            decode_ownership_transferred_event(log);
-        }
-        "BridgeEvent(uint8,uint32,address,uint32,address,uint256,bytes,uint32)" => {
-            decode_bridge_event(log);
-        }
-        "ClaimEvent(uint256,uint32,address,address,uint256)" => {
-            decode_claim_event(log);
-        }
-        "Initialized(uint64)" => {
-            decode_initialized_event(log);
-        }
-        "RoleGranted(bytes32,address,address)" => {
-            decode_role_granted_event(log);
-        }
-        "RoleRevoked(bytes32,address,address)" => {
-            decode_role_revoked_event(log);
-        }
-        "AddExistingRollup(uint32,uint64,address,uint64,uint8,uint64,bytes32)" => {
-            decode_add_existing_rollup_event(log);
-        }
-        "UpdateRollupManagerVersion(string)" => {
-            decode_update_rollup_manager_version_event(log);
-        }
-        "MinDelayChange(uint256,uint256)" => {
-            decode_min_delay_change_event(log);
-        }
-        "SetTrustedSequencer(address)" => {
-            decode_set_trusted_sequencer_event(log);
-        }
-        "SetTrustedAggregator(address)" => {
-            decode_set_trusted_aggregator_event(log);
-        }
-        "SequenceBatches(uint64)" => {
-            decode_sequence_batches_event(log);
-        }
-        "VerifyBatches(uint64,bytes32,address)" => {
-            decode_verify_batches_event(log);
-        }
-        "UpdateL1InfoTree(bytes32,bytes32)" => {
-            decode_update_l1_info_tree_event(log);
-        }
-        "UpdateL1InfoTreeV2(bytes32,uint32,uint256,uint64)" => {
-            decode_update_l1_info_tree_v2_event(log);
-        }
-        "InsertGlobalExitRoot(bytes32)" => {
-            decode_insert_global_exit_root_event(log);
-        }
-        "NewWrappedToken(uint32,address,address,bytes)" => {
-            decode_new_wrapped_token_event(log);
-        }
-        "MessageReceived(address,uint32,bytes,uint256)" => {
-            decode_message_received_event(log);
-        }
-        "AssetReceived(address,uint256)" => {
-            decode_asset_received_event(log);
-        }
-        _ => {
-            println!("  ⚠️  Decoding not implemented for this event type");
-        }
-    }
+/// Output format for the events command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum EventFormat {
+    /// Colored, human readable output
+    #[default]
+    Text,
+    /// One JSON object per event (NDJSON), for piping into jq or indexers
+    Json,
 }
 
-fn decode_transfer_event(log: &Log) {
-    if log.topics.len() >= 3 && log.data.len() >= 32 {
-        let from = Address::from(log.topics[1]);
-        let to = Address::from(log.topics[2]);
-        let amount = U256::from_big_endian(&log.data[0..32]);
-
-        println!("  📤 From: {}", format!("0x{from:x}").cyan());
-        println!("  📥 To: {}", format!("0x{to:x}").cyan());
-        println!("  💰 Amount: {} tokens", amount.to_string().green());
-    }
+/// Terminal color used when printing a decoded field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldStyle {
+    Cyan,
+    Green,
+    Yellow,
+    Dimmed,
 }
 
-fn decode_approval_event(log: &Log) {
-    if log.topics.len() >= 3 && log.data.len() >= 32 {
-        let owner = Address::from(log.topics[1]);
-        let spender = Address::from(log.topics[2]);
-        let amount = U256::from_big_endian(&log.data[0..32]);
-
-        println!("  👤 Owner: {}", format!("0x{owner:x}").cyan());
-        println!("  🤝 Spender: {}", format!("0x{spender:x}").cyan());
-        println!("  💰 Allowance: {} tokens", amount.to_string().green());
-    }
+/// A single decoded event parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedField {
+    /// Snake case parameter name, used as key in JSON output
+    pub key: String,
+    /// Label (with icon) used in human readable output
+    pub label: String,
+    pub value: String,
+    pub style: FieldStyle,
 }
 
-fn decode_bridge_event(log: &Log) {
-    println!("  🌉 Bridge Event Details:");
-    if !log.data.is_empty() && log.data.len() >= 256 {
-        // Decode the bridge event parameters
-        let leaf_type = U256::from(&log.data[0..32]);
-        let origin_network = U256::from(&log.data[32..64]);
-        let origin_address = format!("0x{}", hex::encode(&log.data[76..96]));
-        let destination_network = U256::from(&log.data[96..128]);
-        let destination_address = format!("0x{}", hex::encode(&log.data[140..160]));
-        let amount = U256::from(&log.data[160..192]);
-
-        println!("  🍃 Leaf Type: {}", leaf_type.to_string().dimmed());
-        println!("  🌐 Origin Network: {}", origin_network.to_string().cyan());
-        println!("  📍 Origin Address: {}", origin_address.yellow());
-        println!(
-            "  🎯 Destination Network: {}",
-            destination_network.to_string().cyan()
-        );
-        println!("  📍 Destination Address: {}", destination_address.green());
-        println!("  💰 Amount: {}", amount.to_string().green());
-    } else {
-        println!("  ⚠️  Complex bridge event - showing raw data");
-        println!("  📊 Data length: {} bytes", log.data.len());
+impl DecodedField {
+    /// Build a field from an ABI parameter and its decoded value
+    pub fn from_param(name: &str, kind: &ParamType, value: &Token) -> Self {
+        let key = to_snake_case(name);
+        let (icon, style) = field_presentation(&key, kind);
+        let label = key
+            .split('_')
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        Self {
+            label: format!("{icon} {label}"),
+            key,
+            value: format_token(value),
+            style,
+        }
     }
 }
 
-fn decode_ownership_transferred_event(log: &Log) {
-    println!("  👑 Ownership Transfer:");
-    if log.topics.len() >= 3 {
-        let previous_owner = format!("0x{}", hex::encode(&log.topics[1][12..]));
-        let new_owner = format!("0x{}", hex::encode(&log.topics[2][12..]));
-        println!("  📤 Previous Owner: {}", previous_owner.dimmed());
-        println!("  📥 New Owner: {}", new_owner.green());
-    }
+/// Result of decoding a known event
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodedEvent {
+    pub fields: Vec<DecodedField>,
+    /// Remarks about data that could not be decoded
+    pub notes: Vec<String>,
 }
 
-fn decode_claim_event(log: &Log) {
-    println!("  🎯 Claim Event:");
-    if !log.data.is_empty() && log.data.len() >= 160 {
-        // Decode ClaimEvent(uint256,uint32,address,address,uint256)
-        let global_index = U256::from(&log.data[0..32]);
-        let origin_network = U256::from(&log.data[32..64]);
-        let origin_address = format!("0x{}", hex::encode(&log.data[76..96]));
-        let destination_address = format!("0x{}", hex::encode(&log.data[108..128]));
-        let amount = U256::from(&log.data[128..160]);
-
-        println!("  🌍 Global Index: {}", global_index.to_string().cyan());
-        println!("  🌐 Origin Network: {}", origin_network.to_string().cyan());
-        println!("  📍 Origin Address: {}", origin_address.yellow());
-        println!("  📍 Destination Address: {}", destination_address.green());
-        println!("  💰 Amount: {}", amount.to_string().green());
-    } else {
-        println!("  ⚠️  Complex claim event - showing raw data");
+impl DecodedEvent {
+    /// Decoded fields as a JSON object keyed by field name
+    pub fn to_json(&self) -> serde_json::Value {
+        self.fields
+            .iter()
+            .map(|field| {
+                (
+                    field.key.clone(),
+                    serde_json::Value::String(field.value.clone()),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>()
+            .into()
     }
-}
 
-fn decode_initialized_event(log: &Log) {
-    println!("  🚀 Contract Initialized:");
-    if !log.data.is_empty() && log.data.len() >= 32 {
-        let version = U256::from(&log.data[0..32]);
-        println!("  📊 Version: {}", version.to_string().cyan());
+    /// Print the decoded event in human readable form
+    pub fn print(&self) {
+        for field in &self.fields {
+            let value = match field.style {
+                FieldStyle::Cyan => field.value.cyan(),
+                FieldStyle::Green => field.value.green(),
+                FieldStyle::Yellow => field.value.yellow(),
+                FieldStyle::Dimmed => field.value.dimmed(),
+            };
+            println!("  {}: {value}", field.label);
+        }
+        for note in &self.notes {
+            println!("  {note}");
+        }
     }
 }
 
-fn decode_role_granted_event(log: &Log) {
-    println!("  🔐 Role Granted:");
-    if log.topics.len() >= 4 {
-        let role = format!("0x{}", hex::encode(log.topics[1]));
-        let account = format!("0x{}", hex::encode(&log.topics[2][12..]));
-        let sender = format!("0x{}", hex::encode(&log.topics[3][12..]));
-        println!("  🎭 Role: {}", role.yellow());
-        println!("  👤 Account: {}", account.green());
-        println!("  📤 Granted by: {}", sender.dimmed());
+/// Decode a log against the ABI definition of its event
+fn decode_log(event: &Event, log: &Log) -> DecodedEvent {
+    match event.parse_log(raw_log(log)) {
+        Ok(parsed) => DecodedEvent {
+            fields: parsed
+                .params
+                .iter()
+                .zip(&event.inputs)
+                .map(|(param, input)| {
+                    DecodedField::from_param(&param.name, &input.kind, &param.value)
+                })
+                .collect(),
+            notes: Vec::new(),
+        },
+        Err(e) => DecodedEvent {
+            fields: Vec::new(),
+            notes: vec![format!(
+                "⚠️  Log does not match the {} ABI ({e}) - showing raw data",
+                event.name
+            )],
+        },
     }
 }
 
-fn decode_role_revoked_event(log: &Log) {
-    println!("  🚫 Role Revoked:");
-    if log.topics.len() >= 4 {
-        let role = format!("0x{}", hex::encode(log.topics[1]));
-        let account = format!("0x{}", hex::encode(&log.topics[2][12..]));
-        let sender = format!("0x{}", hex::encode(&log.topics[3][12..]));
-        println!("  🎭 Role: {}", role.yellow());
-        println!("  👤 Account: {}", account.red());
-        println!("  📤 Revoked by: {}", sender.dimmed());
+fn raw_log(log: &Log) -> RawLog {
+    RawLog {
+        topics: log.topics.clone(),
+        data: log.data.to_vec(),
     }
 }
 
-fn decode_add_existing_rollup_event(log: &Log) {
-    println!("  🔄 Add Existing Rollup:");
-    if log.topics.len() >= 2 {
-        let rollup_id = U256::from(log.topics[1].as_bytes());
-        println!("  🆔 Rollup ID: {}", rollup_id.to_string().cyan());
+/// Icon and color for a field, chosen from its name and ABI type
+fn field_presentation(key: &str, kind: &ParamType) -> (&'static str, FieldStyle) {
+    if key.ends_with("amount") || key == "value" {
+        return ("💰", FieldStyle::Green);
     }
-    if !log.data.is_empty() {
-        println!("  ⚠️  Complex rollup data - showing summary only");
+    if key.ends_with("network") {
+        return ("🌐", FieldStyle::Cyan);
     }
-}
-
-fn decode_update_rollup_manager_version_event(log: &Log) {
-    println!("  📦 Rollup Manager Version Update:");
-    if !log.data.is_empty() && log.data.len() >= 64 {
-        // Try to decode string from data
-        println!("  📊 New version updated");
+    match kind {
+        ParamType::Address => ("📍", FieldStyle::Yellow),
+        ParamType::FixedBytes(_) => ("🔗", FieldStyle::Cyan),
+        ParamType::Bytes => ("📋", FieldStyle::Dimmed),
+        ParamType::String => ("📝", FieldStyle::Green),
+        ParamType::Bool => ("🔘", FieldStyle::Cyan),
+        ParamType::Uint(_) | ParamType::Int(_) => ("🔢", FieldStyle::Cyan),
+        _ => ("📦", FieldStyle::Dimmed),
     }
 }
 
-fn decode_min_delay_change_event(log: &Log) {
-    println!("  ⏱️  Min Delay Change:");
-    if !log.data.is_empty() && log.data.len() >= 64 {
-        let old_delay = U256::from(&log.data[0..32]);
-        let new_delay = U256::from(&log.data[32..64]);
-        println!("  📤 Old Delay: {} seconds", old_delay.to_string().dimmed());
-        println!("  📥 New Delay: {} seconds", new_delay.to_string().green());
+/// Render a decoded ABI value: addresses and bytes as 0x hex, integers in decimal
+fn format_token(token: &Token) -> String {
+    match token {
+        Token::Address(address) => format!("0x{address:x}"),
+        Token::FixedBytes(bytes) | Token::Bytes(bytes) => format!("0x{}", hex::encode(bytes)),
+        Token::Uint(value) => value.to_string(),
+        Token::Int(value) => I256::from_raw(*value).to_string(),
+        Token::Bool(value) => value.to_string(),
+        Token::String(value) => value.clone(),
+        Token::Array(tokens) | Token::FixedArray(tokens) | Token::Tuple(tokens) => format!(
+            "[{}]",
+            tokens
+                .iter()
+                .map(format_token)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
     }
 }
 
-fn decode_set_trusted_sequencer_event(log: &Log) {
-    println!("  🔗 Set Trusted Sequencer:");
-    if log.topics.len() >= 2 {
-        let sequencer = format!("0x{}", hex::encode(&log.topics[1][12..]));
-        println!("  👤 New Sequencer: {}", sequencer.green());
+/// Convert a Solidity parameter name such as `rollupID` to `rollup_id`
+fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
     }
+    snake
 }
 
-fn decode_set_trusted_aggregator_event(log: &Log) {
-    println!("  🔗 Set Trusted Aggregator:");
-    if log.topics.len() >= 2 {
-        let aggregator = format!("0x{}", hex::encode(&log.topics[1][12..]));
-        println!("  👤 New Aggregator: {}", aggregator.green());
-    }
-}
+fn get_rpc_url(chain: &str) -> Result<String> {
+    let rpc_url = match chain {
+        "anvil-l1" => {
+            std::env::var("RPC_1").unwrap_or_else(|_| "http://localhost:8545".to_string())
+        }
+        "anvil-l2" => {
+            std::env::var("RPC_2").unwrap_or_else(|_| "http://localhost:8546".to_string())
+        }
+        "anvil-l3" => {
+            std::env::var("RPC_3").unwrap_or_else(|_| "http://localhost:8547".to_string())
+        }
+        _ => {
+            // Extra L2s (anvil-l4 onwards) come from the configuration
+            let network_id = chain
+                .strip_prefix("anvil-l")
+                .and_then(|layer| layer.parse::<u64>().ok())
+                .filter(|&layer| layer >= 4)
+                .map(|layer| layer - 1)
+                .ok_or_else(|| EventError::invalid_chain(chain))?;
+            let config = crate::config::Config::load()?;
+            let extra = config.networks.chain(network_id).ok_or_else(|| {
+                EventError::invalid_chain(&format!(
+                    "{chain} is not configured (set RPC_{})",
+                    network_id + 1
+                ))
+            })?;
+            extra.rpc_url.as_str().to_string()
+        }
+    };
 
-fn decode_sequence_batches_event(log: &Log) {
-    println!("  📦 Sequence Batches:");
-    if log.topics.len() >= 2 {
-        let batch_num = U256::from(log.topics[1].as_bytes());
-        println!("  🔢 Batch Number: {}", batch_num.to_string().cyan());
-    }
+    Ok(rpc_url)
 }
 
-fn decode_verify_batches_event(log: &Log) {
-    println!("  ✅ Verify Batches:");
-    if log.topics.len() >= 2 {
-        let batch_num = U256::from(log.topics[1].as_bytes());
-        println!("  🔢 Batch Number: {}", batch_num.to_string().cyan());
-    }
-    if log.topics.len() >= 4 {
-        let aggregator = format!("0x{}", hex::encode(&log.topics[3][12..]));
-        println!("  👤 Aggregator: {}", aggregator.green());
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+    use ethers::utils::keccak256;
+
+    fn topic0(signature: &str) -> H256 {
+        H256::from(keccak256(signature))
     }
-}
 
-fn decode_update_l1_info_tree_event(log: &Log) {
-    println!("  🌳 Update L1 Info Tree:");
-    if log.topics.len() >= 3 {
-        let main_exit_root = format!("0x{}", hex::encode(log.topics[1]));
-        let rollup_exit_root = format!("0x{}", hex::encode(log.topics[2]));
-        println!("  🔗 Main Exit Root: {}", main_exit_root.cyan());
-        println!("  🔗 Rollup Exit Root: {}", rollup_exit_root.yellow());
+    #[test]
+    fn test_all_event_definitions_parse() {
+        assert_eq!(known_events().len(), EVENT_ABI.len());
     }
-}
 
-fn decode_update_l1_info_tree_v2_event(log: &Log) {
-    println!("  🌳 Update L1 Info Tree V2:");
-    if log.topics.len() >= 2 {
-        let current_l1_info_root = format!("0x{}", hex::encode(log.topics[1]));
-        println!("  🔗 Current L1 Info Root: {}", current_l1_info_root.cyan());
+    #[test]
+    fn test_bridge_event_signature_hash() {
+        let event = known_events()
+            .get(&topic0(
+                "BridgeEvent(uint8,uint32,address,uint32,address,uint256,bytes,uint32)",
+            ))
+            .expect("BridgeEvent is known");
+
+        assert_eq!(
+            format!("0x{:x}", event.signature()),
+            "0x501781209a1f8899323b96b4ef08b168df93e0a90c673d1e4cce39366cb62f9b"
+        );
     }
-    if !log.data.is_empty() && log.data.len() >= 96 {
-        let leaf_count = U256::from(&log.data[0..32]);
-        let block_hash = format!("0x{}", hex::encode(&log.data[32..64]));
-        let timestamp = U256::from(&log.data[64..96]);
-        println!("  📊 Leaf Count: {}", leaf_count.to_string().green());
-        println!("  🧱 Block Hash: {}", block_hash.yellow());
-        println!("  ⏰ Timestamp: {}", timestamp.to_string().dimmed());
+
+    #[test]
+    fn test_decode_transfer_event_fields() {
+        let from = Address::from_low_u64_be(1);
+        let to = Address::from_low_u64_be(2);
+        let mut amount = [0u8; 32];
+        U256::from(1000).to_big_endian(&mut amount);
+        let log = Log {
+            topics: vec![
+                topic0("Transfer(address,address,uint256)"),
+                H256::from(from),
+                H256::from(to),
+            ],
+            data: amount.to_vec().into(),
+            ..Default::default()
+        };
+
+        let event = lookup_event(&log).expect("Transfer is known");
+        let decoded = decode_log(event, &log);
+
+        assert_eq!(event_signature(event), "Transfer(address,address,uint256)");
+        assert_eq!(
+            decoded.to_json(),
+            serde_json::json!({
+                "from": format!("0x{from:x}"),
+                "to": format!("0x{to:x}"),
+                "value": "1000",
+            })
+        );
     }
-}
 
-fn decode_insert_global_exit_root_event(log: &Log) {
-    println!("  🌍 Insert Global Exit Root:");
-    if log.topics.len() >= 2 {
-        let global_exit_root = format!("0x{}", hex::encode(log.topics[1]));
-        println!("  🔗 Global Exit Root: {}", global_exit_root.cyan());
+    #[test]
+    fn test_decode_bridge_event_with_metadata() {
+        let origin = Address::from_low_u64_be(0xaa);
+        let destination = Address::from_low_u64_be(0xbb);
+        let data = ethers::abi::encode(&[
+            Token::Uint(U256::zero()),
+            Token::Uint(U256::from(0)),
+            Token::Address(origin),
+            Token::Uint(U256::from(1)),
+            Token::Address(destination),
+            Token::Uint(U256::from(500)),
+            Token::Bytes(vec![0xde, 0xad]),
+            Token::Uint(U256::from(7)),
+        ]);
+        let log = Log {
+            topics: vec![topic0(
+                "BridgeEvent(uint8,uint32,address,uint32,address,uint256,bytes,uint32)",
+            )],
+            data: data.into(),
+            ..Default::default()
+        };
+
+        let decoded = decode_log(lookup_event(&log).expect("BridgeEvent is known"), &log);
+        let json = decoded.to_json();
+
+        assert_eq!(json["destination_address"], format!("0x{destination:x}"));
+        assert_eq!(json["amount"], "500");
+        assert_eq!(json["metadata"], "0xdead");
+        assert_eq!(json["deposit_count"], "7");
+        assert!(decoded.notes.is_empty());
     }
-}
 
-fn decode_new_wrapped_token_event(log: &Log) {
-    println!("  🪙 New Wrapped Token:");
-    if !log.data.is_empty() && log.data.len() >= 128 {
-        let origin_network = U256::from(&log.data[0..32]);
-        let origin_token_address = format!("0x{}", hex::encode(&log.data[44..64]));
-        let wrapped_token_address = format!("0x{}", hex::encode(&log.data[76..96]));
+    #[test]
+    fn test_mismatched_log_reports_note() {
+        let log = Log {
+            topics: vec![topic0("Transfer(address,address,uint256)")],
+            ..Default::default()
+        };
 
-        println!("  🌐 Origin Network: {}", origin_network.to_string().cyan());
-        println!("  📍 Origin Token: {}", origin_token_address.yellow());
-        println!("  🎁 Wrapped Token: {}", wrapped_token_address.green());
+        let decoded = decode_log(lookup_event(&log).expect("Transfer is known"), &log);
 
-        // Try to decode metadata if present
-        if log.data.len() > 128 {
-            // Skip the first 128 bytes (4 * 32) and try to find the metadata offset
-            if let Ok(metadata_offset) = std::str::from_utf8(&log.data[128..]) {
-                if !metadata_offset.trim().is_empty() {
-                    println!("  📋 Metadata: Available");
-                }
-            }
-        }
+        assert!(decoded.fields.is_empty());
+        assert_eq!(decoded.notes.len(), 1);
     }
-}
 
-fn decode_message_received_event(log: &Log) {
-    println!("  📨 Message Received:");
-    if log.topics.len() >= 2 && !log.data.is_empty() && log.data.len() >= 96 {
-        let origin_address = format!("0x{}", hex::encode(&log.topics[1][12..]));
-        let origin_network = U256::from(&log.data[0..32]);
-        let eth_amount = U256::from(&log.data[64..96]);
+    #[test]
+    fn test_unknown_event_is_not_decoded() {
+        assert!(lookup_event(&Log::default()).is_none());
 
-        println!("  📍 Origin Address: {}", origin_address.yellow());
-        println!("  🌐 Origin Network: {}", origin_network.to_string().cyan());
-        println!("  💰 ETH Amount: {} wei", eth_amount.to_string().green());
+        let log = Log {
+            topics: vec![topic0("Unknown()")],
+            ..Default::default()
+        };
+        assert!(lookup_event(&log).is_none());
+    }
 
-        // Try to decode the data bytes
-        if log.data.len() > 96 {
-            println!("  📋 Message Data: Available");
-        }
+    #[test]
+    fn test_implementation_abi_takes_precedence() {
+        let abi = ethers::abi::parse_abi(&[
+            "event Transfer(address indexed sender, address indexed receiver, uint256 amount)",
+        ])
+        .unwrap();
+        let mut amount = [0u8; 32];
+        U256::from(5).to_big_endian(&mut amount);
+        let log = Log {
+            topics: vec![
+                topic0("Transfer(address,address,uint256)"),
+                H256::from(Address::from_low_u64_be(1)),
+                H256::from(Address::from_low_u64_be(2)),
+            ],
+            data: amount.to_vec().into(),
+            ..Default::default()
+        };
+
+        let event = lookup_event_with(&log, Some(&abi)).expect("Transfer is known");
+
+        assert_eq!(decode_log(&event, &log).to_json()["amount"], "5");
+        assert_eq!(
+            decode_log(&lookup_event_with(&log, None).unwrap(), &log).to_json()["value"],
+            "5"
+        );
     }
-}
 
-fn decode_asset_received_event(log: &Log) {
-    println!("  💰 Asset Received:");
-    if log.topics.len() >= 2 && !log.data.is_empty() && log.data.len() >= 32 {
-        let sender = format!("0x{}", hex::encode(&log.topics[1][12..]));
-        let amount = U256::from(&log.data[0..32]);
+    #[test]
+    fn test_anonymous_event_from_implementation_abi() {
+        let abi = ethers::abi::parse_abi(&["event Ping(uint256 nonce) anonymous"]).unwrap();
+        let mut nonce = [0u8; 32];
+        U256::from(9).to_big_endian(&mut nonce);
+        let log = Log {
+            data: nonce.to_vec().into(),
+            ..Default::default()
+        };
+
+        let event = lookup_event_with(&log, Some(&abi)).expect("Ping matches");
+
+        assert_eq!(event.name, "Ping");
+        assert_eq!(decode_log(&event, &log).to_json()["nonce"], "9");
+        assert!(lookup_event_with(&log, None).is_none());
+    }
 
-        println!("  👤 Sender: {}", sender.yellow());
-        println!("  💰 Amount: {} wei", amount.to_string().green());
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("originNetwork"), "origin_network");
+        assert_eq!(to_snake_case("rollupID"), "rollup_id");
+        assert_eq!(to_snake_case("programVKey"), "program_v_key");
+        assert_eq!(to_snake_case("blockhash"), "blockhash");
     }
-}
 
-fn get_rpc_url(chain: &str) -> Result<String> {
-    let rpc_url = match chain {
-        "anvil-l1" => {
-            std::env::var("RPC_1").unwrap_or_else(|_| "http://localhost:8545".to_string())
-        }
-        "anvil-l2" => {
-            std::env::var("RPC_2").unwrap_or_else(|_| "http://localhost:8546".to_string())
-        }
-        "anvil-l3" => {
-            std::env::var("RPC_3").unwrap_or_else(|_| "http://localhost:8547".to_string())
-        }
-        _ => {
-            return Err(EventError::invalid_chain(chain).into());
+    #[test]
+    fn test_parse_block_bound() {
+        assert_eq!("latest".parse(), Ok(BlockBound::Latest));
+        assert_eq!(" Earliest ".parse(), Ok(BlockBound::Earliest));
+        assert_eq!("1200".parse(), Ok(BlockBound::Number(1200)));
+        assert_eq!("0x10".parse(), Ok(BlockBound::Number(16)));
+        assert!("pending".parse::<BlockBound>().is_err());
+    }
+
+    #[test]
+    fn test_block_range_resolution() {
+        assert_eq!(BlockRange::last(10).resolve(100).unwrap(), (91, 100));
+        assert_eq!(BlockRange::last(500).resolve(100).unwrap(), (0, 100));
+
+        let range = |from, to| BlockRange {
+            blocks: 10,
+            from,
+            to,
+        };
+        assert_eq!(
+            range(Some(BlockBound::Number(20)), None)
+                .resolve(100)
+                .unwrap(),
+            (20, 100)
+        );
+        assert_eq!(
+            range(None, Some(BlockBound::Number(50)))
+                .resolve(100)
+                .unwrap(),
+            (41, 50)
+        );
+        assert_eq!(
+            range(Some(BlockBound::Earliest), Some(BlockBound::Latest))
+                .resolve(100)
+                .unwrap(),
+            (0, 100)
+        );
+        assert!(range(None, Some(BlockBound::Number(101)))
+            .resolve(100)
+            .is_err());
+        assert!(
+            range(Some(BlockBound::Latest), Some(BlockBound::Number(50)))
+                .resolve(100)
+                .is_err()
+        );
+        assert!(
+            range(Some(BlockBound::Number(60)), Some(BlockBound::Number(50)))
+                .validate()
+                .is_err()
+        );
+        // The --blocks limit does not apply to explicit ranges
+        assert!(BlockRange {
+            blocks: 0,
+            from: Some(BlockBound::Earliest),
+            to: None,
         }
-    };
+        .validate()
+        .is_ok());
+    }
 
-    Ok(rpc_url)
+    #[test]
+    fn test_block_chunks() {
+        assert_eq!(block_chunks(5, 5, 2_000), vec![(5, 5)]);
+        assert_eq!(
+            block_chunks(0, 4_500, 2_000),
+            vec![(0, 1_999), (2_000, 3_999), (4_000, 4_500)]
+        );
+        assert_eq!(block_chunks(10, 9, 2_000), Vec::new());
+        assert_eq!(
+            block_chunks(u64::MAX - 1, u64::MAX, 1),
+            vec![(u64::MAX - 1, u64::MAX - 1), (u64::MAX, u64::MAX)]
+        );
+    }
+
+    #[test]
+    fn test_timeline_interleaves_networks_by_timestamp() {
+        let key = |timestamp, network_id, block_number, log_index| TimelineKey {
+            timestamp,
+            network_id,
+            block_number: Some(block_number),
+            log_index: Some(log_index),
+        };
+        let mut timeline = vec![
+            (key(Some(120), 0, 7, 0), "l1 late"),
+            (key(None, 0, 8, 0), "l1 unknown time"),
+            (key(Some(100), 1, 40, 1), "l2 second log"),
+            (key(Some(110), 0, 6, 0), "l1 early"),
+            (key(Some(100), 1, 40, 0), "l2 first log"),
+            (key(Some(100), 0, 5, 0), "l1 same second"),
+        ];
+        sort_timeline(&mut timeline);
+
+        let order: Vec<&str> = timeline.iter().map(|(_, label)| *label).collect();
+        assert_eq!(
+            order,
+            [
+                "l1 same second",
+                "l2 first log",
+                "l2 second log",
+                "l1 early",
+                "l1 late",
+                "l1 unknown time",
+            ]
+        );
+    }
 }
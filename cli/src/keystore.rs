@@ -0,0 +1,238 @@
+//! Signing key resolution for `--account <name>`
+//!
+//! An account name is looked up, in order:
+//!   1. in `[accounts.named.<name>]` of the configuration file, which points at
+//!      an encrypted JSON keystore or at an environment variable holding the key
+//!   2. in the `AGGSANDBOX_ACCOUNT_<NAME>` environment variable
+//!   3. as a keystore file named `<name>` or `<name>.json` in
+//!      `~/.aggsandbox/keystores` or `~/.foundry/keystores`
+//!
+//! Keystore passwords come from the account's `password_env`, then
+//! `AGGSANDBOX_KEYSTORE_PASSWORD`, then an interactive prompt. Keys never have
+//! to appear on the command line, so they stay out of shell history.
+
+use crate::config::{Config, NamedAccount};
+use crate::error::{AggSandboxError, ConfigError, Result};
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::Address;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tracing::info;
+
+/// Prefix of the environment variables holding the key of an account
+pub const ACCOUNT_ENV_PREFIX: &str = "AGGSANDBOX_ACCOUNT_";
+/// Environment variable holding the password of keystores without `password_env`
+pub const PASSWORD_ENV: &str = "AGGSANDBOX_KEYSTORE_PASSWORD";
+/// Keystore directories searched for unconfigured names, relative to the home directory
+const KEYSTORE_DIRS: [&str; 2] = [".aggsandbox/keystores", ".foundry/keystores"];
+
+/// Resolve the account and make it the default signer of `config`
+///
+/// Returns the address of the selected account. Explicit `--private-key`
+/// flags still take precedence over the selected account.
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub fn select_account(config: &mut Config, name: &str) -> Result<Address> {
+    let wallet = resolve_account(config, name)?;
+    let address = wallet.address();
+    info!(account = %name, address = ?address, "Using named account");
+
+    config.accounts.selected_key = Some(format!("0x{}", hex::encode(wallet.signer().to_bytes())));
+    Ok(address)
+}
+
+/// Load the wallet of a named account
+pub fn resolve_account(config: &Config, name: &str) -> Result<LocalWallet> {
+    if let Some(account) = config.accounts.named.get(name) {
+        return wallet_from_named(name, account);
+    }
+
+    let env_var = account_env_var(name);
+    if std::env::var(&env_var).is_ok() {
+        return wallet_from_env(&env_var);
+    }
+
+    let dirs: Vec<PathBuf> = home_dir()
+        .map(|home| KEYSTORE_DIRS.iter().map(|dir| home.join(dir)).collect())
+        .unwrap_or_default();
+    if let Some(path) = find_keystore(&dirs, name) {
+        return decrypt_keystore(&path, None);
+    }
+
+    Err(account_error(&format!(
+        "Unknown account '{name}': add [accounts.named.{name}] to aggsandbox.toml, set {env_var} or import a keystore into ~/.aggsandbox/keystores/{name}"
+    )))
+}
+
+/// Environment variable consulted for an account name, e.g. `AGGSANDBOX_ACCOUNT_OPS_BOT` for `ops-bot`
+pub fn account_env_var(name: &str) -> String {
+    let suffix: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{ACCOUNT_ENV_PREFIX}{suffix}")
+}
+
+fn wallet_from_named(name: &str, account: &NamedAccount) -> Result<LocalWallet> {
+    match (&account.keystore, &account.private_key_env) {
+        (Some(keystore), None) => {
+            decrypt_keystore(&expand_home(keystore), account.password_env.as_deref())
+        }
+        (None, Some(env_var)) => wallet_from_env(env_var),
+        _ => Err(account_error(&format!(
+            "Account '{name}' must set exactly one of 'keystore' or 'private_key_env'"
+        ))),
+    }
+}
+
+fn wallet_from_env(env_var: &str) -> Result<LocalWallet> {
+    let key = std::env::var(env_var)
+        .map_err(|_| AggSandboxError::Config(ConfigError::env_var_not_found(env_var)))?;
+    // The parse error is reported without the value so the key never reaches the logs
+    LocalWallet::from_str(key.trim())
+        .map_err(|_| account_error(&format!("{env_var} does not contain a valid private key")))
+}
+
+fn decrypt_keystore(path: &Path, password_env: Option<&str>) -> Result<LocalWallet> {
+    if !path.is_file() {
+        return Err(account_error(&format!(
+            "Keystore {} does not exist",
+            path.display()
+        )));
+    }
+    let password = keystore_password(path, password_env)?;
+    LocalWallet::decrypt_keystore(path, password).map_err(|e| {
+        account_error(&format!(
+            "Failed to decrypt keystore {}: {e}",
+            path.display()
+        ))
+    })
+}
+
+fn keystore_password(path: &Path, password_env: Option<&str>) -> Result<String> {
+    if let Some(env_var) = password_env {
+        return std::env::var(env_var)
+            .map_err(|_| AggSandboxError::Config(ConfigError::env_var_not_found(env_var)));
+    }
+    if let Ok(password) = std::env::var(PASSWORD_ENV) {
+        return Ok(password);
+    }
+    if !atty::is(atty::Stream::Stdin) {
+        return Err(account_error(&format!(
+            "No password for keystore {}: set {PASSWORD_ENV} or 'password_env' when not running interactively",
+            path.display()
+        )));
+    }
+
+    prompt_password(path)
+}
+
+#[cfg(feature = "tui")]
+fn prompt_password(path: &Path) -> Result<String> {
+    dialoguer::Password::new()
+        .with_prompt(format!("Password for {}", path.display()))
+        .interact()
+        .map_err(|e| AggSandboxError::Other(format!("Password prompt failed: {e}")))
+}
+
+#[cfg(not(feature = "tui"))]
+fn prompt_password(path: &Path) -> Result<String> {
+    Err(account_error(&format!(
+        "No password for keystore {}: set {PASSWORD_ENV} or 'password_env' (prompting needs the 'tui' feature)",
+        path.display()
+    )))
+}
+
+/// First `<name>` or `<name>.json` file in the given directories
+fn find_keystore(dirs: &[PathBuf], name: &str) -> Option<PathBuf> {
+    dirs.iter()
+        .flat_map(|dir| [dir.join(name), dir.join(format!("{name}.json"))])
+        .find(|path| path.is_file())
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+fn account_error(msg: &str) -> AggSandboxError {
+    AggSandboxError::Config(ConfigError::validation_failed(msg))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    const ANVIL_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+    const ANVIL_ADDRESS: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+    #[test]
+    fn test_account_env_var() {
+        assert_eq!(account_env_var("deployer"), "AGGSANDBOX_ACCOUNT_DEPLOYER");
+        assert_eq!(account_env_var("ops-bot.1"), "AGGSANDBOX_ACCOUNT_OPS_BOT_1");
+    }
+
+    #[test]
+    fn test_named_account_from_env() {
+        std::env::set_var("AGGSANDBOX_TEST_NAMED_KEY", ANVIL_KEY);
+        let account = NamedAccount {
+            private_key_env: Some("AGGSANDBOX_TEST_NAMED_KEY".to_string()),
+            ..Default::default()
+        };
+
+        let wallet = wallet_from_named("ops", &account).unwrap();
+
+        assert_eq!(format!("{:#x}", wallet.address()), ANVIL_ADDRESS);
+    }
+
+    #[test]
+    fn test_named_account_needs_one_source() {
+        let both = NamedAccount {
+            keystore: Some(PathBuf::from("deployer.json")),
+            private_key_env: Some("KEY".to_string()),
+            ..Default::default()
+        };
+
+        assert!(wallet_from_named("deployer", &both).is_err());
+        assert!(wallet_from_named("deployer", &NamedAccount::default()).is_err());
+    }
+
+    #[test]
+    fn test_invalid_env_key_is_not_echoed() {
+        std::env::set_var("AGGSANDBOX_TEST_BAD_KEY", "0xnot-a-secret-key");
+
+        let error = wallet_from_env("AGGSANDBOX_TEST_BAD_KEY")
+            .unwrap_err()
+            .to_string();
+
+        assert!(!error.contains("not-a-secret-key"));
+    }
+
+    #[test]
+    fn test_keystore_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = hex::decode(ANVIL_KEY.trim_start_matches("0x")).unwrap();
+        let mut rng = ethers::core::rand::thread_rng();
+        LocalWallet::encrypt_keystore(dir.path(), &mut rng, key, "hunter2", Some("deployer"))
+            .unwrap();
+        std::env::set_var("AGGSANDBOX_TEST_KEYSTORE_PASSWORD", "hunter2");
+
+        let path = find_keystore(&[dir.path().to_path_buf()], "deployer").unwrap();
+        let wallet = decrypt_keystore(&path, Some("AGGSANDBOX_TEST_KEYSTORE_PASSWORD")).unwrap();
+
+        assert_eq!(format!("{:#x}", wallet.address()), ANVIL_ADDRESS);
+        assert!(find_keystore(&[dir.path().to_path_buf()], "missing").is_none());
+    }
+}
@@ -1,10 +1,67 @@
+//! Library API of the sandbox CLI
+//!
+//! The library owns the whole CLI: [`cli`] parses and dispatches commands and
+//! the `aggsandbox` binary is a thin frontend over it. Integration tests and Rust tooling can drive bridge operations directly
+//! instead of shelling out to `aggsandbox`. Initialize the UI once with
+//! [`ui::init_ui`] before calling into [`commands::bridge`], which reports
+//! progress through it:
+//!
+//! ```no_run
+//! use aggsandbox::commands::bridge::{bridge_asset, BridgeAssetArgs};
+//! use aggsandbox::config::Config;
+//! use aggsandbox::ui::{init_ui, OutputFormat};
+//!
+//! # async fn run() -> aggsandbox::error::Result<()> {
+//! init_ui(OutputFormat::Json, true);
+//! let config = Config::load()?;
+//! let args = BridgeAssetArgs::builder()
+//!     .config(&config)
+//!     .source_network(0)
+//!     .destination_network(1)
+//!     .amount("1000000000000000000")
+//!     .token_address("0x0000000000000000000000000000000000000000")
+//!     .build_with_crate_error()?;
+//! bridge_asset(args).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The default features build the full CLI. Embedders that only need the
+//! bridge and claim logic can disable them for a slimmer dependency tree:
+//!
+//! ```toml
+//! aggsandbox = { path = "cli", default-features = false }
+//! ```
+//!
+//! | Feature   | Enables                                                       |
+//! | --------- | ------------------------------------------------------------- |
+//! | `docker`  | [`docker`], the sandbox lifecycle commands, aggkit detection  |
+//! | `tui`     | Prompts, bridge wizard, shell, dashboard, notifications       |
+//! | `metrics` | The `monitor` latency SLO command                             |
+//! | `daemon`  | The `serve` HTTP control server                               |
+//! | `otel`    | OTLP export of the spans in [`telemetry`]                     |
+//!
+//! [`cli`] only offers the commands the enabled features provide.
+
 pub mod api;
 pub mod api_client;
+pub mod artifacts;
+pub mod cli;
+pub mod commands;
+pub mod concurrency;
 pub mod config;
+#[cfg(feature = "docker")]
 pub mod docker;
 pub mod error;
 pub mod events;
+pub mod keystore;
+pub mod logging;
 pub mod logs;
 pub mod progress;
+pub mod project;
+pub mod rpc;
+pub mod schema;
+pub mod telemetry;
 pub mod types;
+pub mod ui;
 pub mod validation;
@@ -9,7 +9,7 @@ use tracing_subscriber::{
     fmt::{self, format::FmtSpan},
     layer::SubscriberExt,
     util::SubscriberInitExt,
-    EnvFilter,
+    EnvFilter, Layer,
 };
 
 /// Logging output format options
@@ -36,6 +36,8 @@ pub struct LogConfig {
     pub include_target: bool,
     /// Whether to include span information
     pub include_spans: bool,
+    /// OTLP/HTTP collector the spans are exported to
+    pub otlp_endpoint: Option<String>,
 }
 
 impl Default for LogConfig {
@@ -46,6 +48,7 @@ impl Default for LogConfig {
             include_location: false,
             include_target: false,
             include_spans: false,
+            otlp_endpoint: None,
         }
     }
 }
@@ -60,6 +63,7 @@ impl LogConfig {
             include_location: true,
             include_target: true,
             include_spans: true,
+            otlp_endpoint: None,
         }
     }
 
@@ -72,6 +76,7 @@ impl LogConfig {
             include_location: false,
             include_target: false,
             include_spans: false,
+            otlp_endpoint: None,
         }
     }
 
@@ -84,6 +89,7 @@ impl LogConfig {
             include_location: true,
             include_target: true,
             include_spans: true,
+            otlp_endpoint: None,
         }
     }
 }
@@ -106,6 +112,13 @@ pub fn init_logging(config: &LogConfig) -> Result<(), Box<dyn std::error::Error
         FmtSpan::NONE
     };
 
+    // The exporter has its own filter: spans below the log level are exported too
+    let otlp_layer = config
+        .otlp_endpoint
+        .as_deref()
+        .map(crate::telemetry::otlp_layer)
+        .transpose()?;
+
     match config.format {
         LogFormat::Pretty => {
             let fmt_layer = fmt::layer()
@@ -117,8 +130,8 @@ pub fn init_logging(config: &LogConfig) -> Result<(), Box<dyn std::error::Error
                 .pretty();
 
             tracing_subscriber::registry()
-                .with(filter)
-                .with(fmt_layer)
+                .with(otlp_layer)
+                .with(fmt_layer.with_filter(filter))
                 .init();
         }
         LogFormat::Compact => {
@@ -131,8 +144,8 @@ pub fn init_logging(config: &LogConfig) -> Result<(), Box<dyn std::error::Error
                 .compact();
 
             tracing_subscriber::registry()
-                .with(filter)
-                .with(fmt_layer)
+                .with(otlp_layer)
+                .with(fmt_layer.with_filter(filter))
                 .init();
         }
         LogFormat::Json => {
@@ -144,8 +157,8 @@ pub fn init_logging(config: &LogConfig) -> Result<(), Box<dyn std::error::Error
                 .json();
 
             tracing_subscriber::registry()
-                .with(filter)
-                .with(fmt_layer)
+                .with(otlp_layer)
+                .with(fmt_layer.with_filter(filter))
                 .init();
         }
     }
@@ -239,6 +252,7 @@ mod tests {
         assert!(!config.include_location);
         assert!(!config.include_target);
         assert!(!config.include_spans);
+        assert!(config.otlp_endpoint.is_none());
     }
 
     #[test]
@@ -0,0 +1,100 @@
+//! Discovery of the sandbox project root
+//!
+//! The compose files, `.env`, config files and the `.aggsandbox/` state all
+//! live in the project root, and the CLI resolves them relative to the working
+//! directory. So the CLI can be run from anywhere, the root is located and
+//! entered before any command runs:
+//!
+//! 1. the `--project-dir` flag
+//! 2. the `AGGSANDBOX_HOME` environment variable
+//! 3. the nearest directory, from the working directory upwards, holding a
+//!    compose file or an `aggsandbox` config file (like git finds `.git`)
+
+use crate::config::CONFIG_FILES;
+use crate::error::{ConfigError, Result};
+use std::path::{Path, PathBuf};
+
+/// Environment variable pointing at the project root
+pub const HOME_ENV: &str = "AGGSANDBOX_HOME";
+
+/// Compose files marking a sandbox checkout
+const COMPOSE_FILES: [&str; 2] = ["docker-compose.yml", "docker-compose.multi-l2.yml"];
+
+/// Whether a directory holds a compose file or a config file
+fn is_project_root(dir: &Path) -> bool {
+    COMPOSE_FILES
+        .iter()
+        .chain(CONFIG_FILES.iter())
+        .any(|file| dir.join(file).is_file())
+}
+
+/// Nearest directory from `start` upwards that is a project root
+pub fn find_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| is_project_root(dir))
+        .map(Path::to_path_buf)
+}
+
+/// Project root to run in, `None` when none is configured or found
+///
+/// Directories given explicitly must exist but need not contain a compose
+/// file: commands that require one report it missing as before.
+pub fn locate(project_dir: Option<&Path>) -> Result<Option<PathBuf>> {
+    let explicit = match project_dir {
+        Some(dir) => Some(("--project-dir", dir.to_path_buf())),
+        None => std::env::var_os(HOME_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(|dir| (HOME_ENV, PathBuf::from(dir))),
+    };
+    if let Some((source, dir)) = explicit {
+        if !dir.is_dir() {
+            return Err(ConfigError::invalid_value(
+                source,
+                &dir.display().to_string(),
+                "must be an existing directory",
+            )
+            .into());
+        }
+        return Ok(Some(dir));
+    }
+
+    Ok(std::env::current_dir().ok().and_then(|cwd| find_root(&cwd)))
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_root_walks_up_to_the_compose_file() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("docker-compose.yml"), "services: {}\n").unwrap();
+        let nested = root.path().join("contracts").join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(find_root(&nested).as_deref(), Some(root.path()));
+        assert_eq!(find_root(root.path()).as_deref(), Some(root.path()));
+    }
+
+    #[test]
+    fn test_find_root_accepts_config_files() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("aggsandbox.toml"), "").unwrap();
+        let nested = root.path().join("scripts");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        assert_eq!(find_root(&nested).as_deref(), Some(root.path()));
+    }
+
+    #[test]
+    fn test_locate_prefers_the_explicit_directory() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(
+            locate(Some(root.path())).unwrap().as_deref(),
+            Some(root.path())
+        );
+        assert!(locate(Some(&root.path().join("missing"))).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! WebSocket connections to the sandbox networks
+//!
+//! A network may configure a `ws_url` (`WS_RPC_<n>`) next to its HTTP RPC URL.
+//! Reads and transactions keep going over HTTP; the WebSocket subscribes to new
+//! blocks, so `events --follow` and receipt waiting react as soon as a block is
+//! mined instead of on their next poll. When no WebSocket is configured, it
+//! cannot be reached or the subscription drops, callers fall back to polling.
+
+use crate::config::{ChainConfig, Config};
+use crate::types::WsUrl;
+use ethers::prelude::*;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// How long connecting to a WebSocket endpoint may take before HTTP is used
+const WS_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Connect to a WebSocket endpoint, `None` if it cannot be reached in time
+#[allow(clippy::disallowed_methods)] // Allow tracing macros
+pub async fn connect_ws(url: &WsUrl) -> Option<Provider<Ws>> {
+    match tokio::time::timeout(WS_CONNECT_TIMEOUT, Provider::<Ws>::connect(url.as_str())).await {
+        Ok(Ok(provider)) => Some(provider),
+        Ok(Err(e)) => {
+            debug!(url = %url, error = %e, "WebSocket connection failed, using HTTP");
+            None
+        }
+        Err(_) => {
+            debug!(url = %url, "WebSocket connection timed out, using HTTP");
+            None
+        }
+    }
+}
+
+/// New block notifications of a chain
+///
+/// Blocks are streamed from an `eth_subscribe("newHeads")` subscription when
+/// the chain has a reachable WebSocket endpoint. Otherwise, and once the
+/// subscription ends, [`NewBlocks::wait`] sleeps for the polling interval.
+pub struct NewBlocks {
+    receiver: Option<mpsc::Receiver<u64>>,
+    task: Option<JoinHandle<()>>,
+}
+
+impl NewBlocks {
+    /// Polling only, without a subscription
+    pub fn polling() -> Self {
+        Self {
+            receiver: None,
+            task: None,
+        }
+    }
+
+    /// Subscribe to the new blocks of a chain, falling back to polling
+    pub async fn subscribe(chain: &ChainConfig) -> Self {
+        let Some(url) = &chain.ws_url else {
+            return Self::polling();
+        };
+        let Some(provider) = connect_ws(url).await else {
+            return Self::polling();
+        };
+
+        let (sender, receiver) = mpsc::channel(16);
+        // The subscription borrows the provider, so the task owns both
+        let task = tokio::spawn(async move {
+            let Ok(mut blocks) = provider.subscribe_blocks().await else {
+                return;
+            };
+            while let Some(block) = blocks.next().await {
+                let number = block.number.unwrap_or_default().as_u64();
+                if sender.send(number).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            receiver: Some(receiver),
+            task: Some(task),
+        }
+    }
+
+    /// Subscribe to the chain a client is connected to, found by its chain ID
+    pub async fn for_client<M: Middleware>(client: &M) -> Self {
+        let Ok(chain_id) = client.get_chainid().await else {
+            return Self::polling();
+        };
+        let Ok(config) = Config::load() else {
+            return Self::polling();
+        };
+        let networks = &config.networks;
+        let chain = networks
+            .network_ids()
+            .into_iter()
+            .filter_map(|network_id| networks.chain(network_id))
+            .find(|chain| chain.chain_id.as_u64().ok() == Some(chain_id.as_u64()));
+        match chain {
+            Some(chain) => Self::subscribe(chain).await,
+            None => Self::polling(),
+        }
+    }
+
+    /// Whether new blocks are streamed over a WebSocket
+    pub fn is_streaming(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Wait until the next block is mined, or at most `timeout`
+    pub async fn wait(&mut self, timeout: Duration) {
+        let Some(receiver) = &mut self.receiver else {
+            tokio::time::sleep(timeout).await;
+            return;
+        };
+        if let Ok(None) = tokio::time::timeout(timeout, receiver.recv()).await {
+            // The subscription ended (e.g. the chain restarted): poll from now on
+            self.receiver = None;
+        }
+    }
+}
+
+impl Drop for NewBlocks {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_polling_waits_for_the_interval() {
+        let mut blocks = NewBlocks::polling();
+        assert!(!blocks.is_streaming());
+
+        let started = std::time::Instant::now();
+        blocks.wait(Duration::from_millis(20)).await;
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[tokio::test]
+    async fn test_chain_without_ws_url_polls() {
+        let chain = ChainConfig {
+            name: "Test-L1".to_string(),
+            chain_id: crate::types::ChainId::new("1").unwrap(),
+            rpc_url: crate::types::RpcUrl::new("http://localhost:8545").unwrap(),
+            fork_url: None,
+            ws_url: None,
+        };
+        assert!(!NewBlocks::subscribe(&chain).await.is_streaming());
+    }
+}
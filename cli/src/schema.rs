@@ -0,0 +1,1863 @@
+//! Versioning and JSON Schemas of the JSON output
+//!
+//! Every document printed with `--output json` (and every NDJSON line) carries
+//! a `schema_version`. The version is bumped whenever a field is removed,
+//! renamed or changes type, so automation can detect breaking output changes;
+//! added fields keep the version, so consumers should ignore unknown fields.
+//! `--schema` prints the JSON Schema of a command's document without running
+//! the command.
+
+use serde_json::{json, Map, Value};
+
+/// Version of the JSON output documents
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// Add `schema_version` to a JSON output document
+///
+/// Objects get the field next to their own, arrays and scalars are wrapped as
+/// `{"schema_version": N, "data": ...}`.
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+pub fn versioned(value: Value) -> Value {
+    match value {
+        Value::Object(mut object) => {
+            object.insert("schema_version".to_string(), SCHEMA_VERSION.into());
+            Value::Object(object)
+        }
+        data => json!({ "schema_version": SCHEMA_VERSION, "data": data }),
+    }
+}
+
+/// Builder of the JSON Schema of one command's document
+type SchemaBuilder = fn() -> Value;
+
+/// Commands with a JSON output document and the builder of its schema
+const SCHEMAS: &[(&str, SchemaBuilder)] = &[
+    ("status", status),
+    ("doctor", doctor),
+    ("upgrade", upgrade),
+    ("info", info),
+    ("report", report),
+    ("show bridges", show_bridges),
+    ("show claims", show_claims),
+    ("show claim-proof", show_claim_proof),
+    ("show l1-info-tree-index", show_l1_info_tree_index),
+    ("show l1-info-tree", show_l1_info_tree),
+    ("show exit-roots", show_exit_roots),
+    ("show rollups", show_rollups),
+    ("show certificates", show_certificates),
+    ("show balances", show_balances),
+    ("show history", show_history),
+    ("show gas-report", show_gas_report),
+    ("show config-endpoints", show_config_endpoints),
+    ("assert balance", assertion),
+    ("assert claimed", assertion),
+    ("bridge asset", bridge_asset),
+    ("bridge claim", bridge_claim),
+    ("bridge claim-bundle", bridge_claim_bundle),
+    ("bridge message", bridge_message),
+    ("bridge bridge-and-call", bridge_and_call),
+    ("bridge claim-all", bridge_claim_all),
+    ("bridge claim-budget", bridge_claim_budget),
+    ("bridge watch", bridge_watch),
+    ("bridge bridge-message", bridge_bridge_message),
+    ("bridge utils build-payload", utils_build_payload),
+    ("bridge utils compute-index", utils_compute_index),
+    ("bridge utils decode-index", utils_decode_index),
+    ("bridge utils get-mapped", utils_get_mapped),
+    ("bridge utils precalculate", utils_precalculate),
+    ("bridge utils get-origin", utils_get_origin),
+    ("bridge utils is-claimed", utils_is_claimed),
+    ("bridge utils network-id", utils_network_id),
+    ("bridge utils check-metadata", utils_check_metadata),
+    ("bridge utils verify-proof", utils_verify_proof),
+    ("bridge utils decode-calldata", utils_decode_calldata),
+    ("bridge utils encode-call", utils_encode_call),
+    ("fund", fund),
+    ("token deploy", token_deploy),
+    ("token list", token_list),
+    ("token info", token_info),
+    ("chain mine", chain_control),
+    ("chain increase-time", chain_control),
+    ("chain set-interval", chain_control),
+    ("chain automine", chain_control),
+    ("tx bump", tx_replacement),
+    ("tx cancel", tx_replacement),
+    ("config show", config_show),
+    ("config validate", config_validate),
+    ("config init", config_init),
+    ("config set", config_set),
+    ("config import", config_import),
+    ("contracts report", contracts_report),
+    ("contracts list", contracts_list),
+    ("contracts refresh", contracts_refresh),
+    ("snapshot save", snapshot_save),
+    ("snapshot restore", snapshot_restore),
+    ("snapshot list", snapshot_list),
+    ("index sync", index_sync),
+    ("index status", index_status),
+    ("index reset", index_reset),
+    ("state prune", state_prune),
+    ("cache clear", cache_clear),
+    ("registry address", registry_address),
+    ("registry list", registry_list),
+    ("registry get", registry_get),
+    ("registry set", registry_set),
+    ("registry publish", registry_publish),
+    ("events", events),
+    ("rpc", rpc),
+    ("test e2e", test_e2e),
+    ("monitor slo", monitor_slo),
+];
+
+/// Commands with a JSON output schema, as space separated subcommand paths
+pub fn commands() -> impl Iterator<Item = &'static str> {
+    SCHEMAS.iter().map(|(command, _)| *command)
+}
+
+/// JSON Schema of a command's output document, e.g. for `"show bridges"`
+pub fn schema_for(command: &str) -> Option<Value> {
+    SCHEMAS
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|(name, build)| {
+            let mut schema = build();
+            schema["title"] = format!("aggsandbox {name} --output json").into();
+            schema
+        })
+}
+
+/// Top-level document with `schema_version` and the given properties
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn document(description: &str, properties: Value, required: &[&str]) -> Value {
+    let mut all_properties = Map::new();
+    all_properties.insert(
+        "schema_version".to_string(),
+        json!({ "const": SCHEMA_VERSION }),
+    );
+    if let Value::Object(properties) = properties {
+        all_properties.extend(properties);
+    }
+    let mut all_required = vec!["schema_version"];
+    all_required.extend_from_slice(required);
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "description": description,
+        "type": "object",
+        "properties": all_properties,
+        "required": all_required,
+    })
+}
+
+/// Document wrapping an array in `data`
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn list(description: &str, item: Value) -> Value {
+    document(description, json!({ "data": array(item) }), &["data"])
+}
+
+/// Document that is one of several shapes, each versioned
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn one_of(description: &str, variants: Vec<Value>) -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "description": description,
+        "oneOf": variants
+            .into_iter()
+            .map(|mut variant| {
+                if let Some(object) = variant.as_object_mut() {
+                    object.remove("$schema");
+                }
+                variant
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn object(properties: Value, required: &[&str]) -> Value {
+    json!({ "type": "object", "properties": properties, "required": required })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn array(item: Value) -> Value {
+    json!({ "type": "array", "items": item })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn nullable(schema: Value) -> Value {
+    json!({ "anyOf": [schema, { "type": "null" }] })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn boolean() -> Value {
+    json!({ "type": "boolean" })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn uint() -> Value {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn address() -> Value {
+    json!({ "type": "string", "pattern": "^0x[0-9a-fA-F]{40}$" })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn hash() -> Value {
+    json!({ "type": "string", "pattern": "^0x[0-9a-fA-F]{64}$" })
+}
+
+/// 0x-prefixed hex bytes, possibly empty
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn bytes() -> Value {
+    json!({ "type": "string", "pattern": "^0x([0-9a-fA-F]{2})*$" })
+}
+
+/// 0x-prefixed hex quantity, as serialized for 256-bit values
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn quantity() -> Value {
+    json!({ "type": "string", "pattern": "^0x[0-9a-fA-F]+$" })
+}
+
+/// Decimal integer kept as a string to avoid precision loss
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn decimal() -> Value {
+    json!({ "type": "string", "pattern": "^[0-9]+$" })
+}
+
+/// `{"networks": [...]}` grouping of a per-network result, or its error
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn grouped(description: &str) -> Value {
+    document(
+        description,
+        json!({
+            "networks": array(object(
+                json!({ "network_id": uint(), "error": string() }),
+                &["network_id"],
+            )),
+        }),
+        &["networks"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn status() -> Value {
+    document(
+        "Service health, chain heads and bridge service state of the sandbox",
+        json!({
+            "running": boolean(),
+            "services": array(object(
+                json!({
+                    "service": string(),
+                    "container": string(),
+                    "state": string(),
+                    "health": nullable(string()),
+                    "ports": array(string()),
+                    "image": string(),
+                    "image_digest": nullable(string()),
+                }),
+                &["service", "container", "state", "ports", "image"],
+            )),
+            "chains": array(object(
+                json!({
+                    "network_id": uint(),
+                    "name": string(),
+                    "rpc_url": string(),
+                    "head_block": nullable(uint()),
+                    "error": nullable(string()),
+                }),
+                &["network_id", "name", "rpc_url", "head_block"],
+            )),
+            "apis": array(object(
+                json!({
+                    "network_id": uint(),
+                    "url": string(),
+                    "reachable": boolean(),
+                    "error": nullable(string()),
+                }),
+                &["network_id", "url", "reachable"],
+            )),
+            "indexing": array(object(
+                json!({
+                    "network_id": uint(),
+                    "chain_head": uint(),
+                    "contract_deposit_count": uint(),
+                    "indexed_deposit_count": uint(),
+                    "last_indexed_block": nullable(uint()),
+                    "blocks_behind": uint(),
+                }),
+                &["network_id", "chain_head", "blocks_behind"],
+            )),
+        }),
+        &["running", "services", "chains", "apis", "indexing"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn doctor() -> Value {
+    document(
+        "Environment checklist",
+        json!({
+            "checks": array(object(
+                json!({
+                    "name": string(),
+                    "status": { "enum": ["pass", "warn", "fail"] },
+                    "detail": string(),
+                    "fix": string(),
+                }),
+                &["name", "status", "detail"],
+            )),
+            "passed": uint(),
+            "warnings": uint(),
+            "failed": uint(),
+        }),
+        &["checks", "passed", "warnings", "failed"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn upgrade() -> Value {
+    document(
+        "Sandbox image digests before and after the upgrade, and the CLI release",
+        json!({
+            "images": array(object(
+                json!({
+                    "image": string(),
+                    "before": nullable(string()),
+                    "after": nullable(string()),
+                    "status": {
+                        "enum": ["up_to_date", "updated", "update_available", "new", "unknown"]
+                    },
+                }),
+                &["image", "before", "after", "status"],
+            )),
+            "cli": nullable(object(
+                json!({
+                    "current": string(),
+                    "latest": string(),
+                    "update_available": boolean(),
+                    "installed": nullable(string()),
+                    "release_url": string(),
+                }),
+                &["current", "latest", "update_available", "installed", "release_url"],
+            )),
+        }),
+        &["images", "cli"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn info() -> Value {
+    document(
+        "Sandbox mode and the effective configuration",
+        json!({
+            "running": boolean(),
+            "fork_mode": boolean(),
+            "multi_l2": boolean(),
+            "api_base_url": string(),
+            "networks": { "type": "object" },
+            "accounts": { "type": "object" },
+            "contracts": { "type": "object" },
+        }),
+        &[
+            "running",
+            "fork_mode",
+            "multi_l2",
+            "api_base_url",
+            "networks",
+        ],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn report() -> Value {
+    document(
+        "Location of the written report and how many of its sections failed",
+        json!({
+            "path": string(),
+            "networks": uint(),
+            "services": uint(),
+            "failed_sections": uint(),
+        }),
+        &["path", "networks", "services", "failed_sections"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_bridges() -> Value {
+    one_of(
+        "Deposits of one network as returned by the bridge service (or the local index), or of several networks grouped by network",
+        vec![
+            document(
+                "Deposits of one network",
+                json!({ "bridges": array(json!({ "type": "object" })) }),
+                &["bridges"],
+            ),
+            grouped("Deposits of several networks, each entry holding the network's `bridges` or its `error`"),
+        ],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_claims() -> Value {
+    one_of(
+        "Claims of one network as returned by the bridge service (or the local index), or of several networks grouped by network",
+        vec![
+            document(
+                "Claims of one network",
+                json!({ "claims": array(json!({ "type": "object" })) }),
+                &["claims"],
+            ),
+            grouped("Claims of several networks, each entry holding the network's `claims` or its `error`"),
+        ],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_claim_proof() -> Value {
+    document(
+        "Merkle proofs of a deposit as returned by the bridge service",
+        json!({
+            "proof_local_exit_root": array(hash()),
+            "proof_rollup_exit_root": array(hash()),
+            "l1_info_tree_leaf": { "type": "object" },
+        }),
+        &[],
+    )
+}
+
+fn show_l1_info_tree_index() -> Value {
+    document(
+        "L1 info tree index of a deposit as returned by the bridge service",
+        json!({ "data": {} }),
+        &[],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn l1_info_leaf() -> Value {
+    json!({
+        "l1_info_tree_index": uint(),
+        "block_num": uint(),
+        "block_pos": uint(),
+        "previous_block_hash": nullable(hash()),
+        "timestamp": uint(),
+        "mainnet_exit_root": hash(),
+        "rollup_exit_root": hash(),
+        "global_exit_root": hash(),
+        "hash": nullable(hash()),
+    })
+}
+
+const L1_INFO_LEAF_REQUIRED: &[&str] = &[
+    "l1_info_tree_index",
+    "mainnet_exit_root",
+    "rollup_exit_root",
+    "global_exit_root",
+];
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_l1_info_tree() -> Value {
+    one_of(
+        "Recent leaves of the L1 info tree seen from a network, or the leaf requested with --leaf-index",
+        vec![
+            document(
+                "Recent leaves, newest first",
+                json!({
+                    "network_id": uint(),
+                    "leaf_count": uint(),
+                    "leaves": array(object(l1_info_leaf(), L1_INFO_LEAF_REQUIRED)),
+                    "missing": array(uint()),
+                }),
+                &["network_id", "leaf_count", "leaves", "missing"],
+            ),
+            document("A single leaf", l1_info_leaf(), L1_INFO_LEAF_REQUIRED),
+        ],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_exit_roots() -> Value {
+    list(
+        "Exit roots of L1 and the L2s",
+        object(
+            json!({
+                "network_id": uint(),
+                "manager": address(),
+                "mainnet_exit_root": nullable(hash()),
+                "rollup_exit_root": hash(),
+                "global_exit_root": nullable(hash()),
+                "injected_count": nullable(uint()),
+                "last_update": nullable(object(
+                    json!({
+                        "block_number": uint(),
+                        "timestamp": nullable(uint()),
+                        "tx_hash": nullable(hash()),
+                    }),
+                    &["block_number"],
+                )),
+                "l1_root_injected": nullable(boolean()),
+            }),
+            &["network_id", "manager", "rollup_exit_root"],
+        ),
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_rollups() -> Value {
+    document(
+        "Rollups registered in the L1 rollup manager",
+        json!({
+            "manager": address(),
+            "rollup_count": uint(),
+            "rollup_exit_root": hash(),
+            "global_rollup_exit_root": hash(),
+            "rollups": array(object(
+                json!({
+                    "rollup_id": uint(),
+                    "rollup_contract": address(),
+                    "chain_id": uint(),
+                    "verifier": address(),
+                    "verifier_type": string(),
+                    "fork_id": uint(),
+                    "rollup_type_id": uint(),
+                    "last_batch_sequenced": uint(),
+                    "last_verified_batch": uint(),
+                    "last_local_exit_root": hash(),
+                    "bridge_local_exit_root": nullable(hash()),
+                    "contribution": { "enum": ["none", "current", "behind", "unknown"] },
+                }),
+                &["rollup_id", "chain_id", "last_verified_batch", "last_local_exit_root", "contribution"],
+            )),
+        }),
+        &["manager", "rollup_count", "rollup_exit_root", "rollups"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_certificates() -> Value {
+    let certificate = nullable(object(
+        json!({
+            "certificate_id": hash(),
+            "height": uint(),
+            "epoch_number": nullable(uint()),
+            "certificate_index": nullable(uint()),
+            "prev_local_exit_root": nullable(hash()),
+            "new_local_exit_root": hash(),
+            "status": {
+                "enum": ["pending", "proven", "candidate", "in_error", "settled", "unknown"]
+            },
+            "error": nullable(string()),
+            "settlement_tx_hash": nullable(hash()),
+        }),
+        &["certificate_id", "height", "new_local_exit_root", "status"],
+    ));
+    list(
+        "Latest certificates the L2s submitted to the agglayer",
+        object(
+            json!({
+                "network_id": uint(),
+                "latest_known": certificate.clone(),
+                "latest_pending": certificate.clone(),
+                "latest_settled": certificate,
+                "bridge_local_exit_root": nullable(hash()),
+                "settlement": { "enum": ["none", "current", "behind", "unknown"] },
+            }),
+            &["network_id", "settlement"],
+        ),
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_config_endpoints() -> Value {
+    document(
+        "Endpoints and bridge contracts of every configured network",
+        json!({
+            "networks": array(object(
+                json!({
+                    "network_id": uint(),
+                    "name": string(),
+                    "chain_id": string(),
+                    "rpc_url": string(),
+                    "ws_url": string(),
+                    "bridge": nullable(address()),
+                    "bridge_extension": nullable(address()),
+                    "api_url": nullable(string()),
+                }),
+                &["network_id", "name", "chain_id", "rpc_url", "ws_url"],
+            )),
+        }),
+        &["networks"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_balances() -> Value {
+    document(
+        "ETH and token balances per account and network",
+        json!({
+            "tokens": array(object(
+                json!({
+                    "symbol": string(),
+                    "network_id": uint(),
+                    "address": address(),
+                    "decimals": uint(),
+                }),
+                &["symbol", "network_id", "address", "decimals"],
+            )),
+            "balances": array(object(
+                json!({
+                    "account": address(),
+                    "network_id": uint(),
+                    "eth": decimal(),
+                    "tokens": array(object(
+                        json!({
+                            "token": string(),
+                            "address": nullable(address()),
+                            "balance": nullable(decimal()),
+                        }),
+                        &["token", "address", "balance"],
+                    )),
+                }),
+                &["account", "network_id", "tokens"],
+            )),
+        }),
+        &["tokens", "balances"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_history() -> Value {
+    document(
+        "Deposits and claims involving an address, oldest first",
+        json!({
+            "address": address(),
+            "entries": array(object(
+                json!({
+                    "kind": { "enum": ["deposit", "claim"] },
+                    "network_id": uint(),
+                    "source_network": uint(),
+                    "destination_network": nullable(uint()),
+                    "deposit_count": uint(),
+                    "tx_hash": hash(),
+                    "from": nullable(address()),
+                    "to": address(),
+                    "origin_network": uint(),
+                    "origin_address": address(),
+                    "token": string(),
+                    "amount": decimal(),
+                    "status": { "enum": ["pending", "claimed", "completed"] },
+                    "block_num": nullable(uint()),
+                    "timestamp": nullable(uint()),
+                }),
+                &["kind", "network_id", "source_network", "deposit_count", "tx_hash", "to", "amount", "status"],
+            )),
+        }),
+        &["address", "entries"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn show_gas_report() -> Value {
+    document(
+        "Gas used by the bridge and claim operations of the session, per operation type",
+        json!({
+            "operations": array(object(
+                json!({
+                    "operation": string(),
+                    "runs": uint(),
+                    "transactions": uint(),
+                    "total_gas_used": decimal(),
+                    "average_gas_used": decimal(),
+                    "min_gas_used": decimal(),
+                    "max_gas_used": decimal(),
+                    "average_gas_price": decimal(),
+                    "total_cost_wei": decimal(),
+                }),
+                &["operation", "runs", "transactions", "total_gas_used", "total_cost_wei"],
+            )),
+        }),
+        &["operations"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn assertion() -> Value {
+    document(
+        "Outcome of an assertion",
+        json!({
+            "assertion": string(),
+            "passed": boolean(),
+            "description": string(),
+            "expected": string(),
+            "actual": string(),
+        }),
+        &["assertion", "passed", "expected", "actual"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn pending_deposit() -> Map<String, Value> {
+    let deposit = json!({
+        "source_network": uint(),
+        "deposit_count": uint(),
+        "leaf_type": uint(),
+        "tx_hash": string(),
+        "amount": string(),
+    });
+    deposit.as_object().cloned().unwrap_or_default()
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn bridge_claim_all() -> Value {
+    let mut outcome = pending_deposit();
+    outcome.insert("success".to_string(), boolean());
+    outcome.insert("error".to_string(), nullable(string()));
+    document(
+        "Result of claiming every pending deposit of a network",
+        json!({
+            "network": uint(),
+            "already_claimed": uint(),
+            "attempted": uint(),
+            "succeeded": uint(),
+            "failed": uint(),
+            "results": array(object(
+                Value::Object(outcome),
+                &["source_network", "deposit_count", "success"],
+            )),
+            "interrupted": boolean(),
+            "checkpoint": string(),
+            "runs": uint(),
+            "total_succeeded": uint(),
+            "total_failed": uint(),
+        }),
+        &["network", "attempted", "succeeded", "failed", "results"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn bridge_claim_budget() -> Value {
+    list(
+        "Claim gas spent per network against its budget",
+        object(
+            json!({
+                "network_id": uint(),
+                "sponsor": nullable(address()),
+                "claims_counted": uint(),
+                "spent_wei": quantity(),
+                "budget_wei": nullable(quantity()),
+                "status": object(
+                    json!({
+                        "state": { "enum": ["ok", "warning", "exceeded"] },
+                        "threshold": uint(),
+                    }),
+                    &["state"],
+                ),
+            }),
+            &["network_id", "claims_counted", "spent_wei", "status"],
+        ),
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn bridge_watch() -> Value {
+    let state = json!({ "enum": ["pending", "claimable", "claimed"] });
+    let mut transition = pending_deposit();
+    transition.insert("from".to_string(), nullable(state.clone()));
+    transition.insert("to".to_string(), state);
+    document(
+        "One NDJSON line per deposit state change",
+        Value::Object(transition),
+        &["source_network", "deposit_count", "to"],
+    )
+}
+
+fn bridge_asset() -> Value {
+    bridge_operation("Bridged assets", bridged(), &["tx_hash"])
+}
+
+fn bridge_claim() -> Value {
+    one_of(
+        "Claimed deposit, or the unsigned claim transaction with `--unsigned`",
+        vec![
+            stage_report(claimed(), &["tx_hash"]),
+            replayed_operation(claimed(), &["tx_hash"]),
+            dry_run(),
+            unsigned_claim(),
+        ],
+    )
+}
+
+fn bridge_claim_bundle() -> Value {
+    one_of(
+        "One claim document per claimed deposit, the asset deposit first",
+        vec![stage_report(claimed(), &["tx_hash"]), dry_run()],
+    )
+}
+
+fn bridge_message() -> Value {
+    bridge_operation("Bridged message", bridged(), &["tx_hash"])
+}
+
+fn bridge_and_call() -> Value {
+    bridge_operation(
+        "Bridged asset and message of a bridge-and-call",
+        bridged(),
+        &["tx_hash"],
+    )
+}
+
+fn bridge_bridge_message() -> Value {
+    let mut properties = bridged();
+    properties["deposit_count"] = nullable(uint());
+    bridge_operation("Bridged message", properties, &["tx_hash"])
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn bridged() -> Value {
+    json!({
+        "tx_hash": hash(),
+        "source_network": uint(),
+        "destination_network": uint(),
+    })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn claimed() -> Value {
+    json!({
+        "tx_hash": hash(),
+        "network": uint(),
+        "source_network": uint(),
+        "deposit_count": nullable(uint()),
+    })
+}
+
+/// Operation that sends funds, replayable under an idempotency key
+fn bridge_operation(description: &str, properties: Value, required: &[&str]) -> Value {
+    one_of(
+        description,
+        vec![
+            stage_report(properties.clone(), required),
+            replayed_operation(properties, required),
+            dry_run(),
+        ],
+    )
+}
+
+/// Finished operation with the time spent in each stage
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn stage_report(properties: Value, required: &[&str]) -> Value {
+    let mut all_properties = json!({
+        "operation": string(),
+        "timings": { "type": "object", "additionalProperties": uint() },
+        "gas": operation_gas(),
+    });
+    extend(&mut all_properties, properties);
+    let mut all_required = vec!["operation", "timings"];
+    all_required.extend_from_slice(required);
+    document(
+        "Finished operation with the time spent in each stage",
+        all_properties,
+        &all_required,
+    )
+}
+
+/// Result recorded under an idempotency key, printed instead of running again
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn replayed_operation(properties: Value, required: &[&str]) -> Value {
+    let mut all_properties = json!({
+        "operation": string(),
+        "gas": operation_gas(),
+        "idempotency_key": string(),
+        "replayed": { "const": true },
+        "completed_at": string(),
+    });
+    extend(&mut all_properties, properties);
+    let mut all_required = vec!["operation", "idempotency_key", "replayed", "completed_at"];
+    all_required.extend_from_slice(required);
+    document(
+        "Result recorded under the idempotency key, nothing was sent",
+        all_properties,
+        &all_required,
+    )
+}
+
+fn extend(target: &mut Value, properties: Value) {
+    if let (Some(target), Value::Object(properties)) = (target.as_object_mut(), properties) {
+        target.extend(properties);
+    }
+}
+
+/// Transaction simulated by `--dry-run`, one per transaction the operation would send
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn dry_run() -> Value {
+    document(
+        "Simulated transaction, not broadcast",
+        json!({
+            "dry_run": object(
+                json!({
+                    "function": string(),
+                    "from": nullable(address()),
+                    "to": nullable(address()),
+                    "value": quantity(),
+                    "calldata": bytes(),
+                    "arguments": array(object(
+                        json!({ "name": string(), "type": string(), "value": string() }),
+                        &["name", "type", "value"],
+                    )),
+                    "estimated_gas": nullable(quantity()),
+                    "revert": nullable(string()),
+                }),
+                &["function", "value", "calldata", "arguments"],
+            ),
+        }),
+        &["dry_run"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn unsigned_claim() -> Value {
+    document(
+        "Filled, unsigned claim transaction for an external signer",
+        json!({
+            "function": string(),
+            "network": uint(),
+            "chain_id": uint(),
+            "from": address(),
+            "to": address(),
+            "nonce": quantity(),
+            "gas": quantity(),
+            "value": quantity(),
+            "calldata": bytes(),
+            "raw_transaction": bytes(),
+            "signing_hash": hash(),
+        }),
+        &[
+            "function",
+            "network",
+            "chain_id",
+            "from",
+            "to",
+            "nonce",
+            "calldata",
+            "raw_transaction",
+            "signing_hash",
+        ],
+    )
+}
+
+/// Gas of the transactions an operation waited for
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn operation_gas() -> Value {
+    object(
+        json!({
+            "transactions": uint(),
+            "gas_used": decimal(),
+            "effective_gas_price": decimal(),
+            "cost_wei": decimal(),
+        }),
+        &[
+            "transactions",
+            "gas_used",
+            "effective_gas_price",
+            "cost_wei",
+        ],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_build_payload() -> Value {
+    document(
+        "Arguments of a claim transaction",
+        json!({
+            "smt_proof": array(hash()),
+            "smt_proof_rollup": nullable(array(hash())),
+            "global_index": decimal(),
+            "mainnet_exit_root": hash(),
+            "rollup_exit_root": hash(),
+            "origin_network": uint(),
+            "origin_token_address": string(),
+            "destination_network": uint(),
+            "destination_address": string(),
+            "amount": string(),
+            "metadata": string(),
+            "leaf_type": uint(),
+            "global_exit_root": hash(),
+            "proof_path": proof_path(),
+        }),
+        &[
+            "smt_proof",
+            "global_index",
+            "mainnet_exit_root",
+            "rollup_exit_root",
+            "origin_network",
+            "destination_network",
+            "amount",
+        ],
+    )
+}
+
+/// Nodes from a deposit leaf to the global exit root
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn proof_path() -> Value {
+    array(object(
+        json!({
+            "label": string(),
+            "via": nullable(string()),
+            "computed": hash(),
+            "expected": nullable(hash()),
+            "issue": nullable(string()),
+        }),
+        &["label", "computed"],
+    ))
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_compute_index() -> Value {
+    document(
+        "Global index of a deposit",
+        json!({
+            "local_index": uint(),
+            "source_network": uint(),
+            "global_index": decimal(),
+        }),
+        &["local_index", "source_network", "global_index"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_decode_index() -> Value {
+    document(
+        "Parts of a global index",
+        json!({
+            "global_index": decimal(),
+            "mainnet_flag": { "type": "boolean" },
+            "rollup_index": uint(),
+            "local_index": uint(),
+            "source_network": uint(),
+        }),
+        &[
+            "global_index",
+            "mainnet_flag",
+            "rollup_index",
+            "local_index",
+            "source_network",
+        ],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_get_mapped() -> Value {
+    document(
+        "Wrapped token of an origin token",
+        json!({
+            "origin_network": uint(),
+            "origin_token_address": string(),
+            "target_network": uint(),
+            "wrapped_token_address": address(),
+        }),
+        &["origin_network", "target_network", "wrapped_token_address"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_precalculate() -> Value {
+    document(
+        "Precalculated wrapped token address",
+        json!({
+            "origin_network": uint(),
+            "origin_token_address": string(),
+            "target_network": uint(),
+            "precalculated_address": address(),
+        }),
+        &["origin_network", "target_network", "precalculated_address"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_get_origin() -> Value {
+    document(
+        "Origin token of a wrapped token",
+        json!({ "origin_network": uint(), "origin_token_address": address() }),
+        &["origin_network", "origin_token_address"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_is_claimed() -> Value {
+    document(
+        "Whether a deposit has been claimed",
+        json!({
+            "network": uint(),
+            "bridge_index": uint(),
+            "source_network": uint(),
+            "is_claimed": boolean(),
+        }),
+        &["network", "bridge_index", "source_network", "is_claimed"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_network_id() -> Value {
+    document(
+        "Network ID reported by a bridge contract",
+        json!({ "network": uint(), "contract_network_id": uint() }),
+        &["network", "contract_network_id"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_check_metadata() -> Value {
+    document(
+        "Wrapped tokens whose metadata differs from their origin token",
+        json!({
+            "network": uint(),
+            "checked": uint(),
+            "out_of_sync": uint(),
+            "tokens": array(object(
+                json!({
+                    "origin_network": uint(),
+                    "origin_token_address": string(),
+                    "wrapped_token_address": string(),
+                    "origin": { "type": "object" },
+                    "wrapped": { "type": "object" },
+                    "issues": array(string()),
+                }),
+                &["origin_network", "wrapped_token_address", "issues"],
+            )),
+        }),
+        &["network", "checked", "out_of_sync", "tokens"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_verify_proof() -> Value {
+    document(
+        "Claim proof recomputed and checked against the on-chain exit roots",
+        json!({
+            "verified": boolean(),
+            "destination_network": uint(),
+            "global_exit_root": hash(),
+            "global_exit_root_on_chain": boolean(),
+            "l1_mainnet_exit_root": nullable(hash()),
+            "l1_rollup_exit_root": hash(),
+            "proof_path": proof_path(),
+        }),
+        &[
+            "verified",
+            "destination_network",
+            "global_exit_root",
+            "global_exit_root_on_chain",
+            "proof_path",
+        ],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn decoded_call() -> Value {
+    object(
+        json!({
+            "contract": string(),
+            "function": string(),
+            "selector": string(),
+            "arguments": { "type": "object", "additionalProperties": string() },
+            "nested": {
+                "type": "object",
+                "description": "Bytes arguments holding a call to a known function, decoded like the outer call",
+            },
+            "notes": array(string()),
+        }),
+        &[
+            "contract",
+            "function",
+            "selector",
+            "arguments",
+            "nested",
+            "notes",
+        ],
+    )
+}
+
+fn utils_decode_calldata() -> Value {
+    let call = decoded_call();
+    document(
+        "Calldata decoded against the bridge, bridge extension and ERC20 functions",
+        call["properties"].clone(),
+        &[
+            "contract",
+            "function",
+            "selector",
+            "arguments",
+            "nested",
+            "notes",
+        ],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn utils_encode_call() -> Value {
+    document(
+        "Calldata ABI-encoded from a function signature and its arguments",
+        json!({
+            "function": string(),
+            "selector": string(),
+            "calldata": string(),
+        }),
+        &["function", "selector", "calldata"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn fund() -> Value {
+    document(
+        "Credited ETH balance and minted tokens",
+        json!({
+            "network_id": uint(),
+            "address": address(),
+            "eth_balance_wei": decimal(),
+            "token": address(),
+            "token_amount": decimal(),
+            "token_tx_hash": hash(),
+        }),
+        &["network_id", "address"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn deployed_token() -> Map<String, Value> {
+    let token = json!({
+        "network_id": uint(),
+        "address": address(),
+        "name": string(),
+        "symbol": string(),
+        "decimals": uint(),
+        "owner": address(),
+        "tx_hash": hash(),
+        "deployed_at": uint(),
+    });
+    token.as_object().cloned().unwrap_or_default()
+}
+
+const DEPLOYED_TOKEN_REQUIRED: [&str; 5] = ["network_id", "address", "name", "symbol", "decimals"];
+
+fn token_deploy() -> Value {
+    let mut token = deployed_token();
+    token.insert("initial_supply".to_string(), decimal());
+    token.insert("bridge_commands".to_string(), array(string()));
+    document(
+        "Deployed test token and the commands bridging it",
+        Value::Object(token),
+        &DEPLOYED_TOKEN_REQUIRED,
+    )
+}
+
+fn token_list() -> Value {
+    list(
+        "Test tokens deployed from this directory",
+        object(Value::Object(deployed_token()), &DEPLOYED_TOKEN_REQUIRED),
+    )
+}
+
+/// Name, symbol and decimals of a token, null when unreadable
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn token_metadata() -> Value {
+    object(
+        json!({
+            "name": nullable(string()),
+            "symbol": nullable(string()),
+            "decimals": nullable(uint()),
+        }),
+        &["name", "symbol", "decimals"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn token_info() -> Value {
+    document(
+        "Token metadata on every network holding the token, with its wrapped counterparts",
+        json!({
+            "tokens": array(object(
+                json!({
+                    "network": uint(),
+                    "address": address(),
+                    "metadata": token_metadata(),
+                    "cached": boolean(),
+                    "wrapped": boolean(),
+                    "origin_network": uint(),
+                    "origin_address": address(),
+                    "counterparts": array(object(
+                        json!({
+                            "network": uint(),
+                            "address": address(),
+                            "metadata": token_metadata(),
+                        }),
+                        &["network", "address", "metadata"],
+                    )),
+                }),
+                &["network", "address", "metadata", "cached", "wrapped", "origin_network", "origin_address", "counterparts"],
+            )),
+        }),
+        &["tokens"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn chain_control() -> Value {
+    document(
+        "Chain heads after a chain control command",
+        json!({
+            "action": string(),
+            "networks": array(object(
+                json!({ "network_id": uint(), "block_number": uint(), "timestamp": uint() }),
+                &["network_id", "block_number", "timestamp"],
+            )),
+        }),
+        &["action", "networks"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn tx_replacement() -> Value {
+    document(
+        "Replacement sent for a stuck transaction",
+        json!({
+            "action": { "enum": ["bump", "cancel"] },
+            "network_id": uint(),
+            "from": address(),
+            "nonce": quantity(),
+            "replaced_tx_hash": nullable(hash()),
+            "tx_hash": nullable(hash()),
+            "gas_price": nullable(quantity()),
+            "max_fee_per_gas": nullable(quantity()),
+            "max_priority_fee_per_gas": nullable(quantity()),
+        }),
+        &["action", "network_id", "from", "nonce", "tx_hash"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn config_show() -> Value {
+    document(
+        "Effective configuration with the source of every value",
+        json!({
+            "config_file": nullable(string()),
+            "settings": array(object(
+                json!({
+                    "key": string(),
+                    "value": {},
+                    "source": { "enum": ["file", "env", "cache", "default"] },
+                    "variable": string(),
+                }),
+                &["key", "value", "source"],
+            )),
+        }),
+        &["config_file", "settings"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn config_validate() -> Value {
+    document(
+        "Validated configuration",
+        json!({
+            "config_file": nullable(string()),
+            "valid": { "const": true },
+            "networks": array(uint()),
+            "contracts": uint(),
+        }),
+        &["config_file", "valid", "networks", "contracts"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn config_init() -> Value {
+    document(
+        "Written config file",
+        json!({ "config_file": string() }),
+        &["config_file"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn config_set() -> Value {
+    document(
+        "Changed configuration key",
+        json!({
+            "config_file": string(),
+            "key": string(),
+            "previous": {},
+            "value": {},
+        }),
+        &["config_file", "key", "previous", "value"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn config_import() -> Value {
+    document(
+        "Written config file and the imported networks and contracts",
+        json!({ "config_file": string(), "mapping": { "type": "object" } }),
+        &["config_file", "mapping"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn contracts_report() -> Value {
+    list(
+        "Code, proxy and ownership details of the deployed contracts",
+        object(
+            json!({
+                "network_id": uint(),
+                "name": string(),
+                "address": address(),
+                "code_size": uint(),
+                "implementation": nullable(address()),
+                "implementation_code_size": nullable(uint()),
+                "proxy_admin": nullable(address()),
+                "beacon": nullable(address()),
+                "owner": nullable(address()),
+                "issues": array(string()),
+            }),
+            &["network_id", "name", "address", "code_size", "issues"],
+        ),
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn contracts_list() -> Value {
+    document(
+        "Contract addresses and whether they are configured or discovered",
+        json!({
+            "contracts": array(object(
+                json!({
+                    "network_id": uint(),
+                    "name": string(),
+                    "address": address(),
+                    "source": { "enum": ["configured", "discovered"] },
+                }),
+                &["network_id", "name", "address", "source"],
+            )),
+            "cache": nullable(object(
+                json!({
+                    "path": string(),
+                    "discovered_at": nullable(string()),
+                    "sources": array(string()),
+                }),
+                &["path", "sources"],
+            )),
+        }),
+        &["contracts", "cache"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn contracts_refresh() -> Value {
+    document(
+        "Result of discovering the deployed contracts",
+        json!({
+            "path": string(),
+            "sources": array(string()),
+            "contracts": uint(),
+            "filled": uint(),
+            "failures": array(string()),
+        }),
+        &["path", "sources", "contracts", "filled", "failures"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn snapshot_metadata() -> Value {
+    object(
+        json!({
+            "name": string(),
+            "description": string(),
+            "created_at": uint(),
+            "networks": array(object(
+                json!({
+                    "network_id": uint(),
+                    "chain_id": uint(),
+                    "block_number": uint(),
+                    "deposit_count": nullable(uint()),
+                    "evm_snapshot_id": nullable(string()),
+                    "state_file": string(),
+                }),
+                &["network_id", "chain_id", "block_number", "state_file"],
+            )),
+            "contracts": { "type": "object" },
+        }),
+        &["name", "created_at", "networks", "contracts"],
+    )
+}
+
+fn snapshot_save() -> Value {
+    let metadata = snapshot_metadata();
+    document(
+        "Metadata of the saved snapshot",
+        metadata["properties"].clone(),
+        &["name", "created_at", "networks", "contracts"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn snapshot_restore() -> Value {
+    document(
+        "Restored snapshot and the differences found while restoring it",
+        json!({ "name": string(), "restored": boolean(), "warnings": array(string()) }),
+        &["name", "restored", "warnings"],
+    )
+}
+
+fn snapshot_list() -> Value {
+    list("Saved snapshots", snapshot_metadata())
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn index_sync() -> Value {
+    document(
+        "One document per synced network and sync round",
+        json!({
+            "network_id": uint(),
+            "from_block": uint(),
+            "to_block": uint(),
+            "new_deposits": uint(),
+            "new_claims": uint(),
+            "new_exit_roots": uint(),
+            "rebuilt": boolean(),
+            "pruned": uint(),
+        }),
+        &["network_id", "from_block", "to_block", "rebuilt"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn index_status() -> Value {
+    document(
+        "Contents of the local bridge index per network",
+        json!({
+            "networks": array(object(
+                json!({
+                    "network_id": uint(),
+                    "last_block": nullable(uint()),
+                    "deposits": uint(),
+                    "claims": uint(),
+                    "exit_roots": uint(),
+                }),
+                &["network_id", "last_block"],
+            )),
+        }),
+        &["networks"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn state_prune() -> Value {
+    document(
+        "Records pruned from the local bridge index",
+        json!({
+            "older_than_secs": uint(),
+            "archived": boolean(),
+            "networks": array(object(
+                json!({
+                    "network_id": uint(),
+                    "deposits": uint(),
+                    "claims": uint(),
+                    "exit_roots": uint(),
+                }),
+                &["network_id", "deposits", "claims", "exit_roots"],
+            )),
+        }),
+        &["older_than_secs", "archived", "networks"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn cache_clear() -> Value {
+    document(
+        "Cached bridge service responses that were dropped",
+        json!({
+            "path": string(),
+            "cleared": uint(),
+        }),
+        &["path", "cleared"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn index_reset() -> Value {
+    document(
+        "Networks whose local index was deleted",
+        json!({ "removed": array(uint()) }),
+        &["removed"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn registry_address() -> Value {
+    document(
+        "Registry address and whether it is deployed on each network",
+        json!({
+            "address": address(),
+            "deployed": { "type": "object", "additionalProperties": boolean() },
+        }),
+        &["address", "deployed"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn registry_list() -> Value {
+    document(
+        "Registry entries of a network",
+        json!({
+            "network_id": uint(),
+            "entries": { "type": "object", "additionalProperties": address() },
+        }),
+        &["network_id", "entries"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn registry_get() -> Value {
+    document(
+        "Address registered under a name",
+        json!({ "network_id": uint(), "name": string(), "address": address() }),
+        &["network_id", "name", "address"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn registry_set() -> Value {
+    document(
+        "Registry entry written",
+        json!({
+            "network_id": uint(),
+            "name": string(),
+            "address": address(),
+            "tx_hash": hash(),
+        }),
+        &["network_id", "name", "address", "tx_hash"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn registry_publish() -> Value {
+    document(
+        "Registry entries updated per network",
+        json!({
+            "networks": array(object(
+                json!({ "network_id": uint(), "address": address(), "updated": array(string()) }),
+                &["network_id", "address", "updated"],
+            )),
+        }),
+        &["networks"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn event() -> Value {
+    object(
+        json!({
+            "block_number": nullable(uint()),
+            "transaction_hash": nullable(hash()),
+            "log_index": nullable(uint()),
+            "contract": address(),
+            "implementation": nullable(address()),
+            "implementation_name": nullable(string()),
+            "event": nullable(string()),
+            "signature": nullable(hash()),
+            "decoded": nullable(json!({ "type": "object" })),
+            "topics": array(hash()),
+            "data": string(),
+            "chain": string(),
+            "network_id": uint(),
+            "timestamp": nullable(uint()),
+        }),
+        &["contract", "topics", "data"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn events_document() -> Value {
+    object(
+        json!({
+            "chain": string(),
+            "from_block": uint(),
+            "to_block": uint(),
+            "address": nullable(string()),
+            "count": uint(),
+            "events": array(event()),
+            "network_id": uint(),
+        }),
+        &["chain", "from_block", "to_block", "count", "events"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn chain_error() -> Value {
+    object(
+        json!({ "network_id": uint(), "chain": string(), "error": string() }),
+        &["chain", "error"],
+    )
+}
+
+/// The raw JSON-RPC result, the one document printed without a `schema_version`
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn rpc() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "description": "Result of the JSON-RPC call, printed unchanged and without schema_version",
+    })
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn events() -> Value {
+    let single = events_document();
+    let mut event_line = event();
+    event_line["properties"]["schema_version"] = json!({ "const": SCHEMA_VERSION });
+    one_of(
+        "Events of one chain, of several chains grouped under `networks` or interleaved with --interleave, or with --format json one NDJSON line per event",
+        vec![
+            document(
+                "Events of one chain",
+                single["properties"].clone(),
+                &["chain", "from_block", "to_block", "count", "events"],
+            ),
+            document(
+                "Events of several chains, each entry holding a chain's events or its `error`",
+                json!({
+                    "networks": array(json!({
+                        "anyOf": [
+                            single,
+                            chain_error(),
+                        ],
+                    })),
+                }),
+                &["networks"],
+            ),
+            document(
+                "Events of several chains ordered by block timestamp (--interleave)",
+                json!({
+                    "networks": array(json!({
+                        "anyOf": [
+                            object(
+                                json!({
+                                    "network_id": uint(),
+                                    "chain": string(),
+                                    "from_block": uint(),
+                                    "to_block": uint(),
+                                    "count": uint(),
+                                }),
+                                &["network_id", "chain", "from_block", "to_block", "count"],
+                            ),
+                            chain_error(),
+                        ],
+                    })),
+                    "count": uint(),
+                    "events": array(event()),
+                }),
+                &["networks", "count", "events"],
+            ),
+            document(
+                "One event (NDJSON line)",
+                event_line["properties"].clone(),
+                &["contract", "topics", "data"],
+            ),
+        ],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn test_e2e() -> Value {
+    document(
+        "Outcome of each step of the end-to-end test",
+        json!({
+            "suite": string(),
+            "network_id": uint(),
+            "passed": boolean(),
+            "steps": array(object(
+                json!({
+                    "step": string(),
+                    "passed": boolean(),
+                    "duration_ms": uint(),
+                    "error": string(),
+                }),
+                &["step", "passed", "duration_ms"],
+            )),
+        }),
+        &["suite", "network_id", "passed", "steps"],
+    )
+}
+
+#[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+fn monitor_slo() -> Value {
+    document(
+        "One NDJSON line per canary round",
+        json!({
+            "timestamp": string(),
+            "source_network": uint(),
+            "network": uint(),
+            "latency_ms": nullable(uint()),
+            "threshold_ms": uint(),
+            "error": string(),
+        }),
+        &[
+            "timestamp",
+            "source_network",
+            "network",
+            "latency_ms",
+            "threshold_ms",
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+    fn test_versioned_object_and_array() {
+        assert_eq!(
+            versioned(json!({ "running": true })),
+            json!({ "running": true, "schema_version": SCHEMA_VERSION })
+        );
+        assert_eq!(
+            versioned(json!([1, 2])),
+            json!({ "schema_version": SCHEMA_VERSION, "data": [1, 2] })
+        );
+    }
+
+    #[test]
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
+    fn test_every_schema_requires_the_version() {
+        for command in commands().filter(|command| *command != "rpc") {
+            let schema = schema_for(command).unwrap();
+            assert_eq!(
+                schema["title"],
+                format!("aggsandbox {command} --output json")
+            );
+            let documents = match schema["oneOf"].as_array() {
+                Some(variants) => variants.clone(),
+                None => vec![schema],
+            };
+            for document in documents {
+                assert_eq!(document["type"], "object", "{command}");
+                assert_eq!(document["required"][0], "schema_version", "{command}");
+                assert_eq!(
+                    document["properties"]["schema_version"]["const"],
+                    SCHEMA_VERSION
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_schema_for_unknown_command() {
+        assert!(schema_for("show").is_none());
+        assert!(schema_for("logs").is_none());
+    }
+}
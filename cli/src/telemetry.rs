@@ -0,0 +1,167 @@
+//! OpenTelemetry trace export
+//!
+//! With `--otlp-endpoint` (or `OTEL_EXPORTER_OTLP_ENDPOINT`) the spans of a
+//! command are exported to an OTLP/HTTP collector: one root span per command,
+//! bridge service requests, the stages of bridge and claim operations, and a
+//! span per JSON-RPC request sent through ethers. Bridge service requests
+//! carry a W3C `traceparent` header, so when aggkit exports its traces to the
+//! same collector both sides of a slow claim show up in one trace.
+
+/// Environment variable naming the collector when `--otlp-endpoint` is not given
+pub const OTLP_ENDPOINT_ENV: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Service name the spans are exported under
+pub const SERVICE_NAME: &str = "aggsandbox";
+
+/// Traces URL of a collector, appending `/v1/traces` to a base URL
+pub fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}/v1/traces")
+    }
+}
+
+#[cfg(feature = "otel")]
+mod otlp {
+    use super::{traces_url, SERVICE_NAME};
+    use opentelemetry::propagation::Injector;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use opentelemetry_sdk::{runtime, Resource};
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+    use std::sync::OnceLock;
+    use tracing::Level;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+    use tracing_subscriber::filter::filter_fn;
+    use tracing_subscriber::{Layer, Registry};
+
+    /// Provider of the exporting tracer, set once the layer is built
+    static PROVIDER: OnceLock<TracerProvider> = OnceLock::new();
+
+    /// Layer exporting spans to the collector at `endpoint`
+    pub fn otlp_layer(
+        endpoint: &str,
+    ) -> std::result::Result<Box<dyn Layer<Registry> + Send + Sync>, String> {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(traces_url(endpoint))
+            .build()
+            .map_err(|e| format!("OTLP exporter for {endpoint}: {e}"))?;
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_resource(Resource::new(vec![KeyValue::new(
+                "service.name",
+                SERVICE_NAME,
+            )]))
+            .build();
+        let tracer = provider.tracer(SERVICE_NAME);
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let _ = PROVIDER.set(provider);
+
+        let filter = filter_fn(|metadata| {
+            let target = metadata.target();
+            if target.starts_with("ethers_providers") {
+                // The `rpc` span of every request, without the payloads it logs
+                metadata.is_span()
+            } else {
+                target.starts_with("aggsandbox") && *metadata.level() <= Level::DEBUG
+            }
+        });
+        Ok(tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(filter)
+            .boxed())
+    }
+
+    /// `traceparent` header of the current span, empty when nothing is exported
+    pub fn trace_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if PROVIDER.get().is_none() {
+            return headers;
+        }
+        let context = tracing::Span::current().context();
+        opentelemetry::global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&context, &mut HeaderInjector(&mut headers));
+        });
+        headers
+    }
+
+    struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+    impl Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+
+    /// Export the spans still buffered before the process exits
+    pub async fn shutdown() {
+        let Some(provider) = PROVIDER.get() else {
+            return;
+        };
+        // Shutting down waits for the batch exporter task on the runtime
+        let provider = provider.clone();
+        let _ = tokio::task::spawn_blocking(move || provider.shutdown()).await;
+    }
+}
+
+#[cfg(feature = "otel")]
+pub use otlp::{otlp_layer, shutdown, trace_headers};
+
+/// Without the `otel` feature no header is added
+#[cfg(not(feature = "otel"))]
+pub fn trace_headers() -> reqwest::header::HeaderMap {
+    reqwest::header::HeaderMap::new()
+}
+
+#[cfg(not(feature = "otel"))]
+pub async fn shutdown() {}
+
+/// Without the `otel` feature there is no exporter to send spans to
+#[cfg(not(feature = "otel"))]
+pub fn otlp_layer(
+    endpoint: &str,
+) -> std::result::Result<
+    Box<dyn tracing_subscriber::Layer<tracing_subscriber::Registry> + Send + Sync>,
+    String,
+> {
+    Err(format!(
+        "cannot export traces to {endpoint}: built without the `otel` feature"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_traces_url() {
+        assert_eq!(
+            traces_url("http://localhost:4318"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_url("http://localhost:4318/"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_url("https://collector.example.com/v1/traces"),
+            "https://collector.example.com/v1/traces"
+        );
+    }
+
+    #[test]
+    fn test_no_trace_headers_without_exporter() {
+        assert!(trace_headers().is_empty());
+    }
+}
@@ -3,8 +3,10 @@
 //! This module provides newtype wrappers around primitive types to prevent
 //! type confusion and enable compile-time safety for domain-specific values.
 
-use crate::error::{ConfigError, Result};
-use serde::{Deserialize, Serialize};
+use crate::error::{ApiError, ConfigError, Result};
+use ethers::types::{Address, H256, U256};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use std::fmt;
 use std::str::FromStr;
 
@@ -63,6 +65,12 @@ impl FromStr for ChainId {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct NetworkId(u64);
 
+/// Highest Agglayer network ID accepted for an L2
+///
+/// Well above the number of L2s a sandbox runs, while still rejecting chain
+/// IDs such as 137 or 1101 passed where a network ID is expected.
+pub const MAX_NETWORK_ID: u64 = 64;
+
 impl NetworkId {
     /// Create a new NetworkId with validation
     pub fn new(id: u64) -> Result<Self> {
@@ -72,8 +80,8 @@ impl NetworkId {
         // 2 = Second L2 (if multi-L2 setup)
         // 3+ = Additional L2 chains
         let valid_ranges = [
-            (0, 3),         // Agglayer network IDs: 0 (L1), 1-3 (L2 chains)
-            (31337, 31339), // Local development networks (for testing)
+            (0, MAX_NETWORK_ID), // Agglayer network IDs: 0 (L1), 1+ (L2 chains)
+            (31337, 31339),      // Local development networks (for testing)
         ];
 
         let is_valid = valid_ranges
@@ -86,7 +94,7 @@ impl NetworkId {
             Err(ConfigError::invalid_value(
                 "network_id",
                 &id.to_string(),
-                "Must be one of: 0 (Ethereum L1), 1-3 (L2 chains), or 31337-31339 (Local development)",
+                "Must be one of: 0 (Ethereum L1), 1-64 (L2 chains), or 31337-31339 (Local development)",
             )
             .into())
         }
@@ -229,6 +237,56 @@ impl FromStr for RpcUrl {
     }
 }
 
+/// Type-safe wrapper for WebSocket RPC URLs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WsUrl(String);
+
+impl WsUrl {
+    /// Create a new WsUrl with validation
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        let url_str = url.into();
+
+        if url_str.trim().is_empty() {
+            return Err(ConfigError::invalid_value("ws_url", &url_str, "cannot be empty").into());
+        }
+
+        // Must start with ws:// or wss://
+        if !url_str.starts_with("ws://") && !url_str.starts_with("wss://") {
+            return Err(ConfigError::invalid_value(
+                "ws_url",
+                &url_str,
+                "must start with ws:// or wss://",
+            )
+            .into());
+        }
+
+        url::Url::parse(&url_str).map_err(|e| {
+            ConfigError::invalid_value("ws_url", &url_str, &format!("invalid URL format: {e}"))
+        })?;
+
+        Ok(WsUrl(url_str))
+    }
+
+    /// Get the inner string value
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for WsUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for WsUrl {
+    type Err = crate::error::AggSandboxError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        WsUrl::new(s)
+    }
+}
+
 /// Type-safe wrapper for contract names
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ContractName(String);
@@ -303,7 +361,188 @@ impl AsRef<str> for ContractName {
     }
 }
 
+/// Deposit listed by the bridge service's `bridges` endpoint
+///
+/// The local index and the chain fallback list deposits in the same layout.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BridgeDeposit {
+    pub bridge_tx_hash: H256,
+    pub deposit_count: u64,
+    /// 0 for asset bridges, 1 for messages
+    #[serde(default)]
+    pub leaf_type: u8,
+    #[serde(default)]
+    pub origin_network: Option<u32>,
+    pub origin_address: Address,
+    /// Destination as reported by the service, which some releases get wrong
+    #[serde(default)]
+    pub destination_network: Option<u32>,
+    pub destination_address: Address,
+    #[serde(deserialize_with = "deserialize_amount")]
+    pub amount: U256,
+    /// Token metadata or message payload, empty when the service omits it
+    #[serde(default, deserialize_with = "deserialize_hex_bytes")]
+    pub metadata: Vec<u8>,
+    #[serde(default)]
+    pub block_num: Option<u64>,
+    /// Sender of the bridge transaction, only listed by the bridge service
+    #[serde(default)]
+    pub from_address: Option<Address>,
+}
+
+impl BridgeDeposit {
+    pub fn from_value(value: &Value) -> Result<Self> {
+        Self::deserialize(value).map_err(|e| {
+            ApiError::response_validation_failed(&format!(
+                "invalid bridge deposit (deposit_count {}): {e}",
+                value["deposit_count"]
+            ))
+            .into()
+        })
+    }
+
+    /// Deposits of a `bridges` response
+    pub fn list_from(response: &Value) -> Result<Vec<Self>> {
+        response["bridges"]
+            .as_array()
+            .ok_or_else(|| {
+                ApiError::response_validation_failed("bridges response has no bridges array")
+            })?
+            .iter()
+            .map(Self::from_value)
+            .collect()
+    }
+
+    pub fn is_asset(&self) -> bool {
+        self.leaf_type == 0
+    }
+}
+
+/// Leaf of the L1 info tree a claim proof is rooted in
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct L1InfoTreeLeaf {
+    pub mainnet_exit_root: H256,
+    pub rollup_exit_root: H256,
+    #[serde(default)]
+    global_exit_root: Option<H256>,
+    /// Older releases nest the global exit root in `inner`
+    #[serde(default)]
+    inner: Option<L1InfoTreeLeafInner>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+struct L1InfoTreeLeafInner {
+    #[serde(default)]
+    global_exit_root: Option<H256>,
+}
+
+impl L1InfoTreeLeaf {
+    pub fn global_exit_root(&self) -> Option<H256> {
+        self.global_exit_root
+            .or_else(|| self.inner.as_ref()?.global_exit_root)
+    }
+}
+
+/// Claim proof in the canonical layout of `api_client::normalize_claim_proof`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ClaimProofResponse {
+    pub smt_proof: Vec<H256>,
+    /// Only present for deposits of rollups
+    #[serde(default)]
+    pub smt_proof_rollup: Option<Vec<H256>>,
+    pub l1_info_tree_leaf: L1InfoTreeLeaf,
+}
+
+impl ClaimProofResponse {
+    pub fn from_value(value: &Value) -> Result<Self> {
+        Self::deserialize(value).map_err(|e| {
+            ApiError::response_validation_failed(&format!("invalid claim proof: {e}")).into()
+        })
+    }
+}
+
+/// Response of the `l1-info-tree-index` endpoint, a bare index or an object wrapping it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum L1InfoTreeIndex {
+    Bare(u64),
+    Wrapped { l1_info_tree_index: u64 },
+}
+
+impl L1InfoTreeIndex {
+    pub fn from_value(value: &Value) -> Result<u64> {
+        let index = Self::deserialize(value).map_err(|_| {
+            ApiError::response_validation_failed(&format!(
+                "invalid L1 info tree index response: {value}"
+            ))
+        })?;
+        Ok(match index {
+            L1InfoTreeIndex::Bare(index)
+            | L1InfoTreeIndex::Wrapped {
+                l1_info_tree_index: index,
+            } => index,
+        })
+    }
+}
+
+/// Leaf of the L1 info tree as returned by the `injected-l1-info-leaf` endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L1InfoLeaf {
+    pub l1_info_tree_index: u32,
+    /// L1 block of the `UpdateL1InfoTree` event that added the leaf
+    #[serde(default)]
+    pub block_num: u64,
+    #[serde(default)]
+    pub block_pos: u64,
+    #[serde(default)]
+    pub previous_block_hash: Option<H256>,
+    #[serde(default)]
+    pub timestamp: u64,
+    pub mainnet_exit_root: H256,
+    pub rollup_exit_root: H256,
+    pub global_exit_root: H256,
+    /// Leaf hash, missing in older releases
+    #[serde(default)]
+    pub hash: Option<H256>,
+}
+
+impl L1InfoLeaf {
+    pub fn from_value(value: &Value) -> Result<Self> {
+        Self::deserialize(value).map_err(|e| {
+            ApiError::response_validation_failed(&format!("invalid L1 info tree leaf: {e}")).into()
+        })
+    }
+}
+
+/// Amount as a decimal string, a `0x` hex string or a JSON number
+fn deserialize_amount<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<U256, D::Error> {
+    use serde::de::Error;
+    match Value::deserialize(deserializer)? {
+        Value::String(amount) => match amount.strip_prefix("0x") {
+            Some(hex) => U256::from_str_radix(hex, 16).map_err(D::Error::custom),
+            None => U256::from_dec_str(&amount).map_err(D::Error::custom),
+        },
+        Value::Number(amount) => amount
+            .as_u64()
+            .map(U256::from)
+            .ok_or_else(|| D::Error::custom(format!("invalid amount {amount}"))),
+        other => Err(D::Error::custom(format!("invalid amount {other}"))),
+    }
+}
+
+/// Hex bytes with or without `0x`, `null` and `""` being empty
+fn deserialize_hex_bytes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<u8>, D::Error> {
+    use serde::de::Error;
+    let hex = Option::<String>::deserialize(deserializer)?.unwrap_or_default();
+    hex::decode(hex.trim_start_matches("0x")).map_err(D::Error::custom)
+}
+
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod tests {
     use super::*;
 
@@ -341,7 +580,8 @@ mod tests {
 
     #[test]
     fn test_network_id_invalid() {
-        assert!(NetworkId::new(4).is_err()); // Beyond L2 range
+        assert!(NetworkId::new(4).is_ok()); // Extra L2
+        assert!(NetworkId::new(MAX_NETWORK_ID + 1).is_err()); // Beyond L2 range
         assert!(NetworkId::new(137).is_err()); // Old chain ID format
         assert!(NetworkId::new(1101).is_err()); // Old chain ID format
         assert!(NetworkId::new(999).is_err());
@@ -380,6 +620,17 @@ mod tests {
         assert!(RpcUrl::new("http://").is_err());
     }
 
+    #[test]
+    fn test_ws_url() {
+        assert_eq!(
+            WsUrl::new("ws://localhost:8545").unwrap().as_str(),
+            "ws://localhost:8545"
+        );
+        assert!(WsUrl::new("wss://rpc.example.org/ws").is_ok());
+        assert!(WsUrl::new("http://localhost:8545").is_err());
+        assert!(WsUrl::new("").is_err());
+    }
+
     #[test]
     fn test_contract_name_valid() {
         let name = ContractName::new("PolygonZkEVM").unwrap();
@@ -406,4 +657,111 @@ mod tests {
         let deserialized: ChainId = serde_json::from_str(&json).unwrap();
         assert_eq!(deserialized, chain_id);
     }
+
+    #[test]
+    fn test_bridge_deposit_from_api_response() {
+        let response = serde_json::json!({
+            "bridges": [{
+                "bridge_tx_hash": "0x3a1e0a3ba6d4cbb7b5e5a15f3e3a3c7d1bc1a2d0f9e1e8d7c6b5a4f3e2d1c0b9",
+                "deposit_count": 7,
+                "leaf_type": 0,
+                "origin_network": 0,
+                "origin_address": "0x0000000000000000000000000000000000000000",
+                "destination_network": 1,
+                "destination_address": "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+                "amount": "1000000000000000000",
+                "metadata": "0x",
+                "block_num": 42,
+            }]
+        });
+
+        let deposits = BridgeDeposit::list_from(&response).unwrap();
+        assert_eq!(deposits.len(), 1);
+        let deposit = &deposits[0];
+        assert_eq!(deposit.deposit_count, 7);
+        assert!(deposit.is_asset());
+        assert_eq!(deposit.destination_network, Some(1));
+        assert_eq!(deposit.amount, U256::exp10(18));
+        assert!(deposit.metadata.is_empty());
+    }
+
+    #[test]
+    fn test_bridge_deposit_validation_errors() {
+        let mut deposit = serde_json::json!({
+            "bridge_tx_hash": "0x3a1e0a3ba6d4cbb7b5e5a15f3e3a3c7d1bc1a2d0f9e1e8d7c6b5a4f3e2d1c0b9",
+            "deposit_count": 7,
+            "origin_address": "0x0000000000000000000000000000000000000000",
+            "destination_address": "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+            "amount": "12abc",
+        });
+        let message = BridgeDeposit::from_value(&deposit).unwrap_err().to_string();
+        assert!(message.contains("deposit_count 7"));
+
+        deposit["amount"] = serde_json::json!("0x10");
+        assert_eq!(
+            BridgeDeposit::from_value(&deposit).unwrap().amount,
+            U256::from(16)
+        );
+        deposit
+            .as_object_mut()
+            .unwrap()
+            .remove("destination_address");
+        assert!(BridgeDeposit::from_value(&deposit).is_err());
+        assert!(BridgeDeposit::list_from(&serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn test_claim_proof_and_tree_index() {
+        let root = |byte: &str| format!("0x{}", byte.repeat(32));
+        let proof = serde_json::json!({
+            "smt_proof": [root("01"), root("02")],
+            "l1_info_tree_leaf": {
+                "mainnet_exit_root": root("aa"),
+                "rollup_exit_root": root("bb"),
+                "inner": { "global_exit_root": root("cc") },
+            },
+        });
+
+        let proof = ClaimProofResponse::from_value(&proof).unwrap();
+        assert_eq!(proof.smt_proof.len(), 2);
+        assert_eq!(proof.smt_proof_rollup, None);
+        assert_eq!(
+            proof.l1_info_tree_leaf.global_exit_root(),
+            Some(H256::repeat_byte(0xcc))
+        );
+        assert!(ClaimProofResponse::from_value(&serde_json::json!({ "smt_proof": [] })).is_err());
+
+        assert_eq!(
+            L1InfoTreeIndex::from_value(&serde_json::json!(5)).unwrap(),
+            5
+        );
+        assert_eq!(
+            L1InfoTreeIndex::from_value(&serde_json::json!({ "l1_info_tree_index": 6 })).unwrap(),
+            6
+        );
+        assert!(L1InfoTreeIndex::from_value(&serde_json::json!({ "index": 6 })).is_err());
+    }
+
+    #[test]
+    fn test_l1_info_leaf() {
+        let root = |byte: &str| format!("0x{}", byte.repeat(32));
+        let leaf = serde_json::json!({
+            "block_num": 120,
+            "block_pos": 1,
+            "l1_info_tree_index": 3,
+            "previous_block_hash": root("01"),
+            "timestamp": 1_700_000_000u64,
+            "mainnet_exit_root": root("aa"),
+            "rollup_exit_root": root("bb"),
+            "global_exit_root": root("cc"),
+        });
+
+        let leaf = L1InfoLeaf::from_value(&leaf).unwrap();
+        assert_eq!(leaf.l1_info_tree_index, 3);
+        assert_eq!(leaf.block_num, 120);
+        assert_eq!(leaf.previous_block_hash, Some(H256::repeat_byte(0x01)));
+        assert_eq!(leaf.global_exit_root, H256::repeat_byte(0xcc));
+        assert_eq!(leaf.hash, None);
+        assert!(L1InfoLeaf::from_value(&serde_json::json!({ "l1_info_tree_index": 3 })).is_err());
+    }
 }
@@ -29,6 +29,7 @@ impl Message for SuccessMessage {
         format!("{} {}", "✓".green().bold(), self.content)
     }
 
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
     fn json(&self) -> Value {
         json!({
             "message_type": "success",
@@ -57,6 +58,7 @@ impl Message for ErrorMessage {
         format!("{} {}", "✗".red().bold(), self.content)
     }
 
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
     fn json(&self) -> Value {
         json!({
             "message_type": "error",
@@ -85,6 +87,7 @@ impl Message for WarningMessage {
         format!("{} {}", "⚠".yellow().bold(), self.content)
     }
 
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
     fn json(&self) -> Value {
         json!({
             "message_type": "warning",
@@ -113,6 +116,7 @@ impl Message for InfoMessage {
         format!("{} {}", "ℹ".blue().bold(), self.content)
     }
 
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
     fn json(&self) -> Value {
         json!({
             "message_type": "info",
@@ -141,6 +145,7 @@ impl Message for TipMessage {
         format!("{} Tip: {}", "💡".bright_yellow().bold(), self.content)
     }
 
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
     fn json(&self) -> Value {
         json!({
             "message_type": "tip",
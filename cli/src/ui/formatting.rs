@@ -100,6 +100,7 @@ impl Message for FormattedTable {
         output
     }
 
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
     fn json(&self) -> Value {
         let rows_object: serde_json::Map<String, serde_json::Value> = self
             .rows
@@ -158,6 +159,7 @@ impl<'a> Message for FormattedJson<'a> {
         output
     }
 
+    #[allow(clippy::disallowed_methods)] // Allow for json! macro expansion
     fn json(&self) -> Value {
         if let Some(title) = &self.title {
             serde_json::json!({
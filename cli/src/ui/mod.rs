@@ -15,7 +15,7 @@ pub use formatting::*;
 pub use message::*;
 
 /// Output format for the CLI
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
 pub enum OutputFormat {
     /// Human-readable output with colors and formatting
     #[default]
@@ -24,6 +24,14 @@ pub enum OutputFormat {
     Json,
 }
 
+impl OutputFormat {
+    /// Check if this is the JSON output format
+    #[must_use]
+    pub fn is_json(self) -> bool {
+        matches!(self, OutputFormat::Json)
+    }
+}
+
 /// Central UI manager for all CLI output
 ///
 /// This struct manages output formatting, colors, and provides consistent
@@ -134,7 +142,7 @@ impl UI {
     /// Print raw JSON (only for JSON mode, otherwise formats nicely)
     pub fn json(&self, data: &serde_json::Value) {
         if self.is_json() {
-            println!("{}", data);
+            println!("{}", crate::schema::versioned(data.clone()));
         } else {
             let formatted = JsonFormatter::new(data).build();
             self.println(&formatted);
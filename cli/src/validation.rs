@@ -1,4 +1,6 @@
 use crate::error::{ConfigError, EventError, Result};
+use crate::types::{RpcUrl, MAX_NETWORK_ID};
+use ethers::types::U256;
 use regex::Regex;
 use url::Url;
 
@@ -8,15 +10,18 @@ pub enum SupportedChain {
     AnvilL1,
     AnvilL2,
     AnvilL3,
+    /// Chain of an extra L2, `anvil-l4` onwards
+    AnvilExtra(String),
 }
 
 impl SupportedChain {
     /// Get the string representation of the chain
-    pub fn as_str(&self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             SupportedChain::AnvilL1 => "anvil-l1",
             SupportedChain::AnvilL2 => "anvil-l2",
             SupportedChain::AnvilL3 => "anvil-l3",
+            SupportedChain::AnvilExtra(name) => name,
         }
     }
 
@@ -26,6 +31,20 @@ impl SupportedChain {
     }
 }
 
+/// Friendly aliases accepted wherever a network ID is expected
+const NETWORK_ALIASES: &[(&str, u64)] = &[
+    ("l1", 0),
+    ("mainnet", 0),
+    ("ethereum", 0),
+    ("anvil-l1", 0),
+    ("l2", 1),
+    ("agglayer-1", 1),
+    ("anvil-l2", 1),
+    ("l3", 2),
+    ("agglayer-2", 2),
+    ("anvil-l3", 2),
+];
+
 /// Validation functions for user inputs
 pub struct Validator;
 
@@ -38,8 +57,15 @@ impl Validator {
             "anvil-l1" => Ok(SupportedChain::AnvilL1),
             "anvil-l2" => Ok(SupportedChain::AnvilL2),
             "anvil-l3" => Ok(SupportedChain::AnvilL3),
+            name if name
+                .strip_prefix("anvil-l")
+                .and_then(|layer| layer.parse::<u64>().ok())
+                .is_some_and(|layer| (4..=MAX_NETWORK_ID + 1).contains(&layer)) =>
+            {
+                Ok(SupportedChain::AnvilExtra(name.to_string()))
+            }
             _ => Err(EventError::invalid_chain(&format!(
-                "Invalid chain '{chain}'. Supported chains: {}",
+                "Invalid chain '{chain}'. Supported chains: {}, or anvil-l<n> for extra L2s",
                 SupportedChain::all_chains().join(", ")
             ))
             .into()),
@@ -59,8 +85,8 @@ impl Validator {
         // 2 = Second L2 (if multi-L2 setup)
         // 3+ = Additional L2 chains
         let valid_ranges = [
-            (0, 3),         // Agglayer network IDs: 0 (L1), 1-3 (L2 chains)
-            (31337, 31339), // Local development networks (for testing)
+            (0, MAX_NETWORK_ID), // Agglayer network IDs: 0 (L1), 1+ (L2 chains)
+            (31337, 31339),      // Local development networks (for testing)
         ];
 
         let is_valid = valid_ranges
@@ -73,12 +99,81 @@ impl Validator {
             Err(ConfigError::invalid_value(
                 "network_id",
                 &network_id.to_string(),
-                "Must be one of: 0 (Ethereum L1), 1-3 (L2 chains), or 31337-31339 (Local development)",
+                "Must be one of: 0 (Ethereum L1), 1-64 (L2 chains), or 31337-31339 (Local development)",
             )
             .into())
         }
     }
 
+    /// Resolve a network ID from a number, a friendly alias or a configured chain name
+    ///
+    /// Accepts `0`/`1`/`2`, aliases such as `l1`, `l2`, `mainnet` or `anvil-l2`,
+    /// and the chain names from the loaded configuration (e.g. `Polygon-zkEVM`).
+    pub fn resolve_network(input: &str) -> Result<u64> {
+        let sanitized = input.trim().to_lowercase();
+
+        if let Ok(network_id) = sanitized.parse::<u64>() {
+            return Ok(network_id);
+        }
+
+        if let Some((_, network_id)) = NETWORK_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == sanitized)
+        {
+            return Ok(*network_id);
+        }
+
+        // Layer names of the extra L2s: l4 / anvil-l4 is network 3
+        if let Some(layer) = sanitized
+            .strip_prefix("anvil-")
+            .unwrap_or(&sanitized)
+            .strip_prefix('l')
+            .and_then(|layer| layer.parse::<u64>().ok())
+            .filter(|&layer| layer > 0)
+        {
+            return Ok(layer - 1);
+        }
+
+        if let Ok(config) = crate::config::Config::load() {
+            let networks = &config.networks;
+            if let Some(network_id) = networks.network_ids().into_iter().find(|&network_id| {
+                networks
+                    .chain(network_id)
+                    .is_some_and(|chain| chain.name.eq_ignore_ascii_case(&sanitized))
+            }) {
+                return Ok(network_id);
+            }
+        }
+
+        Err(ConfigError::invalid_value(
+            "network",
+            input,
+            "Use a network ID (0, 1, 2), an alias (l1, l2, l3, mainnet) or a configured chain name",
+        )
+        .into())
+    }
+
+    /// Resolve the network selected by `--network-id` or the deprecated `--chain`
+    ///
+    /// Prints a deprecation warning whenever `--chain` is used.
+    pub fn resolve_network_selection(network_id: Option<u64>, chain: Option<&str>) -> Result<u64> {
+        match (network_id, chain) {
+            (Some(network_id), Some(_)) => {
+                eprintln!("⚠️  Both --network-id and --chain provided. Using --network-id={network_id}. Please use --network-id only as --chain is deprecated.");
+                Ok(network_id)
+            }
+            (Some(network_id), None) => Ok(network_id),
+            (None, Some(chain)) => {
+                eprintln!("⚠️  --chain parameter is deprecated. Please use --network-id instead (accepts 0/1/2 or aliases such as l1, l2, l3).");
+                Self::resolve_network(chain)
+            }
+            (None, None) => Err(ConfigError::missing_required(
+                "Either --network-id or --chain must be provided",
+            )
+            .into()),
+        }
+    }
+
     /// Validate Ethereum address format
     pub fn validate_ethereum_address(address: &str) -> Result<String> {
         let sanitized = Self::sanitize_address(address);
@@ -142,6 +237,30 @@ impl Validator {
         Ok(blocks)
     }
 
+    /// Parse an unsigned integer given in decimal or as `0x`-prefixed hex
+    ///
+    /// Indices, amounts and gas values copied from explorers and logs are often
+    /// hex, so every command accepts both forms.
+    pub fn parse_uint(input: &str) -> Result<U256> {
+        let value = input.trim();
+        let parsed = match value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+        {
+            Some(hex) if !hex.is_empty() => U256::from_str_radix(hex, 16).ok(),
+            None if !value.is_empty() => U256::from_dec_str(value).ok(),
+            _ => None,
+        };
+        parsed.ok_or_else(|| {
+            ConfigError::invalid_value(
+                "number",
+                input,
+                "Must be a decimal or 0x-prefixed hex unsigned integer",
+            )
+            .into()
+        })
+    }
+
     /// Validate RPC URL format
     #[allow(dead_code)]
     pub fn validate_rpc_url(url: &str) -> Result<String> {
@@ -164,8 +283,46 @@ impl Validator {
         }
     }
 
+    /// Validate that a URL only points at the local machine or a private network
+    ///
+    /// Used by `--offline` mode to guarantee no request leaves the sandbox.
+    /// Loopback, private and link-local addresses are accepted, as are
+    /// single-label hostnames such as Docker Compose service names.
+    pub fn validate_local_url(url: &str, key: &str) -> Result<String> {
+        let sanitized = Self::sanitize_url(url);
+
+        let parsed_url = Url::parse(&sanitized)
+            .map_err(|_| ConfigError::invalid_value(key, url, "Must be a valid URL"))?;
+
+        let is_local = match parsed_url.host() {
+            Some(url::Host::Domain(domain)) => {
+                let domain = domain.to_lowercase();
+                domain == "localhost"
+                    || domain.ends_with(".localhost")
+                    || domain == "host.docker.internal"
+                    || !domain.contains('.')
+            }
+            Some(url::Host::Ipv4(ip)) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+            Some(url::Host::Ipv6(ip)) => {
+                // fc00::/7 is the IPv6 unique local range
+                ip.is_loopback() || (ip.segments()[0] & 0xfe00) == 0xfc00
+            }
+            None => false,
+        };
+
+        if !is_local {
+            return Err(ConfigError::invalid_value(
+                key,
+                url,
+                "Offline mode only allows localhost or private network endpoints",
+            )
+            .into());
+        }
+
+        Ok(sanitized)
+    }
+
     /// Sanitize URL input
-    #[allow(dead_code)]
     fn sanitize_url(url: &str) -> String {
         url.trim().to_string()
     }
@@ -384,10 +541,141 @@ impl Sanitizer {
     }
 }
 
+/// Clap value parser for network arguments that accepts IDs, aliases and chain names
+pub fn parse_network_id(input: &str) -> std::result::Result<u64, String> {
+    Validator::resolve_network(input).map_err(|e| e.to_string())
+}
+
+/// Network ID standing for every configured network, see [`parse_network_selection`]
+pub const ALL_NETWORKS: u64 = u64::MAX;
+
+/// Clap value parser like [`parse_network_id`] that also accepts `all`
+///
+/// `all` is returned as [`ALL_NETWORKS`]; commands expand it to the networks
+/// of the loaded configuration.
+pub fn parse_network_selection(input: &str) -> std::result::Result<u64, String> {
+    if input.trim().eq_ignore_ascii_case("all") {
+        return Ok(ALL_NETWORKS);
+    }
+    parse_network_id(input)
+}
+
+/// Clap value parser for `NETWORK=URL` RPC endpoint overrides
+///
+/// The network accepts the same IDs, aliases and chain names as
+/// [`parse_network_id`], e.g. `l2=https://sandbox.example.com:8546`.
+pub fn parse_rpc_override(input: &str) -> std::result::Result<(u64, RpcUrl), String> {
+    let (network, url) = input.split_once('=').ok_or_else(|| {
+        format!("expected NETWORK=URL, e.g. l1=http://sandbox:8545, got '{input}'")
+    })?;
+    let network_id = parse_network_id(network)?;
+    let url = RpcUrl::new(url.trim()).map_err(|e| e.to_string())?;
+    Ok((network_id, url))
+}
+
+/// Clap value parser for indices, counts and gas limits in decimal or `0x` hex
+pub fn parse_int<T: TryFrom<u64>>(input: &str) -> std::result::Result<T, String> {
+    let value = Validator::parse_uint(input).map_err(|e| e.to_string())?;
+    if value > U256::from(u64::MAX) {
+        return Err(format!("{input} is out of range"));
+    }
+    T::try_from(value.as_u64()).map_err(|_| format!("{input} is out of range"))
+}
+
+/// Clap value parser for uint256 values (amounts, wei) in decimal or `0x` hex
+///
+/// The value is normalized to decimal, the form the bridge commands parse.
+pub fn parse_uint256(input: &str) -> std::result::Result<String, String> {
+    Validator::parse_uint(input)
+        .map(|value| value.to_string())
+        .map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_rpc_override() {
+        let (network_id, url) = parse_rpc_override("l2=https://sandbox.example.com:8546").unwrap();
+        assert_eq!(network_id, 1);
+        assert_eq!(url.as_str(), "https://sandbox.example.com:8546");
+        assert_eq!(parse_rpc_override("0=http://a:1").unwrap().0, 0);
+        assert!(parse_rpc_override("http://a:1").is_err());
+        assert!(parse_rpc_override("l1=").is_err());
+    }
+
+    #[test]
+    fn test_parse_uint() {
+        assert_eq!(Validator::parse_uint("100").unwrap(), U256::from(100));
+        assert_eq!(Validator::parse_uint("0x64").unwrap(), U256::from(100));
+        assert_eq!(Validator::parse_uint(" 0XFF ").unwrap(), U256::from(255));
+        assert_eq!(
+            Validator::parse_uint("0x10000000000000003").unwrap(),
+            U256::from_dec_str("18446744073709551619").unwrap()
+        );
+        assert!(Validator::parse_uint("").is_err());
+        assert!(Validator::parse_uint("0x").is_err());
+        assert!(Validator::parse_uint("1.5").is_err());
+        assert!(Validator::parse_uint("-1").is_err());
+        assert!(Validator::parse_uint("0xzz").is_err());
+    }
+
+    #[test]
+    fn test_parse_int() {
+        assert_eq!(parse_int::<u64>("0x2a"), Ok(42));
+        assert_eq!(parse_int::<u64>("42"), Ok(42));
+        assert_eq!(parse_int::<u32>("0xffffffff"), Ok(u32::MAX));
+        assert!(parse_int::<u32>("0x100000000").is_err());
+        assert!(parse_int::<u64>("0x10000000000000000").is_err());
+        assert_eq!(
+            parse_uint256("0xde0b6b3a7640000"),
+            Ok("1000000000000000000".to_string())
+        );
+        assert!(parse_uint256("1e18").is_err());
+    }
+
+    #[test]
+    fn test_resolve_network() {
+        assert_eq!(Validator::resolve_network("0").unwrap(), 0);
+        assert_eq!(Validator::resolve_network("2").unwrap(), 2);
+        assert_eq!(Validator::resolve_network("L1").unwrap(), 0);
+        assert_eq!(Validator::resolve_network(" l2 ").unwrap(), 1);
+        assert_eq!(Validator::resolve_network("anvil-l3").unwrap(), 2);
+        assert_eq!(Validator::resolve_network("L5").unwrap(), 4);
+        assert_eq!(Validator::resolve_network("anvil-l4").unwrap(), 3);
+        assert_eq!(Validator::resolve_network("Ethereum-L1").unwrap(), 0);
+        assert_eq!(Validator::resolve_network("polygon-zkevm").unwrap(), 1);
+        assert!(Validator::resolve_network("not-a-network").is_err());
+        assert_eq!(parse_network_id("mainnet"), Ok(0));
+    }
+
+    #[test]
+    fn test_parse_network_selection() {
+        assert_eq!(parse_network_selection("all"), Ok(ALL_NETWORKS));
+        assert_eq!(parse_network_selection(" ALL "), Ok(ALL_NETWORKS));
+        assert_eq!(parse_network_selection("l2"), Ok(1));
+        assert!(parse_network_selection("everything").is_err());
+    }
+
+    #[test]
+    fn test_resolve_network_selection() {
+        assert_eq!(
+            Validator::resolve_network_selection(Some(1), None).unwrap(),
+            1
+        );
+        assert_eq!(
+            Validator::resolve_network_selection(Some(1), Some("anvil-l1")).unwrap(),
+            1
+        );
+        assert_eq!(
+            Validator::resolve_network_selection(None, Some("anvil-l2")).unwrap(),
+            1
+        );
+        assert!(Validator::resolve_network_selection(None, None).is_err());
+    }
+
     #[test]
     fn test_validate_chain_valid() {
         assert_eq!(
@@ -402,11 +690,16 @@ mod tests {
             Validator::validate_chain("  anvil-l3  ").unwrap(),
             SupportedChain::AnvilL3
         );
+        assert_eq!(
+            Validator::validate_chain("anvil-l5").unwrap(),
+            SupportedChain::AnvilExtra("anvil-l5".to_string())
+        );
     }
 
     #[test]
     fn test_validate_chain_invalid() {
         assert!(Validator::validate_chain("invalid-chain").is_err());
+        assert!(Validator::validate_chain("anvil-l0").is_err());
         assert!(Validator::validate_chain("ethereum").is_err());
         assert!(Validator::validate_chain("").is_err());
     }
@@ -417,12 +710,13 @@ mod tests {
         assert_eq!(Validator::validate_network_id(1).unwrap(), 1); // First L2
         assert_eq!(Validator::validate_network_id(2).unwrap(), 2); // Second L2
         assert_eq!(Validator::validate_network_id(3).unwrap(), 3); // Third L2
+        assert_eq!(Validator::validate_network_id(7).unwrap(), 7); // Extra L2
         assert_eq!(Validator::validate_network_id(31337).unwrap(), 31337); // Local dev
     }
 
     #[test]
     fn test_validate_network_id_invalid() {
-        assert!(Validator::validate_network_id(4).is_err()); // Beyond L2 range
+        assert!(Validator::validate_network_id(MAX_NETWORK_ID + 1).is_err()); // Beyond L2 range
         assert!(Validator::validate_network_id(137).is_err()); // Old chain ID format
         assert!(Validator::validate_network_id(1101).is_err()); // Old chain ID format
         assert!(Validator::validate_network_id(999).is_err()); // Invalid range
@@ -489,6 +783,22 @@ mod tests {
         assert!(Validator::validate_rpc_url("").is_err());
     }
 
+    #[test]
+    fn test_validate_local_url_valid() {
+        assert!(Validator::validate_local_url("http://localhost:8545", "rpc_url").is_ok());
+        assert!(Validator::validate_local_url("http://127.0.0.1:5577", "rpc_url").is_ok());
+        assert!(Validator::validate_local_url("http://192.168.1.20:8545", "rpc_url").is_ok());
+        assert!(Validator::validate_local_url("http://anvil-l1:8545", "rpc_url").is_ok());
+        assert!(Validator::validate_local_url("http://[::1]:8545", "rpc_url").is_ok());
+    }
+
+    #[test]
+    fn test_validate_local_url_invalid() {
+        assert!(Validator::validate_local_url("https://eth.llamarpc.com", "rpc_url").is_err());
+        assert!(Validator::validate_local_url("http://8.8.8.8:8545", "rpc_url").is_err());
+        assert!(Validator::validate_local_url("not-a-url", "rpc_url").is_err());
+    }
+
     #[test]
     fn test_validate_file_path_valid() {
         assert!(Validator::validate_file_path("docker-compose.yml").is_ok());
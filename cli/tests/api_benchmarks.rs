@@ -3,6 +3,7 @@
 /// These benchmarks measure API operation performance under various conditions
 /// and validate that performance requirements are met for interactive use.
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod api_performance_benchmarks {
     use aggsandbox::api;
     use aggsandbox::config::{
@@ -22,6 +23,9 @@ mod api_performance_benchmarks {
                 base_url: RpcUrl::new(base_url).expect("Valid test URL"),
                 timeout: Duration::from_millis(5000),
                 retry_attempts: 3,
+                max_in_flight: 8,
+                rate_limit: 0,
+                agglayer_url: RpcUrl::new("http://localhost:4444").expect("Valid test URL"),
             },
             networks: NetworkConfig {
                 l1: ChainConfig {
@@ -29,14 +33,17 @@ mod api_performance_benchmarks {
                     chain_id: ChainId::new("1").expect("Valid test chain ID"),
                     rpc_url: RpcUrl::new("http://localhost:8545").expect("Valid test URL"),
                     fork_url: None,
+                    ws_url: None,
                 },
                 l2: ChainConfig {
                     name: "Test-L2".to_string(),
                     chain_id: ChainId::new("1101").expect("Valid test chain ID"),
                     rpc_url: RpcUrl::new("http://localhost:8546").expect("Valid test URL"),
                     fork_url: None,
+                    ws_url: None,
                 },
                 l3: None,
+                extra_l2s: Vec::new(),
             },
             accounts: AccountConfig {
                 accounts: vec![
@@ -44,11 +51,15 @@ mod api_performance_benchmarks {
                         .expect("Valid test address"),
                 ],
                 private_keys: vec!["0xkey".to_string()],
+                named: Default::default(),
+                selected_key: None,
+                hardware_signer: None,
             },
             contracts: ContractConfig {
                 l1_contracts: HashMap::new(),
                 l2_contracts: HashMap::new(),
                 l3_contracts: HashMap::new(),
+                extra_contracts: Default::default(),
             },
         }
     }
@@ -361,6 +372,7 @@ mod api_performance_benchmarks {
 }
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod api_reliability_benchmarks {
     use aggsandbox::api;
     use aggsandbox::api_client::OptimizedApiClient;
@@ -370,6 +382,7 @@ mod api_reliability_benchmarks {
     use aggsandbox::types::{ChainId, EthereumAddress, RpcUrl};
     use serde_json::json;
     use std::collections::HashMap;
+    #[allow(clippy::disallowed_types)] // Allow std::sync::Mutex for test counters
     use std::sync::{Arc, Mutex};
     use std::time::{Duration, Instant};
     use wiremock::matchers::{method, path};
@@ -381,6 +394,9 @@ mod api_reliability_benchmarks {
                 base_url: RpcUrl::new(base_url).expect("Valid test URL"),
                 timeout: Duration::from_millis(2000),
                 retry_attempts: 2,
+                max_in_flight: 8,
+                rate_limit: 0,
+                agglayer_url: RpcUrl::new("http://localhost:4444").expect("Valid test URL"),
             },
             networks: NetworkConfig {
                 l1: ChainConfig {
@@ -388,14 +404,17 @@ mod api_reliability_benchmarks {
                     chain_id: ChainId::new("1").expect("Valid test chain ID"),
                     rpc_url: RpcUrl::new("http://localhost:8545").expect("Valid test URL"),
                     fork_url: None,
+                    ws_url: None,
                 },
                 l2: ChainConfig {
                     name: "Test-L2".to_string(),
                     chain_id: ChainId::new("1101").expect("Valid test chain ID"),
                     rpc_url: RpcUrl::new("http://localhost:8546").expect("Valid test URL"),
                     fork_url: None,
+                    ws_url: None,
                 },
                 l3: None,
+                extra_l2s: Vec::new(),
             },
             accounts: AccountConfig {
                 accounts: vec![
@@ -403,17 +422,22 @@ mod api_reliability_benchmarks {
                         .expect("Valid test address"),
                 ],
                 private_keys: vec!["0xkey".to_string()],
+                named: Default::default(),
+                selected_key: None,
+                hardware_signer: None,
             },
             contracts: ContractConfig {
                 l1_contracts: HashMap::new(),
                 l2_contracts: HashMap::new(),
                 l3_contracts: HashMap::new(),
+                extra_contracts: Default::default(),
             },
         }
     }
 
     /// Test API stability under sustained load
     #[tokio::test]
+    #[allow(clippy::disallowed_types)] // Allow std::sync::Mutex for counters never held across an await
     async fn test_sustained_load_stability() {
         let mock_server = MockServer::start().await;
         let config = Arc::new(create_test_config(&mock_server.uri()));
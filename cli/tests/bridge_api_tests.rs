@@ -0,0 +1,71 @@
+/// Tests of the bridge library API
+///
+/// These tests drive the bridge commands through the `aggsandbox` library the
+/// way external tooling does, without a running sandbox.
+#[cfg(test)]
+mod bridge_api_tests {
+    use aggsandbox::commands::bridge::utilities::{
+        compute_global_index, decode_global_index, ComputeGlobalIndexArgs,
+    };
+    use aggsandbox::commands::bridge::{BridgeAssetArgs, ClaimAssetArgs};
+    use aggsandbox::config::Config;
+    use ethers::types::U256;
+
+    const TX_HASH: &str = "0xb7118cfb20825861028ede1e9586814fc7ccf81745a325db5df355d382d96b4e";
+
+    #[test]
+    fn test_bridge_asset_args_validation() {
+        let config = Config::default();
+
+        let args = BridgeAssetArgs::builder()
+            .config(&config)
+            .source_network(0)
+            .destination_network(1)
+            .amount("1000")
+            .token_address("0x0000000000000000000000000000000000000000")
+            .build_with_crate_error();
+        assert!(args.is_ok());
+
+        let args = BridgeAssetArgs::builder()
+            .config(&config)
+            .source_network(0)
+            .destination_network(1)
+            .amount("not-a-number")
+            .token_address("0x0000000000000000000000000000000000000000")
+            .build_with_crate_error();
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_claim_asset_args_validation() {
+        let config = Config::default();
+
+        let args = ClaimAssetArgs::builder()
+            .config(&config)
+            .network(1)
+            .tx_hash(TX_HASH)
+            .source_network(0)
+            .deposit_count(Some(3))
+            .build_with_crate_error()
+            .expect("Valid claim arguments");
+        assert_eq!(args.deposit_count, Some(3));
+
+        let args = ClaimAssetArgs::builder()
+            .config(&config)
+            .network(1)
+            .tx_hash("0x1234")
+            .source_network(0)
+            .build_with_crate_error();
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_global_index_round_trip() {
+        let global_index = compute_global_index(ComputeGlobalIndexArgs {
+            index_local: 5,
+            source_network_id: 0,
+        });
+        assert_eq!(global_index, (U256::one() << 64) + U256::from(5));
+        assert_eq!(decode_global_index(global_index), (0, 5));
+    }
+}
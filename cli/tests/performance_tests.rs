@@ -3,6 +3,7 @@
 /// These tests verify performance characteristics and resource usage
 /// to ensure the CLI remains responsive under various conditions.
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap and std::thread in tests
 mod perf_tests {
     use aggsandbox::config::Config;
     use aggsandbox::error::ConfigError;
@@ -342,14 +343,17 @@ mod perf_tests {
 }
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap and std::thread in tests
 mod stress_tests {
     use aggsandbox::config::Config;
     use aggsandbox::validation::Validator;
+    #[allow(clippy::disallowed_types)] // Allow std::sync::Mutex shared with std threads
     use std::sync::{Arc, Mutex};
     use std::thread;
     use std::time::{Duration, Instant};
 
     #[test]
+    #[allow(clippy::disallowed_types)] // Allow std::sync::Mutex shared with std threads
     fn test_concurrent_config_loading() {
         // Test that multiple threads can load config simultaneously
         let start = Instant::now();
@@ -386,6 +390,7 @@ mod stress_tests {
     }
 
     #[test]
+    #[allow(clippy::disallowed_types)] // Allow std::sync::Mutex shared with std threads
     fn test_validation_under_load() {
         // Test validation functions under high concurrent load
         let start = Instant::now();
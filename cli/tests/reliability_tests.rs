@@ -3,6 +3,8 @@
 /// These tests validate system reliability under various real-world conditions
 /// including Docker daemon issues, file system permissions, and resource constraints.
 #[cfg(test)]
+#[cfg(feature = "docker")]
+#[allow(clippy::disallowed_methods)] // Allow unwrap in tests
 mod docker_reliability_tests {
     use aggsandbox::docker::DockerComposeBuilder;
     use aggsandbox::error::{AggSandboxError, DockerError};
@@ -178,6 +180,7 @@ services:
 }
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap and std::thread in tests
 mod file_system_reliability_tests {
     use aggsandbox::config::Config;
     use aggsandbox::validation::Validator;
@@ -344,6 +347,7 @@ mod file_system_reliability_tests {
 }
 
 #[cfg(test)]
+#[allow(clippy::disallowed_methods)] // Allow unwrap and std::thread in tests
 mod memory_leak_detection_tests {
     use aggsandbox::error::{AggSandboxError, ApiError, ConfigError, DockerError, EventError};
     use aggsandbox::validation::Validator;
@@ -98,11 +98,20 @@ if [[ -z "$RPC_URL_1" || -z "$RPC_URL_2" ]]; then
     update_env_file "$ENV_FILE" "RPC_URL_2" "$RPC_URL_2"
 fi
 
-# Handle RPC_URL_3 for multi-L2 mode if it exists
-if [[ ! -z "$RPC_URL_3" ]]; then
-    echo_ts "RPC_URL_3 is set. Using for third chain deployment."
-    update_env_file "$ENV_FILE" "RPC_URL_3" "$RPC_URL_3"
-fi
+# Handle RPC_URL_3..RPC_URL_9 for multi-L2 mode if they exist.
+# RPC_URL_<n> is the chain with network ID n-1.
+L2_COUNT=1
+for n in 3 4 5 6 7 8 9; do
+    url_var="RPC_URL_$n"
+    if [[ ! -z "${!url_var}" ]]; then
+        echo_ts "$url_var is set. Using for network $((n - 1)) deployment."
+        update_env_file "$ENV_FILE" "$url_var" "${!url_var}"
+        L2_COUNT=$((n - 1))
+    fi
+done
+
+# Tells deployL1.s.sol how many rollups to register
+export L2_COUNT
 
 # Check if private keys are set
 if [[ -z "$PRIVATE_KEY_1" ]]; then
@@ -146,12 +155,9 @@ while read -r line; do
     elif [[ $line =~ PolygonZkEVM:[[:space:]]+([0-9a-fA-Fx]+) ]]; then
         addr="${BASH_REMATCH[1]}"
         update_env_file "$ENV_FILE" "POLYGON_ZKEVM_$suffix" "$addr"
-    elif [[ $line =~ "PolygonZkEVM L2:"[[:space:]]+([0-9a-fA-Fx]+) ]]; then
-        addr="${BASH_REMATCH[1]}"
-        update_env_file "$ENV_FILE" "POLYGON_ZKEVM_L2_ROLLUP" "$addr"
-    elif [[ $line =~ "PolygonZkEVM L3:"[[:space:]]+([0-9a-fA-Fx]+) ]]; then
-        addr="${BASH_REMATCH[1]}"
-        update_env_file "$ENV_FILE" "POLYGON_ZKEVM_L3_ROLLUP" "$addr"
+    elif [[ $line =~ "PolygonZkEVM L"([0-9]+):[[:space:]]+([0-9a-fA-Fx]+) ]]; then
+        addr="${BASH_REMATCH[2]}"
+        update_env_file "$ENV_FILE" "POLYGON_ZKEVM_L${BASH_REMATCH[1]}_ROLLUP" "$addr"
     elif [[ $line =~ PolygonZkEVMBridgeV2:[[:space:]]+([0-9a-fA-Fx]+) ]]; then
         addr="${BASH_REMATCH[1]}"
         update_env_file "$ENV_FILE" "POLYGON_ZKEVM_BRIDGE_$suffix" "$addr"
@@ -209,22 +215,28 @@ while read -r line; do
     fi
 done < <(echo "$output")
 
-# === NEW SECTION: Deploy L3 contracts if RPC_URL_3 is provided ===
-if [[ ! -z "$RPC_URL_3" ]]; then
-    echo_ts "Deploying L3 contracts..."
-    rpc_url="$RPC_URL_3"
+# Deploy the contracts of every further L2 (L3, L4, ...) whose RPC URL is provided
+for n in 3 4 5 6 7 8 9; do
+    url_var="RPC_URL_$n"
+    if [[ -z "${!url_var}" ]]; then
+        continue
+    fi
+
+    suffix="L$n"
+    echo_ts "Deploying $suffix contracts..."
+    rpc_url="${!url_var}"
     # Re-use PRIVATE_KEY_2 unless a specific key is provided
-    private_key="${PRIVATE_KEY_3:-$PRIVATE_KEY_2}"
-    suffix="L3"
+    key_var="PRIVATE_KEY_$n"
+    private_key="${!key_var:-$PRIVATE_KEY_2}"
 
-    # Deploy contracts on the third chain (re-using the L2 deployment script)
-    output=$(forge script script/deployL3.s.sol:DeployContractsL2 --rpc-url "$rpc_url" --broadcast --private-key "$private_key" 2>&1)
+    # Deploy contracts on the chain (re-using the sovereign chain deployment script)
+    output=$(NETWORK_ID=$((n - 1)) forge script script/deployL3.s.sol:DeployContractsL2 --rpc-url "$rpc_url" --broadcast --private-key "$private_key" 2>&1)
     echo "$output" > deploy_output_$suffix.log
 
-    echo_ts "L3 deployment output:"
+    echo_ts "$suffix deployment output:"
     echo "$output"
 
-    # Parse and update env file for L3
+    # Parse and update env file for the chain
     while read -r line; do
         if [[ $line =~ PolygonZkEVMBridgeV2:[[:space:]]+([0-9a-fA-Fx]+) ]]; then
             addr="${BASH_REMATCH[1]}"
@@ -247,8 +259,8 @@ if [[ ! -z "$RPC_URL_3" ]]; then
         fi
     done < <(echo "$output")
 
-    echo_ts "L3 contract deployment complete. Addresses stored with *_L3 suffix in $ENV_FILE"
-fi
+    echo_ts "$suffix contract deployment complete. Addresses stored with *_$suffix suffix in $ENV_FILE"
+done
 
 # Return to the original directory
 cd "$SCRIPT_DIR"